@@ -1,13 +1,315 @@
+create extension if not exists unaccent;
+create extension if not exists pg_trgm;
+
 create table todo_user (
     id serial primary key not null,
     first_name varchar(128) not null,
-    last_name varchar(128) not null
+    last_name varchar(128) not null,
+    -- Admins can see every user and manage anyone's tasks, while other users only manage their own
+    role varchar(16) not null default 'user',
+    email varchar(254),
+    -- Whether the user is emailed a summary of their open tasks each morning, which needs an email address
+    daily_digest boolean not null default false,
+    -- The day the user's digest was last sent, or attempted if sending failed, so it goes out at most once a day
+    last_digest_on date,
+    constraint todo_user_role_check check (role in ('user', 'admin')),
+    constraint todo_user_daily_digest_email_check check (not daily_digest or email is not null)
 );
 
+-- Normalizes a user's full name for case and accent insensitive searches. unaccent() is only marked
+-- stable, so it's wrapped in an immutable function with an explicit dictionary to allow indexing.
+create function todo_user_search_name(first_name text, last_name text) returns text
+    language sql immutable parallel safe
+    as $$ select lower(public.unaccent('public.unaccent'::regdictionary, first_name || ' ' || last_name)) $$;
+
+create index todo_user_search_name_idx on todo_user
+    using gin (todo_user_search_name(first_name, last_name) gin_trgm_ops);
+
 create table todo_item (
     id serial primary key not null,
     user_id integer not null,
     item_desc text not null,
+    completed boolean not null default false,
+    due_date date,
+    -- An iCalendar RRULE describing how the task repeats. Recurring tasks are due on their first occurrence.
+    recurrence_rule text,
+    -- The user who should do the task, who may differ from the user who created it. Null when unassigned.
+    assignee_user_id integer,
+    -- Set when the task is moved to the trash. Trashed tasks are hidden from reads until they're
+    -- restored or purged.
+    deleted_at timestamptz,
+    item_desc_search tsvector generated always as (to_tsvector('english', item_desc)) stored,
+
+    constraint todo_item_user_id_fk foreign key(user_id) references todo_user(id),
+    constraint todo_item_assignee_user_id_fk foreign key(assignee_user_id) references todo_user(id)
+        on delete set null,
+    constraint todo_item_recurrence_due_date_check check (recurrence_rule is null or due_date is not null)
+);
+
+create index todo_item_search_idx on todo_item using gin (item_desc_search);
+create index todo_item_deleted_at_idx on todo_item(deleted_at) where deleted_at is not null;
+create index todo_item_assignee_user_id_idx on todo_item(assignee_user_id) where assignee_user_id is not null;
+
+-- Tokens granting access to a user's calendar feed, so it can be subscribed to without other credentials.
+-- Each user has at most one token and only its SHA-256 hash is stored.
+create table calendar_feed_token (
+    user_id integer primary key not null,
+    token_hash char(64) not null,
+    created_at timestamptz not null default now(),
+
+    constraint calendar_feed_token_user_id_fk foreign key(user_id) references todo_user(id) on delete cascade
+);
+
+-- Access to a user's tasks which they've granted to another user. A share without a task covers every task
+-- its owner has, including ones they create later. Granting the same share again replaces its permission.
+create table task_share (
+    id serial primary key not null,
+    owner_user_id integer not null,
+    shared_with_user_id integer not null,
+    task_id integer,
+    permission varchar(16) not null,
+    created_at timestamptz not null default now(),
+
+    constraint task_share_owner_user_id_fk foreign key(owner_user_id) references todo_user(id) on delete cascade,
+    constraint task_share_shared_with_user_id_fk foreign key(shared_with_user_id)
+        references todo_user(id) on delete cascade,
+    constraint task_share_task_id_fk foreign key(task_id) references todo_item(id) on delete cascade,
+    constraint task_share_permission_check check (permission in ('read', 'edit')),
+    constraint task_share_not_owner_check check (owner_user_id <> shared_with_user_id)
+);
+
+create unique index task_share_grant_idx on task_share(owner_user_id, shared_with_user_id, coalesce(task_id, 0));
+create index task_share_shared_with_user_id_idx on task_share(shared_with_user_id);
+
+-- Comments users leave on tasks. A comment may reply to another comment on the same task, which threads the
+-- discussion. Deleting a comment deletes the replies to it too.
+create table task_comment (
+    id serial primary key not null,
+    task_id integer not null,
+    author_user_id integer not null,
+    parent_comment_id integer,
+    body text not null,
+    created_at timestamptz not null default now(),
+    -- Set when the comment is edited
+    updated_at timestamptz,
+
+    constraint task_comment_task_id_fk foreign key(task_id) references todo_item(id) on delete cascade,
+    constraint task_comment_author_user_id_fk foreign key(author_user_id) references todo_user(id) on delete cascade,
+    constraint task_comment_parent_comment_id_fk foreign key(parent_comment_id)
+        references task_comment(id) on delete cascade
+);
+
+create index task_comment_task_id_idx on task_comment(task_id, id);
+create index task_comment_parent_comment_id_idx on task_comment(parent_comment_id);
+
+-- Files attached to tasks. Only the details live here; the contents are kept in blob storage under storage_key.
+create table task_attachment (
+    id serial primary key not null,
+    task_id integer not null,
+    uploader_user_id integer not null,
+    file_name text not null,
+    content_type text not null,
+    size_bytes bigint not null,
+    storage_key text not null,
+    created_at timestamptz not null default now(),
+
+    constraint task_attachment_task_id_fk foreign key(task_id) references todo_item(id) on delete cascade,
+    constraint task_attachment_uploader_user_id_fk foreign key(uploader_user_id)
+        references todo_user(id) on delete cascade,
+    constraint task_attachment_storage_key_unique unique (storage_key)
+);
+
+create index task_attachment_task_id_idx on task_attachment(task_id, id);
+
+-- Tasks which can't be completed until other tasks are. Both tasks belong to the same user, and the
+-- dependencies never form a cycle.
+create table task_dependency (
+    task_id integer not null,
+    blocked_by_task_id integer not null,
+
+    primary key (task_id, blocked_by_task_id),
+    constraint task_dependency_task_id_fk foreign key(task_id) references todo_item(id) on delete cascade,
+    constraint task_dependency_blocked_by_task_id_fk foreign key(blocked_by_task_id)
+        references todo_item(id) on delete cascade,
+    constraint task_dependency_not_self check (task_id <> blocked_by_task_id)
+);
+
+create index task_dependency_blocked_by_task_id_idx on task_dependency(blocked_by_task_id);
+
+-- Reminders users set on tasks they can see. A reminder is sent to the user who set it once remind_at passes,
+-- unless the task has been completed or trashed by then.
+create table task_reminder (
+    id serial primary key not null,
+    task_id integer not null,
+    user_id integer not null,
+    remind_at timestamptz not null,
+    attempts integer not null default 0,
+    last_error text,
+    -- When a reminder which failed to send may be tried again
+    next_attempt_at timestamptz,
+    sent_at timestamptz,
+    -- Set when sending the reminder has failed too many times to keep trying
+    failed_at timestamptz,
+    created_at timestamptz not null default now(),
+
+    constraint task_reminder_task_id_fk foreign key(task_id) references todo_item(id) on delete cascade,
+    constraint task_reminder_user_id_fk foreign key(user_id) references todo_user(id) on delete cascade
+);
+
+create index task_reminder_task_id_idx on task_reminder(task_id, user_id);
+create index task_reminder_due_idx on task_reminder(remind_at) where sent_at is null and failed_at is null;
+
+create table idempotency_key (
+    request_scope text not null,
+    idempotency_key varchar(255) not null,
+    request_fingerprint char(64) not null,
+    response_status smallint,
+    response_content_type text,
+    response_body bytea,
+    -- Whether the request which last tried to claim the key got it
+    last_claimed boolean not null,
+    expires_at timestamptz not null,
+
+    primary key (request_scope, idempotency_key)
+);
+
+create index idempotency_key_expires_at_idx on idempotency_key(expires_at);
+
+-- Token buckets for rate limiting which are shared between instances of the app. A client without a row has
+-- a full bucket, so rows which have had time to refill completely can be deleted.
+create table rate_limit_bucket (
+    bucket_key text primary key not null,
+    tokens double precision not null,
+    -- Whether the request which last touched the bucket was given a token
+    last_allowed boolean not null,
+    updated_at timestamptz not null default now()
+);
+
+create index rate_limit_bucket_updated_at_idx on rate_limit_bucket(updated_at);
+
+create table webhook_subscription (
+    id serial primary key not null,
+    url text not null,
+    event_types text[] not null,
+    secret text not null,
+    created_at timestamptz not null default now()
+);
+
+-- Events waiting to be delivered to webhook subscribers, one row per subscription. Rows are written
+-- in the same transaction as the change that produced the event.
+create table webhook_outbox (
+    id bigserial primary key not null,
+    subscription_id integer not null,
+    event_type text not null,
+    payload jsonb not null,
+    created_at timestamptz not null default now(),
+    attempts integer not null default 0,
+    next_attempt_at timestamptz not null default now(),
+    last_error text,
+    delivered_at timestamptz,
+    failed_at timestamptz,
 
-    constraint todo_item_user_id_fk foreign key(user_id) references todo_user(id)
+    constraint webhook_outbox_subscription_id_fk foreign key(subscription_id)
+        references webhook_subscription(id) on delete cascade
 );
+
+create index webhook_outbox_pending_idx on webhook_outbox(next_attempt_at)
+    where delivered_at is null and failed_at is null;
+
+-- Recent changes to tasks, used to stream them to clients. A client which reconnects resumes after
+-- the ID of the last change it received.
+create table task_event (
+    id bigserial primary key not null,
+    user_id integer not null,
+    task_id integer not null,
+    event_type text not null,
+    item_desc text,
+    completed boolean,
+    created_at timestamptz not null default now()
+);
+
+create index task_event_user_id_idx on task_event(user_id, id);
+create index task_event_created_at_idx on task_event(created_at);
+
+-- Records every change to a task and notifies listeners on the task_changes channel with the ID
+-- of the task's owner. Notifications are only delivered once the change is committed. Moving a task
+-- to the trash is recorded as a deletion and restoring it as a creation, so purging a trashed task
+-- records nothing.
+create function record_task_event() returns trigger
+    language plpgsql
+    as $$
+declare
+    changed_task todo_item;
+    change_type text;
+begin
+    if tg_op = 'DELETE' and old.deleted_at is not null then
+        return null;
+    elsif tg_op = 'DELETE' then
+        changed_task := old;
+        change_type := 'task.deleted';
+    elsif tg_op = 'INSERT' then
+        changed_task := new;
+        change_type := 'task.created';
+    elsif new.deleted_at is not null and old.deleted_at is null then
+        changed_task := new;
+        change_type := 'task.deleted';
+    elsif new.deleted_at is null and old.deleted_at is not null then
+        changed_task := new;
+        change_type := 'task.created';
+    elsif new.completed and not old.completed then
+        changed_task := new;
+        change_type := 'task.completed';
+    else
+        changed_task := new;
+        change_type := 'task.updated';
+    end if;
+
+    -- Holding a per-user lock until commit means a user's changes become visible in ID order,
+    -- so a client resuming after an ID can't skip a change committed late with a lower ID.
+    perform pg_advisory_xact_lock(hashtext('task_event'), changed_task.user_id);
+
+    if change_type = 'task.deleted' then
+        insert into task_event(user_id, task_id, event_type)
+            values (changed_task.user_id, changed_task.id, change_type);
+    else
+        insert into task_event(user_id, task_id, event_type, item_desc, completed)
+            values (changed_task.user_id, changed_task.id, change_type, changed_task.item_desc, changed_task.completed);
+    end if;
+
+    perform pg_notify('task_changes', changed_task.user_id::text);
+    return null;
+end
+$$;
+
+create trigger todo_item_record_task_event
+    after insert or update or delete on todo_item
+    for each row execute function record_task_event();
+
+-- Append-only history of every change made to users and tasks. Each row is written by the same statement
+-- as the change it describes, holding the entity's state as JSON before and after the change.
+create table audit_log (
+    id bigserial primary key not null,
+    actor_user_id integer,
+    action text not null,
+    entity_type text not null,
+    entity_id integer not null,
+    before_state jsonb,
+    after_state jsonb,
+    recorded_at timestamptz not null default now()
+);
+
+create index audit_log_entity_idx on audit_log(entity_type, entity_id, id);
+create index audit_log_actor_user_id_idx on audit_log(actor_user_id, id);
+create index audit_log_recorded_at_idx on audit_log(recorded_at);
+
+create function reject_audit_log_change() returns trigger
+    language plpgsql
+    as $$
+begin
+    raise exception 'The audit log is append-only, so % is not allowed', tg_op;
+end
+$$;
+
+create trigger audit_log_append_only
+    before update or delete or truncate on audit_log
+    for each statement execute function reject_audit_log_change();
@@ -0,0 +1,166 @@
+use crate::domain::access::{AccessDenied, Caller};
+use crate::domain::user::driving_ports::{AdminBootstrap, UserPort};
+use crate::external_connections::{with_transaction, ExternalConnectivity, Transactable};
+use crate::routing_utils::{CallingUser, GenericErrorResponse};
+use crate::{domain, dto, persistence, SharedData};
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use log::{error, info};
+use std::sync::Arc;
+
+/// Extractor for the [Caller] making a request. Every caller is trusted while access control is turned
+/// off. Otherwise the caller is the user named by the [CALLING_USER_HEADER](crate::routing_utils::CALLING_USER_HEADER),
+/// acting with the role stored for them, or anonymous if the header doesn't name a user who exists.
+/// The caller is remembered in the request's extensions, so middleware and handlers only look them up once.
+pub struct RequestCaller(pub Caller);
+
+#[async_trait]
+impl FromRequestParts<Arc<SharedData>> for RequestCaller {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<SharedData>,
+    ) -> Result<Self, Self::Rejection> {
+        if !state.access_control {
+            return Ok(RequestCaller(Caller::Trusted));
+        }
+        if let Some(caller) = parts.extensions.get::<Caller>() {
+            return Ok(RequestCaller(*caller));
+        }
+
+        let CallingUser(calling_user) = CallingUser::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let user_service = domain::user::UserService;
+        let user_reader = persistence::db_user_driven_ports::DbReadUsers;
+        let mut ext_cxn = state.ext_cxn.clone();
+
+        match user_service
+            .identify_caller(calling_user, &mut ext_cxn, &user_reader)
+            .await
+        {
+            Ok(caller) => {
+                parts.extensions.insert(caller);
+                Ok(RequestCaller(caller))
+            }
+            Err(identify_err) => {
+                error!("Could not identify the caller: {identify_err}");
+                Err(GenericErrorResponse(identify_err).into_response())
+            }
+        }
+    }
+}
+
+/// Response type for a caller who isn't allowed to do what they asked
+pub struct ForbiddenResponse(pub AccessDenied);
+
+impl From<ForbiddenResponse> for dto::BasicError {
+    fn from(value: ForbiddenResponse) -> Self {
+        dto::BasicError {
+            error_code: "forbidden".to_owned(),
+            error_description: value.0.to_string(),
+            extra_info: None,
+        }
+    }
+}
+
+impl IntoResponse for ForbiddenResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::FORBIDDEN,
+            axum::Json(dto::BasicError::from(self)),
+        )
+            .into_response()
+    }
+}
+
+/// Makes sure the system has an admin when it starts, so someone can manage roles once access control is
+/// turned on. The described user becomes the first admin, and is created if they don't exist yet.
+pub async fn bootstrap_admin<TxAble>(
+    admin: domain::user::CreateUser,
+    ext_cxn: &TxAble,
+    user_service: &impl UserPort,
+) -> Result<AdminBootstrap, anyhow::Error>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    let user_reader = persistence::db_user_driven_ports::DbReadUsers;
+    let user_writer = persistence::db_user_driven_ports::DbWriteUsers::new(None);
+    let user_detector = persistence::db_user_driven_ports::DbDetectUser;
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+
+    let bootstrap = with_transaction(ext_cxn, async |tx_cxn| {
+        user_service
+            .bootstrap_admin(
+                &admin,
+                &mut *tx_cxn,
+                &user_reader,
+                &user_writer,
+                &user_detector,
+                &event_outbox,
+            )
+            .await
+    })
+    .await
+    .map_err(|tx_err| anyhow::anyhow!(tx_err.to_string()))?;
+
+    match bootstrap {
+        AdminBootstrap::AdminAlreadyExists => info!("An admin already exists"),
+        AdminBootstrap::Promoted(user_id) => info!("Made existing user {user_id} the first admin"),
+        AdminBootstrap::Created(user_id) => info!("Created user {user_id} as the first admin"),
+    }
+    Ok(bootstrap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::access::Action;
+    use crate::domain::user::test_util::MockUserService;
+    use crate::external_connections;
+    use speculoos::prelude::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn forbidden_response_describes_denied_action() {
+        let response = ForbiddenResponse(AccessDenied {
+            action: Action::ListUsers,
+        })
+        .into_response();
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+        let body: dto::BasicError =
+            crate::api::test_util::deserialize_body(response.into_body()).await;
+        assert_eq!("forbidden", body.error_code);
+        assert_eq!(
+            "The caller is not allowed to list users.",
+            body.error_description
+        );
+    }
+
+    #[tokio::test]
+    async fn bootstrap_commits_transaction() {
+        let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let user_service = Mutex::new(MockUserService::new());
+
+        let bootstrap_result = bootstrap_admin(
+            domain::user::CreateUser {
+                first_name: "Ada".to_owned(),
+                last_name: "Admin".to_owned(),
+            },
+            &ext_cxn,
+            &user_service,
+        )
+        .await;
+        assert!(matches!(
+            bootstrap_result,
+            Ok(AdminBootstrap::AdminAlreadyExists)
+        ));
+        assert_that!(ext_cxn.did_transaction_commit()).is_true();
+    }
+}
@@ -0,0 +1,803 @@
+use crate::api::access::ForbiddenResponse;
+use crate::domain::access::Caller;
+use crate::domain::attachment::driving_ports::{AttachmentError, AttachmentPort};
+use crate::domain::attachment::{AttachmentLimits, ByteStream, NewAttachment};
+use crate::external_connections::ExternalConnectivity;
+use crate::routing_utils::{GenericErrorResponse, Json};
+use crate::{dto, persistence};
+use axum::body::Body;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{ErrorResponse, IntoResponse, Response};
+use futures::TryStreamExt;
+use log::{error, info};
+use std::fmt::Write;
+
+/// The multipart form field a file is uploaded in
+pub const ATTACHMENT_FIELD: &str = "file";
+/// The name given to uploaded files which don't say what they're called
+const DEFAULT_FILE_NAME: &str = "attachment";
+/// The media type given to uploaded files which don't say what they are
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+/// The longest file name kept for an attachment. Longer names are cut short.
+const MAX_FILE_NAME_CHARS: usize = 255;
+
+/// Builds the response for a request which isn't a usable multipart upload
+fn invalid_upload_response(error_code: &str, error_description: &str) -> ErrorResponse {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(dto::BasicError {
+            error_code: error_code.to_owned(),
+            error_description: error_description.to_owned(),
+            extra_info: None,
+        }),
+    )
+        .into()
+}
+
+/// Converts an [AttachmentError] into the response describing it
+fn handle_attachment_err(err: AttachmentError) -> ErrorResponse {
+    let (status, error_code, error_description) = match err {
+        AttachmentError::UserDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_user",
+            "Could not find a user matching the given information.".to_owned(),
+        ),
+        AttachmentError::TaskDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_task",
+            "The specified task does not exist.".to_owned(),
+        ),
+        AttachmentError::AttachmentDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_attachment",
+            "The specified attachment does not exist on the task.".to_owned(),
+        ),
+        AttachmentError::FileTooLarge { max_bytes } => (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "attachment_too_large",
+            format!("Attachments may be at most {max_bytes} bytes."),
+        ),
+        AttachmentError::UnsupportedContentType(content_type) => (
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported_attachment_type",
+            format!("Files of type {content_type} can't be attached to tasks."),
+        ),
+        AttachmentError::NotUploader => (
+            StatusCode::FORBIDDEN,
+            "not_attachment_uploader",
+            "Only the uploader of an attachment or the owner of its task may delete it.".to_owned(),
+        ),
+        AttachmentError::Forbidden(denied) => return ForbiddenResponse(denied).into(),
+        // The upload itself was cut off or malformed partway through, which is the client's problem
+        AttachmentError::PortError(err) if err.downcast_ref::<multer::Error>().is_some() => {
+            info!("Unreadable attachment upload: {err:#}");
+            return invalid_upload_response(
+                "invalid_upload",
+                "The uploaded file could not be read from the multipart form.",
+            );
+        }
+        AttachmentError::PortError(err) => {
+            error!("Task attachment failure: {err}");
+            return GenericErrorResponse(err).into();
+        }
+    };
+
+    (
+        status,
+        Json(dto::BasicError {
+            error_code: error_code.to_owned(),
+            error_description,
+            extra_info: None,
+        }),
+    )
+        .into()
+}
+
+/// Cleans up the name a file was uploaded with, dropping any directories a client included in it
+fn upload_file_name(file_name: Option<&str>) -> String {
+    let file_name = file_name
+        .unwrap_or_default()
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    if file_name.is_empty() {
+        return DEFAULT_FILE_NAME.to_owned();
+    }
+
+    file_name.chars().take(MAX_FILE_NAME_CHARS).collect()
+}
+
+/// Finds the file in a multipart upload, returning its details along with its contents. The contents are read
+/// from the request as they're consumed rather than all at once.
+async fn uploaded_file(
+    headers: &HeaderMap,
+    body: Body,
+) -> Result<(NewAttachment, ByteStream), ErrorResponse> {
+    let request_content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .unwrap_or_default();
+    let boundary = multer::parse_boundary(request_content_type).map_err(|_| {
+        invalid_upload_response(
+            "invalid_upload",
+            "Files must be uploaded as multipart/form-data.",
+        )
+    })?;
+
+    // Fields are read in order, so anything sent before the file is skipped over
+    let mut multipart = multer::Multipart::new(body.into_data_stream(), boundary);
+    loop {
+        let field = multipart.next_field().await.map_err(|err| {
+            info!("Unreadable attachment upload: {err}");
+            invalid_upload_response(
+                "invalid_upload",
+                "The uploaded file could not be read from the multipart form.",
+            )
+        })?;
+        let Some(field) = field else {
+            return Err(invalid_upload_response(
+                "missing_attachment_file",
+                "The multipart form must contain the file in a field named \"file\".",
+            ));
+        };
+        if field.name() != Some(ATTACHMENT_FIELD) {
+            continue;
+        }
+
+        let new_attachment = NewAttachment {
+            file_name: upload_file_name(field.file_name()),
+            content_type: field
+                .content_type()
+                .map(|content_type| content_type.to_string())
+                .unwrap_or_else(|| DEFAULT_CONTENT_TYPE.to_owned()),
+        };
+        let content: ByteStream = Box::pin(field.map_err(anyhow::Error::from));
+        return Ok((new_attachment, content));
+    }
+}
+
+/// Builds a Content-Disposition header which tells browsers to download a file under its original name. Names
+/// which aren't plain ASCII are given in full through `filename*`, with a simplified fallback for older clients.
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|character| match character {
+            '"' | '\\' => '_',
+            ' ' => ' ',
+            character if character.is_ascii_graphic() => character,
+            _ => '_',
+        })
+        .collect();
+    let mut encoded = String::with_capacity(file_name.len());
+    for byte in file_name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => {
+                let _ = write!(encoded, "%{byte:02X}");
+            }
+        }
+    }
+
+    format!("attachment; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+}
+
+/// Attaches a file to a task the user can see. The file is sent as the `file` field of a multipart form and is
+/// streamed into storage as it arrives, so its size is only limited by the configured attachment limit rather
+/// than the usual request body limit.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/tasks/{task_id}/attachments",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user uploading the file"),
+        ("task_id" = i32, Path, description = "The task to attach the file to"),
+    ),
+    request_body(content = AttachmentUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "File successfully attached", body = TaskAttachment),
+        (
+            status = 400,
+            description = "The request is not a multipart form (error code `invalid_upload`), or the form has no `file` field (error code `missing_attachment_file`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "missing_attachment_file",
+                "error_description": "The multipart form must contain the file in a field named \"file\".",
+                "extra_info": null,
+            })
+        ),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user does not exist or cannot see the task (error code `no_matching_task`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_task",
+                "error_description": "The specified task does not exist.",
+                "extra_info": null,
+            })
+        ),
+        (
+            status = 413,
+            description = "The file is larger than attachments may be (error code `attachment_too_large`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "attachment_too_large",
+                "error_description": "Attachments may be at most 26214400 bytes.",
+                "extra_info": null,
+            })
+        ),
+        (
+            status = 415,
+            description = "Files of this type may not be attached (error code `unsupported_attachment_type`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "unsupported_attachment_type",
+                "error_description": "Files of type application/zip can't be attached to tasks.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_attachment(
+    user_id: i32,
+    task_id: i32,
+    headers: &HeaderMap,
+    body: Body,
+    limits: &AttachmentLimits,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    attachment_service: &impl AttachmentPort,
+) -> Result<(StatusCode, Json<dto::TaskAttachment>), ErrorResponse> {
+    info!("User {user_id} attaching a file to task {task_id}");
+    let (new_attachment, content) = uploaded_file(headers, body).await?;
+
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let attachment_store = persistence::db_attachment_driven_ports::DbAttachmentStore;
+
+    let attachment = attachment_service
+        .upload_attachment(
+            caller,
+            user_id,
+            task_id,
+            &new_attachment,
+            content,
+            limits,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &attachment_store,
+        )
+        .await
+        .map_err(handle_attachment_err)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(dto::TaskAttachment::from(attachment)),
+    ))
+}
+
+/// Lists the files attached to a task the user can see, oldest first
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/{task_id}/attachments",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user reading the attachments"),
+        ("task_id" = i32, Path, description = "The task the files are attached to"),
+    ),
+    responses(
+        (status = 200, description = "Attachments successfully retrieved", body = Vec<TaskAttachment>),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user does not exist or cannot see the task (error code `no_matching_task`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_task",
+                "error_description": "The specified task does not exist.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn get_attachments(
+    user_id: i32,
+    task_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    attachment_service: &impl AttachmentPort,
+) -> Result<Json<Vec<dto::TaskAttachment>>, ErrorResponse> {
+    info!("Listing attachments on task {task_id} for user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let attachment_store = persistence::db_attachment_driven_ports::DbAttachmentStore;
+
+    let attachments = attachment_service
+        .attachments_for_task(
+            caller,
+            user_id,
+            task_id,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &attachment_store,
+        )
+        .await
+        .map_err(handle_attachment_err)?;
+
+    Ok(Json(
+        attachments
+            .into_iter()
+            .map(dto::TaskAttachment::from)
+            .collect(),
+    ))
+}
+
+/// Downloads a file attached to a task the user can see. The file is streamed from storage rather than
+/// read into memory first.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/{task_id}/attachments/{attachment_id}",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user downloading the file"),
+        ("task_id" = i32, Path, description = "The task the file is attached to"),
+        ("attachment_id" = i32, Path, description = "The attachment to download"),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The attached file, with the media type it was uploaded with",
+            content(("application/octet-stream" = String, example = json!("(file contents)"))),
+        ),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user does not exist, cannot see the task, or the attachment is not on the task (error code `no_matching_attachment`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_attachment",
+                "error_description": "The specified attachment does not exist on the task.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn download_attachment(
+    user_id: i32,
+    task_id: i32,
+    attachment_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    attachment_service: &impl AttachmentPort,
+) -> Result<Response, ErrorResponse> {
+    info!("User {user_id} downloading attachment {attachment_id} on task {task_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let attachment_store = persistence::db_attachment_driven_ports::DbAttachmentStore;
+
+    let (attachment, content) = attachment_service
+        .download_attachment(
+            caller,
+            user_id,
+            task_id,
+            attachment_id,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &attachment_store,
+        )
+        .await
+        .map_err(handle_attachment_err)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type.clone()),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition(&attachment.file_name),
+            ),
+            (header::CONTENT_LENGTH, attachment.size_bytes.to_string()),
+            // Browsers shouldn't second-guess the type an uploader gave a file
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_owned()),
+        ],
+        Body::from_stream(content),
+    )
+        .into_response())
+}
+
+/// Deletes a file attached to a task. Uploaders may delete their own attachments, and the owner of a task may
+/// delete any file attached to it.
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/tasks/{task_id}/attachments/{attachment_id}",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user deleting the file"),
+        ("task_id" = i32, Path, description = "The task the file is attached to"),
+        ("attachment_id" = i32, Path, description = "The attachment to delete"),
+    ),
+    responses(
+        (status = 200, description = "Attachment successfully deleted"),
+        (
+            status = 403,
+            description = "The caller may not act for the user, or the user neither uploaded the file nor owns the task (error code `not_attachment_uploader`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "not_attachment_uploader",
+                "error_description": "Only the uploader of an attachment or the owner of its task may delete it.",
+                "extra_info": null,
+            })
+        ),
+        (
+            status = 404,
+            description = "The user does not exist, cannot see the task, or the attachment is not on the task (error code `no_matching_attachment`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_attachment",
+                "error_description": "The specified attachment does not exist on the task.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn delete_attachment(
+    user_id: i32,
+    task_id: i32,
+    attachment_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    attachment_service: &impl AttachmentPort,
+) -> Result<StatusCode, ErrorResponse> {
+    info!("User {user_id} deleting attachment {attachment_id} on task {task_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let attachment_store = persistence::db_attachment_driven_ports::DbAttachmentStore;
+
+    attachment_service
+        .delete_attachment(
+            caller,
+            user_id,
+            task_id,
+            attachment_id,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &attachment_store,
+        )
+        .await
+        .map_err(handle_attachment_err)?;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain;
+    use crate::domain::attachment::test_util::MockAttachmentService;
+    use crate::external_connections;
+    use anyhow::anyhow;
+    use axum::http::HeaderValue;
+    use chrono::{TimeZone, Utc};
+
+    const BOUNDARY: &str = "attachment-test-boundary";
+
+    fn domain_attachment() -> domain::attachment::Attachment {
+        domain::attachment::Attachment {
+            id: 7,
+            task_id: 10,
+            uploader_user_id: 1,
+            file_name: "receipt.png".to_owned(),
+            content_type: "image/png".to_owned(),
+            size_bytes: 16,
+            storage_key: "tasks/10/abc123".to_owned(),
+            created_at: Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap(),
+        }
+    }
+
+    /// Builds the headers and body of a multipart upload with the given fields, each written as
+    /// `(field name, file name, content type, contents)`
+    fn multipart_upload(fields: &[(&str, &str, &str, &str)]) -> (HeaderMap, Body) {
+        let mut body = String::new();
+        for (name, file_name, content_type, content) in fields {
+            body.push_str(&format!(
+                "--{BOUNDARY}\r\n\
+                Content-Disposition: form-data; name=\"{name}\"; filename=\"{file_name}\"\r\n\
+                Content-Type: {content_type}\r\n\r\n\
+                {content}\r\n"
+            ));
+        }
+        body.push_str(&format!("--{BOUNDARY}--\r\n"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_str(&format!("multipart/form-data; boundary={BOUNDARY}")).unwrap(),
+        );
+        (headers, Body::from(body))
+    }
+
+    mod upload_attachment {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let attachment_service = MockAttachmentService::build_locked(|svc| {
+                svc.upload_attachment_result
+                    .set_returned_result(Ok(domain_attachment()));
+            });
+            let (headers, body) = multipart_upload(&[
+                ("note", "note.txt", "text/plain", "ignored"),
+                (
+                    "file",
+                    "C:\\Users\\me\\receipt.png",
+                    "image/png",
+                    "not really a png",
+                ),
+            ]);
+
+            let (status, Json(attachment)) = upload_attachment(
+                1,
+                10,
+                &headers,
+                body,
+                &AttachmentLimits::default(),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &attachment_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(StatusCode::CREATED, status);
+            assert_eq!(dto::TaskAttachment::from(domain_attachment()), attachment);
+
+            let locked_service = attachment_service.lock().unwrap();
+            assert_eq!(
+                [(
+                    1,
+                    10,
+                    NewAttachment {
+                        file_name: "receipt.png".to_owned(),
+                        content_type: "image/png".to_owned(),
+                    }
+                )],
+                locked_service.upload_attachment_result.calls()
+            );
+            assert_eq!(
+                b"not really a png".to_vec(),
+                locked_service.uploaded_content
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_requests_which_are_not_multipart() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let attachment_service = MockAttachmentService::build_locked(|_| {});
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+
+            let response = upload_attachment(
+                1,
+                10,
+                &headers,
+                Body::from("{}"),
+                &AttachmentLimits::default(),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &attachment_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("invalid_upload", body.error_code);
+        }
+
+        #[tokio::test]
+        async fn rejects_forms_without_a_file() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let attachment_service = MockAttachmentService::build_locked(|_| {});
+            let (headers, body) =
+                multipart_upload(&[("document", "receipt.png", "image/png", "png")]);
+
+            let response = upload_attachment(
+                1,
+                10,
+                &headers,
+                body,
+                &AttachmentLimits::default(),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &attachment_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("missing_attachment_file", body.error_code);
+            assert!(attachment_service
+                .lock()
+                .unwrap()
+                .upload_attachment_result
+                .calls()
+                .is_empty());
+        }
+
+        #[tokio::test]
+        async fn maps_errors_to_responses() {
+            let cases = [
+                (
+                    AttachmentError::FileTooLarge { max_bytes: 10 },
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "attachment_too_large",
+                ),
+                (
+                    AttachmentError::UnsupportedContentType("application/zip".to_owned()),
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    "unsupported_attachment_type",
+                ),
+                (
+                    AttachmentError::TaskDoesNotExist,
+                    StatusCode::NOT_FOUND,
+                    "no_matching_task",
+                ),
+                (
+                    AttachmentError::PortError(anyhow!("Whoopsy daisy")),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                ),
+            ];
+
+            for (attachment_err, expected_status, expected_code) in cases {
+                let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let attachment_service = MockAttachmentService::build_locked(|svc| {
+                    svc.upload_attachment_result
+                        .set_returned_result(Err(attachment_err));
+                });
+                let (headers, body) =
+                    multipart_upload(&[("file", "receipt.png", "image/png", "png")]);
+
+                let response = upload_attachment(
+                    1,
+                    10,
+                    &headers,
+                    body,
+                    &AttachmentLimits::default(),
+                    &Caller::Trusted,
+                    &mut ext_cxn,
+                    &attachment_service,
+                )
+                .await
+                .into_response();
+                assert_eq!(expected_status, response.status());
+
+                let body: dto::BasicError = deserialize_body(response.into_body()).await;
+                assert_eq!(expected_code, body.error_code);
+            }
+        }
+    }
+
+    mod download_attachment {
+        use super::*;
+
+        #[tokio::test]
+        async fn streams_file_with_its_details() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let attachment_service = MockAttachmentService::build_locked(|svc| {
+                svc.download_attachment_result
+                    .set_returned_result(Ok((domain_attachment(), b"not really a png".to_vec())));
+            });
+
+            let response = download_attachment(
+                1,
+                10,
+                7,
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &attachment_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(StatusCode::OK, response.status());
+            assert_eq!("image/png", response.headers()[header::CONTENT_TYPE]);
+            assert_eq!(
+                "attachment; filename=\"receipt.png\"; filename*=UTF-8''receipt.png",
+                response.headers()[header::CONTENT_DISPOSITION]
+            );
+
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert_eq!(b"not really a png".as_slice(), body.as_ref());
+            assert_eq!(
+                [(1, 10, 7)],
+                attachment_service
+                    .lock()
+                    .unwrap()
+                    .download_attachment_result
+                    .calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn returns_404_for_missing_attachment() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let attachment_service = MockAttachmentService::build_locked(|svc| {
+                svc.download_attachment_result
+                    .set_returned_result(Err(AttachmentError::AttachmentDoesNotExist));
+            });
+
+            let response = download_attachment(
+                1,
+                10,
+                7,
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &attachment_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_attachment", body.error_code);
+        }
+
+        #[test]
+        fn encodes_names_which_are_not_plain_ascii() {
+            assert_eq!(
+                "attachment; filename=\"r_sum_ _final_.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9%20%22final%22.pdf",
+                content_disposition("résumé \"final\".pdf")
+            );
+        }
+    }
+
+    mod delete_attachment {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_403_for_other_uploaders() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let attachment_service = MockAttachmentService::build_locked(|svc| {
+                svc.delete_attachment_result
+                    .set_returned_result(Err(AttachmentError::NotUploader));
+            });
+
+            let response = delete_attachment(
+                2,
+                10,
+                7,
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &attachment_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("not_attachment_uploader", body.error_code);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::api::access::{ForbiddenResponse, RequestCaller};
+use crate::domain::access::{Action, Caller};
+use crate::domain::audit::driving_ports::AuditPort;
+use crate::external_connections::ExternalConnectivity;
+use crate::routing_utils::{GenericErrorResponse, Json, ValidationErrorResponse};
+use crate::{domain, dto, persistence, AppState, SharedData};
+use axum::extract::{Query, State};
+use axum::response::ErrorResponse;
+use axum::routing::get;
+use axum::Router;
+use log::{error, info};
+use std::sync::Arc;
+use utoipa::OpenApi;
+use validator::Validate;
+
+#[derive(OpenApi)]
+#[openapi(paths(get_audit_entries))]
+/// Defines the OpenAPI documentation for the audit API
+pub struct AuditApi;
+/// Constant used to group audit endpoints in OpenAPI documentation
+pub const AUDIT_API_GROUP: &str = "Audit";
+
+/// Creates a router for endpoints under the "/audit" group of APIs
+pub fn audit_routes() -> Router<Arc<SharedData>> {
+    Router::new().route(
+        "/",
+        get(
+            |State(app_data): AppState,
+             RequestCaller(caller): RequestCaller,
+             Query(params): Query<dto::AuditLogParams>| async move {
+                let audit_service = domain::audit::AuditService;
+                let mut ext_cxn = app_data.ext_cxn.clone();
+
+                get_audit_entries(params, &caller, &mut ext_cxn, &audit_service).await
+            },
+        ),
+    )
+}
+
+/// Searches the record of every change made to users and tasks, newest first. Only admins may search it. To page through
+/// the results, pass the ID of the last entry received as `before_id` on the next request.
+#[utoipa::path(
+    get,
+    path = "/audit",
+    tag = AUDIT_API_GROUP,
+    params(dto::AuditLogParams),
+    responses(
+        (status = 200, description = "Audit entries successfully retrieved", body = Vec<AuditEntry>),
+        (status = 400, response = dto::err_resps::BasicError400Validation),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn get_audit_entries(
+    params: dto::AuditLogParams,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    audit_service: &impl AuditPort,
+) -> Result<Json<Vec<dto::AuditEntry>>, ErrorResponse> {
+    info!("Searching the audit log");
+    domain::access::authorize(caller, Action::Administer).map_err(ForbiddenResponse)?;
+    params.validate().map_err(ValidationErrorResponse::from)?;
+
+    let audit_read = persistence::db_audit_driven_ports::DbAuditLog;
+    let filter = domain::audit::AuditFilter::from(params);
+
+    let entries = audit_service
+        .audit_entries(&filter, &mut *ext_cxn, &audit_read)
+        .await
+        .map_err(|err| {
+            error!("Could not search the audit log: {err}");
+            GenericErrorResponse(err)
+        })?;
+
+    Ok(Json(
+        entries.into_iter().map(dto::AuditEntry::from).collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain::audit::test_util::{entry, MockAuditService};
+    use crate::domain::audit::AuditEntityType;
+    use crate::external_connections;
+    use anyhow::anyhow;
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn happy_path() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let audit_service = MockAuditService::build_locked(|svc| {
+            svc.audit_entries_result.set_returned_anyhow(Ok(vec![entry(
+                7,
+                AuditEntityType::Task,
+                3,
+            )]));
+        });
+        let since = chrono::Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let params = dto::AuditLogParams {
+            entity_type: Some(dto::AuditEntityType::Task),
+            entity_id: Some(3),
+            since: Some(since),
+            ..dto::AuditLogParams::default()
+        };
+
+        let Json(entries) =
+            get_audit_entries(params, &Caller::Trusted, &mut ext_cxn, &audit_service)
+                .await
+                .unwrap_or_else(|err| {
+                    panic!("Didn't get the expected response! Error: {:#?}", err);
+                });
+
+        assert!(matches!(
+            entries.as_slice(),
+            [dto::AuditEntry {
+                id: 7,
+                actor_user_id: Some(1),
+                action: dto::AuditAction::Update,
+                entity_type: dto::AuditEntityType::Task,
+                entity_id: 3,
+                before: Some(_),
+                after: Some(_),
+                ..
+            }]
+        ));
+
+        let locked_service = audit_service.lock().unwrap();
+        assert_eq!(
+            [domain::audit::AuditFilter {
+                entity_type: Some(AuditEntityType::Task),
+                entity_id: Some(3),
+                since: Some(since),
+                ..domain::audit::AuditFilter::default()
+            }],
+            locked_service.audit_entries_result.calls()
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_filters() {
+        let since = chrono::Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let invalid_params = [
+            dto::AuditLogParams {
+                entity_id: Some(3),
+                ..dto::AuditLogParams::default()
+            },
+            dto::AuditLogParams {
+                since: Some(since),
+                until: Some(since - chrono::Duration::hours(1)),
+                ..dto::AuditLogParams::default()
+            },
+            dto::AuditLogParams {
+                limit: Some(0),
+                ..dto::AuditLogParams::default()
+            },
+        ];
+
+        for params in invalid_params {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let audit_service = MockAuditService::build_locked(|_| {});
+
+            let response =
+                get_audit_entries(params, &Caller::Trusted, &mut ext_cxn, &audit_service)
+                    .await
+                    .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("invalid_input", body.error_code);
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_500_when_service_blows_up() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let audit_service = MockAuditService::build_locked(|svc| {
+            svc.audit_entries_result
+                .set_returned_anyhow(Err(anyhow!("Whoopsy daisy")));
+        });
+
+        let response = get_audit_entries(
+            dto::AuditLogParams::default(),
+            &Caller::Trusted,
+            &mut ext_cxn,
+            &audit_service,
+        )
+        .await
+        .into_response();
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+
+        let body: dto::BasicError = deserialize_body(response.into_body()).await;
+        assert_eq!("internal_error", body.error_code);
+    }
+}
@@ -0,0 +1,341 @@
+use crate::domain::access::{Action, Caller};
+use crate::domain::calendar_feed::driving_ports::{CalendarFeedPort, FeedError};
+use crate::external_connections::ExternalConnectivity;
+use crate::routing_utils::{GenericErrorResponse, Json};
+use crate::{domain, dto, persistence};
+use axum::http::{header, StatusCode};
+use axum::response::ErrorResponse;
+use log::{error, info, warn};
+
+/// The content type of a calendar feed
+const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// Converts a [FeedError] into the response describing it
+fn handle_feed_err(err: FeedError) -> ErrorResponse {
+    match err {
+        FeedError::UserDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            Json(dto::BasicError {
+                error_code: "no_matching_user".to_owned(),
+                error_description: "Could not find a user matching the given information."
+                    .to_owned(),
+                extra_info: None,
+            }),
+        )
+            .into(),
+        FeedError::InvalidToken => (
+            StatusCode::UNAUTHORIZED,
+            Json(dto::BasicError {
+                error_code: "invalid_feed_token".to_owned(),
+                error_description: "The feed token is missing, incorrect or has been revoked."
+                    .to_owned(),
+                extra_info: None,
+            }),
+        )
+            .into(),
+        FeedError::PortError(err) => {
+            error!("Calendar feed failure: {err}");
+            GenericErrorResponse(err).into()
+        }
+    }
+}
+
+/// Retrieves a user's tasks as an iCalendar feed containing a VTODO for each task, for subscribing to from a
+/// calendar app. The feed is authenticated by the user's feed token rather than the usual headers, so its URL
+/// can be shared with the calendar app as-is.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks.ics",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose tasks are in the feed"),
+        dto::CalendarFeedParams,
+    ),
+    responses(
+        (status = 200, description = "The user's tasks as an iCalendar document", content_type = "text/calendar", body = String),
+        (
+            status = 401,
+            description = "The feed token is missing, incorrect or has been revoked (error code `invalid_feed_token`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "invalid_feed_token",
+                "error_description": "The feed token is missing, incorrect or has been revoked.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn get_task_calendar(
+    user_id: i32,
+    params: dto::CalendarFeedParams,
+    ext_cxn: &mut impl ExternalConnectivity,
+    feed_service: &impl CalendarFeedPort,
+) -> Result<([(header::HeaderName, &'static str); 1], String), ErrorResponse> {
+    info!("Get calendar feed for user {user_id}");
+    let Some(token) = params.token else {
+        warn!("Calendar feed for user {user_id} was requested without a token");
+        return Err(handle_feed_err(FeedError::InvalidToken));
+    };
+
+    let token_store = persistence::db_calendar_feed_driven_ports::DbFeedTokenStore;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+
+    let calendar = feed_service
+        .task_calendar(user_id, &token, &mut *ext_cxn, &token_store, &task_read)
+        .await
+        .map_err(handle_feed_err)?;
+
+    Ok(([(header::CONTENT_TYPE, CALENDAR_CONTENT_TYPE)], calendar))
+}
+
+/// Issues a new token for a user's calendar feed, revoking the token they had before. The token is only
+/// returned once, so it should be put straight into the feed's URL.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/feed-token",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user to issue a feed token for"),
+    ),
+    responses(
+        (status = 201, description = "Feed token successfully issued", body = FeedToken),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The requested user does not exist in the system (error code `no_matching_user`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_user",
+                "error_description": "Could not find a user matching the given information.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn issue_feed_token(
+    user_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    feed_service: &impl CalendarFeedPort,
+) -> Result<(StatusCode, Json<dto::FeedToken>), ErrorResponse> {
+    info!("Issuing a calendar feed token for user {user_id}");
+    domain::access::authorize(
+        caller,
+        Action::ManageTasks {
+            owner_user_id: user_id,
+        },
+    )
+    .map_err(super::access::ForbiddenResponse)?;
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let token_store = persistence::db_calendar_feed_driven_ports::DbFeedTokenStore;
+
+    let token = feed_service
+        .issue_feed_token(user_id, &mut *ext_cxn, &user_detect, &token_store)
+        .await
+        .map_err(handle_feed_err)?;
+
+    Ok((StatusCode::CREATED, Json(dto::FeedToken { token })))
+}
+
+/// Revokes a user's calendar feed token, after which their feed can't be read until a new token is issued
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/feed-token",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose feed token should be revoked"),
+    ),
+    responses(
+        (status = 200, description = "Feed token successfully revoked"),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 404, response = dto::err_resps::BasicError404),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn revoke_feed_token(
+    user_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    feed_service: &impl CalendarFeedPort,
+) -> Result<StatusCode, ErrorResponse> {
+    info!("Revoking the calendar feed token for user {user_id}");
+    domain::access::authorize(
+        caller,
+        Action::ManageTasks {
+            owner_user_id: user_id,
+        },
+    )
+    .map_err(super::access::ForbiddenResponse)?;
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let token_store = persistence::db_calendar_feed_driven_ports::DbFeedTokenStore;
+
+    feed_service
+        .revoke_feed_token(user_id, &mut *ext_cxn, &user_detect, &token_store)
+        .await
+        .map_err(handle_feed_err)?;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain::calendar_feed::test_util::MockCalendarFeedService;
+    use crate::external_connections;
+    use anyhow::anyhow;
+    use axum::response::IntoResponse;
+
+    mod get_task_calendar {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let feed_service = MockCalendarFeedService::build_locked(|svc| {
+                svc.task_calendar_result
+                    .set_returned_result(Ok("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n".to_owned()));
+            });
+            let params = dto::CalendarFeedParams {
+                token: Some("abc123".to_owned()),
+            };
+
+            let response = get_task_calendar(3, params, &mut ext_cxn, &feed_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::OK, response.status());
+            assert_eq!(
+                Some(CALENDAR_CONTENT_TYPE),
+                response
+                    .headers()
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+            );
+
+            let locked_service = feed_service.lock().unwrap();
+            assert!(matches!(
+                locked_service.task_calendar_result.calls(),
+                [(3, token)] if token == "abc123"
+            ));
+        }
+
+        #[tokio::test]
+        async fn rejects_missing_token() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let feed_service = MockCalendarFeedService::new_locked();
+
+            let response = get_task_calendar(
+                3,
+                dto::CalendarFeedParams { token: None },
+                &mut ext_cxn,
+                &feed_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("invalid_feed_token", body.error_code);
+
+            let locked_service = feed_service.lock().unwrap();
+            assert!(locked_service.task_calendar_result.calls().is_empty());
+        }
+
+        #[tokio::test]
+        async fn rejects_invalid_token() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let feed_service = MockCalendarFeedService::build_locked(|svc| {
+                svc.task_calendar_result
+                    .set_returned_result(Err(FeedError::InvalidToken));
+            });
+            let params = dto::CalendarFeedParams {
+                token: Some("abc123".to_owned()),
+            };
+
+            let response = get_task_calendar(3, params, &mut ext_cxn, &feed_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("invalid_feed_token", body.error_code);
+        }
+    }
+
+    mod issue_feed_token {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let feed_service = MockCalendarFeedService::build_locked(|svc| {
+                svc.issue_feed_token_result
+                    .set_returned_result(Ok("abc123".to_owned()));
+            });
+
+            let (status, Json(feed_token)) =
+                issue_feed_token(3, &Caller::Trusted, &mut ext_cxn, &feed_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+            assert_eq!(StatusCode::CREATED, status);
+            assert_eq!("abc123", feed_token.token);
+        }
+
+        #[tokio::test]
+        async fn returns_404_on_user_not_found() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let feed_service = MockCalendarFeedService::build_locked(|svc| {
+                svc.issue_feed_token_result
+                    .set_returned_result(Err(FeedError::UserDoesNotExist));
+            });
+
+            let response = issue_feed_token(3, &Caller::Trusted, &mut ext_cxn, &feed_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_user", body.error_code);
+        }
+    }
+
+    mod revoke_feed_token {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let feed_service = MockCalendarFeedService::build_locked(|svc| {
+                svc.revoke_feed_token_result.set_returned_result(Ok(()));
+            });
+
+            let revoke_result =
+                revoke_feed_token(3, &Caller::Trusted, &mut ext_cxn, &feed_service).await;
+            assert!(matches!(revoke_result, Ok(StatusCode::OK)));
+
+            let locked_service = feed_service.lock().unwrap();
+            assert_eq!(&[3], locked_service.revoke_feed_token_result.calls());
+        }
+
+        #[tokio::test]
+        async fn returns_500_when_service_blows_up() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let feed_service = MockCalendarFeedService::build_locked(|svc| {
+                svc.revoke_feed_token_result
+                    .set_returned_result(Err(FeedError::PortError(anyhow!("Whoopsy daisy"))));
+            });
+
+            let response = revoke_feed_token(3, &Caller::Trusted, &mut ext_cxn, &feed_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("internal_error", body.error_code);
+        }
+    }
+}
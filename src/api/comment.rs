@@ -0,0 +1,610 @@
+use crate::api::access::ForbiddenResponse;
+use crate::domain::access::Caller;
+use crate::domain::comment::driving_ports::{CommentError, CommentPort};
+use crate::external_connections::ExternalConnectivity;
+use crate::routing_utils::{GenericErrorResponse, Json, ValidationErrorResponse};
+use crate::{domain, dto, persistence};
+use axum::http::StatusCode;
+use axum::response::ErrorResponse;
+use log::{error, info};
+use validator::Validate;
+
+/// Converts a [CommentError] into the response describing it
+fn handle_comment_err(err: CommentError) -> ErrorResponse {
+    let (status, error_code, error_description) = match err {
+        CommentError::UserDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_user",
+            "Could not find a user matching the given information.",
+        ),
+        CommentError::TaskDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_task",
+            "The specified task does not exist.",
+        ),
+        CommentError::CommentDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_comment",
+            "The specified comment does not exist on the task.",
+        ),
+        CommentError::ParentCommentDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_parent_comment",
+            "The comment being replied to does not exist on the task.",
+        ),
+        CommentError::NotCommentAuthor => (
+            StatusCode::FORBIDDEN,
+            "not_comment_author",
+            "Only the author of a comment may change it.",
+        ),
+        CommentError::Forbidden(denied) => return ForbiddenResponse(denied).into(),
+        CommentError::PortError(err) => {
+            error!("Task comment failure: {err}");
+            return GenericErrorResponse(err).into();
+        }
+    };
+
+    (
+        status,
+        Json(dto::BasicError {
+            error_code: error_code.to_owned(),
+            error_description: error_description.to_owned(),
+            extra_info: None,
+        }),
+    )
+        .into()
+}
+
+/// Lists the comments on a task the user can see, oldest first. To page through the comments, pass the ID
+/// of the last comment received as `after_id` on the next request.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/{task_id}/comments",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user reading the comments"),
+        ("task_id" = i32, Path, description = "The task the comments were left on"),
+        dto::CommentPageParams,
+    ),
+    responses(
+        (status = 200, description = "Comments successfully retrieved", body = Vec<TaskComment>),
+        (status = 400, response = dto::err_resps::BasicError400Validation),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user does not exist or cannot see the task (error code `no_matching_task`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_task",
+                "error_description": "The specified task does not exist.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn get_comments(
+    user_id: i32,
+    task_id: i32,
+    params: dto::CommentPageParams,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    comment_service: &impl CommentPort,
+) -> Result<Json<Vec<dto::TaskComment>>, ErrorResponse> {
+    info!("Listing comments on task {task_id} for user {user_id}");
+    params.validate().map_err(ValidationErrorResponse::from)?;
+
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let comment_store = persistence::db_comment_driven_ports::DbCommentStore;
+    let page = domain::comment::CommentPage::from(params);
+
+    let comments = comment_service
+        .comments_for_task(
+            caller,
+            user_id,
+            task_id,
+            &page,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &comment_store,
+        )
+        .await
+        .map_err(handle_comment_err)?;
+
+    Ok(Json(
+        comments.into_iter().map(dto::TaskComment::from).collect(),
+    ))
+}
+
+/// Leaves a comment on a task the user can see, either starting a new thread or replying to another comment
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/tasks/{task_id}/comments",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user writing the comment"),
+        ("task_id" = i32, Path, description = "The task to comment on"),
+    ),
+    request_body = NewComment,
+    responses(
+        (status = 201, description = "Comment successfully added", body = TaskComment),
+        (status = 400, response = dto::err_resps::BasicError400Validation),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user does not exist, cannot see the task, or the comment being replied to is not on the task",
+            body = BasicError,
+            examples(
+                ("No task" = (
+                    summary = "User cannot see the task (error code no_matching_task)",
+                    value = json!({
+                        "error_code": "no_matching_task",
+                        "error_description": "The specified task does not exist.",
+                        "extra_info": null,
+                    })
+                )),
+                ("No parent comment" = (
+                    summary = "Comment being replied to does not exist (error code no_matching_parent_comment)",
+                    value = json!({
+                        "error_code": "no_matching_parent_comment",
+                        "error_description": "The comment being replied to does not exist on the task.",
+                        "extra_info": null,
+                    })
+                ))
+            )
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn add_comment(
+    user_id: i32,
+    task_id: i32,
+    new_comment: dto::NewComment,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    comment_service: &impl CommentPort,
+) -> Result<(StatusCode, Json<dto::TaskComment>), ErrorResponse> {
+    info!("User {user_id} commenting on task {task_id}");
+    new_comment
+        .validate()
+        .map_err(ValidationErrorResponse::from)?;
+
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let comment_store = persistence::db_comment_driven_ports::DbCommentStore;
+    let new_comment = domain::comment::NewComment::from(new_comment);
+
+    let comment = comment_service
+        .add_comment(
+            caller,
+            user_id,
+            task_id,
+            &new_comment,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &comment_store,
+        )
+        .await
+        .map_err(handle_comment_err)?;
+
+    Ok((StatusCode::CREATED, Json(dto::TaskComment::from(comment))))
+}
+
+/// Changes the body of a comment. Only the comment's author may edit it.
+#[utoipa::path(
+    patch,
+    path = "/users/{user_id}/tasks/{task_id}/comments/{comment_id}",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user who wrote the comment"),
+        ("task_id" = i32, Path, description = "The task the comment was left on"),
+        ("comment_id" = i32, Path, description = "The comment to edit"),
+    ),
+    request_body = UpdateComment,
+    responses(
+        (status = 200, description = "Comment successfully edited", body = TaskComment),
+        (status = 400, response = dto::err_resps::BasicError400Validation),
+        (
+            status = 403,
+            description = "The caller may not act for the user, or the user did not write the comment (error code `not_comment_author`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "not_comment_author",
+                "error_description": "Only the author of a comment may change it.",
+                "extra_info": null,
+            })
+        ),
+        (
+            status = 404,
+            description = "The user does not exist, cannot see the task, or the comment is not on the task (error code `no_matching_comment`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_comment",
+                "error_description": "The specified comment does not exist on the task.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn edit_comment(
+    user_id: i32,
+    task_id: i32,
+    comment_id: i32,
+    update: dto::UpdateComment,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    comment_service: &impl CommentPort,
+) -> Result<Json<dto::TaskComment>, ErrorResponse> {
+    info!("User {user_id} editing comment {comment_id} on task {task_id}");
+    update.validate().map_err(ValidationErrorResponse::from)?;
+
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let comment_store = persistence::db_comment_driven_ports::DbCommentStore;
+
+    let comment = comment_service
+        .edit_comment(
+            caller,
+            user_id,
+            task_id,
+            comment_id,
+            &update.body,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &comment_store,
+        )
+        .await
+        .map_err(handle_comment_err)?;
+
+    Ok(Json(dto::TaskComment::from(comment)))
+}
+
+/// Deletes a comment along with every reply to it. Authors may delete their own comments, and the owner of
+/// a task may delete any comment left on it.
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/tasks/{task_id}/comments/{comment_id}",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user deleting the comment"),
+        ("task_id" = i32, Path, description = "The task the comment was left on"),
+        ("comment_id" = i32, Path, description = "The comment to delete"),
+    ),
+    responses(
+        (status = 200, description = "Comment successfully deleted"),
+        (
+            status = 403,
+            description = "The caller may not act for the user, or the user neither wrote the comment nor owns the task (error code `not_comment_author`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "not_comment_author",
+                "error_description": "Only the author of a comment may change it.",
+                "extra_info": null,
+            })
+        ),
+        (
+            status = 404,
+            description = "The user does not exist, cannot see the task, or the comment is not on the task (error code `no_matching_comment`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_comment",
+                "error_description": "The specified comment does not exist on the task.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn delete_comment(
+    user_id: i32,
+    task_id: i32,
+    comment_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    comment_service: &impl CommentPort,
+) -> Result<StatusCode, ErrorResponse> {
+    info!("User {user_id} deleting comment {comment_id} on task {task_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let comment_store = persistence::db_comment_driven_ports::DbCommentStore;
+
+    comment_service
+        .delete_comment(
+            caller,
+            user_id,
+            task_id,
+            comment_id,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &comment_store,
+        )
+        .await
+        .map_err(handle_comment_err)?;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain::comment::test_util::MockCommentService;
+    use crate::external_connections;
+    use anyhow::anyhow;
+    use axum::response::IntoResponse;
+    use chrono::{TimeZone, Utc};
+
+    fn domain_comment() -> domain::comment::TaskComment {
+        domain::comment::TaskComment {
+            id: 13,
+            task_id: 10,
+            author_user_id: 1,
+            parent_comment_id: Some(12),
+            body: "Can we move this to Friday?".to_owned(),
+            created_at: Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap(),
+            updated_at: None,
+        }
+    }
+
+    mod get_comments {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let comment_service = MockCommentService::build_locked(|svc| {
+                svc.comments_for_task_result
+                    .set_returned_result(Ok(vec![domain_comment()]));
+            });
+
+            let Json(comments) = get_comments(
+                1,
+                10,
+                dto::CommentPageParams {
+                    after_id: Some(12),
+                    limit: None,
+                },
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &comment_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(vec![dto::TaskComment::from(domain_comment())], comments);
+
+            let locked_service = comment_service.lock().unwrap();
+            assert_eq!(
+                [(
+                    1,
+                    10,
+                    domain::comment::CommentPage {
+                        after_id: Some(12),
+                        limit: domain::comment::DEFAULT_COMMENT_PAGE_SIZE,
+                    }
+                )],
+                locked_service.comments_for_task_result.calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_out_of_range_limit() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let comment_service = MockCommentService::build_locked(|_| {});
+
+            let response = get_comments(
+                1,
+                10,
+                dto::CommentPageParams {
+                    after_id: None,
+                    limit: Some(0),
+                },
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &comment_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+            assert!(comment_service
+                .lock()
+                .unwrap()
+                .comments_for_task_result
+                .calls()
+                .is_empty());
+        }
+    }
+
+    mod add_comment {
+        use super::*;
+
+        fn new_comment(body: &str) -> dto::NewComment {
+            dto::NewComment {
+                body: body.to_owned(),
+                parent_comment_id: Some(12),
+            }
+        }
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let comment_service = MockCommentService::build_locked(|svc| {
+                svc.add_comment_result
+                    .set_returned_result(Ok(domain_comment()));
+            });
+
+            let (status, Json(comment)) = add_comment(
+                1,
+                10,
+                new_comment("Can we move this to Friday?"),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &comment_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(StatusCode::CREATED, status);
+            assert_eq!(dto::TaskComment::from(domain_comment()), comment);
+
+            let locked_service = comment_service.lock().unwrap();
+            assert_eq!(
+                [(
+                    1,
+                    10,
+                    domain::comment::NewComment {
+                        body: "Can we move this to Friday?".to_owned(),
+                        parent_comment_id: Some(12),
+                    }
+                )],
+                locked_service.add_comment_result.calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_empty_body() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let comment_service = MockCommentService::build_locked(|_| {});
+
+            let response = add_comment(
+                1,
+                10,
+                new_comment(""),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &comment_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        }
+
+        #[tokio::test]
+        async fn maps_errors_to_responses() {
+            let cases = [
+                (
+                    CommentError::TaskDoesNotExist,
+                    StatusCode::NOT_FOUND,
+                    "no_matching_task",
+                ),
+                (
+                    CommentError::ParentCommentDoesNotExist,
+                    StatusCode::NOT_FOUND,
+                    "no_matching_parent_comment",
+                ),
+                (
+                    CommentError::PortError(anyhow!("Whoopsy daisy")),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal_error",
+                ),
+            ];
+
+            for (comment_err, expected_status, expected_code) in cases {
+                let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let comment_service = MockCommentService::build_locked(|svc| {
+                    svc.add_comment_result.set_returned_result(Err(comment_err));
+                });
+
+                let response = add_comment(
+                    1,
+                    10,
+                    new_comment("Hello"),
+                    &Caller::Trusted,
+                    &mut ext_cxn,
+                    &comment_service,
+                )
+                .await
+                .into_response();
+                assert_eq!(expected_status, response.status());
+
+                let body: dto::BasicError = deserialize_body(response.into_body()).await;
+                assert_eq!(expected_code, body.error_code);
+            }
+        }
+    }
+
+    mod edit_comment {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_403_for_other_authors() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let comment_service = MockCommentService::build_locked(|svc| {
+                svc.edit_comment_result
+                    .set_returned_result(Err(CommentError::NotCommentAuthor));
+            });
+
+            let response = edit_comment(
+                2,
+                10,
+                13,
+                dto::UpdateComment {
+                    body: "Rewritten".to_owned(),
+                },
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &comment_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("not_comment_author", body.error_code);
+        }
+    }
+
+    mod delete_comment {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let comment_service = MockCommentService::build_locked(|svc| {
+                svc.delete_comment_result.set_returned_result(Ok(()));
+            });
+
+            let status =
+                delete_comment(1, 10, 13, &Caller::Trusted, &mut ext_cxn, &comment_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+            assert_eq!(StatusCode::OK, status);
+            assert_eq!(
+                [(1, 10, 13)],
+                comment_service
+                    .lock()
+                    .unwrap()
+                    .delete_comment_result
+                    .calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn returns_404_for_missing_comment() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let comment_service = MockCommentService::build_locked(|svc| {
+                svc.delete_comment_result
+                    .set_returned_result(Err(CommentError::CommentDoesNotExist));
+            });
+
+            let response =
+                delete_comment(1, 10, 13, &Caller::Trusted, &mut ext_cxn, &comment_service)
+                    .await
+                    .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_comment", body.error_code);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use crate::api::access::ForbiddenResponse;
+use crate::domain::access::Caller;
+use crate::domain::digest::driven_ports::DigestSender;
+use crate::domain::digest::driving_ports::{DigestError, DigestPort};
+use crate::domain::digest::DigestRun;
+use crate::external_connections::{
+    with_transaction, ExternalConnectivity, Savepointable, Transactable,
+};
+use crate::routing_utils::{GenericErrorResponse, Json, ValidationErrorResponse};
+use crate::{domain, dto, persistence};
+use axum::http::StatusCode;
+use axum::response::ErrorResponse;
+use chrono::Utc;
+use log::{error, info};
+use std::time::Duration;
+use validator::Validate;
+
+/// Converts a [DigestError] into the response describing it
+fn handle_digest_err(err: DigestError) -> ErrorResponse {
+    match err {
+        DigestError::UserDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            Json(dto::BasicError {
+                error_code: "no_matching_user".to_owned(),
+                error_description: "Could not find a user matching the given information."
+                    .to_owned(),
+                extra_info: None,
+            }),
+        )
+            .into(),
+        DigestError::Forbidden(denied) => ForbiddenResponse(denied).into(),
+        DigestError::PortError(err) => {
+            error!("Digest preference failure: {err}");
+            GenericErrorResponse(err).into()
+        }
+    }
+}
+
+/// Periodically emails users who asked for one a digest of their open tasks. Digests go out once a day, from
+/// [send_hour] o'clock UTC onwards. Each run happens in a transaction which keeps the users it claimed locked, so
+/// several instances of the app can poll without sending anyone the same digest twice.
+pub async fn send_digests<TxAble>(
+    ext_cxn: TxAble,
+    sender: impl DigestSender,
+    send_hour: u32,
+    poll_interval: Duration,
+) where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity + Savepointable,
+{
+    let digest_service = domain::digest::DigestService;
+    let queue = persistence::db_digest_driven_ports::DbDigestStore;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let mut interval = tokio::time::interval(poll_interval);
+
+    loop {
+        interval.tick().await;
+        let Some(day) = domain::digest::digest_day(Utc::now(), send_hour) else {
+            continue;
+        };
+        let run_result = with_transaction(&ext_cxn, async |tx_cxn| {
+            digest_service
+                .send_due_digests(day, &mut *tx_cxn, &queue, &task_read, &sender)
+                .await
+        })
+        .await;
+        match run_result {
+            Ok(run) if run == DigestRun::default() => {}
+            Ok(run) => info!(
+                "Sent digests for {day}: {} sent, {} skipped with no open tasks, {} failed",
+                run.sent, run.skipped, run.failed
+            ),
+            Err(run_err) => error!("Failed to send digests: {run_err}"),
+        }
+    }
+}
+
+/// Retrieves how a user wants to hear about their tasks by email
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/digest-preferences",
+    tag = super::user::USER_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose preferences to retrieve"),
+    ),
+    responses(
+        (status = 200, description = "Preferences successfully retrieved", body = DigestPreferences),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The requested user does not exist in the system (error code `no_matching_user`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_user",
+                "error_description": "Could not find a user matching the given information.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn get_digest_preferences(
+    user_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    digest_service: &impl DigestPort,
+) -> Result<Json<dto::DigestPreferences>, ErrorResponse> {
+    info!("Get digest preferences of user {user_id}");
+    let preference_store = persistence::db_digest_driven_ports::DbDigestStore;
+
+    let preferences = digest_service
+        .digest_preferences(caller, user_id, &mut *ext_cxn, &preference_store)
+        .await
+        .map_err(handle_digest_err)?;
+
+    Ok(Json(dto::DigestPreferences::from(preferences)))
+}
+
+/// Sets the user's email address and whether they're emailed a daily digest of their open and overdue tasks
+#[utoipa::path(
+    put,
+    path = "/users/{user_id}/digest-preferences",
+    tag = super::user::USER_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose preferences to change"),
+    ),
+    request_body = DigestPreferences,
+    responses(
+        (status = 200, description = "Preferences successfully changed", body = DigestPreferences),
+        (status = 400, response = dto::err_resps::BasicError400Validation),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The requested user does not exist in the system (error code `no_matching_user`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_user",
+                "error_description": "Could not find a user matching the given information.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn set_digest_preferences(
+    user_id: i32,
+    new_preferences: dto::DigestPreferences,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    digest_service: &impl DigestPort,
+) -> Result<Json<dto::DigestPreferences>, ErrorResponse> {
+    info!("Set digest preferences of user {user_id}");
+    new_preferences
+        .validate()
+        .map_err(ValidationErrorResponse::from)?;
+
+    let preference_store = persistence::db_digest_driven_ports::DbDigestStore;
+    let preferences = domain::digest::DigestPreferences::from(new_preferences);
+
+    digest_service
+        .set_digest_preferences(
+            caller,
+            user_id,
+            &preferences,
+            &mut *ext_cxn,
+            &preference_store,
+        )
+        .await
+        .map_err(handle_digest_err)?;
+
+    Ok(Json(dto::DigestPreferences::from(preferences)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain::digest::test_util::MockDigestService;
+    use crate::external_connections;
+    use anyhow::anyhow;
+    use axum::response::IntoResponse;
+
+    fn preferences() -> domain::digest::DigestPreferences {
+        domain::digest::DigestPreferences {
+            email: Some("dana@example.com".to_owned()),
+            daily_digest: true,
+        }
+    }
+
+    mod get_digest_preferences {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let digest_service = MockDigestService::build_locked(|svc| {
+                svc.digest_preferences_result
+                    .set_returned_result(Ok(preferences()));
+            });
+
+            let Json(returned) =
+                get_digest_preferences(1, &Caller::Trusted, &mut ext_cxn, &digest_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+            assert_eq!(dto::DigestPreferences::from(preferences()), returned);
+
+            let locked_service = digest_service.lock().unwrap();
+            assert_eq!([1], locked_service.digest_preferences_result.calls());
+        }
+
+        #[tokio::test]
+        async fn returns_404_for_missing_user() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let digest_service = MockDigestService::build_locked(|svc| {
+                svc.digest_preferences_result
+                    .set_returned_result(Err(DigestError::UserDoesNotExist));
+            });
+
+            let response =
+                get_digest_preferences(1, &Caller::Trusted, &mut ext_cxn, &digest_service)
+                    .await
+                    .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_user", body.error_code);
+        }
+    }
+
+    mod set_digest_preferences {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let digest_service = MockDigestService::build_locked(|svc| {
+                svc.set_digest_preferences_result
+                    .set_returned_result(Ok(()));
+            });
+
+            let Json(returned) = set_digest_preferences(
+                1,
+                dto::DigestPreferences::from(preferences()),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &digest_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(dto::DigestPreferences::from(preferences()), returned);
+
+            let locked_service = digest_service.lock().unwrap();
+            assert_eq!(
+                [(1, preferences())],
+                locked_service.set_digest_preferences_result.calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_digest_without_email() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let digest_service = MockDigestService::build_locked(|_| {});
+
+            let response = set_digest_preferences(
+                1,
+                dto::DigestPreferences {
+                    email: None,
+                    daily_digest: true,
+                },
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &digest_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+            let locked_service = digest_service.lock().unwrap();
+            assert!(locked_service
+                .set_digest_preferences_result
+                .calls()
+                .is_empty());
+        }
+
+        #[tokio::test]
+        async fn rejects_invalid_email() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let digest_service = MockDigestService::build_locked(|_| {});
+
+            let response = set_digest_preferences(
+                1,
+                dto::DigestPreferences {
+                    email: Some("not an address".to_owned()),
+                    daily_digest: false,
+                },
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &digest_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        }
+
+        #[tokio::test]
+        async fn returns_500_on_port_error() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let digest_service = MockDigestService::build_locked(|svc| {
+                svc.set_digest_preferences_result
+                    .set_returned_result(Err(DigestError::PortError(anyhow!("Whoops!"))));
+            });
+
+            let response = set_digest_preferences(
+                1,
+                dto::DigestPreferences::from(preferences()),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &digest_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        }
+    }
+}
@@ -0,0 +1,537 @@
+use crate::api::idempotency::{IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER};
+use crate::api::rate_limit::{
+    RATE_LIMIT_LIMIT_HEADER, RATE_LIMIT_POLICY_HEADER, RATE_LIMIT_REMAINING_HEADER,
+    RATE_LIMIT_RESET_HEADER,
+};
+use crate::dto;
+use crate::routing_utils::{Json, CALLING_USER_HEADER};
+use axum::extract::{DefaultBodyLimit, Request, State};
+use axum::http::{header, HeaderName, HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use log::warn;
+use std::str::FromStr;
+use std::time::Duration;
+use thiserror::Error;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Header browsers send to resume an event stream
+const LAST_EVENT_ID_HEADER: HeaderName = HeaderName::from_static("last-event-id");
+/// How long browsers may cache the answer to a CORS preflight request
+const CORS_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Settings for the HTTP middleware which wraps every route
+#[derive(Debug, Clone)]
+pub struct HttpSettings {
+    /// Which origins browsers may call the API from
+    pub cors_origins: CorsOrigins,
+    /// Whether responses are compressed for clients which accept it
+    pub compress_responses: bool,
+    /// The largest request body which will be accepted
+    pub max_request_bytes: usize,
+    /// How long a request may take before it's abandoned, if there's a limit
+    pub request_timeout: Option<Duration>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+/// Reasons HTTP middleware configuration can't be used
+pub enum HttpSettingsError {
+    #[error("\"{0}\" is not an origin such as https://example.com.")]
+    InvalidOrigin(String),
+}
+
+/// Which origins browsers may make cross-origin requests to the API from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorsOrigins {
+    /// Browsers may only call the API from its own origin
+    Disabled,
+    /// Browsers may call the API from any origin
+    Any,
+    /// Browsers may call the API from these origins
+    List(Vec<HeaderValue>),
+}
+
+impl FromStr for CorsOrigins {
+    type Err = HttpSettingsError;
+
+    /// Reads `*` for any origin, or a comma-separated list of origins such as
+    /// `https://example.com,http://localhost:3000`
+    fn from_str(origins: &str) -> Result<Self, Self::Err> {
+        if origins.trim() == "*" {
+            return Ok(CorsOrigins::Any);
+        }
+
+        let origins = origins
+            .split(',')
+            .map(str::trim)
+            .filter(|origin| !origin.is_empty())
+            .map(|origin| {
+                let has_scheme = origin.starts_with("http://") || origin.starts_with("https://");
+                match HeaderValue::from_str(origin) {
+                    Ok(origin_value) if has_scheme && !origin.ends_with('/') => Ok(origin_value),
+                    _ => Err(HttpSettingsError::InvalidOrigin(origin.to_owned())),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if origins.is_empty() {
+            Ok(CorsOrigins::Disabled)
+        } else {
+            Ok(CorsOrigins::List(origins))
+        }
+    }
+}
+
+/// Wraps every route of an app in the middleware configured by [HttpSettings]: CORS, response compression,
+/// a maximum request body size and a timeout. Requests which are rejected get a [dto::BasicError] like any
+/// other API error.
+pub fn apply_http_stack(router: Router, settings: &HttpSettings) -> Router {
+    let mut router = router.layer(DefaultBodyLimit::max(settings.max_request_bytes));
+    if let Some(request_timeout) = settings.request_timeout {
+        router = router.layer(middleware::from_fn_with_state(
+            request_timeout,
+            request_timeout_middleware,
+        ));
+    }
+    router = router.layer(middleware::from_fn_with_state(
+        settings.max_request_bytes,
+        body_limit_middleware,
+    ));
+    if settings.compress_responses {
+        router = router.layer(CompressionLayer::new());
+    }
+    if let Some(cors_layer) = cors_layer(&settings.cors_origins) {
+        router = router.layer(cors_layer);
+    }
+
+    router
+}
+
+/// Builds the CORS layer allowing the given origins to use the API, if any are allowed
+fn cors_layer(origins: &CorsOrigins) -> Option<CorsLayer> {
+    let allow_origin = match origins {
+        CorsOrigins::Disabled => return None,
+        CorsOrigins::Any => AllowOrigin::any(),
+        CorsOrigins::List(origins) => AllowOrigin::list(origins.iter().cloned()),
+    };
+    let calling_user_header = HeaderName::try_from(CALLING_USER_HEADER)
+        .expect("The calling user header should be a valid header name");
+
+    Some(
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
+            .allow_headers([
+                header::CONTENT_TYPE,
+                calling_user_header,
+                HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+                LAST_EVENT_ID_HEADER,
+            ])
+            .expose_headers([
+                header::CONTENT_DISPOSITION,
+                header::RETRY_AFTER,
+                HeaderName::from_static(IDEMPOTENT_REPLAYED_HEADER),
+                RATE_LIMIT_LIMIT_HEADER,
+                RATE_LIMIT_REMAINING_HEADER,
+                RATE_LIMIT_RESET_HEADER,
+                RATE_LIMIT_POLICY_HEADER,
+            ])
+            .max_age(CORS_MAX_AGE),
+    )
+}
+
+/// Middleware which abandons requests that take too long to respond to. Only producing the response is timed,
+/// so event streams, WebSockets and exports which keep sending their body afterwards aren't cut off.
+async fn request_timeout_middleware(
+    State(request_timeout): State<Duration>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_owned();
+
+    match tokio::time::timeout(request_timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            warn!("{method} {path} timed out after {request_timeout:?}");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(dto::BasicError {
+                    error_code: "request_timeout".to_owned(),
+                    error_description: format!(
+                        "The request could not be completed within {} seconds.",
+                        request_timeout.as_secs_f64()
+                    ),
+                    extra_info: None,
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Middleware which rejects request bodies larger than the limit. Bodies which say how big they are up front
+/// are rejected before reaching a route, and the plain-text rejections of extractors which find out while
+/// reading the body are replaced. Multipart uploads are streamed by the routes which accept them and held to
+/// the attachment size limit instead, so they're let through.
+async fn body_limit_middleware(
+    State(max_request_bytes): State<usize>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let declared_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<u64>().ok());
+    let is_multipart = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|content_type| content_type.as_bytes().starts_with(b"multipart/form-data"));
+    if !is_multipart && declared_length.is_some_and(|length| length > max_request_bytes as u64) {
+        return request_too_large_response(max_request_bytes);
+    }
+
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|content_type| content_type.as_bytes().starts_with(b"application/json"));
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return request_too_large_response(max_request_bytes);
+    }
+
+    response
+}
+
+fn request_too_large_response(max_request_bytes: usize) -> Response {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(dto::BasicError {
+            error_code: "request_too_large".to_owned(),
+            error_description: format!(
+                "The request body can't be larger than {max_request_bytes} bytes."
+            ),
+            extra_info: None,
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use axum::body::{self, Body, Bytes};
+    use axum::routing::{get, post};
+    use futures::{stream, StreamExt};
+    use tower::Service;
+
+    fn settings() -> HttpSettings {
+        HttpSettings {
+            cors_origins: CorsOrigins::List(vec![HeaderValue::from_static("https://example.com")]),
+            compress_responses: true,
+            max_request_bytes: 16,
+            request_timeout: Some(Duration::from_millis(50)),
+        }
+    }
+
+    fn test_app(settings: &HttpSettings) -> Router {
+        let routes = Router::new()
+            .route("/echo", post(|body: Bytes| async move { body }))
+            .route(
+                "/upload",
+                post(|body: Body| async move {
+                    let uploaded = body::to_bytes(body, usize::MAX).await.unwrap();
+                    uploaded.len().to_string()
+                }),
+            )
+            .route(
+                "/json",
+                post(|Json(body): Json<serde_json::Value>| async move { Json(body) }),
+            )
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                    "Done"
+                }),
+            )
+            .route(
+                "/stream",
+                get(|| async {
+                    let chunks = stream::unfold(0, |chunk| async move {
+                        if chunk == 2 {
+                            return None;
+                        }
+                        tokio::time::sleep(Duration::from_millis(60)).await;
+                        Some((Ok::<_, std::io::Error>(format!("chunk{chunk} ")), chunk + 1))
+                    });
+                    Body::from_stream(chunks)
+                }),
+            )
+            .route("/long", get(|| async { "Some text. ".repeat(100) }));
+
+        apply_http_stack(routes, settings)
+    }
+
+    mod cors_origins {
+        use super::*;
+
+        #[test]
+        fn reads_any_origin() {
+            assert_eq!(Ok(CorsOrigins::Any), " * ".parse());
+        }
+
+        #[test]
+        fn reads_list_of_origins() {
+            assert_eq!(
+                Ok(CorsOrigins::List(vec![
+                    HeaderValue::from_static("https://example.com"),
+                    HeaderValue::from_static("http://localhost:3000"),
+                ])),
+                "https://example.com, http://localhost:3000,".parse()
+            );
+        }
+
+        #[test]
+        fn empty_list_disables_cors() {
+            assert_eq!(Ok(CorsOrigins::Disabled), " ".parse());
+        }
+
+        #[test]
+        fn rejects_origins_which_are_not_urls() {
+            assert_eq!(
+                Err(HttpSettingsError::InvalidOrigin("example.com".to_owned())),
+                "https://example.com,example.com".parse::<CorsOrigins>()
+            );
+            assert_eq!(
+                Err(HttpSettingsError::InvalidOrigin(
+                    "https://example.com/".to_owned()
+                )),
+                "https://example.com/".parse::<CorsOrigins>()
+            );
+        }
+    }
+
+    mod body_limit {
+        use super::*;
+
+        #[tokio::test]
+        async fn accepts_small_bodies() {
+            let mut app = test_app(&settings());
+            let echo_req = Request::builder()
+                .method(Method::POST)
+                .uri("/echo")
+                .body(Body::from("Small"))
+                .unwrap();
+            let echo_resp = app.call(echo_req).await.unwrap();
+            assert_eq!(StatusCode::OK, echo_resp.status());
+        }
+
+        #[tokio::test]
+        async fn rejects_declared_large_bodies() {
+            let mut app = test_app(&settings());
+            let echo_req = Request::builder()
+                .method(Method::POST)
+                .uri("/echo")
+                .header(header::CONTENT_LENGTH, 17)
+                .body(Body::from("Way too large body"))
+                .unwrap();
+            let echo_resp = app.call(echo_req).await.unwrap();
+            assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, echo_resp.status());
+
+            let body: dto::BasicError = deserialize_body(echo_resp.into_body()).await;
+            assert_eq!("request_too_large", body.error_code);
+        }
+
+        #[tokio::test]
+        async fn rejects_streamed_large_bodies() {
+            let mut app = test_app(&settings());
+            for uri in ["/echo", "/json"] {
+                let chunks = stream::iter(["[\"Way too \",", " \"large body\"]"])
+                    .map(|chunk| Ok::<_, std::io::Error>(chunk.to_owned()));
+                let streamed_req = Request::builder()
+                    .method(Method::POST)
+                    .uri(uri)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from_stream(chunks))
+                    .unwrap();
+                let streamed_resp = app.call(streamed_req).await.unwrap();
+                assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, streamed_resp.status());
+
+                let body: dto::BasicError = deserialize_body(streamed_resp.into_body()).await;
+                assert_eq!("request_too_large", body.error_code);
+            }
+        }
+
+        #[tokio::test]
+        async fn lets_multipart_uploads_through_to_streaming_routes() {
+            let mut app = test_app(&settings());
+            let upload_body = "Way too large body for the limit";
+            let multipart_request = |uri: &str| {
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(uri)
+                    .header(header::CONTENT_TYPE, "multipart/form-data; boundary=xyz")
+                    .header(header::CONTENT_LENGTH, upload_body.len())
+                    .body(Body::from(upload_body))
+                    .unwrap()
+            };
+
+            let upload_resp = app.call(multipart_request("/upload")).await.unwrap();
+            assert_eq!(StatusCode::OK, upload_resp.status());
+            let uploaded = body::to_bytes(upload_resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert_eq!(upload_body.len().to_string().as_bytes(), uploaded.as_ref());
+
+            // Routes which read the whole body are still held to the limit
+            let echo_resp = app.call(multipart_request("/echo")).await.unwrap();
+            assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, echo_resp.status());
+        }
+    }
+
+    mod request_timeout {
+        use super::*;
+
+        #[tokio::test]
+        async fn abandons_slow_requests() {
+            let mut app = test_app(&settings());
+            let slow_req = Request::builder().uri("/slow").body(Body::empty()).unwrap();
+            let slow_resp = app.call(slow_req).await.unwrap();
+            assert_eq!(StatusCode::SERVICE_UNAVAILABLE, slow_resp.status());
+
+            let body: dto::BasicError = deserialize_body(slow_resp.into_body()).await;
+            assert_eq!("request_timeout", body.error_code);
+        }
+
+        #[tokio::test]
+        async fn does_not_cut_off_streamed_bodies() {
+            let mut app = test_app(&settings());
+            let stream_req = Request::builder()
+                .uri("/stream")
+                .body(Body::empty())
+                .unwrap();
+            let stream_resp = app.call(stream_req).await.unwrap();
+            assert_eq!(StatusCode::OK, stream_resp.status());
+
+            let stream_bytes = body::to_bytes(stream_resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert_eq!(b"chunk0 chunk1 ", stream_bytes.as_ref());
+        }
+
+        #[tokio::test]
+        async fn can_be_turned_off() {
+            let mut app = test_app(&HttpSettings {
+                request_timeout: None,
+                ..settings()
+            });
+            let stream_req = Request::builder()
+                .uri("/stream")
+                .body(Body::empty())
+                .unwrap();
+            let stream_resp = app.call(stream_req).await.unwrap();
+            assert_eq!(StatusCode::OK, stream_resp.status());
+        }
+    }
+
+    mod compression {
+        use super::*;
+
+        async fn content_encoding(
+            settings: &HttpSettings,
+            accept_encoding: &str,
+        ) -> Option<String> {
+            let long_req = Request::builder()
+                .uri("/long")
+                .header(header::ACCEPT_ENCODING, accept_encoding)
+                .body(Body::empty())
+                .unwrap();
+            let long_resp = test_app(settings).call(long_req).await.unwrap();
+            assert_eq!(StatusCode::OK, long_resp.status());
+
+            long_resp
+                .headers()
+                .get(header::CONTENT_ENCODING)
+                .and_then(|encoding| encoding.to_str().ok())
+                .map(str::to_owned)
+        }
+
+        #[tokio::test]
+        async fn uses_encoding_client_accepts() {
+            for encoding in ["gzip", "br", "zstd"] {
+                assert_eq!(
+                    Some(encoding.to_owned()),
+                    content_encoding(&settings(), encoding).await
+                );
+            }
+        }
+
+        #[tokio::test]
+        async fn can_be_turned_off() {
+            let settings = HttpSettings {
+                compress_responses: false,
+                ..settings()
+            };
+            assert_eq!(None, content_encoding(&settings, "gzip").await);
+        }
+    }
+
+    mod cors {
+        use super::*;
+
+        async fn preflight(app: &mut Router, origin: &str) -> Response {
+            let preflight_req = Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/json")
+                .header(header::ORIGIN, origin)
+                .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                .header(
+                    header::ACCESS_CONTROL_REQUEST_HEADERS,
+                    "content-type,x-user-id",
+                )
+                .body(Body::empty())
+                .unwrap();
+            app.call(preflight_req).await.unwrap()
+        }
+
+        #[tokio::test]
+        async fn allows_listed_origins() {
+            let mut app = test_app(&settings());
+            let preflight_resp = preflight(&mut app, "https://example.com").await;
+            let headers = preflight_resp.headers();
+            assert_eq!(
+                Some(&HeaderValue::from_static("https://example.com")),
+                headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            );
+            assert!(headers
+                .get(header::ACCESS_CONTROL_ALLOW_HEADERS)
+                .and_then(|allowed| allowed.to_str().ok())
+                .is_some_and(|allowed| allowed.contains("x-user-id")));
+        }
+
+        #[tokio::test]
+        async fn ignores_other_origins() {
+            let mut app = test_app(&settings());
+            let preflight_resp = preflight(&mut app, "https://elsewhere.com").await;
+            assert!(!preflight_resp
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+        }
+
+        #[tokio::test]
+        async fn can_be_turned_off() {
+            let mut app = test_app(&HttpSettings {
+                cors_origins: CorsOrigins::Disabled,
+                ..settings()
+            });
+            let preflight_resp = preflight(&mut app, "https://example.com").await;
+            assert!(!preflight_resp
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+        }
+    }
+}
@@ -0,0 +1,569 @@
+use crate::api::access::RequestCaller;
+use crate::domain::access::Caller;
+use crate::domain::idempotency::driving_ports::{
+    IdempotencyError, IdempotencyOutcome, IdempotencyPort,
+};
+use crate::domain::idempotency::{IdempotentRequest, StoredResponse};
+use crate::external_connections::ExternalConnectivity;
+use crate::routing_utils::{GenericErrorResponse, Json};
+use crate::{domain, dto, persistence, AppState};
+use axum::body::{self, Body, HttpBody};
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use log::{error, info};
+use sha2::{Digest, Sha256};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// Header clients send to make a POST request safe to retry
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Header added to responses which were replayed from an earlier request with the same idempotency key
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+/// The longest idempotency key clients may send
+const MAX_KEY_LENGTH: usize = 255;
+/// The largest request or response body which will be buffered for an idempotent request
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Middleware which handles the `Idempotency-Key` header on POST requests. The first response for a key is
+/// stored and replayed verbatim when a client retries the request, and reusing a key for a different request
+/// body is rejected. Requests without the header pass through untouched, as do multipart uploads, which are
+/// streamed by the routes which accept them and held to the attachment size limit instead.
+pub async fn idempotency_middleware(
+    State(app_data): AppState,
+    RequestCaller(caller): RequestCaller,
+    request: Request,
+    next: Next,
+) -> Response {
+    let idempotency_service = domain::idempotency::IdempotencyService;
+    let mut ext_cxn = app_data.ext_cxn.clone();
+
+    handle_idempotent_request(
+        request,
+        &caller,
+        app_data.idempotency_key_ttl,
+        &mut ext_cxn,
+        &idempotency_service,
+        async |request| next.run(request).await,
+    )
+    .await
+}
+
+/// Periodically removes idempotency keys which have outlived their TTL so the table doesn't grow forever
+pub async fn purge_expired_keys(mut ext_cxn: impl ExternalConnectivity, purge_interval: Duration) {
+    let idempotency_service = domain::idempotency::IdempotencyService;
+    let store = persistence::db_idempotency_driven_ports::DbIdempotencyStore;
+    let mut interval = tokio::time::interval(purge_interval);
+
+    loop {
+        interval.tick().await;
+        match idempotency_service
+            .purge_expired_keys(&mut ext_cxn, &store)
+            .await
+        {
+            Ok(purged) => info!("Purged {purged} expired idempotency keys"),
+            Err(purge_err) => error!("Failed to purge expired idempotency keys: {purge_err}"),
+        }
+    }
+}
+
+/// Builds a [BasicError] response for a problem with an idempotent request
+fn idempotency_error_response(status: StatusCode, error_code: &str, description: &str) -> Response {
+    (
+        status,
+        Json(dto::BasicError {
+            error_code: error_code.to_owned(),
+            error_description: description.to_owned(),
+            extra_info: None,
+        }),
+    )
+        .into_response()
+}
+
+/// Converts a response recorded for an earlier request back into an HTTP response
+fn replayed_response(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.body).into_response();
+    let headers = response.headers_mut();
+    headers.remove(header::CONTENT_TYPE);
+    if let Some(content_type) = stored
+        .content_type
+        .and_then(|content_type| HeaderValue::from_str(&content_type).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+
+    response
+}
+
+/// Describes who sent a request, so clients can't see each other's responses by reusing the same key.
+/// Only a verified user is trusted to be who they say they are, and other clients are told apart by address.
+fn request_sender(request: &Request, caller: &Caller) -> String {
+    if let Caller::User { user_id, .. } = caller {
+        return format!("user:{user_id}");
+    }
+
+    match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(address)) => format!("ip:{}", address.ip()),
+        None => "unknown".to_owned(),
+    }
+}
+
+async fn handle_idempotent_request(
+    request: Request,
+    caller: &Caller,
+    ttl: Duration,
+    ext_cxn: &mut impl ExternalConnectivity,
+    idempotency_service: &impl IdempotencyPort,
+    run_request: impl AsyncFnOnce(Request) -> Response,
+) -> Response {
+    let is_multipart = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|content_type| content_type.as_bytes().starts_with(b"multipart/form-data"));
+    if request.method() != Method::POST || is_multipart {
+        return run_request(request).await;
+    }
+    let Some(key_header) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return run_request(request).await;
+    };
+    let key = match key_header.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_owned(),
+        _ => return idempotency_error_response(
+            StatusCode::BAD_REQUEST,
+            "invalid_idempotency_key",
+            "The Idempotency-Key header must contain between 1 and 255 visible ASCII characters.",
+        ),
+    };
+
+    let scope = format!(
+        "{} {} {}",
+        request_sender(&request, caller),
+        request.method(),
+        request.uri().path()
+    );
+    let (request_parts, request_body) = request.into_parts();
+    let Ok(request_bytes) = body::to_bytes(request_body, MAX_BODY_BYTES).await else {
+        return idempotency_error_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "payload_too_large",
+            "The request body was too large to process.",
+        );
+    };
+    let idempotent_request = IdempotentRequest {
+        scope,
+        key,
+        fingerprint: format!("{:x}", Sha256::digest(&request_bytes)),
+    };
+    info!(
+        "Handling idempotent request {} with key {}",
+        idempotent_request.scope, idempotent_request.key
+    );
+
+    let store = persistence::db_idempotency_driven_ports::DbIdempotencyStore;
+    let start_result = idempotency_service
+        .start_request(&idempotent_request, ttl, &mut *ext_cxn, &store)
+        .await;
+    match start_result {
+        Ok(IdempotencyOutcome::Proceed) => {}
+        Ok(IdempotencyOutcome::Replay(stored)) => return replayed_response(stored),
+        Err(IdempotencyError::KeyReused) => {
+            return idempotency_error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "idempotency_key_reused",
+                "The idempotency key was already used for a request with a different body.",
+            )
+        }
+        Err(IdempotencyError::RequestInProgress) => {
+            return idempotency_error_response(
+                StatusCode::CONFLICT,
+                "idempotency_key_in_progress",
+                "A request with the same idempotency key is still being processed.",
+            )
+        }
+        Err(IdempotencyError::PortError(err)) => {
+            error!("Failed to start idempotent request: {err}");
+            return GenericErrorResponse(err).into_response();
+        }
+    }
+
+    let response = run_request(Request::from_parts(
+        request_parts,
+        Body::from(request_bytes),
+    ))
+    .await;
+    // Streamed or overly large responses can't be stored for replay, so they're passed along and the key is released
+    let response_size = response.body().size_hint().upper();
+    if response_size.is_none_or(|size| size > MAX_BODY_BYTES as u64) {
+        if let Err(release_err) = idempotency_service
+            .abandon_request(&idempotent_request, &mut *ext_cxn, &store)
+            .await
+        {
+            error!("Failed to release idempotency key: {release_err}");
+        }
+        return response;
+    }
+    let (response_parts, response_body) = response.into_parts();
+    let response_bytes = match body::to_bytes(response_body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(body_err) => {
+            error!("Could not read response body for idempotent request: {body_err}");
+            if let Err(release_err) = idempotency_service
+                .abandon_request(&idempotent_request, &mut *ext_cxn, &store)
+                .await
+            {
+                error!("Failed to release idempotency key: {release_err}");
+            }
+            return GenericErrorResponse(anyhow::Error::new(body_err)).into_response();
+        }
+    };
+
+    // Server errors aren't recorded so the client can retry once the problem is resolved
+    let record_result = if response_parts.status.is_server_error() {
+        idempotency_service
+            .abandon_request(&idempotent_request, &mut *ext_cxn, &store)
+            .await
+    } else {
+        let stored = StoredResponse {
+            status: response_parts.status.as_u16(),
+            content_type: response_parts
+                .headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok())
+                .map(str::to_owned),
+            body: response_bytes.to_vec(),
+        };
+        idempotency_service
+            .finish_request(&idempotent_request, &stored, &mut *ext_cxn, &store)
+            .await
+    };
+    if let Err(record_err) = record_result {
+        error!("Failed to record idempotent response: {record_err}");
+    }
+
+    Response::from_parts(response_parts, Body::from(response_bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain::idempotency::test_util::{stored_response, MockIdempotencyService};
+    use crate::domain::user::Role;
+    use crate::external_connections;
+    use crate::routing_utils::CALLING_USER_HEADER;
+    use anyhow::anyhow;
+    use futures::stream;
+    use std::convert::Infallible;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn post_request(key: Option<&str>) -> Request {
+        let mut builder = Request::builder()
+            .method(Method::POST)
+            .uri("/users")
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(key) = key {
+            builder = builder.header(IDEMPOTENCY_KEY_HEADER, key);
+        }
+
+        builder
+            .body(Body::from(r#"{"first_name":"John","last_name":"Doe"}"#))
+            .unwrap()
+    }
+
+    async fn created_response(_: Request) -> Response {
+        (StatusCode::CREATED, Json(dto::InsertedUser { id: 10 })).into_response()
+    }
+
+    #[tokio::test]
+    async fn passes_through_requests_without_key() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|_| {});
+
+        let response = handle_idempotent_request(
+            post_request(None),
+            &Caller::Trusted,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |req| created_response(req).await,
+        )
+        .await;
+
+        assert_eq!(StatusCode::CREATED, response.status());
+        let locked_service = idempotency_service.lock().unwrap();
+        assert_eq!(0, locked_service.start_request_result.calls().len());
+    }
+
+    #[tokio::test]
+    async fn records_response_for_new_key() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|svc| {
+            svc.start_request_result
+                .set_returned_result(Ok(IdempotencyOutcome::Proceed));
+            svc.finish_request_result.set_returned_anyhow(Ok(()));
+        });
+
+        let response = handle_idempotent_request(
+            post_request(Some("abc")),
+            &Caller::Trusted,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |req| created_response(req).await,
+        )
+        .await;
+
+        assert_eq!(StatusCode::CREATED, response.status());
+        let body: dto::InsertedUser = deserialize_body(response.into_body()).await;
+        assert_eq!(10, body.id);
+
+        let locked_service = idempotency_service.lock().unwrap();
+        let [(request, stored)] = locked_service.finish_request_result.calls() else {
+            panic!("Expected exactly one recorded response");
+        };
+        assert_eq!("unknown POST /users", request.scope);
+        assert_eq!("abc", request.key);
+        assert_eq!(201, stored.status);
+        assert_eq!(br#"{"id":10}"#.to_vec(), stored.body);
+    }
+
+    #[tokio::test]
+    async fn scopes_keys_to_the_verified_user() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|svc| {
+            svc.start_request_result
+                .set_returned_result(Ok(IdempotencyOutcome::Proceed));
+            svc.finish_request_result.set_returned_anyhow(Ok(()));
+        });
+        let caller = Caller::User {
+            user_id: 7,
+            role: Role::User,
+        };
+
+        handle_idempotent_request(
+            post_request(Some("abc")),
+            &caller,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |req| created_response(req).await,
+        )
+        .await;
+
+        let locked_service = idempotency_service.lock().unwrap();
+        let [request] = locked_service.start_request_result.calls() else {
+            panic!("Expected exactly one started request");
+        };
+        assert_eq!("user:7 POST /users", request.scope);
+    }
+
+    #[tokio::test]
+    async fn scopes_keys_from_unverified_callers_to_their_address() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|svc| {
+            svc.start_request_result
+                .set_returned_result(Ok(IdempotencyOutcome::Proceed));
+            svc.finish_request_result.set_returned_anyhow(Ok(()));
+        });
+        let mut request = post_request(Some("abc"));
+        request
+            .headers_mut()
+            .insert(CALLING_USER_HEADER, HeaderValue::from_static("7"));
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+
+        handle_idempotent_request(
+            request,
+            &Caller::Anonymous,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |req| created_response(req).await,
+        )
+        .await;
+
+        let locked_service = idempotency_service.lock().unwrap();
+        let [request] = locked_service.start_request_result.calls() else {
+            panic!("Expected exactly one started request");
+        };
+        assert_eq!("ip:10.0.0.1 POST /users", request.scope);
+    }
+
+    #[tokio::test]
+    async fn replays_stored_response() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|svc| {
+            svc.start_request_result
+                .set_returned_result(Ok(IdempotencyOutcome::Replay(stored_response())));
+        });
+
+        let response = handle_idempotent_request(
+            post_request(Some("abc")),
+            &Caller::Trusted,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |_| panic!("The request should not have been processed again"),
+        )
+        .await;
+
+        assert_eq!(StatusCode::CREATED, response.status());
+        assert_eq!("true", response.headers()[IDEMPOTENT_REPLAYED_HEADER]);
+        let body: dto::InsertedUser = deserialize_body(response.into_body()).await;
+        assert_eq!(1, body.id);
+    }
+
+    #[tokio::test]
+    async fn returns_422_on_reused_key() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|svc| {
+            svc.start_request_result
+                .set_returned_result(Err(IdempotencyError::KeyReused));
+        });
+
+        let response = handle_idempotent_request(
+            post_request(Some("abc")),
+            &Caller::Trusted,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |_| panic!("The request should not have been processed"),
+        )
+        .await;
+
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, response.status());
+        let body: dto::BasicError = deserialize_body(response.into_body()).await;
+        assert_eq!("idempotency_key_reused", body.error_code);
+    }
+
+    #[tokio::test]
+    async fn returns_409_on_request_in_progress() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|svc| {
+            svc.start_request_result
+                .set_returned_result(Err(IdempotencyError::RequestInProgress));
+        });
+
+        let response = handle_idempotent_request(
+            post_request(Some("abc")),
+            &Caller::Trusted,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |_| panic!("The request should not have been processed"),
+        )
+        .await;
+
+        assert_eq!(StatusCode::CONFLICT, response.status());
+        let body: dto::BasicError = deserialize_body(response.into_body()).await;
+        assert_eq!("idempotency_key_in_progress", body.error_code);
+    }
+
+    #[tokio::test]
+    async fn releases_key_on_server_error() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|svc| {
+            svc.start_request_result
+                .set_returned_result(Ok(IdempotencyOutcome::Proceed));
+            svc.abandon_request_result.set_returned_anyhow(Ok(()));
+        });
+
+        let response = handle_idempotent_request(
+            post_request(Some("abc")),
+            &Caller::Trusted,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |_| GenericErrorResponse(anyhow!("Whoopsie daisy")).into_response(),
+        )
+        .await;
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        let locked_service = idempotency_service.lock().unwrap();
+        assert_eq!(1, locked_service.abandon_request_result.calls().len());
+        assert_eq!(0, locked_service.finish_request_result.calls().len());
+    }
+
+    #[tokio::test]
+    async fn passes_through_multipart_uploads() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|_| {});
+        let mut request = post_request(Some("abc"));
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("multipart/form-data; boundary=X-BOUNDARY"),
+        );
+
+        let response = handle_idempotent_request(
+            request,
+            &Caller::Trusted,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |req| created_response(req).await,
+        )
+        .await;
+
+        assert_eq!(StatusCode::CREATED, response.status());
+        let locked_service = idempotency_service.lock().unwrap();
+        assert_eq!(0, locked_service.start_request_result.calls().len());
+    }
+
+    #[tokio::test]
+    async fn releases_key_for_streamed_response() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|svc| {
+            svc.start_request_result
+                .set_returned_result(Ok(IdempotencyOutcome::Proceed));
+            svc.abandon_request_result.set_returned_anyhow(Ok(()));
+        });
+
+        let response = handle_idempotent_request(
+            post_request(Some("abc")),
+            &Caller::Trusted,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |_| {
+                let chunks = stream::iter([Ok::<_, Infallible>("streamed"), Ok(" body")]);
+                Body::from_stream(chunks).into_response()
+            },
+        )
+        .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let response_bytes = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(b"streamed body".as_slice(), &response_bytes[..]);
+        let locked_service = idempotency_service.lock().unwrap();
+        assert_eq!(1, locked_service.abandon_request_result.calls().len());
+        assert_eq!(0, locked_service.finish_request_result.calls().len());
+    }
+
+    #[tokio::test]
+    async fn rejects_overly_long_key() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let idempotency_service = MockIdempotencyService::build_locked(|_| {});
+        let long_key = "a".repeat(MAX_KEY_LENGTH + 1);
+
+        let response = handle_idempotent_request(
+            post_request(Some(&long_key)),
+            &Caller::Trusted,
+            TTL,
+            &mut ext_cxn,
+            &idempotency_service,
+            async |_| panic!("The request should not have been processed"),
+        )
+        .await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let body: dto::BasicError = deserialize_body(response.into_body()).await;
+        assert_eq!("invalid_idempotency_key", body.error_code);
+    }
+}
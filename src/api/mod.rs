@@ -1,6 +1,21 @@
+pub mod access;
+pub mod attachment;
+pub mod audit;
+pub mod calendar_feed;
+pub mod comment;
+pub mod digest;
+pub mod http_stack;
+pub mod idempotency;
+pub mod rate_limit;
+pub mod reminder;
+pub mod share;
 pub mod swagger_main;
+pub mod task_dependency;
+pub mod task_socket;
+pub mod task_transfer;
 pub mod todo;
 pub mod user;
+pub mod webhook;
 
 #[cfg(test)]
 pub mod test_util;
@@ -0,0 +1,364 @@
+use crate::api::access::RequestCaller;
+use crate::domain::access::Caller;
+use crate::domain::rate_limit::driven_ports::BucketStore;
+use crate::domain::rate_limit::driving_ports::RateLimitPort;
+use crate::domain::rate_limit::{Client, RateLimitDecision, RateLimits};
+use crate::external_connections::ExternalConnectivity;
+use crate::routing_utils::Json;
+use crate::{domain, dto, persistence, AppState, SharedData};
+use axum::extract::{ConnectInfo, MatchedPath, Request, State};
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use log::{error, info, warn};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Header telling clients how many requests their limit allows at once
+pub const RATE_LIMIT_LIMIT_HEADER: HeaderName = HeaderName::from_static("ratelimit-limit");
+/// Header telling clients how many more requests they can make right now
+pub const RATE_LIMIT_REMAINING_HEADER: HeaderName = HeaderName::from_static("ratelimit-remaining");
+/// Header telling clients how many seconds until their full allowance is back
+pub const RATE_LIMIT_RESET_HEADER: HeaderName = HeaderName::from_static("ratelimit-reset");
+/// Header describing the limit as the number of requests allowed in a window (`w`) of seconds
+pub const RATE_LIMIT_POLICY_HEADER: HeaderName = HeaderName::from_static("ratelimit-policy");
+
+/// Middleware which limits how often each client can make requests, using a token bucket per client. Callers
+/// verified as a user are limited as that user, and other clients by their address. Requests over the limit
+/// are rejected with a 429, and every limited response says how much of the limit is left.
+pub async fn rate_limit_middleware(
+    State(app_data): AppState,
+    RequestCaller(caller): RequestCaller,
+    request: Request,
+    next: Next,
+) -> Response {
+    let rate_limit_service = domain::rate_limit::RateLimitService;
+    let mut ext_cxn = app_data.ext_cxn.clone();
+
+    if app_data.share_rate_limits {
+        handle_rate_limited_request(
+            request,
+            &caller,
+            &app_data.rate_limits,
+            &mut ext_cxn,
+            &rate_limit_service,
+            &persistence::db_rate_limit_driven_ports::DbBucketStore,
+            async |request| next.run(request).await,
+        )
+        .await
+    } else {
+        handle_rate_limited_request(
+            request,
+            &caller,
+            &app_data.rate_limits,
+            &mut ext_cxn,
+            &rate_limit_service,
+            &app_data.rate_limit_buckets,
+            async |request| next.run(request).await,
+        )
+        .await
+    }
+}
+
+/// Periodically forgets the buckets of clients who haven't made a request for long enough that their
+/// bucket would be full again, so the set of buckets doesn't grow forever
+pub async fn purge_idle_buckets(app_data: Arc<SharedData>, purge_interval: Duration) {
+    let rate_limit_service = domain::rate_limit::RateLimitService;
+    let mut ext_cxn = app_data.ext_cxn.clone();
+    let idle_for = app_data.rate_limits.longest_period();
+    let mut interval = tokio::time::interval(purge_interval);
+
+    loop {
+        interval.tick().await;
+        let purge_result = if app_data.share_rate_limits {
+            rate_limit_service
+                .purge_idle_buckets(
+                    idle_for,
+                    &mut ext_cxn,
+                    &persistence::db_rate_limit_driven_ports::DbBucketStore,
+                )
+                .await
+        } else {
+            rate_limit_service
+                .purge_idle_buckets(idle_for, &mut ext_cxn, &app_data.rate_limit_buckets)
+                .await
+        };
+        match purge_result {
+            Ok(purged) => info!("Purged {purged} idle rate limit buckets"),
+            Err(purge_err) => error!("Failed to purge idle rate limit buckets: {purge_err}"),
+        }
+    }
+}
+
+/// Works out who a request should be counted against. Only a verified user is counted as themselves, so
+/// clients can't dodge their limit or use up someone else's by claiming to be another user.
+fn request_client(request: &Request, caller: &Caller) -> Client {
+    if let Caller::User { user_id, .. } = caller {
+        return Client::User(*user_id);
+    }
+
+    match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(address)) => Client::Address(address.ip()),
+        None => Client::Unknown,
+    }
+}
+
+/// Describes the route a request was sent to, such as `POST /users/:user_id/tasks`
+fn request_route(request: &Request) -> String {
+    let path = match request.extensions().get::<MatchedPath>() {
+        Some(matched_path) => matched_path.as_str(),
+        None => request.uri().path(),
+    };
+
+    format!("{} {path}", request.method())
+}
+
+/// Adds headers describing the client's limit to a response
+fn add_rate_limit_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    headers.insert(RATE_LIMIT_LIMIT_HEADER, decision.limit.capacity.into());
+    headers.insert(RATE_LIMIT_REMAINING_HEADER, decision.remaining.into());
+    headers.insert(
+        RATE_LIMIT_RESET_HEADER,
+        decision.reset_after.as_secs().into(),
+    );
+    if let Ok(policy) = HeaderValue::from_str(&format!(
+        "{};w={}",
+        decision.limit.capacity,
+        decision.limit.period.as_secs()
+    )) {
+        headers.insert(RATE_LIMIT_POLICY_HEADER, policy);
+    }
+}
+
+/// Builds the response for a request which went over its limit
+fn rate_limited_response(decision: &RateLimitDecision) -> Response {
+    let retry_after = decision.retry_after.as_secs().max(1);
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(dto::BasicError {
+            error_code: "rate_limited".to_owned(),
+            error_description: format!(
+                "Too many requests were made. Try again in {retry_after} seconds."
+            ),
+            extra_info: None,
+        }),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, retry_after.into());
+
+    response
+}
+
+async fn handle_rate_limited_request(
+    request: Request,
+    caller: &Caller,
+    limits: &RateLimits,
+    ext_cxn: &mut impl ExternalConnectivity,
+    rate_limit_service: &impl RateLimitPort,
+    store: &impl BucketStore,
+    run_request: impl AsyncFnOnce(Request) -> Response,
+) -> Response {
+    let client = request_client(&request, caller);
+    let route = request_route(&request);
+
+    let check_result = rate_limit_service
+        .check_request(&client, &route, limits, &mut *ext_cxn, store)
+        .await;
+    let decision = match check_result {
+        Ok(Some(decision)) => decision,
+        Ok(None) => return run_request(request).await,
+        Err(check_err) => {
+            // An outage of the bucket store shouldn't take the whole API down with it
+            error!("Could not check the rate limit for {client}, so the request was let through: {check_err}");
+            return run_request(request).await;
+        }
+    };
+
+    let mut response = if decision.allowed {
+        run_request(request).await
+    } else {
+        warn!("Rate limited {client} on {route}");
+        rate_limited_response(&decision)
+    };
+    add_rate_limit_headers(response.headers_mut(), &decision);
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain::rate_limit::test_util::{InMemoryBucketStore, MockRateLimitService};
+    use crate::domain::rate_limit::RateLimit;
+    use crate::domain::user::Role;
+    use crate::external_connections;
+    use crate::routing_utils::CALLING_USER_HEADER;
+    use anyhow::anyhow;
+    use axum::body::Body;
+    use axum::http::Method;
+
+    fn decision(allowed: bool) -> RateLimitDecision {
+        RateLimitDecision {
+            allowed,
+            limit: RateLimit {
+                capacity: 10,
+                period: Duration::from_secs(60),
+            },
+            remaining: 0,
+            reset_after: Duration::from_secs(60),
+            retry_after: if allowed {
+                Duration::ZERO
+            } else {
+                Duration::from_secs(6)
+            },
+        }
+    }
+
+    fn post_request(calling_user: Option<&str>) -> Request {
+        let mut builder = Request::builder()
+            .method(Method::POST)
+            .uri("/users/3/tasks");
+        if let Some(calling_user) = calling_user {
+            builder = builder.header(CALLING_USER_HEADER, calling_user);
+        }
+
+        builder.body(Body::empty()).unwrap()
+    }
+
+    async fn created_response(_: Request) -> Response {
+        StatusCode::CREATED.into_response()
+    }
+
+    fn header<'resp>(response: &'resp Response, name: &HeaderName) -> Option<&'resp str> {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    }
+
+    #[tokio::test]
+    async fn lets_allowed_requests_through_with_headers() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let rate_limit_service = MockRateLimitService::build_locked(|svc| {
+            svc.check_request_result
+                .set_returned_anyhow(Ok(Some(decision(true))));
+        });
+
+        let response = handle_rate_limited_request(
+            post_request(Some("7")),
+            &Caller::User {
+                user_id: 7,
+                role: Role::User,
+            },
+            &RateLimits::default(),
+            &mut ext_cxn,
+            &rate_limit_service,
+            &InMemoryBucketStore::new_locked(),
+            async |req| created_response(req).await,
+        )
+        .await;
+        assert_eq!(StatusCode::CREATED, response.status());
+        assert_eq!(Some("10"), header(&response, &RATE_LIMIT_LIMIT_HEADER));
+        assert_eq!(Some("0"), header(&response, &RATE_LIMIT_REMAINING_HEADER));
+        assert_eq!(Some("60"), header(&response, &RATE_LIMIT_RESET_HEADER));
+        assert_eq!(
+            Some("10;w=60"),
+            header(&response, &RATE_LIMIT_POLICY_HEADER)
+        );
+        assert_eq!(None, header(&response, &header::RETRY_AFTER));
+
+        let locked_service = rate_limit_service.lock().unwrap();
+        assert!(matches!(
+            locked_service.check_request_result.calls(),
+            [(Client::User(7), route)] if route == "POST /users/3/tasks"
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_over_the_limit() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let rate_limit_service = MockRateLimitService::build_locked(|svc| {
+            svc.check_request_result
+                .set_returned_anyhow(Ok(Some(decision(false))));
+        });
+
+        let response = handle_rate_limited_request(
+            post_request(None),
+            &Caller::Anonymous,
+            &RateLimits::default(),
+            &mut ext_cxn,
+            &rate_limit_service,
+            &InMemoryBucketStore::new_locked(),
+            async |_| panic!("A rate limited request was let through"),
+        )
+        .await;
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+        assert_eq!(Some("6"), header(&response, &header::RETRY_AFTER));
+        assert_eq!(Some("0"), header(&response, &RATE_LIMIT_REMAINING_HEADER));
+
+        let body: dto::BasicError = deserialize_body(response.into_body()).await;
+        assert_eq!("rate_limited", body.error_code);
+
+        let locked_service = rate_limit_service.lock().unwrap();
+        assert!(matches!(
+            locked_service.check_request_result.calls(),
+            [(Client::Unknown, _)]
+        ));
+    }
+
+    #[tokio::test]
+    async fn counts_unverified_calling_user_by_address() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let rate_limit_service = MockRateLimitService::build_locked(|svc| {
+            svc.check_request_result.set_returned_anyhow(Ok(None));
+        });
+        let mut request = post_request(Some("7"));
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+
+        let response = handle_rate_limited_request(
+            request,
+            &Caller::Anonymous,
+            &RateLimits::default(),
+            &mut ext_cxn,
+            &rate_limit_service,
+            &InMemoryBucketStore::new_locked(),
+            async |req| created_response(req).await,
+        )
+        .await;
+        assert_eq!(StatusCode::CREATED, response.status());
+        assert_eq!(None, header(&response, &RATE_LIMIT_LIMIT_HEADER));
+
+        let locked_service = rate_limit_service.lock().unwrap();
+        assert!(matches!(
+            locked_service.check_request_result.calls(),
+            [(Client::Address(address), _)] if address.to_string() == "10.0.0.1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn lets_requests_through_when_store_is_down() {
+        let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let rate_limit_service = MockRateLimitService::build_locked(|svc| {
+            svc.check_request_result
+                .set_returned_anyhow(Err(anyhow!("Whoopsy daisy")));
+        });
+
+        let response = handle_rate_limited_request(
+            post_request(None),
+            &Caller::Anonymous,
+            &RateLimits::default(),
+            &mut ext_cxn,
+            &rate_limit_service,
+            &InMemoryBucketStore::new_locked(),
+            async |req| created_response(req).await,
+        )
+        .await;
+        assert_eq!(StatusCode::CREATED, response.status());
+    }
+}
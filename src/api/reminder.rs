@@ -0,0 +1,435 @@
+use crate::api::access::ForbiddenResponse;
+use crate::domain::access::Caller;
+use crate::domain::reminder::driven_ports::Notifier;
+use crate::domain::reminder::driving_ports::{ReminderError, ReminderPort};
+use crate::domain::reminder::DispatchSummary;
+use crate::external_connections::{with_transaction, ExternalConnectivity, Transactable};
+use crate::routing_utils::{GenericErrorResponse, Json};
+use crate::{domain, dto, persistence};
+use axum::http::StatusCode;
+use axum::response::ErrorResponse;
+use log::{error, info};
+use std::time::Duration;
+
+/// Converts a [ReminderError] into the response describing it
+fn handle_reminder_err(err: ReminderError) -> ErrorResponse {
+    let (status, error_code, error_description) = match err {
+        ReminderError::UserDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_user",
+            "Could not find a user matching the given information.",
+        ),
+        ReminderError::TaskDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_task",
+            "The specified task does not exist.",
+        ),
+        ReminderError::ReminderDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_reminder",
+            "The specified reminder does not exist on the task.",
+        ),
+        ReminderError::RemindAtInPast => (
+            StatusCode::BAD_REQUEST,
+            "remind_at_in_past",
+            "Reminders must be set for a time in the future.",
+        ),
+        ReminderError::Forbidden(denied) => return ForbiddenResponse(denied).into(),
+        ReminderError::PortError(err) => {
+            error!("Task reminder failure: {err}");
+            return GenericErrorResponse(err).into();
+        }
+    };
+
+    (
+        status,
+        Json(dto::BasicError {
+            error_code: error_code.to_owned(),
+            error_description: error_description.to_owned(),
+            extra_info: None,
+        }),
+    )
+        .into()
+}
+
+/// Periodically sends reminders which have come due through the notifier. Each dispatch runs in a transaction
+/// which keeps the reminders it claimed locked, so several instances of the app can poll without sending the
+/// same reminder twice.
+pub async fn dispatch_reminders<TxAble>(
+    ext_cxn: TxAble,
+    notifier: impl Notifier,
+    poll_interval: Duration,
+) where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    let reminder_service = domain::reminder::ReminderService;
+    let queue = persistence::db_reminder_driven_ports::DbReminderStore;
+    let mut interval = tokio::time::interval(poll_interval);
+
+    loop {
+        interval.tick().await;
+        let dispatch_result = with_transaction(&ext_cxn, async |tx_cxn| {
+            reminder_service
+                .dispatch_due(&mut *tx_cxn, &queue, &notifier)
+                .await
+        })
+        .await;
+        match dispatch_result {
+            Ok(summary) if summary == DispatchSummary::default() => {}
+            Ok(summary) => info!(
+                "Dispatched reminders: {} sent, {} to be retried, {} failed permanently",
+                summary.sent, summary.retrying, summary.failed
+            ),
+            Err(dispatch_err) => error!("Failed to dispatch reminders: {dispatch_err}"),
+        }
+    }
+}
+
+/// Lists the reminders the user has set on a task, soonest first
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/{task_id}/reminders",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user who set the reminders"),
+        ("task_id" = i32, Path, description = "The task the reminders are for"),
+    ),
+    responses(
+        (status = 200, description = "Reminders successfully retrieved", body = Vec<TaskReminder>),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user does not exist or cannot see the task (error code `no_matching_task`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_task",
+                "error_description": "The specified task does not exist.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn get_reminders(
+    user_id: i32,
+    task_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    reminder_service: &impl ReminderPort,
+) -> Result<Json<Vec<dto::TaskReminder>>, ErrorResponse> {
+    info!("Listing reminders on task {task_id} for user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let reminder_store = persistence::db_reminder_driven_ports::DbReminderStore;
+
+    let reminders = reminder_service
+        .reminders_for_task(
+            caller,
+            user_id,
+            task_id,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &reminder_store,
+        )
+        .await
+        .map_err(handle_reminder_err)?;
+
+    Ok(Json(
+        reminders.into_iter().map(dto::TaskReminder::from).collect(),
+    ))
+}
+
+/// Reminds the user of a task they can see at a time in the future. Reminders aren't sent for tasks which
+/// have been completed or trashed by the time they come due.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/tasks/{task_id}/reminders",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user to remind"),
+        ("task_id" = i32, Path, description = "The task to be reminded of"),
+    ),
+    request_body = NewReminder,
+    responses(
+        (status = 201, description = "Reminder successfully set", body = TaskReminder),
+        (
+            status = 400,
+            description = "The reminder is not in the future (error code `remind_at_in_past`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "remind_at_in_past",
+                "error_description": "Reminders must be set for a time in the future.",
+                "extra_info": null,
+            })
+        ),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user does not exist or cannot see the task (error code `no_matching_task`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_task",
+                "error_description": "The specified task does not exist.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn add_reminder(
+    user_id: i32,
+    task_id: i32,
+    new_reminder: dto::NewReminder,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    reminder_service: &impl ReminderPort,
+) -> Result<(StatusCode, Json<dto::TaskReminder>), ErrorResponse> {
+    info!(
+        "Reminding user {user_id} of task {task_id} at {}",
+        new_reminder.remind_at
+    );
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let reminder_store = persistence::db_reminder_driven_ports::DbReminderStore;
+
+    let reminder = reminder_service
+        .add_reminder(
+            caller,
+            user_id,
+            task_id,
+            new_reminder.remind_at,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &reminder_store,
+        )
+        .await
+        .map_err(handle_reminder_err)?;
+
+    Ok((StatusCode::CREATED, Json(dto::TaskReminder::from(reminder))))
+}
+
+/// Removes one of the user's reminders on a task
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/tasks/{task_id}/reminders/{reminder_id}",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user who set the reminder"),
+        ("task_id" = i32, Path, description = "The task the reminder is for"),
+        ("reminder_id" = i32, Path, description = "The reminder to remove"),
+    ),
+    responses(
+        (status = 204, description = "Reminder successfully removed"),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user does not exist, cannot see the task, or has no such reminder on it (error code `no_matching_reminder`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_reminder",
+                "error_description": "The specified reminder does not exist on the task.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn delete_reminder(
+    user_id: i32,
+    task_id: i32,
+    reminder_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    reminder_service: &impl ReminderPort,
+) -> Result<StatusCode, ErrorResponse> {
+    info!("Removing reminder {reminder_id} on task {task_id} for user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let reminder_store = persistence::db_reminder_driven_ports::DbReminderStore;
+
+    reminder_service
+        .delete_reminder(
+            caller,
+            user_id,
+            task_id,
+            reminder_id,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &reminder_store,
+        )
+        .await
+        .map_err(handle_reminder_err)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain::reminder::test_util::MockReminderService;
+    use crate::external_connections;
+    use anyhow::anyhow;
+    use axum::response::IntoResponse;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn remind_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2030, 5, 1, 9, 0, 0).unwrap()
+    }
+
+    fn domain_reminder() -> domain::reminder::TaskReminder {
+        domain::reminder::TaskReminder {
+            id: 3,
+            task_id: 10,
+            user_id: 1,
+            remind_at: remind_at(),
+            sent_at: None,
+            failed_at: None,
+        }
+    }
+
+    mod get_reminders {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let reminder_service = MockReminderService::build_locked(|svc| {
+                svc.reminders_for_task_result
+                    .set_returned_result(Ok(vec![domain_reminder()]));
+            });
+
+            let Json(reminders) =
+                get_reminders(1, 10, &Caller::Trusted, &mut ext_cxn, &reminder_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+            assert_eq!(vec![dto::TaskReminder::from(domain_reminder())], reminders);
+
+            let locked_service = reminder_service.lock().unwrap();
+            assert_eq!([(1, 10)], locked_service.reminders_for_task_result.calls());
+        }
+
+        #[tokio::test]
+        async fn returns_500_on_port_error() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let reminder_service = MockReminderService::build_locked(|svc| {
+                svc.reminders_for_task_result
+                    .set_returned_result(Err(ReminderError::PortError(anyhow!("Whoops!"))));
+            });
+
+            let response = get_reminders(1, 10, &Caller::Trusted, &mut ext_cxn, &reminder_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        }
+    }
+
+    mod add_reminder {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let reminder_service = MockReminderService::build_locked(|svc| {
+                svc.add_reminder_result
+                    .set_returned_result(Ok(domain_reminder()));
+            });
+
+            let (status, Json(reminder)) = add_reminder(
+                1,
+                10,
+                dto::NewReminder {
+                    remind_at: remind_at(),
+                },
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &reminder_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(StatusCode::CREATED, status);
+            assert_eq!(dto::TaskReminder::from(domain_reminder()), reminder);
+
+            let locked_service = reminder_service.lock().unwrap();
+            assert_eq!(
+                [(1, 10, remind_at())],
+                locked_service.add_reminder_result.calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn returns_400_for_past_time() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let reminder_service = MockReminderService::build_locked(|svc| {
+                svc.add_reminder_result
+                    .set_returned_result(Err(ReminderError::RemindAtInPast));
+            });
+
+            let response = add_reminder(
+                1,
+                10,
+                dto::NewReminder {
+                    remind_at: remind_at(),
+                },
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &reminder_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("remind_at_in_past", body.error_code);
+        }
+    }
+
+    mod delete_reminder {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let reminder_service = MockReminderService::build_locked(|svc| {
+                svc.delete_reminder_result.set_returned_result(Ok(()));
+            });
+
+            let status =
+                delete_reminder(1, 10, 3, &Caller::Trusted, &mut ext_cxn, &reminder_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+            assert_eq!(StatusCode::NO_CONTENT, status);
+
+            let locked_service = reminder_service.lock().unwrap();
+            assert_eq!([(1, 10, 3)], locked_service.delete_reminder_result.calls());
+        }
+
+        #[tokio::test]
+        async fn returns_404_for_missing_reminder() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let reminder_service = MockReminderService::build_locked(|svc| {
+                svc.delete_reminder_result
+                    .set_returned_result(Err(ReminderError::ReminderDoesNotExist));
+            });
+
+            let response =
+                delete_reminder(1, 10, 3, &Caller::Trusted, &mut ext_cxn, &reminder_service)
+                    .await
+                    .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_reminder", body.error_code);
+        }
+    }
+}
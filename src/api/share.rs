@@ -0,0 +1,508 @@
+use crate::api::access::ForbiddenResponse;
+use crate::domain::access::Caller;
+use crate::domain::share::driving_ports::{ShareError, SharePort};
+use crate::external_connections::ExternalConnectivity;
+use crate::routing_utils::{GenericErrorResponse, Json};
+use crate::{domain, dto, persistence};
+use axum::http::StatusCode;
+use axum::response::ErrorResponse;
+use log::{error, info};
+
+/// Converts a [ShareError] into the response describing it
+fn handle_share_err(err: ShareError) -> ErrorResponse {
+    let (status, error_code, error_description) = match err {
+        ShareError::UserDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_user",
+            "Could not find a user matching the given information.",
+        ),
+        ShareError::ShareeDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_sharee",
+            "The user to share with does not exist.",
+        ),
+        ShareError::TaskDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_task",
+            "The specified task does not exist.",
+        ),
+        ShareError::ShareDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            "no_matching_share",
+            "The user has not granted a share with the given ID.",
+        ),
+        ShareError::SharedWithOwner => (
+            StatusCode::BAD_REQUEST,
+            "shared_with_owner",
+            "Tasks can't be shared with the user who owns them.",
+        ),
+        ShareError::Forbidden(denied) => return ForbiddenResponse(denied).into(),
+        ShareError::PortError(err) => {
+            error!("Task sharing failure: {err}");
+            return GenericErrorResponse(err).into();
+        }
+    };
+
+    (
+        status,
+        Json(dto::BasicError {
+            error_code: error_code.to_owned(),
+            error_description: error_description.to_owned(),
+            extra_info: None,
+        }),
+    )
+        .into()
+}
+
+/// Lists the shares a user has granted on their tasks
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/shares",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user who granted the shares"),
+    ),
+    responses(
+        (status = 200, description = "Shares successfully retrieved", body = Vec<TaskShare>),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 404, response = dto::err_resps::BasicError404),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn get_shares(
+    user_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    share_service: &impl SharePort,
+) -> Result<Json<Vec<dto::TaskShare>>, ErrorResponse> {
+    info!("Listing the shares granted by user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let share_store = persistence::db_share_driven_ports::DbShareStore;
+
+    let shares = share_service
+        .shares_granted_by(caller, user_id, &mut *ext_cxn, &user_detect, &share_store)
+        .await
+        .map_err(handle_share_err)?;
+
+    Ok(Json(shares.into_iter().map(dto::TaskShare::from).collect()))
+}
+
+/// Shares a user's tasks with another user, either a single task or every task they have. Sharing the same
+/// thing with the same user again replaces the permission they were granted.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/shares",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose tasks are shared"),
+    ),
+    request_body = NewShare,
+    responses(
+        (status = 201, description = "Share successfully granted", body = InsertedShare),
+        (
+            status = 400,
+            description = "The tasks would be shared with their owner (error code `shared_with_owner`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "shared_with_owner",
+                "error_description": "Tasks can't be shared with the user who owns them.",
+                "extra_info": null,
+            })
+        ),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user, the user to share with or the task does not exist",
+            body = BasicError,
+            examples(
+                ("No user" = (
+                    summary = "User does not exist (error code no_matching_user)",
+                    value = json!({
+                        "error_code": "no_matching_user",
+                        "error_description": "Could not find a user matching the given information.",
+                        "extra_info": null,
+                    })
+                )),
+                ("No sharee" = (
+                    summary = "User to share with does not exist (error code no_matching_sharee)",
+                    value = json!({
+                        "error_code": "no_matching_sharee",
+                        "error_description": "The user to share with does not exist.",
+                        "extra_info": null,
+                    })
+                )),
+                ("No task" = (
+                    summary = "User does not own the task (error code no_matching_task)",
+                    value = json!({
+                        "error_code": "no_matching_task",
+                        "error_description": "The specified task does not exist.",
+                        "extra_info": null,
+                    })
+                ))
+            )
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn grant_share(
+    user_id: i32,
+    new_share: dto::NewShare,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    share_service: &impl SharePort,
+) -> Result<(StatusCode, Json<dto::InsertedShare>), ErrorResponse> {
+    info!(
+        "Sharing tasks of user {user_id} with user {}",
+        new_share.shared_with_user_id
+    );
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let share_store = persistence::db_share_driven_ports::DbShareStore;
+    let new_share = domain::share::NewShare::from(new_share);
+
+    let share_id = share_service
+        .grant_share(
+            caller,
+            user_id,
+            &new_share,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &share_store,
+        )
+        .await
+        .map_err(handle_share_err)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(dto::InsertedShare { id: share_id }),
+    ))
+}
+
+/// Takes back one of the shares a user has granted, after which the other user can no longer reach the tasks
+/// through it
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/shares/{share_id}",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user who granted the share"),
+        ("share_id" = i32, Path, description = "The share to revoke"),
+    ),
+    responses(
+        (status = 200, description = "Share successfully revoked"),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user does not exist or has not granted the share (error code `no_matching_share`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_share",
+                "error_description": "The user has not granted a share with the given ID.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn revoke_share(
+    user_id: i32,
+    share_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    share_service: &impl SharePort,
+) -> Result<StatusCode, ErrorResponse> {
+    info!("Revoking share {share_id} granted by user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let share_store = persistence::db_share_driven_ports::DbShareStore;
+
+    share_service
+        .revoke_share(
+            caller,
+            user_id,
+            share_id,
+            &mut *ext_cxn,
+            &user_detect,
+            &share_store,
+        )
+        .await
+        .map_err(handle_share_err)?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Lists the tasks other users have shared with a user, along with what the user may do with each one.
+/// Shared tasks can also be read through the user's own task endpoints, and changed if they may edit them.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/shared-tasks",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user the tasks were shared with"),
+    ),
+    responses(
+        (status = 200, description = "Shared tasks successfully retrieved", body = Vec<SharedTask>),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 404, response = dto::err_resps::BasicError404),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn get_shared_tasks(
+    user_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    share_service: &impl SharePort,
+) -> Result<Json<Vec<dto::SharedTask>>, ErrorResponse> {
+    info!("Listing the tasks shared with user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let share_store = persistence::db_share_driven_ports::DbShareStore;
+
+    let shared_tasks = share_service
+        .tasks_shared_with(caller, user_id, &mut *ext_cxn, &user_detect, &share_store)
+        .await
+        .map_err(handle_share_err)?;
+
+    Ok(Json(
+        shared_tasks
+            .into_iter()
+            .map(dto::SharedTask::from)
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain::access::Action;
+    use crate::domain::share::test_util::MockShareService;
+    use crate::external_connections;
+    use anyhow::anyhow;
+    use axum::response::IntoResponse;
+
+    mod get_shares {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let share_service = MockShareService::build_locked(|svc| {
+                svc.shares_granted_by_result.set_returned_result(Ok(vec![
+                    domain::share::TaskShare {
+                        id: 3,
+                        owner_user_id: 1,
+                        shared_with_user_id: 2,
+                        task_id: None,
+                        permission: domain::share::SharePermission::Edit,
+                    },
+                ]));
+            });
+
+            let Json(shares) = get_shares(1, &Caller::Trusted, &mut ext_cxn, &share_service)
+                .await
+                .unwrap_or_else(|err| {
+                    panic!("Didn't get the expected response! Error: {:#?}", err);
+                });
+            assert!(matches!(
+                shares.as_slice(),
+                [dto::TaskShare {
+                    id: 3,
+                    shared_with_user_id: 2,
+                    task_id: None,
+                    permission: dto::SharePermission::Edit,
+                }]
+            ));
+        }
+
+        #[tokio::test]
+        async fn returns_500_when_service_blows_up() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let share_service = MockShareService::build_locked(|svc| {
+                svc.shares_granted_by_result
+                    .set_returned_result(Err(ShareError::PortError(anyhow!("Whoopsy daisy"))));
+            });
+
+            let response = get_shares(1, &Caller::Trusted, &mut ext_cxn, &share_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("internal_error", body.error_code);
+        }
+    }
+
+    mod grant_share {
+        use super::*;
+
+        fn new_share() -> dto::NewShare {
+            dto::NewShare {
+                shared_with_user_id: 2,
+                task_id: Some(10),
+                permission: dto::SharePermission::Read,
+            }
+        }
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let share_service = MockShareService::build_locked(|svc| {
+                svc.grant_share_result.set_returned_result(Ok(3));
+            });
+
+            let (status, Json(inserted)) = grant_share(
+                1,
+                new_share(),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &share_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(StatusCode::CREATED, status);
+            assert_eq!(3, inserted.id);
+
+            let locked_service = share_service.lock().unwrap();
+            assert_eq!(
+                [(
+                    1,
+                    domain::share::NewShare {
+                        shared_with_user_id: 2,
+                        task_id: Some(10),
+                        permission: domain::share::SharePermission::Read,
+                    }
+                )],
+                locked_service.grant_share_result.calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn maps_errors_to_responses() {
+            let cases = [
+                (
+                    ShareError::SharedWithOwner,
+                    StatusCode::BAD_REQUEST,
+                    "shared_with_owner",
+                ),
+                (
+                    ShareError::ShareeDoesNotExist,
+                    StatusCode::NOT_FOUND,
+                    "no_matching_sharee",
+                ),
+                (
+                    ShareError::TaskDoesNotExist,
+                    StatusCode::NOT_FOUND,
+                    "no_matching_task",
+                ),
+                (
+                    ShareError::Forbidden(domain::access::AccessDenied {
+                        action: Action::ManageTasks { owner_user_id: 1 },
+                    }),
+                    StatusCode::FORBIDDEN,
+                    "forbidden",
+                ),
+            ];
+
+            for (share_err, expected_status, expected_code) in cases {
+                let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let share_service = MockShareService::build_locked(|svc| {
+                    svc.grant_share_result.set_returned_result(Err(share_err));
+                });
+
+                let response = grant_share(
+                    1,
+                    new_share(),
+                    &Caller::Trusted,
+                    &mut ext_cxn,
+                    &share_service,
+                )
+                .await
+                .into_response();
+                assert_eq!(expected_status, response.status());
+
+                let body: dto::BasicError = deserialize_body(response.into_body()).await;
+                assert_eq!(expected_code, body.error_code);
+            }
+        }
+    }
+
+    mod revoke_share {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let share_service = MockShareService::build_locked(|svc| {
+                svc.revoke_share_result.set_returned_result(Ok(()));
+            });
+
+            let revoke_result =
+                revoke_share(1, 3, &Caller::Trusted, &mut ext_cxn, &share_service).await;
+            assert!(matches!(revoke_result, Ok(StatusCode::OK)));
+
+            let locked_service = share_service.lock().unwrap();
+            assert_eq!([(1, 3)], locked_service.revoke_share_result.calls());
+        }
+
+        #[tokio::test]
+        async fn returns_404_when_share_missing() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let share_service = MockShareService::build_locked(|svc| {
+                svc.revoke_share_result
+                    .set_returned_result(Err(ShareError::ShareDoesNotExist));
+            });
+
+            let response = revoke_share(1, 3, &Caller::Trusted, &mut ext_cxn, &share_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_share", body.error_code);
+        }
+    }
+
+    mod get_shared_tasks {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let share_service = MockShareService::build_locked(|svc| {
+                svc.tasks_shared_with_result.set_returned_result(Ok(vec![
+                    domain::share::SharedTask {
+                        task: domain::todo::TodoTask {
+                            id: 10,
+                            owner_user_id: 1,
+                            item_desc: "Plan the offsite".to_owned(),
+                            completed: false,
+                            due_date: None,
+                            recurrence: None,
+                            assignee_user_id: None,
+                            comment_count: 0,
+                            blocked: false,
+                        },
+                        permission: domain::share::SharePermission::Read,
+                    },
+                ]));
+            });
+
+            let Json(shared_tasks) =
+                get_shared_tasks(2, &Caller::Trusted, &mut ext_cxn, &share_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+            assert!(matches!(
+                shared_tasks.as_slice(),
+                [dto::SharedTask {
+                    task: dto::TodoTask { id: 10, description, .. },
+                    owner_user_id: 1,
+                    permission: dto::SharePermission::Read,
+                }] if description == "Plan the offsite"
+            ));
+        }
+    }
+}
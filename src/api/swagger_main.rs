@@ -17,6 +17,8 @@ pub fn build_documentation() -> SwaggerUi {
     api_docs.merge(dto::OpenApiSchemas::openapi());
     api_docs.merge(super::user::UsersApi::openapi());
     api_docs.merge(super::todo::TaskApi::openapi());
+    api_docs.merge(super::webhook::WebhookApi::openapi());
+    api_docs.merge(super::audit::AuditApi::openapi());
 
     SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", api_docs)
 }
@@ -0,0 +1,378 @@
+use crate::domain::access::Caller;
+use crate::domain::todo::dependency::TaskDependency;
+use crate::domain::todo::driving_ports::TaskPort;
+use crate::external_connections::{
+    with_transaction, with_transaction_options, ExternalConnectivity, IsolationLevel, Transactable,
+    TransactionOptions, TxOrSourceError,
+};
+use crate::routing_utils::{GenericErrorResponse, Json};
+use crate::{dto, persistence};
+use anyhow::anyhow;
+use axum::http::StatusCode;
+use axum::response::ErrorResponse;
+use log::{error, info};
+
+/// Says that a task is blocked by another of the same user's tasks, so it can't be completed until the other
+/// task is. A task can't be blocked by a task which is already waiting on it, directly or through other tasks.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/tasks/{task_id}/dependencies",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user changing the task"),
+        ("task_id" = i32, Path, description = "The task which should be blocked"),
+    ),
+    request_body = NewTaskDependency,
+    responses(
+        (status = 204, description = "Dependency successfully added"),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user does not exist or either task is not one of the user's tasks (error code `no_matching_task`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_task",
+                "error_description": "The specified task does not exist.",
+                "extra_info": null,
+            })
+        ),
+        (
+            status = 409,
+            description = "The blocking task is already waiting on the task (error code `dependency_cycle`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "dependency_cycle",
+                "error_description": "The task would end up blocking itself.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn add_task_dependency<TxAble>(
+    user_id: i32,
+    task_id: i32,
+    new_dependency: dto::NewTaskDependency,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl TaskPort,
+) -> Result<StatusCode, ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    info!(
+        "Blocking task {task_id} on task {} for user {user_id}",
+        new_dependency.blocked_by_task_id
+    );
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let dependency_store = persistence::db_todo_driven_ports::DbDependencyStore;
+    let dependency = TaskDependency {
+        task_id,
+        blocked_by_task_id: new_dependency.blocked_by_task_id,
+    };
+
+    // The domain locks the user's dependencies before checking for cycles, which holds until this transaction ends
+    let add_result = with_transaction(ext_cxn, async |tx_cxn| {
+        task_service
+            .add_dependency(
+                caller,
+                user_id,
+                &dependency,
+                &mut *tx_cxn,
+                &user_detect,
+                &task_read,
+                &dependency_store,
+            )
+            .await
+    })
+    .await;
+    match add_result {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(TxOrSourceError::Source(task_err)) => Err(super::user::handle_todo_task_err(task_err)),
+        Err(tx_err) => {
+            error!("Failed to add a task dependency: {tx_err}");
+            Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into())
+        }
+    }
+}
+
+/// Stops a task from being blocked by another task
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/tasks/{task_id}/dependencies/{blocked_by_task_id}",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user changing the task"),
+        ("task_id" = i32, Path, description = "The blocked task"),
+        ("blocked_by_task_id" = i32, Path, description = "The task which should no longer block it"),
+    ),
+    responses(
+        (status = 204, description = "Dependency successfully removed"),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The user or task does not exist, or the task is not blocked by the other task",
+            body = BasicError,
+            examples(
+                ("No task" = (
+                    value = json!({
+                        "error_code": "no_matching_task",
+                        "error_description": "The specified task does not exist.",
+                        "extra_info": null,
+                    })
+                )),
+                ("No dependency" = (
+                    value = json!({
+                        "error_code": "no_matching_dependency",
+                        "error_description": "The task is not blocked by the specified task.",
+                        "extra_info": null,
+                    })
+                )),
+            ),
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn remove_task_dependency(
+    user_id: i32,
+    task_id: i32,
+    blocked_by_task_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_service: &impl TaskPort,
+) -> Result<StatusCode, ErrorResponse> {
+    info!("Unblocking task {task_id} from task {blocked_by_task_id} for user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let dependency_store = persistence::db_todo_driven_ports::DbDependencyStore;
+    let dependency = TaskDependency {
+        task_id,
+        blocked_by_task_id,
+    };
+
+    task_service
+        .remove_dependency(
+            caller,
+            user_id,
+            &dependency,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+            &dependency_store,
+        )
+        .await
+        .map_err(super::user::handle_todo_task_err)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Retrieves a user's tasks in an order they can be done in, where each task comes after every task
+/// blocking it. Tasks which can be done in either order are ordered by ID.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/ordered",
+    tag = super::user::USER_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose tasks should be ordered"),
+    ),
+    responses(
+        (status = 200, description = "Tasks successfully ordered", body = Vec<TodoTask>),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 404, response = dto::err_resps::BasicError404),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn get_tasks_in_dependency_order<TxAble>(
+    user_id: i32,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl TaskPort,
+) -> Result<Json<Vec<dto::TodoTask>>, ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    info!("Ordering tasks by their dependencies for user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let dependency_store = persistence::db_todo_driven_ports::DbDependencyStore;
+
+    // Tasks and their dependencies are read separately, so they're read from one snapshot to agree with each other
+    let snapshot = TransactionOptions {
+        isolation_level: IsolationLevel::RepeatableRead,
+        read_only: true,
+    };
+    let order_result = with_transaction_options(ext_cxn, snapshot, async |tx_cxn| {
+        task_service
+            .tasks_in_dependency_order(
+                caller,
+                user_id,
+                &mut *tx_cxn,
+                &user_detect,
+                &task_read,
+                &dependency_store,
+            )
+            .await
+    })
+    .await;
+    match order_result {
+        Ok(tasks) => Ok(Json(tasks.into_iter().map(dto::TodoTask::from).collect())),
+        Err(TxOrSourceError::Source(task_err)) => Err(super::user::handle_todo_task_err(task_err)),
+        Err(tx_err) => {
+            error!("Failed to order tasks by their dependencies: {tx_err}");
+            Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain;
+    use crate::domain::todo::driving_ports::TaskError;
+    use crate::domain::todo::test_util::MockTaskService;
+    use crate::external_connections;
+    use axum::response::IntoResponse;
+
+    mod add_task_dependency {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.add_dependency_result.set_returned_result(Ok(()));
+            });
+
+            let response = add_task_dependency(
+                1,
+                5,
+                dto::NewTaskDependency {
+                    blocked_by_task_id: 4,
+                },
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(StatusCode::NO_CONTENT, response);
+
+            let locked_service = task_service.lock().unwrap();
+            assert_eq!(
+                [(
+                    1,
+                    TaskDependency {
+                        task_id: 5,
+                        blocked_by_task_id: 4,
+                    }
+                )],
+                locked_service.add_dependency_result.calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn returns_409_on_cycle() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.add_dependency_result
+                    .set_returned_result(Err(TaskError::DependencyCycle));
+            });
+
+            let response = add_task_dependency(
+                1,
+                5,
+                dto::NewTaskDependency {
+                    blocked_by_task_id: 4,
+                },
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::CONFLICT, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("dependency_cycle", body.error_code);
+        }
+    }
+
+    mod remove_task_dependency {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_404_when_not_blocked() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.remove_dependency_result
+                    .set_returned_result(Err(TaskError::DependencyDoesNotExist));
+            });
+
+            let response =
+                remove_task_dependency(1, 5, 4, &Caller::Trusted, &mut ext_cxn, &task_service)
+                    .await
+                    .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_dependency", body.error_code);
+        }
+    }
+
+    mod get_tasks_in_dependency_order {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.tasks_in_dependency_order_result
+                    .set_returned_result(Ok(vec![domain::todo::TodoTask {
+                        id: 4,
+                        owner_user_id: 1,
+                        item_desc: "Buy paint".to_owned(),
+                        completed: false,
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                        comment_count: 0,
+                        blocked: false,
+                    }]));
+            });
+
+            let Json(tasks) =
+                get_tasks_in_dependency_order(1, &Caller::Trusted, &ext_cxn, &task_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+            assert!(matches!(
+                tasks.as_slice(),
+                [dto::TodoTask {
+                    id: 4,
+                    blocked: false,
+                    ..
+                }]
+            ));
+
+            let locked_service = task_service.lock().unwrap();
+            assert_eq!(
+                &[1],
+                locked_service.tasks_in_dependency_order_result.calls()
+            );
+            assert_eq!(
+                vec![TransactionOptions {
+                    isolation_level: IsolationLevel::RepeatableRead,
+                    read_only: true,
+                }],
+                ext_cxn.started_transactions()
+            );
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use crate::domain::access::{Action, Caller};
+use crate::external_connections::{ExternalConnectivity, Transactable};
+use crate::routing_utils::ValidationErrorResponse;
+use crate::{domain, dto, persistence};
+use axum::extract::ws::Message;
+use axum::response::ErrorResponse;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use log::{info, warn};
+use std::fmt::Display;
+use std::pin::pin;
+
+/// Prepares a client to collaborate on a user's tasks over a WebSocket, returning the ID of the latest change
+/// to the user's tasks. The client receives every change made after it.
+///
+/// Once connected, the client can send commands as JSON text messages. A command contains a `request_id` chosen
+/// by the client along with a task operation in the same format as the batch endpoint, for example
+/// `{"request_id": "1", "op": "update", "task_id": 5, "description": "Something to do"}`. Each command is
+/// answered with a `result` message holding the same `request_id`. Every change to the user's tasks, including
+/// those made by the client itself, is sent as a `change` message.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/socket",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose tasks are being collaborated on"),
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log for every command"),
+    ),
+    responses(
+        (status = 101, description = "Switched to the WebSocket protocol"),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The requested user does not exist in the system (error code `no_matching_user`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_user",
+                "error_description": "No user exists in the system with the given id",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn open_task_socket(
+    user_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_event_service: &impl domain::task_event::driving_ports::TaskEventPort,
+) -> Result<i64, ErrorResponse> {
+    info!("Opening a task socket for user {user_id}");
+    domain::access::authorize(
+        caller,
+        Action::ManageTasks {
+            owner_user_id: user_id,
+        },
+    )
+    .map_err(super::access::ForbiddenResponse)?;
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let change_log = persistence::db_task_event_driven_ports::DbTaskChangeLog;
+
+    task_event_service
+        .start_following(user_id, None, &mut *ext_cxn, &user_detect, &change_log)
+        .await
+        .map_err(super::user::handle_todo_task_err)
+}
+
+/// Runs a task collaboration session, forwarding `changes` to the client and applying the commands
+/// it sends until either the client disconnects or changes stop arriving. Commands are recorded as made by `calling_user`
+/// and are held to what `caller` may do.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_task_socket<TxAble, SendErr>(
+    user_id: i32,
+    calling_user: Option<i32>,
+    caller: Caller,
+    incoming: impl Stream<Item = Result<Message, axum::Error>>,
+    mut outgoing: impl Sink<Message, Error = SendErr> + Unpin,
+    changes: impl Stream<Item = domain::task_event::TaskChange>,
+    ext_cxn: &TxAble,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+    SendErr: Display,
+{
+    let mut incoming = pin!(incoming);
+    let mut changes = pin!(changes);
+
+    loop {
+        let reply = tokio::select! {
+            message = incoming.next() => match message {
+                Some(Ok(Message::Text(command))) => {
+                    apply_command(user_id, &command, calling_user, &caller, ext_cxn, task_service).await
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                // Pings are answered automatically and there's nothing to do for other messages
+                Some(Ok(_)) => continue,
+                Some(Err(receive_err)) => {
+                    warn!("Closing task socket for user {user_id}: {receive_err}");
+                    break;
+                }
+            },
+            change = changes.next() => match change {
+                Some(change) => dto::TaskSocketMessage::from(change),
+                None => break,
+            },
+        };
+
+        let reply =
+            serde_json::to_string(&reply).expect("Socket messages can always be serialized");
+        if let Err(send_err) = outgoing.send(Message::Text(reply)).await {
+            warn!("Closing task socket for user {user_id}: {send_err}");
+            break;
+        }
+    }
+
+    info!("Task socket for user {user_id} closed");
+}
+
+/// Applies a command received from a client, producing the result to send back to it
+async fn apply_command<TxAble>(
+    user_id: i32,
+    command: &str,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> dto::TaskSocketMessage
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    let failure = |request_id: Option<String>, error: dto::BasicError| {
+        dto::TaskSocketMessage::CommandResult {
+            request_id,
+            result: dto::TaskBatchOperationResult::Failed { error },
+        }
+    };
+
+    // Pull out the request ID first so it can be sent back even if the rest of the command is unreadable
+    let command: serde_json::Value = match serde_json::from_str(command) {
+        Ok(command) => command,
+        Err(parse_err) => return failure(None, invalid_command_error(parse_err)),
+    };
+    let request_id = command
+        .get("request_id")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_owned);
+    let dto::TaskSocketCommand {
+        request_id,
+        operation,
+    } = match serde_json::from_value(command) {
+        Ok(command) => command,
+        Err(parse_err) => return failure(request_id, invalid_command_error(parse_err)),
+    };
+    let request_id = Some(request_id);
+
+    if let Err(validation_err) = operation.validate() {
+        return failure(
+            request_id,
+            dto::BasicError::from(ValidationErrorResponse::from(validation_err)),
+        );
+    }
+
+    let operation = domain::todo::BatchOperation::from(operation);
+    let result = super::user::apply_task_operation(
+        user_id,
+        &operation,
+        calling_user,
+        caller,
+        ext_cxn,
+        task_service,
+    )
+    .await;
+
+    dto::TaskSocketMessage::CommandResult { request_id, result }
+}
+
+/// Describes a command which could not be read
+fn invalid_command_error(parse_err: serde_json::Error) -> dto::BasicError {
+    dto::BasicError {
+        error_code: "invalid_json".to_owned(),
+        error_description: "The command was malformed or unreadable JSON.".to_owned(),
+        extra_info: Some(dto::ExtraInfo::Message(parse_err.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::task_event::{TaskChange, TaskChangeKind};
+    use crate::domain::todo::driving_ports::TaskError;
+    use crate::domain::todo::test_util::MockTaskService;
+    use crate::external_connections;
+    use futures::channel::mpsc;
+    use futures::stream;
+
+    /// Runs a session to completion, returning every message sent to the client
+    async fn run_session(
+        incoming: impl Stream<Item = Message>,
+        changes: impl Stream<Item = TaskChange>,
+        task_service: &impl domain::todo::driving_ports::TaskPort,
+    ) -> Vec<dto::TaskSocketMessage> {
+        let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let (outgoing, sent) = mpsc::unbounded();
+
+        run_task_socket(
+            3,
+            Some(3),
+            Caller::Trusted,
+            incoming.map(Ok),
+            outgoing,
+            changes,
+            &ext_cxn,
+            task_service,
+        )
+        .await;
+
+        sent.map(|message| match message {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("Expected a text message, got {:?}", other),
+        })
+        .collect()
+        .await
+    }
+
+    #[tokio::test]
+    async fn applies_commands_and_replies_with_results() {
+        let task_service = MockTaskService::build_locked(|svc| {
+            svc.apply_batch_operation_result.set_returned_result(Ok(8));
+        });
+
+        let sent = run_session(
+            stream::iter([
+                Message::Text(
+                    r#"{"request_id":"a","op":"create","item_desc":"Something to do"}"#.to_owned(),
+                ),
+                Message::Close(None),
+            ]),
+            stream::pending(),
+            &task_service,
+        )
+        .await;
+
+        assert!(matches!(sent.as_slice(), [
+            dto::TaskSocketMessage::CommandResult {
+                request_id: Some(request_id),
+                result: dto::TaskBatchOperationResult::Succeeded { task_id: 8 },
+            }
+        ] if request_id == "a"));
+
+        let locked_service = task_service.lock().unwrap();
+        assert!(
+            matches!(locked_service.apply_batch_operation_result.calls(), [
+            (3, domain::todo::BatchOperation::Create(domain::todo::NewTask { description, .. }))
+        ] if description == "Something to do")
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_failed_commands() {
+        let task_service = MockTaskService::build_locked(|svc| {
+            svc.apply_batch_operation_result
+                .set_returned_result(Err(TaskError::TaskDoesNotExist));
+        });
+
+        let sent = run_session(
+            stream::iter([
+                Message::Text(r#"{"request_id":"a","op":"delete","task_id":2}"#.to_owned()),
+                Message::Text(
+                    r#"{"request_id":"b","op":"update","task_id":2,"description":""}"#.to_owned(),
+                ),
+                Message::Text(r#"{"request_id":"c","op":"explode"}"#.to_owned()),
+                Message::Text("not json".to_owned()),
+            ]),
+            stream::pending(),
+            &task_service,
+        )
+        .await;
+
+        let error_codes: Vec<(Option<&str>, &str)> = sent
+            .iter()
+            .map(|message| match message {
+                dto::TaskSocketMessage::CommandResult {
+                    request_id,
+                    result: dto::TaskBatchOperationResult::Failed { error },
+                } => (request_id.as_deref(), error.error_code.as_str()),
+                other => panic!("Expected a failed command, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (Some("a"), "no_matching_task"),
+                (Some("b"), "invalid_input"),
+                (Some("c"), "invalid_json"),
+                (None, "invalid_json"),
+            ],
+            error_codes
+        );
+
+        // Only the valid command should have reached the service
+        let locked_service = task_service.lock().unwrap();
+        assert_eq!(1, locked_service.apply_batch_operation_result.calls().len());
+    }
+
+    #[tokio::test]
+    async fn forwards_task_changes() {
+        let task_service = MockTaskService::new_locked();
+        let change = TaskChange {
+            id: 12,
+            task_id: 2,
+            kind: TaskChangeKind::Updated,
+            description: Some("Something else to do".to_owned()),
+            completed: Some(false),
+        };
+
+        let sent = run_session(stream::pending(), stream::iter([change]), &task_service).await;
+
+        assert!(matches!(sent.as_slice(), [
+            dto::TaskSocketMessage::Change {
+                id: 12,
+                event,
+                change: dto::TaskChange { task_id: 2, description: Some(description), completed: Some(false) },
+            }
+        ] if event == "task.updated" && description == "Something else to do"));
+    }
+}
@@ -0,0 +1,1083 @@
+use crate::domain::access::Caller;
+use crate::domain::todo::driving_ports::TaskPort;
+use crate::domain::todo::todo_txt::TodoTxtTask;
+use crate::domain::todo::{TodoTask, EXPORT_PAGE_SIZE};
+use crate::external_connections::{
+    with_transaction, ExternalConnectivity, Transactable, TxOrSourceError,
+};
+use crate::routing_utils::{GenericErrorResponse, Json};
+use crate::{domain, dto, persistence};
+use anyhow::{anyhow, Context};
+use axum::body::Bytes;
+use axum::http::StatusCode;
+use axum::response::ErrorResponse;
+use chrono::NaiveDate;
+use futures::{stream, Stream};
+use log::{error, info};
+use serde_json::value::RawValue;
+
+/// The columns of a CSV export, in order
+const CSV_COLUMNS: [&str; 5] = ["id", "description", "completed", "due_date", "recurrence"];
+/// The todo.txt tag holding a task's due date, which most todo.txt apps understand
+const DUE_DATE_TAG: &str = "due";
+/// The todo.txt tag holding a task's recurrence rule
+const RECURRENCE_TAG: &str = "rrule";
+/// The todo.txt tag holding a task's priority where the format has nowhere else for it, such as in a task's
+/// description or on a completed task
+const PRIORITY_TAG: &str = "pri";
+/// The todo.txt tag holding the date a task was created on, since tasks have nowhere else for it
+const CREATION_DATE_TAG: &str = "created";
+/// The todo.txt tag holding the date a task was completed on, since tasks have nowhere else for it
+const COMPLETION_DATE_TAG: &str = "completed";
+
+/// The content type of a file in the given format
+pub fn content_type(format: dto::TaskFileFormat) -> &'static str {
+    match format {
+        dto::TaskFileFormat::Csv => "text/csv; charset=utf-8",
+        dto::TaskFileFormat::Json => "application/json",
+        dto::TaskFileFormat::TodoTxt => "text/plain; charset=utf-8",
+    }
+}
+
+/// The extension of a file in the given format
+pub fn file_extension(format: dto::TaskFileFormat) -> &'static str {
+    match format {
+        dto::TaskFileFormat::Csv => "csv",
+        dto::TaskFileFormat::Json => "json",
+        dto::TaskFileFormat::TodoTxt => "txt",
+    }
+}
+
+/// Downloads all of a user's tasks as a CSV, JSON or todo.txt file. Tasks are streamed in order of ID, so large
+/// exports don't have to be held in memory.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/export",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose tasks should be exported"),
+        dto::TaskFileParams,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The user's tasks. A CSV export has a header row naming its columns. In a todo.txt \
+                export, the due date and recurrence rule of a task are `due:` and `rrule:` tags, a `pri:` tag in \
+                the description of an open task is its priority, and `created:` and `completed:` tags are its creation \
+                and completion dates.",
+            content(
+                ("application/json" = Vec<TodoTask>),
+                ("text/csv" = String, example = json!("id,description,completed,due_date,recurrence\n10,Something to do,false,2024-03-04,FREQ=WEEKLY;BYDAY=MO\n")),
+                ("text/plain" = String, example = json!("(A) Something to do +work due:2024-03-04 rrule:FREQ=WEEKLY;BYDAY=MO\nx Something done @home\n")),
+            )
+        ),
+        (
+            status = 404,
+            description = "The requested user does not exist in the system (error code `no_matching_user`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_user",
+                "error_description": "Could not find a user matching the given information.",
+                "extra_info": null,
+            })
+        ),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn export_tasks(
+    user_id: i32,
+    format: dto::TaskFileFormat,
+    caller: Caller,
+    mut ext_cxn: impl ExternalConnectivity,
+    task_service: impl TaskPort,
+) -> Result<impl Stream<Item = Result<Bytes, anyhow::Error>>, ErrorResponse> {
+    info!("Exporting tasks for user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+
+    // The first page is read up front so a missing user gets an error response instead of a broken export
+    let first_page = task_service
+        .export_page(
+            &caller,
+            user_id,
+            None,
+            &mut ext_cxn,
+            &user_detect,
+            &task_read,
+        )
+        .await
+        .map_err(super::user::handle_todo_task_err)?;
+
+    Ok(export_chunks(
+        user_id,
+        format,
+        caller,
+        first_page,
+        ext_cxn,
+        task_service,
+    ))
+}
+
+/// The next page of an export to write
+enum NextPage {
+    /// The page was already read
+    Ready(Vec<TodoTask>),
+    /// The page starts after the task with this ID and hasn't been read yet
+    After(i32),
+}
+
+/// Where a streamed export is up to
+struct ExportProgress<Cxn, Svc> {
+    next_page: NextPage,
+    /// Whether any tasks have been written yet. Only the last page can be empty, so this is also whether
+    /// anything has been written at all.
+    wrote_tasks: bool,
+    ext_cxn: Cxn,
+    task_service: Svc,
+}
+
+/// Produces the chunks of an export, reading a page of tasks for each chunk. The stream ends with an error
+/// if a page can't be read, which cuts the export short.
+fn export_chunks(
+    user_id: i32,
+    format: dto::TaskFileFormat,
+    caller: Caller,
+    first_page: Vec<TodoTask>,
+    ext_cxn: impl ExternalConnectivity,
+    task_service: impl TaskPort,
+) -> impl Stream<Item = Result<Bytes, anyhow::Error>> {
+    let progress = ExportProgress {
+        next_page: NextPage::Ready(first_page),
+        wrote_tasks: false,
+        ext_cxn,
+        task_service,
+    };
+
+    stream::unfold(Some(progress), move |progress| async move {
+        let mut progress = progress?;
+        let page = match progress.next_page {
+            NextPage::Ready(page) => page,
+            NextPage::After(after_task_id) => {
+                let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+                let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+                let page_result = progress
+                    .task_service
+                    .export_page(
+                        &caller,
+                        user_id,
+                        Some(after_task_id),
+                        &mut progress.ext_cxn,
+                        &user_detect,
+                        &task_read,
+                    )
+                    .await;
+                match page_result {
+                    Ok(page) => page,
+                    Err(err) => {
+                        error!("Stopped exporting tasks for user {user_id}: {err}");
+                        return Some((Err(anyhow!(err.to_string())), None));
+                    }
+                }
+            }
+        };
+
+        let is_last_page = page.len() < EXPORT_PAGE_SIZE as usize;
+        let last_task_id = page.last().map(|task| task.id);
+        let chunk = encode_page(format, page, progress.wrote_tasks, is_last_page);
+        match (is_last_page, last_task_id) {
+            (false, Some(last_task_id)) => {
+                progress.next_page = NextPage::After(last_task_id);
+                progress.wrote_tasks = true;
+                Some((chunk, Some(progress)))
+            }
+            _ => Some((chunk, None)),
+        }
+    })
+}
+
+/// Writes a page of tasks in the export's format, along with anything which starts or ends the file
+fn encode_page(
+    format: dto::TaskFileFormat,
+    page: Vec<TodoTask>,
+    wrote_tasks: bool,
+    is_last_page: bool,
+) -> Result<Bytes, anyhow::Error> {
+    let mut chunk = Vec::new();
+    match format {
+        dto::TaskFileFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(&mut chunk);
+            if !wrote_tasks {
+                writer
+                    .write_record(CSV_COLUMNS)
+                    .context("writing the CSV header")?;
+            }
+            for task in page {
+                // Only the columns in the header are written, so every row has the same number of fields
+                let task = dto::TodoTask::from(task);
+                writer
+                    .serialize((
+                        task.id,
+                        task.description,
+                        task.completed,
+                        task.due_date,
+                        task.recurrence,
+                    ))
+                    .context("writing a task as CSV")?;
+            }
+            writer.flush().context("writing tasks as CSV")?;
+        }
+        dto::TaskFileFormat::Json => {
+            if !wrote_tasks {
+                chunk.push(b'[');
+            }
+            for (idx, task) in page.into_iter().enumerate() {
+                if wrote_tasks || idx > 0 {
+                    chunk.push(b',');
+                }
+                serde_json::to_writer(&mut chunk, &dto::TodoTask::from(task))
+                    .context("writing a task as JSON")?;
+            }
+            if is_last_page {
+                chunk.push(b']');
+            }
+        }
+        dto::TaskFileFormat::TodoTxt => {
+            for task in page {
+                chunk.extend_from_slice(todo_txt_task(task).to_string().as_bytes());
+                chunk.push(b'\n');
+            }
+        }
+    }
+
+    Ok(Bytes::from(chunk))
+}
+
+/// Adds tasks from a CSV, JSON or todo.txt file to a user, in the same format as an export. Every task is
+/// checked against the rules for new tasks before any are added, and if any task is invalid, nothing is
+/// imported. CSV files need a header row naming their columns. The priority and creation and completion dates
+/// of todo.txt tasks are kept as `pri:`, `created:` and `completed:` tags in the description.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/tasks/import",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user to import tasks for"),
+        dto::TaskFileParams,
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log"),
+    ),
+    request_body(
+        content = Vec<ImportedTask>,
+        description = "The tasks to import, as a JSON array or, with `format=csv` or `format=todotxt`, a CSV or todo.txt file",
+        content_type = "application/json",
+    ),
+    responses(
+        (status = 201, description = "Tasks successfully imported", body = ImportedTasks),
+        (
+            status = 400,
+            description = "Some tasks couldn't be read or broke the rules for new tasks (error code `invalid_import`), \
+                or the import has too many tasks (error code `import_too_large`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "invalid_import",
+                "error_description": "Some tasks in the import are invalid, so none were imported.",
+                "extra_info": [
+                    {
+                        "line": 3,
+                        "error_description": "The task breaks the rules for new tasks.",
+                        "validation_issues": {
+                            "item_desc": [{"code": "length", "message": null, "params": {"min": 1, "value": ""}}]
+                        }
+                    }
+                ],
+            })
+        ),
+        (
+            status = 404,
+            description = "The requested user does not exist in the system (error code `no_matching_user`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_user",
+                "error_description": "Could not find a user matching the given information.",
+                "extra_info": null,
+            })
+        ),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+pub async fn import_tasks<TxAble>(
+    user_id: i32,
+    format: dto::TaskFileFormat,
+    file: &[u8],
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl TaskPort,
+) -> Result<(StatusCode, Json<dto::ImportedTasks>), ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    info!("Importing tasks for user {user_id}");
+    let rows = match format {
+        dto::TaskFileFormat::Csv => read_csv_rows(file),
+        dto::TaskFileFormat::Json => read_json_rows(file),
+        dto::TaskFileFormat::TodoTxt => read_todo_txt_rows(file),
+    };
+    if rows.len() > dto::MAX_IMPORTED_TASKS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(dto::BasicError {
+                error_code: "import_too_large".to_owned(),
+                error_description: format!(
+                    "At most {} tasks can be imported at once.",
+                    dto::MAX_IMPORTED_TASKS
+                ),
+                extra_info: None,
+            }),
+        )
+            .into());
+    }
+    let tasks = validate_rows(rows).map_err(|issues| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(dto::BasicError {
+                error_code: "invalid_import".to_owned(),
+                error_description: "Some tasks in the import are invalid, so none were imported."
+                    .to_owned(),
+                extra_info: Some(dto::ExtraInfo::ImportIssues(issues)),
+            }),
+        )
+    })?;
+
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_write = persistence::db_todo_driven_ports::DbTaskWriter::new(calling_user);
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+
+    let import_result = with_transaction(ext_cxn, async |tx_cxn| {
+        task_service
+            .import_tasks(
+                caller,
+                user_id,
+                &tasks,
+                &mut *tx_cxn,
+                &user_detect,
+                &task_write,
+                &event_outbox,
+            )
+            .await
+    })
+    .await;
+    let task_ids = match import_result {
+        Ok(task_ids) => task_ids,
+        Err(TxOrSourceError::Source(domain_error)) => {
+            return Err(super::user::handle_todo_task_err(domain_error))
+        }
+        Err(tx_err) => return Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into()),
+    };
+
+    Ok((StatusCode::CREATED, Json(dto::ImportedTasks { task_ids })))
+}
+
+/// A row read from an import file, or a description of why it couldn't be read
+type ImportRow = (u64, Result<dto::ImportedTask, String>);
+
+/// Reads the rows of a CSV import along with the line each starts on. Reading stops at the first row
+/// which isn't valid CSV, since the rows after it can't be found reliably.
+fn read_csv_rows(file: &[u8]) -> Vec<ImportRow> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(file);
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(err) => return vec![(1, Err(format!("The CSV header can't be read: {err}")))],
+    };
+
+    let mut rows = Vec::new();
+    let mut record = csv::StringRecord::new();
+    loop {
+        match reader.read_record(&mut record) {
+            Ok(false) => break,
+            Ok(true) => {
+                let line = record.position().map_or(0, |position| position.line());
+                let task = record
+                    .deserialize::<dto::ImportedTask>(Some(&headers))
+                    .map_err(|err| match err.kind() {
+                        csv::ErrorKind::Deserialize { err, .. } => {
+                            format!("The task can't be read: {err}")
+                        }
+                        _ => format!("The task can't be read: {err}"),
+                    });
+                rows.push((line, task));
+            }
+            Err(err) => {
+                let line = err.position().map_or(0, |position| position.line());
+                rows.push((line, Err(format!("The row isn't valid CSV: {err}"))));
+                break;
+            }
+        }
+    }
+
+    rows
+}
+
+/// Reads the tasks of a JSON import along with the line each starts on
+fn read_json_rows(file: &[u8]) -> Vec<ImportRow> {
+    let raw_tasks: Vec<&RawValue> = match serde_json::from_slice(file) {
+        Ok(raw_tasks) => raw_tasks,
+        Err(err) => {
+            return vec![(
+                err.line() as u64,
+                Err(format!(
+                    "The import isn't a JSON array of tasks: {}",
+                    json_error_message(&err)
+                )),
+            )]
+        }
+    };
+
+    raw_tasks
+        .into_iter()
+        .map(|raw_task| {
+            // Each task borrows from the file, so its offset into the file finds the line it starts on
+            let offset = raw_task.get().as_ptr() as usize - file.as_ptr() as usize;
+            let line = file[..offset].iter().filter(|&&byte| byte == b'\n').count() as u64 + 1;
+            let task = serde_json::from_str::<dto::ImportedTask>(raw_task.get())
+                .map_err(|err| format!("The task can't be read: {}", json_error_message(&err)));
+
+            (line, task)
+        })
+        .collect()
+}
+
+/// Reads the tasks of a todo.txt import along with the line each is on. Blank lines are skipped.
+fn read_todo_txt_rows(file: &[u8]) -> Vec<ImportRow> {
+    let file = match std::str::from_utf8(file) {
+        Ok(file) => file,
+        Err(err) => {
+            let valid_part = &file[..err.valid_up_to()];
+            let line = valid_part.iter().filter(|&&byte| byte == b'\n').count() as u64 + 1;
+            return vec![(line, Err("The line isn't valid UTF-8.".to_owned()))];
+        }
+    };
+
+    file.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| (idx as u64 + 1, imported_todo_txt_task(line)))
+        .collect()
+}
+
+/// Reads a line of todo.txt as a task, taking its due date and recurrence rule from its `due:` and `rrule:`
+/// tags. A priority and creation and completion dates are kept as `pri:`, `created:` and `completed:` tags in
+/// the description, since tasks have nowhere else for them.
+fn imported_todo_txt_task(line: &str) -> Result<dto::ImportedTask, String> {
+    let mut task: TodoTxtTask = line
+        .parse()
+        .map_err(|err| format!("The task can't be read: {err}"))?;
+    let due_date = task
+        .take_tag(DUE_DATE_TAG)
+        .map(|due_date| {
+            due_date
+                .parse::<NaiveDate>()
+                .map_err(|_| format!("The task can't be read: {due_date} is not a valid due date."))
+        })
+        .transpose()?;
+    let recurrence = task.take_tag(RECURRENCE_TAG);
+    if let Some(priority) = task.priority {
+        if task.tag(PRIORITY_TAG).is_none() {
+            task.add_tag(PRIORITY_TAG, priority);
+        }
+    }
+    if let Some(creation_date) = task.creation_date {
+        if task.tag(CREATION_DATE_TAG).is_none() {
+            task.add_tag(CREATION_DATE_TAG, creation_date);
+        }
+    }
+    if let Some(completion_date) = task.completion_date {
+        if task.tag(COMPLETION_DATE_TAG).is_none() {
+            task.add_tag(COMPLETION_DATE_TAG, completion_date);
+        }
+    }
+
+    Ok(dto::ImportedTask {
+        description: task.description,
+        completed: task.completed,
+        due_date,
+        recurrence,
+    })
+}
+
+/// Writes a task as a line of todo.txt, with its due date and recurrence rule as `due:` and `rrule:` tags.
+/// A `pri:` tag in the description of an open task becomes its priority, and `created:` and `completed:` tags
+/// become its creation and completion dates where the format allows them. Line breaks in the description
+/// are replaced with spaces, since each task has to fit on one line.
+fn todo_txt_task(task: TodoTask) -> TodoTxtTask {
+    let mut line = TodoTxtTask {
+        completed: task.completed,
+        priority: None,
+        completion_date: None,
+        creation_date: None,
+        description: task
+            .item_desc
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    if !line.completed {
+        line.priority = line.tag(PRIORITY_TAG).and_then(priority_from_tag);
+        if line.priority.is_some() {
+            line.take_tag(PRIORITY_TAG);
+        }
+    }
+    // Only completed tasks have a completion date, and a completed task's creation date would be read back
+    // as its completion date without one
+    if line.completed {
+        line.completion_date = line.tag(COMPLETION_DATE_TAG).and_then(date_from_tag);
+        if line.completion_date.is_some() {
+            line.take_tag(COMPLETION_DATE_TAG);
+        }
+    }
+    if !line.completed || line.completion_date.is_some() {
+        line.creation_date = line.tag(CREATION_DATE_TAG).and_then(date_from_tag);
+        if line.creation_date.is_some() {
+            line.take_tag(CREATION_DATE_TAG);
+        }
+    }
+    if let Some(due_date) = task.due_date {
+        line.add_tag(DUE_DATE_TAG, due_date);
+    }
+    if let Some(recurrence) = task.recurrence {
+        line.add_tag(RECURRENCE_TAG, recurrence);
+    }
+
+    line
+}
+
+/// The priority in a `pri:` tag, if it's a valid priority
+fn priority_from_tag(value: &str) -> Option<char> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(priority), None) if priority.is_ascii_uppercase() => Some(priority),
+        _ => None,
+    }
+}
+
+/// The date in a `created:` or `completed:` tag, if it's a valid date
+fn date_from_tag(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+/// Describes a JSON error without its position, which is reported separately
+fn json_error_message(err: &serde_json::Error) -> String {
+    let message = err.to_string();
+    let position = format!(" at line {} column {}", err.line(), err.column());
+
+    message
+        .strip_suffix(&position)
+        .unwrap_or(&message)
+        .to_owned()
+}
+
+/// Checks every row of an import against the rules for new tasks, describing each row which can't be
+/// imported if there are any
+fn validate_rows(
+    rows: Vec<ImportRow>,
+) -> Result<Vec<domain::todo::ImportedTask>, Vec<dto::ImportRowIssue>> {
+    let mut tasks = Vec::with_capacity(rows.len());
+    let mut issues = Vec::new();
+    for (line, row) in rows {
+        match row.map(dto::ImportedTask::validate_into_domain) {
+            Ok(Ok(task)) => tasks.push(task),
+            Ok(Err(validation_errors)) => issues.push(dto::ImportRowIssue {
+                line,
+                error_description: "The task breaks the rules for new tasks.".to_owned(),
+                validation_issues: Some(dto::ValidationErrorSchema(validation_errors)),
+            }),
+            Err(read_error) => issues.push(dto::ImportRowIssue {
+                line,
+                error_description: read_error,
+                validation_issues: None,
+            }),
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(tasks)
+    } else {
+        Err(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain::todo::driving_ports::TaskError;
+    use crate::domain::todo::test_util::MockTaskService;
+    use crate::external_connections;
+    use axum::response::IntoResponse;
+    use futures::StreamExt;
+    use proptest::prelude::*;
+    use std::sync::Mutex;
+
+    fn task(id: i32, item_desc: &str) -> TodoTask {
+        TodoTask {
+            id,
+            owner_user_id: 1,
+            item_desc: item_desc.to_owned(),
+            completed: id % 2 == 0,
+            due_date: NaiveDate::from_ymd_opt(2024, 3, 4),
+            recurrence: None,
+            assignee_user_id: None,
+            comment_count: 0,
+            blocked: false,
+        }
+    }
+
+    async fn export_to_string(
+        format: dto::TaskFileFormat,
+        task_service: Mutex<MockTaskService>,
+    ) -> String {
+        let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+        let chunks = export_tasks(1, format, Caller::Trusted, ext_cxn, task_service)
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+
+        let mut export = Vec::new();
+        for chunk in chunks.collect::<Vec<_>>().await {
+            export.extend_from_slice(&chunk.expect("Export was cut short"));
+        }
+        String::from_utf8(export).unwrap()
+    }
+
+    mod export_tasks {
+        use super::*;
+
+        #[tokio::test]
+        async fn exports_json() {
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.export_page_result
+                    .set_returned_result(Ok(vec![task(1, "Something"), task(2, "Another")]));
+            });
+
+            let export = export_to_string(dto::TaskFileFormat::Json, task_service).await;
+            let exported: Vec<dto::TodoTask> = serde_json::from_str(&export).unwrap();
+            assert!(matches!(
+                exported.as_slice(),
+                [
+                    dto::TodoTask {
+                        id: 1,
+                        completed: false,
+                        ..
+                    },
+                    dto::TodoTask {
+                        id: 2,
+                        completed: true,
+                        ..
+                    },
+                ]
+            ));
+        }
+
+        #[tokio::test]
+        async fn exports_csv() {
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.export_page_result
+                    .set_returned_result(Ok(vec![task(1, "Buy eggs, milk")]));
+            });
+
+            let export = export_to_string(dto::TaskFileFormat::Csv, task_service).await;
+            assert_eq!(
+                "id,description,completed,due_date,recurrence\n\
+                1,\"Buy eggs, milk\",false,2024-03-04,\n",
+                export
+            );
+        }
+
+        #[tokio::test]
+        async fn exports_todo_txt() {
+            let mut open_task = task(1, "Call mom +family pri:B");
+            open_task.recurrence = Some("FREQ=WEEKLY".parse().unwrap());
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.export_page_result
+                    .set_returned_result(Ok(vec![open_task, task(2, "Feed the\ncat pri:A")]));
+            });
+
+            let export = export_to_string(dto::TaskFileFormat::TodoTxt, task_service).await;
+            assert_eq!(
+                "(B) Call mom +family due:2024-03-04 rrule:FREQ=WEEKLY\n\
+                x Feed the cat pri:A due:2024-03-04\n",
+                export
+            );
+        }
+
+        #[tokio::test]
+        async fn exports_empty_files() {
+            for (format, expected) in [
+                (dto::TaskFileFormat::Json, "[]"),
+                (
+                    dto::TaskFileFormat::Csv,
+                    "id,description,completed,due_date,recurrence\n",
+                ),
+            ] {
+                let task_service = MockTaskService::build_locked(|svc| {
+                    svc.export_page_result.set_returned_result(Ok(Vec::new()));
+                });
+
+                assert_eq!(expected, export_to_string(format, task_service).await);
+            }
+        }
+
+        #[tokio::test]
+        async fn returns_404_on_user_not_found() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.export_page_result
+                    .set_returned_result(Err(TaskError::UserDoesNotExist));
+            });
+
+            let export_result = export_tasks(
+                1,
+                dto::TaskFileFormat::Json,
+                Caller::Trusted,
+                ext_cxn,
+                task_service,
+            )
+            .await;
+            let Err(error_response) = export_result else {
+                panic!("Export succeeded for a user who doesn't exist");
+            };
+            let response = Err::<(), _>(error_response).into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_user", body.error_code);
+        }
+    }
+
+    mod import_tasks {
+        use super::*;
+
+        #[tokio::test]
+        async fn imports_csv() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.import_tasks_result.set_returned_result(Ok(vec![5, 6]));
+            });
+            let file = "id,description,completed,due_date,recurrence\n\
+                1,Something to do,true,,\n\
+                2,\"Stretch, daily\",false,2024-03-04,FREQ=DAILY\n";
+
+            let (status, Json(imported)) = import_tasks(
+                1,
+                dto::TaskFileFormat::Csv,
+                file.as_bytes(),
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(StatusCode::CREATED, status);
+            assert_eq!(vec![5, 6], imported.task_ids);
+
+            let locked_service = task_service.lock().unwrap();
+            assert!(matches!(
+                locked_service.import_tasks_result.calls(),
+                [(1, tasks)] if matches!(tasks.as_slice(), [
+                    domain::todo::ImportedTask {
+                        task: domain::todo::NewTask { description: first, due_date: None, recurrence: None, assignee_user_id: None },
+                        completed: true,
+                    },
+                    domain::todo::ImportedTask {
+                        task: domain::todo::NewTask { description: second, due_date: Some(_), recurrence: Some(_), assignee_user_id: None },
+                        completed: false,
+                    },
+                ] if first == "Something to do" && second == "Stretch, daily")
+            ));
+        }
+
+        #[tokio::test]
+        async fn imports_json() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.import_tasks_result.set_returned_result(Ok(vec![5]));
+            });
+            let file =
+                r#"[{"id": 10, "description": "Something to do", "due_date": "2024-03-04"}]"#;
+
+            let (status, Json(imported)) = import_tasks(
+                1,
+                dto::TaskFileFormat::Json,
+                file.as_bytes(),
+                None,
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(StatusCode::CREATED, status);
+            assert_eq!(vec![5], imported.task_ids);
+        }
+
+        async fn import_issues(format: dto::TaskFileFormat, file: &str) -> serde_json::Value {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = MockTaskService::new_locked();
+
+            let response = import_tasks(
+                1,
+                format,
+                file.as_bytes(),
+                None,
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+            assert!(task_service
+                .lock()
+                .unwrap()
+                .import_tasks_result
+                .calls()
+                .is_empty());
+
+            let body: serde_json::Value = deserialize_body(response.into_body()).await;
+            assert_eq!("invalid_import", body["error_code"]);
+            body["extra_info"].clone()
+        }
+
+        #[tokio::test]
+        async fn reports_invalid_csv_rows_by_line() {
+            let file = "description,due_date,recurrence\n\
+                Fine,,\n\
+                ,,\n\
+                Bad date,2024-13-01,\n\
+                No due date,,FREQ=DAILY\n";
+
+            let issues = import_issues(dto::TaskFileFormat::Csv, file).await;
+            let lines: Vec<u64> = issues
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|issue| issue["line"].as_u64().unwrap())
+                .collect();
+            assert_eq!(vec![3, 4, 5], lines);
+            assert!(issues[0]["validation_issues"]["item_desc"].is_array());
+            assert!(issues[1]["validation_issues"].is_null());
+            assert!(issues[2]["validation_issues"]["__all__"].is_array());
+        }
+
+        #[tokio::test]
+        async fn reports_invalid_json_tasks_by_line() {
+            let file = "[\n  {\"description\": \"Fine\"},\n  {\"description\": \"\"},\n  {\"completed\": true}\n]";
+
+            let issues = import_issues(dto::TaskFileFormat::Json, file).await;
+            assert_eq!(2, issues.as_array().unwrap().len());
+            assert_eq!(3, issues[0]["line"]);
+            assert_eq!(4, issues[1]["line"]);
+            assert_eq!(
+                "The task can't be read: missing field `description`",
+                issues[1]["error_description"]
+            );
+        }
+
+        #[tokio::test]
+        async fn reports_malformed_json() {
+            let issues = import_issues(dto::TaskFileFormat::Json, "[\n{\"description\": ").await;
+            assert_eq!(2, issues[0]["line"]);
+        }
+
+        #[tokio::test]
+        async fn imports_todo_txt() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.import_tasks_result.set_returned_result(Ok(vec![5, 6]));
+            });
+            let file = "(A) 2024-03-01 Call mom +family @phone due:2024-03-06\r\n\
+                \r\n\
+                x 2024-03-02 Stretch rrule:FREQ=DAILY due:2024-03-02\r\n";
+
+            let import_result = import_tasks(
+                1,
+                dto::TaskFileFormat::TodoTxt,
+                file.as_bytes(),
+                None,
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await;
+            assert!(matches!(import_result, Ok((StatusCode::CREATED, _))));
+
+            let locked_service = task_service.lock().unwrap();
+            assert!(matches!(
+                locked_service.import_tasks_result.calls(),
+                [(1, tasks)] if matches!(tasks.as_slice(), [
+                    domain::todo::ImportedTask {
+                        task: domain::todo::NewTask { description: first, due_date: Some(_), recurrence: None, assignee_user_id: None },
+                        completed: false,
+                    },
+                    domain::todo::ImportedTask {
+                        task: domain::todo::NewTask { description: second, due_date: Some(_), recurrence: Some(_), assignee_user_id: None },
+                        completed: true,
+                    },
+                ] if first == "Call mom +family @phone pri:A created:2024-03-01" && second == "Stretch completed:2024-03-02")
+            ));
+        }
+
+        #[tokio::test]
+        async fn reports_invalid_todo_txt_lines_by_line() {
+            let file =
+                "Fine\n\nx 2024-03-01\nBad due date due:soon\nNo due date rrule:FREQ=DAILY\n";
+
+            let issues = import_issues(dto::TaskFileFormat::TodoTxt, file).await;
+            let lines: Vec<u64> = issues
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|issue| issue["line"].as_u64().unwrap())
+                .collect();
+            assert_eq!(vec![3, 4, 5], lines);
+            assert_eq!(
+                "The task can't be read: The task has no description.",
+                issues[0]["error_description"]
+            );
+            assert!(issues[2]["validation_issues"]["__all__"].is_array());
+        }
+
+        #[tokio::test]
+        async fn returns_404_on_user_not_found() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = MockTaskService::build_locked(|svc| {
+                svc.import_tasks_result
+                    .set_returned_result(Err(TaskError::UserDoesNotExist));
+            });
+
+            let response = import_tasks(
+                1,
+                dto::TaskFileFormat::Json,
+                b"[]",
+                None,
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+        }
+    }
+
+    mod todo_txt {
+        use super::*;
+
+        fn any_date() -> impl Strategy<Value = NaiveDate> {
+            (2000..2100i32, 1..=12u32, 1..=28u32)
+                .prop_map(|(year, month, day)| NaiveDate::from_ymd_opt(year, month, day).unwrap())
+        }
+
+        /// Any description without tags, including ones which start like a completed task, a priority or a date
+        fn any_description() -> impl Strategy<Value = String> {
+            let first_word = prop_oneof![
+                4 => "[^\\s:]{1,8}",
+                1 => Just("x".to_owned()),
+                1 => "\\([A-Z]\\)",
+                1 => any_date().prop_map(|date| date.to_string()),
+                1 => "\\\\[^\\s:]{0,5}",
+            ];
+            (first_word, "( [+@]?[^\\s:]{1,8}){0,5}")
+                .prop_map(|(first_word, rest)| format!("{first_word}{rest}"))
+        }
+
+        prop_compose! {
+            fn any_task()(
+                description in any_description(),
+                priority in prop::option::of(prop::char::range('A', 'Z')),
+                completed in any::<bool>(),
+                due_date in prop::option::of(any_date()),
+                recurring in any::<bool>(),
+                creation_date in prop::option::of(any_date()),
+                completion_date in prop::option::of(any_date()),
+            ) -> TodoTask {
+                let mut item_desc = description;
+                if let Some(priority) = priority {
+                    item_desc = format!("{item_desc} pri:{priority}");
+                }
+                if let Some(creation_date) = creation_date {
+                    item_desc = format!("{item_desc} created:{creation_date}");
+                }
+                // Open tasks haven't been completed yet
+                if let Some(completion_date) = completion_date.filter(|_| completed) {
+                    item_desc = format!("{item_desc} completed:{completion_date}");
+                }
+                TodoTask {
+                    id: 1,
+                    owner_user_id: 1,
+                    item_desc,
+                    completed,
+                    due_date,
+                    recurrence: due_date
+                        .filter(|_| recurring)
+                        .map(|_| "FREQ=WEEKLY;BYDAY=MO,FR".parse().unwrap()),
+                    assignee_user_id: None,
+                    comment_count: 0,
+                    blocked: false,
+                }
+            }
+        }
+
+        #[test]
+        fn keeps_dates_through_a_round_trip() {
+            let imported =
+                imported_todo_txt_task("x (B) 2024-03-05 2024-03-01 Call mom due:2024-03-06")
+                    .unwrap();
+            assert_eq!(
+                "Call mom pri:B created:2024-03-01 completed:2024-03-05",
+                imported.description
+            );
+
+            let mut exported = task(1, &imported.description);
+            exported.completed = imported.completed;
+            exported.due_date = imported.due_date;
+            assert_eq!(
+                "x 2024-03-05 2024-03-01 Call mom pri:B due:2024-03-06",
+                todo_txt_task(exported).to_string()
+            );
+        }
+
+        #[test]
+        fn keeps_open_tasks_which_look_completed_open() {
+            let line = todo_txt_task(task(1, "x marks the spot pri:C")).to_string();
+            assert_eq!("(C) \\x marks the spot due:2024-03-04", line);
+
+            let imported = imported_todo_txt_task(&line).unwrap();
+            assert!(!imported.completed);
+            assert_eq!("x marks the spot pri:C", imported.description);
+        }
+
+        proptest! {
+            #[test]
+            fn exported_tasks_import_unchanged(task in any_task()) {
+                let line = todo_txt_task(task.clone()).to_string();
+                let imported = imported_todo_txt_task(&line).unwrap();
+
+                prop_assert_eq!(task.item_desc, imported.description);
+                prop_assert_eq!(task.completed, imported.completed);
+                prop_assert_eq!(task.due_date, imported.due_date);
+                prop_assert_eq!(task.recurrence.map(|rule| rule.to_string()), imported.recurrence);
+            }
+        }
+    }
+}
@@ -1,18 +1,25 @@
-use crate::external_connections::ExternalConnectivity;
-use crate::routing_utils::{GenericErrorResponse, Json, ValidationErrorResponse};
+use crate::api::access::RequestCaller;
+use crate::domain::access::Caller;
+use crate::domain::todo::driving_ports::TaskPort;
+use crate::external_connections::{
+    with_transaction, ExternalConnectivity, Transactable, TxOrSourceError,
+};
+use crate::routing_utils::{CallingUser, GenericErrorResponse, Json, ValidationErrorResponse};
 use crate::{domain, dto, persistence, AppState, SharedData};
+use anyhow::anyhow;
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
-use axum::response::{ErrorResponse, IntoResponse, Response};
-use axum::routing::patch;
+use axum::response::ErrorResponse;
+use axum::routing::{patch, post, put};
 use axum::Router;
 use log::{error, info};
 use std::sync::Arc;
+use std::time::Duration;
 use utoipa::OpenApi;
 use validator::Validate;
 
 #[derive(OpenApi)]
-#[openapi(paths(update_task, delete_task,))]
+#[openapi(paths(update_task, delete_task, complete_task, restore_task, assign_task))]
 /// Defines the OpenAPI documentation for the tasks API
 pub struct TaskApi;
 /// Constant used to group task endpoints in OpenAPI documentation
@@ -20,27 +27,108 @@ pub const TASK_API_GROUP: &str = "Tasks";
 
 /// Creates a router for endpoints under the "/tasks" group of APIs
 pub fn task_routes() -> Router<Arc<SharedData>> {
-    Router::new().route(
-        "/:task_id",
-        patch(
-            |State(app_state): AppState,
-             Path(task_id): Path<i32>,
-             Json(update): Json<dto::UpdateTask>| async move {
-                let mut ext_cxn = app_state.ext_cxn.clone();
-                let task_service = domain::todo::TaskService;
-
-                update_task(task_id, update, &mut ext_cxn, &task_service).await
-            },
+    Router::new()
+        .route(
+            "/:task_id",
+            patch(
+                |State(app_state): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(task_id): Path<i32>,
+                 CallingUser(calling_user): CallingUser,
+                 Json(update): Json<dto::UpdateTask>| async move {
+                    let task_service = domain::todo::TaskService;
+
+                    update_task(
+                        task_id,
+                        update,
+                        calling_user,
+                        &caller,
+                        &app_state.ext_cxn,
+                        &task_service,
+                    )
+                    .await
+                },
+            )
+            .delete(
+                |State(app_state): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(task_id): Path<i32>,
+                 CallingUser(calling_user): CallingUser| async move {
+                    let task_service = domain::todo::TaskService;
+
+                    delete_task(
+                        task_id,
+                        calling_user,
+                        &caller,
+                        &app_state.ext_cxn,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:task_id/complete",
+            post(
+                |State(app_state): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(task_id): Path<i32>,
+                 CallingUser(calling_user): CallingUser| async move {
+                    let task_service = domain::todo::TaskService;
+
+                    complete_task(
+                        task_id,
+                        calling_user,
+                        &caller,
+                        &app_state.ext_cxn,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:task_id/restore",
+            post(
+                |State(app_state): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(task_id): Path<i32>,
+                 CallingUser(calling_user): CallingUser| async move {
+                    let task_service = domain::todo::TaskService;
+
+                    restore_task(
+                        task_id,
+                        calling_user,
+                        &caller,
+                        &app_state.ext_cxn,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:task_id/assignee",
+            put(
+                |State(app_state): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(task_id): Path<i32>,
+                 CallingUser(calling_user): CallingUser,
+                 Json(assignment): Json<dto::TaskAssignment>| async move {
+                    let task_service = domain::todo::TaskService;
+
+                    assign_task(
+                        task_id,
+                        assignment,
+                        calling_user,
+                        &caller,
+                        &app_state.ext_cxn,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
         )
-        .delete(
-            |State(app_state): AppState, Path(task_id): Path<i32>| async move {
-                let mut ext_cxn = app_state.ext_cxn.clone();
-                let task_service = domain::todo::TaskService;
-
-                delete_task(task_id, &mut ext_cxn, &task_service).await
-            },
-        ),
-    )
 }
 
 /// Updates the content of a task
@@ -50,69 +138,351 @@ pub fn task_routes() -> Router<Arc<SharedData>> {
     tag = TASK_API_GROUP,
     params(
         ("task_id" = i32, Path, description = "The ID of the task to update"),
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log"),
     ),
     request_body = UpdateTask,
     responses(
         (status = 200, description = "Task successfully updated"),
         (status = 400, response = dto::err_resps::BasicError400Validation),
+        (status = 403, response = dto::err_resps::BasicError403),
         (status = 500, response = dto::err_resps::BasicError500),
     ),
 )]
-async fn update_task(
+async fn update_task<TxAble>(
     task_id: i32,
     task_data: dto::UpdateTask,
-    ext_cxn: &mut impl ExternalConnectivity,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
     task_service: &impl domain::todo::driving_ports::TaskPort,
-) -> Result<StatusCode, ErrorResponse> {
+) -> Result<StatusCode, ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
     info!("Updating task {task_id}");
     task_data
         .validate()
         .map_err(ValidationErrorResponse::from)?;
 
     let domain_update = domain::todo::UpdateTask::from(task_data);
-    let task_writer = persistence::db_todo_driven_ports::DbTaskWriter;
-
-    let update_result = task_service
-        .update_task(task_id, &domain_update, &mut *ext_cxn, &task_writer)
-        .await;
+    let task_reader = persistence::db_todo_driven_ports::DbTaskReader;
+    let task_writer = persistence::db_todo_driven_ports::DbTaskWriter::new(calling_user);
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+
+    let update_result = with_transaction(ext_cxn, async |tx_cxn| {
+        task_service
+            .update_task(
+                caller,
+                task_id,
+                &domain_update,
+                &mut *tx_cxn,
+                &task_reader,
+                &task_writer,
+                &event_outbox,
+            )
+            .await
+    })
+    .await;
     match update_result {
         Ok(_) => Ok(StatusCode::OK),
-        Err(db_err) => {
-            error!("Update task failure: {db_err}");
-            Err(GenericErrorResponse(db_err).into())
+        Err(TxOrSourceError::Source(task_err)) => {
+            error!("Update task failure: {task_err}");
+            Err(super::user::handle_todo_task_err(task_err))
+        }
+        Err(tx_err) => {
+            error!("Update task failure: {tx_err}");
+            Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into())
         }
     }
 }
 
-/// Deletes a task
+/// Moves a task to the trash. Deleted tasks can be restored until they are purged.
 #[utoipa::path(
     delete,
     path = "/tasks/{task_id}",
     tag = TASK_API_GROUP,
     params(
-        ("task_id" = i32, Path, description = "The ID of the task to delete")
+        ("task_id" = i32, Path, description = "The ID of the task to delete"),
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log"),
     ),
     responses(
         (status = 200, description = "Task successfully deleted"),
+        (status = 403, response = dto::err_resps::BasicError403),
         (status = 500, response = dto::err_resps::BasicError500),
     ),
 )]
-async fn delete_task(
+async fn delete_task<TxAble>(
     task_id: i32,
-    ext_cxn: &mut impl ExternalConnectivity,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
     task_service: &impl domain::todo::driving_ports::TaskPort,
-) -> Result<StatusCode, Response> {
+) -> Result<StatusCode, ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
     info!("Deleting task {task_id}");
-    let task_write = persistence::db_todo_driven_ports::DbTaskWriter;
-
-    let delete_result = task_service
-        .delete_task(task_id, &mut *ext_cxn, &task_write)
-        .await;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let task_write = persistence::db_todo_driven_ports::DbTaskWriter::new(calling_user);
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+
+    let delete_result = with_transaction(ext_cxn, async |tx_cxn| {
+        task_service
+            .delete_task(
+                caller,
+                task_id,
+                &mut *tx_cxn,
+                &task_read,
+                &task_write,
+                &event_outbox,
+            )
+            .await
+    })
+    .await;
     match delete_result {
         Ok(_) => Ok(StatusCode::OK),
-        Err(db_err) => {
-            error!("Failed to delete task: {db_err}");
-            Err(GenericErrorResponse(db_err).into_response())
+        Err(TxOrSourceError::Source(task_err)) => {
+            error!("Failed to delete task: {task_err}");
+            Err(super::user::handle_todo_task_err(task_err))
+        }
+        Err(tx_err) => {
+            error!("Failed to delete task: {tx_err}");
+            Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into())
+        }
+    }
+}
+
+/// Marks a task as completed. Completing a recurring task creates its next occurrence, due on the next date
+/// its recurrence rule produces. Tasks which are blocked by unfinished tasks can't be completed.
+#[utoipa::path(
+    post,
+    path = "/tasks/{task_id}/complete",
+    tag = TASK_API_GROUP,
+    params(
+        ("task_id" = i32, Path, description = "The ID of the task to complete"),
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log"),
+    ),
+    responses(
+        (status = 200, description = "Task successfully completed", body = CompletedTask),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The task does not exist (error code `no_matching_task`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_task",
+                "error_description": "The specified task does not exist.",
+                "extra_info": null,
+            })
+        ),
+        (
+            status = 409,
+            description = "The task is blocked by a task which hasn't been completed yet (error code `task_blocked`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "task_blocked",
+                "error_description": "The task is blocked by another task which has not been completed yet.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn complete_task<TxAble>(
+    task_id: i32,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> Result<Json<dto::CompletedTask>, ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    info!("Completing task {task_id}");
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let task_write = persistence::db_todo_driven_ports::DbTaskWriter::new(calling_user);
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+
+    let complete_result = with_transaction(ext_cxn, async |tx_cxn| {
+        task_service
+            .complete_task(
+                caller,
+                task_id,
+                &mut *tx_cxn,
+                &task_read,
+                &task_write,
+                &event_outbox,
+            )
+            .await
+    })
+    .await;
+    match complete_result {
+        Ok(next_occurrence) => Ok(Json(dto::CompletedTask {
+            next_occurrence: next_occurrence.map(dto::TodoTask::from),
+        })),
+        Err(TxOrSourceError::Source(task_err)) => Err(super::user::handle_todo_task_err(task_err)),
+        Err(tx_err) => {
+            error!("Failed to complete task: {tx_err}");
+            Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into())
+        }
+    }
+}
+
+/// Takes a deleted task back out of the trash
+#[utoipa::path(
+    post,
+    path = "/tasks/{task_id}/restore",
+    tag = TASK_API_GROUP,
+    params(
+        ("task_id" = i32, Path, description = "The ID of the deleted task to restore"),
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log"),
+    ),
+    responses(
+        (status = 200, description = "Task successfully restored", body = TodoTask),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The task is not in the trash (error code `no_matching_task`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_task",
+                "error_description": "The specified task does not exist.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn restore_task<TxAble>(
+    task_id: i32,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> Result<Json<dto::TodoTask>, ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    info!("Restoring task {task_id}");
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let task_write = persistence::db_todo_driven_ports::DbTaskWriter::new(calling_user);
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+
+    let restore_result = with_transaction(ext_cxn, async |tx_cxn| {
+        task_service
+            .restore_task(
+                caller,
+                task_id,
+                &mut *tx_cxn,
+                &task_read,
+                &task_write,
+                &event_outbox,
+            )
+            .await
+    })
+    .await;
+    match restore_result {
+        Ok(task) => Ok(Json(dto::TodoTask::from(task))),
+        Err(TxOrSourceError::Source(task_err)) => Err(super::user::handle_todo_task_err(task_err)),
+        Err(tx_err) => {
+            error!("Failed to restore task: {tx_err}");
+            Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into())
+        }
+    }
+}
+
+/// Gives a task to a different user to do, or unassigns it. Subscribers to `task.assigned` webhook events are
+/// notified when the assignee changes.
+#[utoipa::path(
+    put,
+    path = "/tasks/{task_id}/assignee",
+    tag = TASK_API_GROUP,
+    params(
+        ("task_id" = i32, Path, description = "The ID of the task to assign"),
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log"),
+    ),
+    request_body = TaskAssignment,
+    responses(
+        (status = 200, description = "Task successfully assigned", body = TodoTask),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "The task or the user to assign it to does not exist (error code `no_matching_task` or `no_matching_assignee`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_assignee",
+                "error_description": "The user to assign the task to does not exist.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn assign_task<TxAble>(
+    task_id: i32,
+    assignment: dto::TaskAssignment,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> Result<Json<dto::TodoTask>, ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    info!("Assigning task {task_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let task_write = persistence::db_todo_driven_ports::DbTaskWriter::new(calling_user);
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+
+    let assign_result = with_transaction(ext_cxn, async |tx_cxn| {
+        task_service
+            .assign_task(
+                caller,
+                task_id,
+                assignment.assignee_user_id,
+                &mut *tx_cxn,
+                &user_detect,
+                &task_read,
+                &task_write,
+                &event_outbox,
+            )
+            .await
+    })
+    .await;
+    match assign_result {
+        Ok(task) => Ok(Json(dto::TodoTask::from(task))),
+        Err(TxOrSourceError::Source(task_err)) => Err(super::user::handle_todo_task_err(task_err)),
+        Err(tx_err) => {
+            error!("Failed to assign task: {tx_err}");
+            Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into())
+        }
+    }
+}
+
+/// Periodically removes tasks which have been in the trash longer than the retention period
+pub async fn purge_trash(
+    mut ext_cxn: impl ExternalConnectivity,
+    retention: Duration,
+    purge_interval: Duration,
+) {
+    let task_service = domain::todo::TaskService;
+    let task_write = persistence::db_todo_driven_ports::DbTaskWriter::new(None);
+    let mut interval = tokio::time::interval(purge_interval);
+
+    loop {
+        interval.tick().await;
+        match task_service
+            .purge_trash(retention, &mut ext_cxn, &task_write)
+            .await
+        {
+            Ok(purged) => info!("Purged {purged} tasks from the trash"),
+            Err(purge_err) => error!("Failed to purge the trash: {purge_err}"),
         }
     }
 }
@@ -120,8 +490,10 @@ async fn delete_task(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::todo::driving_ports::TaskError;
     use crate::{domain, dto, external_connections};
     use anyhow::anyhow;
+    use axum::response::IntoResponse;
     use speculoos::prelude::*;
     use std::sync::Mutex;
 
@@ -132,11 +504,11 @@ mod tests {
         #[tokio::test]
         async fn happy_path() {
             let mut task_service_raw = domain::todo::test_util::MockTaskService::new();
-            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
             task_service_raw
                 .update_task_result
-                .set_returned_anyhow(Ok(()));
+                .set_returned_result(Ok(()));
             let task_service = Mutex::new(task_service_raw);
 
             let update_task_response = update_task(
@@ -144,7 +516,9 @@ mod tests {
                 dto::UpdateTask {
                     description: "Something to do".to_owned(),
                 },
-                &mut ext_cxn,
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
                 &task_service,
             )
             .await;
@@ -161,11 +535,11 @@ mod tests {
         #[tokio::test]
         async fn returns_500_on_failed_update() {
             let mut task_service_raw = domain::todo::test_util::MockTaskService::new();
-            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
             task_service_raw
                 .update_task_result
-                .set_returned_anyhow(Err(anyhow!("Something went wrong!")));
+                .set_returned_result(Err(TaskError::PortError(anyhow!("Something went wrong!"))));
             let task_service = Mutex::new(task_service_raw);
 
             let update_task_response = update_task(
@@ -173,7 +547,9 @@ mod tests {
                 dto::UpdateTask {
                     description: "Something to do".to_owned(),
                 },
-                &mut ext_cxn,
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
                 &task_service,
             )
             .await;
@@ -189,14 +565,16 @@ mod tests {
         #[tokio::test]
         async fn returns_400_on_bad_input() {
             let task_service = domain::todo::test_util::MockTaskService::new_locked();
-            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
             let update_task_response = update_task(
                 5,
                 dto::UpdateTask {
                     description: String::new(),
                 },
-                &mut ext_cxn,
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
                 &task_service,
             )
             .await;
@@ -216,13 +594,14 @@ mod tests {
 
         #[tokio::test]
         async fn happy_path() {
-            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
-                svc.delete_task_result.set_returned_anyhow(Ok(()));
+                svc.delete_task_result.set_returned_result(Ok(()));
             });
 
             // Verify we got the expected response
-            let delete_task_result = delete_task(5, &mut ext_cxn, &task_service).await;
+            let delete_task_result =
+                delete_task(5, Some(1), &Caller::Trusted, &ext_cxn, &task_service).await;
             let Ok(status) = delete_task_result else {
                 panic!(
                     "Didn't receive expected response: {:#?}",
@@ -241,14 +620,15 @@ mod tests {
 
         #[tokio::test]
         async fn returns_500_when_service_blows_up() {
-            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
                 svc.delete_task_result
-                    .set_returned_anyhow(Err(anyhow!("Whoopsie daisy!")));
+                    .set_returned_result(Err(TaskError::PortError(anyhow!("Whoopsie daisy!"))));
             });
 
             // Verify we got the expected response
-            let delete_task_result = delete_task(5, &mut ext_cxn, &task_service).await;
+            let delete_task_result =
+                delete_task(5, Some(1), &Caller::Trusted, &ext_cxn, &task_service).await;
             let response = delete_task_result.into_response();
 
             assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
@@ -257,4 +637,234 @@ mod tests {
             assert_eq!("internal_error", deserialized_body.error_code);
         }
     }
+
+    mod complete_task {
+        use super::*;
+        use crate::api::test_util::deserialize_body;
+        use crate::domain::todo::driving_ports::TaskError;
+        use chrono::NaiveDate;
+
+        #[tokio::test]
+        async fn happy_path_recurring() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.complete_task_result
+                    .set_returned_result(Ok(Some(domain::todo::TodoTask {
+                        id: 6,
+                        owner_user_id: 1,
+                        item_desc: "Water the plants".to_owned(),
+                        completed: false,
+                        due_date: NaiveDate::from_ymd_opt(2024, 3, 11),
+                        recurrence: Some("FREQ=WEEKLY".parse().unwrap()),
+                        assignee_user_id: None,
+                        comment_count: 0,
+                        blocked: false,
+                    })));
+            });
+
+            let Json(completed) =
+                complete_task(5, Some(1), &Caller::Trusted, &ext_cxn, &task_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+            assert!(matches!(completed.next_occurrence, Some(dto::TodoTask {
+                id: 6,
+                completed: false,
+                due_date: Some(due_date),
+                recurrence: Some(ref rule),
+                ..
+            }) if due_date == NaiveDate::from_ymd_opt(2024, 3, 11).unwrap() && rule == "FREQ=WEEKLY"));
+
+            let locked_service = task_service.lock().unwrap();
+            assert_eq!(&[5], locked_service.complete_task_result.calls());
+        }
+
+        #[tokio::test]
+        async fn happy_path_not_recurring() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.complete_task_result.set_returned_result(Ok(None));
+            });
+
+            let Json(completed) =
+                complete_task(5, Some(1), &Caller::Trusted, &ext_cxn, &task_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+            assert!(completed.next_occurrence.is_none());
+        }
+
+        #[tokio::test]
+        async fn returns_404_when_task_doesnt_exist() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.complete_task_result
+                    .set_returned_result(Err(TaskError::TaskDoesNotExist));
+            });
+
+            let response = complete_task(5, Some(1), &Caller::Trusted, &ext_cxn, &task_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_task", body.error_code);
+        }
+
+        #[tokio::test]
+        async fn returns_409_when_task_is_blocked() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.complete_task_result
+                    .set_returned_result(Err(TaskError::TaskBlocked));
+            });
+
+            let response = complete_task(5, Some(1), &Caller::Trusted, &ext_cxn, &task_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::CONFLICT, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("task_blocked", body.error_code);
+        }
+    }
+
+    mod restore_task {
+        use super::*;
+        use crate::api::test_util::deserialize_body;
+        use crate::domain::todo::driving_ports::TaskError;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.restore_task_result
+                    .set_returned_result(Ok(domain::todo::TodoTask {
+                        id: 5,
+                        owner_user_id: 1,
+                        item_desc: "Something to do".to_owned(),
+                        completed: false,
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                        comment_count: 0,
+                        blocked: false,
+                    }));
+            });
+
+            let Json(task) = restore_task(5, Some(1), &Caller::Trusted, &ext_cxn, &task_service)
+                .await
+                .unwrap_or_else(|err| {
+                    panic!("Didn't get the expected response! Error: {:#?}", err);
+                });
+            assert!(matches!(task, dto::TodoTask {
+                id: 5,
+                description,
+                completed: false,
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+                comment_count: 0,
+                blocked: false,
+            } if description == "Something to do"));
+
+            let locked_service = task_service.lock().unwrap();
+            assert_eq!(&[5], locked_service.restore_task_result.calls());
+        }
+
+        #[tokio::test]
+        async fn returns_404_when_task_isnt_trashed() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.restore_task_result
+                    .set_returned_result(Err(TaskError::TaskDoesNotExist));
+            });
+
+            let response = restore_task(5, Some(1), &Caller::Trusted, &ext_cxn, &task_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_task", body.error_code);
+        }
+    }
+    mod assign_task {
+        use super::*;
+        use crate::api::test_util::deserialize_body;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.assign_task_result
+                    .set_returned_result(Ok(domain::todo::TodoTask {
+                        id: 5,
+                        owner_user_id: 1,
+                        item_desc: "Something to do".to_owned(),
+                        completed: false,
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: Some(2),
+                        comment_count: 0,
+                        blocked: false,
+                    }));
+            });
+
+            let Json(task) = assign_task(
+                5,
+                dto::TaskAssignment {
+                    assignee_user_id: Some(2),
+                },
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert!(matches!(
+                task,
+                dto::TodoTask {
+                    id: 5,
+                    assignee_user_id: Some(2),
+                    ..
+                }
+            ));
+            assert_that!(ext_cxn.did_transaction_commit()).is_true();
+
+            let locked_service = task_service.lock().unwrap();
+            assert_eq!(&[(5, Some(2))], locked_service.assign_task_result.calls());
+        }
+
+        #[tokio::test]
+        async fn returns_404_when_assignee_doesnt_exist() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.assign_task_result
+                    .set_returned_result(Err(TaskError::AssigneeDoesNotExist));
+            });
+
+            let response = assign_task(
+                5,
+                dto::TaskAssignment {
+                    assignee_user_id: Some(20),
+                },
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_assignee", body.error_code);
+        }
+    }
 }
@@ -1,26 +1,74 @@
+use crate::api::access::{ForbiddenResponse, RequestCaller};
+use crate::domain::access::{Action, Caller};
+use crate::domain::task_event::driving_ports::TaskEventPort;
 use crate::domain::todo::driving_ports::TaskError;
-use crate::domain::user::driving_ports::CreateUserError;
-use crate::external_connections::ExternalConnectivity;
-use crate::routing_utils::{GenericErrorResponse, Json, ValidationErrorResponse};
+use crate::domain::user::driving_ports::{CreateUserError, UserError};
+use crate::external_connections::{
+    with_transaction, ExternalConnectivity, Transactable, TxOrSourceError,
+};
+use crate::routing_utils::{CallingUser, GenericErrorResponse, Json, ValidationErrorResponse};
 use crate::{domain, dto, persistence, AppState, SharedData};
-use axum::extract::{Path, State};
-use axum::http::StatusCode;
+use anyhow::anyhow;
+use axum::body::{Body, Bytes};
+use axum::extract::ws::WebSocketUpgrade;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::ErrorResponse;
-use axum::routing::get;
+use axum::routing::{delete, get, patch, post, put};
 use axum::Router;
+use chrono::NaiveDate;
+use futures::{stream, Stream, StreamExt};
 use log::{error, info};
 use serde::Deserialize;
+use std::collections::VecDeque;
 use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
 use utoipa::OpenApi;
 use validator::Validate;
 
 #[derive(OpenApi)]
 #[openapi(paths(
     get_users,
+    get_user,
+    set_user_role,
     create_user,
     get_tasks_for_user,
+    get_trashed_tasks_for_user,
+    get_assigned_tasks,
     get_task_for_user,
+    preview_task_occurrences,
+    search_tasks_for_user,
+    stream_task_events,
+    super::task_socket::open_task_socket,
+    super::calendar_feed::get_task_calendar,
+    super::calendar_feed::issue_feed_token,
+    super::calendar_feed::revoke_feed_token,
+    super::digest::get_digest_preferences,
+    super::digest::set_digest_preferences,
+    super::share::get_shares,
+    super::share::grant_share,
+    super::share::revoke_share,
+    super::share::get_shared_tasks,
+    super::comment::get_comments,
+    super::comment::add_comment,
+    super::comment::edit_comment,
+    super::comment::delete_comment,
+    super::attachment::get_attachments,
+    super::attachment::upload_attachment,
+    super::attachment::download_attachment,
+    super::attachment::delete_attachment,
+    super::task_dependency::add_task_dependency,
+    super::task_dependency::remove_task_dependency,
+    super::task_dependency::get_tasks_in_dependency_order,
+    super::reminder::get_reminders,
+    super::reminder::add_reminder,
+    super::reminder::delete_reminder,
+    super::task_transfer::export_tasks,
+    super::task_transfer::import_tasks,
     add_task_for_user,
+    apply_task_batch,
 ))]
 /// Defines the OpenAPI spec for user endpoints
 pub struct UsersApi;
@@ -33,81 +81,831 @@ pub fn user_routes() -> Router<Arc<SharedData>> {
     Router::new()
         .route(
             "/",
-            get(|State(app_data): AppState| async move {
-                let user_service = domain::user::UserService;
-                let mut external_connectivity = app_data.ext_cxn.clone();
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Query(params): Query<dto::UserListParams>| async move {
+                    let user_service = domain::user::UserService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
 
-                get_users(&mut external_connectivity, &user_service).await
-            })
+                    get_users(params, &caller, &mut external_connectivity, &user_service).await
+                },
+            )
             .post(
-                |State(app_data): AppState, Json(new_user): Json<dto::NewUser>| async move {
+                |State(app_data): AppState,
+                 CallingUser(calling_user): CallingUser,
+                 Json(new_user): Json<dto::NewUser>| async move {
+                    let user_service = domain::user::UserService;
+
+                    create_user(new_user, calling_user, &app_data.ext_cxn, &user_service).await
+                },
+            ),
+        )
+        .route(
+            "/:user_id",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>| async move {
                     let user_service = domain::user::UserService;
                     let mut external_connectivity = app_data.ext_cxn.clone();
 
-                    create_user(new_user, &mut external_connectivity, &user_service).await
+                    get_user(user_id, &caller, &mut external_connectivity, &user_service).await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/role",
+            put(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 CallingUser(calling_user): CallingUser,
+                 Path(user_id): Path<i32>,
+                 Json(role): Json<dto::UserRole>| async move {
+                    let user_service = domain::user::UserService;
+
+                    set_user_role(
+                        user_id,
+                        role,
+                        calling_user,
+                        &caller,
+                        &app_data.ext_cxn,
+                        &user_service,
+                    )
+                    .await
                 },
             ),
         )
         .route(
             "/:user_id/tasks",
             get(
-                |State(app_data): AppState, Path(user_id): Path<i32>| async move {
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>| async move {
                     let task_service = domain::todo::TaskService;
                     let mut external_connectivity = app_data.ext_cxn.clone();
 
-                    get_tasks_for_user(user_id, &mut external_connectivity, &task_service).await
+                    get_tasks_for_user(user_id, &caller, &mut external_connectivity, &task_service)
+                        .await
                 },
             )
             .post(
                 |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
                  Path(user_id): Path<i32>,
+                 CallingUser(calling_user): CallingUser,
                  Json(new_task): Json<dto::NewTask>| async move {
                     let task_service = domain::todo::TaskService;
+
+                    add_task_for_user(
+                        user_id,
+                        new_task,
+                        calling_user,
+                        &caller,
+                        &app_data.ext_cxn,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks.ics",
+            get(
+                |State(app_data): AppState,
+                 Path(user_id): Path<i32>,
+                 Query(params): Query<dto::CalendarFeedParams>| async move {
+                    let feed_service = domain::calendar_feed::CalendarFeedService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::calendar_feed::get_task_calendar(
+                        user_id,
+                        params,
+                        &mut external_connectivity,
+                        &feed_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/feed-token",
+            post(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>| async move {
+                    let feed_service = domain::calendar_feed::CalendarFeedService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::calendar_feed::issue_feed_token(
+                        user_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &feed_service,
+                    )
+                    .await
+                },
+            )
+            .delete(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>| async move {
+                    let feed_service = domain::calendar_feed::CalendarFeedService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::calendar_feed::revoke_feed_token(
+                        user_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &feed_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/digest-preferences",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>| async move {
+                    let digest_service = domain::digest::DigestService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::digest::get_digest_preferences(
+                        user_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &digest_service,
+                    )
+                    .await
+                },
+            )
+            .put(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>,
+                 Json(preferences): Json<dto::DigestPreferences>| async move {
+                    let digest_service = domain::digest::DigestService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::digest::set_digest_preferences(
+                        user_id,
+                        preferences,
+                        &caller,
+                        &mut external_connectivity,
+                        &digest_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/shares",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>| async move {
+                    let share_service = domain::share::ShareService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::share::get_shares(
+                        user_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &share_service,
+                    )
+                    .await
+                },
+            )
+            .post(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>,
+                 Json(new_share): Json<dto::NewShare>| async move {
+                    let share_service = domain::share::ShareService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::share::grant_share(
+                        user_id,
+                        new_share,
+                        &caller,
+                        &mut external_connectivity,
+                        &share_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/shares/:share_id",
+            delete(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, share_id)): Path<(i32, i32)>| async move {
+                    let share_service = domain::share::ShareService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::share::revoke_share(
+                        user_id,
+                        share_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &share_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/shared-tasks",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>| async move {
+                    let share_service = domain::share::ShareService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::share::get_shared_tasks(
+                        user_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &share_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/assigned-tasks",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>| async move {
+                    let task_service = domain::todo::TaskService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    get_assigned_tasks(user_id, &caller, &mut external_connectivity, &task_service)
+                        .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/trash",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>| async move {
+                    let task_service = domain::todo::TaskService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    get_trashed_tasks_for_user(
+                        user_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/search",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>,
+                 Query(params): Query<dto::TaskSearchParams>| async move {
+                    let task_service = domain::todo::TaskService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    search_tasks_for_user(
+                        user_id,
+                        params,
+                        &caller,
+                        &mut external_connectivity,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/ordered",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>| async move {
+                    let task_service = domain::todo::TaskService;
+
+                    super::task_dependency::get_tasks_in_dependency_order(
+                        user_id,
+                        &caller,
+                        &app_data.ext_cxn,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/events",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>,
+                 headers: HeaderMap| async move {
+                    let task_event_service = domain::task_event::TaskEventService;
+                    let notices =
+                        persistence::db_task_event_driven_ports::TaskChangeSubscription::new(
+                            &app_data.task_changes,
+                        );
+                    let last_event_id = headers
+                        .get(LAST_EVENT_ID_HEADER)
+                        .map(|id| id.to_str().unwrap_or_default().to_owned());
+
+                    let events = stream_task_events(
+                        user_id,
+                        last_event_id,
+                        &caller,
+                        app_data.ext_cxn.clone(),
+                        task_event_service,
+                        notices,
+                    )
+                    .await?;
+                    Ok::<_, ErrorResponse>(Sse::new(events).keep_alive(KeepAlive::default()))
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/socket",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>,
+                 CallingUser(calling_user): CallingUser,
+                 upgrade: WebSocketUpgrade| async move {
+                    let task_event_service = domain::task_event::TaskEventService;
+                    let notices =
+                        persistence::db_task_event_driven_ports::TaskChangeSubscription::new(
+                            &app_data.task_changes,
+                        );
                     let mut external_connectivity = app_data.ext_cxn.clone();
 
-                    add_task_for_user(user_id, new_task, &mut external_connectivity, &task_service)
+                    let cursor = super::task_socket::open_task_socket(
+                        user_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &task_event_service,
+                    )
+                    .await?;
+                    Ok::<_, ErrorResponse>(upgrade.on_upgrade(move |socket| async move {
+                        let (outgoing, incoming) = socket.split();
+                        let changes = follow_task_changes(
+                            user_id,
+                            cursor,
+                            external_connectivity,
+                            task_event_service,
+                            notices,
+                        );
+
+                        super::task_socket::run_task_socket(
+                            user_id,
+                            calling_user,
+                            caller,
+                            incoming,
+                            outgoing,
+                            changes,
+                            &app_data.ext_cxn,
+                            &domain::todo::TaskService,
+                        )
                         .await
+                    }))
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/batch",
+            post(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>,
+                 CallingUser(calling_user): CallingUser,
+                 Json(batch): Json<dto::TaskBatch>| async move {
+                    let task_service = domain::todo::TaskService;
+
+                    apply_task_batch(
+                        user_id,
+                        batch,
+                        calling_user,
+                        &caller,
+                        &app_data.ext_cxn,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/export",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>,
+                 Query(params): Query<dto::TaskFileParams>| async move {
+                    let format = params.format.unwrap_or_default();
+
+                    let chunks = super::task_transfer::export_tasks(
+                        user_id,
+                        format,
+                        caller,
+                        app_data.ext_cxn.clone(),
+                        domain::todo::TaskService,
+                    )
+                    .await?;
+                    let disposition = format!(
+                        "attachment; filename=\"tasks.{}\"",
+                        super::task_transfer::file_extension(format)
+                    );
+                    Ok::<_, ErrorResponse>((
+                        [
+                            (
+                                header::CONTENT_TYPE,
+                                super::task_transfer::content_type(format).to_owned(),
+                            ),
+                            (header::CONTENT_DISPOSITION, disposition),
+                        ],
+                        Body::from_stream(chunks),
+                    ))
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/import",
+            post(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(user_id): Path<i32>,
+                 CallingUser(calling_user): CallingUser,
+                 Query(params): Query<dto::TaskFileParams>,
+                 file: Bytes| async move {
+                    let task_service = domain::todo::TaskService;
+
+                    super::task_transfer::import_tasks(
+                        user_id,
+                        params.format.unwrap_or_default(),
+                        &file,
+                        calling_user,
+                        &caller,
+                        &app_data.ext_cxn,
+                        &task_service,
+                    )
+                    .await
                 },
             ),
         )
         .route(
             "/:user_id/tasks/:task_id",
             get(
-                |State(app_data): AppState, Path(path): Path<GetTaskPath>| async move {
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(path): Path<GetTaskPath>| async move {
                     let task_service = domain::todo::TaskService;
                     let mut external_connectivity = app_data.ext_cxn.clone();
 
-                    get_task_for_user(path, &mut external_connectivity, &task_service).await
+                    get_task_for_user(path, &caller, &mut external_connectivity, &task_service)
+                        .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/:task_id/occurrences",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(path): Path<GetTaskPath>,
+                 Query(params): Query<dto::OccurrencePreviewParams>| async move {
+                    let task_service = domain::todo::TaskService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    preview_task_occurrences(
+                        path,
+                        params,
+                        &caller,
+                        &mut external_connectivity,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/:task_id/comments",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id)): Path<(i32, i32)>,
+                 Query(params): Query<dto::CommentPageParams>| async move {
+                    let comment_service = domain::comment::CommentService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::comment::get_comments(
+                        user_id,
+                        task_id,
+                        params,
+                        &caller,
+                        &mut external_connectivity,
+                        &comment_service,
+                    )
+                    .await
+                },
+            )
+            .post(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id)): Path<(i32, i32)>,
+                 Json(new_comment): Json<dto::NewComment>| async move {
+                    let comment_service = domain::comment::CommentService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::comment::add_comment(
+                        user_id,
+                        task_id,
+                        new_comment,
+                        &caller,
+                        &mut external_connectivity,
+                        &comment_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/:task_id/comments/:comment_id",
+            patch(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id, comment_id)): Path<(i32, i32, i32)>,
+                 Json(update): Json<dto::UpdateComment>| async move {
+                    let comment_service = domain::comment::CommentService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::comment::edit_comment(
+                        user_id,
+                        task_id,
+                        comment_id,
+                        update,
+                        &caller,
+                        &mut external_connectivity,
+                        &comment_service,
+                    )
+                    .await
+                },
+            )
+            .delete(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id, comment_id)): Path<(i32, i32, i32)>| async move {
+                    let comment_service = domain::comment::CommentService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::comment::delete_comment(
+                        user_id,
+                        task_id,
+                        comment_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &comment_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/:task_id/dependencies",
+            post(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id)): Path<(i32, i32)>,
+                 Json(new_dependency): Json<dto::NewTaskDependency>| async move {
+                    let task_service = domain::todo::TaskService;
+
+                    super::task_dependency::add_task_dependency(
+                        user_id,
+                        task_id,
+                        new_dependency,
+                        &caller,
+                        &app_data.ext_cxn,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/:task_id/dependencies/:blocked_by_task_id",
+            delete(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id, blocked_by_task_id)): Path<(i32, i32, i32)>| async move {
+                    let task_service = domain::todo::TaskService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::task_dependency::remove_task_dependency(
+                        user_id,
+                        task_id,
+                        blocked_by_task_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &task_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/:task_id/reminders",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id)): Path<(i32, i32)>| async move {
+                    let reminder_service = domain::reminder::ReminderService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::reminder::get_reminders(
+                        user_id,
+                        task_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &reminder_service,
+                    )
+                    .await
+                },
+            )
+            .post(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id)): Path<(i32, i32)>,
+                 Json(new_reminder): Json<dto::NewReminder>| async move {
+                    let reminder_service = domain::reminder::ReminderService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::reminder::add_reminder(
+                        user_id,
+                        task_id,
+                        new_reminder,
+                        &caller,
+                        &mut external_connectivity,
+                        &reminder_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/:task_id/reminders/:reminder_id",
+            delete(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id, reminder_id)): Path<(i32, i32, i32)>| async move {
+                    let reminder_service = domain::reminder::ReminderService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::reminder::delete_reminder(
+                        user_id,
+                        task_id,
+                        reminder_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &reminder_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/:task_id/attachments",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id)): Path<(i32, i32)>| async move {
+                    let attachment_service = domain::attachment::AttachmentService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::attachment::get_attachments(
+                        user_id,
+                        task_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &attachment_service,
+                    )
+                    .await
+                },
+            )
+            .post(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id)): Path<(i32, i32)>,
+                 headers: HeaderMap,
+                 body: Body| async move {
+                    let attachment_service = domain::attachment::AttachmentService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::attachment::upload_attachment(
+                        user_id,
+                        task_id,
+                        &headers,
+                        body,
+                        &app_data.attachment_limits,
+                        &caller,
+                        &mut external_connectivity,
+                        &attachment_service,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route(
+            "/:user_id/tasks/:task_id/attachments/:attachment_id",
+            get(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id, attachment_id)): Path<(i32, i32, i32)>| async move {
+                    let attachment_service = domain::attachment::AttachmentService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::attachment::download_attachment(
+                        user_id,
+                        task_id,
+                        attachment_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &attachment_service,
+                    )
+                    .await
+                },
+            )
+            .delete(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path((user_id, task_id, attachment_id)): Path<(i32, i32, i32)>| async move {
+                    let attachment_service = domain::attachment::AttachmentService;
+                    let mut external_connectivity = app_data.ext_cxn.clone();
+
+                    super::attachment::delete_attachment(
+                        user_id,
+                        task_id,
+                        attachment_id,
+                        &caller,
+                        &mut external_connectivity,
+                        &attachment_service,
+                    )
+                    .await
                 },
             ),
         )
 }
 
-/// Retrieves a list of all the users in the system.
+/// Retrieves a list of the users in the system, optionally filtered by name.
 #[utoipa::path(
     get,
     path = "/users",
     tag = USER_API_GROUP,
+    params(dto::UserListParams),
     responses(
         (status = 200, description = "User list successfully retrieved", body = Vec<TodoUser>),
+        (status = 400, response = dto::err_resps::BasicError400Validation),
+        (status = 403, response = dto::err_resps::BasicError403),
         (status = 500, response = dto::err_resps::BasicError500)
     ),
 )]
 async fn get_users(
+    params: dto::UserListParams,
+    caller: &Caller,
     ext_cxn: &mut impl ExternalConnectivity,
     user_service: &impl domain::user::driving_ports::UserPort,
 ) -> Result<Json<Vec<dto::TodoUser>>, ErrorResponse> {
     info!("Requested users");
+    params.validate().map_err(ValidationErrorResponse::from)?;
+
     let user_reader = persistence::db_user_driven_ports::DbReadUsers;
-    let users_result = user_service.get_users(&mut *ext_cxn, &user_reader).await;
-    if users_result.is_err() {
-        error!(
-            "Could not retrieve users: {}",
-            users_result.as_ref().unwrap_err()
-        );
-    }
+    let users_result = match params.name {
+        Some(name) => {
+            user_service
+                .search_users(caller, &name, &mut *ext_cxn, &user_reader)
+                .await
+        }
+        None => {
+            user_service
+                .get_users(caller, &mut *ext_cxn, &user_reader)
+                .await
+        }
+    };
     let response = users_result
-        .map_err(GenericErrorResponse)?
+        .map_err(|users_err| {
+            error!("Could not retrieve users: {}", users_err);
+            handle_user_err(users_err)
+        })?
         .into_iter()
         .map(dto::TodoUser::from)
         .collect::<Vec<_>>();
@@ -115,15 +913,162 @@ async fn get_users(
     Ok(Json(response))
 }
 
+/// Retrieves a single user along with counts of the tasks they own
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}",
+    tag = USER_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user to look up")
+    ),
+    responses(
+        (status = 200, description = "User successfully retrieved", body = UserDetails),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 404, response = dto::err_resps::BasicError404),
+        (status = 500, response = dto::err_resps::BasicError500)
+    ),
+)]
+async fn get_user(
+    user_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    user_service: &impl domain::user::driving_ports::UserPort,
+) -> Result<Json<dto::UserDetails>, ErrorResponse> {
+    info!("Requested user {user_id}");
+    let user_reader = persistence::db_user_driven_ports::DbReadUsers;
+    let task_reader = persistence::db_todo_driven_ports::DbTaskReader;
+
+    let user_result = user_service
+        .get_user(caller, user_id, &mut *ext_cxn, &user_reader, &task_reader)
+        .await;
+    match user_result {
+        Ok(Some(user)) => Ok(Json(dto::UserDetails::from(user))),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(dto::BasicError {
+                error_code: "no_matching_user".to_owned(),
+                error_description: "Could not find a user matching the given information."
+                    .to_owned(),
+                extra_info: None,
+            }),
+        )
+            .into()),
+        Err(user_err) => {
+            error!("Could not retrieve user {user_id}: {user_err}");
+            Err(handle_user_err(user_err))
+        }
+    }
+}
+
+/// Changes what a user is allowed to do. Only admins may change roles, and the last admin can't be made a
+/// regular user.
+#[utoipa::path(
+    put,
+    path = "/users/{user_id}/role",
+    tag = USER_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose role should change"),
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log"),
+    ),
+    request_body = UserRole,
+    responses(
+        (status = 204, description = "Role successfully changed"),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 404, response = dto::err_resps::BasicError404),
+        (
+            status = 409,
+            description = "The user is the last admin, so they must stay one (error code `last_admin`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "last_admin",
+                "error_description": "The last admin can't stop being an admin.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn set_user_role<TxAble>(
+    user_id: i32,
+    role: dto::UserRole,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    user_service: &impl domain::user::driving_ports::UserPort,
+) -> Result<StatusCode, ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    info!("Setting the role of user {user_id}");
+    let user_reader = persistence::db_user_driven_ports::DbReadUsers;
+    let user_writer = persistence::db_user_driven_ports::DbWriteUsers::new(calling_user);
+    let role = domain::user::Role::from(role.role);
+
+    let role_result = with_transaction(ext_cxn, async |tx_cxn| {
+        user_service
+            .set_user_role(
+                caller,
+                user_id,
+                role,
+                &mut *tx_cxn,
+                &user_reader,
+                &user_writer,
+            )
+            .await
+    })
+    .await;
+    match role_result {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(TxOrSourceError::Source(user_err)) => Err(handle_user_err(user_err)),
+        Err(tx_err) => {
+            error!("Could not set the role of user {user_id}: {tx_err}");
+            Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into())
+        }
+    }
+}
+
+/// Handles [UserError] instances coming from business logic
+fn handle_user_err(err: UserError) -> ErrorResponse {
+    match err {
+        UserError::Forbidden(denied) => ForbiddenResponse(denied).into(),
+        UserError::UserDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            Json(dto::BasicError {
+                error_code: "no_matching_user".to_owned(),
+                error_description: "Could not find a user matching the given information."
+                    .to_owned(),
+                extra_info: None,
+            }),
+        )
+            .into(),
+        UserError::LastAdmin => (
+            StatusCode::CONFLICT,
+            Json(dto::BasicError {
+                error_code: "last_admin".to_owned(),
+                error_description: "The last admin can't stop being an admin.".to_owned(),
+                extra_info: None,
+            }),
+        )
+            .into(),
+        UserError::PortError(err) => GenericErrorResponse(err).into(),
+    }
+}
+
 /// Creates a user.
 #[utoipa::path(
     post,
     path = "/users",
     tag = USER_API_GROUP,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Makes the request safe to retry. The first response for a key is replayed on later requests with the same key."),
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log"),
+    ),
     request_body = NewUser,
     responses(
         (status = 201, description = "User successfully created", body = InsertedUser),
         (status = 400, response = dto::err_resps::BasicError400Validation),
+        (status = 422, response = dto::err_resps::BasicError422IdempotencyKeyReused),
         (
             status = 409,
             description = "User with matching data already exists (error code `user_exists`)", 
@@ -137,33 +1082,43 @@ async fn get_users(
         (status = 500, response = dto::err_resps::BasicError500)
     )
 )]
-async fn create_user(
+async fn create_user<TxAble>(
     new_user: dto::NewUser,
-    ext_cxn: &mut impl ExternalConnectivity,
+    calling_user: Option<i32>,
+    ext_cxn: &TxAble,
     user_service: &impl domain::user::driving_ports::UserPort,
-) -> Result<(StatusCode, Json<dto::InsertedUser>), ErrorResponse> {
+) -> Result<(StatusCode, Json<dto::InsertedUser>), ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
     info!("Attempt to create user: {}", new_user);
     new_user.validate().map_err(ValidationErrorResponse::from)?;
 
     let user_detector = persistence::db_user_driven_ports::DbDetectUser;
-    let user_writer = persistence::db_user_driven_ports::DbWriteUsers;
+    let user_writer = persistence::db_user_driven_ports::DbWriteUsers::new(calling_user);
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
 
     let domain_user_create = domain::user::CreateUser {
         first_name: new_user.first_name,
         last_name: new_user.last_name,
     };
-    let creation_result = user_service
-        .create_user(
-            &domain_user_create,
-            &mut *ext_cxn,
-            &user_writer,
-            &user_detector,
-        )
-        .await;
+    let creation_result = with_transaction(ext_cxn, async |tx_cxn| {
+        user_service
+            .create_user(
+                &domain_user_create,
+                &mut *tx_cxn,
+                &user_writer,
+                &user_detector,
+                &event_outbox,
+            )
+            .await
+    })
+    .await;
     let user_id =
         match creation_result {
             Ok(id) => id,
-            Err(CreateUserError::UserAlreadyExists) => {
+            Err(TxOrSourceError::Source(CreateUserError::UserAlreadyExists)) => {
                 return Err((
                     StatusCode::CONFLICT,
                     Json(dto::BasicError {
@@ -176,29 +1131,89 @@ async fn create_user(
                 )
                     .into())
             }
-            Err(CreateUserError::PortError(err)) => return Err(GenericErrorResponse(err).into()),
+            Err(TxOrSourceError::Source(CreateUserError::PortError(err))) => {
+                return Err(GenericErrorResponse(err).into())
+            }
+            Err(tx_err) => return Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into()),
         };
 
     Ok((StatusCode::CREATED, Json(dto::InsertedUser { id: user_id })))
 }
 
 /// Handles [TaskError] instances coming from business logic
-fn handle_todo_task_err(err: TaskError) -> ErrorResponse {
+pub(super) fn handle_todo_task_err(err: TaskError) -> ErrorResponse {
+    let (status, basic_error) = todo_task_err_to_basic_error(err);
+    (status, Json(basic_error)).into()
+}
+
+/// Converts a [TaskError] into the status code and [dto::BasicError] which describe it
+fn todo_task_err_to_basic_error(err: TaskError) -> (StatusCode, dto::BasicError) {
     match err {
         TaskError::UserDoesNotExist => (
             StatusCode::NOT_FOUND,
-            Json(dto::BasicError {
+            dto::BasicError {
                 error_code: "no_matching_user".to_owned(),
                 error_description: "Could not find a user matching the given information."
                     .to_owned(),
                 extra_info: None,
-            }),
-        )
-            .into(),
+            },
+        ),
+
+        TaskError::TaskDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            dto::BasicError {
+                error_code: "no_matching_task".to_owned(),
+                error_description: "The specified task does not exist.".to_owned(),
+                extra_info: None,
+            },
+        ),
+
+        TaskError::AssigneeDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            dto::BasicError {
+                error_code: "no_matching_assignee".to_owned(),
+                error_description: "The user to assign the task to does not exist.".to_owned(),
+                extra_info: None,
+            },
+        ),
+
+        TaskError::TaskBlocked => (
+            StatusCode::CONFLICT,
+            dto::BasicError {
+                error_code: "task_blocked".to_owned(),
+                error_description:
+                    "The task is blocked by another task which has not been completed yet."
+                        .to_owned(),
+                extra_info: None,
+            },
+        ),
+
+        TaskError::DependencyCycle => (
+            StatusCode::CONFLICT,
+            dto::BasicError {
+                error_code: "dependency_cycle".to_owned(),
+                error_description: "The task would end up blocking itself.".to_owned(),
+                extra_info: None,
+            },
+        ),
+
+        TaskError::DependencyDoesNotExist => (
+            StatusCode::NOT_FOUND,
+            dto::BasicError {
+                error_code: "no_matching_dependency".to_owned(),
+                error_description: "The task is not blocked by the specified task.".to_owned(),
+                extra_info: None,
+            },
+        ),
+
+        TaskError::Forbidden(denied) => (StatusCode::FORBIDDEN, ForbiddenResponse(denied).into()),
 
         TaskError::PortError(err) => {
             error!("Encountered a problem fetching a task: {}", err);
-            GenericErrorResponse(err).into()
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GenericErrorResponse(err).into(),
+            )
         }
     }
 }
@@ -223,11 +1238,13 @@ fn handle_todo_task_err(err: TaskError) -> ErrorResponse {
                 "extra_info": null,
             })
         ),
+        (status = 403, response = dto::err_resps::BasicError403),
         (status = 500, response = dto::err_resps::BasicError500)
     ),
 )]
 async fn get_tasks_for_user(
     user_id: i32,
+    caller: &Caller,
     ext_cxn: &mut impl ExternalConnectivity,
     task_service: &impl domain::todo::driving_ports::TaskPort,
 ) -> Result<Json<Vec<dto::TodoTask>>, ErrorResponse> {
@@ -237,7 +1254,7 @@ async fn get_tasks_for_user(
     let task_read = persistence::db_todo_driven_ports::DbTaskReader;
 
     let tasks_result = task_service
-        .tasks_for_user(user_id, &mut *ext_cxn, &user_detect, &task_read)
+        .tasks_for_user(caller, user_id, &mut *ext_cxn, &user_detect, &task_read)
         .await;
     let tasks: Vec<dto::TodoTask> = match tasks_result {
         Ok(tasks) => tasks.into_iter().map(dto::TodoTask::from).collect(),
@@ -247,6 +1264,100 @@ async fn get_tasks_for_user(
     Ok(Json(tasks))
 }
 
+/// Retrieves the tasks a user has deleted which haven't been purged yet, most recently deleted first
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/trash",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "Which user to look up deleted tasks for")
+    ),
+    responses(
+        (status = 200, description = "Trashed tasks successfully retrieved", body = Vec<TrashedTask>),
+        (
+            status = 404,
+            description = "The requested user does not exist in the system (error code `no_matching_user`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_user",
+                "error_description": "No user exists in the system with the given id",
+                "extra_info": null,
+            })
+        ),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 500, response = dto::err_resps::BasicError500)
+    ),
+)]
+async fn get_trashed_tasks_for_user(
+    user_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> Result<Json<Vec<dto::TrashedTask>>, ErrorResponse> {
+    info!("Get trashed tasks for user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+
+    let trashed_tasks = task_service
+        .trashed_tasks_for_user(caller, user_id, &mut *ext_cxn, &user_detect, &task_read)
+        .await
+        .map_err(handle_todo_task_err)?;
+
+    Ok(Json(
+        trashed_tasks
+            .into_iter()
+            .map(dto::TrashedTask::from)
+            .collect(),
+    ))
+}
+
+/// Retrieves the tasks assigned to a user, including tasks created by other users
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/assigned-tasks",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "Which user to look up assigned tasks for")
+    ),
+    responses(
+        (status = 200, description = "Assigned tasks successfully retrieved", body = Vec<TodoTask>),
+        (
+            status = 404,
+            description = "The requested user does not exist in the system (error code `no_matching_user`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_user",
+                "error_description": "No user exists in the system with the given id",
+                "extra_info": null,
+            })
+        ),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 500, response = dto::err_resps::BasicError500)
+    ),
+)]
+async fn get_assigned_tasks(
+    user_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> Result<Json<Vec<dto::TodoTask>>, ErrorResponse> {
+    info!("Get tasks assigned to user {user_id}");
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+
+    let assigned_tasks = task_service
+        .tasks_assigned_to(caller, user_id, &mut *ext_cxn, &user_detect, &task_read)
+        .await
+        .map_err(handle_todo_task_err)?;
+
+    Ok(Json(
+        assigned_tasks
+            .into_iter()
+            .map(dto::TodoTask::from)
+            .collect(),
+    ))
+}
+
 /// Captures path variables from the "get task" endpoint
 #[derive(Deserialize)]
 struct GetTaskPath {
@@ -254,7 +1365,7 @@ struct GetTaskPath {
     task_id: i32,
 }
 
-/// Retrieves a specific task owned by a user
+/// Retrieves a specific task owned by a user, or which another user has shared with them
 #[utoipa::path(
     get,
     path = "/users/{user_id}/tasks/{task_id}",
@@ -289,11 +1400,13 @@ struct GetTaskPath {
                 ))
             )
         ),
+        (status = 403, response = dto::err_resps::BasicError403),
         (status = 500, response = dto::err_resps::BasicError500),
     )
 )]
 async fn get_task_for_user(
     path: GetTaskPath,
+    caller: &Caller,
     ext_cxn: &mut impl ExternalConnectivity,
     task_service: &impl domain::todo::driving_ports::TaskPort,
 ) -> Result<Json<dto::TodoTask>, ErrorResponse> {
@@ -304,6 +1417,7 @@ async fn get_task_for_user(
 
     let task_result = task_service
         .user_task_by_id(
+            caller,
             path.user_id,
             path.task_id,
             &mut *ext_cxn,
@@ -330,21 +1444,309 @@ async fn get_task_for_user(
     Ok(Json(dto::TodoTask::from(task)))
 }
 
+/// Lists the upcoming due dates of a user's task, starting with its current due date. A task which doesn't
+/// recur has at most one.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/{task_id}/occurrences",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user who owns the task"),
+        ("task_id" = i32, Path, description = "The task to list the occurrences of"),
+        dto::OccurrencePreviewParams,
+    ),
+    responses(
+        (status = 200, description = "Upcoming due dates, earliest first", body = Vec<NaiveDate>, example = json!(["2024-03-04", "2024-03-11"])),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 400, response = dto::err_resps::BasicError400Validation),
+        (
+            status = 404,
+            description = "Specified user or task does not exist (error code `no_matching_user` or `no_matching_task`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_task",
+                "error_description": "The specified task does not exist.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn preview_task_occurrences(
+    path: GetTaskPath,
+    params: dto::OccurrencePreviewParams,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> Result<Json<Vec<NaiveDate>>, ErrorResponse> {
+    info!(
+        "Previewing occurrences of task {} for user {}",
+        path.task_id, path.user_id
+    );
+    params.validate().map_err(ValidationErrorResponse::from)?;
+
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+
+    let occurrences = task_service
+        .upcoming_occurrences(
+            caller,
+            path.user_id,
+            path.task_id,
+            params.count.unwrap_or(dto::DEFAULT_PREVIEWED_OCCURRENCES),
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+        )
+        .await
+        .map_err(handle_todo_task_err)?;
+
+    Ok(Json(occurrences))
+}
+
+/// Searches the descriptions of a user's tasks, returning the best matches first
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/search",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose tasks should be searched"),
+        dto::TaskSearchParams,
+    ),
+    responses(
+        (status = 200, description = "Matching tasks, most relevant first", body = Vec<TaskSearchResult>),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 400, response = dto::err_resps::BasicError400Validation),
+        (
+            status = 404,
+            description = "The requested user does not exist in the system (error code `no_matching_user`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_user",
+                "error_description": "No user exists in the system with the given id",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn search_tasks_for_user(
+    user_id: i32,
+    params: dto::TaskSearchParams,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> Result<Json<Vec<dto::TaskSearchResult>>, ErrorResponse> {
+    info!("Searching tasks for user {user_id}");
+    params.validate().map_err(ValidationErrorResponse::from)?;
+
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+
+    let search_result = task_service
+        .search_tasks(
+            caller,
+            user_id,
+            &params.q,
+            &mut *ext_cxn,
+            &user_detect,
+            &task_read,
+        )
+        .await;
+    let matches: Vec<dto::TaskSearchResult> = match search_result {
+        Ok(matches) => matches
+            .into_iter()
+            .map(dto::TaskSearchResult::from)
+            .collect(),
+        Err(domain_err) => return Err(handle_todo_task_err(domain_err)),
+    };
+
+    Ok(Json(matches))
+}
+
+/// The header a reconnecting event stream client sends containing the ID of the last event it received
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+/// Streams changes to a user's tasks as Server-Sent Events while they happen. Each event's type is the kind
+/// of change (`task.created`, `task.updated`, `task.completed` or `task.deleted`). Clients which reconnect with
+/// the `Last-Event-ID` header receive the changes they missed before any new ones.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/tasks/events",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose task changes should be streamed"),
+        ("Last-Event-ID" = Option<i64>, Header, description = "Resume the stream after the event with this ID. Changes are kept for a day."),
+    ),
+    responses(
+        (status = 200, description = "A stream of task changes", content_type = "text/event-stream", body = TaskChange),
+        (
+            status = 400,
+            description = "The `Last-Event-ID` header is not an event ID (error code `invalid_last_event_id`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "invalid_last_event_id",
+                "error_description": "The Last-Event-ID header must contain the ID of an event from this stream.",
+                "extra_info": null,
+            })
+        ),
+        (
+            status = 404,
+            description = "The requested user does not exist in the system (error code `no_matching_user`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_user",
+                "error_description": "No user exists in the system with the given id",
+                "extra_info": null,
+            })
+        ),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn stream_task_events(
+    user_id: i32,
+    last_event_id: Option<String>,
+    caller: &Caller,
+    mut ext_cxn: impl ExternalConnectivity,
+    task_event_service: impl domain::task_event::driving_ports::TaskEventPort,
+    notices: impl domain::task_event::driven_ports::TaskChangeNotices,
+) -> Result<impl Stream<Item = Result<Event, axum::Error>>, ErrorResponse> {
+    info!("Streaming task events for user {user_id}");
+    domain::access::authorize(
+        caller,
+        Action::ManageTasks {
+            owner_user_id: user_id,
+        },
+    )
+    .map_err(ForbiddenResponse)?;
+    let last_seen_id = match last_event_id {
+        None => None,
+        Some(id) => Some(id.parse::<i64>().map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(dto::BasicError {
+                    error_code: "invalid_last_event_id".to_owned(),
+                    error_description:
+                        "The Last-Event-ID header must contain the ID of an event from this stream."
+                            .to_owned(),
+                    extra_info: None,
+                }),
+            )
+        })?),
+    };
+
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let change_log = persistence::db_task_event_driven_ports::DbTaskChangeLog;
+    let cursor = task_event_service
+        .start_following(
+            user_id,
+            last_seen_id,
+            &mut ext_cxn,
+            &user_detect,
+            &change_log,
+        )
+        .await
+        .map_err(handle_todo_task_err)?;
+
+    let events =
+        follow_task_changes(user_id, cursor, ext_cxn, task_event_service, notices).map(|change| {
+            Event::default()
+                .id(change.id.to_string())
+                .event(change.kind.name())
+                .json_data(dto::TaskChange::from(change))
+        });
+
+    Ok(events)
+}
+
+/// Produces every change made to a user's tasks after the change with ID `cursor`, waiting for new changes
+/// once it has caught up. The stream ends if changes can no longer be retrieved.
+pub(super) fn follow_task_changes(
+    user_id: i32,
+    cursor: i64,
+    ext_cxn: impl ExternalConnectivity,
+    task_event_service: impl domain::task_event::driving_ports::TaskEventPort,
+    notices: impl domain::task_event::driven_ports::TaskChangeNotices,
+) -> impl Stream<Item = domain::task_event::TaskChange> {
+    let stream_state = (
+        cursor,
+        VecDeque::new(),
+        ext_cxn,
+        task_event_service,
+        notices,
+    );
+
+    stream::unfold(
+        stream_state,
+        move |(mut cursor, mut pending, mut ext_cxn, task_event_service, mut notices)| async move {
+            let change_log = persistence::db_task_event_driven_ports::DbTaskChangeLog;
+            while pending.is_empty() {
+                let next_changes = task_event_service
+                    .next_changes(user_id, cursor, &mut ext_cxn, &change_log, &mut notices)
+                    .await;
+                match next_changes {
+                    Ok(changes) => pending.extend(changes),
+                    Err(err) => {
+                        error!("Stopped following task changes for user {user_id}: {err}");
+                        return None;
+                    }
+                }
+            }
+
+            let change: domain::task_event::TaskChange = pending.pop_front()?;
+            cursor = change.id;
+
+            Some((
+                change,
+                (cursor, pending, ext_cxn, task_event_service, notices),
+            ))
+        },
+    )
+}
+
+/// Periodically removes task changes which are too old for event streams to resume from
+pub async fn purge_task_changes(
+    mut ext_cxn: impl ExternalConnectivity,
+    retention: Duration,
+    purge_interval: Duration,
+) {
+    let task_event_service = domain::task_event::TaskEventService;
+    let change_log = persistence::db_task_event_driven_ports::DbTaskChangeLog;
+    let mut interval = tokio::time::interval(purge_interval);
+
+    loop {
+        interval.tick().await;
+        match task_event_service
+            .purge_old_changes(retention, &mut ext_cxn, &change_log)
+            .await
+        {
+            Ok(purged) => info!("Purged {purged} old task changes"),
+            Err(purge_err) => error!("Failed to purge old task changes: {purge_err}"),
+        }
+    }
+}
+
 /// Adds a new task for a user
 #[utoipa::path(
     post,
     path = "/users/{user_id}/tasks",
     tag = super::todo::TASK_API_GROUP,
     params(
-        ("user_id" = i32, Path, description = "The user to add a task for")
+        ("user_id" = i32, Path, description = "The user to add a task for"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Makes the request safe to retry. The first response for a key is replayed on later requests with the same key."),
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log"),
     ),
     request_body = NewTask,
     responses(
         (status = 201, description = "Task successfully created", body = InsertedTask),
+        (status = 403, response = dto::err_resps::BasicError403),
         (status = 400, response = dto::err_resps::BasicError400Validation),
+        (status = 422, response = dto::err_resps::BasicError422IdempotencyKeyReused),
+        (status = 429, response = dto::err_resps::BasicError429),
         (
             status = 404,
-            description = "Specified user does not exist (error code `no_matching_user`)",
+            description = "Specified user or assignee does not exist (error code `no_matching_user` or `no_matching_assignee`)",
             body = BasicError,
             example = json!({
                 "error_code": "no_matching_user",
@@ -355,37 +1757,336 @@ async fn get_task_for_user(
         (status = 500, response = dto::err_resps::BasicError500),
     ),
 )]
-async fn add_task_for_user(
+async fn add_task_for_user<TxAble>(
+    user_id: i32,
+    new_task: dto::NewTask,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> Result<(StatusCode, Json<dto::InsertedTask>), ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    info!("Adding task for user {user_id}");
+    new_task.validate().map_err(ValidationErrorResponse::from)?;
+
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_write = persistence::db_todo_driven_ports::DbTaskWriter::new(calling_user);
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+    let domain_new_task = domain::todo::NewTask::from(new_task);
+
+    let inserted_task_result = with_transaction(ext_cxn, async |tx_cxn| {
+        task_service
+            .create_task_for_user(
+                caller,
+                user_id,
+                &domain_new_task,
+                &mut *tx_cxn,
+                &user_detect,
+                &task_write,
+                &event_outbox,
+            )
+            .await
+    })
+    .await;
+    let new_task_id = match inserted_task_result {
+        Ok(id) => id,
+        Err(TxOrSourceError::Source(domain_error)) => {
+            return Err(handle_todo_task_err(domain_error))
+        }
+        Err(tx_err) => return Err(GenericErrorResponse(anyhow!(tx_err.to_string())).into()),
+    };
+
+    Ok((
+        StatusCode::CREATED,
+        Json(dto::InsertedTask { id: new_task_id }),
+    ))
+}
+
+/// Applies several task operations for a user in a single request
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/tasks/batch",
+    tag = super::todo::TASK_API_GROUP,
+    params(
+        ("user_id" = i32, Path, description = "The user whose tasks the operations apply to"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Makes the request safe to retry. The first response for a key is replayed on later requests with the same key."),
+        ("X-User-Id" = Option<i32>, Header, description = "The ID of the user making the request, recorded in the audit log"),
+    ),
+    request_body = TaskBatch,
+    responses(
+        (
+            status = 200,
+            description = "The batch was processed. Check `committed` and the result of each operation to see what was saved.",
+            body = TaskBatchResult,
+            example = json!({
+                "committed": false,
+                "results": [
+                    { "status": "rolled_back" },
+                    {
+                        "status": "failed",
+                        "error": {
+                            "error_code": "no_matching_task",
+                            "error_description": "The specified task does not exist.",
+                            "extra_info": null,
+                        }
+                    },
+                    { "status": "skipped" },
+                ]
+            })
+        ),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 400, response = dto::err_resps::BasicError400Validation),
+        (status = 422, response = dto::err_resps::BasicError422IdempotencyKeyReused),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn apply_task_batch<TxAble>(
+    user_id: i32,
+    batch: dto::TaskBatch,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> Result<Json<dto::TaskBatchResult>, ErrorResponse>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    info!(
+        "Applying a batch of {} task operations for user {user_id}",
+        batch.operations.len()
+    );
+    batch.validate().map_err(ValidationErrorResponse::from)?;
+
+    let operations: Vec<Result<domain::todo::BatchOperation, dto::BasicError>> = batch
+        .operations
+        .into_iter()
+        .map(|operation| {
+            operation
+                .validate()
+                .map_err(|err| dto::BasicError::from(ValidationErrorResponse::from(err)))?;
+            Ok(domain::todo::BatchOperation::from(operation))
+        })
+        .collect();
+
+    let batch_result = match batch.mode {
+        dto::BatchMode::Atomic => apply_atomic_batch(
+            user_id,
+            operations,
+            calling_user,
+            caller,
+            ext_cxn,
+            task_service,
+        )
+        .await
+        .map_err(|err| {
+            error!("Could not apply a task batch: {err}");
+            GenericErrorResponse(err)
+        })?,
+        dto::BatchMode::BestEffort => {
+            apply_best_effort_batch(
+                user_id,
+                operations,
+                calling_user,
+                caller,
+                ext_cxn,
+                task_service,
+            )
+            .await
+        }
+    };
+
+    Ok(Json(batch_result))
+}
+
+/// Returned from an atomic batch's transaction to roll it back when one of its operations fails
+#[derive(Debug, Error)]
+#[error("Batch operation {failed_index} failed: {}", .error.error_description)]
+struct BatchOperationFailed {
+    failed_index: usize,
+    error: dto::BasicError,
+}
+
+/// Applies all operations in a batch inside one transaction, which is only committed if every operation succeeds.
+/// If any operation is invalid, nothing is attempted.
+async fn apply_atomic_batch<TxAble>(
+    user_id: i32,
+    operations: Vec<Result<domain::todo::BatchOperation, dto::BasicError>>,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> Result<dto::TaskBatchResult, anyhow::Error>
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    if operations.iter().any(Result::is_err) {
+        let results = operations
+            .into_iter()
+            .map(|operation| match operation {
+                Ok(_) => dto::TaskBatchOperationResult::Skipped,
+                Err(error) => dto::TaskBatchOperationResult::Failed { error },
+            })
+            .collect();
+
+        return Ok(dto::TaskBatchResult {
+            committed: false,
+            results,
+        });
+    }
+
+    let user_detect = persistence::db_user_driven_ports::DbDetectUser;
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let task_write = persistence::db_todo_driven_ports::DbTaskWriter::new(calling_user);
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+
+    let operations: Vec<domain::todo::BatchOperation> = operations.into_iter().flatten().collect();
+    let tx_result = with_transaction(ext_cxn, async |tx_cxn| {
+        let mut task_ids = Vec::with_capacity(operations.len());
+        for (idx, operation) in operations.iter().enumerate() {
+            let task_id = task_service
+                .apply_batch_operation(
+                    caller,
+                    user_id,
+                    operation,
+                    &mut *tx_cxn,
+                    &user_detect,
+                    &task_read,
+                    &task_write,
+                    &event_outbox,
+                )
+                .await
+                .map_err(|task_err| BatchOperationFailed {
+                    failed_index: idx,
+                    error: todo_task_err_to_basic_error(task_err).1,
+                })?;
+            task_ids.push(task_id);
+        }
+
+        Ok(task_ids)
+    })
+    .await;
+
+    match tx_result {
+        Ok(task_ids) => Ok(dto::TaskBatchResult {
+            committed: true,
+            results: task_ids
+                .into_iter()
+                .map(|task_id| dto::TaskBatchOperationResult::Succeeded { task_id })
+                .collect(),
+        }),
+        Err(TxOrSourceError::Source(BatchOperationFailed {
+            failed_index,
+            error,
+        })) => {
+            let mut results: Vec<dto::TaskBatchOperationResult> = (0..failed_index)
+                .map(|_| dto::TaskBatchOperationResult::RolledBack)
+                .collect();
+            results.push(dto::TaskBatchOperationResult::Failed { error });
+            results.extend(
+                (failed_index + 1..operations.len())
+                    .map(|_| dto::TaskBatchOperationResult::Skipped),
+            );
+
+            Ok(dto::TaskBatchResult {
+                committed: false,
+                results,
+            })
+        }
+        Err(tx_err) => Err(anyhow!(tx_err.to_string())),
+    }
+}
+
+/// Applies a single task operation for a user inside its own transaction
+pub(super) async fn apply_task_operation<TxAble>(
     user_id: i32,
-    new_task: dto::NewTask,
-    ext_cxn: &mut impl ExternalConnectivity,
+    operation: &domain::todo::BatchOperation,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
     task_service: &impl domain::todo::driving_ports::TaskPort,
-) -> Result<(StatusCode, Json<dto::InsertedTask>), ErrorResponse> {
-    info!("Adding task for user {user_id}");
-    new_task.validate().map_err(ValidationErrorResponse::from)?;
-
+) -> dto::TaskBatchOperationResult
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
     let user_detect = persistence::db_user_driven_ports::DbDetectUser;
-    let task_write = persistence::db_todo_driven_ports::DbTaskWriter;
-    let domain_new_task = domain::todo::NewTask::from(new_task);
+    let task_read = persistence::db_todo_driven_ports::DbTaskReader;
+    let task_write = persistence::db_todo_driven_ports::DbTaskWriter::new(calling_user);
+    let event_outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+
+    let tx_result = with_transaction(ext_cxn, async |tx_cxn| {
+        task_service
+            .apply_batch_operation(
+                caller,
+                user_id,
+                operation,
+                &mut *tx_cxn,
+                &user_detect,
+                &task_read,
+                &task_write,
+                &event_outbox,
+            )
+            .await
+    })
+    .await;
+    match tx_result {
+        Ok(task_id) => dto::TaskBatchOperationResult::Succeeded { task_id },
+        Err(TxOrSourceError::Source(task_err)) => dto::TaskBatchOperationResult::Failed {
+            error: todo_task_err_to_basic_error(task_err).1,
+        },
+        Err(tx_err) => {
+            error!("Could not apply a task operation: {tx_err}");
+            dto::TaskBatchOperationResult::Failed {
+                error: GenericErrorResponse(anyhow!(tx_err.to_string())).into(),
+            }
+        }
+    }
+}
+
+/// Applies each operation in a batch inside its own transaction, so failing operations do not affect the others
+async fn apply_best_effort_batch<TxAble>(
+    user_id: i32,
+    operations: Vec<Result<domain::todo::BatchOperation, dto::BasicError>>,
+    calling_user: Option<i32>,
+    caller: &Caller,
+    ext_cxn: &TxAble,
+    task_service: &impl domain::todo::driving_ports::TaskPort,
+) -> dto::TaskBatchResult
+where
+    TxAble: Transactable,
+    for<'handle> TxAble::Handle<'handle>: ExternalConnectivity,
+{
+    let mut results = Vec::with_capacity(operations.len());
+    for operation in operations {
+        let operation = match operation {
+            Ok(op) => op,
+            Err(error) => {
+                results.push(dto::TaskBatchOperationResult::Failed { error });
+                continue;
+            }
+        };
 
-    let inserted_task_result = task_service
-        .create_task_for_user(
+        let operation_result = apply_task_operation(
             user_id,
-            &domain_new_task,
-            &mut *ext_cxn,
-            &user_detect,
-            &task_write,
+            &operation,
+            calling_user,
+            caller,
+            ext_cxn,
+            task_service,
         )
         .await;
-    let new_task_id = match inserted_task_result {
-        Ok(id) => id,
-        Err(domain_error) => return Err(handle_todo_task_err(domain_error)),
-    };
+        results.push(operation_result);
+    }
 
-    Ok((
-        StatusCode::CREATED,
-        Json(dto::InsertedTask { id: new_task_id }),
-    ))
+    let committed = results
+        .iter()
+        .any(|result| matches!(result, dto::TaskBatchOperationResult::Succeeded { .. }));
+    dto::TaskBatchResult { committed, results }
 }
 
 #[cfg(test)]
@@ -405,21 +2106,29 @@ mod tests {
         async fn happy_path() {
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let user_port = domain::user::test_util::MockUserService::build_locked(|svc| {
-                svc.get_users_response.set_returned_anyhow(Ok(vec![
+                svc.get_users_response.set_returned_result(Ok(vec![
                     domain::user::TodoUser {
                         id: 1,
                         first_name: "John".to_owned(),
                         last_name: "Doe".to_owned(),
+                        role: domain::user::Role::User,
                     },
                     domain::user::TodoUser {
                         id: 2,
                         first_name: "Jane".to_owned(),
                         last_name: "Doe".to_owned(),
+                        role: domain::user::Role::User,
                     },
                 ]));
             });
 
-            let endpoint_result = get_users(&mut ext_cxn, &user_port).await;
+            let endpoint_result = get_users(
+                dto::UserListParams::default(),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &user_port,
+            )
+            .await;
             assert_that!(endpoint_result)
                 .is_ok()
                 .matches(|Json(user_list)| {
@@ -428,11 +2137,13 @@ mod tests {
                             id: 1,
                             first_name: f1,
                             last_name: l1,
+                            ..
                         },
                         dto::TodoUser {
                             id: 2,
                             first_name: f2,
                             last_name: l2,
+                            ..
                         }
                     ] if f1 == "John" &&
                          f2 == "Jane" &&
@@ -448,11 +2159,17 @@ mod tests {
             let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
                 // Configure what the service will return
                 svc.get_users_response
-                    .set_returned_anyhow(Err(anyhow!("Whoopsy daisy")));
+                    .set_returned_result(Err(UserError::PortError(anyhow!("Whoopsy daisy"))));
             });
 
             // Execute endpoint, get response
-            let response_result = get_users(&mut ext_cxn, &user_service).await;
+            let response_result = get_users(
+                dto::UserListParams::default(),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &user_service,
+            )
+            .await;
             let (req_parts, response_body) = response_result.into_response().into_parts();
 
             // Verify status code
@@ -463,6 +2180,164 @@ mod tests {
             // Verify error code is correct
             assert_eq!("internal_error", deserialized_body.error_code);
         }
+
+        #[tokio::test]
+        async fn returns_403_when_caller_is_not_allowed() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
+                svc.get_users_response
+                    .set_returned_result(Err(UserError::Forbidden(domain::access::AccessDenied {
+                        action: Action::ListUsers,
+                    })));
+            });
+
+            let response_result = get_users(
+                dto::UserListParams::default(),
+                &Caller::Anonymous,
+                &mut ext_cxn,
+                &user_service,
+            )
+            .await;
+            let (req_parts, response_body) = response_result.into_response().into_parts();
+            assert_eq!(StatusCode::FORBIDDEN, req_parts.status);
+
+            let deserialized_body: dto::BasicError = deserialize_body(response_body).await;
+            assert_eq!("forbidden", deserialized_body.error_code);
+        }
+    }
+
+    mod get_user {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
+                svc.get_user_response.set_returned_result(Ok(Some(
+                    domain::user::UserWithTaskSummary {
+                        user: domain::user::TodoUser {
+                            id: 4,
+                            first_name: "John".to_owned(),
+                            last_name: "Doe".to_owned(),
+                            role: domain::user::Role::User,
+                        },
+                        tasks: domain::todo::TaskSummary {
+                            total: 12,
+                            completed: 5,
+                            overdue: 2,
+                        },
+                    },
+                )));
+            });
+
+            let Json(user) = get_user(4, &Caller::Trusted, &mut ext_cxn, &user_service)
+                .await
+                .unwrap_or_else(|err| {
+                    panic!("Didn't get the expected response! Error: {:#?}", err);
+                });
+
+            assert!(matches!(user, dto::UserDetails {
+                id: 4,
+                first_name,
+                task_summary: dto::TaskSummary {
+                    total: 12,
+                    completed: 5,
+                    overdue: 2,
+                },
+                ..
+            } if first_name == "John"));
+        }
+
+        #[tokio::test]
+        async fn returns_404_on_user_not_found() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
+                svc.get_user_response.set_returned_result(Ok(None));
+            });
+
+            let response = get_user(4, &Caller::Trusted, &mut ext_cxn, &user_service)
+                .await
+                .into_response();
+            let (parts, body) = response.into_parts();
+
+            assert_eq!(StatusCode::NOT_FOUND, parts.status);
+
+            let body: dto::BasicError = deserialize_body(body).await;
+            assert_eq!("no_matching_user", body.error_code);
+        }
+
+        #[tokio::test]
+        async fn returns_500_when_service_blows_up() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
+                svc.get_user_response
+                    .set_returned_result(Err(UserError::PortError(anyhow!("Whoopsy daisy"))));
+            });
+
+            let response = get_user(4, &Caller::Trusted, &mut ext_cxn, &user_service)
+                .await
+                .into_response();
+            let (parts, body) = response.into_parts();
+
+            assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, parts.status);
+
+            let body: dto::BasicError = deserialize_body(body).await;
+            assert_eq!("internal_error", body.error_code);
+        }
+    }
+
+    mod search_users {
+        use super::*;
+
+        #[tokio::test]
+        async fn searches_when_name_given() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
+                svc.search_users_response
+                    .set_returned_result(Ok(vec![domain::user::TodoUser {
+                        id: 1,
+                        first_name: "John".to_owned(),
+                        last_name: "Doe".to_owned(),
+                        role: domain::user::Role::User,
+                    }]));
+            });
+            let params = dto::UserListParams {
+                name: Some("jo".to_owned()),
+            };
+
+            let Json(users) = get_users(params, &Caller::Trusted, &mut ext_cxn, &user_service)
+                .await
+                .unwrap_or_else(|err| {
+                    panic!("Didn't get the expected response! Error: {:#?}", err);
+                });
+
+            assert!(matches!(users.as_slice(), [dto::TodoUser { id: 1, .. }]));
+
+            let locked_service = user_service.lock().expect("Lock is poisoned!");
+            assert!(matches!(
+                locked_service.search_users_response.calls(),
+                [name] if name == "jo"
+            ));
+        }
+
+        #[tokio::test]
+        async fn rejects_overly_long_name() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let user_service = domain::user::test_util::MockUserService::build_locked(|_| {});
+            let params = dto::UserListParams {
+                name: Some("a".repeat(300)),
+            };
+
+            let response = get_users(params, &Caller::Trusted, &mut ext_cxn, &user_service)
+                .await
+                .into_response();
+            let (parts, body) = response.into_parts();
+
+            assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+
+            let body: dto::BasicError = deserialize_body(body).await;
+            assert_eq!("invalid_input", body.error_code);
+        }
     }
 
     mod create_user {
@@ -479,12 +2354,12 @@ mod tests {
         async fn happy_path() {
             let user = create_user_payload();
 
-            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
                 svc.create_user_response.set_returned_result(Ok(10));
             });
 
-            let create_user_result = create_user(user, &mut ext_cxn, &user_service).await;
+            let create_user_result = create_user(user, Some(1), &ext_cxn, &user_service).await;
             let Ok((status, Json(inserted_user))) = create_user_result else {
                 panic!(
                     "Could not read response from router: {:#?}",
@@ -500,13 +2375,13 @@ mod tests {
         async fn responds_409_on_already_existing_user() {
             let user = create_user_payload();
 
-            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
                 svc.create_user_response
                     .set_returned_result(Err(CreateUserError::UserAlreadyExists));
             });
 
-            let response = create_user(user, &mut ext_cxn, &user_service)
+            let response = create_user(user, Some(1), &ext_cxn, &user_service)
                 .await
                 .into_response();
             let (resp_parts, resp_body) = response.into_parts();
@@ -521,7 +2396,7 @@ mod tests {
         async fn responds_500_on_port_error() {
             let payload = create_user_payload();
 
-            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
                 svc.create_user_response
                     .set_returned_result(Err(CreateUserError::PortError(anyhow!(
@@ -529,7 +2404,7 @@ mod tests {
                     ))));
             });
 
-            let response = create_user(payload, &mut ext_cxn, &user_service)
+            let response = create_user(payload, Some(1), &ext_cxn, &user_service)
                 .await
                 .into_response();
             let (resp_parts, resp_body) = response.into_parts();
@@ -541,6 +2416,93 @@ mod tests {
         }
     }
 
+    mod set_user_role {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
+                svc.set_user_role_response.set_returned_result(Ok(()));
+            });
+
+            let role_result = set_user_role(
+                3,
+                dto::UserRole {
+                    role: dto::Role::Admin,
+                },
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &user_service,
+            )
+            .await;
+            assert!(matches!(role_result, Ok(StatusCode::NO_CONTENT)));
+            assert_that!(ext_cxn.did_transaction_commit()).is_true();
+
+            let locked_service = user_service.lock().expect("Lock is poisoned!");
+            assert_that!(locked_service.set_user_role_response.calls())
+                .is_equal_to([(3, domain::user::Role::Admin)].as_slice());
+        }
+
+        #[tokio::test]
+        async fn returns_409_when_demoting_last_admin() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
+                svc.set_user_role_response
+                    .set_returned_result(Err(UserError::LastAdmin));
+            });
+
+            let role_result = set_user_role(
+                1,
+                dto::UserRole {
+                    role: dto::Role::User,
+                },
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &user_service,
+            )
+            .await;
+            let (req_parts, response_body) = role_result.into_response().into_parts();
+            assert_eq!(StatusCode::CONFLICT, req_parts.status);
+
+            let deserialized_body: dto::BasicError = deserialize_body(response_body).await;
+            assert_eq!("last_admin", deserialized_body.error_code);
+        }
+
+        #[tokio::test]
+        async fn returns_403_when_caller_is_not_admin() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let user_service = domain::user::test_util::MockUserService::build_locked(|svc| {
+                svc.set_user_role_response
+                    .set_returned_result(Err(UserError::Forbidden(domain::access::AccessDenied {
+                        action: Action::ChangeRoles,
+                    })));
+            });
+
+            let role_result = set_user_role(
+                2,
+                dto::UserRole {
+                    role: dto::Role::Admin,
+                },
+                Some(2),
+                &Caller::User {
+                    user_id: 2,
+                    role: domain::user::Role::User,
+                },
+                &ext_cxn,
+                &user_service,
+            )
+            .await;
+            let (req_parts, response_body) = role_result.into_response().into_parts();
+            assert_eq!(StatusCode::FORBIDDEN, req_parts.status);
+
+            let deserialized_body: dto::BasicError = deserialize_body(response_body).await;
+            assert_eq!("forbidden", deserialized_body.error_code);
+        }
+    }
+
     mod handle_todo_task_err {
         use super::*;
 
@@ -550,84 +2512,232 @@ mod tests {
                 Err::<(), _>(handle_todo_task_err(TaskError::UserDoesNotExist)).into_response();
             let (res_parts, res_body) = produced_response.into_parts();
 
-            assert_eq!(StatusCode::NOT_FOUND, res_parts.status);
+            assert_eq!(StatusCode::NOT_FOUND, res_parts.status);
+
+            let deserialized_body: dto::BasicError = deserialize_body(res_body).await;
+            assert_eq!("no_matching_user", deserialized_body.error_code);
+        }
+
+        #[tokio::test]
+        async fn converts_port_error_to_500() {
+            let produced_response = Err::<(), _>(handle_todo_task_err(TaskError::PortError(
+                anyhow!("Whoopsie daisy"),
+            )))
+            .into_response();
+            let (res_parts, res_body) = produced_response.into_parts();
+
+            assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, res_parts.status);
+
+            let deserialized_body: dto::BasicError = deserialize_body(res_body).await;
+            assert_eq!("internal_error", deserialized_body.error_code);
+        }
+    }
+
+    mod get_tasks_for_user {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.tasks_for_user_result.set_returned_result(Ok(vec![
+                    domain::todo::TodoTask {
+                        id: 3,
+                        owner_user_id: 2,
+                        item_desc: "Something to do".to_owned(),
+                        completed: false,
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                        comment_count: 0,
+                        blocked: false,
+                    },
+                    domain::todo::TodoTask {
+                        id: 10,
+                        owner_user_id: 2,
+                        item_desc: "Another thing to do".to_owned(),
+                        completed: true,
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                        comment_count: 0,
+                        blocked: false,
+                    },
+                ]));
+            });
+
+            let Json(tasks) = get_tasks_for_user(2, &Caller::Trusted, &mut ext_cxn, &task_service)
+                .await
+                .unwrap_or_else(|err| {
+                    panic!("Didn't get the expected response! Error: {:#?}", err);
+                });
+
+            assert!(matches!(tasks.as_slice(), [
+                dto::TodoTask{
+                    id: 3,
+                    description: d1,
+                    completed: false,
+                    due_date: None,
+                    recurrence: None,
+                    assignee_user_id: None,
+                    comment_count: 0,
+                    blocked: false,
+                },
+                dto::TodoTask {
+                    id: 10,
+                    description: d2,
+                    completed: true,
+                    due_date: None,
+                    recurrence: None,
+                    assignee_user_id: None,
+                    comment_count: 0,
+                    blocked: false,
+                }
+            ] if d1 == "Something to do" &&
+                 d2 == "Another thing to do"
+            ))
+        }
+
+        #[tokio::test]
+        async fn returns_404_on_user_not_found() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.tasks_for_user_result
+                    .set_returned_result(Err(TaskError::UserDoesNotExist));
+            });
+
+            let response = get_tasks_for_user(2, &Caller::Trusted, &mut ext_cxn, &task_service)
+                .await
+                .into_response();
+            let (parts, body) = response.into_parts();
+
+            assert_eq!(StatusCode::NOT_FOUND, parts.status);
+
+            let body: dto::BasicError = deserialize_body(body).await;
+            assert_eq!("no_matching_user", body.error_code);
+        }
+    }
+
+    mod get_trashed_tasks_for_user {
+        use super::*;
+        use chrono::TimeZone;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let deleted_at = chrono::Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.trashed_tasks_for_user_result
+                    .set_returned_result(Ok(vec![domain::todo::TrashedTask {
+                        task: domain::todo::TodoTask {
+                            id: 3,
+                            owner_user_id: 2,
+                            item_desc: "Something to do".to_owned(),
+                            completed: false,
+                            due_date: None,
+                            recurrence: None,
+                            assignee_user_id: None,
+                            comment_count: 0,
+                            blocked: false,
+                        },
+                        deleted_at,
+                    }]));
+            });
+
+            let Json(tasks) =
+                get_trashed_tasks_for_user(2, &Caller::Trusted, &mut ext_cxn, &task_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+
+            assert!(matches!(tasks.as_slice(), [
+                dto::TrashedTask {
+                    id: 3,
+                    description,
+                    completed: false,
+                    deleted_at: trashed_at,
+                }
+            ] if description == "Something to do" && *trashed_at == deleted_at));
 
-            let deserialized_body: dto::BasicError = deserialize_body(res_body).await;
-            assert_eq!("no_matching_user", deserialized_body.error_code);
+            let locked_service = task_service.lock().unwrap();
+            assert_eq!(&[2], locked_service.trashed_tasks_for_user_result.calls());
         }
 
         #[tokio::test]
-        async fn converts_port_error_to_500() {
-            let produced_response = Err::<(), _>(handle_todo_task_err(TaskError::PortError(
-                anyhow!("Whoopsie daisy"),
-            )))
-            .into_response();
-            let (res_parts, res_body) = produced_response.into_parts();
+        async fn returns_404_on_user_not_found() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.trashed_tasks_for_user_result
+                    .set_returned_result(Err(TaskError::UserDoesNotExist));
+            });
 
-            assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, res_parts.status);
+            let response =
+                get_trashed_tasks_for_user(2, &Caller::Trusted, &mut ext_cxn, &task_service)
+                    .await
+                    .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
 
-            let deserialized_body: dto::BasicError = deserialize_body(res_body).await;
-            assert_eq!("internal_error", deserialized_body.error_code);
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_user", body.error_code);
         }
     }
 
-    mod get_tasks_for_user {
+    mod get_assigned_tasks {
         use super::*;
 
         #[tokio::test]
         async fn happy_path() {
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
-                svc.tasks_for_user_result.set_returned_result(Ok(vec![
+                svc.tasks_assigned_to_result.set_returned_result(Ok(vec![
                     domain::todo::TodoTask {
                         id: 3,
-                        owner_user_id: 2,
+                        owner_user_id: 1,
                         item_desc: "Something to do".to_owned(),
-                    },
-                    domain::todo::TodoTask {
-                        id: 10,
-                        owner_user_id: 2,
-                        item_desc: "Another thing to do".to_owned(),
+                        completed: false,
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: Some(2),
+                        comment_count: 0,
+                        blocked: false,
                     },
                 ]));
             });
 
-            let Json(tasks) = get_tasks_for_user(2, &mut ext_cxn, &task_service)
+            let Json(tasks) = get_assigned_tasks(2, &Caller::Trusted, &mut ext_cxn, &task_service)
                 .await
                 .unwrap_or_else(|err| {
                     panic!("Didn't get the expected response! Error: {:#?}", err);
                 });
 
             assert!(matches!(tasks.as_slice(), [
-                dto::TodoTask{
-                    id: 3,
-                    description: d1,
-                },
                 dto::TodoTask {
-                    id: 10,
-                    description: d2,
+                    id: 3,
+                    description,
+                    assignee_user_id: Some(2),
+                    ..
                 }
-            ] if d1 == "Something to do" &&
-                 d2 == "Another thing to do"
-            ))
+            ] if description == "Something to do"));
+
+            let locked_service = task_service.lock().unwrap();
+            assert_eq!(&[2], locked_service.tasks_assigned_to_result.calls());
         }
 
         #[tokio::test]
         async fn returns_404_on_user_not_found() {
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
-                svc.tasks_for_user_result
+                svc.tasks_assigned_to_result
                     .set_returned_result(Err(TaskError::UserDoesNotExist));
             });
 
-            let response = get_tasks_for_user(2, &mut ext_cxn, &task_service)
+            let response = get_assigned_tasks(2, &Caller::Trusted, &mut ext_cxn, &task_service)
                 .await
                 .into_response();
-            let (parts, body) = response.into_parts();
-
-            assert_eq!(StatusCode::NOT_FOUND, parts.status);
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
 
-            let body: dto::BasicError = deserialize_body(body).await;
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
             assert_eq!("no_matching_user", body.error_code);
         }
     }
@@ -652,19 +2762,32 @@ mod tests {
                         id: path_vars.task_id,
                         owner_user_id: path_vars.user_id,
                         item_desc: "Something to do".to_owned(),
+                        completed: false,
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                        comment_count: 0,
+                        blocked: false,
                     })));
             });
 
-            let Json(task) = get_task_for_user(path_vars, &mut ext_cxn, &task_service)
-                .await
-                .unwrap_or_else(|err| {
-                    panic!("Didn't get expected response, instead got this: {:#?}", err);
-                });
+            let Json(task) =
+                get_task_for_user(path_vars, &Caller::Trusted, &mut ext_cxn, &task_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get expected response, instead got this: {:#?}", err);
+                    });
 
             assert!(matches!(task,
                 dto::TodoTask {
                     id: 10,
-                    description
+                    description,
+                    completed: false,
+                    due_date: None,
+                    recurrence: None,
+                    assignee_user_id: None,
+                    comment_count: 0,
+                    blocked: false,
                 } if description == "Something to do",
             ));
         }
@@ -678,9 +2801,10 @@ mod tests {
                     .set_returned_result(Err(TaskError::UserDoesNotExist));
             });
 
-            let response = get_task_for_user(path_vars, &mut ext_cxn, &task_service)
-                .await
-                .into_response();
+            let response =
+                get_task_for_user(path_vars, &Caller::Trusted, &mut ext_cxn, &task_service)
+                    .await
+                    .into_response();
             let (parts, body) = response.into_parts();
 
             assert_eq!(StatusCode::NOT_FOUND, parts.status);
@@ -697,9 +2821,10 @@ mod tests {
                 svc.user_task_by_id_result.set_returned_result(Ok(None));
             });
 
-            let response = get_task_for_user(path_vars, &mut ext_cxn, &task_service)
-                .await
-                .into_response();
+            let response =
+                get_task_for_user(path_vars, &Caller::Trusted, &mut ext_cxn, &task_service)
+                    .await
+                    .into_response();
             let (parts, body) = response.into_parts();
 
             assert_eq!(StatusCode::NOT_FOUND, parts.status);
@@ -715,21 +2840,30 @@ mod tests {
         fn new_task_payload() -> dto::NewTask {
             dto::NewTask {
                 item_desc: "Something to do".to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
             }
         }
         #[tokio::test]
         async fn happy_path() {
-            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
                 svc.create_task_for_user_result.set_returned_result(Ok(10));
             });
 
-            let (status, Json(new_task_info)) =
-                add_task_for_user(3, new_task_payload(), &mut ext_cxn, &task_service)
-                    .await
-                    .unwrap_or_else(|err| {
-                        panic!("Didn't get a successful response: {:#?}", err);
-                    });
+            let (status, Json(new_task_info)) = add_task_for_user(
+                3,
+                new_task_payload(),
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get a successful response: {:#?}", err);
+            });
 
             assert_eq!(StatusCode::CREATED, status);
             assert_eq!(10, new_task_info.id);
@@ -737,15 +2871,22 @@ mod tests {
 
         #[tokio::test]
         async fn gives_appropriate_404_on_no_user() {
-            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
                 svc.create_task_for_user_result
                     .set_returned_result(Err(TaskError::UserDoesNotExist));
             });
 
-            let response = add_task_for_user(10, new_task_payload(), &mut ext_cxn, &task_service)
-                .await
-                .into_response();
+            let response = add_task_for_user(
+                10,
+                new_task_payload(),
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .into_response();
             let (parts, body) = response.into_parts();
 
             assert_eq!(StatusCode::NOT_FOUND, parts.status);
@@ -754,4 +2895,547 @@ mod tests {
             assert_eq!("no_matching_user", deserialized_body.error_code);
         }
     }
+    mod search_tasks_for_user {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.search_tasks_result.set_returned_result(Ok(vec![
+                    domain::todo::TaskSearchMatch {
+                        task: domain::todo::TodoTask {
+                            id: 3,
+                            owner_user_id: 2,
+                            item_desc: "Buy groceries".to_owned(),
+                            completed: false,
+                            due_date: None,
+                            recurrence: None,
+                            assignee_user_id: None,
+                            comment_count: 0,
+                            blocked: false,
+                        },
+                        rank: 0.5,
+                        snippet: "<mark>Buy</mark> groceries".to_owned(),
+                    },
+                ]));
+            });
+            let params = dto::TaskSearchParams {
+                q: "buy".to_owned(),
+            };
+
+            let Json(matches) =
+                search_tasks_for_user(2, params, &Caller::Trusted, &mut ext_cxn, &task_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+
+            assert!(matches!(matches.as_slice(), [
+                dto::TaskSearchResult {
+                    task: dto::TodoTask { id: 3, .. },
+                    snippet,
+                    ..
+                }
+            ] if snippet == "<mark>Buy</mark> groceries"));
+
+            let locked_service = task_service
+                .lock()
+                .expect("mock task service mutex poisoned");
+            assert!(matches!(
+                locked_service.search_tasks_result.calls(),
+                [(2, search_text)] if search_text == "buy"
+            ));
+        }
+
+        #[tokio::test]
+        async fn rejects_empty_search() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::new_locked();
+            let params = dto::TaskSearchParams { q: "".to_owned() };
+
+            let response =
+                search_tasks_for_user(2, params, &Caller::Trusted, &mut ext_cxn, &task_service)
+                    .await
+                    .into_response();
+            let (parts, body) = response.into_parts();
+
+            assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+
+            let body: dto::BasicError = deserialize_body(body).await;
+            assert_eq!("invalid_input", body.error_code);
+        }
+
+        #[tokio::test]
+        async fn returns_404_on_user_not_found() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.search_tasks_result
+                    .set_returned_result(Err(TaskError::UserDoesNotExist));
+            });
+            let params = dto::TaskSearchParams {
+                q: "buy".to_owned(),
+            };
+
+            let response =
+                search_tasks_for_user(2, params, &Caller::Trusted, &mut ext_cxn, &task_service)
+                    .await
+                    .into_response();
+            let (parts, body) = response.into_parts();
+
+            assert_eq!(StatusCode::NOT_FOUND, parts.status);
+
+            let body: dto::BasicError = deserialize_body(body).await;
+            assert_eq!("no_matching_user", body.error_code);
+        }
+    }
+
+    mod preview_task_occurrences {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.upcoming_occurrences_result.set_returned_result(Ok(vec![
+                    NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 3, 11).unwrap(),
+                ]));
+            });
+            let path = GetTaskPath {
+                user_id: 2,
+                task_id: 3,
+            };
+
+            let Json(occurrences) = preview_task_occurrences(
+                path,
+                dto::OccurrencePreviewParams { count: None },
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &task_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(2, occurrences.len());
+
+            let locked_service = task_service
+                .lock()
+                .expect("mock task service mutex poisoned");
+            assert_eq!(
+                &[(2, 3, dto::DEFAULT_PREVIEWED_OCCURRENCES)],
+                locked_service.upcoming_occurrences_result.calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_out_of_range_count() {
+            for count in [0, 101] {
+                let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let task_service = domain::todo::test_util::MockTaskService::new_locked();
+                let path = GetTaskPath {
+                    user_id: 2,
+                    task_id: 3,
+                };
+
+                let response = preview_task_occurrences(
+                    path,
+                    dto::OccurrencePreviewParams { count: Some(count) },
+                    &Caller::Trusted,
+                    &mut ext_cxn,
+                    &task_service,
+                )
+                .await
+                .into_response();
+                assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+                let body: dto::BasicError = deserialize_body(response.into_body()).await;
+                assert_eq!("invalid_input", body.error_code);
+            }
+        }
+
+        #[tokio::test]
+        async fn returns_404_on_task_not_found() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.upcoming_occurrences_result
+                    .set_returned_result(Err(TaskError::TaskDoesNotExist));
+            });
+            let path = GetTaskPath {
+                user_id: 2,
+                task_id: 3,
+            };
+
+            let response = preview_task_occurrences(
+                path,
+                dto::OccurrencePreviewParams { count: Some(3) },
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &task_service,
+            )
+            .await
+            .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_task", body.error_code);
+        }
+    }
+
+    mod stream_task_events {
+        use super::*;
+        use crate::domain::task_event::test_util::{FakeTaskChangeNotices, MockTaskEventService};
+        use crate::domain::task_event::{TaskChange, TaskChangeKind};
+        use std::collections::HashMap;
+
+        fn changes() -> Vec<TaskChange> {
+            vec![
+                TaskChange {
+                    id: 4,
+                    task_id: 1,
+                    kind: TaskChangeKind::Created,
+                    description: Some("Something to do".to_owned()),
+                    completed: Some(false),
+                },
+                TaskChange {
+                    id: 5,
+                    task_id: 1,
+                    kind: TaskChangeKind::Completed,
+                    description: Some("Something to do".to_owned()),
+                    completed: Some(true),
+                },
+                TaskChange {
+                    id: 6,
+                    task_id: 1,
+                    kind: TaskChangeKind::Deleted,
+                    description: None,
+                    completed: None,
+                },
+            ]
+        }
+
+        /// Reads every event sent on an SSE response as a map of its fields
+        async fn read_events(response: axum::response::Response) -> Vec<HashMap<String, String>> {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Could not read event stream");
+            let body = String::from_utf8(bytes.to_vec()).expect("Event stream wasn't UTF-8");
+
+            body.split("\n\n")
+                .filter(|event| !event.trim().is_empty())
+                .map(|event| {
+                    event
+                        .lines()
+                        .filter_map(|line| line.split_once(':'))
+                        .map(|(field, value)| (field.to_owned(), value.trim_start().to_owned()))
+                        .collect()
+                })
+                .collect()
+        }
+
+        #[tokio::test]
+        async fn streams_changes_after_cursor() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_event_service = MockTaskEventService::build_shared(|svc| {
+                svc.start_following_result.set_returned_result(Ok(4));
+                svc.changes = changes();
+            });
+
+            let events = stream_task_events(
+                3,
+                None,
+                &Caller::Trusted,
+                ext_cxn,
+                Arc::clone(&task_event_service),
+                FakeTaskChangeNotices::new(),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            let events = read_events(Sse::new(events).into_response()).await;
+
+            assert_eq!(2, events.len());
+            assert_eq!("5", events[0]["id"]);
+            assert_eq!("task.completed", events[0]["event"]);
+            assert_eq!(
+                r#"{"task_id":1,"description":"Something to do","completed":true}"#,
+                events[0]["data"]
+            );
+            assert_eq!("6", events[1]["id"]);
+            assert_eq!("task.deleted", events[1]["event"]);
+            assert_eq!(r#"{"task_id":1}"#, events[1]["data"]);
+
+            let locked_service = task_event_service.lock().unwrap();
+            assert_eq!([(3, None)], locked_service.start_following_result.calls());
+        }
+
+        #[tokio::test]
+        async fn resumes_from_last_event_id() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_event_service = MockTaskEventService::build_shared(|svc| {
+                svc.start_following_result.set_returned_result(Ok(5));
+                svc.changes = changes();
+            });
+
+            let events = stream_task_events(
+                3,
+                Some("5".to_owned()),
+                &Caller::Trusted,
+                ext_cxn,
+                Arc::clone(&task_event_service),
+                FakeTaskChangeNotices::new(),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            let events = read_events(Sse::new(events).into_response()).await;
+
+            assert_eq!(1, events.len());
+            assert_eq!("6", events[0]["id"]);
+
+            let locked_service = task_event_service.lock().unwrap();
+            assert_eq!(
+                [(3, Some(5))],
+                locked_service.start_following_result.calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_invalid_last_event_id() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_event_service = MockTaskEventService::build_shared(|_| {});
+
+            let response = stream_task_events(
+                3,
+                Some("yesterday".to_owned()),
+                &Caller::Trusted,
+                ext_cxn,
+                Arc::clone(&task_event_service),
+                FakeTaskChangeNotices::new(),
+            )
+            .await
+            .map(Sse::new)
+            .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("invalid_last_event_id", body.error_code);
+
+            let locked_service = task_event_service.lock().unwrap();
+            assert!(locked_service.start_following_result.calls().is_empty());
+        }
+
+        #[tokio::test]
+        async fn returns_404_when_user_missing() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_event_service = MockTaskEventService::build_shared(|svc| {
+                svc.start_following_result
+                    .set_returned_result(Err(TaskError::UserDoesNotExist));
+            });
+
+            let response = stream_task_events(
+                3,
+                None,
+                &Caller::Trusted,
+                ext_cxn,
+                task_event_service,
+                FakeTaskChangeNotices::new(),
+            )
+            .await
+            .map(Sse::new)
+            .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_user", body.error_code);
+        }
+    }
+
+    mod apply_task_batch {
+        use super::*;
+
+        fn batch_payload(mode: dto::BatchMode) -> dto::TaskBatch {
+            dto::TaskBatch {
+                mode,
+                operations: vec![
+                    dto::TaskBatchOperation::Create(dto::NewTask {
+                        item_desc: "Something to do".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    }),
+                    dto::TaskBatchOperation::Complete { task_id: 5 },
+                ],
+            }
+        }
+
+        #[tokio::test]
+        async fn atomic_happy_path() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.apply_batch_operation_result.set_returned_result(Ok(5));
+            });
+
+            let Json(batch_result) = apply_task_batch(
+                1,
+                batch_payload(dto::BatchMode::Atomic),
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get a successful response: {:#?}", err);
+            });
+
+            assert!(batch_result.committed);
+            assert!(matches!(
+                batch_result.results.as_slice(),
+                [
+                    dto::TaskBatchOperationResult::Succeeded { task_id: 5 },
+                    dto::TaskBatchOperationResult::Succeeded { task_id: 5 },
+                ]
+            ));
+            assert!(ext_cxn.did_transaction_commit());
+            assert_eq!(1, ext_cxn.started_transactions().len());
+        }
+
+        #[tokio::test]
+        async fn atomic_failure_rolls_back_whole_batch() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.apply_batch_operation_result
+                    .set_returned_result(Err(TaskError::TaskDoesNotExist));
+            });
+
+            let Json(batch_result) = apply_task_batch(
+                1,
+                batch_payload(dto::BatchMode::Atomic),
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get a successful response: {:#?}", err);
+            });
+
+            assert!(!batch_result.committed);
+            assert!(matches!(
+                batch_result.results.as_slice(),
+                [
+                    dto::TaskBatchOperationResult::Failed { error },
+                    dto::TaskBatchOperationResult::Skipped,
+                ] if error.error_code == "no_matching_task"
+            ));
+            assert!(!ext_cxn.did_transaction_commit());
+
+            let locked_service = task_service
+                .lock()
+                .expect("mock task service mutex poisoned");
+            assert_eq!(1, locked_service.apply_batch_operation_result.calls().len());
+        }
+
+        #[tokio::test]
+        async fn atomic_batch_with_invalid_operation_is_not_attempted() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::new_locked();
+            let mut payload = batch_payload(dto::BatchMode::Atomic);
+            payload
+                .operations
+                .push(dto::TaskBatchOperation::Update(dto::TaskBatchUpdate {
+                    task_id: 5,
+                    description: "".to_owned(),
+                }));
+
+            let Json(batch_result) = apply_task_batch(
+                1,
+                payload,
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get a successful response: {:#?}", err);
+            });
+
+            assert!(!batch_result.committed);
+            assert!(matches!(
+                batch_result.results.as_slice(),
+                [
+                    dto::TaskBatchOperationResult::Skipped,
+                    dto::TaskBatchOperationResult::Skipped,
+                    dto::TaskBatchOperationResult::Failed { error },
+                ] if error.error_code == "invalid_input"
+            ));
+            assert!(ext_cxn.started_transactions().is_empty());
+        }
+
+        #[tokio::test]
+        async fn best_effort_reports_each_operation() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::build_locked(|svc| {
+                svc.apply_batch_operation_result
+                    .set_returned_result(Err(TaskError::UserDoesNotExist));
+            });
+
+            let Json(batch_result) = apply_task_batch(
+                1,
+                batch_payload(dto::BatchMode::BestEffort),
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get a successful response: {:#?}", err);
+            });
+
+            assert!(!batch_result.committed);
+            assert!(matches!(
+                batch_result.results.as_slice(),
+                [
+                    dto::TaskBatchOperationResult::Failed { error: first_error },
+                    dto::TaskBatchOperationResult::Failed { error: second_error },
+                ] if first_error.error_code == "no_matching_user" &&
+                     second_error.error_code == "no_matching_user"
+            ));
+            assert_eq!(2, ext_cxn.started_transactions().len());
+        }
+
+        #[tokio::test]
+        async fn rejects_empty_batch() {
+            let ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task_service = domain::todo::test_util::MockTaskService::new_locked();
+            let payload = dto::TaskBatch {
+                mode: dto::BatchMode::Atomic,
+                operations: vec![],
+            };
+
+            let response = apply_task_batch(
+                1,
+                payload,
+                Some(1),
+                &Caller::Trusted,
+                &ext_cxn,
+                &task_service,
+            )
+            .await
+            .into_response();
+            let (parts, body) = response.into_parts();
+
+            assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+
+            let deserialized_body: dto::BasicError = deserialize_body(body).await;
+            assert_eq!("invalid_input", deserialized_body.error_code);
+        }
+    }
 }
@@ -0,0 +1,412 @@
+use crate::api::access::{ForbiddenResponse, RequestCaller};
+use crate::domain::access::{Action, Caller};
+use crate::domain::webhook::driven_ports::WebhookSender;
+use crate::domain::webhook::driving_ports::{WebhookError, WebhookPort};
+use crate::domain::webhook::DispatchSummary;
+use crate::external_connections::ExternalConnectivity;
+use crate::routing_utils::{GenericErrorResponse, Json, ValidationErrorResponse};
+use crate::{domain, dto, persistence, AppState, SharedData};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::ErrorResponse;
+use axum::routing::{delete, get};
+use axum::Router;
+use log::{error, info};
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::OpenApi;
+use validator::Validate;
+
+#[derive(OpenApi)]
+#[openapi(paths(get_subscriptions, create_subscription, delete_subscription,))]
+/// Defines the OpenAPI documentation for the webhooks API
+pub struct WebhookApi;
+/// Constant used to group webhook endpoints in OpenAPI documentation
+pub const WEBHOOK_API_GROUP: &str = "Webhooks";
+
+/// Creates a router for endpoints under the "/webhooks" group of APIs
+pub fn webhook_routes() -> Router<Arc<SharedData>> {
+    Router::new()
+        .route(
+            "/",
+            get(
+                |State(app_data): AppState, RequestCaller(caller): RequestCaller| async move {
+                    let webhook_service = domain::webhook::WebhookService;
+                    let mut ext_cxn = app_data.ext_cxn.clone();
+
+                    get_subscriptions(&caller, &mut ext_cxn, &webhook_service).await
+                },
+            )
+            .post(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Json(new_subscription): Json<dto::NewWebhookSubscription>| async move {
+                    let webhook_service = domain::webhook::WebhookService;
+                    let mut ext_cxn = app_data.ext_cxn.clone();
+
+                    create_subscription(new_subscription, &caller, &mut ext_cxn, &webhook_service)
+                        .await
+                },
+            ),
+        )
+        .route(
+            "/:subscription_id",
+            delete(
+                |State(app_data): AppState,
+                 RequestCaller(caller): RequestCaller,
+                 Path(subscription_id): Path<i32>| async move {
+                    let webhook_service = domain::webhook::WebhookService;
+                    let mut ext_cxn = app_data.ext_cxn.clone();
+
+                    delete_subscription(subscription_id, &caller, &mut ext_cxn, &webhook_service)
+                        .await
+                },
+            ),
+        )
+}
+
+/// Periodically delivers events waiting in the webhook outbox to their subscribers
+pub async fn dispatch_webhooks(
+    mut ext_cxn: impl ExternalConnectivity,
+    sender: impl WebhookSender,
+    poll_interval: Duration,
+) {
+    let webhook_service = domain::webhook::WebhookService;
+    let outbox = persistence::db_webhook_driven_ports::DbEventOutbox;
+    let mut interval = tokio::time::interval(poll_interval);
+
+    loop {
+        interval.tick().await;
+        match webhook_service
+            .dispatch_pending(&mut ext_cxn, &outbox, &sender)
+            .await
+        {
+            Ok(summary) if summary == DispatchSummary::default() => {}
+            Ok(summary) => info!(
+                "Dispatched webhooks: {} delivered, {} to be retried, {} failed permanently",
+                summary.delivered, summary.retrying, summary.failed
+            ),
+            Err(dispatch_err) => error!("Failed to dispatch webhooks: {dispatch_err}"),
+        }
+    }
+}
+
+/// Lists every webhook subscription
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = WEBHOOK_API_GROUP,
+    responses(
+        (status = 200, description = "Subscriptions successfully retrieved", body = Vec<WebhookSubscription>),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn get_subscriptions(
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    webhook_service: &impl WebhookPort,
+) -> Result<Json<Vec<dto::WebhookSubscription>>, ErrorResponse> {
+    info!("Listing webhook subscriptions");
+    domain::access::authorize(caller, Action::Administer).map_err(ForbiddenResponse)?;
+    let subscription_reader = persistence::db_webhook_driven_ports::DbWebhookSubscriptions;
+
+    let subscriptions = webhook_service
+        .list_subscriptions(&mut *ext_cxn, &subscription_reader)
+        .await
+        .map_err(|err| {
+            error!("Could not list webhook subscriptions: {err}");
+            GenericErrorResponse(err)
+        })?;
+
+    Ok(Json(
+        subscriptions
+            .into_iter()
+            .map(dto::WebhookSubscription::from)
+            .collect(),
+    ))
+}
+
+/// Registers a URL to receive webhook events. Each event is sent as a signed JSON POST request
+/// and retried with an increasing delay until the URL responds with a 2XX status.
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    tag = WEBHOOK_API_GROUP,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Makes the request safe to retry. The first response for a key is replayed on later requests with the same key."),
+    ),
+    request_body = NewWebhookSubscription,
+    responses(
+        (status = 201, description = "Subscription successfully created", body = InsertedWebhookSubscription),
+        (status = 400, response = dto::err_resps::BasicError400Validation),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (status = 422, response = dto::err_resps::BasicError422IdempotencyKeyReused),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn create_subscription(
+    new_subscription: dto::NewWebhookSubscription,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    webhook_service: &impl WebhookPort,
+) -> Result<(StatusCode, Json<dto::InsertedWebhookSubscription>), ErrorResponse> {
+    info!("Subscribing {} to webhooks", new_subscription.url);
+    domain::access::authorize(caller, Action::Administer).map_err(ForbiddenResponse)?;
+    new_subscription
+        .validate()
+        .map_err(ValidationErrorResponse::from)?;
+
+    let subscription_writer = persistence::db_webhook_driven_ports::DbWebhookSubscriptions;
+    let domain_subscription = domain::webhook::NewWebhookSubscription::from(new_subscription);
+
+    let subscription_id = webhook_service
+        .create_subscription(&domain_subscription, &mut *ext_cxn, &subscription_writer)
+        .await
+        .map_err(|err| {
+            error!("Could not create webhook subscription: {err}");
+            GenericErrorResponse(err)
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(dto::InsertedWebhookSubscription {
+            id: subscription_id,
+        }),
+    ))
+}
+
+/// Removes a webhook subscription. Events which haven't been delivered to it yet are discarded.
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{subscription_id}",
+    tag = WEBHOOK_API_GROUP,
+    params(
+        ("subscription_id" = i32, Path, description = "The ID of the subscription to remove"),
+    ),
+    responses(
+        (status = 200, description = "Subscription successfully removed"),
+        (status = 403, response = dto::err_resps::BasicError403),
+        (
+            status = 404,
+            description = "Specified subscription does not exist (error code `no_matching_webhook`)",
+            body = BasicError,
+            example = json!({
+                "error_code": "no_matching_webhook",
+                "error_description": "No webhook subscription matches the given ID.",
+                "extra_info": null,
+            })
+        ),
+        (status = 500, response = dto::err_resps::BasicError500),
+    ),
+)]
+async fn delete_subscription(
+    subscription_id: i32,
+    caller: &Caller,
+    ext_cxn: &mut impl ExternalConnectivity,
+    webhook_service: &impl WebhookPort,
+) -> Result<StatusCode, ErrorResponse> {
+    info!("Removing webhook subscription {subscription_id}");
+    domain::access::authorize(caller, Action::Administer).map_err(ForbiddenResponse)?;
+    let subscription_writer = persistence::db_webhook_driven_ports::DbWebhookSubscriptions;
+
+    let delete_result = webhook_service
+        .delete_subscription(subscription_id, &mut *ext_cxn, &subscription_writer)
+        .await;
+    match delete_result {
+        Ok(()) => Ok(StatusCode::OK),
+        Err(WebhookError::SubscriptionDoesNotExist) => Err((
+            StatusCode::NOT_FOUND,
+            Json(dto::BasicError {
+                error_code: "no_matching_webhook".to_owned(),
+                error_description: "No webhook subscription matches the given ID.".to_owned(),
+                extra_info: None,
+            }),
+        )
+            .into()),
+        Err(WebhookError::PortError(err)) => {
+            error!("Could not delete webhook subscription {subscription_id}: {err}");
+            Err(GenericErrorResponse(err).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::deserialize_body;
+    use crate::domain::webhook::test_util::MockWebhookService;
+    use crate::domain::webhook::EventType;
+    use crate::external_connections;
+    use anyhow::anyhow;
+    use axum::response::IntoResponse;
+
+    mod get_subscriptions {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let webhook_service = MockWebhookService::build_locked(|svc| {
+                svc.list_subscriptions_result.set_returned_anyhow(Ok(vec![
+                    domain::webhook::WebhookSubscription {
+                        id: 3,
+                        url: "https://example.com/hook".to_owned(),
+                        event_types: vec![EventType::TaskCreated, EventType::UserCreated],
+                    },
+                ]));
+            });
+
+            let Json(subscriptions) =
+                get_subscriptions(&Caller::Trusted, &mut ext_cxn, &webhook_service)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("Didn't get the expected response! Error: {:#?}", err);
+                    });
+
+            assert!(matches!(subscriptions.as_slice(), [
+                dto::WebhookSubscription {
+                    id: 3,
+                    url,
+                    event_types,
+                }
+            ] if url == "https://example.com/hook"
+                && *event_types == vec![dto::WebhookEventType::TaskCreated, dto::WebhookEventType::UserCreated]));
+        }
+
+        #[tokio::test]
+        async fn returns_500_when_service_blows_up() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let webhook_service = MockWebhookService::build_locked(|svc| {
+                svc.list_subscriptions_result
+                    .set_returned_anyhow(Err(anyhow!("Whoopsy daisy")));
+            });
+
+            let response = get_subscriptions(&Caller::Trusted, &mut ext_cxn, &webhook_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("internal_error", body.error_code);
+        }
+    }
+
+    mod create_subscription {
+        use super::*;
+
+        fn new_subscription() -> dto::NewWebhookSubscription {
+            dto::NewWebhookSubscription {
+                url: "https://example.com/hook".to_owned(),
+                event_types: vec![dto::WebhookEventType::TaskDeleted],
+                secret: "a-very-secret-value".to_owned(),
+            }
+        }
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let webhook_service = MockWebhookService::build_locked(|svc| {
+                svc.create_subscription_result.set_returned_anyhow(Ok(4));
+            });
+
+            let (status, Json(inserted)) = create_subscription(
+                new_subscription(),
+                &Caller::Trusted,
+                &mut ext_cxn,
+                &webhook_service,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("Didn't get the expected response! Error: {:#?}", err);
+            });
+            assert_eq!(StatusCode::CREATED, status);
+            assert_eq!(4, inserted.id);
+
+            let locked_service = webhook_service.lock().unwrap();
+            assert_eq!(
+                [domain::webhook::NewWebhookSubscription {
+                    url: "https://example.com/hook".to_owned(),
+                    event_types: vec![EventType::TaskDeleted],
+                    secret: "a-very-secret-value".to_owned(),
+                }],
+                locked_service.create_subscription_result.calls()
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_invalid_subscriptions() {
+            let invalid_subscriptions = [
+                dto::NewWebhookSubscription {
+                    url: "ftp://example.com/hook".to_owned(),
+                    ..new_subscription()
+                },
+                dto::NewWebhookSubscription {
+                    event_types: Vec::new(),
+                    ..new_subscription()
+                },
+                dto::NewWebhookSubscription {
+                    secret: "short".to_owned(),
+                    ..new_subscription()
+                },
+            ];
+
+            for subscription in invalid_subscriptions {
+                let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let webhook_service = MockWebhookService::build_locked(|_| {});
+
+                let response = create_subscription(
+                    subscription,
+                    &Caller::Trusted,
+                    &mut ext_cxn,
+                    &webhook_service,
+                )
+                .await
+                .into_response();
+                assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+                let body: dto::BasicError = deserialize_body(response.into_body()).await;
+                assert_eq!("invalid_input", body.error_code);
+            }
+        }
+    }
+
+    mod delete_subscription {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let webhook_service = MockWebhookService::build_locked(|svc| {
+                svc.delete_subscription_result.set_returned_result(Ok(()));
+            });
+
+            let delete_result =
+                delete_subscription(2, &Caller::Trusted, &mut ext_cxn, &webhook_service).await;
+            let Ok(status) = delete_result else {
+                panic!("Didn't receive expected response: {:#?}", delete_result);
+            };
+            assert_eq!(StatusCode::OK, status);
+
+            let locked_service = webhook_service.lock().unwrap();
+            assert_eq!([2], locked_service.delete_subscription_result.calls());
+        }
+
+        #[tokio::test]
+        async fn returns_404_when_subscription_missing() {
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let webhook_service = MockWebhookService::build_locked(|svc| {
+                svc.delete_subscription_result
+                    .set_returned_result(Err(WebhookError::SubscriptionDoesNotExist));
+            });
+
+            let response = delete_subscription(2, &Caller::Trusted, &mut ext_cxn, &webhook_service)
+                .await
+                .into_response();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+            let body: dto::BasicError = deserialize_body(response.into_body()).await;
+            assert_eq!("no_matching_webhook", body.error_code);
+        }
+    }
+}
@@ -2,6 +2,76 @@
 pub const DB_URL: &str = "DATABASE_URL";
 /// Log level configuration for the application. For formatting info, see [env_logger's documentation](https://docs.rs/env_logger/latest/env_logger/#enabling-logging)
 pub const LOG_LEVEL: &str = "LOG_LEVEL";
+/// How long, in seconds, a response stored for an `Idempotency-Key` is replayed before the key expires. Defaults to 24 hours.
+pub const IDEMPOTENCY_KEY_TTL_SECONDS: &str = "IDEMPOTENCY_KEY_TTL_SECONDS";
+/// How long, in seconds, a deleted task stays in the trash before it's permanently removed. Defaults to 30 days.
+pub const TASK_TRASH_RETENTION_SECONDS: &str = "TASK_TRASH_RETENTION_SECONDS";
+/// How many requests each client may make to routes without a limit of their own, written as `REQUESTS/SECONDS`.
+/// Defaults to 600/60. Set to `off` to leave those routes unlimited.
+pub const RATE_LIMIT: &str = "RATE_LIMIT";
+/// Limits for particular routes, written as a comma-separated list of `METHOD /path=REQUESTS/SECONDS` using the
+/// route's path pattern, such as `POST /users/:user_id/tasks=30/60`
+pub const RATE_LIMIT_ROUTES: &str = "RATE_LIMIT_ROUTES";
+/// Set to `true` to keep rate limits in the database so they're shared by every instance of the app, instead of
+/// each instance keeping its own
+pub const RATE_LIMIT_SHARED: &str = "RATE_LIMIT_SHARED";
+/// Origins browsers may call the API from, as a comma-separated list such as `https://example.com,http://localhost:3000`
+/// or `*` for any origin. Cross-origin requests aren't allowed by default.
+pub const CORS_ALLOWED_ORIGINS: &str = "CORS_ALLOWED_ORIGINS";
+/// Set to `off` to stop compressing responses with gzip, Brotli or Zstandard for clients which accept them
+pub const RESPONSE_COMPRESSION: &str = "RESPONSE_COMPRESSION";
+/// The largest request body, in bytes, which will be accepted. Defaults to 2 MiB.
+pub const MAX_REQUEST_BODY_BYTES: &str = "MAX_REQUEST_BODY_BYTES";
+/// How long, in seconds, a request may take before it's abandoned with a 503. Defaults to 30 seconds. Set to `off`
+/// to let requests take as long as they need.
+pub const REQUEST_TIMEOUT_SECONDS: &str = "REQUEST_TIMEOUT_SECONDS";
+/// Set to `on` to hold callers to the access policy, identifying them by the `X-User-Id` header. Admins may do
+/// anything, while other users may only see themselves and manage their own tasks. Off by default, in which case
+/// every caller may do anything.
+pub const ACCESS_CONTROL: &str = "ACCESS_CONTROL";
+/// First name of a user to make the first admin when the app starts, if there isn't an admin yet. The user is
+/// created if they don't exist. Must be given along with [BOOTSTRAP_ADMIN_LAST_NAME].
+pub const BOOTSTRAP_ADMIN_FIRST_NAME: &str = "BOOTSTRAP_ADMIN_FIRST_NAME";
+/// Last name of the user to make the first admin, alongside [BOOTSTRAP_ADMIN_FIRST_NAME]
+pub const BOOTSTRAP_ADMIN_LAST_NAME: &str = "BOOTSTRAP_ADMIN_LAST_NAME";
+/// The largest file, in bytes, which may be attached to a task. Defaults to 25 MiB. Uploads are streamed, so this
+/// isn't bound by [MAX_REQUEST_BODY_BYTES].
+pub const ATTACHMENT_MAX_BYTES: &str = "ATTACHMENT_MAX_BYTES";
+/// The media types which may be attached to tasks, as a comma-separated list such as `image/*,application/pdf`.
+/// Defaults to images, PDFs, plain text and CSV files.
+pub const ATTACHMENT_CONTENT_TYPES: &str = "ATTACHMENT_CONTENT_TYPES";
+/// Where the contents of attached files are kept: `local` for a directory on this machine, or `s3` for a bucket in
+/// an S3-compatible service such as MinIO. Defaults to `local`.
+pub const BLOB_STORAGE: &str = "BLOB_STORAGE";
+/// The directory files are kept in when [BLOB_STORAGE] is `local`. Defaults to `attachments` in the working directory.
+pub const BLOB_STORAGE_DIR: &str = "BLOB_STORAGE_DIR";
+/// Base URL of the S3-compatible service files are kept in when [BLOB_STORAGE] is `s3`, such as
+/// `http://localhost:9000` for a local MinIO
+pub const S3_ENDPOINT: &str = "S3_ENDPOINT";
+/// Bucket files are kept in when [BLOB_STORAGE] is `s3`. The bucket must already exist.
+pub const S3_BUCKET: &str = "S3_BUCKET";
+/// Region requests to the S3-compatible service are signed for. Defaults to `us-east-1`.
+pub const S3_REGION: &str = "S3_REGION";
+/// Access key ID used to sign requests to the S3-compatible service
+pub const S3_ACCESS_KEY_ID: &str = "S3_ACCESS_KEY_ID";
+/// Secret access key used to sign requests to the S3-compatible service
+pub const S3_SECRET_ACCESS_KEY: &str = "S3_SECRET_ACCESS_KEY";
+
+/// How users are told about due reminders: `log` to write them to the application log, `smtp` to email them to the
+/// address in their digest preferences, or `webhook` to post them to a URL. Defaults to `log`.
+pub const NOTIFIER: &str = "NOTIFIER";
+/// Host of the mail server digests are emailed through, along with reminders when [NOTIFIER] is `smtp`. Defaults
+/// to `localhost`.
+pub const SMTP_HOST: &str = "SMTP_HOST";
+/// Port of the mail server. Defaults to 1025, which local mail catchers such as Mailpit listen on.
+pub const SMTP_PORT: &str = "SMTP_PORT";
+/// Address mail is sent from. Defaults to `noreply@localhost`.
+pub const SMTP_FROM: &str = "SMTP_FROM";
+/// URL reminders are posted to as JSON when [NOTIFIER] is `webhook`
+pub const REMINDER_WEBHOOK_URL: &str = "REMINDER_WEBHOOK_URL";
+/// The hour of the day, in UTC, from which users who asked for one are emailed a digest of their open tasks.
+/// Defaults to 7.
+pub const DIGEST_SEND_HOUR: &str = "DIGEST_SEND_HOUR";
 
 #[cfg(test)]
 pub mod test {
@@ -0,0 +1,162 @@
+use crate::domain::user::Role;
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Whoever is asking the application to do something
+pub enum Caller {
+    /// Access control is turned off, so the caller may do anything
+    Trusted,
+    /// The caller didn't identify themselves as a user who exists
+    Anonymous,
+    /// A known user, who may do what their role allows
+    User { user_id: i32, role: Role },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Things a caller may or may not be allowed to do
+pub enum Action {
+    /// See every user in the system
+    ListUsers,
+    /// See the details of a single user
+    ViewUser(i32),
+    /// Read and change the tasks owned by a user
+    ManageTasks { owner_user_id: i32 },
+    /// Make users admins or take that away from them
+    ChangeRoles,
+    /// See and change things which affect the whole system, such as the audit log and webhooks
+    Administer,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ListUsers => write!(f, "list users"),
+            Self::ViewUser(user_id) => write!(f, "view user {user_id}"),
+            Self::ManageTasks { owner_user_id } => {
+                write!(f, "manage the tasks of user {owner_user_id}")
+            }
+            Self::ChangeRoles => write!(f, "change the roles of users"),
+            Self::Administer => write!(f, "administer the system"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("The caller is not allowed to {action}.")]
+/// Error returned when a caller tries to do something they aren't allowed to
+pub struct AccessDenied {
+    pub action: Action,
+}
+
+/// The access policy: decides whether a caller may perform an action. Admins may do anything, while other
+/// users may only see themselves and manage their own tasks.
+pub fn authorize(caller: &Caller, action: Action) -> Result<(), AccessDenied> {
+    let allowed = match caller {
+        Caller::Trusted => true,
+        Caller::Anonymous => false,
+        Caller::User {
+            role: Role::Admin, ..
+        } => true,
+        Caller::User {
+            user_id,
+            role: Role::User,
+        } => match action {
+            Action::ViewUser(viewed_user_id) => viewed_user_id == *user_id,
+            Action::ManageTasks { owner_user_id } => owner_user_id == *user_id,
+            Action::ListUsers | Action::ChangeRoles | Action::Administer => false,
+        },
+    };
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(AccessDenied { action })
+    }
+}
+
+/// Whether a caller may perform every action, so checks which would need to look something up first
+/// can be skipped
+pub fn is_unrestricted(caller: &Caller) -> bool {
+    matches!(
+        caller,
+        Caller::Trusted
+            | Caller::User {
+                role: Role::Admin,
+                ..
+            }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVERY_ACTION: [Action; 5] = [
+        Action::ListUsers,
+        Action::ViewUser(1),
+        Action::ManageTasks { owner_user_id: 1 },
+        Action::ChangeRoles,
+        Action::Administer,
+    ];
+
+    #[test]
+    fn trusted_callers_and_admins_may_do_anything() {
+        let admin = Caller::User {
+            user_id: 2,
+            role: Role::Admin,
+        };
+        for caller in [Caller::Trusted, admin] {
+            for action in EVERY_ACTION {
+                assert_eq!(Ok(()), authorize(&caller, action));
+            }
+            assert!(is_unrestricted(&caller));
+        }
+    }
+
+    #[test]
+    fn anonymous_callers_may_do_nothing() {
+        for action in EVERY_ACTION {
+            assert_eq!(
+                Err(AccessDenied { action }),
+                authorize(&Caller::Anonymous, action)
+            );
+        }
+        assert!(!is_unrestricted(&Caller::Anonymous));
+    }
+
+    #[test]
+    fn users_may_only_see_themselves_and_their_own_tasks() {
+        let user = Caller::User {
+            user_id: 1,
+            role: Role::User,
+        };
+        assert_eq!(Ok(()), authorize(&user, Action::ViewUser(1)));
+        assert_eq!(
+            Ok(()),
+            authorize(&user, Action::ManageTasks { owner_user_id: 1 })
+        );
+        assert!(!is_unrestricted(&user));
+
+        for action in [
+            Action::ListUsers,
+            Action::ViewUser(2),
+            Action::ManageTasks { owner_user_id: 2 },
+            Action::ChangeRoles,
+            Action::Administer,
+        ] {
+            assert_eq!(Err(AccessDenied { action }), authorize(&user, action));
+        }
+    }
+
+    #[test]
+    fn describes_denied_action() {
+        let denied = AccessDenied {
+            action: Action::ManageTasks { owner_user_id: 3 },
+        };
+        assert_eq!(
+            "The caller is not allowed to manage the tasks of user 3.",
+            denied.to_string()
+        );
+    }
+}
@@ -0,0 +1,1169 @@
+use crate::domain;
+use crate::domain::access::Caller;
+use crate::domain::attachment::driven_ports::{AttachmentStore, BlobStore};
+use crate::domain::attachment::driving_ports::AttachmentError;
+use crate::domain::todo::driven_ports::TaskReader;
+use crate::external_connections::ExternalConnectivity;
+use anyhow::{anyhow, Context};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use log::error;
+use rand::RngCore;
+use std::pin::Pin;
+use thiserror::Error;
+
+/// The largest file which may be attached to a task if not configured
+pub const DEFAULT_MAX_ATTACHMENT_BYTES: u64 = 25 * 1024 * 1024;
+/// The types of file which may be attached to tasks if not configured
+pub const DEFAULT_ATTACHMENT_CONTENT_TYPES: [&str; 4] =
+    ["image/*", "application/pdf", "text/plain", "text/csv"];
+
+/// The contents of a file, read a chunk at a time so large files never have to be held in memory
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, anyhow::Error>> + Send>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A file attached to a task. The file's contents are kept in blob storage under [Attachment::storage_key].
+pub struct Attachment {
+    pub id: i32,
+    pub task_id: i32,
+    pub uploader_user_id: i32,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Describes a file being attached to a task
+pub struct NewAttachment {
+    pub file_name: String,
+    pub content_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Restricts which files may be attached to tasks
+pub struct AttachmentLimits {
+    /// The largest file which may be attached, in bytes
+    pub max_bytes: u64,
+    /// The media types which may be attached, such as `application/pdf`. A type ending in `/*`, like
+    /// `image/*`, allows every subtype.
+    pub content_types: Vec<String>,
+}
+
+impl Default for AttachmentLimits {
+    fn default() -> Self {
+        AttachmentLimits {
+            max_bytes: DEFAULT_MAX_ATTACHMENT_BYTES,
+            content_types: DEFAULT_ATTACHMENT_CONTENT_TYPES
+                .iter()
+                .map(|content_type| (*content_type).to_owned())
+                .collect(),
+        }
+    }
+}
+
+impl AttachmentLimits {
+    /// Parses a comma-separated list of allowed media types, such as `image/*,application/pdf`
+    pub fn parse_content_types(content_types: &str) -> Result<Vec<String>, anyhow::Error> {
+        content_types
+            .split(',')
+            .map(|content_type| {
+                let content_type = content_type.trim().to_ascii_lowercase();
+                match content_type.split_once('/') {
+                    Some((kind, subtype)) if !kind.is_empty() && !subtype.is_empty() => {
+                        Ok(content_type)
+                    }
+                    _ => Err(anyhow!("{content_type:?} is not a media type")),
+                }
+            })
+            .collect()
+    }
+
+    /// Whether files of the given media type may be attached. Parameters such as `; charset=utf-8` are
+    /// ignored.
+    pub fn allows_content_type(&self, content_type: &str) -> bool {
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        let Some((kind, _)) = essence.split_once('/') else {
+            return false;
+        };
+
+        self.content_types.iter().any(|allowed| {
+            *allowed == essence
+                || allowed
+                    .strip_suffix("/*")
+                    .is_some_and(|allowed_kind| allowed_kind == kind)
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("The file is larger than the {max_bytes} byte limit.")]
+/// Ends a [ByteStream] which grew past the size limit for attachments
+pub struct FileTooLarge {
+    pub max_bytes: u64,
+}
+
+/// Wraps a file's contents so reading them fails with [FileTooLarge] as soon as more than `max_bytes` have
+/// been read, instead of after the whole file has been stored
+pub fn limit_size(content: ByteStream, max_bytes: u64) -> ByteStream {
+    let mut read_bytes = 0u64;
+    let mut exceeded = false;
+
+    Box::pin(content.map(move |chunk| {
+        if exceeded {
+            return Err(anyhow::Error::new(FileTooLarge { max_bytes }));
+        }
+        let chunk = chunk?;
+        read_bytes += chunk.len() as u64;
+        if read_bytes > max_bytes {
+            exceeded = true;
+            return Err(anyhow::Error::new(FileTooLarge { max_bytes }));
+        }
+
+        Ok(chunk)
+    }))
+}
+
+/// Generates the key a new attachment's contents are stored under. Keys are random so they can't be guessed
+/// and two uploads of files with the same name never collide.
+fn new_storage_key(task_id: i32) -> String {
+    let mut key = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut key);
+
+    format!("tasks/{task_id}/{}", hex::encode(key))
+}
+
+/// The set of driven ports invoked by task attachment business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// An external system which stores the details of files attached to tasks
+    pub trait AttachmentStore {
+        /// Record a file attached to a task whose contents were stored under `storage_key`
+        async fn add_attachment(
+            &self,
+            task_id: i32,
+            uploader_user_id: i32,
+            new_attachment: &NewAttachment,
+            storage_key: &str,
+            size_bytes: i64,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Attachment, anyhow::Error>;
+
+        /// Retrieve a single attachment by its ID
+        async fn attachment_by_id(
+            &self,
+            attachment_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<Attachment>, anyhow::Error>;
+
+        /// Retrieve the files attached to a task, oldest first
+        async fn attachments_for_task(
+            &self,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<Attachment>, anyhow::Error>;
+
+        /// Remove the record of an attachment, returning whether it existed
+        async fn delete_attachment(
+            &self,
+            attachment_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error>;
+    }
+
+    /// An external system which stores the contents of files, such as a directory or an S3 bucket. It's
+    /// reached through [ExternalConnectivity::blob_store].
+    pub trait BlobStore: Sync {
+        /// Store a file under the given key, returning how many bytes it holds. If reading the contents
+        /// fails, nothing is left behind under the key and the error is returned as-is.
+        async fn put_blob(&self, key: &str, content: ByteStream) -> Result<u64, anyhow::Error>;
+
+        /// Read the file stored under the given key, or [None] if there isn't one
+        async fn get_blob(&self, key: &str) -> Result<Option<ByteStream>, anyhow::Error>;
+
+        /// Remove the file stored under the given key. Removing a file which doesn't exist succeeds.
+        async fn delete_blob(&self, key: &str) -> Result<(), anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for attaching files to tasks
+pub mod driving_ports {
+    use super::*;
+
+    #[derive(Debug, Error)]
+    /// The set of things that can go wrong while working with task attachments
+    pub enum AttachmentError {
+        #[error("The specified user did not exist.")]
+        UserDoesNotExist,
+        #[error("The specified task did not exist.")]
+        TaskDoesNotExist,
+        #[error("The specified attachment did not exist.")]
+        AttachmentDoesNotExist,
+        #[error("The file is larger than the {max_bytes} byte limit.")]
+        FileTooLarge { max_bytes: u64 },
+        #[error("Files of type {0} can't be attached to tasks.")]
+        UnsupportedContentType(String),
+        #[error("Only the uploader of an attachment or the owner of its task may delete it.")]
+        NotUploader,
+        #[error(transparent)]
+        Forbidden(#[from] domain::access::AccessDenied),
+        #[error(transparent)]
+        PortError(#[from] anyhow::Error),
+    }
+
+    impl From<domain::user::UserExistsErr> for AttachmentError {
+        fn from(value: domain::user::UserExistsErr) -> Self {
+            match value {
+                domain::user::UserExistsErr::UserDoesNotExist(user_id) => {
+                    error!(
+                        "User {} didn't exist when working with attachments.",
+                        user_id
+                    );
+                    AttachmentError::UserDoesNotExist
+                }
+                domain::user::UserExistsErr::PortError(err) => {
+                    AttachmentError::from(err.context("Working with attachments"))
+                }
+            }
+        }
+    }
+
+    impl From<domain::todo::driving_ports::TaskError> for AttachmentError {
+        fn from(value: domain::todo::driving_ports::TaskError) -> Self {
+            use domain::todo::driving_ports::TaskError;
+            match value {
+                TaskError::UserDoesNotExist => AttachmentError::UserDoesNotExist,
+                TaskError::TaskDoesNotExist => AttachmentError::TaskDoesNotExist,
+                TaskError::Forbidden(denied) => AttachmentError::Forbidden(denied),
+                TaskError::PortError(err) => AttachmentError::PortError(err),
+                other => AttachmentError::PortError(other.into()),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::items_after_test_module)]
+    mod attachment_error_clone {
+        use super::AttachmentError;
+        use anyhow::anyhow;
+
+        // Implements clone for AttachmentError so it can be used in mocks during API tests
+        impl Clone for AttachmentError {
+            fn clone(&self) -> Self {
+                match self {
+                    Self::UserDoesNotExist => Self::UserDoesNotExist,
+                    Self::TaskDoesNotExist => Self::TaskDoesNotExist,
+                    Self::AttachmentDoesNotExist => Self::AttachmentDoesNotExist,
+                    Self::FileTooLarge { max_bytes } => Self::FileTooLarge {
+                        max_bytes: *max_bytes,
+                    },
+                    Self::UnsupportedContentType(content_type) => {
+                        Self::UnsupportedContentType(content_type.clone())
+                    }
+                    Self::NotUploader => Self::NotUploader,
+                    Self::Forbidden(denied) => Self::Forbidden(denied.clone()),
+                    Self::PortError(err) => Self::PortError(anyhow!(format!("{}", err))),
+                }
+            }
+        }
+    }
+
+    /// The driving port which lets users attach files to tasks they can see. Every operation acts on behalf
+    /// of a user, who must be able to see the task, and only a caller who may manage that user's tasks may
+    /// act for them.
+    pub trait AttachmentPort {
+        /// Attach a file to a task as the given user, streaming its contents into blob storage
+        #[allow(clippy::too_many_arguments)]
+        async fn upload_attachment(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            new_attachment: &NewAttachment,
+            content: ByteStream,
+            limits: &AttachmentLimits,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            attachment_store: &impl driven_ports::AttachmentStore,
+        ) -> Result<Attachment, AttachmentError>;
+
+        /// Retrieve the files attached to a task
+        #[allow(clippy::too_many_arguments)]
+        async fn attachments_for_task(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            attachment_store: &impl driven_ports::AttachmentStore,
+        ) -> Result<Vec<Attachment>, AttachmentError>;
+
+        /// Retrieve an attachment along with its contents
+        #[allow(clippy::too_many_arguments)]
+        async fn download_attachment(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            attachment_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            attachment_store: &impl driven_ports::AttachmentStore,
+        ) -> Result<(Attachment, ByteStream), AttachmentError>;
+
+        /// Remove an attachment and its contents. Uploaders may delete their own attachments, and task
+        /// owners may delete any attachment on their tasks.
+        #[allow(clippy::too_many_arguments)]
+        async fn delete_attachment(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            attachment_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            attachment_store: &impl driven_ports::AttachmentStore,
+        ) -> Result<(), AttachmentError>;
+    }
+}
+
+/// Looks up an attachment, treating attachments on other tasks as though they don't exist
+async fn attachment_on_task(
+    task_id: i32,
+    attachment_id: i32,
+    ext_cxn: &mut impl ExternalConnectivity,
+    attachment_store: &impl AttachmentStore,
+) -> Result<Attachment, AttachmentError> {
+    attachment_store
+        .attachment_by_id(attachment_id, &mut *ext_cxn)
+        .await
+        .context("looking up an attachment")?
+        .filter(|attachment| attachment.task_id == task_id)
+        .ok_or(AttachmentError::AttachmentDoesNotExist)
+}
+
+/// Implementation of the driving port for attaching files to tasks
+pub struct AttachmentService;
+
+impl driving_ports::AttachmentPort for AttachmentService {
+    async fn upload_attachment(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        new_attachment: &NewAttachment,
+        content: ByteStream,
+        limits: &AttachmentLimits,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        attachment_store: &impl AttachmentStore,
+    ) -> Result<Attachment, AttachmentError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+        if !limits.allows_content_type(&new_attachment.content_type) {
+            return Err(AttachmentError::UnsupportedContentType(
+                new_attachment.content_type.clone(),
+            ));
+        }
+
+        let storage_key = new_storage_key(task_id);
+        let put_result = ext_cxn
+            .blob_store()
+            .put_blob(&storage_key, limit_size(content, limits.max_bytes))
+            .await;
+        let size_bytes = match put_result {
+            Ok(size_bytes) => size_bytes,
+            Err(err) => {
+                return match err.downcast_ref::<FileTooLarge>() {
+                    Some(too_large) => Err(AttachmentError::FileTooLarge {
+                        max_bytes: too_large.max_bytes,
+                    }),
+                    None => Err(AttachmentError::from(
+                        err.context("storing an attachment's contents"),
+                    )),
+                };
+            }
+        };
+
+        let add_result = attachment_store
+            .add_attachment(
+                task_id,
+                user_id,
+                new_attachment,
+                &storage_key,
+                size_bytes as i64,
+                &mut *ext_cxn,
+            )
+            .await;
+        match add_result {
+            Ok(attachment) => Ok(attachment),
+            Err(err) => {
+                // Don't leave contents behind which nothing refers to
+                if let Err(delete_err) = ext_cxn.blob_store().delete_blob(&storage_key).await {
+                    error!("Could not clean up contents of unsaved attachment {storage_key}: {delete_err}");
+                }
+                Err(AttachmentError::from(
+                    err.context("recording an attachment"),
+                ))
+            }
+        }
+    }
+
+    async fn attachments_for_task(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        attachment_store: &impl AttachmentStore,
+    ) -> Result<Vec<Attachment>, AttachmentError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+
+        let attachments = attachment_store
+            .attachments_for_task(task_id, &mut *ext_cxn)
+            .await
+            .context("fetching the attachments on a task")?;
+        Ok(attachments)
+    }
+
+    async fn download_attachment(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        attachment_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        attachment_store: &impl AttachmentStore,
+    ) -> Result<(Attachment, ByteStream), AttachmentError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+
+        let attachment =
+            attachment_on_task(task_id, attachment_id, &mut *ext_cxn, attachment_store).await?;
+        let content = ext_cxn
+            .blob_store()
+            .get_blob(&attachment.storage_key)
+            .await
+            .context("reading an attachment's contents")?
+            .ok_or_else(|| {
+                anyhow!(
+                    "The contents of attachment {} are missing from blob storage",
+                    attachment.id
+                )
+            })?;
+
+        Ok((attachment, content))
+    }
+
+    async fn delete_attachment(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        attachment_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        attachment_store: &impl AttachmentStore,
+    ) -> Result<(), AttachmentError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+
+        let attachment =
+            attachment_on_task(task_id, attachment_id, &mut *ext_cxn, attachment_store).await?;
+        if attachment.uploader_user_id != user_id {
+            let task_owner = task_read
+                .task_owner(task_id, &mut *ext_cxn)
+                .await
+                .context("looking up the owner of a task with attachments")?;
+            if task_owner != Some(user_id) {
+                return Err(AttachmentError::NotUploader);
+            }
+        }
+
+        let deleted = attachment_store
+            .delete_attachment(attachment_id, &mut *ext_cxn)
+            .await
+            .context("deleting an attachment")?;
+        if !deleted {
+            return Err(AttachmentError::AttachmentDoesNotExist);
+        }
+
+        // The attachment is already gone, so failing to remove its contents only wastes space
+        if let Err(err) = ext_cxn
+            .blob_store()
+            .delete_blob(&attachment.storage_key)
+            .await
+        {
+            error!(
+                "Could not remove the contents of deleted attachment {}: {err}",
+                attachment.id
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::AttachmentPort;
+    use super::test_util::{content_of, InMemoryAttachmentPersistence};
+    use super::*;
+    use crate::domain::test_util::Connectivity;
+    use crate::domain::todo::test_util::{InMemoryUserTaskPersistence, NewTaskWithOwner};
+    use crate::domain::todo::NewTask;
+    use crate::domain::user::test_util::{as_user, two_users};
+    use crate::external_connections::test_util::FakeExternalConnectivity;
+    use futures::stream;
+    use speculoos::prelude::*;
+    use std::sync::RwLock;
+
+    const OWNER: i32 = 1;
+    const OTHER_USER: i32 = 2;
+
+    /// Gives the owner a single task
+    fn owners_task() -> RwLock<InMemoryUserTaskPersistence> {
+        RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+            NewTaskWithOwner {
+                owner: OWNER,
+                task: NewTask {
+                    description: "File the expense report".to_owned(),
+                    due_date: None,
+                    recurrence: None,
+                    assignee_user_id: None,
+                },
+            },
+        ]))
+    }
+
+    fn receipt() -> NewAttachment {
+        NewAttachment {
+            file_name: "receipt.png".to_owned(),
+            content_type: "image/png".to_owned(),
+        }
+    }
+
+    /// Splits the given contents into several chunks, as an upload would arrive
+    fn chunked(content: &'static [u8]) -> ByteStream {
+        Box::pin(stream::iter(
+            content
+                .chunks(4)
+                .map(|chunk| Ok(Bytes::from_static(chunk)))
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    async fn upload(
+        ext_cxn: &mut FakeExternalConnectivity,
+        task_persist: &RwLock<InMemoryUserTaskPersistence>,
+        attachment_persist: &RwLock<InMemoryAttachmentPersistence>,
+        content: ByteStream,
+        limits: &AttachmentLimits,
+    ) -> Result<Attachment, AttachmentError> {
+        AttachmentService
+            .upload_attachment(
+                &as_user(OWNER),
+                OWNER,
+                1,
+                &receipt(),
+                content,
+                limits,
+                ext_cxn,
+                &two_users(),
+                task_persist,
+                attachment_persist,
+            )
+            .await
+    }
+
+    mod limits {
+        use super::*;
+
+        #[test]
+        fn matches_exact_and_wildcard_types() {
+            let limits = AttachmentLimits {
+                max_bytes: 10,
+                content_types: AttachmentLimits::parse_content_types("image/*, application/pdf")
+                    .unwrap(),
+            };
+
+            assert!(limits.allows_content_type("image/png"));
+            assert!(limits.allows_content_type("Application/PDF"));
+            assert!(limits.allows_content_type("image/svg+xml; charset=utf-8"));
+            assert!(!limits.allows_content_type("application/zip"));
+            assert!(!limits.allows_content_type("imagepng"));
+        }
+
+        #[test]
+        fn rejects_malformed_types() {
+            assert_that!(AttachmentLimits::parse_content_types("image/png,pdf")).is_err();
+        }
+    }
+
+    mod upload_attachment {
+        use super::*;
+
+        #[tokio::test]
+        async fn streams_content_into_blob_storage() {
+            let mut ext_cxn = FakeExternalConnectivity::new();
+            let task_persist = owners_task();
+            let attachment_persist = InMemoryAttachmentPersistence::new_locked();
+
+            let upload_result = upload(
+                &mut ext_cxn,
+                &task_persist,
+                &attachment_persist,
+                chunked(b"not really a png"),
+                &AttachmentLimits::default(),
+            )
+            .await;
+
+            let attachment = upload_result.expect("Could not upload attachment");
+            assert_eq!(16, attachment.size_bytes);
+            assert_eq!(OWNER, attachment.uploader_user_id);
+            assert!(attachment.storage_key.starts_with("tasks/1/"));
+            assert_eq!(
+                Some(b"not really a png".to_vec()),
+                content_of(&ext_cxn, &attachment.storage_key)
+            );
+        }
+
+        #[tokio::test]
+        async fn stops_reading_files_over_the_limit() {
+            let mut ext_cxn = FakeExternalConnectivity::new();
+            let task_persist = owners_task();
+            let attachment_persist = InMemoryAttachmentPersistence::new_locked();
+
+            let upload_result = upload(
+                &mut ext_cxn,
+                &task_persist,
+                &attachment_persist,
+                chunked(b"this file is too big"),
+                &AttachmentLimits {
+                    max_bytes: 10,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            assert!(matches!(
+                upload_result,
+                Err(AttachmentError::FileTooLarge { max_bytes: 10 })
+            ));
+            assert_that!(attachment_persist.read().unwrap().attachments).is_empty();
+            assert_that!(ext_cxn.blob_store().blob_count()).is_equal_to(0);
+        }
+
+        #[tokio::test]
+        async fn rejects_unsupported_types() {
+            let mut ext_cxn = FakeExternalConnectivity::new();
+            let task_persist = owners_task();
+            let attachment_persist = InMemoryAttachmentPersistence::new_locked();
+
+            let upload_result = AttachmentService
+                .upload_attachment(
+                    &as_user(OWNER),
+                    OWNER,
+                    1,
+                    &NewAttachment {
+                        file_name: "setup.exe".to_owned(),
+                        content_type: "application/x-msdownload".to_owned(),
+                    },
+                    chunked(b"MZ"),
+                    &AttachmentLimits::default(),
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &attachment_persist,
+                )
+                .await;
+            assert!(matches!(
+                upload_result,
+                Err(AttachmentError::UnsupportedContentType(_))
+            ));
+        }
+
+        #[tokio::test]
+        async fn fails_for_task_user_cannot_see() {
+            let mut ext_cxn = FakeExternalConnectivity::new();
+            let task_persist = owners_task();
+            let attachment_persist = InMemoryAttachmentPersistence::new_locked();
+
+            let upload_result = AttachmentService
+                .upload_attachment(
+                    &as_user(OTHER_USER),
+                    OTHER_USER,
+                    1,
+                    &receipt(),
+                    chunked(b"sneaky"),
+                    &AttachmentLimits::default(),
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &attachment_persist,
+                )
+                .await;
+            assert!(matches!(
+                upload_result,
+                Err(AttachmentError::TaskDoesNotExist)
+            ));
+        }
+
+        #[tokio::test]
+        async fn removes_contents_when_details_cannot_be_saved() {
+            let mut ext_cxn = FakeExternalConnectivity::new();
+            let task_persist = owners_task();
+            let attachment_persist = InMemoryAttachmentPersistence::new_locked();
+            attachment_persist.write().unwrap().connected = Connectivity::Disconnected;
+
+            let upload_result = upload(
+                &mut ext_cxn,
+                &task_persist,
+                &attachment_persist,
+                chunked(b"not really a png"),
+                &AttachmentLimits::default(),
+            )
+            .await;
+
+            assert!(matches!(upload_result, Err(AttachmentError::PortError(_))));
+            assert_that!(ext_cxn.blob_store().blob_count()).is_equal_to(0);
+        }
+    }
+
+    mod download_attachment {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_uploaded_content() {
+            let mut ext_cxn = FakeExternalConnectivity::new();
+            let task_persist = owners_task();
+            let attachment_persist = InMemoryAttachmentPersistence::new_locked();
+            let uploaded = upload(
+                &mut ext_cxn,
+                &task_persist,
+                &attachment_persist,
+                chunked(b"not really a png"),
+                &AttachmentLimits::default(),
+            )
+            .await
+            .expect("Could not upload attachment");
+
+            let (attachment, content) = AttachmentService
+                .download_attachment(
+                    &as_user(OWNER),
+                    OWNER,
+                    1,
+                    uploaded.id,
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &attachment_persist,
+                )
+                .await
+                .expect("Could not download attachment");
+
+            assert_eq!(uploaded, attachment);
+            let downloaded: Vec<Bytes> = content
+                .map(|chunk| chunk.expect("Could not read chunk"))
+                .collect()
+                .await;
+            assert_eq!(b"not really a png".to_vec(), downloaded.concat());
+        }
+    }
+
+    mod delete_attachment {
+        use super::*;
+
+        #[tokio::test]
+        async fn removes_details_and_contents() {
+            let mut ext_cxn = FakeExternalConnectivity::new();
+            let task_persist = owners_task();
+            let attachment_persist = InMemoryAttachmentPersistence::new_locked();
+            let uploaded = upload(
+                &mut ext_cxn,
+                &task_persist,
+                &attachment_persist,
+                chunked(b"not really a png"),
+                &AttachmentLimits::default(),
+            )
+            .await
+            .expect("Could not upload attachment");
+
+            let delete_result = AttachmentService
+                .delete_attachment(
+                    &as_user(OWNER),
+                    OWNER,
+                    1,
+                    uploaded.id,
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &attachment_persist,
+                )
+                .await;
+
+            assert_that!(delete_result).is_ok();
+            assert_that!(attachment_persist.read().unwrap().attachments).is_empty();
+            assert_that!(content_of(&ext_cxn, &uploaded.storage_key)).is_none();
+        }
+
+        #[tokio::test]
+        async fn fails_for_attachment_on_another_task() {
+            let mut ext_cxn = FakeExternalConnectivity::new();
+            let task_persist = owners_task();
+            let attachment_persist = InMemoryAttachmentPersistence::new_locked();
+            let uploaded = upload(
+                &mut ext_cxn,
+                &task_persist,
+                &attachment_persist,
+                chunked(b"not really a png"),
+                &AttachmentLimits::default(),
+            )
+            .await
+            .expect("Could not upload attachment");
+            attachment_persist.write().unwrap().attachments[0].task_id = 2;
+
+            let delete_result = AttachmentService
+                .delete_attachment(
+                    &as_user(OWNER),
+                    OWNER,
+                    1,
+                    uploaded.id,
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &attachment_persist,
+                )
+                .await;
+            assert!(matches!(
+                delete_result,
+                Err(AttachmentError::AttachmentDoesNotExist)
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driving_ports::AttachmentPort;
+    use super::*;
+    use crate::domain::test_util::{Connectivity, FakeImplementation};
+    use crate::external_connections::test_util::FakeExternalConnectivity;
+    use futures::stream;
+    use std::collections::HashMap;
+    use std::sync::{Mutex, RwLock};
+
+    /// An in-memory store of the details of task attachments
+    pub struct InMemoryAttachmentPersistence {
+        pub attachments: Vec<Attachment>,
+        pub connected: Connectivity,
+    }
+
+    impl InMemoryAttachmentPersistence {
+        /// Constructor for InMemoryAttachmentPersistence
+        pub fn new() -> InMemoryAttachmentPersistence {
+            InMemoryAttachmentPersistence {
+                attachments: Vec::new(),
+                connected: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for InMemoryAttachmentPersistence which wraps it in an RwLock so it can be
+        /// immediately used as a driven port
+        pub fn new_locked() -> RwLock<InMemoryAttachmentPersistence> {
+            RwLock::new(Self::new())
+        }
+    }
+
+    impl AttachmentStore for RwLock<InMemoryAttachmentPersistence> {
+        async fn add_attachment(
+            &self,
+            task_id: i32,
+            uploader_user_id: i32,
+            new_attachment: &NewAttachment,
+            storage_key: &str,
+            size_bytes: i64,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Attachment, anyhow::Error> {
+            let mut persistence = self.write().expect("attachment persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let attachment = Attachment {
+                id: persistence
+                    .attachments
+                    .last()
+                    .map(|attachment| attachment.id + 1)
+                    .unwrap_or(1),
+                task_id,
+                uploader_user_id,
+                file_name: new_attachment.file_name.clone(),
+                content_type: new_attachment.content_type.clone(),
+                size_bytes,
+                storage_key: storage_key.to_owned(),
+                created_at: Utc::now(),
+            };
+            persistence.attachments.push(attachment.clone());
+            Ok(attachment)
+        }
+
+        async fn attachment_by_id(
+            &self,
+            attachment_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<Attachment>, anyhow::Error> {
+            let persistence = self.read().expect("attachment persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            Ok(persistence
+                .attachments
+                .iter()
+                .find(|attachment| attachment.id == attachment_id)
+                .cloned())
+        }
+
+        async fn attachments_for_task(
+            &self,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<Attachment>, anyhow::Error> {
+            let persistence = self.read().expect("attachment persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            Ok(persistence
+                .attachments
+                .iter()
+                .filter(|attachment| attachment.task_id == task_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn delete_attachment(
+            &self,
+            attachment_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error> {
+            let mut persistence = self.write().expect("attachment persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let attachment_count = persistence.attachments.len();
+            persistence
+                .attachments
+                .retain(|attachment| attachment.id != attachment_id);
+            Ok(persistence.attachments.len() < attachment_count)
+        }
+    }
+
+    /// A blob store which keeps files in memory, used by [FakeExternalConnectivity]
+    pub struct InMemoryBlobStore {
+        blobs: Mutex<HashMap<String, Vec<u8>>>,
+    }
+
+    impl InMemoryBlobStore {
+        /// Constructor for InMemoryBlobStore
+        pub fn new() -> InMemoryBlobStore {
+            InMemoryBlobStore {
+                blobs: Mutex::new(HashMap::new()),
+            }
+        }
+
+        /// Returns how many files are stored
+        pub fn blob_count(&self) -> usize {
+            self.blobs.lock().expect("blob store mutex poisoned").len()
+        }
+
+        /// Returns the contents of the file stored under the given key, if there is one
+        pub fn blob(&self, key: &str) -> Option<Vec<u8>> {
+            self.blobs
+                .lock()
+                .expect("blob store mutex poisoned")
+                .get(key)
+                .cloned()
+        }
+    }
+
+    impl BlobStore for InMemoryBlobStore {
+        async fn put_blob(&self, key: &str, mut content: ByteStream) -> Result<u64, anyhow::Error> {
+            let mut blob = Vec::new();
+            while let Some(chunk) = content.next().await {
+                blob.extend_from_slice(&chunk?);
+            }
+
+            let size = blob.len() as u64;
+            self.blobs
+                .lock()
+                .expect("blob store mutex poisoned")
+                .insert(key.to_owned(), blob);
+            Ok(size)
+        }
+
+        async fn get_blob(&self, key: &str) -> Result<Option<ByteStream>, anyhow::Error> {
+            Ok(self.blob(key).map(|blob| -> ByteStream {
+                Box::pin(stream::once(async move { Ok(Bytes::from(blob)) }))
+            }))
+        }
+
+        async fn delete_blob(&self, key: &str) -> Result<(), anyhow::Error> {
+            self.blobs
+                .lock()
+                .expect("blob store mutex poisoned")
+                .remove(key);
+            Ok(())
+        }
+    }
+
+    /// Returns the contents stored under the given key in the fake's blob store
+    pub fn content_of(ext_cxn: &FakeExternalConnectivity, key: &str) -> Option<Vec<u8>> {
+        ext_cxn.blob_store().blob(key)
+    }
+
+    /// What [MockAttachmentService] returns when asked to download an attachment
+    type DownloadResult = Result<(Attachment, Vec<u8>), AttachmentError>;
+
+    /// A mock of AttachmentService for use in API tests. Uploaded contents are read into
+    /// [MockAttachmentService::uploaded_content] so tests can check what was streamed to the service.
+    pub struct MockAttachmentService {
+        pub upload_attachment_result:
+            FakeImplementation<(i32, i32, NewAttachment), Result<Attachment, AttachmentError>>,
+        pub uploaded_content: Vec<u8>,
+        pub attachments_for_task_result:
+            FakeImplementation<(i32, i32), Result<Vec<Attachment>, AttachmentError>>,
+        pub download_attachment_result: FakeImplementation<(i32, i32, i32), DownloadResult>,
+        pub delete_attachment_result:
+            FakeImplementation<(i32, i32, i32), Result<(), AttachmentError>>,
+    }
+
+    impl MockAttachmentService {
+        /// Constructor for MockAttachmentService
+        pub fn new() -> MockAttachmentService {
+            MockAttachmentService {
+                upload_attachment_result: FakeImplementation::new(),
+                uploaded_content: Vec::new(),
+                attachments_for_task_result: FakeImplementation::new(),
+                download_attachment_result: FakeImplementation::new(),
+                delete_attachment_result: FakeImplementation::new(),
+            }
+        }
+
+        /// Constructs a new MockAttachmentService, allowing for configuration of mocks
+        /// in the builder function before the mock is wrapped in a Mutex for use in API tests
+        pub fn build_locked(builder: impl FnOnce(&mut Self)) -> Mutex<Self> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Mutex::new(new_svc)
+        }
+    }
+
+    impl AttachmentPort for Mutex<MockAttachmentService> {
+        async fn upload_attachment(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            new_attachment: &NewAttachment,
+            content: ByteStream,
+            limits: &AttachmentLimits,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _attachment_store: &impl AttachmentStore,
+        ) -> Result<Attachment, AttachmentError> {
+            let mut uploaded_content = Vec::new();
+            let mut limited_content = limit_size(content, limits.max_bytes);
+            while let Some(chunk) = limited_content.next().await {
+                uploaded_content.extend_from_slice(&chunk?);
+            }
+
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self.uploaded_content = uploaded_content;
+            locked_self.upload_attachment_result.save_arguments((
+                user_id,
+                task_id,
+                new_attachment.clone(),
+            ));
+            locked_self.upload_attachment_result.return_value_result()
+        }
+
+        async fn attachments_for_task(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _attachment_store: &impl AttachmentStore,
+        ) -> Result<Vec<Attachment>, AttachmentError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .attachments_for_task_result
+                .save_arguments((user_id, task_id));
+            locked_self
+                .attachments_for_task_result
+                .return_value_result()
+        }
+
+        async fn download_attachment(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            attachment_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _attachment_store: &impl AttachmentStore,
+        ) -> Result<(Attachment, ByteStream), AttachmentError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self.download_attachment_result.save_arguments((
+                user_id,
+                task_id,
+                attachment_id,
+            ));
+            let (attachment, content) = locked_self
+                .download_attachment_result
+                .return_value_result()?;
+
+            let content: ByteStream =
+                Box::pin(stream::once(async move { Ok(Bytes::from(content)) }));
+            Ok((attachment, content))
+        }
+
+        async fn delete_attachment(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            attachment_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _attachment_store: &impl AttachmentStore,
+        ) -> Result<(), AttachmentError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .delete_attachment_result
+                .save_arguments((user_id, task_id, attachment_id));
+            locked_self.delete_attachment_result.return_value_result()
+        }
+    }
+}
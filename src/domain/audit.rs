@@ -0,0 +1,430 @@
+use crate::domain::audit::driven_ports::AuditLogReader;
+use crate::external_connections::ExternalConnectivity;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+
+/// How many audit entries are returned from a query if the caller doesn't ask for a number
+pub const DEFAULT_AUDIT_ENTRIES: i64 = 50;
+/// The most audit entries returned from a single query
+pub const MAX_AUDIT_ENTRIES: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kinds of changes recorded in the audit log
+pub enum AuditAction {
+    Create,
+    Update,
+    Complete,
+    /// A task was given to a different assignee
+    Assign,
+    /// A task was moved to the trash
+    Delete,
+    /// A task was taken back out of the trash
+    Restore,
+    /// A task was permanently removed after sitting in the trash
+    Purge,
+}
+
+impl AuditAction {
+    /// The name the action is stored under, such as "create"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Complete => "complete",
+            Self::Assign => "assign",
+            Self::Delete => "delete",
+            Self::Restore => "restore",
+            Self::Purge => "purge",
+        }
+    }
+
+    /// Looks up an action by its name, returning [None] if no action has that name
+    pub fn from_name(name: &str) -> Option<AuditAction> {
+        match name {
+            "create" => Some(Self::Create),
+            "update" => Some(Self::Update),
+            "complete" => Some(Self::Complete),
+            "assign" => Some(Self::Assign),
+            "delete" => Some(Self::Delete),
+            "restore" => Some(Self::Restore),
+            "purge" => Some(Self::Purge),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kinds of entities whose changes are recorded in the audit log
+pub enum AuditEntityType {
+    Task,
+    User,
+}
+
+impl AuditEntityType {
+    /// The name the entity type is stored under, such as "task"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Task => "task",
+            Self::User => "user",
+        }
+    }
+
+    /// Looks up an entity type by its name, returning [None] if no entity type has that name
+    pub fn from_name(name: &str) -> Option<AuditEntityType> {
+        match name {
+            "task" => Some(Self::Task),
+            "user" => Some(Self::User),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(test, derive(Clone))]
+/// A single change recorded in the audit log
+pub struct AuditEntry {
+    pub id: i64,
+    /// The user who made the change, if the request identified one
+    pub actor_user_id: Option<i32>,
+    pub action: AuditAction,
+    pub entity_type: AuditEntityType,
+    pub entity_id: i32,
+    /// The entity before the change. Not present when the entity was created.
+    pub before: Option<serde_json::Value>,
+    /// The entity after the change. Not present when the entity was purged.
+    pub after: Option<serde_json::Value>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Narrows down which audit entries are returned from a query. Entries are always returned newest first.
+pub struct AuditFilter {
+    pub entity_type: Option<AuditEntityType>,
+    pub entity_id: Option<i32>,
+    pub actor_user_id: Option<i32>,
+    /// Only include entries recorded at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only include entries recorded before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Only include entries with a lower ID than this one, used to page through the log
+    pub before_id: Option<i64>,
+    /// The most entries to return
+    pub limit: i64,
+}
+
+impl Default for AuditFilter {
+    fn default() -> Self {
+        AuditFilter {
+            entity_type: None,
+            entity_id: None,
+            actor_user_id: None,
+            since: None,
+            until: None,
+            before_id: None,
+            limit: DEFAULT_AUDIT_ENTRIES,
+        }
+    }
+}
+
+/// Contains the driven ports invoked by the audit business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// An external system which stores the audit log. Entries are recorded by the writers of the
+    /// entities being audited, so this port only reads them.
+    pub trait AuditLogReader: Sync {
+        /// Retrieve the newest entries matching the filter, returning at most `filter.limit` entries
+        async fn entries(
+            &self,
+            filter: &AuditFilter,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<AuditEntry>, anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for reading the audit log
+pub mod driving_ports {
+    use super::*;
+
+    /// The driving port which exposes audit business logic to driving adapters
+    pub trait AuditPort {
+        /// Retrieve the newest audit entries matching the filter
+        async fn audit_entries(
+            &self,
+            filter: &AuditFilter,
+            ext_cxn: &mut impl ExternalConnectivity,
+            audit_read: &impl driven_ports::AuditLogReader,
+        ) -> Result<Vec<AuditEntry>, anyhow::Error>;
+    }
+}
+
+/// Implementation of the driving port for the audit log
+pub struct AuditService;
+
+impl driving_ports::AuditPort for AuditService {
+    async fn audit_entries(
+        &self,
+        filter: &AuditFilter,
+        ext_cxn: &mut impl ExternalConnectivity,
+        audit_read: &impl AuditLogReader,
+    ) -> Result<Vec<AuditEntry>, anyhow::Error> {
+        let empty_range =
+            matches!((filter.since, filter.until), (Some(since), Some(until)) if since >= until);
+        if empty_range {
+            return Ok(Vec::new());
+        }
+
+        let filter = AuditFilter {
+            limit: filter.limit.clamp(1, MAX_AUDIT_ENTRIES),
+            ..filter.clone()
+        };
+        audit_read
+            .entries(&filter, &mut *ext_cxn)
+            .await
+            .context("Reading the audit log")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::AuditPort;
+    use super::test_util::*;
+    use super::*;
+    use crate::domain::test_util::Connectivity;
+    use crate::external_connections;
+    use chrono::TimeZone;
+    use speculoos::prelude::*;
+
+    #[test]
+    fn names_round_trip() {
+        for action in [
+            AuditAction::Create,
+            AuditAction::Update,
+            AuditAction::Complete,
+            AuditAction::Assign,
+            AuditAction::Delete,
+        ] {
+            assert_eq!(Some(action), AuditAction::from_name(action.name()));
+        }
+        for entity_type in [AuditEntityType::Task, AuditEntityType::User] {
+            assert_eq!(
+                Some(entity_type),
+                AuditEntityType::from_name(entity_type.name())
+            );
+        }
+        assert_eq!(None, AuditAction::from_name("explode"));
+        assert_eq!(None, AuditEntityType::from_name("planet"));
+    }
+
+    mod audit_entries {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_matching_entries_newest_first() {
+            let audit_log = InMemoryAuditLog::new_locked_with_entries(vec![
+                entry(1, AuditEntityType::Task, 4),
+                entry(2, AuditEntityType::User, 4),
+                entry(3, AuditEntityType::Task, 4),
+                entry(4, AuditEntityType::Task, 5),
+            ]);
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let filter = AuditFilter {
+                entity_type: Some(AuditEntityType::Task),
+                entity_id: Some(4),
+                ..AuditFilter::default()
+            };
+
+            let entries = AuditService
+                .audit_entries(&filter, &mut ext_cxn, &audit_log)
+                .await
+                .expect("Could not read the audit log");
+
+            let entry_ids: Vec<i64> = entries.iter().map(|entry| entry.id).collect();
+            assert_eq!(vec![3, 1], entry_ids);
+        }
+
+        #[tokio::test]
+        async fn limits_number_of_entries() {
+            let audit_log = InMemoryAuditLog::new_locked_with_entries(
+                (1..=MAX_AUDIT_ENTRIES + 5)
+                    .map(|id| entry(id, AuditEntityType::Task, 1))
+                    .collect(),
+            );
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            for (requested, expected) in
+                [(0, 1), (3, 3), (MAX_AUDIT_ENTRIES + 1, MAX_AUDIT_ENTRIES)]
+            {
+                let filter = AuditFilter {
+                    limit: requested,
+                    ..AuditFilter::default()
+                };
+                let entries = AuditService
+                    .audit_entries(&filter, &mut ext_cxn, &audit_log)
+                    .await
+                    .expect("Could not read the audit log");
+
+                assert_eq!(expected as usize, entries.len());
+            }
+        }
+
+        #[tokio::test]
+        async fn skips_reading_for_empty_time_range() {
+            let mut audit_log = InMemoryAuditLog::new();
+            audit_log.connectivity = Connectivity::Disconnected;
+            let audit_log = std::sync::RwLock::new(audit_log);
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let moment = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+            let filter = AuditFilter {
+                since: Some(moment),
+                until: Some(moment),
+                ..AuditFilter::default()
+            };
+
+            let entries = AuditService
+                .audit_entries(&filter, &mut ext_cxn, &audit_log)
+                .await;
+            assert_that!(entries).is_ok().is_empty();
+        }
+
+        #[tokio::test]
+        async fn propagates_port_error() {
+            let mut audit_log = InMemoryAuditLog::new();
+            audit_log.connectivity = Connectivity::Disconnected;
+            let audit_log = std::sync::RwLock::new(audit_log);
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let entries = AuditService
+                .audit_entries(&AuditFilter::default(), &mut ext_cxn, &audit_log)
+                .await;
+            assert_that!(entries).is_err();
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driven_ports::*;
+    use super::driving_ports::AuditPort;
+    use super::*;
+    use crate::domain::test_util::{Connectivity, FakeImplementation};
+    use chrono::TimeZone;
+    use serde_json::json;
+    use std::sync::{Mutex, RwLock};
+
+    /// Creates an entry recording that a user updated an entity, recorded `id` minutes after a fixed time
+    pub fn entry(id: i64, entity_type: AuditEntityType, entity_id: i32) -> AuditEntry {
+        AuditEntry {
+            id,
+            actor_user_id: Some(1),
+            action: AuditAction::Update,
+            entity_type,
+            entity_id,
+            before: Some(json!({ "id": entity_id, "item_desc": "Before" })),
+            after: Some(json!({ "id": entity_id, "item_desc": "After" })),
+            recorded_at: Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap()
+                + chrono::Duration::minutes(id),
+        }
+    }
+
+    /// A fake audit log which keeps its entries in memory
+    pub struct InMemoryAuditLog {
+        pub entries: Vec<AuditEntry>,
+        pub connectivity: Connectivity,
+    }
+
+    impl InMemoryAuditLog {
+        /// Constructor for InMemoryAuditLog
+        pub fn new() -> InMemoryAuditLog {
+            InMemoryAuditLog {
+                entries: Vec::new(),
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for InMemoryAuditLog which starts with the given entries and wraps it in an RwLock
+        /// so it can be immediately used as a driven port
+        pub fn new_locked_with_entries(entries: Vec<AuditEntry>) -> RwLock<InMemoryAuditLog> {
+            RwLock::new(InMemoryAuditLog {
+                entries,
+                ..Self::new()
+            })
+        }
+    }
+
+    impl AuditLogReader for RwLock<InMemoryAuditLog> {
+        async fn entries(
+            &self,
+            filter: &AuditFilter,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<AuditEntry>, anyhow::Error> {
+            let audit_log = self.read().expect("audit log rwlock poisoned");
+            audit_log.connectivity.blow_up_if_disconnected()?;
+
+            let matches = |entry: &&AuditEntry| {
+                filter
+                    .entity_type
+                    .is_none_or(|wanted| entry.entity_type == wanted)
+                    && filter
+                        .entity_id
+                        .is_none_or(|wanted| entry.entity_id == wanted)
+                    && filter
+                        .actor_user_id
+                        .is_none_or(|wanted| entry.actor_user_id == Some(wanted))
+                    && filter.since.is_none_or(|since| entry.recorded_at >= since)
+                    && filter.until.is_none_or(|until| entry.recorded_at < until)
+                    && filter
+                        .before_id
+                        .is_none_or(|before_id| entry.id < before_id)
+            };
+
+            Ok(audit_log
+                .entries
+                .iter()
+                .rev()
+                .filter(matches)
+                .take(filter.limit as usize)
+                .cloned()
+                .collect())
+        }
+    }
+
+    /// A mock of AuditService for use in API tests
+    pub struct MockAuditService {
+        pub audit_entries_result:
+            FakeImplementation<AuditFilter, Result<Vec<AuditEntry>, anyhow::Error>>,
+    }
+
+    impl MockAuditService {
+        /// Constructor for MockAuditService
+        pub fn new() -> MockAuditService {
+            MockAuditService {
+                audit_entries_result: FakeImplementation::new(),
+            }
+        }
+
+        /// Constructs a new MockAuditService, allowing for configuration of mocks
+        /// in the builder function before the mock is wrapped in a Mutex for use in API tests
+        pub fn build_locked(builder: impl FnOnce(&mut Self)) -> Mutex<Self> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Mutex::new(new_svc)
+        }
+    }
+
+    impl AuditPort for Mutex<MockAuditService> {
+        async fn audit_entries(
+            &self,
+            filter: &AuditFilter,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _audit_read: &impl AuditLogReader,
+        ) -> Result<Vec<AuditEntry>, anyhow::Error> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .audit_entries_result
+                .save_arguments(filter.clone());
+            locked_self.audit_entries_result.return_value_anyhow()
+        }
+    }
+}
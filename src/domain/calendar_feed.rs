@@ -0,0 +1,732 @@
+use crate::domain;
+use crate::domain::calendar_feed::driven_ports::FeedTokenStore;
+use crate::domain::calendar_feed::driving_ports::FeedError;
+use crate::domain::todo::driven_ports::TaskReader;
+use crate::domain::todo::TodoTask;
+use crate::external_connections::ExternalConnectivity;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Identifies the product which generated a calendar, as required by iCalendar
+const PRODUCT_ID: &str = "-//Rust Todo API//Tasks//EN";
+/// The longest a calendar line may be, in octets, before it's folded onto the next line
+const MAX_LINE_OCTETS: usize = 75;
+/// The number of random bytes in a feed token
+const FEED_TOKEN_BYTES: usize = 32;
+
+/// The set of driven ports invoked by calendar feed business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// An external system which stores the tokens granting access to users' calendar feeds.
+    /// Tokens are only ever stored and looked up by their hash.
+    pub trait FeedTokenStore: Sync {
+        /// Store the hash of a user's feed token, replacing the token they had before
+        async fn replace_token(
+            &self,
+            user_id: i32,
+            token_hash: &str,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Remove a user's feed token so it no longer grants access to their feed
+        async fn revoke_token(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Check whether a token hash belongs to the user's current feed token
+        async fn token_matches(
+            &self,
+            user_id: i32,
+            token_hash: &str,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for calendar feeds
+pub mod driving_ports {
+    use super::*;
+    use log::error;
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    /// The set of things that can go wrong while dealing with calendar feeds
+    pub enum FeedError {
+        #[error("The specified user did not exist.")]
+        UserDoesNotExist,
+        #[error("The feed token is missing, incorrect or has been revoked.")]
+        InvalidToken,
+        #[error(transparent)]
+        PortError(#[from] anyhow::Error),
+    }
+
+    impl From<domain::user::UserExistsErr> for FeedError {
+        fn from(value: domain::user::UserExistsErr) -> Self {
+            match value {
+                domain::user::UserExistsErr::UserDoesNotExist(user_id) => {
+                    error!(
+                        "User {} didn't exist when managing their feed token.",
+                        user_id
+                    );
+                    FeedError::UserDoesNotExist
+                }
+                domain::user::UserExistsErr::PortError(err) => {
+                    FeedError::from(err.context("Managing a feed token"))
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::items_after_test_module)]
+    mod feed_error_clone {
+        use super::FeedError;
+        use anyhow::anyhow;
+
+        // Implements clone for FeedError so it can be used in mocks during API tests
+        impl Clone for FeedError {
+            fn clone(&self) -> Self {
+                match self {
+                    Self::UserDoesNotExist => Self::UserDoesNotExist,
+                    Self::InvalidToken => Self::InvalidToken,
+                    Self::PortError(err) => Self::PortError(anyhow!(format!("{}", err))),
+                }
+            }
+        }
+    }
+
+    /// The driving port which exposes users' tasks as subscribable calendars
+    pub trait CalendarFeedPort {
+        /// Create a new feed token for a user, revoking the one they had before. The token is only
+        /// returned here, so it can't be retrieved again later.
+        async fn issue_feed_token(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            token_store: &impl driven_ports::FeedTokenStore,
+        ) -> Result<String, FeedError>;
+
+        /// Revoke a user's feed token so their feed can no longer be read with it
+        async fn revoke_feed_token(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            token_store: &impl driven_ports::FeedTokenStore,
+        ) -> Result<(), FeedError>;
+
+        /// Render a user's tasks as an iCalendar document, as long as `token` is their current feed token
+        async fn task_calendar(
+            &self,
+            user_id: i32,
+            token: &str,
+            ext_cxn: &mut impl ExternalConnectivity,
+            token_store: &impl driven_ports::FeedTokenStore,
+            task_read: &impl TaskReader,
+        ) -> Result<String, FeedError>;
+    }
+}
+
+/// Generates a random feed token which is safe to use in a URL
+fn generate_feed_token() -> String {
+    let mut token = [0u8; FEED_TOKEN_BYTES];
+    rand::thread_rng().fill_bytes(&mut token);
+
+    hex::encode(token)
+}
+
+/// Hashes a feed token into the form it's stored in
+fn hash_feed_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Escapes characters which have a special meaning in iCalendar text values
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
+
+/// Appends a content line to a calendar, folding it onto continuation lines so no line is longer than
+/// [MAX_LINE_OCTETS]. Lines are only folded between characters, never inside a multi-byte character.
+fn push_line(calendar: &mut String, line: &str) {
+    let mut line_start = 0;
+    let mut line_octets = 0;
+    for (idx, ch) in line.char_indices() {
+        if line_octets + ch.len_utf8() > MAX_LINE_OCTETS {
+            calendar.push_str(&line[line_start..idx]);
+            calendar.push_str("\r\n ");
+            line_start = idx;
+            // The space starting a continuation line counts towards its length
+            line_octets = 1;
+        }
+        line_octets += ch.len_utf8();
+    }
+    calendar.push_str(&line[line_start..]);
+    calendar.push_str("\r\n");
+}
+
+/// Renders tasks as an iCalendar document containing a VTODO for each task. `generated_at` is recorded
+/// as the time each VTODO was produced. Recurring tasks are rendered as their current occurrence, since
+/// completing one creates the next occurrence as its own task.
+pub fn render_calendar(tasks: &[TodoTask], generated_at: DateTime<Utc>) -> String {
+    let timestamp = generated_at.format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut calendar = String::new();
+    push_line(&mut calendar, "BEGIN:VCALENDAR");
+    push_line(&mut calendar, "VERSION:2.0");
+    push_line(&mut calendar, &format!("PRODID:{PRODUCT_ID}"));
+    push_line(&mut calendar, "CALSCALE:GREGORIAN");
+    for task in tasks {
+        push_line(&mut calendar, "BEGIN:VTODO");
+        push_line(
+            &mut calendar,
+            &format!("UID:task-{}@rust-todo-api", task.id),
+        );
+        push_line(&mut calendar, &format!("DTSTAMP:{timestamp}"));
+        push_line(
+            &mut calendar,
+            &format!("SUMMARY:{}", escape_text(&task.item_desc)),
+        );
+        let status = if task.completed {
+            "COMPLETED"
+        } else {
+            "NEEDS-ACTION"
+        };
+        push_line(&mut calendar, &format!("STATUS:{status}"));
+        if let Some(due_date) = task.due_date {
+            push_line(
+                &mut calendar,
+                &format!("DUE;VALUE=DATE:{}", due_date.format("%Y%m%d")),
+            );
+        }
+        push_line(&mut calendar, "END:VTODO");
+    }
+    push_line(&mut calendar, "END:VCALENDAR");
+
+    calendar
+}
+
+/// Implementation of the driving port which serves calendar feeds
+pub struct CalendarFeedService;
+
+impl driving_ports::CalendarFeedPort for CalendarFeedService {
+    async fn issue_feed_token(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        token_store: &impl FeedTokenStore,
+    ) -> Result<String, FeedError> {
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+
+        let token = generate_feed_token();
+        token_store
+            .replace_token(user_id, &hash_feed_token(&token), &mut *ext_cxn)
+            .await
+            .context("storing a feed token")?;
+
+        Ok(token)
+    }
+
+    async fn revoke_feed_token(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        token_store: &impl FeedTokenStore,
+    ) -> Result<(), FeedError> {
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+
+        token_store
+            .revoke_token(user_id, &mut *ext_cxn)
+            .await
+            .context("revoking a feed token")?;
+
+        Ok(())
+    }
+
+    async fn task_calendar(
+        &self,
+        user_id: i32,
+        token: &str,
+        ext_cxn: &mut impl ExternalConnectivity,
+        token_store: &impl FeedTokenStore,
+        task_read: &impl TaskReader,
+    ) -> Result<String, FeedError> {
+        // Unknown users have no token, so they're rejected here without revealing whether they exist
+        let token_matches = token_store
+            .token_matches(user_id, &hash_feed_token(token), &mut *ext_cxn)
+            .await
+            .context("checking a feed token")?;
+        if !token_matches {
+            return Err(FeedError::InvalidToken);
+        }
+
+        let tasks = task_read
+            .tasks_for_user(user_id, &mut *ext_cxn)
+            .await
+            .context("fetching tasks for a calendar feed")?;
+
+        Ok(render_calendar(&tasks, Utc::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::CalendarFeedPort;
+    use super::test_util::*;
+    use super::*;
+    use crate::domain::test_util::Connectivity;
+    use crate::domain::todo::test_util::{InMemoryUserTaskPersistence, NewTaskWithOwner};
+    use crate::domain::todo::NewTask;
+    use crate::domain::user::test_util::InMemoryUserPersistence;
+    use crate::external_connections;
+    use chrono::{NaiveDate, TimeZone};
+    use speculoos::prelude::*;
+    use std::sync::RwLock;
+
+    fn task(id: i32, item_desc: &str, completed: bool, due_date: Option<NaiveDate>) -> TodoTask {
+        TodoTask {
+            id,
+            owner_user_id: 1,
+            item_desc: item_desc.to_owned(),
+            completed,
+            due_date,
+            recurrence: None,
+            assignee_user_id: None,
+            comment_count: 0,
+            blocked: false,
+        }
+    }
+
+    mod render_calendar {
+        use super::*;
+
+        #[test]
+        fn renders_a_vtodo_per_task() {
+            let generated_at = Utc.with_ymd_and_hms(2024, 3, 4, 9, 30, 0).unwrap();
+            let tasks = [
+                task(
+                    1,
+                    "Water the plants",
+                    false,
+                    NaiveDate::from_ymd_opt(2024, 3, 5),
+                ),
+                task(2, "Feed the cat", true, None),
+            ];
+
+            assert_eq!(
+                "BEGIN:VCALENDAR\r\n\
+                VERSION:2.0\r\n\
+                PRODID:-//Rust Todo API//Tasks//EN\r\n\
+                CALSCALE:GREGORIAN\r\n\
+                BEGIN:VTODO\r\n\
+                UID:task-1@rust-todo-api\r\n\
+                DTSTAMP:20240304T093000Z\r\n\
+                SUMMARY:Water the plants\r\n\
+                STATUS:NEEDS-ACTION\r\n\
+                DUE;VALUE=DATE:20240305\r\n\
+                END:VTODO\r\n\
+                BEGIN:VTODO\r\n\
+                UID:task-2@rust-todo-api\r\n\
+                DTSTAMP:20240304T093000Z\r\n\
+                SUMMARY:Feed the cat\r\n\
+                STATUS:COMPLETED\r\n\
+                END:VTODO\r\n\
+                END:VCALENDAR\r\n",
+                render_calendar(&tasks, generated_at)
+            );
+        }
+
+        #[test]
+        fn renders_an_empty_calendar() {
+            let generated_at = Utc.with_ymd_and_hms(2024, 3, 4, 9, 30, 0).unwrap();
+
+            assert_eq!(
+                "BEGIN:VCALENDAR\r\n\
+                VERSION:2.0\r\n\
+                PRODID:-//Rust Todo API//Tasks//EN\r\n\
+                CALSCALE:GREGORIAN\r\n\
+                END:VCALENDAR\r\n",
+                render_calendar(&[], generated_at)
+            );
+        }
+
+        #[test]
+        fn escapes_special_characters() {
+            let tasks = [task(1, "Buy eggs, milk; \\ bread\r\nand jam", false, None)];
+
+            let calendar = render_calendar(&tasks, Utc::now());
+            assert_that!(calendar)
+                .contains("\r\nSUMMARY:Buy eggs\\, milk\\; \\\\ bread\\nand jam\r\n");
+        }
+
+        #[test]
+        fn folds_long_lines() {
+            let description = "Ünïcödé ".repeat(20);
+            let tasks = [task(1, &description, false, None)];
+
+            let calendar = render_calendar(&tasks, Utc::now());
+            for line in calendar.split("\r\n") {
+                assert!(line.len() <= MAX_LINE_OCTETS, "Line too long: {line}");
+            }
+
+            let unfolded = calendar.replace("\r\n ", "");
+            assert_that!(unfolded).contains(format!("\r\nSUMMARY:{description}\r\n").as_str());
+        }
+    }
+
+    mod issue_feed_token {
+        use super::*;
+
+        #[tokio::test]
+        async fn replaces_existing_token() {
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+            ]));
+            let token_store = InMemoryFeedTokenStore::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let first_token = CalendarFeedService
+                .issue_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await
+                .expect("Could not issue a feed token");
+            let second_token = CalendarFeedService
+                .issue_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await
+                .expect("Could not issue a feed token");
+            assert_ne!(first_token, second_token);
+            assert_eq!(FEED_TOKEN_BYTES * 2, second_token.len());
+
+            let locked_store = token_store
+                .read()
+                .expect("feed token store rwlock poisoned");
+            assert_eq!(
+                vec![(1, hash_feed_token(&second_token))],
+                locked_store.token_hashes
+            );
+        }
+
+        #[tokio::test]
+        async fn fails_if_user_doesnt_exist() {
+            let user_persist = InMemoryUserPersistence::new_locked();
+            let token_store = InMemoryFeedTokenStore::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let issue_result = CalendarFeedService
+                .issue_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await;
+            assert!(matches!(issue_result, Err(FeedError::UserDoesNotExist)));
+        }
+
+        #[tokio::test]
+        async fn returns_port_err() {
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+            ]));
+            let token_store = InMemoryFeedTokenStore::new_locked();
+            token_store.write().unwrap().connectivity = Connectivity::Disconnected;
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let issue_result = CalendarFeedService
+                .issue_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await;
+            assert!(matches!(issue_result, Err(FeedError::PortError(_))));
+        }
+    }
+
+    mod revoke_feed_token {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+            ]));
+            let token_store = InMemoryFeedTokenStore::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            CalendarFeedService
+                .issue_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await
+                .expect("Could not issue a feed token");
+
+            let revoke_result = CalendarFeedService
+                .revoke_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await;
+            assert_that!(revoke_result).is_ok();
+
+            let locked_store = token_store
+                .read()
+                .expect("feed token store rwlock poisoned");
+            assert_that!(locked_store.token_hashes).is_empty();
+        }
+
+        #[tokio::test]
+        async fn fails_if_user_doesnt_exist() {
+            let user_persist = InMemoryUserPersistence::new_locked();
+            let token_store = InMemoryFeedTokenStore::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let revoke_result = CalendarFeedService
+                .revoke_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await;
+            assert!(matches!(revoke_result, Err(FeedError::UserDoesNotExist)));
+        }
+    }
+
+    mod task_calendar {
+        use super::*;
+
+        fn task_persist() -> RwLock<InMemoryUserTaskPersistence> {
+            RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Water the plants".to_owned(),
+                        due_date: NaiveDate::from_ymd_opt(2024, 3, 5),
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+                NewTaskWithOwner {
+                    owner: 2,
+                    task: NewTask {
+                        description: "Someone else's task".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]))
+        }
+
+        #[tokio::test]
+        async fn happy_path() {
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+            ]));
+            let token_store = InMemoryFeedTokenStore::new_locked();
+            let task_persist = task_persist();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let token = CalendarFeedService
+                .issue_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await
+                .expect("Could not issue a feed token");
+
+            let calendar_result = CalendarFeedService
+                .task_calendar(1, &token, &mut ext_cxn, &token_store, &task_persist)
+                .await;
+            assert_that!(calendar_result).is_ok().matches(|calendar| {
+                calendar.contains("\r\nUID:task-1@rust-todo-api\r\n")
+                    && calendar.contains("\r\nDUE;VALUE=DATE:20240305\r\n")
+                    && !calendar.contains("UID:task-2@")
+            });
+        }
+
+        #[tokio::test]
+        async fn rejects_revoked_token() {
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+            ]));
+            let token_store = InMemoryFeedTokenStore::new_locked();
+            let task_persist = task_persist();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let revoked_token = CalendarFeedService
+                .issue_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await
+                .expect("Could not issue a feed token");
+            CalendarFeedService
+                .issue_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await
+                .expect("Could not issue a feed token");
+
+            let calendar_result = CalendarFeedService
+                .task_calendar(1, &revoked_token, &mut ext_cxn, &token_store, &task_persist)
+                .await;
+            assert!(matches!(calendar_result, Err(FeedError::InvalidToken)));
+        }
+
+        #[tokio::test]
+        async fn rejects_another_users_token() {
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+                domain::user::test_util::user_create_default(),
+            ]));
+            let token_store = InMemoryFeedTokenStore::new_locked();
+            let task_persist = task_persist();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let token = CalendarFeedService
+                .issue_feed_token(1, &mut ext_cxn, &user_persist, &token_store)
+                .await
+                .expect("Could not issue a feed token");
+
+            let calendar_result = CalendarFeedService
+                .task_calendar(2, &token, &mut ext_cxn, &token_store, &task_persist)
+                .await;
+            assert!(matches!(calendar_result, Err(FeedError::InvalidToken)));
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driving_ports::CalendarFeedPort;
+    use super::*;
+    use crate::domain::test_util::{Connectivity, FakeImplementation};
+    use std::sync::{Mutex, RwLock};
+
+    /// A fake store of feed tokens for domain logic tests
+    pub struct InMemoryFeedTokenStore {
+        /// Pairs of user IDs and the hash of their feed token
+        pub token_hashes: Vec<(i32, String)>,
+        pub connectivity: Connectivity,
+    }
+
+    impl InMemoryFeedTokenStore {
+        /// Constructor for InMemoryFeedTokenStore
+        pub fn new() -> InMemoryFeedTokenStore {
+            InMemoryFeedTokenStore {
+                token_hashes: Vec::new(),
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for InMemoryFeedTokenStore which wraps it in an RwLock right away
+        pub fn new_locked() -> RwLock<InMemoryFeedTokenStore> {
+            RwLock::new(Self::new())
+        }
+    }
+
+    impl FeedTokenStore for RwLock<InMemoryFeedTokenStore> {
+        async fn replace_token(
+            &self,
+            user_id: i32,
+            token_hash: &str,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut store = self.write().expect("feed token store rwlock poisoned");
+            store.connectivity.blow_up_if_disconnected()?;
+
+            store.token_hashes.retain(|(owner, _)| *owner != user_id);
+            store.token_hashes.push((user_id, token_hash.to_owned()));
+            Ok(())
+        }
+
+        async fn revoke_token(
+            &self,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut store = self.write().expect("feed token store rwlock poisoned");
+            store.connectivity.blow_up_if_disconnected()?;
+
+            store.token_hashes.retain(|(owner, _)| *owner != user_id);
+            Ok(())
+        }
+
+        async fn token_matches(
+            &self,
+            user_id: i32,
+            token_hash: &str,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error> {
+            let store = self.read().expect("feed token store rwlock poisoned");
+            store.connectivity.blow_up_if_disconnected()?;
+
+            Ok(store
+                .token_hashes
+                .iter()
+                .any(|(owner, stored_hash)| *owner == user_id && stored_hash == token_hash))
+        }
+    }
+
+    /// A mock of CalendarFeedService for use in API tests
+    pub struct MockCalendarFeedService {
+        pub issue_feed_token_result: FakeImplementation<i32, Result<String, FeedError>>,
+        pub revoke_feed_token_result: FakeImplementation<i32, Result<(), FeedError>>,
+        pub task_calendar_result: FakeImplementation<(i32, String), Result<String, FeedError>>,
+    }
+
+    impl MockCalendarFeedService {
+        /// Constructor for MockCalendarFeedService
+        pub fn new() -> MockCalendarFeedService {
+            MockCalendarFeedService {
+                issue_feed_token_result: FakeImplementation::new(),
+                revoke_feed_token_result: FakeImplementation::new(),
+                task_calendar_result: FakeImplementation::new(),
+            }
+        }
+
+        /// Constructs a new MockCalendarFeedService wrapped in a Mutex for use in API tests
+        pub fn new_locked() -> Mutex<MockCalendarFeedService> {
+            Mutex::new(Self::new())
+        }
+
+        /// Constructs a new MockCalendarFeedService, allowing for configuration of mocks
+        /// in the builder function before the mock is wrapped in a Mutex for use in API tests
+        pub fn build_locked(builder: impl FnOnce(&mut Self)) -> Mutex<Self> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Mutex::new(new_svc)
+        }
+    }
+
+    impl CalendarFeedPort for Mutex<MockCalendarFeedService> {
+        async fn issue_feed_token(
+            &self,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _token_store: &impl FeedTokenStore,
+        ) -> Result<String, FeedError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self.issue_feed_token_result.save_arguments(user_id);
+            locked_self.issue_feed_token_result.return_value_result()
+        }
+
+        async fn revoke_feed_token(
+            &self,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _token_store: &impl FeedTokenStore,
+        ) -> Result<(), FeedError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self.revoke_feed_token_result.save_arguments(user_id);
+            locked_self.revoke_feed_token_result.return_value_result()
+        }
+
+        async fn task_calendar(
+            &self,
+            user_id: i32,
+            token: &str,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _token_store: &impl FeedTokenStore,
+            _task_read: &impl TaskReader,
+        ) -> Result<String, FeedError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .task_calendar_result
+                .save_arguments((user_id, token.to_owned()));
+            locked_self.task_calendar_result.return_value_result()
+        }
+    }
+}
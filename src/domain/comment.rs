@@ -0,0 +1,1015 @@
+use crate::domain;
+use crate::domain::access::Caller;
+use crate::domain::comment::driven_ports::CommentStore;
+use crate::domain::comment::driving_ports::CommentError;
+use crate::domain::todo::driven_ports::TaskReader;
+use crate::external_connections::ExternalConnectivity;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+
+/// How many comments are returned in a page when no limit is given
+pub const DEFAULT_COMMENT_PAGE_SIZE: i64 = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A comment left on a task by a user who can see it
+pub struct TaskComment {
+    pub id: i32,
+    pub task_id: i32,
+    pub author_user_id: i32,
+    /// The comment this one replies to, or [None] if it starts a new thread
+    pub parent_comment_id: Option<i32>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    /// When the comment was last edited, or [None] if it never has been
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Contains information necessary to leave a comment on a task
+pub struct NewComment {
+    pub body: String,
+    /// The comment being replied to, which must be on the same task
+    pub parent_comment_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Selects a page of a task's comments. Comments are always returned oldest first.
+pub struct CommentPage {
+    /// Only include comments with a higher ID than this one, used to page through a task's comments
+    pub after_id: Option<i32>,
+    /// The most comments to return
+    pub limit: i64,
+}
+
+impl Default for CommentPage {
+    fn default() -> Self {
+        CommentPage {
+            after_id: None,
+            limit: DEFAULT_COMMENT_PAGE_SIZE,
+        }
+    }
+}
+
+/// The set of driven ports invoked by task comment business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// An external system which stores the comments left on tasks
+    pub trait CommentStore {
+        /// Save a new comment on a task, returning it as stored
+        async fn add_comment(
+            &self,
+            task_id: i32,
+            author_user_id: i32,
+            new_comment: &NewComment,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<TaskComment, anyhow::Error>;
+
+        /// Retrieve a single comment by its ID
+        async fn comment_by_id(
+            &self,
+            comment_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TaskComment>, anyhow::Error>;
+
+        /// Replace the body of a comment and record when it was edited, returning the edited comment or
+        /// [None] if it does not exist
+        async fn edit_comment(
+            &self,
+            comment_id: i32,
+            body: &str,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TaskComment>, anyhow::Error>;
+
+        /// Remove a comment along with every reply beneath it, returning whether the comment existed
+        async fn delete_comment(
+            &self,
+            comment_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error>;
+
+        /// Retrieve a page of the comments on a task, oldest first
+        async fn comments_for_task(
+            &self,
+            task_id: i32,
+            page: &CommentPage,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskComment>, anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for commenting on tasks
+pub mod driving_ports {
+    use super::*;
+    use log::error;
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    /// The set of things that can go wrong while working with task comments
+    pub enum CommentError {
+        #[error("The specified user did not exist.")]
+        UserDoesNotExist,
+        #[error("The specified task did not exist.")]
+        TaskDoesNotExist,
+        #[error("The specified comment did not exist.")]
+        CommentDoesNotExist,
+        #[error("The comment being replied to did not exist.")]
+        ParentCommentDoesNotExist,
+        #[error("Only the author of a comment may change it.")]
+        NotCommentAuthor,
+        #[error(transparent)]
+        Forbidden(#[from] domain::access::AccessDenied),
+        #[error(transparent)]
+        PortError(#[from] anyhow::Error),
+    }
+
+    impl From<domain::user::UserExistsErr> for CommentError {
+        fn from(value: domain::user::UserExistsErr) -> Self {
+            match value {
+                domain::user::UserExistsErr::UserDoesNotExist(user_id) => {
+                    error!("User {} didn't exist when commenting on a task.", user_id);
+                    CommentError::UserDoesNotExist
+                }
+                domain::user::UserExistsErr::PortError(err) => {
+                    CommentError::from(err.context("Commenting on a task"))
+                }
+            }
+        }
+    }
+
+    impl From<domain::todo::driving_ports::TaskError> for CommentError {
+        fn from(value: domain::todo::driving_ports::TaskError) -> Self {
+            use domain::todo::driving_ports::TaskError;
+            match value {
+                TaskError::UserDoesNotExist => CommentError::UserDoesNotExist,
+                TaskError::TaskDoesNotExist => CommentError::TaskDoesNotExist,
+                TaskError::Forbidden(denied) => CommentError::Forbidden(denied),
+                TaskError::PortError(err) => CommentError::PortError(err),
+                other => CommentError::PortError(other.into()),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::items_after_test_module)]
+    mod comment_error_clone {
+        use super::CommentError;
+        use anyhow::anyhow;
+
+        // Implements clone for CommentError so it can be used in mocks during API tests
+        impl Clone for CommentError {
+            fn clone(&self) -> Self {
+                match self {
+                    Self::UserDoesNotExist => Self::UserDoesNotExist,
+                    Self::TaskDoesNotExist => Self::TaskDoesNotExist,
+                    Self::CommentDoesNotExist => Self::CommentDoesNotExist,
+                    Self::ParentCommentDoesNotExist => Self::ParentCommentDoesNotExist,
+                    Self::NotCommentAuthor => Self::NotCommentAuthor,
+                    Self::Forbidden(denied) => Self::Forbidden(denied.clone()),
+                    Self::PortError(err) => Self::PortError(anyhow!(format!("{}", err))),
+                }
+            }
+        }
+    }
+
+    /// The driving port which lets users discuss tasks they can see. Every operation acts on behalf of a
+    /// user, who must be able to see the task, and only a caller who may manage that user's tasks may act
+    /// for them.
+    pub trait CommentPort {
+        /// Retrieve a page of the comments on a task
+        #[allow(clippy::too_many_arguments)]
+        async fn comments_for_task(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            page: &CommentPage,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            comment_store: &impl driven_ports::CommentStore,
+        ) -> Result<Vec<TaskComment>, CommentError>;
+
+        /// Leave a comment on a task as the given user, optionally replying to another comment
+        #[allow(clippy::too_many_arguments)]
+        async fn add_comment(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            new_comment: &NewComment,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            comment_store: &impl driven_ports::CommentStore,
+        ) -> Result<TaskComment, CommentError>;
+
+        /// Change the body of a comment the user wrote
+        #[allow(clippy::too_many_arguments)]
+        async fn edit_comment(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            comment_id: i32,
+            body: &str,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            comment_store: &impl driven_ports::CommentStore,
+        ) -> Result<TaskComment, CommentError>;
+
+        /// Remove a comment along with its replies. Authors may delete their own comments, and task owners
+        /// may delete any comment on their tasks.
+        #[allow(clippy::too_many_arguments)]
+        async fn delete_comment(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            comment_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            comment_store: &impl driven_ports::CommentStore,
+        ) -> Result<(), CommentError>;
+    }
+}
+
+/// Looks up a comment, treating comments on other tasks as though they don't exist
+async fn comment_on_task(
+    task_id: i32,
+    comment_id: i32,
+    ext_cxn: &mut impl ExternalConnectivity,
+    comment_store: &impl CommentStore,
+) -> Result<TaskComment, CommentError> {
+    comment_store
+        .comment_by_id(comment_id, &mut *ext_cxn)
+        .await
+        .context("looking up a comment")?
+        .filter(|comment| comment.task_id == task_id)
+        .ok_or(CommentError::CommentDoesNotExist)
+}
+
+/// Implementation of the driving port for commenting on tasks
+pub struct CommentService;
+
+impl driving_ports::CommentPort for CommentService {
+    async fn comments_for_task(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        page: &CommentPage,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        comment_store: &impl CommentStore,
+    ) -> Result<Vec<TaskComment>, CommentError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+
+        let comments = comment_store
+            .comments_for_task(task_id, page, &mut *ext_cxn)
+            .await
+            .context("fetching comments on a task")?;
+        Ok(comments)
+    }
+
+    async fn add_comment(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        new_comment: &NewComment,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        comment_store: &impl CommentStore,
+    ) -> Result<TaskComment, CommentError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+
+        if let Some(parent_comment_id) = new_comment.parent_comment_id {
+            match comment_on_task(task_id, parent_comment_id, &mut *ext_cxn, comment_store).await {
+                Ok(_) => {}
+                Err(CommentError::CommentDoesNotExist) => {
+                    return Err(CommentError::ParentCommentDoesNotExist)
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let comment = comment_store
+            .add_comment(task_id, user_id, new_comment, &mut *ext_cxn)
+            .await
+            .context("adding a comment")?;
+        Ok(comment)
+    }
+
+    async fn edit_comment(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        comment_id: i32,
+        body: &str,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        comment_store: &impl CommentStore,
+    ) -> Result<TaskComment, CommentError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+
+        let comment = comment_on_task(task_id, comment_id, &mut *ext_cxn, comment_store).await?;
+        if comment.author_user_id != user_id {
+            return Err(CommentError::NotCommentAuthor);
+        }
+
+        comment_store
+            .edit_comment(comment_id, body, &mut *ext_cxn)
+            .await
+            .context("editing a comment")?
+            .ok_or(CommentError::CommentDoesNotExist)
+    }
+
+    async fn delete_comment(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        comment_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        comment_store: &impl CommentStore,
+    ) -> Result<(), CommentError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+
+        let comment = comment_on_task(task_id, comment_id, &mut *ext_cxn, comment_store).await?;
+        if comment.author_user_id != user_id {
+            let task_owner = task_read
+                .task_owner(task_id, &mut *ext_cxn)
+                .await
+                .context("looking up the owner of a commented task")?;
+            if task_owner != Some(user_id) {
+                return Err(CommentError::NotCommentAuthor);
+            }
+        }
+
+        let deleted = comment_store
+            .delete_comment(comment_id, &mut *ext_cxn)
+            .await
+            .context("deleting a comment")?;
+        if deleted {
+            Ok(())
+        } else {
+            Err(CommentError::CommentDoesNotExist)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::CommentPort;
+    use super::test_util::InMemoryCommentPersistence;
+    use super::*;
+    use crate::domain::share::driving_ports::SharePort;
+    use crate::domain::share::{NewShare, SharePermission, ShareService};
+    use crate::domain::test_util::Connectivity;
+    use crate::domain::todo::test_util::{InMemoryUserTaskPersistence, NewTaskWithOwner};
+    use crate::domain::todo::NewTask;
+    use crate::domain::user::test_util::{as_user, two_users};
+    use crate::external_connections;
+    use speculoos::prelude::*;
+    use std::sync::RwLock;
+
+    const OWNER: i32 = 1;
+    const TEAMMATE: i32 = 2;
+
+    /// Gives the owner a task, which is shared with the teammate for reading, and a second task which isn't
+    /// shared
+    async fn shared_task() -> RwLock<InMemoryUserTaskPersistence> {
+        let task = |description: &str| NewTaskWithOwner {
+            owner: OWNER,
+            task: NewTask {
+                description: description.to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+            },
+        };
+        let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+            task("Plan the offsite"),
+            task("Private task"),
+        ]));
+
+        ShareService
+            .grant_share(
+                &Caller::Trusted,
+                OWNER,
+                &NewShare {
+                    shared_with_user_id: TEAMMATE,
+                    task_id: Some(1),
+                    permission: SharePermission::Read,
+                },
+                &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                &two_users(),
+                &task_persist,
+                &task_persist,
+            )
+            .await
+            .expect("Could not share task");
+        task_persist
+    }
+
+    fn comment(body: &str) -> NewComment {
+        NewComment {
+            body: body.to_owned(),
+            parent_comment_id: None,
+        }
+    }
+
+    async fn add(
+        task_persist: &RwLock<InMemoryUserTaskPersistence>,
+        comment_persist: &RwLock<InMemoryCommentPersistence>,
+        user_id: i32,
+        new_comment: &NewComment,
+    ) -> Result<TaskComment, CommentError> {
+        CommentService
+            .add_comment(
+                &as_user(user_id),
+                user_id,
+                1,
+                new_comment,
+                &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                &two_users(),
+                task_persist,
+                comment_persist,
+            )
+            .await
+    }
+
+    mod add_comment {
+        use super::*;
+
+        #[tokio::test]
+        async fn users_with_shared_task_can_reply() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+
+            let first = add(
+                &task_persist,
+                &comment_persist,
+                OWNER,
+                &comment("Any venue ideas?"),
+            )
+            .await
+            .expect("Could not add comment");
+            let reply_result = add(
+                &task_persist,
+                &comment_persist,
+                TEAMMATE,
+                &NewComment {
+                    body: "The lake house".to_owned(),
+                    parent_comment_id: Some(first.id),
+                },
+            )
+            .await;
+
+            assert_that!(reply_result).is_ok().matches(|reply| {
+                reply.author_user_id == TEAMMATE
+                    && reply.task_id == 1
+                    && reply.parent_comment_id == Some(first.id)
+                    && reply.updated_at.is_none()
+            });
+            assert_that!(comment_persist.read().unwrap().comments).has_length(2);
+        }
+
+        #[tokio::test]
+        async fn fails_for_task_user_cannot_see() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+
+            let add_result = CommentService
+                .add_comment(
+                    &as_user(TEAMMATE),
+                    TEAMMATE,
+                    2,
+                    &comment("Hello?"),
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &task_persist,
+                    &comment_persist,
+                )
+                .await;
+            assert!(matches!(add_result, Err(CommentError::TaskDoesNotExist)));
+        }
+
+        #[tokio::test]
+        async fn parent_must_be_on_same_task() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+            let other_task_comment = CommentService
+                .add_comment(
+                    &Caller::Trusted,
+                    OWNER,
+                    2,
+                    &comment("Note to self"),
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &task_persist,
+                    &comment_persist,
+                )
+                .await
+                .expect("Could not add comment");
+
+            let reply_result = add(
+                &task_persist,
+                &comment_persist,
+                OWNER,
+                &NewComment {
+                    body: "Replying across tasks".to_owned(),
+                    parent_comment_id: Some(other_task_comment.id),
+                },
+            )
+            .await;
+            assert!(matches!(
+                reply_result,
+                Err(CommentError::ParentCommentDoesNotExist)
+            ));
+        }
+
+        #[tokio::test]
+        async fn users_may_not_comment_as_others() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+
+            let add_result = CommentService
+                .add_comment(
+                    &as_user(TEAMMATE),
+                    OWNER,
+                    1,
+                    &comment("Pretending to be the owner"),
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &task_persist,
+                    &comment_persist,
+                )
+                .await;
+            assert!(matches!(add_result, Err(CommentError::Forbidden(_))));
+        }
+
+        #[tokio::test]
+        async fn returns_port_err() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+            comment_persist.write().unwrap().connected = Connectivity::Disconnected;
+
+            let add_result = add(
+                &task_persist,
+                &comment_persist,
+                OWNER,
+                &comment("Any venue ideas?"),
+            )
+            .await;
+            assert!(matches!(add_result, Err(CommentError::PortError(_))));
+        }
+    }
+
+    mod comments_for_task {
+        use super::*;
+
+        #[tokio::test]
+        async fn pages_oldest_first() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+            for body in ["First", "Second", "Third"] {
+                add(&task_persist, &comment_persist, OWNER, &comment(body))
+                    .await
+                    .expect("Could not add comment");
+            }
+
+            let page_result = CommentService
+                .comments_for_task(
+                    &as_user(TEAMMATE),
+                    TEAMMATE,
+                    1,
+                    &CommentPage {
+                        after_id: Some(1),
+                        limit: 1,
+                    },
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &task_persist,
+                    &comment_persist,
+                )
+                .await;
+            assert_that!(page_result)
+                .is_ok()
+                .matches(|comments| matches!(comments.as_slice(), [TaskComment { id: 2, .. }]));
+        }
+    }
+
+    mod edit_comment {
+        use super::*;
+
+        #[tokio::test]
+        async fn author_can_edit() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+            let added = add(&task_persist, &comment_persist, TEAMMATE, &comment("Tpyo"))
+                .await
+                .expect("Could not add comment");
+
+            let edit_result = CommentService
+                .edit_comment(
+                    &as_user(TEAMMATE),
+                    TEAMMATE,
+                    1,
+                    added.id,
+                    "Typo",
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &task_persist,
+                    &comment_persist,
+                )
+                .await;
+            assert_that!(edit_result)
+                .is_ok()
+                .matches(|edited| edited.body == "Typo" && edited.updated_at.is_some());
+        }
+
+        #[tokio::test]
+        async fn task_owner_cannot_edit_others_comments() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+            let added = add(&task_persist, &comment_persist, TEAMMATE, &comment("Mine"))
+                .await
+                .expect("Could not add comment");
+
+            let edit_result = CommentService
+                .edit_comment(
+                    &as_user(OWNER),
+                    OWNER,
+                    1,
+                    added.id,
+                    "Not anymore",
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &task_persist,
+                    &comment_persist,
+                )
+                .await;
+            assert!(matches!(edit_result, Err(CommentError::NotCommentAuthor)));
+        }
+
+        #[tokio::test]
+        async fn comment_must_be_on_task() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+            let added = add(
+                &task_persist,
+                &comment_persist,
+                OWNER,
+                &comment("On task 1"),
+            )
+            .await
+            .expect("Could not add comment");
+
+            let edit_result = CommentService
+                .edit_comment(
+                    &as_user(OWNER),
+                    OWNER,
+                    2,
+                    added.id,
+                    "On task 2",
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &task_persist,
+                    &comment_persist,
+                )
+                .await;
+            assert!(matches!(
+                edit_result,
+                Err(CommentError::CommentDoesNotExist)
+            ));
+        }
+    }
+
+    mod delete_comment {
+        use super::*;
+
+        #[tokio::test]
+        async fn task_owner_can_delete_thread() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+            let added = add(&task_persist, &comment_persist, TEAMMATE, &comment("Spam"))
+                .await
+                .expect("Could not add comment");
+            add(
+                &task_persist,
+                &comment_persist,
+                TEAMMATE,
+                &NewComment {
+                    body: "More spam".to_owned(),
+                    parent_comment_id: Some(added.id),
+                },
+            )
+            .await
+            .expect("Could not add reply");
+
+            let delete_result = CommentService
+                .delete_comment(
+                    &as_user(OWNER),
+                    OWNER,
+                    1,
+                    added.id,
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &task_persist,
+                    &comment_persist,
+                )
+                .await;
+            assert_that!(delete_result).is_ok();
+            assert_that!(comment_persist.read().unwrap().comments).is_empty();
+        }
+
+        #[tokio::test]
+        async fn others_cannot_delete_owners_comment() {
+            let task_persist = shared_task().await;
+            let comment_persist = InMemoryCommentPersistence::new_locked();
+            let added = add(&task_persist, &comment_persist, OWNER, &comment("Agenda"))
+                .await
+                .expect("Could not add comment");
+
+            let delete_result = CommentService
+                .delete_comment(
+                    &as_user(TEAMMATE),
+                    TEAMMATE,
+                    1,
+                    added.id,
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &task_persist,
+                    &comment_persist,
+                )
+                .await;
+            assert!(matches!(delete_result, Err(CommentError::NotCommentAuthor)));
+            assert_that!(comment_persist.read().unwrap().comments).has_length(1);
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driving_ports::CommentPort;
+    use super::*;
+    use crate::domain::test_util::{Connectivity, FakeImplementation};
+    use std::sync::{Mutex, RwLock};
+
+    /// An in-memory store of task comments
+    pub struct InMemoryCommentPersistence {
+        pub comments: Vec<TaskComment>,
+        pub connected: Connectivity,
+    }
+
+    impl InMemoryCommentPersistence {
+        /// Constructor for InMemoryCommentPersistence
+        pub fn new() -> InMemoryCommentPersistence {
+            InMemoryCommentPersistence {
+                comments: Vec::new(),
+                connected: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for InMemoryCommentPersistence which wraps it in an RwLock so it can be
+        /// immediately used as a driven port
+        pub fn new_locked() -> RwLock<InMemoryCommentPersistence> {
+            RwLock::new(Self::new())
+        }
+    }
+
+    impl CommentStore for RwLock<InMemoryCommentPersistence> {
+        async fn add_comment(
+            &self,
+            task_id: i32,
+            author_user_id: i32,
+            new_comment: &NewComment,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<TaskComment, anyhow::Error> {
+            let mut persistence = self.write().expect("comment persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let comment = TaskComment {
+                id: persistence
+                    .comments
+                    .last()
+                    .map(|comment| comment.id + 1)
+                    .unwrap_or(1),
+                task_id,
+                author_user_id,
+                parent_comment_id: new_comment.parent_comment_id,
+                body: new_comment.body.clone(),
+                created_at: Utc::now(),
+                updated_at: None,
+            };
+            persistence.comments.push(comment.clone());
+            Ok(comment)
+        }
+
+        async fn comment_by_id(
+            &self,
+            comment_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TaskComment>, anyhow::Error> {
+            let persistence = self.read().expect("comment persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            Ok(persistence
+                .comments
+                .iter()
+                .find(|comment| comment.id == comment_id)
+                .cloned())
+        }
+
+        async fn edit_comment(
+            &self,
+            comment_id: i32,
+            body: &str,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TaskComment>, anyhow::Error> {
+            let mut persistence = self.write().expect("comment persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            Ok(persistence
+                .comments
+                .iter_mut()
+                .find(|comment| comment.id == comment_id)
+                .map(|comment| {
+                    comment.body = body.to_owned();
+                    comment.updated_at = Some(Utc::now());
+                    comment.clone()
+                }))
+        }
+
+        async fn delete_comment(
+            &self,
+            comment_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error> {
+            let mut persistence = self.write().expect("comment persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            // Replies always have a higher ID than what they reply to, so one pass finds the whole thread
+            let mut removed_ids = vec![comment_id];
+            for comment in &persistence.comments {
+                if comment
+                    .parent_comment_id
+                    .is_some_and(|parent_id| removed_ids.contains(&parent_id))
+                {
+                    removed_ids.push(comment.id);
+                }
+            }
+
+            let comment_count = persistence.comments.len();
+            persistence
+                .comments
+                .retain(|comment| !removed_ids.contains(&comment.id));
+            Ok(persistence.comments.len() < comment_count)
+        }
+
+        async fn comments_for_task(
+            &self,
+            task_id: i32,
+            page: &CommentPage,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskComment>, anyhow::Error> {
+            let persistence = self.read().expect("comment persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            Ok(persistence
+                .comments
+                .iter()
+                .filter(|comment| {
+                    comment.task_id == task_id
+                        && page.after_id.is_none_or(|after_id| comment.id > after_id)
+                })
+                .take(page.limit as usize)
+                .cloned()
+                .collect())
+        }
+    }
+
+    /// A mock of CommentService for use in API tests
+    pub struct MockCommentService {
+        pub comments_for_task_result:
+            FakeImplementation<(i32, i32, CommentPage), Result<Vec<TaskComment>, CommentError>>,
+        pub add_comment_result:
+            FakeImplementation<(i32, i32, NewComment), Result<TaskComment, CommentError>>,
+        pub edit_comment_result:
+            FakeImplementation<(i32, i32, i32, String), Result<TaskComment, CommentError>>,
+        pub delete_comment_result: FakeImplementation<(i32, i32, i32), Result<(), CommentError>>,
+    }
+
+    impl MockCommentService {
+        /// Constructor for MockCommentService
+        pub fn new() -> MockCommentService {
+            MockCommentService {
+                comments_for_task_result: FakeImplementation::new(),
+                add_comment_result: FakeImplementation::new(),
+                edit_comment_result: FakeImplementation::new(),
+                delete_comment_result: FakeImplementation::new(),
+            }
+        }
+
+        /// Constructs a new MockCommentService, allowing for configuration of mocks
+        /// in the builder function before the mock is wrapped in a Mutex for use in API tests
+        pub fn build_locked(builder: impl FnOnce(&mut Self)) -> Mutex<Self> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Mutex::new(new_svc)
+        }
+    }
+
+    impl CommentPort for Mutex<MockCommentService> {
+        async fn comments_for_task(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            page: &CommentPage,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _comment_store: &impl CommentStore,
+        ) -> Result<Vec<TaskComment>, CommentError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .comments_for_task_result
+                .save_arguments((user_id, task_id, *page));
+            locked_self.comments_for_task_result.return_value_result()
+        }
+
+        async fn add_comment(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            new_comment: &NewComment,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _comment_store: &impl CommentStore,
+        ) -> Result<TaskComment, CommentError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .add_comment_result
+                .save_arguments((user_id, task_id, new_comment.clone()));
+            locked_self.add_comment_result.return_value_result()
+        }
+
+        async fn edit_comment(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            comment_id: i32,
+            body: &str,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _comment_store: &impl CommentStore,
+        ) -> Result<TaskComment, CommentError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self.edit_comment_result.save_arguments((
+                user_id,
+                task_id,
+                comment_id,
+                body.to_owned(),
+            ));
+            locked_self.edit_comment_result.return_value_result()
+        }
+
+        async fn delete_comment(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            comment_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _comment_store: &impl CommentStore,
+        ) -> Result<(), CommentError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .delete_comment_result
+                .save_arguments((user_id, task_id, comment_id));
+            locked_self.delete_comment_result.return_value_result()
+        }
+    }
+}
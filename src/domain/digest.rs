@@ -0,0 +1,987 @@
+use crate::domain;
+use crate::domain::access::{authorize, Action, Caller};
+use crate::domain::digest::driven_ports::{DigestPreferenceStore, DigestQueue, DigestSender};
+use crate::domain::digest::driving_ports::DigestError;
+use crate::domain::todo::driven_ports::TaskReader;
+use crate::domain::todo::TodoTask;
+use crate::external_connections::{with_savepoint, ExternalConnectivity, Savepointable};
+use anyhow::Context;
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use log::{error, warn};
+
+/// The most users sent a digest in a single run
+const DIGEST_BATCH_SIZE: i64 = 50;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// How a user wants to hear about their tasks by email
+pub struct DigestPreferences {
+    pub email: Option<String>,
+    /// Whether the user is sent a daily digest of their open tasks. Requires an email address.
+    pub daily_digest: bool,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(Clone, PartialEq, Eq))]
+/// A user whose digest is due, along with what's needed to write to them
+pub struct DigestRecipient {
+    pub user_id: i32,
+    pub first_name: String,
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The groups open tasks are split into in a digest, in the order they're listed
+pub enum DigestSection {
+    /// Tasks whose due date has passed
+    Overdue,
+    DueToday,
+    /// Tasks due after today
+    Upcoming,
+    NoDueDate,
+}
+
+impl DigestSection {
+    /// The heading the section is listed under
+    pub fn heading(&self) -> &'static str {
+        match self {
+            Self::Overdue => "Overdue",
+            Self::DueToday => "Due today",
+            Self::Upcoming => "Upcoming",
+            Self::NoDueDate => "No due date",
+        }
+    }
+
+    /// Works out which section an open task belongs in on the given day
+    fn of(task: &TodoTask, today: NaiveDate) -> DigestSection {
+        match task.due_date {
+            Some(due_date) if due_date < today => Self::Overdue,
+            Some(due_date) if due_date == today => Self::DueToday,
+            Some(_) => Self::Upcoming,
+            None => Self::NoDueDate,
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(Clone, PartialEq, Eq))]
+/// The open tasks listed under one heading of a digest
+pub struct DigestGroup {
+    pub section: DigestSection,
+    pub tasks: Vec<TodoTask>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(Clone, PartialEq, Eq))]
+/// A summary of a user's open tasks on a given day, ready to be sent to them
+pub struct TaskDigest {
+    pub recipient: DigestRecipient,
+    pub date: NaiveDate,
+    /// Groups which have at least one task, in [DigestSection] order
+    pub groups: Vec<DigestGroup>,
+}
+
+impl TaskDigest {
+    /// The number of tasks in the digest which are overdue
+    pub fn overdue_count(&self) -> usize {
+        self.groups
+            .iter()
+            .filter(|group| group.section == DigestSection::Overdue)
+            .map(|group| group.tasks.len())
+            .sum()
+    }
+
+    /// The number of open tasks in the digest
+    pub fn task_count(&self) -> usize {
+        self.groups.iter().map(|group| group.tasks.len()).sum()
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+/// Describes what happened to the users whose digests were due during a run
+pub struct DigestRun {
+    pub sent: usize,
+    /// Users with no open tasks, who aren't sent an empty digest
+    pub skipped: usize,
+    /// Users whose digest couldn't be sent. They're tried again the next day, or on the next run if their tasks
+    /// couldn't be fetched.
+    pub failed: usize,
+}
+
+/// Works out the day whose digests should go out at [now], or [None] if it's too early in the day. Digests are
+/// sent from [send_hour] o'clock UTC onwards.
+pub fn digest_day(now: DateTime<Utc>, send_hour: u32) -> Option<NaiveDate> {
+    (now.hour() >= send_hour).then(|| now.date_naive())
+}
+
+/// Builds a digest of the user's tasks which are still open on [today], or [None] if they have no open tasks.
+/// Tasks are split into [DigestSection]s and keep the order of the user's task list within each section.
+pub fn build_digest(
+    recipient: DigestRecipient,
+    tasks: Vec<TodoTask>,
+    today: NaiveDate,
+) -> Option<TaskDigest> {
+    let mut open_tasks: Vec<TodoTask> = tasks.into_iter().filter(|task| !task.completed).collect();
+    if open_tasks.is_empty() {
+        return None;
+    }
+    open_tasks.sort_by_key(|task| task.id);
+
+    let mut groups: Vec<DigestGroup> = [
+        DigestSection::Overdue,
+        DigestSection::DueToday,
+        DigestSection::Upcoming,
+        DigestSection::NoDueDate,
+    ]
+    .into_iter()
+    .map(|section| DigestGroup {
+        section,
+        tasks: Vec::new(),
+    })
+    .collect();
+    for task in open_tasks {
+        let section = DigestSection::of(&task, today);
+        if let Some(group) = groups.iter_mut().find(|group| group.section == section) {
+            group.tasks.push(task);
+        }
+    }
+    groups.retain(|group| !group.tasks.is_empty());
+
+    Some(TaskDigest {
+        recipient,
+        date: today,
+        groups,
+    })
+}
+
+/// The set of driven ports invoked by digest business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// An external system which stores how users want to hear about their tasks
+    pub trait DigestPreferenceStore: Sync {
+        /// Retrieve a user's digest preferences, or [None] if the user doesn't exist
+        async fn digest_preferences(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<DigestPreferences>, anyhow::Error>;
+
+        /// Replace a user's digest preferences, returning false if the user doesn't exist
+        async fn set_digest_preferences(
+            &self,
+            user_id: i32,
+            preferences: &DigestPreferences,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error>;
+    }
+
+    /// An external system which hands out the users whose digests are due
+    pub trait DigestQueue: Sync {
+        /// Claim up to [limit] users who want a daily digest and haven't had one for [day] yet. Claimed users are
+        /// locked until the transaction [ext_cxn] belongs to ends, and other runs skip them in the meantime.
+        async fn claim_due(
+            &self,
+            day: NaiveDate,
+            limit: i64,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<DigestRecipient>, anyhow::Error>;
+
+        /// Record that a user's digest for [day] has been dealt with, so they aren't claimed again that day
+        async fn mark_done(
+            &self,
+            user_id: i32,
+            day: NaiveDate,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+    }
+
+    /// An external system which delivers digests to their recipients
+    pub trait DigestSender: Sync {
+        /// Send the digest to its recipient, returning an error if it couldn't be delivered
+        async fn send_digest(&self, digest: &TaskDigest) -> Result<(), anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for digest preferences and sending digests
+pub mod driving_ports {
+    use super::*;
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    /// The set of things that can go wrong while working with digest preferences
+    pub enum DigestError {
+        #[error("The specified user did not exist.")]
+        UserDoesNotExist,
+        #[error(transparent)]
+        Forbidden(#[from] domain::access::AccessDenied),
+        #[error(transparent)]
+        PortError(#[from] anyhow::Error),
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::items_after_test_module)]
+    mod digest_error_clone {
+        use super::DigestError;
+        use anyhow::anyhow;
+
+        // Implements clone for DigestError so it can be used in mocks during API tests
+        impl Clone for DigestError {
+            fn clone(&self) -> Self {
+                match self {
+                    Self::UserDoesNotExist => Self::UserDoesNotExist,
+                    Self::Forbidden(denied) => Self::Forbidden(denied.clone()),
+                    Self::PortError(err) => Self::PortError(anyhow!(format!("{}", err))),
+                }
+            }
+        }
+    }
+
+    /// The driving port which lets users choose to be emailed a daily digest of their open tasks, and sends
+    /// those digests. Only a caller who may manage a user's tasks may see or change their preferences.
+    pub trait DigestPort {
+        /// Retrieve how a user wants to hear about their tasks
+        async fn digest_preferences(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            preference_store: &impl driven_ports::DigestPreferenceStore,
+        ) -> Result<DigestPreferences, DigestError>;
+
+        /// Change how a user wants to hear about their tasks
+        async fn set_digest_preferences(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            preferences: &DigestPreferences,
+            ext_cxn: &mut impl ExternalConnectivity,
+            preference_store: &impl driven_ports::DigestPreferenceStore,
+        ) -> Result<(), DigestError>;
+
+        /// Send a batch of the digests due on [day] through the sender. Each user is sent at most one digest a
+        /// day, and one which couldn't be sent isn't tried again until the next day. [ext_cxn] must be inside a
+        /// transaction, so each user's work can be rolled back on its own.
+        async fn send_due_digests<TxCxn>(
+            &self,
+            day: NaiveDate,
+            ext_cxn: &mut TxCxn,
+            queue: &impl driven_ports::DigestQueue,
+            task_read: &impl TaskReader,
+            sender: &impl driven_ports::DigestSender,
+        ) -> Result<DigestRun, anyhow::Error>
+        where
+            TxCxn: ExternalConnectivity + Savepointable;
+    }
+}
+
+/// Implementation of the driving port for digests
+pub struct DigestService;
+
+impl driving_ports::DigestPort for DigestService {
+    async fn digest_preferences(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        preference_store: &impl DigestPreferenceStore,
+    ) -> Result<DigestPreferences, DigestError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
+
+        preference_store
+            .digest_preferences(user_id, &mut *ext_cxn)
+            .await
+            .context("fetching digest preferences")?
+            .ok_or(DigestError::UserDoesNotExist)
+    }
+
+    async fn set_digest_preferences(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        preferences: &DigestPreferences,
+        ext_cxn: &mut impl ExternalConnectivity,
+        preference_store: &impl DigestPreferenceStore,
+    ) -> Result<(), DigestError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
+
+        let updated = preference_store
+            .set_digest_preferences(user_id, preferences, &mut *ext_cxn)
+            .await
+            .context("updating digest preferences")?;
+        if updated {
+            Ok(())
+        } else {
+            Err(DigestError::UserDoesNotExist)
+        }
+    }
+
+    async fn send_due_digests<TxCxn>(
+        &self,
+        day: NaiveDate,
+        ext_cxn: &mut TxCxn,
+        queue: &impl DigestQueue,
+        task_read: &impl TaskReader,
+        sender: &impl DigestSender,
+    ) -> Result<DigestRun, anyhow::Error>
+    where
+        TxCxn: ExternalConnectivity + Savepointable,
+    {
+        let recipients = queue
+            .claim_due(day, DIGEST_BATCH_SIZE, &mut *ext_cxn)
+            .await
+            .context("Claiming due digests")?;
+
+        let mut run = DigestRun::default();
+        for recipient in recipients {
+            let user_id = recipient.user_id;
+            // Earlier recipients may already have been emailed, so one user's tasks failing to load mustn't
+            // abort the run and roll back their progress. The user is left unmarked and tried again next run.
+            // A failed query spoils the rest of a database transaction, so each one runs in its own savepoint.
+            let fetched_tasks = with_savepoint(&mut *ext_cxn, async move |sp_cxn| {
+                task_read.tasks_for_user(user_id, &mut *sp_cxn).await
+            })
+            .await;
+            let tasks = match fetched_tasks {
+                Ok(tasks) => tasks,
+                Err(task_err) => {
+                    error!("Could not fetch tasks for the digest of user {user_id}: {task_err:#}");
+                    run.failed += 1;
+                    continue;
+                }
+            };
+
+            match build_digest(recipient, tasks, day) {
+                None => run.skipped += 1,
+                Some(digest) => match sender.send_digest(&digest).await {
+                    Ok(()) => run.sent += 1,
+                    Err(send_err) => {
+                        warn!("Could not send the digest of user {user_id}: {send_err:#}");
+                        run.failed += 1;
+                    }
+                },
+            }
+
+            let marked = with_savepoint(&mut *ext_cxn, async move |sp_cxn| {
+                queue.mark_done(user_id, day, &mut *sp_cxn).await
+            })
+            .await;
+            if let Err(queue_err) = marked {
+                error!("Could not record the digest of user {user_id} as done: {queue_err}");
+            }
+        }
+
+        Ok(run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::DigestPort;
+    use super::test_util::*;
+    use super::*;
+    use crate::domain::test_util::Connectivity;
+    use crate::domain::todo::test_util::{InMemoryUserTaskPersistence, NewTaskWithOwner};
+    use crate::domain::todo::NewTask;
+    use crate::domain::user::Role;
+    use crate::external_connections;
+    use chrono::TimeZone;
+    use speculoos::prelude::*;
+    use std::sync::RwLock;
+
+    const OWNER: i32 = 1;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()
+    }
+
+    fn recipient() -> DigestRecipient {
+        DigestRecipient {
+            user_id: OWNER,
+            first_name: "Dana".to_owned(),
+            email: "dana@example.com".to_owned(),
+        }
+    }
+
+    fn task_due(description: &str, due_date: Option<NaiveDate>) -> NewTaskWithOwner {
+        NewTaskWithOwner {
+            owner: OWNER,
+            task: NewTask {
+                description: description.to_owned(),
+                due_date,
+                recurrence: None,
+                assignee_user_id: None,
+            },
+        }
+    }
+
+    mod digest_day {
+        use super::*;
+
+        #[test]
+        fn waits_for_send_hour() {
+            let early = Utc.with_ymd_and_hms(2024, 5, 1, 6, 59, 0).unwrap();
+            let on_time = Utc.with_ymd_and_hms(2024, 5, 1, 7, 0, 0).unwrap();
+
+            assert_eq!(None, digest_day(early, 7));
+            assert_eq!(Some(today()), digest_day(on_time, 7));
+        }
+    }
+
+    mod build_digest {
+        use super::*;
+
+        #[test]
+        fn groups_open_tasks_by_due_date() {
+            let yesterday = today().pred_opt();
+            let tomorrow = today().succ_opt();
+            let mut tasks = InMemoryUserTaskPersistence::new_with_tasks(&[
+                task_due("Someday", None),
+                task_due("Pay rent", yesterday),
+                task_due("Book flights", tomorrow),
+                task_due("Water plants", Some(today())),
+                task_due("File taxes", yesterday),
+                task_due("Already done", yesterday),
+            ])
+            .tasks;
+            tasks[5].completed = true;
+            tasks.reverse();
+
+            let digest = build_digest(recipient(), tasks, today()).expect("Expected a digest");
+            let grouped: Vec<(DigestSection, Vec<&str>)> = digest
+                .groups
+                .iter()
+                .map(|group| {
+                    (
+                        group.section,
+                        group
+                            .tasks
+                            .iter()
+                            .map(|task| task.item_desc.as_str())
+                            .collect(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                vec![
+                    (DigestSection::Overdue, vec!["Pay rent", "File taxes"]),
+                    (DigestSection::DueToday, vec!["Water plants"]),
+                    (DigestSection::Upcoming, vec!["Book flights"]),
+                    (DigestSection::NoDueDate, vec!["Someday"]),
+                ],
+                grouped
+            );
+            assert_eq!(2, digest.overdue_count());
+            assert_eq!(5, digest.task_count());
+        }
+
+        #[test]
+        fn skips_users_without_open_tasks() {
+            let mut tasks =
+                InMemoryUserTaskPersistence::new_with_tasks(&[task_due("Already done", None)])
+                    .tasks;
+            tasks[0].completed = true;
+
+            assert_that!(build_digest(recipient(), tasks, today())).is_none();
+        }
+    }
+
+    mod set_digest_preferences {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let digest_persist = RwLock::new(InMemoryDigestPersistence::new_with_users(&["Dana"]));
+            let preferences = DigestPreferences {
+                email: Some("dana@example.com".to_owned()),
+                daily_digest: true,
+            };
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let set_result = DigestService
+                .set_digest_preferences(
+                    &Caller::Trusted,
+                    OWNER,
+                    &preferences,
+                    &mut ext_cxn,
+                    &digest_persist,
+                )
+                .await;
+            assert_that!(set_result).is_ok();
+
+            let get_result = DigestService
+                .digest_preferences(&Caller::Trusted, OWNER, &mut ext_cxn, &digest_persist)
+                .await;
+            assert_that!(get_result).is_ok().is_equal_to(preferences);
+        }
+
+        #[tokio::test]
+        async fn fails_if_user_doesnt_exist() {
+            let digest_persist = RwLock::new(InMemoryDigestPersistence::new_with_users(&["Dana"]));
+
+            let set_result = DigestService
+                .set_digest_preferences(
+                    &Caller::Trusted,
+                    OWNER + 1,
+                    &DigestPreferences::default(),
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &digest_persist,
+                )
+                .await;
+            assert!(matches!(set_result, Err(DigestError::UserDoesNotExist)));
+        }
+
+        #[tokio::test]
+        async fn forbids_other_users() {
+            let digest_persist =
+                RwLock::new(InMemoryDigestPersistence::new_with_users(&["Dana", "Eli"]));
+
+            let set_result = DigestService
+                .set_digest_preferences(
+                    &Caller::User {
+                        user_id: OWNER + 1,
+                        role: Role::User,
+                    },
+                    OWNER,
+                    &DigestPreferences::default(),
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &digest_persist,
+                )
+                .await;
+            assert!(matches!(set_result, Err(DigestError::Forbidden(_))));
+        }
+    }
+
+    mod send_due_digests {
+        use super::*;
+        use crate::external_connections::test_util::FakeExternalConnectivity;
+        use crate::external_connections::Transactable;
+
+        fn subscribed_user() -> RwLock<InMemoryDigestPersistence> {
+            let mut digest_persist = InMemoryDigestPersistence::new_with_users(&["Dana", "Eli"]);
+            digest_persist.users[0].preferences = DigestPreferences {
+                email: Some("dana@example.com".to_owned()),
+                daily_digest: true,
+            };
+
+            RwLock::new(digest_persist)
+        }
+
+        /// Starts the transaction digests are sent in
+        async fn transaction() -> FakeExternalConnectivity {
+            FakeExternalConnectivity::new()
+                .start_transaction()
+                .await
+                .unwrap()
+        }
+
+        fn owned_tasks() -> RwLock<InMemoryUserTaskPersistence> {
+            RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[task_due(
+                "Pay rent",
+                today().pred_opt(),
+            )]))
+        }
+
+        #[tokio::test]
+        async fn sends_once_a_day() {
+            let digest_persist = subscribed_user();
+            let task_persist = owned_tasks();
+            let sender = FakeDigestSender::new_locked();
+            let mut ext_cxn = transaction().await;
+
+            for expected_sent in [1, 0] {
+                let run_result = DigestService
+                    .send_due_digests(
+                        today(),
+                        &mut ext_cxn,
+                        &digest_persist,
+                        &task_persist,
+                        &sender,
+                    )
+                    .await;
+                assert_that!(run_result).is_ok().is_equal_to(DigestRun {
+                    sent: expected_sent,
+                    ..DigestRun::default()
+                });
+            }
+
+            let locked_sender = sender.read().expect("sender rwlock poisoned");
+            let [digest] = locked_sender.sent.as_slice() else {
+                panic!("Expected one digest, got {:#?}", locked_sender.sent);
+            };
+            assert_eq!(recipient(), digest.recipient);
+            assert_eq!(1, digest.overdue_count());
+            assert_eq!(
+                Some(today()),
+                digest_persist.read().unwrap().users[0].last_digest_on
+            );
+        }
+
+        #[tokio::test]
+        async fn skips_users_without_open_tasks() {
+            let digest_persist = subscribed_user();
+            let sender = FakeDigestSender::new_locked();
+
+            let run_result = DigestService
+                .send_due_digests(
+                    today(),
+                    &mut transaction().await,
+                    &digest_persist,
+                    &InMemoryUserTaskPersistence::new_locked(),
+                    &sender,
+                )
+                .await;
+            assert_that!(run_result).is_ok().is_equal_to(DigestRun {
+                skipped: 1,
+                ..DigestRun::default()
+            });
+            assert_that!(sender.read().unwrap().sent).is_empty();
+        }
+
+        #[tokio::test]
+        async fn waits_a_day_after_failing_to_send() {
+            let digest_persist = subscribed_user();
+            let task_persist = owned_tasks();
+            let mut raw_sender = FakeDigestSender::new();
+            raw_sender.connectivity = Connectivity::Disconnected;
+            let sender = RwLock::new(raw_sender);
+            let mut ext_cxn = transaction().await;
+
+            let run_result = DigestService
+                .send_due_digests(
+                    today(),
+                    &mut ext_cxn,
+                    &digest_persist,
+                    &task_persist,
+                    &sender,
+                )
+                .await;
+            assert_that!(run_result).is_ok().is_equal_to(DigestRun {
+                failed: 1,
+                ..DigestRun::default()
+            });
+
+            sender.write().unwrap().connectivity = Connectivity::Connected;
+            let tomorrow = today().succ_opt().unwrap();
+            let run_result = DigestService
+                .send_due_digests(
+                    tomorrow,
+                    &mut ext_cxn,
+                    &digest_persist,
+                    &task_persist,
+                    &sender,
+                )
+                .await;
+            assert_that!(run_result).is_ok().is_equal_to(DigestRun {
+                sent: 1,
+                ..DigestRun::default()
+            });
+        }
+
+        #[tokio::test]
+        async fn retries_users_whose_tasks_cannot_be_fetched() {
+            let digest_persist = subscribed_user();
+            let task_persist = owned_tasks();
+            task_persist.write().unwrap().connected = Connectivity::Disconnected;
+            let sender = FakeDigestSender::new_locked();
+            let mut ext_cxn = transaction().await;
+
+            let run_result = DigestService
+                .send_due_digests(
+                    today(),
+                    &mut ext_cxn,
+                    &digest_persist,
+                    &task_persist,
+                    &sender,
+                )
+                .await;
+            assert_that!(run_result).is_ok().is_equal_to(DigestRun {
+                failed: 1,
+                ..DigestRun::default()
+            });
+            assert_that!(digest_persist.read().unwrap().users[0].last_digest_on).is_none();
+            assert_that!(ext_cxn.savepoints_started()).is_equal_to(1);
+            assert_that!(ext_cxn.savepoints_released()).is_equal_to(0);
+
+            task_persist.write().unwrap().connected = Connectivity::Connected;
+            let run_result = DigestService
+                .send_due_digests(
+                    today(),
+                    &mut ext_cxn,
+                    &digest_persist,
+                    &task_persist,
+                    &sender,
+                )
+                .await;
+            assert_that!(run_result).is_ok().is_equal_to(DigestRun {
+                sent: 1,
+                ..DigestRun::default()
+            });
+        }
+
+        #[tokio::test]
+        async fn propagates_queue_error() {
+            let digest_persist = subscribed_user();
+            digest_persist.write().unwrap().connectivity = Connectivity::Disconnected;
+
+            let run_result = DigestService
+                .send_due_digests(
+                    today(),
+                    &mut transaction().await,
+                    &digest_persist,
+                    &owned_tasks(),
+                    &FakeDigestSender::new_locked(),
+                )
+                .await;
+            assert_that!(run_result).is_err();
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driving_ports::DigestPort;
+    use super::*;
+    use crate::domain::test_util::{Connectivity, FakeImplementation};
+    use anyhow::anyhow;
+    use std::sync::{Mutex, RwLock};
+
+    /// A user kept by [InMemoryDigestPersistence]
+    pub struct InMemoryDigestUser {
+        pub user_id: i32,
+        pub first_name: String,
+        pub preferences: DigestPreferences,
+        pub last_digest_on: Option<NaiveDate>,
+    }
+
+    /// A fake of the digest driven ports which keeps users' preferences in memory
+    pub struct InMemoryDigestPersistence {
+        pub users: Vec<InMemoryDigestUser>,
+        pub connectivity: Connectivity,
+    }
+
+    impl InMemoryDigestPersistence {
+        /// Constructor for InMemoryDigestPersistence which adds a user with default preferences for each of
+        /// the given first names, numbering them from 1
+        pub fn new_with_users(first_names: &[&str]) -> InMemoryDigestPersistence {
+            InMemoryDigestPersistence {
+                users: first_names
+                    .iter()
+                    .enumerate()
+                    .map(|(index, first_name)| InMemoryDigestUser {
+                        user_id: index as i32 + 1,
+                        first_name: (*first_name).to_owned(),
+                        preferences: DigestPreferences::default(),
+                        last_digest_on: None,
+                    })
+                    .collect(),
+                connectivity: Connectivity::Connected,
+            }
+        }
+    }
+
+    impl DigestPreferenceStore for RwLock<InMemoryDigestPersistence> {
+        async fn digest_preferences(
+            &self,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<DigestPreferences>, anyhow::Error> {
+            let persistence = self.read().expect("digest rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            Ok(persistence
+                .users
+                .iter()
+                .find(|user| user.user_id == user_id)
+                .map(|user| user.preferences.clone()))
+        }
+
+        async fn set_digest_preferences(
+            &self,
+            user_id: i32,
+            preferences: &DigestPreferences,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error> {
+            let mut persistence = self.write().expect("digest rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            match persistence
+                .users
+                .iter_mut()
+                .find(|user| user.user_id == user_id)
+            {
+                Some(user) => {
+                    user.preferences = preferences.clone();
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+    }
+
+    impl DigestQueue for RwLock<InMemoryDigestPersistence> {
+        async fn claim_due(
+            &self,
+            day: NaiveDate,
+            limit: i64,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<DigestRecipient>, anyhow::Error> {
+            let persistence = self.read().expect("digest rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            Ok(persistence
+                .users
+                .iter()
+                .filter(|user| {
+                    user.preferences.daily_digest
+                        && user
+                            .last_digest_on
+                            .filter(|last_day| *last_day >= day)
+                            .is_none()
+                })
+                .filter_map(|user| {
+                    Some(DigestRecipient {
+                        user_id: user.user_id,
+                        first_name: user.first_name.clone(),
+                        email: user.preferences.email.clone()?,
+                    })
+                })
+                .take(limit as usize)
+                .collect())
+        }
+
+        async fn mark_done(
+            &self,
+            user_id: i32,
+            day: NaiveDate,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut persistence = self.write().expect("digest rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            let user = persistence
+                .users
+                .iter_mut()
+                .find(|user| user.user_id == user_id)
+                .ok_or_else(|| anyhow!("No user with ID {user_id}"))?;
+            user.last_digest_on = Some(day);
+            Ok(())
+        }
+    }
+
+    /// A fake [DigestSender] which records the digests it's asked to send instead of sending them.
+    /// Every digest fails to send while disconnected.
+    pub struct FakeDigestSender {
+        pub sent: Vec<TaskDigest>,
+        pub connectivity: Connectivity,
+    }
+
+    impl FakeDigestSender {
+        /// Constructor for FakeDigestSender
+        pub fn new() -> FakeDigestSender {
+            FakeDigestSender {
+                sent: Vec::new(),
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for FakeDigestSender which wraps it in an RwLock so it can be
+        /// immediately used as a driven port
+        pub fn new_locked() -> RwLock<FakeDigestSender> {
+            RwLock::new(Self::new())
+        }
+    }
+
+    impl DigestSender for RwLock<FakeDigestSender> {
+        async fn send_digest(&self, digest: &TaskDigest) -> Result<(), anyhow::Error> {
+            let mut sender = self.write().expect("sender rwlock poisoned");
+            sender.connectivity.blow_up_if_disconnected()?;
+
+            sender.sent.push(digest.clone());
+            Ok(())
+        }
+    }
+
+    /// A mock of DigestService for use in API tests
+    pub struct MockDigestService {
+        pub digest_preferences_result:
+            FakeImplementation<i32, Result<DigestPreferences, DigestError>>,
+        pub set_digest_preferences_result:
+            FakeImplementation<(i32, DigestPreferences), Result<(), DigestError>>,
+    }
+
+    impl MockDigestService {
+        /// Constructor for MockDigestService
+        pub fn new() -> MockDigestService {
+            MockDigestService {
+                digest_preferences_result: FakeImplementation::new(),
+                set_digest_preferences_result: FakeImplementation::new(),
+            }
+        }
+
+        /// Constructs a new MockDigestService, allowing for configuration of mocks
+        /// in the builder function before the mock is wrapped in a Mutex for use in API tests
+        pub fn build_locked(builder: impl FnOnce(&mut Self)) -> Mutex<Self> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Mutex::new(new_svc)
+        }
+    }
+
+    impl DigestPort for Mutex<MockDigestService> {
+        async fn digest_preferences(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _preference_store: &impl DigestPreferenceStore,
+        ) -> Result<DigestPreferences, DigestError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .digest_preferences_result
+                .save_arguments(user_id);
+            locked_self.digest_preferences_result.return_value_result()
+        }
+
+        async fn set_digest_preferences(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            preferences: &DigestPreferences,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _preference_store: &impl DigestPreferenceStore,
+        ) -> Result<(), DigestError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .set_digest_preferences_result
+                .save_arguments((user_id, preferences.clone()));
+            locked_self
+                .set_digest_preferences_result
+                .return_value_result()
+        }
+
+        async fn send_due_digests<TxCxn>(
+            &self,
+            _day: NaiveDate,
+            _ext_cxn: &mut TxCxn,
+            _queue: &impl DigestQueue,
+            _task_read: &impl TaskReader,
+            _sender: &impl DigestSender,
+        ) -> Result<DigestRun, anyhow::Error>
+        where
+            TxCxn: ExternalConnectivity + Savepointable,
+        {
+            Ok(DigestRun::default())
+        }
+    }
+}
@@ -0,0 +1,591 @@
+use crate::domain::idempotency::driven_ports::{ClaimResult, IdempotencyStore};
+use crate::domain::idempotency::driving_ports::{IdempotencyError, IdempotencyOutcome};
+use crate::external_connections::ExternalConnectivity;
+use anyhow::Context;
+use std::time::Duration;
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(Clone, PartialEq, Eq))]
+/// Identifies a client request which may be retried using the same idempotency key
+pub struct IdempotentRequest {
+    /// Who sent the request and the endpoint it was sent to, such as "user:7 POST /users". Keys are only unique
+    /// within a scope.
+    pub scope: String,
+    /// The idempotency key provided by the client
+    pub key: String,
+    /// A hash of the request content, used to detect a key being reused for a different request
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A response recorded for an idempotent request so it can be replayed verbatim on retry
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(Clone))]
+/// The state of an idempotency key which was claimed by an earlier request
+pub struct IdempotencyRecord {
+    pub fingerprint: String,
+    /// The response produced by the earlier request, or [None] if it hasn't finished yet
+    pub response: Option<StoredResponse>,
+}
+
+/// The set of driven ports invoked by idempotency business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// The result of trying to claim an idempotency key for a request
+    pub enum ClaimResult {
+        /// The key was unused or expired and now belongs to the request
+        Claimed,
+        /// The key is already held by an earlier request
+        AlreadyClaimed(IdempotencyRecord),
+    }
+
+    /// An external system which stores idempotency keys and the responses recorded for them
+    pub trait IdempotencyStore: Sync {
+        /// Atomically claim the request's key for `ttl` if it has not been used or has expired,
+        /// otherwise return the record left by the request which holds it
+        async fn claim(
+            &self,
+            request: &IdempotentRequest,
+            ttl: Duration,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<ClaimResult, anyhow::Error>;
+
+        /// Record the response produced for a claimed key
+        async fn save_response(
+            &self,
+            request: &IdempotentRequest,
+            response: &StoredResponse,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Release a claimed key which has no recorded response so it may be used again
+        async fn release(
+            &self,
+            request: &IdempotentRequest,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Delete every key whose TTL has passed, returning the number of keys removed
+        async fn purge_expired(
+            &self,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<u64, anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for idempotent request handling
+pub mod driving_ports {
+    use super::*;
+    use thiserror::Error;
+
+    #[derive(Debug)]
+    /// Tells the driving adapter what to do with an incoming idempotent request
+    pub enum IdempotencyOutcome {
+        /// This is the first time the key was seen, so the request should be processed
+        Proceed,
+        /// The request was already processed, so the stored response should be sent back
+        Replay(StoredResponse),
+    }
+
+    #[derive(Debug, Error)]
+    /// The set of reasons an idempotent request may not be processed
+    pub enum IdempotencyError {
+        #[error("The idempotency key was already used for a different request.")]
+        KeyReused,
+        #[error("A request with the same idempotency key is still being processed.")]
+        RequestInProgress,
+        #[error(transparent)]
+        PortError(#[from] anyhow::Error),
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::items_after_test_module)]
+    mod idempotency_error_clone {
+        use super::IdempotencyError;
+        use anyhow::anyhow;
+
+        // Implements clone for IdempotencyError so it can be used in mocks during API tests
+        impl Clone for IdempotencyError {
+            fn clone(&self) -> Self {
+                match self {
+                    Self::KeyReused => Self::KeyReused,
+                    Self::RequestInProgress => Self::RequestInProgress,
+                    Self::PortError(err) => Self::PortError(anyhow!(format!("{}", err))),
+                }
+            }
+        }
+    }
+
+    /// The driving port which exposes idempotent request handling to driving adapters
+    pub trait IdempotencyPort {
+        /// Determine whether a request should be processed or answered with a previously stored response
+        async fn start_request(
+            &self,
+            request: &IdempotentRequest,
+            ttl: Duration,
+            ext_cxn: &mut impl ExternalConnectivity,
+            store: &impl driven_ports::IdempotencyStore,
+        ) -> Result<IdempotencyOutcome, IdempotencyError>;
+
+        /// Record the response to a request so it can be replayed on retry
+        async fn finish_request(
+            &self,
+            request: &IdempotentRequest,
+            response: &StoredResponse,
+            ext_cxn: &mut impl ExternalConnectivity,
+            store: &impl driven_ports::IdempotencyStore,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Give up on a request which could not be completed so the client may retry it
+        async fn abandon_request(
+            &self,
+            request: &IdempotentRequest,
+            ext_cxn: &mut impl ExternalConnectivity,
+            store: &impl driven_ports::IdempotencyStore,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Remove idempotency keys which have outlived their TTL
+        async fn purge_expired_keys(
+            &self,
+            ext_cxn: &mut impl ExternalConnectivity,
+            store: &impl driven_ports::IdempotencyStore,
+        ) -> Result<u64, anyhow::Error>;
+    }
+}
+
+/// Implementation of the driving port which handles idempotent requests
+pub struct IdempotencyService;
+
+impl driving_ports::IdempotencyPort for IdempotencyService {
+    async fn start_request(
+        &self,
+        request: &IdempotentRequest,
+        ttl: Duration,
+        ext_cxn: &mut impl ExternalConnectivity,
+        store: &impl IdempotencyStore,
+    ) -> Result<IdempotencyOutcome, IdempotencyError> {
+        let claim_result = store
+            .claim(request, ttl, &mut *ext_cxn)
+            .await
+            .context("Claiming idempotency key")?;
+
+        match claim_result {
+            ClaimResult::Claimed => Ok(IdempotencyOutcome::Proceed),
+            ClaimResult::AlreadyClaimed(record) if record.fingerprint != request.fingerprint => {
+                Err(IdempotencyError::KeyReused)
+            }
+            ClaimResult::AlreadyClaimed(IdempotencyRecord {
+                response: Some(response),
+                ..
+            }) => Ok(IdempotencyOutcome::Replay(response)),
+            ClaimResult::AlreadyClaimed(IdempotencyRecord { response: None, .. }) => {
+                Err(IdempotencyError::RequestInProgress)
+            }
+        }
+    }
+
+    async fn finish_request(
+        &self,
+        request: &IdempotentRequest,
+        response: &StoredResponse,
+        ext_cxn: &mut impl ExternalConnectivity,
+        store: &impl IdempotencyStore,
+    ) -> Result<(), anyhow::Error> {
+        store
+            .save_response(request, response, &mut *ext_cxn)
+            .await
+            .context("Recording response for idempotency key")
+    }
+
+    async fn abandon_request(
+        &self,
+        request: &IdempotentRequest,
+        ext_cxn: &mut impl ExternalConnectivity,
+        store: &impl IdempotencyStore,
+    ) -> Result<(), anyhow::Error> {
+        store
+            .release(request, &mut *ext_cxn)
+            .await
+            .context("Releasing idempotency key")
+    }
+
+    async fn purge_expired_keys(
+        &self,
+        ext_cxn: &mut impl ExternalConnectivity,
+        store: &impl IdempotencyStore,
+    ) -> Result<u64, anyhow::Error> {
+        store
+            .purge_expired(&mut *ext_cxn)
+            .await
+            .context("Purging expired idempotency keys")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::IdempotencyPort;
+    use super::test_util::*;
+    use super::*;
+    use crate::domain::test_util::Connectivity;
+    use crate::external_connections;
+    use speculoos::prelude::*;
+    use std::sync::RwLock;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    mod start_request {
+        use super::*;
+
+        #[tokio::test]
+        async fn proceeds_on_new_key() {
+            let store = InMemoryIdempotencyStore::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let outcome = IdempotencyService
+                .start_request(&request("abc"), TTL, &mut ext_cxn, &store)
+                .await;
+            assert_that!(outcome)
+                .is_ok()
+                .matches(|outcome| matches!(outcome, IdempotencyOutcome::Proceed));
+
+            let locked_store = store.read().expect("idempotency store rwlock poisoned");
+            assert_that!(locked_store.records).has_length(1);
+        }
+
+        #[tokio::test]
+        async fn replays_completed_request() {
+            let store = RwLock::new(InMemoryIdempotencyStore::new_with_records(vec![(
+                request("abc"),
+                Some(stored_response()),
+            )]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let outcome = IdempotencyService
+                .start_request(&request("abc"), TTL, &mut ext_cxn, &store)
+                .await;
+            assert_that!(outcome).is_ok().matches(|outcome| {
+                matches!(outcome, IdempotencyOutcome::Replay(response) if *response == stored_response())
+            });
+        }
+
+        #[tokio::test]
+        async fn rejects_key_reused_for_different_request() {
+            let store = RwLock::new(InMemoryIdempotencyStore::new_with_records(vec![(
+                request("abc"),
+                Some(stored_response()),
+            )]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let different_request = IdempotentRequest {
+                fingerprint: "something else".to_owned(),
+                ..request("abc")
+            };
+
+            let outcome = IdempotencyService
+                .start_request(&different_request, TTL, &mut ext_cxn, &store)
+                .await;
+            assert_that!(outcome)
+                .is_err()
+                .matches(|err| matches!(err, IdempotencyError::KeyReused));
+        }
+
+        #[tokio::test]
+        async fn rejects_request_still_in_progress() {
+            let store = RwLock::new(InMemoryIdempotencyStore::new_with_records(vec![(
+                request("abc"),
+                None,
+            )]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let outcome = IdempotencyService
+                .start_request(&request("abc"), TTL, &mut ext_cxn, &store)
+                .await;
+            assert_that!(outcome)
+                .is_err()
+                .matches(|err| matches!(err, IdempotencyError::RequestInProgress));
+        }
+
+        #[tokio::test]
+        async fn propagates_port_error() {
+            let mut raw_store = InMemoryIdempotencyStore::new();
+            raw_store.connectivity = Connectivity::Disconnected;
+            let store = RwLock::new(raw_store);
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let outcome = IdempotencyService
+                .start_request(&request("abc"), TTL, &mut ext_cxn, &store)
+                .await;
+            assert_that!(outcome)
+                .is_err()
+                .matches(|err| matches!(err, IdempotencyError::PortError(_)));
+        }
+    }
+
+    mod finish_request {
+        use super::*;
+
+        #[tokio::test]
+        async fn stores_response_for_replay() {
+            let store = RwLock::new(InMemoryIdempotencyStore::new_with_records(vec![(
+                request("abc"),
+                None,
+            )]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let finish_result = IdempotencyService
+                .finish_request(&request("abc"), &stored_response(), &mut ext_cxn, &store)
+                .await;
+            assert_that!(finish_result).is_ok();
+
+            let locked_store = store.read().expect("idempotency store rwlock poisoned");
+            assert_that!(locked_store.records[0].1).is_equal_to(Some(stored_response()));
+        }
+    }
+
+    mod abandon_request {
+        use super::*;
+
+        #[tokio::test]
+        async fn releases_key() {
+            let store = RwLock::new(InMemoryIdempotencyStore::new_with_records(vec![(
+                request("abc"),
+                None,
+            )]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let abandon_result = IdempotencyService
+                .abandon_request(&request("abc"), &mut ext_cxn, &store)
+                .await;
+            assert_that!(abandon_result).is_ok();
+
+            let locked_store = store.read().expect("idempotency store rwlock poisoned");
+            assert_that!(locked_store.records).is_empty();
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driven_ports::{ClaimResult, IdempotencyStore};
+    use super::driving_ports::{IdempotencyOutcome, IdempotencyPort};
+    use super::*;
+    use crate::domain::test_util::{Connectivity, FakeImplementation};
+    use std::sync::{Mutex, RwLock};
+
+    /// Creates an [IdempotentRequest] for "POST /users" from user 1 with the given key
+    pub fn request(key: &str) -> IdempotentRequest {
+        IdempotentRequest {
+            scope: "user:1 POST /users".to_owned(),
+            key: key.to_owned(),
+            fingerprint: "fingerprint".to_owned(),
+        }
+    }
+
+    /// Creates a sample [StoredResponse]
+    pub fn stored_response() -> StoredResponse {
+        StoredResponse {
+            status: 201,
+            content_type: Some("application/json".to_owned()),
+            body: br#"{"id":1}"#.to_vec(),
+        }
+    }
+
+    /// A fake of the idempotency driven port which keeps keys in memory. Keys never expire.
+    pub struct InMemoryIdempotencyStore {
+        pub records: Vec<(IdempotentRequest, Option<StoredResponse>)>,
+        pub connectivity: Connectivity,
+    }
+
+    impl InMemoryIdempotencyStore {
+        /// Constructor for InMemoryIdempotencyStore
+        pub fn new() -> InMemoryIdempotencyStore {
+            InMemoryIdempotencyStore {
+                records: Vec::new(),
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for InMemoryIdempotencyStore which starts with a set of claimed keys
+        pub fn new_with_records(
+            records: Vec<(IdempotentRequest, Option<StoredResponse>)>,
+        ) -> InMemoryIdempotencyStore {
+            InMemoryIdempotencyStore {
+                records,
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for InMemoryIdempotencyStore which wraps it in an RwLock so it can be
+        /// immediately used as a driven port
+        pub fn new_locked() -> RwLock<InMemoryIdempotencyStore> {
+            RwLock::new(Self::new())
+        }
+    }
+
+    impl IdempotencyStore for RwLock<InMemoryIdempotencyStore> {
+        async fn claim(
+            &self,
+            request: &IdempotentRequest,
+            _ttl: Duration,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<ClaimResult, anyhow::Error> {
+            let mut store = self.write().expect("idempotency store rwlock poisoned");
+            store.connectivity.blow_up_if_disconnected()?;
+
+            let existing = store
+                .records
+                .iter()
+                .find(|(stored, _)| stored.scope == request.scope && stored.key == request.key);
+            if let Some((stored, response)) = existing {
+                return Ok(ClaimResult::AlreadyClaimed(IdempotencyRecord {
+                    fingerprint: stored.fingerprint.clone(),
+                    response: response.clone(),
+                }));
+            }
+
+            store.records.push((request.clone(), None));
+            Ok(ClaimResult::Claimed)
+        }
+
+        async fn save_response(
+            &self,
+            request: &IdempotentRequest,
+            response: &StoredResponse,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut store = self.write().expect("idempotency store rwlock poisoned");
+            store.connectivity.blow_up_if_disconnected()?;
+
+            if let Some((_, stored_response)) = store
+                .records
+                .iter_mut()
+                .find(|(stored, _)| stored.scope == request.scope && stored.key == request.key)
+            {
+                *stored_response = Some(response.clone());
+            }
+
+            Ok(())
+        }
+
+        async fn release(
+            &self,
+            request: &IdempotentRequest,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut store = self.write().expect("idempotency store rwlock poisoned");
+            store.connectivity.blow_up_if_disconnected()?;
+
+            store.records.retain(|(stored, response)| {
+                stored.scope != request.scope || stored.key != request.key || response.is_some()
+            });
+            Ok(())
+        }
+
+        async fn purge_expired(
+            &self,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<u64, anyhow::Error> {
+            let store = self.read().expect("idempotency store rwlock poisoned");
+            store.connectivity.blow_up_if_disconnected()?;
+
+            Ok(0)
+        }
+    }
+
+    /// A mock of IdempotencyService for use in API tests
+    pub struct MockIdempotencyService {
+        pub start_request_result:
+            FakeImplementation<IdempotentRequest, Result<IdempotencyOutcome, IdempotencyError>>,
+        pub finish_request_result:
+            FakeImplementation<(IdempotentRequest, StoredResponse), Result<(), anyhow::Error>>,
+        pub abandon_request_result:
+            FakeImplementation<IdempotentRequest, Result<(), anyhow::Error>>,
+    }
+
+    impl MockIdempotencyService {
+        /// Constructor for MockIdempotencyService
+        pub fn new() -> MockIdempotencyService {
+            MockIdempotencyService {
+                start_request_result: FakeImplementation::new(),
+                finish_request_result: FakeImplementation::new(),
+                abandon_request_result: FakeImplementation::new(),
+            }
+        }
+
+        /// Constructs a new MockIdempotencyService, allowing for configuration of mocks
+        /// in the builder function before the mock is wrapped in a Mutex for use in API tests
+        pub fn build_locked(builder: impl FnOnce(&mut Self)) -> Mutex<Self> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Mutex::new(new_svc)
+        }
+    }
+
+    // Implements clone for IdempotencyOutcome so it can be used with mocks
+    impl Clone for IdempotencyOutcome {
+        fn clone(&self) -> Self {
+            match self {
+                Self::Proceed => Self::Proceed,
+                Self::Replay(response) => Self::Replay(response.clone()),
+            }
+        }
+    }
+
+    impl IdempotencyPort for Mutex<MockIdempotencyService> {
+        async fn start_request(
+            &self,
+            request: &IdempotentRequest,
+            _ttl: Duration,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _store: &impl IdempotencyStore,
+        ) -> Result<IdempotencyOutcome, IdempotencyError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .start_request_result
+                .save_arguments(request.clone());
+            locked_self.start_request_result.return_value_result()
+        }
+
+        async fn finish_request(
+            &self,
+            request: &IdempotentRequest,
+            response: &StoredResponse,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _store: &impl IdempotencyStore,
+        ) -> Result<(), anyhow::Error> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .finish_request_result
+                .save_arguments((request.clone(), response.clone()));
+            locked_self.finish_request_result.return_value_anyhow()
+        }
+
+        async fn abandon_request(
+            &self,
+            request: &IdempotentRequest,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _store: &impl IdempotencyStore,
+        ) -> Result<(), anyhow::Error> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .abandon_request_result
+                .save_arguments(request.clone());
+            locked_self.abandon_request_result.return_value_anyhow()
+        }
+
+        async fn purge_expired_keys(
+            &self,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _store: &impl IdempotencyStore,
+        ) -> Result<u64, anyhow::Error> {
+            Ok(0)
+        }
+    }
+}
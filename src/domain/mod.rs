@@ -1,7 +1,19 @@
 use thiserror::Error;
 
+pub mod access;
+pub mod attachment;
+pub mod audit;
+pub mod calendar_feed;
+pub mod comment;
+pub mod digest;
+pub mod idempotency;
+pub mod rate_limit;
+pub mod reminder;
+pub mod share;
+pub mod task_event;
 pub mod todo;
 pub mod user;
+pub mod webhook;
 
 #[cfg(test)]
 mod test_util;
@@ -0,0 +1,558 @@
+use crate::domain::rate_limit::driven_ports::BucketStore;
+use crate::external_connections::ExternalConnectivity;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A token bucket allowing a client to make up to [capacity](RateLimit::capacity) requests at once. The bucket
+/// refills at a steady rate, so over a long stretch a client can make [capacity](RateLimit::capacity) requests
+/// every [period](RateLimit::period).
+pub struct RateLimit {
+    pub capacity: u32,
+    pub period: Duration,
+}
+
+impl RateLimit {
+    /// How many tokens the bucket regains each second
+    pub fn refill_rate(&self) -> f64 {
+        f64::from(self.capacity) / self.period.as_secs_f64()
+    }
+
+    /// Adds the tokens regained over [elapsed] to a bucket holding [tokens], without going over capacity
+    pub fn refill(&self, tokens: f64, elapsed: Duration) -> f64 {
+        (tokens + elapsed.as_secs_f64() * self.refill_rate()).min(f64::from(self.capacity))
+    }
+
+    /// How long a bucket holding [tokens] takes to hold [target] tokens
+    fn time_until(&self, tokens: f64, target: f64) -> Duration {
+        let seconds = ((target - tokens) / self.refill_rate()).max(0.0);
+        Duration::from_secs(seconds.ceil() as u64)
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+/// Reasons rate limit configuration can't be used
+pub enum RateLimitConfigError {
+    #[error("\"{0}\" is not of the form REQUESTS/SECONDS.")]
+    Malformed(String),
+    #[error("A rate limit must allow at least one request over at least one second.")]
+    Empty,
+    #[error("\"{0}\" is not of the form \"METHOD /path=REQUESTS/SECONDS\".")]
+    MalformedRoute(String),
+}
+
+impl FromStr for RateLimit {
+    type Err = RateLimitConfigError;
+
+    /// Reads a limit written as `REQUESTS/SECONDS`, such as `100/60`
+    fn from_str(limit: &str) -> Result<Self, Self::Err> {
+        let malformed = || RateLimitConfigError::Malformed(limit.to_owned());
+        let (capacity, seconds) = limit.trim().split_once('/').ok_or_else(malformed)?;
+        let capacity: u32 = capacity.trim().parse().map_err(|_| malformed())?;
+        let seconds: u64 = seconds.trim().parse().map_err(|_| malformed())?;
+        if capacity == 0 || seconds == 0 {
+            return Err(RateLimitConfigError::Empty);
+        }
+
+        Ok(RateLimit {
+            capacity,
+            period: Duration::from_secs(seconds),
+        })
+    }
+}
+
+/// The name of the bucket scope used for routes without a limit of their own
+const DEFAULT_SCOPE: &str = "*";
+
+#[derive(Debug, Clone, Default)]
+/// The rate limits which apply to requests, keyed by route
+pub struct RateLimits {
+    /// The limit for routes which don't have one of their own, or [None] to leave them unlimited
+    pub default: Option<RateLimit>,
+    /// Limits for particular routes, keyed like `POST /users/:user_id/tasks`. Each route gets its own buckets.
+    pub routes: HashMap<String, RateLimit>,
+}
+
+impl RateLimits {
+    /// Reads per-route limits written as a comma-separated list of `METHOD /path=REQUESTS/SECONDS`, such as
+    /// `POST /users/:user_id/tasks=10/60, GET /users=100/60`
+    pub fn parse_routes(routes: &str) -> Result<HashMap<String, RateLimit>, RateLimitConfigError> {
+        routes
+            .split(',')
+            .filter(|route_limit| !route_limit.trim().is_empty())
+            .map(|route_limit| {
+                let (route, limit) = route_limit.rsplit_once('=').ok_or_else(|| {
+                    RateLimitConfigError::MalformedRoute(route_limit.trim().to_owned())
+                })?;
+                let route = route.split_whitespace().collect::<Vec<_>>().join(" ");
+                if route.split(' ').count() != 2 {
+                    return Err(RateLimitConfigError::MalformedRoute(
+                        route_limit.trim().to_owned(),
+                    ));
+                }
+
+                Ok((route, limit.parse()?))
+            })
+            .collect()
+    }
+
+    /// The longest period of any of the limits, after which every bucket has refilled completely
+    pub fn longest_period(&self) -> Duration {
+        self.routes
+            .values()
+            .chain(&self.default)
+            .map(|limit| limit.period)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The limit which applies to a route along with the scope of its buckets, or [None] if the route is unlimited
+    fn for_route<'limits>(&'limits self, route: &'limits str) -> Option<(&'limits str, RateLimit)> {
+        match self.routes.get(route) {
+            Some(limit) => Some((route, *limit)),
+            None => self.default.map(|limit| (DEFAULT_SCOPE, limit)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Who a request is counted against
+pub enum Client {
+    /// A user who identified themselves
+    User(i32),
+    /// An anonymous client, identified by its address
+    Address(IpAddr),
+    /// A client whose address isn't known. Every such client shares a bucket.
+    Unknown,
+}
+
+impl fmt::Display for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User(user_id) => write!(f, "user:{user_id}"),
+            Self::Address(address) => write!(f, "ip:{address}"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The state of a bucket after trying to take a token from it
+pub struct TokenTake {
+    /// Whether a token was available, meaning the request may go ahead
+    pub allowed: bool,
+    /// How many tokens are left in the bucket, including fractions of a token
+    pub tokens_left: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Whether a request may go ahead, along with what the client should be told about its limit
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: RateLimit,
+    /// How many more requests the client can make right now
+    pub remaining: u32,
+    /// How long until the client's bucket is full again
+    pub reset_after: Duration,
+    /// How long the client has to wait before its next request is allowed, which is zero if it's allowed now
+    pub retry_after: Duration,
+}
+
+/// The set of driven ports invoked by rate limiting business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// An external system which keeps the token buckets of rate limited clients
+    pub trait BucketStore: Sync {
+        /// Atomically refill the bucket with the given key according to [limit], then take a token from it if
+        /// one is available. Buckets which haven't been used before start out full.
+        async fn take_token(
+            &self,
+            bucket_key: &str,
+            limit: &RateLimit,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<TokenTake, anyhow::Error>;
+
+        /// Forget buckets which haven't been used for [idle_for], returning how many were removed
+        async fn purge_idle(
+            &self,
+            idle_for: Duration,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<u64, anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for rate limiting
+pub mod driving_ports {
+    use super::*;
+
+    /// The driving port which exposes rate limiting to driving adapters
+    pub trait RateLimitPort {
+        /// Determine whether a client may make a request to a route, using up some of its allowance if so.
+        /// Returns [None] if the route isn't limited.
+        async fn check_request(
+            &self,
+            client: &Client,
+            route: &str,
+            limits: &RateLimits,
+            ext_cxn: &mut impl ExternalConnectivity,
+            store: &impl driven_ports::BucketStore,
+        ) -> Result<Option<RateLimitDecision>, anyhow::Error>;
+
+        /// Remove buckets which haven't been used for [idle_for]
+        async fn purge_idle_buckets(
+            &self,
+            idle_for: Duration,
+            ext_cxn: &mut impl ExternalConnectivity,
+            store: &impl driven_ports::BucketStore,
+        ) -> Result<u64, anyhow::Error>;
+    }
+}
+
+/// Implementation of the driving port which applies rate limits
+pub struct RateLimitService;
+
+impl driving_ports::RateLimitPort for RateLimitService {
+    async fn check_request(
+        &self,
+        client: &Client,
+        route: &str,
+        limits: &RateLimits,
+        ext_cxn: &mut impl ExternalConnectivity,
+        store: &impl BucketStore,
+    ) -> Result<Option<RateLimitDecision>, anyhow::Error> {
+        let Some((scope, limit)) = limits.for_route(route) else {
+            return Ok(None);
+        };
+
+        let bucket_key = format!("{scope} {client}");
+        let take = store
+            .take_token(&bucket_key, &limit, &mut *ext_cxn)
+            .await
+            .context("Taking a token from a rate limit bucket")?;
+
+        Ok(Some(RateLimitDecision {
+            allowed: take.allowed,
+            limit,
+            remaining: take.tokens_left.floor() as u32,
+            reset_after: limit.time_until(take.tokens_left, f64::from(limit.capacity)),
+            retry_after: if take.allowed {
+                Duration::ZERO
+            } else {
+                limit.time_until(take.tokens_left, 1.0)
+            },
+        }))
+    }
+
+    async fn purge_idle_buckets(
+        &self,
+        idle_for: Duration,
+        ext_cxn: &mut impl ExternalConnectivity,
+        store: &impl BucketStore,
+    ) -> Result<u64, anyhow::Error> {
+        store
+            .purge_idle(idle_for, &mut *ext_cxn)
+            .await
+            .context("Purging idle rate limit buckets")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::RateLimitPort;
+    use super::test_util::*;
+    use super::*;
+    use crate::domain::test_util::Connectivity;
+    use crate::external_connections;
+    use speculoos::prelude::*;
+    use std::sync::RwLock;
+
+    fn limit(capacity: u32, seconds: u64) -> RateLimit {
+        RateLimit {
+            capacity,
+            period: Duration::from_secs(seconds),
+        }
+    }
+
+    mod parsing {
+        use super::*;
+
+        #[test]
+        fn reads_limits() {
+            assert_eq!(Ok(limit(100, 60)), " 100 / 60 ".parse());
+            assert_eq!(
+                Err(RateLimitConfigError::Malformed("100".to_owned())),
+                "100".parse::<RateLimit>()
+            );
+            assert_eq!(
+                Err(RateLimitConfigError::Empty),
+                "0/60".parse::<RateLimit>()
+            );
+        }
+
+        #[test]
+        fn reads_route_limits() {
+            let routes =
+                RateLimits::parse_routes("POST  /users/:user_id/tasks=10/60, GET /users=5/1,")
+                    .unwrap();
+            assert_eq!(2, routes.len());
+            assert_eq!(
+                Some(&limit(10, 60)),
+                routes.get("POST /users/:user_id/tasks")
+            );
+            assert_eq!(Some(&limit(5, 1)), routes.get("GET /users"));
+
+            assert_eq!(
+                Err(RateLimitConfigError::MalformedRoute(
+                    "/users=5/1".to_owned()
+                )),
+                RateLimits::parse_routes("/users=5/1")
+            );
+        }
+    }
+
+    mod refill {
+        use super::*;
+
+        #[test]
+        fn refills_at_steady_rate_up_to_capacity() {
+            let limit = limit(10, 60);
+            assert_eq!(1.0, limit.refill(0.0, Duration::from_secs(6)));
+            assert_eq!(10.0, limit.refill(9.5, Duration::from_secs(60)));
+        }
+    }
+
+    mod check_request {
+        use super::*;
+
+        fn limits() -> RateLimits {
+            RateLimits {
+                default: Some(limit(2, 60)),
+                routes: HashMap::from([("POST /users".to_owned(), limit(1, 10))]),
+            }
+        }
+
+        #[tokio::test]
+        async fn allows_requests_until_bucket_is_empty() {
+            let store = InMemoryBucketStore::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let client = Client::User(3);
+
+            let mut decisions = Vec::new();
+            for _ in 0..3 {
+                let decision = RateLimitService
+                    .check_request(&client, "GET /users", &limits(), &mut ext_cxn, &store)
+                    .await
+                    .unwrap();
+                decisions.push(decision.unwrap());
+            }
+
+            assert!(matches!(
+                decisions.as_slice(),
+                [
+                    RateLimitDecision { allowed: true, remaining: 1, .. },
+                    RateLimitDecision { allowed: true, remaining: 0, .. },
+                    RateLimitDecision { allowed: false, remaining: 0, retry_after, reset_after, .. },
+                ] if *retry_after == Duration::from_secs(30) && *reset_after == Duration::from_secs(60)
+            ));
+
+            let locked_store = store.read().expect("bucket store rwlock poisoned");
+            assert_eq!(vec!["* user:3"], locked_store.bucket_keys());
+        }
+
+        #[tokio::test]
+        async fn routes_with_own_limit_use_own_buckets() {
+            let store = InMemoryBucketStore::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let client = Client::Address("10.0.0.1".parse().unwrap());
+
+            let decision = RateLimitService
+                .check_request(&client, "POST /users", &limits(), &mut ext_cxn, &store)
+                .await;
+            assert_that!(decision)
+                .is_ok()
+                .is_some()
+                .matches(|decision| decision.limit == limit(1, 10) && decision.remaining == 0);
+
+            let locked_store = store.read().expect("bucket store rwlock poisoned");
+            assert_eq!(vec!["POST /users ip:10.0.0.1"], locked_store.bucket_keys());
+        }
+
+        #[tokio::test]
+        async fn unlimited_routes_are_not_counted() {
+            let store = InMemoryBucketStore::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let limits = RateLimits {
+                default: None,
+                ..limits()
+            };
+
+            let decision = RateLimitService
+                .check_request(
+                    &Client::Unknown,
+                    "GET /users",
+                    &limits,
+                    &mut ext_cxn,
+                    &store,
+                )
+                .await;
+            assert_that!(decision).is_ok().is_none();
+
+            let locked_store = store.read().expect("bucket store rwlock poisoned");
+            assert!(locked_store.bucket_keys().is_empty());
+        }
+
+        #[tokio::test]
+        async fn propagates_port_error() {
+            let mut raw_store = InMemoryBucketStore::new();
+            raw_store.connectivity = Connectivity::Disconnected;
+            let store = RwLock::new(raw_store);
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let decision = RateLimitService
+                .check_request(
+                    &Client::Unknown,
+                    "GET /users",
+                    &limits(),
+                    &mut ext_cxn,
+                    &store,
+                )
+                .await;
+            assert_that!(decision).is_err();
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driven_ports::BucketStore;
+    use super::driving_ports::RateLimitPort;
+    use super::*;
+    use crate::domain::test_util::{Connectivity, FakeImplementation};
+    use std::sync::{Mutex, RwLock};
+
+    /// A fake of the bucket store driven port which keeps buckets in memory. Time stands still, so buckets
+    /// never refill.
+    pub struct InMemoryBucketStore {
+        pub buckets: Vec<(String, f64)>,
+        pub connectivity: Connectivity,
+    }
+
+    impl InMemoryBucketStore {
+        /// Constructor for InMemoryBucketStore
+        pub fn new() -> InMemoryBucketStore {
+            InMemoryBucketStore {
+                buckets: Vec::new(),
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for InMemoryBucketStore which wraps it in an RwLock so it can be
+        /// immediately used as a driven port
+        pub fn new_locked() -> RwLock<InMemoryBucketStore> {
+            RwLock::new(Self::new())
+        }
+
+        /// The keys of every bucket in the store
+        pub fn bucket_keys(&self) -> Vec<&str> {
+            self.buckets.iter().map(|(key, _)| key.as_str()).collect()
+        }
+    }
+
+    impl BucketStore for RwLock<InMemoryBucketStore> {
+        async fn take_token(
+            &self,
+            bucket_key: &str,
+            limit: &RateLimit,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<TokenTake, anyhow::Error> {
+            let mut store = self.write().expect("bucket store rwlock poisoned");
+            store.connectivity.blow_up_if_disconnected()?;
+
+            let tokens = match store.buckets.iter_mut().find(|(key, _)| key == bucket_key) {
+                Some((_, tokens)) => tokens,
+                None => {
+                    store
+                        .buckets
+                        .push((bucket_key.to_owned(), f64::from(limit.capacity)));
+                    &mut store.buckets.last_mut().unwrap().1
+                }
+            };
+            let allowed = *tokens >= 1.0;
+            if allowed {
+                *tokens -= 1.0;
+            }
+
+            Ok(TokenTake {
+                allowed,
+                tokens_left: *tokens,
+            })
+        }
+
+        async fn purge_idle(
+            &self,
+            _idle_for: Duration,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<u64, anyhow::Error> {
+            let store = self.read().expect("bucket store rwlock poisoned");
+            store.connectivity.blow_up_if_disconnected()?;
+
+            Ok(0)
+        }
+    }
+
+    /// A mock of RateLimitService for use in API tests
+    pub struct MockRateLimitService {
+        pub check_request_result:
+            FakeImplementation<(Client, String), Result<Option<RateLimitDecision>, anyhow::Error>>,
+    }
+
+    impl MockRateLimitService {
+        /// Constructor for MockRateLimitService
+        pub fn new() -> MockRateLimitService {
+            MockRateLimitService {
+                check_request_result: FakeImplementation::new(),
+            }
+        }
+
+        /// Constructs a new MockRateLimitService, allowing for configuration of mocks
+        /// in the builder function before the mock is wrapped in a Mutex for use in API tests
+        pub fn build_locked(builder: impl FnOnce(&mut Self)) -> Mutex<Self> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Mutex::new(new_svc)
+        }
+    }
+
+    impl RateLimitPort for Mutex<MockRateLimitService> {
+        async fn check_request(
+            &self,
+            client: &Client,
+            route: &str,
+            _limits: &RateLimits,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _store: &impl BucketStore,
+        ) -> Result<Option<RateLimitDecision>, anyhow::Error> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .check_request_result
+                .save_arguments((client.clone(), route.to_owned()));
+            locked_self.check_request_result.return_value_anyhow()
+        }
+
+        async fn purge_idle_buckets(
+            &self,
+            _idle_for: Duration,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _store: &impl BucketStore,
+        ) -> Result<u64, anyhow::Error> {
+            Ok(0)
+        }
+    }
+}
@@ -0,0 +1,978 @@
+use crate::domain;
+use crate::domain::access::Caller;
+use crate::domain::reminder::driven_ports::{DueReminderQueue, Notifier, ReminderStore};
+use crate::domain::reminder::driving_ports::ReminderError;
+use crate::domain::todo::driven_ports::TaskReader;
+use crate::external_connections::ExternalConnectivity;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use log::{error, warn};
+use std::time::Duration;
+
+/// The number of times sending a reminder is attempted before giving up on it
+pub const MAX_NOTIFY_ATTEMPTS: i32 = 5;
+/// The most reminders sent in a single dispatch
+const REMINDER_BATCH_SIZE: i64 = 50;
+/// How long to wait before trying to send a reminder again after the first failure. The wait doubles after each
+/// failure after that.
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A reminder a user set on a task they can see, which is sent to them once its time comes
+pub struct TaskReminder {
+    pub id: i32,
+    pub task_id: i32,
+    /// The user who set the reminder and who is reminded
+    pub user_id: i32,
+    pub remind_at: DateTime<Utc>,
+    /// When the reminder was sent, or [None] if it hasn't been yet
+    pub sent_at: Option<DateTime<Utc>>,
+    /// When sending the reminder was given up on, or [None] if it hasn't been
+    pub failed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(Clone, PartialEq, Eq))]
+/// A reminder whose time has come, along with what's needed to tell its user about the task
+pub struct DueReminder {
+    pub id: i32,
+    pub task_id: i32,
+    pub user_id: i32,
+    pub item_desc: String,
+    pub remind_at: DateTime<Utc>,
+    /// The number of attempts to send the reminder made before this one
+    pub attempts: i32,
+    /// The email address of the user being reminded, or [None] if they haven't given one
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+/// Describes what happened to the reminders attempted during a dispatch
+pub struct DispatchSummary {
+    pub sent: usize,
+    pub retrying: usize,
+    /// Reminders which failed too many times and won't be attempted again
+    pub failed: usize,
+}
+
+/// The set of driven ports invoked by reminder business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// An external system which stores the reminders users set on tasks
+    pub trait ReminderStore {
+        /// Save a new reminder for a user on a task, returning it as stored
+        async fn add_reminder(
+            &self,
+            task_id: i32,
+            user_id: i32,
+            remind_at: DateTime<Utc>,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<TaskReminder, anyhow::Error>;
+
+        /// Retrieve the reminders a user has set on a task, soonest first
+        async fn reminders_for_task(
+            &self,
+            task_id: i32,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskReminder>, anyhow::Error>;
+
+        /// Remove one of a user's reminders on a task, returning whether it existed
+        async fn delete_reminder(
+            &self,
+            reminder_id: i32,
+            task_id: i32,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error>;
+    }
+
+    /// An external system which hands out reminders once they're due
+    pub trait DueReminderQueue: Sync {
+        /// Claim up to [limit] due reminders on tasks which are still open, leaving out ones which failed to send
+        /// and aren't ready to be tried again yet. Claimed reminders are locked until the transaction [ext_cxn]
+        /// belongs to ends, and other dispatchers skip them in the meantime.
+        async fn claim_due(
+            &self,
+            limit: i64,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<DueReminder>, anyhow::Error>;
+
+        /// Record that a reminder was sent
+        async fn mark_sent(
+            &self,
+            reminder_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Record that sending a reminder failed. It's attempted again once [retry_after] has passed, or given up
+        /// on if [retry_after] is [None].
+        async fn record_failure(
+            &self,
+            reminder_id: i32,
+            error: &str,
+            retry_after: Option<Duration>,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+    }
+
+    /// An external system which tells users about their due reminders
+    pub trait Notifier: Sync {
+        /// Tell the reminder's user about it, returning an error if they couldn't be reached
+        async fn notify(&self, reminder: &DueReminder) -> Result<(), anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for setting and sending reminders
+pub mod driving_ports {
+    use super::*;
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    /// The set of things that can go wrong while working with reminders
+    pub enum ReminderError {
+        #[error("The specified user did not exist.")]
+        UserDoesNotExist,
+        #[error("The specified task did not exist.")]
+        TaskDoesNotExist,
+        #[error("The specified reminder did not exist.")]
+        ReminderDoesNotExist,
+        #[error("Reminders must be set for a time in the future.")]
+        RemindAtInPast,
+        #[error(transparent)]
+        Forbidden(#[from] domain::access::AccessDenied),
+        #[error(transparent)]
+        PortError(#[from] anyhow::Error),
+    }
+
+    impl From<domain::user::UserExistsErr> for ReminderError {
+        fn from(value: domain::user::UserExistsErr) -> Self {
+            match value {
+                domain::user::UserExistsErr::UserDoesNotExist(user_id) => {
+                    error!("User {} didn't exist when working with reminders.", user_id);
+                    ReminderError::UserDoesNotExist
+                }
+                domain::user::UserExistsErr::PortError(err) => {
+                    ReminderError::from(err.context("Working with reminders"))
+                }
+            }
+        }
+    }
+
+    impl From<domain::todo::driving_ports::TaskError> for ReminderError {
+        fn from(value: domain::todo::driving_ports::TaskError) -> Self {
+            use domain::todo::driving_ports::TaskError;
+            match value {
+                TaskError::UserDoesNotExist => ReminderError::UserDoesNotExist,
+                TaskError::TaskDoesNotExist => ReminderError::TaskDoesNotExist,
+                TaskError::Forbidden(denied) => ReminderError::Forbidden(denied),
+                TaskError::PortError(err) => ReminderError::PortError(err),
+                other => ReminderError::PortError(other.into()),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::items_after_test_module)]
+    mod reminder_error_clone {
+        use super::ReminderError;
+        use anyhow::anyhow;
+
+        // Implements clone for ReminderError so it can be used in mocks during API tests
+        impl Clone for ReminderError {
+            fn clone(&self) -> Self {
+                match self {
+                    Self::UserDoesNotExist => Self::UserDoesNotExist,
+                    Self::TaskDoesNotExist => Self::TaskDoesNotExist,
+                    Self::ReminderDoesNotExist => Self::ReminderDoesNotExist,
+                    Self::RemindAtInPast => Self::RemindAtInPast,
+                    Self::Forbidden(denied) => Self::Forbidden(denied.clone()),
+                    Self::PortError(err) => Self::PortError(anyhow!(format!("{}", err))),
+                }
+            }
+        }
+    }
+
+    /// The driving port which lets users be reminded of tasks they can see. Every reminder belongs to the
+    /// user who set it, and only a caller who may manage that user's tasks may act for them.
+    pub trait ReminderPort {
+        /// Retrieve the reminders a user has set on a task
+        #[allow(clippy::too_many_arguments)]
+        async fn reminders_for_task(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            reminder_store: &impl driven_ports::ReminderStore,
+        ) -> Result<Vec<TaskReminder>, ReminderError>;
+
+        /// Remind a user of a task at a time in the future
+        #[allow(clippy::too_many_arguments)]
+        async fn add_reminder(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            remind_at: DateTime<Utc>,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            reminder_store: &impl driven_ports::ReminderStore,
+        ) -> Result<TaskReminder, ReminderError>;
+
+        /// Remove one of a user's reminders on a task
+        #[allow(clippy::too_many_arguments)]
+        async fn delete_reminder(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            reminder_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            reminder_store: &impl driven_ports::ReminderStore,
+        ) -> Result<(), ReminderError>;
+
+        /// Send a batch of due reminders through the notifier. Reminders which couldn't be sent are attempted
+        /// again on later dispatches, up to [MAX_NOTIFY_ATTEMPTS] times.
+        async fn dispatch_due(
+            &self,
+            ext_cxn: &mut impl ExternalConnectivity,
+            queue: &impl driven_ports::DueReminderQueue,
+            notifier: &impl driven_ports::Notifier,
+        ) -> Result<DispatchSummary, anyhow::Error>;
+    }
+}
+
+/// Implementation of the driving port for reminders
+pub struct ReminderService;
+
+impl driving_ports::ReminderPort for ReminderService {
+    async fn reminders_for_task(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        reminder_store: &impl ReminderStore,
+    ) -> Result<Vec<TaskReminder>, ReminderError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+
+        let reminders = reminder_store
+            .reminders_for_task(task_id, user_id, &mut *ext_cxn)
+            .await
+            .context("fetching reminders on a task")?;
+        Ok(reminders)
+    }
+
+    async fn add_reminder(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        remind_at: DateTime<Utc>,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        reminder_store: &impl ReminderStore,
+    ) -> Result<TaskReminder, ReminderError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+        if remind_at <= Utc::now() {
+            return Err(ReminderError::RemindAtInPast);
+        }
+
+        let reminder = reminder_store
+            .add_reminder(task_id, user_id, remind_at, &mut *ext_cxn)
+            .await
+            .context("adding a reminder")?;
+        Ok(reminder)
+    }
+
+    async fn delete_reminder(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        reminder_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        reminder_store: &impl ReminderStore,
+    ) -> Result<(), ReminderError> {
+        domain::todo::visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+            .await?;
+
+        let deleted = reminder_store
+            .delete_reminder(reminder_id, task_id, user_id, &mut *ext_cxn)
+            .await
+            .context("deleting a reminder")?;
+        if deleted {
+            Ok(())
+        } else {
+            Err(ReminderError::ReminderDoesNotExist)
+        }
+    }
+
+    async fn dispatch_due(
+        &self,
+        ext_cxn: &mut impl ExternalConnectivity,
+        queue: &impl DueReminderQueue,
+        notifier: &impl Notifier,
+    ) -> Result<DispatchSummary, anyhow::Error> {
+        let reminders = queue
+            .claim_due(REMINDER_BATCH_SIZE, &mut *ext_cxn)
+            .await
+            .context("Claiming due reminders")?;
+
+        let mut summary = DispatchSummary::default();
+        for reminder in reminders {
+            let notify_error = match notifier.notify(&reminder).await {
+                Ok(()) => {
+                    summary.sent += 1;
+                    if let Err(queue_err) = queue.mark_sent(reminder.id, &mut *ext_cxn).await {
+                        error!(
+                            "Could not mark reminder {} as sent: {queue_err}",
+                            reminder.id
+                        );
+                    }
+                    continue;
+                }
+                Err(notify_err) => format!("{notify_err:#}"),
+            };
+
+            let retry_after = if reminder.attempts + 1 >= MAX_NOTIFY_ATTEMPTS {
+                warn!(
+                    "Giving up on reminder {} after {} attempts: {notify_error}",
+                    reminder.id,
+                    reminder.attempts + 1
+                );
+                summary.failed += 1;
+                None
+            } else {
+                summary.retrying += 1;
+                Some(retry_delay(reminder.attempts))
+            };
+            if let Err(queue_err) = queue
+                .record_failure(reminder.id, &notify_error, retry_after, &mut *ext_cxn)
+                .await
+            {
+                error!(
+                    "Could not record failure of reminder {}: {queue_err}",
+                    reminder.id
+                );
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+/// How long to wait before trying to send a reminder again, given how many attempts failed before the latest one
+fn retry_delay(earlier_attempts: i32) -> Duration {
+    FIRST_RETRY_DELAY * 2u32.pow(earlier_attempts.clamp(0, MAX_NOTIFY_ATTEMPTS) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::ReminderPort;
+    use super::test_util::*;
+    use super::*;
+    use crate::domain::test_util::Connectivity;
+    use crate::domain::todo::test_util::{InMemoryUserTaskPersistence, NewTaskWithOwner};
+    use crate::domain::todo::NewTask;
+    use crate::domain::user::test_util::{as_user, two_users};
+    use crate::external_connections;
+    use chrono::Duration;
+    use speculoos::prelude::*;
+    use std::sync::RwLock;
+
+    const OWNER: i32 = 1;
+    const STRANGER: i32 = 2;
+
+    fn owned_task() -> RwLock<InMemoryUserTaskPersistence> {
+        RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+            NewTaskWithOwner {
+                owner: OWNER,
+                task: NewTask {
+                    description: "Renew passport".to_owned(),
+                    due_date: None,
+                    recurrence: None,
+                    assignee_user_id: None,
+                },
+            },
+        ]))
+    }
+
+    async fn add(
+        reminder_persist: &RwLock<InMemoryReminderPersistence>,
+        user_id: i32,
+        remind_at: DateTime<Utc>,
+    ) -> Result<TaskReminder, ReminderError> {
+        ReminderService
+            .add_reminder(
+                &as_user(user_id),
+                user_id,
+                1,
+                remind_at,
+                &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                &two_users(),
+                &owned_task(),
+                reminder_persist,
+            )
+            .await
+    }
+
+    mod add_reminder {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let reminder_persist = InMemoryReminderPersistence::new_locked();
+            let remind_at = Utc::now() + Duration::hours(2);
+
+            let add_result = add(&reminder_persist, OWNER, remind_at).await;
+            assert_that!(add_result).is_ok().is_equal_to(TaskReminder {
+                id: 1,
+                task_id: 1,
+                user_id: OWNER,
+                remind_at,
+                sent_at: None,
+                failed_at: None,
+            });
+        }
+
+        #[tokio::test]
+        async fn rejects_times_in_the_past() {
+            let reminder_persist = InMemoryReminderPersistence::new_locked();
+
+            let add_result = add(&reminder_persist, OWNER, Utc::now() - Duration::minutes(1)).await;
+            assert!(matches!(add_result, Err(ReminderError::RemindAtInPast)));
+            assert_that!(reminder_persist.read().unwrap().reminders).is_empty();
+        }
+
+        #[tokio::test]
+        async fn fails_for_task_user_cannot_see() {
+            let reminder_persist = InMemoryReminderPersistence::new_locked();
+
+            let add_result =
+                add(&reminder_persist, STRANGER, Utc::now() + Duration::hours(2)).await;
+            assert!(matches!(add_result, Err(ReminderError::TaskDoesNotExist)));
+        }
+
+        #[tokio::test]
+        async fn returns_port_err() {
+            let reminder_persist = InMemoryReminderPersistence::new_locked();
+            reminder_persist.write().unwrap().connectivity = Connectivity::Disconnected;
+
+            let add_result = add(&reminder_persist, OWNER, Utc::now() + Duration::hours(2)).await;
+            assert!(matches!(add_result, Err(ReminderError::PortError(_))));
+        }
+    }
+
+    mod delete_reminder {
+        use super::*;
+
+        #[tokio::test]
+        async fn only_deletes_own_reminders() {
+            let reminder_persist = InMemoryReminderPersistence::new_locked();
+            let added = add(&reminder_persist, OWNER, Utc::now() + Duration::hours(2))
+                .await
+                .expect("Could not add reminder");
+
+            let delete_result = ReminderService
+                .delete_reminder(
+                    &Caller::Trusted,
+                    STRANGER,
+                    1,
+                    added.id,
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &owned_task(),
+                    &reminder_persist,
+                )
+                .await;
+            assert!(matches!(
+                delete_result,
+                Err(ReminderError::TaskDoesNotExist)
+            ));
+
+            let delete_result = ReminderService
+                .delete_reminder(
+                    &Caller::Trusted,
+                    OWNER,
+                    1,
+                    added.id + 1,
+                    &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                    &two_users(),
+                    &owned_task(),
+                    &reminder_persist,
+                )
+                .await;
+            assert!(matches!(
+                delete_result,
+                Err(ReminderError::ReminderDoesNotExist)
+            ));
+            assert_that!(reminder_persist.read().unwrap().reminders).has_length(1);
+        }
+    }
+
+    mod dispatch_due {
+        use super::*;
+
+        fn persistence_with_due_reminder() -> RwLock<InMemoryReminderPersistence> {
+            let mut persistence = InMemoryReminderPersistence::new();
+            persistence.add(1, OWNER, Utc::now() - Duration::minutes(1));
+            persistence.add(1, OWNER, Utc::now() + Duration::hours(1));
+
+            RwLock::new(persistence)
+        }
+
+        #[tokio::test]
+        async fn notifies_about_due_reminders() {
+            let reminder_persist = persistence_with_due_reminder();
+            let notifier = FakeNotifier::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let dispatch_result = ReminderService
+                .dispatch_due(&mut ext_cxn, &reminder_persist, &notifier)
+                .await;
+            assert_that!(dispatch_result)
+                .is_ok()
+                .is_equal_to(DispatchSummary {
+                    sent: 1,
+                    ..DispatchSummary::default()
+                });
+
+            let locked_notifier = notifier.read().expect("notifier rwlock poisoned");
+            assert!(matches!(
+                locked_notifier.notified.as_slice(),
+                [DueReminder {
+                    id: 1,
+                    task_id: 1,
+                    user_id: OWNER,
+                    ..
+                }]
+            ));
+            let locked_persist = reminder_persist.read().unwrap();
+            assert!(locked_persist.reminders[0].reminder.sent_at.is_some());
+            assert!(locked_persist.reminders[1].reminder.sent_at.is_none());
+        }
+
+        #[tokio::test]
+        async fn retries_failed_notifications() {
+            let reminder_persist = persistence_with_due_reminder();
+            let mut raw_notifier = FakeNotifier::new();
+            raw_notifier.connectivity = Connectivity::Disconnected;
+            let notifier = RwLock::new(raw_notifier);
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let dispatch_result = ReminderService
+                .dispatch_due(&mut ext_cxn, &reminder_persist, &notifier)
+                .await;
+            assert_that!(dispatch_result)
+                .is_ok()
+                .is_equal_to(DispatchSummary {
+                    retrying: 1,
+                    ..DispatchSummary::default()
+                });
+
+            {
+                let locked_persist = reminder_persist.read().unwrap();
+                assert_eq!(1, locked_persist.reminders[0].attempts);
+                assert!(locked_persist.reminders[0].reminder.failed_at.is_none());
+                assert!(locked_persist.reminders[0]
+                    .next_attempt_at
+                    .is_some_and(|next_attempt| next_attempt > Utc::now()));
+            }
+
+            // The reminder waits out its backoff instead of being tried again on the next dispatch
+            let dispatch_result = ReminderService
+                .dispatch_due(&mut ext_cxn, &reminder_persist, &notifier)
+                .await;
+            assert_that!(dispatch_result)
+                .is_ok()
+                .is_equal_to(DispatchSummary::default());
+        }
+
+        #[test]
+        fn retry_delay_doubles_after_each_failure() {
+            assert_eq!(
+                vec![60, 120, 240, 480],
+                (0..4)
+                    .map(|attempts| retry_delay(attempts).as_secs())
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[tokio::test]
+        async fn gives_up_after_max_attempts() {
+            let reminder_persist = persistence_with_due_reminder();
+            reminder_persist.write().unwrap().reminders[0].attempts = MAX_NOTIFY_ATTEMPTS - 1;
+            let mut raw_notifier = FakeNotifier::new();
+            raw_notifier.connectivity = Connectivity::Disconnected;
+            let notifier = RwLock::new(raw_notifier);
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let dispatch_result = ReminderService
+                .dispatch_due(&mut ext_cxn, &reminder_persist, &notifier)
+                .await;
+            assert_that!(dispatch_result)
+                .is_ok()
+                .is_equal_to(DispatchSummary {
+                    failed: 1,
+                    ..DispatchSummary::default()
+                });
+
+            let locked_persist = reminder_persist.read().unwrap();
+            assert!(locked_persist.reminders[0].reminder.failed_at.is_some());
+        }
+
+        #[tokio::test]
+        async fn propagates_queue_error() {
+            let reminder_persist = persistence_with_due_reminder();
+            reminder_persist.write().unwrap().connectivity = Connectivity::Disconnected;
+            let notifier = FakeNotifier::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let dispatch_result = ReminderService
+                .dispatch_due(&mut ext_cxn, &reminder_persist, &notifier)
+                .await;
+            assert_that!(dispatch_result).is_err();
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driving_ports::ReminderPort;
+    use super::*;
+    use crate::domain::test_util::{Connectivity, FakeImplementation};
+    use anyhow::anyhow;
+    use std::sync::{Mutex, RwLock};
+
+    /// A reminder kept by [InMemoryReminderPersistence]
+    pub struct InMemoryReminder {
+        pub reminder: TaskReminder,
+        pub attempts: i32,
+        /// When the reminder may be tried again after failing to send
+        pub next_attempt_at: Option<DateTime<Utc>>,
+    }
+
+    /// A fake of the reminder driven ports which keeps reminders in memory. Every task is treated as open,
+    /// and is described as "Task {id}".
+    pub struct InMemoryReminderPersistence {
+        pub reminders: Vec<InMemoryReminder>,
+        pub connectivity: Connectivity,
+    }
+
+    impl InMemoryReminderPersistence {
+        /// Constructor for InMemoryReminderPersistence
+        pub fn new() -> InMemoryReminderPersistence {
+            InMemoryReminderPersistence {
+                reminders: Vec::new(),
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for InMemoryReminderPersistence which wraps it in an RwLock so it can be
+        /// immediately used as a driven port
+        pub fn new_locked() -> RwLock<InMemoryReminderPersistence> {
+            RwLock::new(Self::new())
+        }
+
+        /// Stores a new reminder, returning it
+        pub fn add(
+            &mut self,
+            task_id: i32,
+            user_id: i32,
+            remind_at: DateTime<Utc>,
+        ) -> TaskReminder {
+            let reminder = TaskReminder {
+                id: self
+                    .reminders
+                    .last()
+                    .map(|stored| stored.reminder.id + 1)
+                    .unwrap_or(1),
+                task_id,
+                user_id,
+                remind_at,
+                sent_at: None,
+                failed_at: None,
+            };
+            self.reminders.push(InMemoryReminder {
+                reminder: reminder.clone(),
+                attempts: 0,
+                next_attempt_at: None,
+            });
+
+            reminder
+        }
+
+        fn reminder_mut(
+            &mut self,
+            reminder_id: i32,
+        ) -> Result<&mut InMemoryReminder, anyhow::Error> {
+            self.reminders
+                .iter_mut()
+                .find(|stored| stored.reminder.id == reminder_id)
+                .ok_or_else(|| anyhow!("No reminder with ID {reminder_id}"))
+        }
+    }
+
+    impl ReminderStore for RwLock<InMemoryReminderPersistence> {
+        async fn add_reminder(
+            &self,
+            task_id: i32,
+            user_id: i32,
+            remind_at: DateTime<Utc>,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<TaskReminder, anyhow::Error> {
+            let mut persistence = self.write().expect("reminder rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            Ok(persistence.add(task_id, user_id, remind_at))
+        }
+
+        async fn reminders_for_task(
+            &self,
+            task_id: i32,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskReminder>, anyhow::Error> {
+            let persistence = self.read().expect("reminder rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            let mut reminders: Vec<TaskReminder> = persistence
+                .reminders
+                .iter()
+                .map(|stored| &stored.reminder)
+                .filter(|reminder| reminder.task_id == task_id && reminder.user_id == user_id)
+                .cloned()
+                .collect();
+            reminders.sort_by_key(|reminder| (reminder.remind_at, reminder.id));
+            Ok(reminders)
+        }
+
+        async fn delete_reminder(
+            &self,
+            reminder_id: i32,
+            task_id: i32,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error> {
+            let mut persistence = self.write().expect("reminder rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            let reminder_count = persistence.reminders.len();
+            persistence.reminders.retain(|stored| {
+                stored.reminder.id != reminder_id
+                    || stored.reminder.task_id != task_id
+                    || stored.reminder.user_id != user_id
+            });
+            Ok(persistence.reminders.len() < reminder_count)
+        }
+    }
+
+    impl DueReminderQueue for RwLock<InMemoryReminderPersistence> {
+        async fn claim_due(
+            &self,
+            limit: i64,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<DueReminder>, anyhow::Error> {
+            let persistence = self.read().expect("reminder rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            let now = Utc::now();
+            Ok(persistence
+                .reminders
+                .iter()
+                .filter(|stored| {
+                    stored.reminder.remind_at <= now
+                        && stored
+                            .next_attempt_at
+                            .is_none_or(|next_attempt| next_attempt <= now)
+                        && stored.reminder.sent_at.is_none()
+                        && stored.reminder.failed_at.is_none()
+                })
+                .take(limit as usize)
+                .map(|stored| DueReminder {
+                    id: stored.reminder.id,
+                    task_id: stored.reminder.task_id,
+                    user_id: stored.reminder.user_id,
+                    item_desc: format!("Task {}", stored.reminder.task_id),
+                    remind_at: stored.reminder.remind_at,
+                    attempts: stored.attempts,
+                    email: Some(format!("user{}@example.com", stored.reminder.user_id)),
+                })
+                .collect())
+        }
+
+        async fn mark_sent(
+            &self,
+            reminder_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut persistence = self.write().expect("reminder rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            let stored = persistence.reminder_mut(reminder_id)?;
+            stored.attempts += 1;
+            stored.reminder.sent_at = Some(Utc::now());
+            Ok(())
+        }
+
+        async fn record_failure(
+            &self,
+            reminder_id: i32,
+            _error: &str,
+            retry_after: Option<Duration>,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut persistence = self.write().expect("reminder rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            let stored = persistence.reminder_mut(reminder_id)?;
+            stored.attempts += 1;
+            match retry_after {
+                Some(retry_after) => stored.next_attempt_at = Some(Utc::now() + retry_after),
+                None => stored.reminder.failed_at = Some(Utc::now()),
+            }
+            Ok(())
+        }
+    }
+
+    /// A fake [Notifier] which records the reminders it's asked to send instead of sending them.
+    /// Every notification fails while disconnected.
+    pub struct FakeNotifier {
+        pub notified: Vec<DueReminder>,
+        pub connectivity: Connectivity,
+    }
+
+    impl FakeNotifier {
+        /// Constructor for FakeNotifier
+        pub fn new() -> FakeNotifier {
+            FakeNotifier {
+                notified: Vec::new(),
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for FakeNotifier which wraps it in an RwLock so it can be
+        /// immediately used as a driven port
+        pub fn new_locked() -> RwLock<FakeNotifier> {
+            RwLock::new(Self::new())
+        }
+    }
+
+    impl Notifier for RwLock<FakeNotifier> {
+        async fn notify(&self, reminder: &DueReminder) -> Result<(), anyhow::Error> {
+            let mut notifier = self.write().expect("notifier rwlock poisoned");
+            notifier.connectivity.blow_up_if_disconnected()?;
+
+            notifier.notified.push(reminder.clone());
+            Ok(())
+        }
+    }
+
+    /// A mock of ReminderService for use in API tests
+    pub struct MockReminderService {
+        pub reminders_for_task_result:
+            FakeImplementation<(i32, i32), Result<Vec<TaskReminder>, ReminderError>>,
+        pub add_reminder_result:
+            FakeImplementation<(i32, i32, DateTime<Utc>), Result<TaskReminder, ReminderError>>,
+        pub delete_reminder_result: FakeImplementation<(i32, i32, i32), Result<(), ReminderError>>,
+    }
+
+    impl MockReminderService {
+        /// Constructor for MockReminderService
+        pub fn new() -> MockReminderService {
+            MockReminderService {
+                reminders_for_task_result: FakeImplementation::new(),
+                add_reminder_result: FakeImplementation::new(),
+                delete_reminder_result: FakeImplementation::new(),
+            }
+        }
+
+        /// Constructs a new MockReminderService, allowing for configuration of mocks
+        /// in the builder function before the mock is wrapped in a Mutex for use in API tests
+        pub fn build_locked(builder: impl FnOnce(&mut Self)) -> Mutex<Self> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Mutex::new(new_svc)
+        }
+    }
+
+    impl ReminderPort for Mutex<MockReminderService> {
+        async fn reminders_for_task(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _reminder_store: &impl ReminderStore,
+        ) -> Result<Vec<TaskReminder>, ReminderError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .reminders_for_task_result
+                .save_arguments((user_id, task_id));
+            locked_self.reminders_for_task_result.return_value_result()
+        }
+
+        async fn add_reminder(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            remind_at: DateTime<Utc>,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _reminder_store: &impl ReminderStore,
+        ) -> Result<TaskReminder, ReminderError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .add_reminder_result
+                .save_arguments((user_id, task_id, remind_at));
+            locked_self.add_reminder_result.return_value_result()
+        }
+
+        async fn delete_reminder(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            reminder_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _reminder_store: &impl ReminderStore,
+        ) -> Result<(), ReminderError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .delete_reminder_result
+                .save_arguments((user_id, task_id, reminder_id));
+            locked_self.delete_reminder_result.return_value_result()
+        }
+
+        async fn dispatch_due(
+            &self,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _queue: &impl DueReminderQueue,
+            _notifier: &impl Notifier,
+        ) -> Result<DispatchSummary, anyhow::Error> {
+            Ok(DispatchSummary::default())
+        }
+    }
+}
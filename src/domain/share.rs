@@ -0,0 +1,1019 @@
+use crate::domain;
+use crate::domain::access::{authorize, Action, Caller};
+use crate::domain::share::driven_ports::ShareStore;
+use crate::domain::share::driving_ports::ShareError;
+use crate::domain::todo::driven_ports::TaskReader;
+use crate::domain::todo::TodoTask;
+use crate::external_connections::ExternalConnectivity;
+use anyhow::Context;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// How much a user may do with tasks shared with them. Later permissions include everything earlier ones
+/// allow.
+pub enum SharePermission {
+    /// The user may see the task
+    Read,
+    /// The user may see and change the task, including completing it and moving it to the trash
+    Edit,
+}
+
+impl SharePermission {
+    /// The name the permission is stored under, such as "read"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Edit => "edit",
+        }
+    }
+
+    /// Looks up a permission by its name, returning [None] if no permission has that name
+    pub fn from_name(name: &str) -> Option<SharePermission> {
+        match name {
+            "read" => Some(Self::Read),
+            "edit" => Some(Self::Edit),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Access to a user's tasks which they've granted to another user
+pub struct TaskShare {
+    pub id: i32,
+    pub owner_user_id: i32,
+    pub shared_with_user_id: i32,
+    /// The single task which is shared, or [None] if every task the owner has is shared, including ones they
+    /// create later
+    pub task_id: Option<i32>,
+    pub permission: SharePermission,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Contains information necessary to share tasks with another user
+pub struct NewShare {
+    pub shared_with_user_id: i32,
+    /// The single task to share, or [None] to share every task
+    pub task_id: Option<i32>,
+    pub permission: SharePermission,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(Clone))]
+/// A task which another user has shared, along with the strongest permission they've granted on it
+pub struct SharedTask {
+    pub task: TodoTask,
+    pub permission: SharePermission,
+}
+
+/// The set of driven ports invoked by task sharing business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// An external system which stores the shares users have granted on their tasks
+    pub trait ShareStore {
+        /// Grant another user access to an owner's tasks, returning the ID of the share. A user has at most
+        /// one share per task, or for every task, from each owner, so granting it again replaces its
+        /// permission.
+        async fn grant_share(
+            &self,
+            owner_user_id: i32,
+            new_share: &NewShare,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<i32, anyhow::Error>;
+
+        /// Remove one of an owner's shares, returning whether they had a share with the given ID
+        async fn revoke_share(
+            &self,
+            owner_user_id: i32,
+            share_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error>;
+
+        /// Retrieve the shares an owner has granted, oldest first
+        async fn shares_granted_by(
+            &self,
+            owner_user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskShare>, anyhow::Error>;
+
+        /// Retrieve the tasks other users have shared with a user in order of ID, leaving out tasks in the
+        /// trash
+        async fn tasks_shared_with(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<SharedTask>, anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for sharing tasks between users
+pub mod driving_ports {
+    use super::*;
+    use log::error;
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    /// The set of things that can go wrong while sharing tasks
+    pub enum ShareError {
+        #[error("The specified user did not exist.")]
+        UserDoesNotExist,
+        #[error("The user to share with did not exist.")]
+        ShareeDoesNotExist,
+        #[error("The specified task did not exist.")]
+        TaskDoesNotExist,
+        #[error("The specified share did not exist.")]
+        ShareDoesNotExist,
+        #[error("Tasks can't be shared with the user who owns them.")]
+        SharedWithOwner,
+        #[error(transparent)]
+        Forbidden(#[from] domain::access::AccessDenied),
+        #[error(transparent)]
+        PortError(#[from] anyhow::Error),
+    }
+
+    impl From<domain::user::UserExistsErr> for ShareError {
+        fn from(value: domain::user::UserExistsErr) -> Self {
+            match value {
+                domain::user::UserExistsErr::UserDoesNotExist(user_id) => {
+                    error!("User {} didn't exist when sharing tasks.", user_id);
+                    ShareError::UserDoesNotExist
+                }
+                domain::user::UserExistsErr::PortError(err) => {
+                    ShareError::from(err.context("Sharing tasks"))
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::items_after_test_module)]
+    mod share_error_clone {
+        use super::ShareError;
+        use anyhow::anyhow;
+
+        // Implements clone for ShareError so it can be used in mocks during API tests
+        impl Clone for ShareError {
+            fn clone(&self) -> Self {
+                match self {
+                    Self::UserDoesNotExist => Self::UserDoesNotExist,
+                    Self::ShareeDoesNotExist => Self::ShareeDoesNotExist,
+                    Self::TaskDoesNotExist => Self::TaskDoesNotExist,
+                    Self::ShareDoesNotExist => Self::ShareDoesNotExist,
+                    Self::SharedWithOwner => Self::SharedWithOwner,
+                    Self::Forbidden(denied) => Self::Forbidden(denied.clone()),
+                    Self::PortError(err) => Self::PortError(anyhow!(format!("{}", err))),
+                }
+            }
+        }
+    }
+
+    /// The driving port which lets users share their tasks with each other. Only a caller who may manage
+    /// a user's tasks may share them or see what has been shared with that user.
+    pub trait SharePort {
+        /// Share a user's tasks with another user, returning the ID of the share
+        #[allow(clippy::too_many_arguments)]
+        async fn grant_share(
+            &self,
+            caller: &Caller,
+            owner_user_id: i32,
+            new_share: &NewShare,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl TaskReader,
+            share_store: &impl driven_ports::ShareStore,
+        ) -> Result<i32, ShareError>;
+
+        /// Take back one of the shares a user has granted
+        async fn revoke_share(
+            &self,
+            caller: &Caller,
+            owner_user_id: i32,
+            share_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            share_store: &impl driven_ports::ShareStore,
+        ) -> Result<(), ShareError>;
+
+        /// Retrieve the shares a user has granted on their tasks
+        async fn shares_granted_by(
+            &self,
+            caller: &Caller,
+            owner_user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            share_store: &impl driven_ports::ShareStore,
+        ) -> Result<Vec<TaskShare>, ShareError>;
+
+        /// Retrieve the tasks other users have shared with a user
+        async fn tasks_shared_with(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            share_store: &impl driven_ports::ShareStore,
+        ) -> Result<Vec<SharedTask>, ShareError>;
+    }
+}
+
+/// Implementation of the driving port for sharing tasks
+pub struct ShareService;
+
+impl driving_ports::SharePort for ShareService {
+    async fn grant_share(
+        &self,
+        caller: &Caller,
+        owner_user_id: i32,
+        new_share: &NewShare,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        share_store: &impl ShareStore,
+    ) -> Result<i32, ShareError> {
+        authorize(caller, Action::ManageTasks { owner_user_id })?;
+        if new_share.shared_with_user_id == owner_user_id {
+            return Err(ShareError::SharedWithOwner);
+        }
+        domain::user::verify_user_exists(owner_user_id, &mut *ext_cxn, u_detect).await?;
+        match domain::user::verify_user_exists(
+            new_share.shared_with_user_id,
+            &mut *ext_cxn,
+            u_detect,
+        )
+        .await
+        {
+            Ok(()) => {}
+            Err(domain::user::UserExistsErr::UserDoesNotExist(_)) => {
+                return Err(ShareError::ShareeDoesNotExist)
+            }
+            Err(domain::user::UserExistsErr::PortError(err)) => {
+                return Err(ShareError::from(
+                    err.context("looking up the user to share with"),
+                ))
+            }
+        }
+
+        if let Some(task_id) = new_share.task_id {
+            // Users may only share their own tasks, not ones which were shared with them
+            let task_owner = task_read
+                .task_owner(task_id, &mut *ext_cxn)
+                .await
+                .context("looking up the owner of a task to share")?;
+            if task_owner != Some(owner_user_id) {
+                return Err(ShareError::TaskDoesNotExist);
+            }
+        }
+
+        let share_id = share_store
+            .grant_share(owner_user_id, new_share, &mut *ext_cxn)
+            .await
+            .context("granting a share")?;
+        Ok(share_id)
+    }
+
+    async fn revoke_share(
+        &self,
+        caller: &Caller,
+        owner_user_id: i32,
+        share_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        share_store: &impl ShareStore,
+    ) -> Result<(), ShareError> {
+        authorize(caller, Action::ManageTasks { owner_user_id })?;
+        domain::user::verify_user_exists(owner_user_id, &mut *ext_cxn, u_detect).await?;
+
+        let revoked = share_store
+            .revoke_share(owner_user_id, share_id, &mut *ext_cxn)
+            .await
+            .context("revoking a share")?;
+        if revoked {
+            Ok(())
+        } else {
+            Err(ShareError::ShareDoesNotExist)
+        }
+    }
+
+    async fn shares_granted_by(
+        &self,
+        caller: &Caller,
+        owner_user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        share_store: &impl ShareStore,
+    ) -> Result<Vec<TaskShare>, ShareError> {
+        authorize(caller, Action::ManageTasks { owner_user_id })?;
+        domain::user::verify_user_exists(owner_user_id, &mut *ext_cxn, u_detect).await?;
+
+        let shares = share_store
+            .shares_granted_by(owner_user_id, &mut *ext_cxn)
+            .await
+            .context("fetching granted shares")?;
+        Ok(shares)
+    }
+
+    async fn tasks_shared_with(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        share_store: &impl ShareStore,
+    ) -> Result<Vec<SharedTask>, ShareError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+
+        let shared_tasks = share_store
+            .tasks_shared_with(user_id, &mut *ext_cxn)
+            .await
+            .context("fetching shared tasks")?;
+        Ok(shared_tasks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::SharePort;
+    use super::*;
+    use crate::domain::test_util::Connectivity;
+    use crate::domain::todo::driving_ports::{TaskError, TaskPort};
+    use crate::domain::todo::test_util::{InMemoryUserTaskPersistence, NewTaskWithOwner};
+    use crate::domain::todo::{NewTask, TaskService, UpdateTask};
+    use crate::domain::user::test_util::{as_user, two_users};
+    use crate::domain::webhook::test_util::InMemoryWebhookPersistence;
+    use crate::external_connections;
+    use speculoos::prelude::*;
+    use std::sync::RwLock;
+
+    const OWNER: i32 = 1;
+    const TEAMMATE: i32 = 2;
+
+    /// Gives the owner two tasks and the teammate one
+    fn tasks_for_two_users() -> RwLock<InMemoryUserTaskPersistence> {
+        let task = |owner: i32, description: &str| NewTaskWithOwner {
+            owner,
+            task: NewTask {
+                description: description.to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+            },
+        };
+
+        RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+            task(OWNER, "Plan the offsite"),
+            task(OWNER, "Book a venue"),
+            task(TEAMMATE, "Teammate's task"),
+        ]))
+    }
+
+    async fn share(
+        task_persist: &RwLock<InMemoryUserTaskPersistence>,
+        task_id: Option<i32>,
+        permission: SharePermission,
+    ) -> i32 {
+        ShareService
+            .grant_share(
+                &Caller::Trusted,
+                OWNER,
+                &NewShare {
+                    shared_with_user_id: TEAMMATE,
+                    task_id,
+                    permission,
+                },
+                &mut external_connections::test_util::FakeExternalConnectivity::new(),
+                &two_users(),
+                task_persist,
+                task_persist,
+            )
+            .await
+            .expect("Could not share tasks")
+    }
+
+    mod grant_share {
+        use super::*;
+
+        #[tokio::test]
+        async fn regranting_replaces_permission() {
+            let task_persist = tasks_for_two_users();
+
+            let first_id = share(&task_persist, Some(1), SharePermission::Read).await;
+            let second_id = share(&task_persist, Some(1), SharePermission::Edit).await;
+            assert_eq!(first_id, second_id);
+
+            let locked_persist = task_persist.read().unwrap();
+            assert_eq!(
+                vec![TaskShare {
+                    id: first_id,
+                    owner_user_id: OWNER,
+                    shared_with_user_id: TEAMMATE,
+                    task_id: Some(1),
+                    permission: SharePermission::Edit,
+                }],
+                locked_persist.shares
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_sharing_with_owner() {
+            let task_persist = tasks_for_two_users();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let share_result = ShareService
+                .grant_share(
+                    &Caller::Trusted,
+                    OWNER,
+                    &NewShare {
+                        shared_with_user_id: OWNER,
+                        task_id: None,
+                        permission: SharePermission::Read,
+                    },
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(share_result, Err(ShareError::SharedWithOwner)));
+        }
+
+        #[tokio::test]
+        async fn rejects_unknown_sharee() {
+            let task_persist = tasks_for_two_users();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let share_result = ShareService
+                .grant_share(
+                    &Caller::Trusted,
+                    OWNER,
+                    &NewShare {
+                        shared_with_user_id: 3,
+                        task_id: None,
+                        permission: SharePermission::Read,
+                    },
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(share_result, Err(ShareError::ShareeDoesNotExist)));
+        }
+
+        #[tokio::test]
+        async fn rejects_task_owned_by_someone_else() {
+            let task_persist = tasks_for_two_users();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let share_result = ShareService
+                .grant_share(
+                    &Caller::Trusted,
+                    OWNER,
+                    &NewShare {
+                        shared_with_user_id: TEAMMATE,
+                        task_id: Some(3),
+                        permission: SharePermission::Read,
+                    },
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(share_result, Err(ShareError::TaskDoesNotExist)));
+        }
+
+        #[tokio::test]
+        async fn rejects_trashed_task() {
+            let task_persist = tasks_for_two_users();
+            let webhook_persist = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            TaskService
+                .delete_task(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhook_persist,
+                )
+                .await
+                .expect("Could not delete task");
+
+            let share_result = ShareService
+                .grant_share(
+                    &Caller::Trusted,
+                    OWNER,
+                    &NewShare {
+                        shared_with_user_id: TEAMMATE,
+                        task_id: Some(1),
+                        permission: SharePermission::Read,
+                    },
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(share_result, Err(ShareError::TaskDoesNotExist)));
+        }
+
+        #[tokio::test]
+        async fn users_may_not_share_others_tasks() {
+            let task_persist = tasks_for_two_users();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let share_result = ShareService
+                .grant_share(
+                    &as_user(TEAMMATE),
+                    OWNER,
+                    &NewShare {
+                        shared_with_user_id: TEAMMATE,
+                        task_id: None,
+                        permission: SharePermission::Edit,
+                    },
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(share_result, Err(ShareError::Forbidden(_))));
+        }
+
+        #[tokio::test]
+        async fn returns_port_err() {
+            let task_persist = tasks_for_two_users();
+            task_persist.write().unwrap().connected = Connectivity::Disconnected;
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let share_result = ShareService
+                .grant_share(
+                    &Caller::Trusted,
+                    OWNER,
+                    &NewShare {
+                        shared_with_user_id: TEAMMATE,
+                        task_id: None,
+                        permission: SharePermission::Read,
+                    },
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(share_result, Err(ShareError::PortError(_))));
+        }
+    }
+
+    mod revoke_share {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let task_persist = tasks_for_two_users();
+            let share_id = share(&task_persist, None, SharePermission::Read).await;
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let revoke_result = ShareService
+                .revoke_share(
+                    &Caller::Trusted,
+                    OWNER,
+                    share_id,
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                )
+                .await;
+            assert_that!(revoke_result).is_ok();
+            assert_that!(task_persist.read().unwrap().shares).is_empty();
+        }
+
+        #[tokio::test]
+        async fn fails_for_another_owners_share() {
+            let task_persist = tasks_for_two_users();
+            let share_id = share(&task_persist, None, SharePermission::Read).await;
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let revoke_result = ShareService
+                .revoke_share(
+                    &Caller::Trusted,
+                    TEAMMATE,
+                    share_id,
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(revoke_result, Err(ShareError::ShareDoesNotExist)));
+            assert_that!(task_persist.read().unwrap().shares).has_length(1);
+        }
+    }
+
+    mod tasks_shared_with {
+        use super::*;
+
+        #[tokio::test]
+        async fn combines_list_and_task_shares() {
+            let task_persist = tasks_for_two_users();
+            share(&task_persist, None, SharePermission::Read).await;
+            share(&task_persist, Some(2), SharePermission::Edit).await;
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let shared_result = ShareService
+                .tasks_shared_with(
+                    &as_user(TEAMMATE),
+                    TEAMMATE,
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                )
+                .await;
+            assert_that!(shared_result).is_ok().matches(|shared_tasks| {
+                matches!(
+                    shared_tasks.as_slice(),
+                    [
+                        SharedTask {
+                            task: TodoTask { id: 1, .. },
+                            permission: SharePermission::Read,
+                        },
+                        SharedTask {
+                            task: TodoTask { id: 2, .. },
+                            permission: SharePermission::Edit,
+                        },
+                    ]
+                )
+            });
+        }
+
+        #[tokio::test]
+        async fn users_may_not_see_what_others_were_shared() {
+            let task_persist = tasks_for_two_users();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let shared_result = ShareService
+                .tasks_shared_with(
+                    &as_user(TEAMMATE),
+                    OWNER,
+                    &mut ext_cxn,
+                    &two_users(),
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(shared_result, Err(ShareError::Forbidden(_))));
+        }
+    }
+
+    mod shared_task_access {
+        use super::*;
+
+        #[tokio::test]
+        async fn shared_tasks_can_be_read_by_id() {
+            let task_persist = tasks_for_two_users();
+            share(&task_persist, Some(1), SharePermission::Read).await;
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            for (task_id, visible) in [(1, true), (2, false)] {
+                let task_result = TaskService
+                    .user_task_by_id(
+                        &as_user(TEAMMATE),
+                        TEAMMATE,
+                        task_id,
+                        &mut ext_cxn,
+                        &two_users(),
+                        &task_persist,
+                    )
+                    .await;
+                assert_that!(task_result)
+                    .is_ok()
+                    .matches(|task| task.is_some() == visible);
+            }
+        }
+
+        #[tokio::test]
+        async fn read_shares_do_not_allow_changes() {
+            let task_persist = tasks_for_two_users();
+            share(&task_persist, None, SharePermission::Read).await;
+            let webhook_persist = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let update_result = TaskService
+                .update_task(
+                    &as_user(TEAMMATE),
+                    1,
+                    &UpdateTask {
+                        description: "Plan the offsite somewhere sunny".to_owned(),
+                    },
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhook_persist,
+                )
+                .await;
+            assert!(matches!(update_result, Err(TaskError::Forbidden(_))));
+        }
+
+        #[tokio::test]
+        async fn edit_shares_allow_changes() {
+            let task_persist = tasks_for_two_users();
+            share(&task_persist, Some(1), SharePermission::Edit).await;
+            let webhook_persist = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let update_result = TaskService
+                .update_task(
+                    &as_user(TEAMMATE),
+                    1,
+                    &UpdateTask {
+                        description: "Plan the offsite somewhere sunny".to_owned(),
+                    },
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhook_persist,
+                )
+                .await;
+            assert_that!(update_result).is_ok();
+
+            let complete_result = TaskService
+                .complete_task(
+                    &as_user(TEAMMATE),
+                    2,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhook_persist,
+                )
+                .await;
+            assert!(matches!(complete_result, Err(TaskError::Forbidden(_))));
+        }
+
+        #[tokio::test]
+        async fn edit_shares_allow_restoring_trashed_tasks() {
+            let task_persist = tasks_for_two_users();
+            share(&task_persist, Some(1), SharePermission::Edit).await;
+            share(&task_persist, Some(2), SharePermission::Read).await;
+            let webhook_persist = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            for task_id in [1, 2] {
+                TaskService
+                    .delete_task(
+                        &Caller::Trusted,
+                        task_id,
+                        &mut ext_cxn,
+                        &task_persist,
+                        &task_persist,
+                        &webhook_persist,
+                    )
+                    .await
+                    .expect("Could not delete task");
+            }
+
+            for (task_id, allowed) in [(1, true), (2, false)] {
+                let restore_result = TaskService
+                    .restore_task(
+                        &as_user(TEAMMATE),
+                        task_id,
+                        &mut ext_cxn,
+                        &task_persist,
+                        &task_persist,
+                        &webhook_persist,
+                    )
+                    .await;
+                assert_eq!(allowed, restore_result.is_ok(), "Task {task_id}");
+            }
+        }
+
+        #[tokio::test]
+        async fn batches_respect_share_permission() {
+            let task_persist = tasks_for_two_users();
+            share(&task_persist, Some(1), SharePermission::Read).await;
+            share(&task_persist, Some(2), SharePermission::Edit).await;
+            let webhook_persist = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            for (task_id, allowed) in [(1, false), (2, true)] {
+                let complete_result = TaskService
+                    .apply_batch_operation(
+                        &Caller::Trusted,
+                        TEAMMATE,
+                        &domain::todo::BatchOperation::Complete { task_id },
+                        &mut ext_cxn,
+                        &two_users(),
+                        &task_persist,
+                        &task_persist,
+                        &webhook_persist,
+                    )
+                    .await;
+                assert_eq!(allowed, complete_result.is_ok(), "Task {task_id}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driving_ports::SharePort;
+    use super::*;
+    use crate::domain::test_util::FakeImplementation;
+    use crate::domain::todo::test_util::{share_covers, InMemoryUserTaskPersistence};
+    use std::sync::{Mutex, RwLock};
+
+    impl ShareStore for RwLock<InMemoryUserTaskPersistence> {
+        async fn grant_share(
+            &self,
+            owner_user_id: i32,
+            new_share: &NewShare,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<i32, anyhow::Error> {
+            let mut persistence = self.write().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            if let Some(existing) = persistence.shares.iter_mut().find(|share| {
+                share.owner_user_id == owner_user_id
+                    && share.shared_with_user_id == new_share.shared_with_user_id
+                    && share.task_id == new_share.task_id
+            }) {
+                existing.permission = new_share.permission;
+                return Ok(existing.id);
+            }
+
+            let share_id = persistence
+                .shares
+                .iter()
+                .map(|share| share.id)
+                .max()
+                .unwrap_or_default()
+                + 1;
+            persistence.shares.push(TaskShare {
+                id: share_id,
+                owner_user_id,
+                shared_with_user_id: new_share.shared_with_user_id,
+                task_id: new_share.task_id,
+                permission: new_share.permission,
+            });
+            Ok(share_id)
+        }
+
+        async fn revoke_share(
+            &self,
+            owner_user_id: i32,
+            share_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error> {
+            let mut persistence = self.write().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let share_count = persistence.shares.len();
+            persistence
+                .shares
+                .retain(|share| share.id != share_id || share.owner_user_id != owner_user_id);
+            Ok(persistence.shares.len() < share_count)
+        }
+
+        async fn shares_granted_by(
+            &self,
+            owner_user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskShare>, anyhow::Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            Ok(persistence
+                .shares
+                .iter()
+                .filter(|share| share.owner_user_id == owner_user_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn tasks_shared_with(
+            &self,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<SharedTask>, anyhow::Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let mut shared_tasks: Vec<SharedTask> = persistence
+                .tasks
+                .iter()
+                .filter_map(|task| {
+                    let permission = persistence
+                        .shares
+                        .iter()
+                        .filter(|share| {
+                            share.shared_with_user_id == user_id && share_covers(share, task)
+                        })
+                        .map(|share| share.permission)
+                        .max()?;
+
+                    Some(SharedTask {
+                        task: task.clone(),
+                        permission,
+                    })
+                })
+                .collect();
+            shared_tasks.sort_by_key(|shared| shared.task.id);
+
+            Ok(shared_tasks)
+        }
+    }
+
+    /// A mock of ShareService for use in API tests
+    pub struct MockShareService {
+        pub grant_share_result: FakeImplementation<(i32, NewShare), Result<i32, ShareError>>,
+        pub revoke_share_result: FakeImplementation<(i32, i32), Result<(), ShareError>>,
+        pub shares_granted_by_result: FakeImplementation<i32, Result<Vec<TaskShare>, ShareError>>,
+        pub tasks_shared_with_result: FakeImplementation<i32, Result<Vec<SharedTask>, ShareError>>,
+    }
+
+    impl MockShareService {
+        /// Constructor for MockShareService
+        pub fn new() -> MockShareService {
+            MockShareService {
+                grant_share_result: FakeImplementation::new(),
+                revoke_share_result: FakeImplementation::new(),
+                shares_granted_by_result: FakeImplementation::new(),
+                tasks_shared_with_result: FakeImplementation::new(),
+            }
+        }
+
+        /// Constructs a new MockShareService, allowing for configuration of mocks
+        /// in the builder function before the mock is wrapped in a Mutex for use in API tests
+        pub fn build_locked(builder: impl FnOnce(&mut Self)) -> Mutex<Self> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Mutex::new(new_svc)
+        }
+    }
+
+    impl SharePort for Mutex<MockShareService> {
+        async fn grant_share(
+            &self,
+            _caller: &Caller,
+            owner_user_id: i32,
+            new_share: &NewShare,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _task_read: &impl TaskReader,
+            _share_store: &impl ShareStore,
+        ) -> Result<i32, ShareError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .grant_share_result
+                .save_arguments((owner_user_id, new_share.clone()));
+            locked_self.grant_share_result.return_value_result()
+        }
+
+        async fn revoke_share(
+            &self,
+            _caller: &Caller,
+            owner_user_id: i32,
+            share_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _share_store: &impl ShareStore,
+        ) -> Result<(), ShareError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .revoke_share_result
+                .save_arguments((owner_user_id, share_id));
+            locked_self.revoke_share_result.return_value_result()
+        }
+
+        async fn shares_granted_by(
+            &self,
+            _caller: &Caller,
+            owner_user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _share_store: &impl ShareStore,
+        ) -> Result<Vec<TaskShare>, ShareError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .shares_granted_by_result
+                .save_arguments(owner_user_id);
+            locked_self.shares_granted_by_result.return_value_result()
+        }
+
+        async fn tasks_shared_with(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _share_store: &impl ShareStore,
+        ) -> Result<Vec<SharedTask>, ShareError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self.tasks_shared_with_result.save_arguments(user_id);
+            locked_self.tasks_shared_with_result.return_value_result()
+        }
+    }
+}
@@ -0,0 +1,516 @@
+use crate::domain;
+use crate::domain::task_event::driven_ports::{TaskChangeLog, TaskChangeNotices};
+use crate::domain::todo::driving_ports::TaskError;
+use crate::external_connections::ExternalConnectivity;
+use anyhow::Context;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kinds of changes which can happen to a task
+pub enum TaskChangeKind {
+    Created,
+    Updated,
+    Completed,
+    Deleted,
+}
+
+impl TaskChangeKind {
+    /// The name the change is sent to clients with
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Created => "task.created",
+            Self::Updated => "task.updated",
+            Self::Completed => "task.completed",
+            Self::Deleted => "task.deleted",
+        }
+    }
+
+    /// Looks up a change kind by its [name][TaskChangeKind::name]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "task.created" => Some(Self::Created),
+            "task.updated" => Some(Self::Updated),
+            "task.completed" => Some(Self::Completed),
+            "task.deleted" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A change made to one of a user's tasks
+pub struct TaskChange {
+    /// Orders the changes to a user's tasks. Later changes always have a higher ID.
+    pub id: i64,
+    pub task_id: i32,
+    pub kind: TaskChangeKind,
+    /// The task's description after the change, or [None] if the task was deleted
+    pub description: Option<String>,
+    /// Whether the task is completed after the change, or [None] if the task was deleted
+    pub completed: Option<bool>,
+}
+
+/// The most changes returned from the change log at once
+pub const CHANGE_BATCH_SIZE: i64 = 100;
+
+/// The set of driven ports invoked by task event business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// An external system which keeps a log of recent changes to tasks
+    pub trait TaskChangeLog: Sync {
+        /// Retrieve up to `limit` of a user's task changes with an ID greater than `after_id`, oldest first
+        async fn changes_since(
+            &self,
+            user_id: i32,
+            after_id: i64,
+            limit: i64,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskChange>, anyhow::Error>;
+
+        /// Retrieve the ID of the most recent change to a user's tasks, or 0 if there are none
+        async fn latest_change_id(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<i64, anyhow::Error>;
+
+        /// Delete changes older than `age`, returning the number of changes removed
+        async fn purge_changes_older_than(
+            &self,
+            age: Duration,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<u64, anyhow::Error>;
+    }
+
+    /// A source of notifications sent when tasks change
+    pub trait TaskChangeNotices: Send {
+        /// Wait until the user's tasks may have changed. Returns an error if notifications will no longer be sent.
+        async fn wait_for_user(&mut self, user_id: i32) -> Result<(), anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for following changes to a user's tasks
+pub mod driving_ports {
+    use super::*;
+
+    /// The driving port which lets driving adapters follow changes to a user's tasks as they happen
+    pub trait TaskEventPort {
+        /// Prepare to follow a user's task changes, returning the ID of the last change the follower has seen.
+        /// Following resumes after `last_seen_id` if given, otherwise it starts with the next change to be made.
+        async fn start_following(
+            &self,
+            user_id: i32,
+            last_seen_id: Option<i64>,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            change_log: &impl driven_ports::TaskChangeLog,
+        ) -> Result<i64, TaskError>;
+
+        /// Retrieve the user's task changes made after `after_id`, waiting for a change to be made if there are none
+        async fn next_changes(
+            &self,
+            user_id: i32,
+            after_id: i64,
+            ext_cxn: &mut impl ExternalConnectivity,
+            change_log: &impl driven_ports::TaskChangeLog,
+            notices: &mut impl driven_ports::TaskChangeNotices,
+        ) -> Result<Vec<TaskChange>, anyhow::Error>;
+
+        /// Remove changes which are too old to be resumed from
+        async fn purge_old_changes(
+            &self,
+            retention: Duration,
+            ext_cxn: &mut impl ExternalConnectivity,
+            change_log: &impl driven_ports::TaskChangeLog,
+        ) -> Result<u64, anyhow::Error>;
+    }
+}
+
+/// Implementation of the driving port for following changes to tasks
+pub struct TaskEventService;
+
+impl driving_ports::TaskEventPort for TaskEventService {
+    async fn start_following(
+        &self,
+        user_id: i32,
+        last_seen_id: Option<i64>,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        change_log: &impl TaskChangeLog,
+    ) -> Result<i64, TaskError> {
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+        if let Some(last_seen_id) = last_seen_id {
+            return Ok(last_seen_id);
+        }
+
+        let latest_id = change_log
+            .latest_change_id(user_id, &mut *ext_cxn)
+            .await
+            .context("looking up the latest task change")?;
+        Ok(latest_id)
+    }
+
+    async fn next_changes(
+        &self,
+        user_id: i32,
+        after_id: i64,
+        ext_cxn: &mut impl ExternalConnectivity,
+        change_log: &impl TaskChangeLog,
+        notices: &mut impl TaskChangeNotices,
+    ) -> Result<Vec<TaskChange>, anyhow::Error> {
+        loop {
+            let changes = change_log
+                .changes_since(user_id, after_id, CHANGE_BATCH_SIZE, &mut *ext_cxn)
+                .await
+                .context("fetching task changes")?;
+            if !changes.is_empty() {
+                return Ok(changes);
+            }
+
+            notices
+                .wait_for_user(user_id)
+                .await
+                .context("waiting for task changes")?;
+        }
+    }
+
+    async fn purge_old_changes(
+        &self,
+        retention: Duration,
+        ext_cxn: &mut impl ExternalConnectivity,
+        change_log: &impl TaskChangeLog,
+    ) -> Result<u64, anyhow::Error> {
+        change_log
+            .purge_changes_older_than(retention, &mut *ext_cxn)
+            .await
+            .context("purging old task changes")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::TaskEventPort;
+    use super::test_util::*;
+    use super::*;
+    use crate::domain::test_util::Connectivity;
+    use crate::domain::user::test_util::InMemoryUserPersistence;
+    use crate::external_connections;
+    use speculoos::prelude::*;
+    use std::sync::RwLock;
+
+    #[test]
+    fn change_kind_names_round_trip() {
+        for kind in [
+            TaskChangeKind::Created,
+            TaskChangeKind::Updated,
+            TaskChangeKind::Completed,
+            TaskChangeKind::Deleted,
+        ] {
+            assert_eq!(Some(kind), TaskChangeKind::from_name(kind.name()));
+        }
+        assert_eq!(None, TaskChangeKind::from_name("task.exploded"));
+    }
+
+    mod start_following {
+        use super::*;
+
+        fn one_user() -> RwLock<InMemoryUserPersistence> {
+            RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+            ]))
+        }
+
+        #[tokio::test]
+        async fn resumes_after_last_seen_change() {
+            let user_persist = one_user();
+            let change_log = RwLock::new(InMemoryTaskChangeLog::new_with_changes(vec![
+                (1, created_change(1, 1)),
+                (1, created_change(2, 2)),
+            ]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let cursor = TaskEventService
+                .start_following(1, Some(1), &mut ext_cxn, &user_persist, &change_log)
+                .await;
+            assert_that!(cursor).is_ok_containing(1);
+        }
+
+        #[tokio::test]
+        async fn starts_at_latest_change_for_new_followers() {
+            let user_persist = one_user();
+            let change_log = RwLock::new(InMemoryTaskChangeLog::new_with_changes(vec![
+                (1, created_change(1, 1)),
+                (1, created_change(4, 2)),
+                (2, created_change(7, 3)),
+            ]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let cursor = TaskEventService
+                .start_following(1, None, &mut ext_cxn, &user_persist, &change_log)
+                .await;
+            assert_that!(cursor).is_ok_containing(4);
+        }
+
+        #[tokio::test]
+        async fn fails_if_user_doesnt_exist() {
+            let user_persist = InMemoryUserPersistence::new_locked();
+            let change_log = InMemoryTaskChangeLog::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let cursor = TaskEventService
+                .start_following(1, None, &mut ext_cxn, &user_persist, &change_log)
+                .await;
+            let Err(TaskError::UserDoesNotExist) = cursor else {
+                panic!(
+                    "Got an unexpected result from start_following: {:#?}",
+                    cursor
+                );
+            };
+        }
+    }
+
+    mod next_changes {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_users_changes_without_waiting() {
+            let change_log = RwLock::new(InMemoryTaskChangeLog::new_with_changes(vec![
+                (1, created_change(1, 1)),
+                (2, created_change(2, 2)),
+                (1, created_change(3, 3)),
+            ]));
+            let mut notices = FakeTaskChangeNotices::new();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let changes = TaskEventService
+                .next_changes(1, 0, &mut ext_cxn, &change_log, &mut notices)
+                .await;
+            assert_that!(changes)
+                .is_ok()
+                .is_equal_to(vec![created_change(1, 1), created_change(3, 3)]);
+            assert_that!(notices.waits).is_empty();
+        }
+
+        #[tokio::test]
+        async fn waits_for_notice_when_caught_up() {
+            let change_log = RwLock::new(InMemoryTaskChangeLog::new_with_changes(vec![(
+                1,
+                created_change(1, 1),
+            )]));
+            let mut notices = FakeTaskChangeNotices::new();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let changes = TaskEventService
+                .next_changes(1, 1, &mut ext_cxn, &change_log, &mut notices)
+                .await;
+            assert_that!(changes).is_err();
+            assert_that!(notices.waits).is_equal_to(vec![1]);
+        }
+
+        #[tokio::test]
+        async fn returns_port_error() {
+            let mut raw_change_log = InMemoryTaskChangeLog::new();
+            raw_change_log.connectivity = Connectivity::Disconnected;
+            let change_log = RwLock::new(raw_change_log);
+            let mut notices = FakeTaskChangeNotices::new();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let changes = TaskEventService
+                .next_changes(1, 0, &mut ext_cxn, &change_log, &mut notices)
+                .await;
+            assert_that!(changes).is_err();
+            assert_that!(notices.waits).is_empty();
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driving_ports::TaskEventPort;
+    use super::*;
+    use crate::domain::test_util::{Connectivity, FakeImplementation};
+    use anyhow::anyhow;
+    use std::sync::{Arc, Mutex, RwLock};
+
+    /// Creates a change recording the creation of a task
+    pub fn created_change(id: i64, task_id: i32) -> TaskChange {
+        TaskChange {
+            id,
+            task_id,
+            kind: TaskChangeKind::Created,
+            description: Some("Something to do".to_owned()),
+            completed: Some(false),
+        }
+    }
+
+    /// A fake change log which keeps changes in memory, along with the user who owns each changed task
+    pub struct InMemoryTaskChangeLog {
+        pub changes: Vec<(i32, TaskChange)>,
+        pub connectivity: Connectivity,
+    }
+
+    impl InMemoryTaskChangeLog {
+        /// Constructor for InMemoryTaskChangeLog
+        pub fn new() -> InMemoryTaskChangeLog {
+            Self::new_with_changes(Vec::new())
+        }
+
+        /// Constructor for InMemoryTaskChangeLog containing existing changes as (user ID, change) pairs
+        pub fn new_with_changes(changes: Vec<(i32, TaskChange)>) -> InMemoryTaskChangeLog {
+            InMemoryTaskChangeLog {
+                changes,
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for InMemoryTaskChangeLog which wraps it in an RwLock right away
+        pub fn new_locked() -> RwLock<InMemoryTaskChangeLog> {
+            RwLock::new(Self::new())
+        }
+    }
+
+    impl driven_ports::TaskChangeLog for RwLock<InMemoryTaskChangeLog> {
+        async fn changes_since(
+            &self,
+            user_id: i32,
+            after_id: i64,
+            limit: i64,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskChange>, anyhow::Error> {
+            let log = self.read().expect("task change log rwlock poisoned");
+            log.connectivity.blow_up_if_disconnected()?;
+
+            Ok(log
+                .changes
+                .iter()
+                .filter(|(owner, change)| *owner == user_id && change.id > after_id)
+                .take(limit as usize)
+                .map(|(_, change)| change.clone())
+                .collect())
+        }
+
+        async fn latest_change_id(
+            &self,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<i64, anyhow::Error> {
+            let log = self.read().expect("task change log rwlock poisoned");
+            log.connectivity.blow_up_if_disconnected()?;
+
+            Ok(log
+                .changes
+                .iter()
+                .filter(|(owner, _)| *owner == user_id)
+                .map(|(_, change)| change.id)
+                .max()
+                .unwrap_or(0))
+        }
+
+        async fn purge_changes_older_than(
+            &self,
+            _age: Duration,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<u64, anyhow::Error> {
+            let log = self.read().expect("task change log rwlock poisoned");
+            log.connectivity.blow_up_if_disconnected()?;
+
+            Ok(0)
+        }
+    }
+
+    /// Fake change notifications which record each wait, then report that notifications have stopped
+    pub struct FakeTaskChangeNotices {
+        pub waits: Vec<i32>,
+    }
+
+    impl FakeTaskChangeNotices {
+        /// Constructor for FakeTaskChangeNotices
+        pub fn new() -> FakeTaskChangeNotices {
+            FakeTaskChangeNotices { waits: Vec::new() }
+        }
+    }
+
+    impl driven_ports::TaskChangeNotices for FakeTaskChangeNotices {
+        async fn wait_for_user(&mut self, user_id: i32) -> Result<(), anyhow::Error> {
+            self.waits.push(user_id);
+            Err(anyhow!("no more task changes"))
+        }
+    }
+
+    /// A mock of TaskEventService for use in API tests. [next_changes][TaskEventPort::next_changes]
+    /// returns the configured changes which come after the requested ID, or an error once there are none
+    /// so streams built on it come to an end. The mock is shared so calls can still be inspected after
+    /// it's handed over to a stream.
+    pub struct MockTaskEventService {
+        pub start_following_result: FakeImplementation<(i32, Option<i64>), Result<i64, TaskError>>,
+        pub changes: Vec<TaskChange>,
+    }
+
+    impl MockTaskEventService {
+        /// Constructor for MockTaskEventService
+        pub fn new() -> MockTaskEventService {
+            MockTaskEventService {
+                start_following_result: FakeImplementation::new(),
+                changes: Vec::new(),
+            }
+        }
+
+        /// Constructs a new MockTaskEventService, allowing for configuration of mocks
+        /// in the builder function before the mock is shared for use in API tests
+        pub fn build_shared(builder: impl FnOnce(&mut Self)) -> Arc<Mutex<Self>> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Arc::new(Mutex::new(new_svc))
+        }
+    }
+
+    impl TaskEventPort for Arc<Mutex<MockTaskEventService>> {
+        async fn start_following(
+            &self,
+            user_id: i32,
+            last_seen_id: Option<i64>,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl domain::user::driven_ports::DetectUser,
+            _change_log: &impl driven_ports::TaskChangeLog,
+        ) -> Result<i64, TaskError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .start_following_result
+                .save_arguments((user_id, last_seen_id));
+            locked_self.start_following_result.return_value_result()
+        }
+
+        async fn next_changes(
+            &self,
+            _user_id: i32,
+            after_id: i64,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _change_log: &impl driven_ports::TaskChangeLog,
+            _notices: &mut impl driven_ports::TaskChangeNotices,
+        ) -> Result<Vec<TaskChange>, anyhow::Error> {
+            let locked_self = self.lock().expect("Lock is poisoned!");
+            let changes: Vec<TaskChange> = locked_self
+                .changes
+                .iter()
+                .filter(|change| change.id > after_id)
+                .cloned()
+                .collect();
+            if changes.is_empty() {
+                return Err(anyhow!("no more task changes"));
+            }
+
+            Ok(changes)
+        }
+
+        async fn purge_old_changes(
+            &self,
+            _retention: Duration,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _change_log: &impl driven_ports::TaskChangeLog,
+        ) -> Result<u64, anyhow::Error> {
+            Ok(0)
+        }
+    }
+}
@@ -1,23 +1,67 @@
 use crate::domain;
-use crate::domain::todo::driven_ports::{TaskReader, TaskWriter};
+use crate::domain::access::{authorize, is_unrestricted, AccessDenied, Action, Caller};
+use crate::domain::share::SharePermission;
+use crate::domain::todo::dependency::TaskDependency;
+use crate::domain::todo::driven_ports::{DependencyStore, TaskReader, TaskWriter};
 use crate::domain::todo::driving_ports::TaskError;
+use crate::domain::todo::recurrence::Recurrence;
+use crate::domain::webhook::driven_ports::EventOutbox;
+use crate::domain::webhook::WebhookEvent;
 use crate::external_connections::ExternalConnectivity;
 use anyhow::{Context, Error};
+use chrono::{DateTime, NaiveDate, Utc};
 use log::error;
+use std::time::Duration;
+
+pub mod dependency;
+pub mod recurrence;
+pub mod todo_txt;
 
 #[derive(PartialEq, Eq, Debug)]
 #[cfg_attr(test, derive(Clone))]
-/// A task available for a user
+/// A task available for a user. The task belongs to the user who created it, who may assign it to a different
+/// user to do.
 pub struct TodoTask {
     pub id: i32,
     pub owner_user_id: i32,
     pub item_desc: String,
+    pub completed: bool,
+    pub due_date: Option<NaiveDate>,
+    /// How the task repeats. Completing a recurring task creates its next occurrence.
+    pub recurrence: Option<Recurrence>,
+    /// The user who should do the task, or [None] if it's unassigned
+    pub assignee_user_id: Option<i32>,
+    /// How many comments have been left on the task
+    pub comment_count: i64,
+    /// Whether the task is blocked by another task which hasn't been completed yet. Blocked tasks can't be
+    /// completed.
+    pub blocked: bool,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(test, derive(Clone))]
+/// A task which has been moved to the trash. It stays there until it's restored or purged.
+pub struct TrashedTask {
+    pub task: TodoTask,
+    pub deleted_at: DateTime<Utc>,
 }
 
 #[cfg_attr(test, derive(Clone))]
 /// Contains information necessary to create a new task
 pub struct NewTask {
     pub description: String,
+    pub due_date: Option<NaiveDate>,
+    /// How the task repeats. Recurring tasks must have a due date, which is their first occurrence.
+    pub recurrence: Option<Recurrence>,
+    /// The user who should do the task, who must exist. Tasks without an assignee are unassigned.
+    pub assignee_user_id: Option<i32>,
+}
+
+#[cfg_attr(test, derive(Clone))]
+/// A task brought in from elsewhere, such as another to-do app, which may already be completed
+pub struct ImportedTask {
+    pub task: NewTask,
+    pub completed: bool,
 }
 
 #[cfg_attr(test, derive(Clone))]
@@ -26,12 +70,55 @@ pub struct UpdateTask {
     pub description: String,
 }
 
+#[derive(PartialEq, Eq, Debug, Default)]
+#[cfg_attr(test, derive(Clone))]
+/// Aggregate counts describing the state of a user's tasks
+pub struct TaskSummary {
+    pub total: i64,
+    pub completed: i64,
+    /// Tasks which are not completed and whose due date has passed. Tasks without a due date are never overdue.
+    pub overdue: i64,
+}
+
+#[derive(PartialEq, Debug)]
+#[cfg_attr(test, derive(Clone))]
+/// A task which matched a search, along with how well it matched
+pub struct TaskSearchMatch {
+    pub task: TodoTask,
+    /// Relevance of the task to the search. Higher values are better matches.
+    pub rank: f32,
+    /// An HTML-escaped excerpt of the task's description with matching terms wrapped in
+    /// [SEARCH_HIGHLIGHT_START] and [SEARCH_HIGHLIGHT_END]
+    pub snippet: String,
+}
+
+/// Marks the start of a matching term in a [TaskSearchMatch] snippet
+pub const SEARCH_HIGHLIGHT_START: &str = "<mark>";
+/// Marks the end of a matching term in a [TaskSearchMatch] snippet
+pub const SEARCH_HIGHLIGHT_END: &str = "</mark>";
+/// The most matches returned from a single task search
+pub const MAX_SEARCH_RESULTS: i64 = 50;
+/// The most upcoming occurrences of a recurring task which can be previewed at once
+pub const MAX_PREVIEWED_OCCURRENCES: usize = 100;
+/// The most tasks in a single page of an export. A shorter page is the last page.
+pub const EXPORT_PAGE_SIZE: i64 = 500;
+
+#[cfg_attr(test, derive(Clone))]
+/// A single change to a user's tasks which is applied as part of a batch
+pub enum BatchOperation {
+    Create(NewTask),
+    Update { task_id: i32, update: UpdateTask },
+    Delete { task_id: i32 },
+    Complete { task_id: i32 },
+}
+
 /// Contains the set of driven ports invoked by the business logic
 pub mod driven_ports {
     use super::*;
     use crate::external_connections::ExternalConnectivity;
 
-    /// An external system that can read a user's tasks
+    /// An external system that can read a user's tasks. Tasks in the trash are left out of every
+    /// read except [TaskReader::trashed_tasks_for_user].
     pub trait TaskReader {
         /// Retrieve the set of tasks for a user
         async fn tasks_for_user(
@@ -40,13 +127,103 @@ pub mod driven_ports {
             ext_cxn: &mut impl ExternalConnectivity,
         ) -> Result<Vec<TodoTask>, anyhow::Error>;
 
-        /// Retrieve a single task belonging to a user
+        /// Retrieve at most [limit] of a user's tasks in order of ID, starting after the task with ID
+        /// [after_task_id], or from the first task if it's [None]
+        async fn task_page_for_user(
+            &self,
+            user_id: i32,
+            after_task_id: Option<i32>,
+            limit: i64,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TodoTask>, anyhow::Error>;
+
+        /// Retrieve a single task which belongs to a user, is assigned to them, or which another user has
+        /// shared with them
         async fn user_task_by_id(
             &self,
             user_id: i32,
             task_id: i32,
             ext_cxn: &mut impl ExternalConnectivity,
         ) -> Result<Option<TodoTask>, anyhow::Error>;
+
+        /// Retrieve a single task by its ID, regardless of who owns it
+        async fn task_by_id(
+            &self,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TodoTask>, anyhow::Error>;
+
+        /// Look up the ID of the user who owns a task. Tasks in the trash have no owner here, so they can't be
+        /// shared or discussed until they're restored.
+        async fn task_owner(
+            &self,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<i32>, anyhow::Error>;
+
+        /// Look up the ID of the user who owns a task in the trash, so the task can be restored
+        async fn trashed_task_owner(
+            &self,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<i32>, anyhow::Error>;
+
+        /// Look up the ID of the user a task is assigned to, including tasks in the trash. [None] if the task is
+        /// unassigned or doesn't exist.
+        async fn task_assignee(
+            &self,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<i32>, anyhow::Error>;
+
+        /// Retrieve the tasks assigned to a user, whoever they belong to, in order of ID
+        async fn tasks_assigned_to(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TodoTask>, anyhow::Error>;
+
+        /// Look up the strongest permission a user has been granted on another user's task, whether it was
+        /// shared on its own or along with the rest of its owner's tasks. Tasks in the trash grant nothing.
+        async fn shared_permission(
+            &self,
+            user_id: i32,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<SharePermission>, anyhow::Error>;
+
+        /// Look up the strongest permission a user has been granted on another user's task in the trash, so
+        /// they can restore it if they could edit it before
+        async fn trashed_shared_permission(
+            &self,
+            user_id: i32,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<SharePermission>, anyhow::Error>;
+
+        /// Count a user's tasks by their state
+        async fn task_summary_for_user(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<TaskSummary, anyhow::Error>;
+
+        /// Search a user's task descriptions for the given text, returning at most [limit] matches
+        /// ordered from most to least relevant
+        async fn search_tasks(
+            &self,
+            user_id: i32,
+            search_text: &str,
+            limit: i64,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskSearchMatch>, anyhow::Error>;
+
+        /// Retrieve the tasks a user has moved to the trash, most recently trashed first
+        async fn trashed_tasks_for_user(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TrashedTask>, anyhow::Error>;
     }
 
     /// An external system that can edit the set of tasks for a user
@@ -59,13 +236,28 @@ pub mod driven_ports {
             ext_cxn: &mut impl ExternalConnectivity,
         ) -> Result<i32, anyhow::Error>;
 
-        /// Delete a task by its ID
+        /// Move a task to the trash by its ID
         async fn delete_task(
             &self,
             task_id: i32,
             ext_cxn: &mut impl ExternalConnectivity,
         ) -> Result<(), anyhow::Error>;
 
+        /// Take a task back out of the trash, returning it or [None] if the task wasn't in the trash
+        async fn restore_task(
+            &self,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TodoTask>, anyhow::Error>;
+
+        /// Permanently remove tasks which were moved to the trash longer than [retention] ago,
+        /// returning how many were removed
+        async fn purge_trashed_tasks(
+            &self,
+            retention: Duration,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<u64, anyhow::Error>;
+
         /// Update the content of an existing task
         async fn update_task(
             &self,
@@ -73,6 +265,53 @@ pub mod driven_ports {
             update: &UpdateTask,
             ext_cxn: &mut impl ExternalConnectivity,
         ) -> Result<(), anyhow::Error>;
+
+        /// Mark an existing task as completed
+        async fn complete_task(
+            &self,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Give an existing task to a different assignee, or unassign it if [assignee_user_id] is [None]
+        async fn assign_task(
+            &self,
+            task_id: i32,
+            assignee_user_id: Option<i32>,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+    }
+
+    /// An external system that keeps track of which tasks are blocked by which other tasks
+    pub trait DependencyStore {
+        /// Retrieve the dependencies between a user's tasks, including tasks in the trash
+        async fn dependencies_for_user(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskDependency>, anyhow::Error>;
+
+        /// Wait for anyone else changing a user's dependencies to finish, then keep others waiting until the
+        /// surrounding transaction ends, so the dependencies can't change between checking and adding one
+        async fn lock_dependencies_for_user(
+            &self,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Record that a task is blocked by another task. Recording a dependency which already exists does nothing.
+        async fn add_dependency(
+            &self,
+            dependency: &TaskDependency,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Stop a task from being blocked by another task, returning false if it wasn't blocked by it
+        async fn remove_dependency(
+            &self,
+            dependency: &TaskDependency,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error>;
     }
 }
 
@@ -89,6 +328,18 @@ pub mod driving_ports {
     pub enum TaskError {
         #[error("The specified user did not exist.")]
         UserDoesNotExist,
+        #[error("The specified task did not exist.")]
+        TaskDoesNotExist,
+        #[error("The user the task was assigned to did not exist.")]
+        AssigneeDoesNotExist,
+        #[error("The task is blocked by another task which has not been completed yet.")]
+        TaskBlocked,
+        #[error("The task would end up blocking itself.")]
+        DependencyCycle,
+        #[error("The task is not blocked by the specified task.")]
+        DependencyDoesNotExist,
+        #[error(transparent)]
+        Forbidden(#[from] domain::access::AccessDenied),
         #[error(transparent)]
         PortError(#[from] anyhow::Error),
     }
@@ -118,26 +369,36 @@ pub mod driving_ports {
             fn clone(&self) -> Self {
                 match self {
                     Self::UserDoesNotExist => Self::UserDoesNotExist,
+                    Self::TaskDoesNotExist => Self::TaskDoesNotExist,
+                    Self::AssigneeDoesNotExist => Self::AssigneeDoesNotExist,
+                    Self::TaskBlocked => Self::TaskBlocked,
+                    Self::DependencyCycle => Self::DependencyCycle,
+                    Self::DependencyDoesNotExist => Self::DependencyDoesNotExist,
+                    Self::Forbidden(denied) => Self::Forbidden(denied.clone()),
                     Self::PortError(err) => Self::PortError(anyhow!(format!("{}", err))),
                 }
             }
         }
     }
 
-    /// The driving port, or the set of business logic functions exposed to driving adapters
+    /// The driving port, or the set of business logic functions exposed to driving adapters. Functions
+    /// which act on behalf of a [Caller] fail with [TaskError::Forbidden] when the access policy doesn't
+    /// allow them.
     pub trait TaskPort {
         /// Retrieve the set of tasks belonging to a user
         async fn tasks_for_user(
             &self,
+            caller: &Caller,
             user_id: i32,
             ext_cxn: &mut impl ExternalConnectivity,
             u_detect: &impl domain::user::driven_ports::DetectUser,
             task_read: &impl driven_ports::TaskReader,
         ) -> Result<Vec<TodoTask>, TaskError>;
 
-        /// Retrieve a single task belonging to a user
+        /// Retrieve a single task belonging to a user, or which another user has shared with them
         async fn user_task_by_id(
             &self,
+            caller: &Caller,
             user_id: i32,
             task_id: i32,
             ext_cxn: &mut impl ExternalConnectivity,
@@ -145,32 +406,219 @@ pub mod driving_ports {
             task_read: &impl driven_ports::TaskReader,
         ) -> Result<Option<TodoTask>, TaskError>;
 
-        /// Create a new task for a user
+        /// Search a user's tasks by description, returning the best matches first
+        async fn search_tasks(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            search_text: &str,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl driven_ports::TaskReader,
+        ) -> Result<Vec<TaskSearchMatch>, TaskError>;
+
+        /// Retrieve the next page of a user's tasks for an export, in order of ID. The export starts from
+        /// the first task when [after_task_id] is [None], and otherwise continues after the last task of
+        /// the previous page. Pages hold [EXPORT_PAGE_SIZE] tasks, except for the last one.
+        async fn export_page(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            after_task_id: Option<i32>,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl driven_ports::TaskReader,
+        ) -> Result<Vec<TodoTask>, TaskError>;
+
+        /// Create a set of tasks for a user, returning the IDs of the new tasks in the same order. Records
+        /// the same events as creating, and if needed completing, each task on its own. Completed tasks
+        /// don't schedule their next occurrence, since the import is expected to contain it already.
+        #[allow(clippy::too_many_arguments)]
+        async fn import_tasks(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            tasks: &[ImportedTask],
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_write: &impl driven_ports::TaskWriter,
+            event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
+        ) -> Result<Vec<i32>, TaskError>;
+
+        /// Create a new task for a user, recording a [WebhookEvent::TaskCreated] event. Tasks created with an
+        /// assignee also record a [WebhookEvent::TaskAssigned] event.
+        #[allow(clippy::too_many_arguments)]
         async fn create_task_for_user(
             &self,
+            caller: &Caller,
             user_id: i32,
             task: &NewTask,
             ext_cxn: &mut impl ExternalConnectivity,
             u_detect: &impl domain::user::driven_ports::DetectUser,
             task_write: &impl driven_ports::TaskWriter,
+            event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
         ) -> Result<i32, TaskError>;
 
-        /// Delete a task by its ID
+        /// Move a task to the trash by its ID, recording a [WebhookEvent::TaskDeleted] event
         async fn delete_task(
             &self,
+            caller: &Caller,
             task_id: i32,
             ext_cxn: &mut impl ExternalConnectivity,
+            task_read: &impl driven_ports::TaskReader,
             task_write: &impl driven_ports::TaskWriter,
-        ) -> Result<(), anyhow::Error>;
+            event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
+        ) -> Result<(), TaskError>;
 
-        /// Update the content of an existing task
+        /// Mark a task as completed, recording a [WebhookEvent::TaskUpdated] event. If the task recurs, its
+        /// next occurrence is created and returned, recording a [WebhookEvent::TaskCreated] event. Tasks which
+        /// are blocked by unfinished tasks can't be completed.
+        async fn complete_task(
+            &self,
+            caller: &Caller,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            task_read: &impl driven_ports::TaskReader,
+            task_write: &impl driven_ports::TaskWriter,
+            event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
+        ) -> Result<Option<TodoTask>, TaskError>;
+
+        /// List the due dates of a user's task, starting with its current due date. Tasks which don't recur
+        /// have at most one.
+        #[allow(clippy::too_many_arguments)]
+        async fn upcoming_occurrences(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            limit: usize,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl driven_ports::TaskReader,
+        ) -> Result<Vec<NaiveDate>, TaskError>;
+
+        /// Retrieve the tasks a user has moved to the trash
+        async fn trashed_tasks_for_user(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl driven_ports::TaskReader,
+        ) -> Result<Vec<TrashedTask>, TaskError>;
+
+        /// Take a task back out of the trash, recording a [WebhookEvent::TaskCreated] event so
+        /// subscribers which forgot about the task learn of it again
+        async fn restore_task(
+            &self,
+            caller: &Caller,
+            task_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            task_read: &impl driven_ports::TaskReader,
+            task_write: &impl driven_ports::TaskWriter,
+            event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
+        ) -> Result<TodoTask, TaskError>;
+
+        /// Permanently remove tasks which have been in the trash longer than the retention period
+        async fn purge_trash(
+            &self,
+            retention: Duration,
+            ext_cxn: &mut impl ExternalConnectivity,
+            task_write: &impl driven_ports::TaskWriter,
+        ) -> Result<u64, anyhow::Error>;
+
+        /// Update the content of an existing task, recording a [WebhookEvent::TaskUpdated] event
+        #[allow(clippy::too_many_arguments)]
         async fn update_task(
             &self,
+            caller: &Caller,
             task_id: i32,
             update: &UpdateTask,
             ext_cxn: &mut impl ExternalConnectivity,
+            task_read: &impl driven_ports::TaskReader,
             task_write: &impl driven_ports::TaskWriter,
-        ) -> Result<(), anyhow::Error>;
+            event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
+        ) -> Result<(), TaskError>;
+
+        /// Give a task to a different assignee, or unassign it if [assignee_user_id] is [None], returning the
+        /// task as it's now assigned. Records a [WebhookEvent::TaskAssigned] event if the assignee changed.
+        #[allow(clippy::too_many_arguments)]
+        async fn assign_task(
+            &self,
+            caller: &Caller,
+            task_id: i32,
+            assignee_user_id: Option<i32>,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl driven_ports::TaskReader,
+            task_write: &impl driven_ports::TaskWriter,
+            event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
+        ) -> Result<TodoTask, TaskError>;
+
+        /// Retrieve the tasks assigned to a user, including ones which belong to other users
+        async fn tasks_assigned_to(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl driven_ports::TaskReader,
+        ) -> Result<Vec<TodoTask>, TaskError>;
+
+        /// Apply a single operation from a batch to a user's tasks, returning the ID of the affected task.
+        /// Operations on existing tasks fail if the task does not belong to the user. Records the same
+        /// event the operation would record if it were performed on its own.
+        #[allow(clippy::too_many_arguments)]
+        async fn apply_batch_operation(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            operation: &BatchOperation,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl driven_ports::TaskReader,
+            task_write: &impl driven_ports::TaskWriter,
+            event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
+        ) -> Result<i32, TaskError>;
+
+        /// Record that a user's task is blocked by another of their tasks, so it can't be completed until the
+        /// other task is. Fails with [TaskError::DependencyCycle] if the other task is already blocked by it,
+        /// directly or through other tasks.
+        #[allow(clippy::too_many_arguments)]
+        async fn add_dependency(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            dependency: &TaskDependency,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl driven_ports::TaskReader,
+            dependency_store: &impl driven_ports::DependencyStore,
+        ) -> Result<(), TaskError>;
+
+        /// Stop a user's task from being blocked by another task
+        #[allow(clippy::too_many_arguments)]
+        async fn remove_dependency(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            dependency: &TaskDependency,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl driven_ports::TaskReader,
+            dependency_store: &impl driven_ports::DependencyStore,
+        ) -> Result<(), TaskError>;
+
+        /// Retrieve a user's tasks ordered so each task comes after the tasks blocking it
+        async fn tasks_in_dependency_order(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_detect: &impl domain::user::driven_ports::DetectUser,
+            task_read: &impl driven_ports::TaskReader,
+            dependency_store: &impl driven_ports::DependencyStore,
+        ) -> Result<Vec<TodoTask>, TaskError>;
     }
 }
 
@@ -181,11 +629,18 @@ pub struct TaskService;
 impl driving_ports::TaskPort for TaskService {
     async fn tasks_for_user(
         &self,
+        caller: &Caller,
         user_id: i32,
         ext_cxn: &mut impl ExternalConnectivity,
         u_detect: &impl domain::user::driven_ports::DetectUser,
         task_read: &impl TaskReader,
     ) -> Result<Vec<TodoTask>, TaskError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
         domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
         let tasks_result = task_read.tasks_for_user(user_id, &mut *ext_cxn).await?;
 
@@ -194,12 +649,19 @@ impl driving_ports::TaskPort for TaskService {
 
     async fn user_task_by_id(
         &self,
+        caller: &Caller,
         user_id: i32,
         task_id: i32,
         ext_cxn: &mut impl ExternalConnectivity,
         u_detect: &impl domain::user::driven_ports::DetectUser,
         task_read: &impl TaskReader,
     ) -> Result<Option<TodoTask>, TaskError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
         domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
         let tasks_result = task_read
             .user_task_by_id(user_id, task_id, &mut *ext_cxn)
@@ -208,56 +670,815 @@ impl driving_ports::TaskPort for TaskService {
         Ok(tasks_result)
     }
 
+    async fn search_tasks(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        search_text: &str,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+    ) -> Result<Vec<TaskSearchMatch>, TaskError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+        let matches = task_read
+            .search_tasks(user_id, search_text, MAX_SEARCH_RESULTS, &mut *ext_cxn)
+            .await
+            .context("searching tasks")?;
+
+        Ok(matches)
+    }
+
+    async fn export_page(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        after_task_id: Option<i32>,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+    ) -> Result<Vec<TodoTask>, TaskError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+        let tasks = task_read
+            .task_page_for_user(user_id, after_task_id, EXPORT_PAGE_SIZE, &mut *ext_cxn)
+            .await
+            .context("reading tasks to export")?;
+
+        Ok(tasks)
+    }
+
+    async fn import_tasks(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        tasks: &[ImportedTask],
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_write: &impl TaskWriter,
+        event_outbox: &impl EventOutbox,
+    ) -> Result<Vec<i32>, TaskError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+
+        let mut task_ids = Vec::with_capacity(tasks.len());
+        for imported in tasks {
+            let task_id = task_write
+                .create_task_for_user(user_id, &imported.task, &mut *ext_cxn)
+                .await
+                .context("importing a task")?;
+            event_outbox
+                .record_event(
+                    &WebhookEvent::TaskCreated {
+                        task_id,
+                        user_id,
+                        description: imported.task.description.clone(),
+                    },
+                    &mut *ext_cxn,
+                )
+                .await
+                .context("recording an imported task")?;
+
+            if imported.completed {
+                task_write
+                    .complete_task(task_id, &mut *ext_cxn)
+                    .await
+                    .context("completing an imported task")?;
+                event_outbox
+                    .record_event(
+                        &WebhookEvent::TaskUpdated {
+                            task_id,
+                            description: None,
+                            completed: Some(true),
+                        },
+                        &mut *ext_cxn,
+                    )
+                    .await
+                    .context("recording completion of an imported task")?;
+            }
+
+            task_ids.push(task_id);
+        }
+
+        Ok(task_ids)
+    }
+
     async fn create_task_for_user(
         &self,
+        caller: &Caller,
         user_id: i32,
         task: &NewTask,
         ext_cxn: &mut impl ExternalConnectivity,
         u_detect: &impl domain::user::driven_ports::DetectUser,
         task_write: &impl TaskWriter,
+        event_outbox: &impl EventOutbox,
     ) -> Result<i32, TaskError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
         domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+        verify_assignee_exists(task.assignee_user_id, &mut *ext_cxn, u_detect).await?;
         let created_task_id = task_write
             .create_task_for_user(user_id, task, &mut *ext_cxn)
             .await?;
+        event_outbox
+            .record_event(
+                &WebhookEvent::TaskCreated {
+                    task_id: created_task_id,
+                    user_id,
+                    description: task.description.clone(),
+                },
+                &mut *ext_cxn,
+            )
+            .await
+            .context("recording task creation")?;
+        if task.assignee_user_id.is_some() {
+            event_outbox
+                .record_event(
+                    &WebhookEvent::TaskAssigned {
+                        task_id: created_task_id,
+                        assignee_user_id: task.assignee_user_id,
+                        previous_assignee_user_id: None,
+                    },
+                    &mut *ext_cxn,
+                )
+                .await
+                .context("recording the assignment of a new task")?;
+        }
         Ok(created_task_id)
     }
 
     async fn delete_task(
         &self,
+        caller: &Caller,
         task_id: i32,
         ext_cxn: &mut impl ExternalConnectivity,
+        task_read: &impl TaskReader,
         task_write: &impl TaskWriter,
-    ) -> Result<(), Error> {
+        event_outbox: &impl EventOutbox,
+    ) -> Result<(), TaskError> {
+        authorize_task_access(caller, task_id, &mut *ext_cxn, task_read).await?;
         task_write
             .delete_task(task_id, &mut *ext_cxn)
             .await
             .context("deleting a task")?;
+        event_outbox
+            .record_event(&WebhookEvent::TaskDeleted { task_id }, &mut *ext_cxn)
+            .await
+            .context("recording task deletion")?;
         Ok(())
     }
 
-    async fn update_task(
+    async fn complete_task(
         &self,
+        caller: &Caller,
         task_id: i32,
-        update: &UpdateTask,
         ext_cxn: &mut impl ExternalConnectivity,
+        task_read: &impl TaskReader,
         task_write: &impl TaskWriter,
-    ) -> Result<(), Error> {
-        task_write
-            .update_task(task_id, update, &mut *ext_cxn)
+        event_outbox: &impl EventOutbox,
+    ) -> Result<Option<TodoTask>, TaskError> {
+        authorize_task_access(caller, task_id, &mut *ext_cxn, task_read).await?;
+        let task = task_read
+            .task_by_id(task_id, &mut *ext_cxn)
             .await
-            .context("updating a task")?;
-        Ok(())
+            .context("looking up a task to complete")?
+            .ok_or(TaskError::TaskDoesNotExist)?;
+
+        let next_occurrence =
+            complete_and_schedule_next(&task, &mut *ext_cxn, task_write, event_outbox).await?;
+        Ok(next_occurrence)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::test_util::*;
+    async fn upcoming_occurrences(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        task_id: i32,
+        limit: usize,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+    ) -> Result<Vec<NaiveDate>, TaskError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+        let task = task_read
+            .user_task_by_id(user_id, task_id, &mut *ext_cxn)
+            .await
+            .context("looking up a task to preview")?
+            .ok_or(TaskError::TaskDoesNotExist)?;
+
+        let limit = limit.min(MAX_PREVIEWED_OCCURRENCES);
+        let occurrences = match (task.due_date, &task.recurrence) {
+            (Some(due_date), Some(recurrence)) => {
+                recurrence.occurrences(due_date).take(limit).collect()
+            }
+            (due_date, _) => due_date.into_iter().take(limit).collect(),
+        };
+        Ok(occurrences)
+    }
+
+    async fn trashed_tasks_for_user(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+    ) -> Result<Vec<TrashedTask>, TaskError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+        let trashed_tasks = task_read
+            .trashed_tasks_for_user(user_id, &mut *ext_cxn)
+            .await
+            .context("fetching trashed tasks")?;
+
+        Ok(trashed_tasks)
+    }
+
+    async fn restore_task(
+        &self,
+        caller: &Caller,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        task_read: &impl TaskReader,
+        task_write: &impl TaskWriter,
+        event_outbox: &impl EventOutbox,
+    ) -> Result<TodoTask, TaskError> {
+        authorize_trashed_task_access(caller, task_id, &mut *ext_cxn, task_read).await?;
+        let restored_task = task_write
+            .restore_task(task_id, &mut *ext_cxn)
+            .await
+            .context("restoring a task")?
+            .ok_or(TaskError::TaskDoesNotExist)?;
+        event_outbox
+            .record_event(
+                &WebhookEvent::TaskCreated {
+                    task_id,
+                    user_id: restored_task.owner_user_id,
+                    description: restored_task.item_desc.clone(),
+                },
+                &mut *ext_cxn,
+            )
+            .await
+            .context("recording task restoration")?;
+
+        Ok(restored_task)
+    }
+
+    async fn purge_trash(
+        &self,
+        retention: Duration,
+        ext_cxn: &mut impl ExternalConnectivity,
+        task_write: &impl TaskWriter,
+    ) -> Result<u64, Error> {
+        task_write
+            .purge_trashed_tasks(retention, &mut *ext_cxn)
+            .await
+            .context("purging trashed tasks")
+    }
+
+    async fn update_task(
+        &self,
+        caller: &Caller,
+        task_id: i32,
+        update: &UpdateTask,
+        ext_cxn: &mut impl ExternalConnectivity,
+        task_read: &impl TaskReader,
+        task_write: &impl TaskWriter,
+        event_outbox: &impl EventOutbox,
+    ) -> Result<(), TaskError> {
+        authorize_task_access(caller, task_id, &mut *ext_cxn, task_read).await?;
+        task_write
+            .update_task(task_id, update, &mut *ext_cxn)
+            .await
+            .context("updating a task")?;
+        event_outbox
+            .record_event(
+                &WebhookEvent::TaskUpdated {
+                    task_id,
+                    description: Some(update.description.clone()),
+                    completed: None,
+                },
+                &mut *ext_cxn,
+            )
+            .await
+            .context("recording task update")?;
+        Ok(())
+    }
+
+    async fn assign_task(
+        &self,
+        caller: &Caller,
+        task_id: i32,
+        assignee_user_id: Option<i32>,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        task_write: &impl TaskWriter,
+        event_outbox: &impl EventOutbox,
+    ) -> Result<TodoTask, TaskError> {
+        authorize_task_access(caller, task_id, &mut *ext_cxn, task_read).await?;
+        let task = task_read
+            .task_by_id(task_id, &mut *ext_cxn)
+            .await
+            .context("looking up a task to assign")?
+            .ok_or(TaskError::TaskDoesNotExist)?;
+        verify_assignee_exists(assignee_user_id, &mut *ext_cxn, u_detect).await?;
+        if task.assignee_user_id == assignee_user_id {
+            return Ok(task);
+        }
+
+        task_write
+            .assign_task(task_id, assignee_user_id, &mut *ext_cxn)
+            .await
+            .context("assigning a task")?;
+        event_outbox
+            .record_event(
+                &WebhookEvent::TaskAssigned {
+                    task_id,
+                    assignee_user_id,
+                    previous_assignee_user_id: task.assignee_user_id,
+                },
+                &mut *ext_cxn,
+            )
+            .await
+            .context("recording task assignment")?;
+
+        Ok(TodoTask {
+            assignee_user_id,
+            ..task
+        })
+    }
+
+    async fn tasks_assigned_to(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+    ) -> Result<Vec<TodoTask>, TaskError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+        let assigned_tasks = task_read
+            .tasks_assigned_to(user_id, &mut *ext_cxn)
+            .await
+            .context("fetching assigned tasks")?;
+
+        Ok(assigned_tasks)
+    }
+
+    async fn apply_batch_operation(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        operation: &BatchOperation,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        task_write: &impl TaskWriter,
+        event_outbox: &impl EventOutbox,
+    ) -> Result<i32, TaskError> {
+        let (task_id, event) = match operation {
+            BatchOperation::Create(new_task) => {
+                return self
+                    .create_task_for_user(
+                        caller,
+                        user_id,
+                        new_task,
+                        &mut *ext_cxn,
+                        u_detect,
+                        task_write,
+                        event_outbox,
+                    )
+                    .await;
+            }
+            BatchOperation::Update { task_id, update } => {
+                let task_id = *task_id;
+                editable_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read).await?;
+                task_write
+                    .update_task(task_id, update, &mut *ext_cxn)
+                    .await
+                    .context("updating a task in a batch")?;
+                let event = WebhookEvent::TaskUpdated {
+                    task_id,
+                    description: Some(update.description.clone()),
+                    completed: None,
+                };
+                (task_id, event)
+            }
+            BatchOperation::Delete { task_id } => {
+                let task_id = *task_id;
+                editable_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read).await?;
+                task_write
+                    .delete_task(task_id, &mut *ext_cxn)
+                    .await
+                    .context("deleting a task in a batch")?;
+                (task_id, WebhookEvent::TaskDeleted { task_id })
+            }
+            BatchOperation::Complete { task_id } => {
+                let task_id = *task_id;
+                let task =
+                    editable_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read)
+                        .await?;
+                complete_and_schedule_next(&task, &mut *ext_cxn, task_write, event_outbox).await?;
+                return Ok(task_id);
+            }
+        };
+        event_outbox
+            .record_event(&event, &mut *ext_cxn)
+            .await
+            .context("recording a task change in a batch")?;
+
+        Ok(task_id)
+    }
+
+    async fn add_dependency(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        dependency: &TaskDependency,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        dependency_store: &impl DependencyStore,
+    ) -> Result<(), TaskError> {
+        let task = editable_task(
+            caller,
+            user_id,
+            dependency.task_id,
+            &mut *ext_cxn,
+            u_detect,
+            task_read,
+        )
+        .await?;
+        // The blocking task must be one of the same user's tasks which the caller can see too
+        let blocking_task = task_read
+            .user_task_by_id(user_id, dependency.blocked_by_task_id, &mut *ext_cxn)
+            .await
+            .context("looking up the task blocking another task")?
+            .filter(|blocking_task| blocking_task.owner_user_id == task.owner_user_id)
+            .ok_or(TaskError::TaskDoesNotExist)?;
+
+        // Two dependencies added at once could each be fine alone but form a cycle together
+        dependency_store
+            .lock_dependencies_for_user(blocking_task.owner_user_id, &mut *ext_cxn)
+            .await
+            .context("locking a user's task dependencies")?;
+        let existing_dependencies = dependency_store
+            .dependencies_for_user(blocking_task.owner_user_id, &mut *ext_cxn)
+            .await
+            .context("looking up existing task dependencies")?;
+        if dependency::creates_cycle(&existing_dependencies, *dependency) {
+            return Err(TaskError::DependencyCycle);
+        }
+        dependency_store
+            .add_dependency(dependency, &mut *ext_cxn)
+            .await
+            .context("adding a task dependency")?;
+
+        Ok(())
+    }
+
+    async fn remove_dependency(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        dependency: &TaskDependency,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        dependency_store: &impl DependencyStore,
+    ) -> Result<(), TaskError> {
+        editable_task(
+            caller,
+            user_id,
+            dependency.task_id,
+            &mut *ext_cxn,
+            u_detect,
+            task_read,
+        )
+        .await?;
+        let removed = dependency_store
+            .remove_dependency(dependency, &mut *ext_cxn)
+            .await
+            .context("removing a task dependency")?;
+
+        if removed {
+            Ok(())
+        } else {
+            Err(TaskError::DependencyDoesNotExist)
+        }
+    }
+
+    async fn tasks_in_dependency_order(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_detect: &impl domain::user::driven_ports::DetectUser,
+        task_read: &impl TaskReader,
+        dependency_store: &impl DependencyStore,
+    ) -> Result<Vec<TodoTask>, TaskError> {
+        authorize(
+            caller,
+            Action::ManageTasks {
+                owner_user_id: user_id,
+            },
+        )?;
+        domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+        let tasks = task_read
+            .tasks_for_user(user_id, &mut *ext_cxn)
+            .await
+            .context("fetching tasks to order")?;
+        let dependencies = dependency_store
+            .dependencies_for_user(user_id, &mut *ext_cxn)
+            .await
+            .context("fetching the dependencies between tasks")?;
+
+        Ok(dependency::topological_order(tasks, &dependencies))
+    }
+}
+
+/// Looks up a task the user can see, checking that the caller may act for the user. Anything kept alongside
+/// tasks, such as comments or reminders, should be checked through here so it's visible to the same users.
+pub async fn visible_task(
+    caller: &Caller,
+    user_id: i32,
+    task_id: i32,
+    ext_cxn: &mut impl ExternalConnectivity,
+    u_detect: &impl domain::user::driven_ports::DetectUser,
+    task_read: &impl TaskReader,
+) -> Result<TodoTask, TaskError> {
+    authorize(
+        caller,
+        Action::ManageTasks {
+            owner_user_id: user_id,
+        },
+    )?;
+    domain::user::verify_user_exists(user_id, &mut *ext_cxn, u_detect).await?;
+
+    let task = task_read
+        .user_task_by_id(user_id, task_id, &mut *ext_cxn)
+        .await
+        .context("looking up a task the user can see")?
+        .ok_or(TaskError::TaskDoesNotExist)?;
+    Ok(task)
+}
+
+/// Looks up a task the user is changing, checking that they're allowed to change it
+async fn editable_task(
+    caller: &Caller,
+    user_id: i32,
+    task_id: i32,
+    ext_cxn: &mut impl ExternalConnectivity,
+    u_detect: &impl domain::user::driven_ports::DetectUser,
+    task_read: &impl TaskReader,
+) -> Result<TodoTask, TaskError> {
+    let task = visible_task(caller, user_id, task_id, &mut *ext_cxn, u_detect, task_read).await?;
+    authorize_edit(user_id, &task, &mut *ext_cxn, task_read).await?;
+
+    Ok(task)
+}
+
+/// Checks that a caller may change a task which isn't in the trash. Besides the task's owner, the user it's
+/// assigned to and users it has been shared with for editing may change it. A task which doesn't exist has
+/// nothing to protect, so callers may go on to find that out for themselves.
+async fn authorize_task_access(
+    caller: &Caller,
+    task_id: i32,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_read: &impl TaskReader,
+) -> Result<(), TaskError> {
+    authorize_task_access_in(caller, task_id, false, ext_cxn, task_read).await
+}
+
+/// Checks that a caller may restore a task from the trash, which the same users who could change it before it
+/// was deleted may do
+async fn authorize_trashed_task_access(
+    caller: &Caller,
+    task_id: i32,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_read: &impl TaskReader,
+) -> Result<(), TaskError> {
+    authorize_task_access_in(caller, task_id, true, ext_cxn, task_read).await
+}
+
+/// Checks that a caller may change a task, looking for it either in or out of the trash
+async fn authorize_task_access_in(
+    caller: &Caller,
+    task_id: i32,
+    in_trash: bool,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_read: &impl TaskReader,
+) -> Result<(), TaskError> {
+    if is_unrestricted(caller) {
+        return Ok(());
+    }
+
+    let owner = if in_trash {
+        task_read.trashed_task_owner(task_id, &mut *ext_cxn).await
+    } else {
+        task_read.task_owner(task_id, &mut *ext_cxn).await
+    }
+    .context("looking up the owner of a task")?;
+    let Some(owner_user_id) = owner else {
+        return Ok(());
+    };
+    let Err(denied) = authorize(caller, Action::ManageTasks { owner_user_id }) else {
+        return Ok(());
+    };
+
+    if let Caller::User { user_id, .. } = caller {
+        let assignee = task_read
+            .task_assignee(task_id, &mut *ext_cxn)
+            .await
+            .context("looking up the assignee of a task")?;
+        if assignee == Some(*user_id) {
+            return Ok(());
+        }
+        let permission = if in_trash {
+            task_read
+                .trashed_shared_permission(*user_id, task_id, &mut *ext_cxn)
+                .await
+        } else {
+            task_read
+                .shared_permission(*user_id, task_id, &mut *ext_cxn)
+                .await
+        }
+        .context("looking up the permission shared on a task")?;
+        if permission == Some(SharePermission::Edit) {
+            return Ok(());
+        }
+    }
+    Err(denied.into())
+}
+
+/// Checks that a user may change a task they can see, which they may if they own it, it's assigned to them, or
+/// it was shared with them for editing
+async fn authorize_edit(
+    user_id: i32,
+    task: &TodoTask,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_read: &impl TaskReader,
+) -> Result<(), TaskError> {
+    if task.owner_user_id == user_id || task.assignee_user_id == Some(user_id) {
+        return Ok(());
+    }
+
+    let permission = task_read
+        .shared_permission(user_id, task.id, &mut *ext_cxn)
+        .await
+        .context("looking up the permission shared on a task")?;
+    if permission == Some(SharePermission::Edit) {
+        Ok(())
+    } else {
+        Err(AccessDenied {
+            action: Action::ManageTasks {
+                owner_user_id: task.owner_user_id,
+            },
+        }
+        .into())
+    }
+}
+
+/// Checks that the user a task is being assigned to exists. Unassigned tasks have nobody to check.
+async fn verify_assignee_exists(
+    assignee_user_id: Option<i32>,
+    ext_cxn: &mut impl ExternalConnectivity,
+    u_detect: &impl domain::user::driven_ports::DetectUser,
+) -> Result<(), TaskError> {
+    let Some(assignee_user_id) = assignee_user_id else {
+        return Ok(());
+    };
+
+    let assignee_exists = u_detect
+        .user_exists(assignee_user_id, &mut *ext_cxn)
+        .await
+        .context("looking up the user a task is assigned to")?;
+    if assignee_exists {
+        Ok(())
+    } else {
+        Err(TaskError::AssigneeDoesNotExist)
+    }
+}
+
+/// Marks a task as completed and, if it recurs, creates its next occurrence, recording an event for each
+/// change. Returns the next occurrence if one was created. Completing a task which is already completed
+/// doesn't create another occurrence, and tasks which are blocked can't be completed at all.
+async fn complete_and_schedule_next(
+    task: &TodoTask,
+    ext_cxn: &mut impl ExternalConnectivity,
+    task_write: &impl TaskWriter,
+    event_outbox: &impl EventOutbox,
+) -> Result<Option<TodoTask>, TaskError> {
+    if task.blocked {
+        return Err(TaskError::TaskBlocked);
+    }
+
+    task_write
+        .complete_task(task.id, &mut *ext_cxn)
+        .await
+        .context("completing a task")?;
+    event_outbox
+        .record_event(
+            &WebhookEvent::TaskUpdated {
+                task_id: task.id,
+                description: None,
+                completed: Some(true),
+            },
+            &mut *ext_cxn,
+        )
+        .await
+        .context("recording task completion")?;
+
+    if task.completed {
+        return Ok(None);
+    }
+    let (Some(due_date), Some(recurrence)) = (task.due_date, &task.recurrence) else {
+        return Ok(None);
+    };
+    let Some((next_due_date, next_recurrence)) = recurrence.next_occurrence(due_date) else {
+        return Ok(None);
+    };
+
+    let next_task = NewTask {
+        description: task.item_desc.clone(),
+        due_date: Some(next_due_date),
+        recurrence: Some(next_recurrence),
+        assignee_user_id: task.assignee_user_id,
+    };
+    let next_task_id = task_write
+        .create_task_for_user(task.owner_user_id, &next_task, &mut *ext_cxn)
+        .await
+        .context("creating the next occurrence of a task")?;
+    event_outbox
+        .record_event(
+            &WebhookEvent::TaskCreated {
+                task_id: next_task_id,
+                user_id: task.owner_user_id,
+                description: next_task.description.clone(),
+            },
+            &mut *ext_cxn,
+        )
+        .await
+        .context("recording the next occurrence of a task")?;
+
+    Ok(Some(TodoTask {
+        id: next_task_id,
+        owner_user_id: task.owner_user_id,
+        item_desc: next_task.description,
+        completed: false,
+        due_date: next_task.due_date,
+        recurrence: next_task.recurrence,
+        assignee_user_id: next_task.assignee_user_id,
+        comment_count: 0,
+        blocked: false,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_util::*;
     use super::*;
     use crate::domain::todo::driving_ports::TaskPort;
     use crate::domain::user::test_util::InMemoryUserPersistence;
     use crate::domain::user::CreateUser;
+    use crate::domain::webhook::test_util::InMemoryWebhookPersistence;
     use crate::external_connections;
     use speculoos::prelude::*;
     use std::sync::RwLock;
@@ -267,28 +1488,37 @@ mod tests {
 
         #[tokio::test]
         async fn happy_path() {
-            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
-                domain::user::test_util::user_create_default(),
-                domain::user::test_util::user_create_default(),
-            ]));
+            let user_persist = domain::user::test_util::two_users();
             let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
                 NewTaskWithOwner {
                     owner: 1,
                     task: NewTask {
                         description: "Something to do".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
                 NewTaskWithOwner {
                     owner: 2,
                     task: NewTask {
                         description: "Another thing to do".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
             ]));
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
             let fetched_tasks = TaskService {}
-                .tasks_for_user(1, &mut ext_cxn, &user_persist, &task_persist)
+                .tasks_for_user(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
                 .await;
             assert_that!(fetched_tasks).is_ok().matches(|tasks| {
                 matches!(tasks.as_slice(), [
@@ -296,6 +1526,12 @@ mod tests {
                         id: 1,
                         owner_user_id: 1,
                         item_desc,
+                        completed: false,
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                        comment_count: 0,
+                        blocked: false,
                     }
                 ] if item_desc == "Something to do")
             });
@@ -308,7 +1544,13 @@ mod tests {
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
             let fetched_task_result = TaskService {}
-                .tasks_for_user(1, &mut ext_cxn, &user_persist, &task_persist)
+                .tasks_for_user(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
                 .await;
             let Err(TaskError::UserDoesNotExist) = fetched_task_result else {
                 panic!(
@@ -324,34 +1566,47 @@ mod tests {
 
         #[tokio::test]
         async fn happy_path() {
-            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
-                domain::user::test_util::user_create_default(),
-                domain::user::test_util::user_create_default(),
-            ]));
+            let user_persist = domain::user::test_util::two_users();
             let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
                 NewTaskWithOwner {
                     owner: 1,
                     task: NewTask {
                         description: "abcde".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
                 NewTaskWithOwner {
                     owner: 1,
                     task: NewTask {
                         description: "fghijk".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
                 NewTaskWithOwner {
                     owner: 2,
                     task: NewTask {
                         description: "lmnop".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
             ]));
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
             let task_fetch_result = TaskService {}
-                .user_task_by_id(1, 2, &mut ext_cxn, &user_persist, &task_persist)
+                .user_task_by_id(
+                    &Caller::Trusted,
+                    1,
+                    2,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
                 .await;
             assert_that!(task_fetch_result)
                 .is_ok()
@@ -360,41 +1615,60 @@ mod tests {
                     matches!(task, TodoTask {
                        id: 2,
                        owner_user_id: 1,
-                       item_desc
+                       item_desc,
+                       completed: false,
+                       due_date: None,
+                       recurrence: None,
+                        assignee_user_id: None,
+                        comment_count: 0,
+                        blocked: false,
                     } if item_desc == "fghijk")
                 });
         }
 
         #[tokio::test]
         async fn happy_path_not_found() {
-            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
-                domain::user::test_util::user_create_default(),
-                domain::user::test_util::user_create_default(),
-            ]));
+            let user_persist = domain::user::test_util::two_users();
             let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
                 NewTaskWithOwner {
                     owner: 1,
                     task: NewTask {
                         description: "abcde".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
                 NewTaskWithOwner {
                     owner: 1,
                     task: NewTask {
                         description: "fghijk".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
                 NewTaskWithOwner {
                     owner: 2,
                     task: NewTask {
                         description: "lmnop".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
             ]));
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
             let task_fetch_result = TaskService {}
-                .user_task_by_id(1, 3, &mut ext_cxn, &user_persist, &task_persist)
+                .user_task_by_id(
+                    &Caller::Trusted,
+                    1,
+                    3,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
                 .await;
             assert_that!(task_fetch_result).is_ok().is_none();
         }
@@ -406,7 +1680,14 @@ mod tests {
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
             let task_fetch_result = TaskService {}
-                .user_task_by_id(1, 5, &mut ext_cxn, &user_persist, &task_persist)
+                .user_task_by_id(
+                    &Caller::Trusted,
+                    1,
+                    5,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
                 .await;
             let Err(TaskError::UserDoesNotExist) = task_fetch_result else {
                 panic!(
@@ -417,46 +1698,433 @@ mod tests {
         }
     }
 
-    mod create_task_for_user {
+    mod search_tasks {
         use super::*;
 
         #[tokio::test]
         async fn happy_path() {
-            let task_persist = InMemoryUserTaskPersistence::new_locked();
-            let user_persist =
-                RwLock::new(InMemoryUserPersistence::new_with_users(&[CreateUser {
-                    first_name: "John".to_owned(),
-                    last_name: "Doe".to_owned(),
-                }]));
+            let user_persist = domain::user::test_util::two_users();
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Buy groceries".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Walk the dog".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+                NewTaskWithOwner {
+                    owner: 2,
+                    task: NewTask {
+                        description: "Buy a new car".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]));
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
-            let task = NewTask {
-                description: "Something to do".to_owned(),
-            };
-            let service = TaskService {};
 
-            let create_result = service
-                .create_task_for_user(1, &task, &mut ext_cxn, &user_persist, &task_persist)
+            let search_result = TaskService {}
+                .search_tasks(
+                    &Caller::Trusted,
+                    1,
+                    "buy",
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
                 .await;
-            assert_that!(create_result).is_ok_containing(1);
+            assert_that!(search_result).is_ok().matches(|matches| {
+                matches!(matches.as_slice(), [
+                    TaskSearchMatch {
+                        task: TodoTask { id: 1, .. },
+                        snippet,
+                        ..
+                    }
+                ] if snippet == "<mark>Buy</mark> groceries")
+            });
         }
 
         #[tokio::test]
-        async fn does_not_allow_tasks_for_nonexistent_user() {
-            let writer = InMemoryUserTaskPersistence::new_locked();
-            let user_detector = InMemoryUserPersistence::new_locked();
-            let task = NewTask {
-                description: String::new(),
-            };
+        async fn fails_if_user_doesnt_exist() {
+            let user_persist = InMemoryUserPersistence::new_locked();
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let search_result = TaskService {}
+                .search_tasks(
+                    &Caller::Trusted,
+                    1,
+                    "buy",
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(search_result, Err(TaskError::UserDoesNotExist)));
+        }
+    }
+
+    mod export_page {
+        use super::*;
+
+        #[tokio::test]
+        async fn continues_after_previous_page() {
+            let user_persist = domain::user::test_util::two_users();
+            let tasks: Vec<NewTaskWithOwner> = (0..EXPORT_PAGE_SIZE + 2)
+                .map(|idx| NewTaskWithOwner {
+                    owner: if idx == 1 { 2 } else { 1 },
+                    task: NewTask {
+                        description: format!("Task {idx}"),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                })
+                .collect();
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&tasks));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let first_page = TaskService {}
+                .export_page(
+                    &Caller::Trusted,
+                    1,
+                    None,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await
+                .unwrap();
+            assert_eq!(EXPORT_PAGE_SIZE as usize, first_page.len());
+            assert!(first_page.iter().all(|task| task.owner_user_id == 1));
+
+            let last_task_id = first_page.last().unwrap().id;
+            let second_page_result = TaskService {}
+                .export_page(
+                    &Caller::Trusted,
+                    1,
+                    Some(last_task_id),
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert_that!(second_page_result).is_ok().matches(
+                |page| matches!(page.as_slice(), [TodoTask { id, .. }] if *id == last_task_id + 1),
+            );
+        }
+
+        #[tokio::test]
+        async fn fails_if_user_doesnt_exist() {
+            let user_persist = InMemoryUserPersistence::new_locked();
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let export_result = TaskService {}
+                .export_page(
+                    &Caller::Trusted,
+                    1,
+                    None,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(export_result, Err(TaskError::UserDoesNotExist)));
+        }
+    }
+
+    mod import_tasks {
+        use super::*;
+
+        #[tokio::test]
+        async fn creates_and_completes_tasks() {
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+            ]));
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let tasks = [
+                ImportedTask {
+                    task: NewTask {
+                        description: "Already done".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                    completed: true,
+                },
+                ImportedTask {
+                    task: NewTask {
+                        description: "Still to do".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                    completed: false,
+                },
+            ];
+
+            let import_result = TaskService {}
+                .import_tasks(
+                    &Caller::Trusted,
+                    1,
+                    &tasks,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(import_result).is_ok_containing(vec![1, 2]);
+
+            let locked_tasks = task_persist.read().expect("task persist rw lock poisoned");
+            assert!(matches!(
+                locked_tasks.tasks.as_slice(),
+                [
+                    TodoTask {
+                        id: 1,
+                        owner_user_id: 1,
+                        completed: true,
+                        ..
+                    },
+                    TodoTask {
+                        id: 2,
+                        owner_user_id: 1,
+                        completed: false,
+                        ..
+                    },
+                ]
+            ));
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert!(matches!(
+                locked_webhooks.events.as_slice(),
+                [
+                    WebhookEvent::TaskCreated {
+                        task_id: 1,
+                        user_id: 1,
+                        ..
+                    },
+                    WebhookEvent::TaskUpdated {
+                        task_id: 1,
+                        description: None,
+                        completed: Some(true),
+                    },
+                    WebhookEvent::TaskCreated {
+                        task_id: 2,
+                        user_id: 1,
+                        ..
+                    },
+                ]
+            ));
+        }
+
+        #[tokio::test]
+        async fn fails_if_user_doesnt_exist() {
+            let user_persist = InMemoryUserPersistence::new_locked();
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let tasks = [ImportedTask {
+                task: NewTask {
+                    description: "Something to do".to_owned(),
+                    due_date: None,
+                    recurrence: None,
+                    assignee_user_id: None,
+                },
+                completed: false,
+            }];
+
+            let import_result = TaskService {}
+                .import_tasks(
+                    &Caller::Trusted,
+                    1,
+                    &tasks,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert!(matches!(import_result, Err(TaskError::UserDoesNotExist)));
+
+            let locked_tasks = task_persist.read().expect("task persist rw lock poisoned");
+            assert!(locked_tasks.tasks.is_empty());
+        }
+    }
+
+    mod create_task_for_user {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let user_persist =
+                RwLock::new(InMemoryUserPersistence::new_with_users(&[CreateUser {
+                    first_name: "John".to_owned(),
+                    last_name: "Doe".to_owned(),
+                }]));
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task = NewTask {
+                description: "Something to do".to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+            };
+            let service = TaskService {};
+
+            let create_result = service
+                .create_task_for_user(
+                    &Caller::Trusted,
+                    1,
+                    &task,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(create_result).is_ok_containing(1);
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert!(matches!(locked_webhooks.events.as_slice(), [
+                WebhookEvent::TaskCreated {
+                    task_id: 1,
+                    user_id: 1,
+                    description,
+                }
+            ] if description == "Something to do"));
+        }
+
+        #[tokio::test]
+        async fn does_not_allow_tasks_for_nonexistent_user() {
+            let writer = InMemoryUserTaskPersistence::new_locked();
+            let user_detector = InMemoryUserPersistence::new_locked();
+            let task = NewTask {
+                description: String::new(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+            };
+            let webhooks = InMemoryWebhookPersistence::new_locked();
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
             let service = TaskService {};
 
             let create_result = service
-                .create_task_for_user(1, &task, &mut ext_cxn, &user_detector, &writer)
+                .create_task_for_user(
+                    &Caller::Trusted,
+                    1,
+                    &task,
+                    &mut ext_cxn,
+                    &user_detector,
+                    &writer,
+                    &webhooks,
+                )
                 .await;
             let Err(TaskError::UserDoesNotExist) = create_result else {
                 panic!("Did not get expected error, instead got this: {create_result:#?}");
             };
         }
+
+        #[tokio::test]
+        async fn assigns_task_to_another_user() {
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+                CreateUser {
+                    first_name: "Jane".to_owned(),
+                    last_name: "Doe".to_owned(),
+                },
+            ]));
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task = NewTask {
+                description: "Something to do".to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: Some(2),
+            };
+
+            let create_result = TaskService {}
+                .create_task_for_user(
+                    &Caller::Trusted,
+                    1,
+                    &task,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(create_result).is_ok_containing(1);
+
+            let locked_tasks = task_persist.read().expect("task rwlock poisoned");
+            assert_eq!(1, locked_tasks.tasks[0].owner_user_id);
+            assert_eq!(Some(2), locked_tasks.tasks[0].assignee_user_id);
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert!(matches!(
+                locked_webhooks.events.as_slice(),
+                [
+                    WebhookEvent::TaskCreated { task_id: 1, .. },
+                    WebhookEvent::TaskAssigned {
+                        task_id: 1,
+                        assignee_user_id: Some(2),
+                        previous_assignee_user_id: None,
+                    }
+                ]
+            ));
+        }
+
+        #[tokio::test]
+        async fn does_not_allow_nonexistent_assignee() {
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+            ]));
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let task = NewTask {
+                description: "Something to do".to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: Some(2),
+            };
+
+            let create_result = TaskService {}
+                .create_task_for_user(
+                    &Caller::Trusted,
+                    1,
+                    &task,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            let Err(TaskError::AssigneeDoesNotExist) = create_result else {
+                panic!("Did not get expected error, instead got this: {create_result:#?}");
+            };
+            assert!(task_persist
+                .read()
+                .expect("task rwlock poisoned")
+                .tasks
+                .is_empty());
+        }
     }
 
     mod delete_task {
@@ -470,128 +2138,1585 @@ mod tests {
                     owner: 1,
                     task: NewTask {
                         description: "abcde".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
                 NewTaskWithOwner {
                     owner: 1,
                     task: NewTask {
                         description: "fghij".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
             ]));
+            let webhooks = InMemoryWebhookPersistence::new_locked();
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
-            let delete_result = TaskService {}.delete_task(2, &mut ext_cxn, &writer).await;
+            let delete_result = TaskService {}
+                .delete_task(
+                    &Caller::Trusted,
+                    2,
+                    &mut ext_cxn,
+                    &writer,
+                    &writer,
+                    &webhooks,
+                )
+                .await;
             assert_that!(delete_result).is_ok();
 
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_eq!(
+                vec![WebhookEvent::TaskDeleted { task_id: 2 }],
+                locked_webhooks.events
+            );
+
             let locked_writer = writer.read().expect("task writer rw lock poisoned");
             assert!(matches!(locked_writer.tasks.as_slice(), [
                     TodoTask {
                         id: 1,
                         owner_user_id: 1,
                         item_desc,
+                        completed: false,
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                        comment_count: 0,
+                        blocked: false,
                     }
                 ] if item_desc == "abcde"));
+            assert!(matches!(
+                locked_writer.trash.as_slice(),
+                [TrashedTask {
+                    task: TodoTask { id: 2, .. },
+                    ..
+                }]
+            ));
         }
 
         #[tokio::test]
         async fn happy_path_task_doesnt_exist() {
             let writer = InMemoryUserTaskPersistence::new_locked();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let delete_result = TaskService {}
+                .delete_task(
+                    &Caller::Trusted,
+                    5,
+                    &mut ext_cxn,
+                    &writer,
+                    &writer,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(delete_result).is_ok();
+        }
+
+        #[tokio::test]
+        async fn returns_port_err() {
+            let writer = InMemoryUserTaskPersistence::new_locked();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            {
+                let mut locked_writer = writer.write().expect("writer rw lock poisoned");
+                locked_writer.connected = Connectivity::Disconnected;
+            }
+
+            let delete_result = TaskService {}
+                .delete_task(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &writer,
+                    &writer,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(delete_result).is_err();
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_that!(locked_webhooks.events).is_empty();
+        }
+    }
+
+    mod complete_task {
+        use super::*;
+
+        fn weekly_task(completed: bool) -> RwLock<InMemoryUserTaskPersistence> {
+            let task_persist = InMemoryUserTaskPersistence::new_with_tasks(&[NewTaskWithOwner {
+                owner: 1,
+                task: NewTask {
+                    description: "Take out the trash".to_owned(),
+                    due_date: NaiveDate::from_ymd_opt(2024, 3, 4),
+                    recurrence: Some("FREQ=WEEKLY;COUNT=3".parse().unwrap()),
+                    assignee_user_id: None,
+                },
+            }]);
+            let task_persist = RwLock::new(task_persist);
+            task_persist.write().unwrap().tasks[0].completed = completed;
+            task_persist
+        }
+
+        #[tokio::test]
+        async fn creates_next_occurrence_of_recurring_task() {
+            let task_persist = weekly_task(false);
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let complete_result = TaskService {}
+                .complete_task(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(complete_result)
+                .is_ok()
+                .is_some()
+                .matches(|task| {
+                    matches!(task, TodoTask {
+                        id: 2,
+                        owner_user_id: 1,
+                        completed: false,
+                        due_date: Some(due_date),
+                        recurrence: Some(recurrence),
+                        ..
+                    } if *due_date == NaiveDate::from_ymd_opt(2024, 3, 11).unwrap()
+                        && recurrence.to_string() == "FREQ=WEEKLY;COUNT=2")
+                });
+
+            let locked_tasks = task_persist.read().expect("task persist rw lock poisoned");
+            assert!(matches!(
+                locked_tasks.tasks.as_slice(),
+                [
+                    TodoTask {
+                        id: 1,
+                        completed: true,
+                        ..
+                    },
+                    TodoTask {
+                        id: 2,
+                        completed: false,
+                        ..
+                    },
+                ]
+            ));
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_eq!(
+                vec![
+                    WebhookEvent::TaskUpdated {
+                        task_id: 1,
+                        description: None,
+                        completed: Some(true),
+                    },
+                    WebhookEvent::TaskCreated {
+                        task_id: 2,
+                        user_id: 1,
+                        description: "Take out the trash".to_owned(),
+                    },
+                ],
+                locked_webhooks.events
+            );
+        }
+
+        #[tokio::test]
+        async fn stops_when_recurrence_runs_out() {
+            let task_persist = weekly_task(false);
+            task_persist.write().unwrap().tasks[0].recurrence =
+                Some("FREQ=WEEKLY;COUNT=1".parse().unwrap());
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let complete_result = TaskService {}
+                .complete_task(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(complete_result).is_ok().is_none();
+
+            let locked_tasks = task_persist.read().expect("task persist rw lock poisoned");
+            assert_eq!(1, locked_tasks.tasks.len());
+        }
+
+        #[tokio::test]
+        async fn does_not_repeat_already_completed_task() {
+            let task_persist = weekly_task(true);
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let complete_result = TaskService {}
+                .complete_task(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(complete_result).is_ok().is_none();
+
+            let locked_tasks = task_persist.read().expect("task persist rw lock poisoned");
+            assert_eq!(1, locked_tasks.tasks.len());
+        }
+
+        #[tokio::test]
+        async fn fails_if_task_doesnt_exist() {
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let complete_result = TaskService {}
+                .complete_task(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert!(matches!(complete_result, Err(TaskError::TaskDoesNotExist)));
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_that!(locked_webhooks.events).is_empty();
+        }
+    }
+
+    mod upcoming_occurrences {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+            ]));
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Pay rent".to_owned(),
+                        due_date: NaiveDate::from_ymd_opt(2024, 1, 31),
+                        recurrence: Some("FREQ=MONTHLY;BYMONTHDAY=-1".parse().unwrap()),
+                        assignee_user_id: None,
+                    },
+                },
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "File taxes".to_owned(),
+                        due_date: NaiveDate::from_ymd_opt(2024, 4, 15),
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let recurring_result = TaskService {}
+                .upcoming_occurrences(
+                    &Caller::Trusted,
+                    1,
+                    1,
+                    3,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert_that!(recurring_result).is_ok_containing(vec![
+                NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            ]);
+
+            let one_off_result = TaskService {}
+                .upcoming_occurrences(
+                    &Caller::Trusted,
+                    1,
+                    2,
+                    3,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert_that!(one_off_result)
+                .is_ok_containing(vec![NaiveDate::from_ymd_opt(2024, 4, 15).unwrap()]);
+        }
+
+        #[tokio::test]
+        async fn fails_if_task_belongs_to_someone_else() {
+            let user_persist = domain::user::test_util::two_users();
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 2,
+                    task: NewTask {
+                        description: "Pay rent".to_owned(),
+                        due_date: NaiveDate::from_ymd_opt(2024, 1, 31),
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let preview_result = TaskService {}
+                .upcoming_occurrences(
+                    &Caller::Trusted,
+                    1,
+                    1,
+                    3,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(preview_result, Err(TaskError::TaskDoesNotExist)));
+        }
+
+        #[tokio::test]
+        async fn fails_if_user_doesnt_exist() {
+            let user_persist = InMemoryUserPersistence::new_locked();
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let preview_result = TaskService {}
+                .upcoming_occurrences(
+                    &Caller::Trusted,
+                    1,
+                    1,
+                    3,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(preview_result, Err(TaskError::UserDoesNotExist)));
+        }
+    }
+
+    mod trashed_tasks_for_user {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let user_persist = domain::user::test_util::two_users();
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Keep this".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Throw this out".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+                NewTaskWithOwner {
+                    owner: 2,
+                    task: NewTask {
+                        description: "Someone else's".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]));
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            for task_id in [2, 3] {
+                TaskService {}
+                    .delete_task(
+                        &Caller::Trusted,
+                        task_id,
+                        &mut ext_cxn,
+                        &task_persist,
+                        &task_persist,
+                        &webhooks,
+                    )
+                    .await
+                    .expect("Could not trash a task");
+            }
+
+            let trash_result = TaskService {}
+                .trashed_tasks_for_user(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert_that!(trash_result).is_ok().matches(|trash| {
+                matches!(
+                    trash.as_slice(),
+                    [TrashedTask {
+                        task: TodoTask {
+                            id: 2,
+                            owner_user_id: 1,
+                            ..
+                        },
+                        ..
+                    }]
+                )
+            });
+
+            let tasks_result = TaskService {}
+                .tasks_for_user(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert_that!(tasks_result)
+                .is_ok()
+                .matches(|tasks| matches!(tasks.as_slice(), [TodoTask { id: 1, .. }]));
+        }
+
+        #[tokio::test]
+        async fn fails_if_user_doesnt_exist() {
+            let user_persist = InMemoryUserPersistence::new_locked();
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let trash_result = TaskService {}
+                .trashed_tasks_for_user(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(trash_result, Err(TaskError::UserDoesNotExist)));
+        }
+    }
+
+    mod restore_task {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Oops".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]));
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            TaskService {}
+                .delete_task(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await
+                .expect("Could not trash a task");
+
+            let restore_result = TaskService {}
+                .restore_task(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(restore_result).is_ok().matches(|task| {
+                matches!(task, TodoTask {
+                    id: 1,
+                    owner_user_id: 1,
+                    item_desc,
+                    completed: false,
+                    due_date: None,
+                    recurrence: None,
+                    assignee_user_id: None,
+                    comment_count: 0,
+                    blocked: false,
+                } if item_desc == "Oops")
+            });
+
+            let locked_tasks = task_persist.read().expect("task persist rw lock poisoned");
+            assert_eq!(1, locked_tasks.tasks.len());
+            assert_that!(locked_tasks.trash).is_empty();
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_eq!(
+                vec![
+                    WebhookEvent::TaskDeleted { task_id: 1 },
+                    WebhookEvent::TaskCreated {
+                        task_id: 1,
+                        user_id: 1,
+                        description: "Oops".to_owned(),
+                    },
+                ],
+                locked_webhooks.events
+            );
+        }
+
+        #[tokio::test]
+        async fn fails_if_task_isnt_trashed() {
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Still here".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]));
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let restore_result = TaskService {}
+                .restore_task(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert!(matches!(restore_result, Err(TaskError::TaskDoesNotExist)));
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_that!(locked_webhooks.events).is_empty();
+        }
+    }
+
+    mod purge_trash {
+        use super::*;
+
+        #[tokio::test]
+        async fn only_purges_tasks_past_retention() {
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Old news".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Recently trashed".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]));
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            for task_id in [1, 2] {
+                TaskService {}
+                    .delete_task(
+                        &Caller::Trusted,
+                        task_id,
+                        &mut ext_cxn,
+                        &task_persist,
+                        &task_persist,
+                        &webhooks,
+                    )
+                    .await
+                    .expect("Could not trash a task");
+            }
+            {
+                let mut locked_tasks = task_persist.write().expect("task persist rw lock poisoned");
+                locked_tasks.trash[0].deleted_at -= chrono::Duration::days(2);
+            }
+
+            let purge_result = TaskService {}
+                .purge_trash(
+                    Duration::from_secs(24 * 60 * 60),
+                    &mut ext_cxn,
+                    &task_persist,
+                )
+                .await;
+            assert_that!(purge_result).is_ok_containing(1);
+
+            let locked_tasks = task_persist.read().expect("task persist rw lock poisoned");
+            assert!(matches!(
+                locked_tasks.trash.as_slice(),
+                [TrashedTask {
+                    task: TodoTask { id: 2, .. },
+                    ..
+                }]
+            ));
+        }
+    }
+
+    mod update_task {
+        use super::*;
+        use crate::domain::test_util::Connectivity;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let writer = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "abcde".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "fghij".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]));
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let update_result = TaskService {}
+                .update_task(
+                    &Caller::Trusted,
+                    2,
+                    &UpdateTask {
+                        description: "Something to do".to_owned(),
+                    },
+                    &mut ext_cxn,
+                    &writer,
+                    &writer,
+                    &webhooks,
+                )
+                .await;
+
+            assert_that!(update_result).is_ok();
+
+            let locked_writer = writer.read().expect("rw lock poisoned");
+            assert_eq!("Something to do", locked_writer.tasks[1].item_desc);
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_eq!(
+                vec![WebhookEvent::TaskUpdated {
+                    task_id: 2,
+                    description: Some("Something to do".to_owned()),
+                    completed: None,
+                }],
+                locked_webhooks.events
+            );
+        }
+
+        #[tokio::test]
+        async fn happy_path_task_doesnt_exist() {
+            let writer = InMemoryUserTaskPersistence::new_locked();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let update_result = TaskService {}
+                .update_task(
+                    &Caller::Trusted,
+                    5,
+                    &UpdateTask {
+                        description: "Something to do".to_owned(),
+                    },
+                    &mut ext_cxn,
+                    &writer,
+                    &writer,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(update_result).is_ok();
+        }
+
+        #[tokio::test]
+        async fn returns_port_err() {
+            let mut raw_writer = InMemoryUserTaskPersistence::new();
+            raw_writer.connected = Connectivity::Disconnected;
+            let writer = RwLock::new(raw_writer);
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let update_result = TaskService {}
+                .update_task(
+                    &Caller::Trusted,
+                    1,
+                    &UpdateTask {
+                        description: "Something to do".to_owned(),
+                    },
+                    &mut ext_cxn,
+                    &writer,
+                    &writer,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(update_result).is_err();
+        }
+    }
+
+    mod apply_batch_operation {
+        use super::*;
+
+        fn two_users_with_tasks() -> (
+            RwLock<InMemoryUserPersistence>,
+            RwLock<InMemoryUserTaskPersistence>,
+        ) {
+            let user_persist = domain::user::test_util::two_users();
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "abcde".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+                NewTaskWithOwner {
+                    owner: 2,
+                    task: NewTask {
+                        description: "fghij".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]));
+
+            (user_persist, task_persist)
+        }
+
+        #[tokio::test]
+        async fn creates_task() {
+            let (user_persist, task_persist) = two_users_with_tasks();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let batch_result = TaskService {}
+                .apply_batch_operation(
+                    &Caller::Trusted,
+                    1,
+                    &BatchOperation::Create(NewTask {
+                        description: "Something to do".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    }),
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+
+            assert_that!(batch_result).is_ok_containing(3);
+            let locked_tasks = task_persist.read().expect("rw lock poisoned");
+            assert_eq!("Something to do", locked_tasks.tasks[2].item_desc);
+        }
+
+        #[tokio::test]
+        async fn completes_owned_task() {
+            let (user_persist, task_persist) = two_users_with_tasks();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let batch_result = TaskService {}
+                .apply_batch_operation(
+                    &Caller::Trusted,
+                    1,
+                    &BatchOperation::Complete { task_id: 1 },
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+
+            assert_that!(batch_result).is_ok_containing(1);
+            let locked_tasks = task_persist.read().expect("rw lock poisoned");
+            assert!(locked_tasks.tasks[0].completed);
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_eq!(
+                vec![WebhookEvent::TaskUpdated {
+                    task_id: 1,
+                    description: None,
+                    completed: Some(true),
+                }],
+                locked_webhooks.events
+            );
+        }
+
+        #[tokio::test]
+        async fn does_not_touch_tasks_owned_by_other_users() {
+            let (user_persist, task_persist) = two_users_with_tasks();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let batch_result = TaskService {}
+                .apply_batch_operation(
+                    &Caller::Trusted,
+                    1,
+                    &BatchOperation::Delete { task_id: 2 },
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+
+            assert!(matches!(batch_result, Err(TaskError::TaskDoesNotExist)));
+            let locked_tasks = task_persist.read().expect("rw lock poisoned");
+            assert_eq!(2, locked_tasks.tasks.len());
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_that!(locked_webhooks.events).is_empty();
+        }
+
+        #[tokio::test]
+        async fn fails_if_user_doesnt_exist() {
+            let user_persist = InMemoryUserPersistence::new_locked();
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let batch_result = TaskService {}
+                .apply_batch_operation(
+                    &Caller::Trusted,
+                    1,
+                    &BatchOperation::Update {
+                        task_id: 1,
+                        update: UpdateTask {
+                            description: "Something to do".to_owned(),
+                        },
+                    },
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+
+            assert!(matches!(batch_result, Err(TaskError::UserDoesNotExist)));
+        }
+    }
+
+    mod assign_task {
+        use super::*;
+        use crate::domain::user::test_util::two_users;
+
+        fn unassigned_task() -> RwLock<InMemoryUserTaskPersistence> {
+            RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: NewTask {
+                        description: "Something to do".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    },
+                },
+            ]))
+        }
+
+        #[tokio::test]
+        async fn happy_path() {
+            let user_persist = two_users();
+            let task_persist = unassigned_task();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let assign_result = TaskService {}
+                .assign_task(
+                    &Caller::Trusted,
+                    1,
+                    Some(2),
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert!(matches!(
+                assign_result,
+                Ok(TodoTask {
+                    id: 1,
+                    owner_user_id: 1,
+                    assignee_user_id: Some(2),
+                    ..
+                })
+            ));
+
+            let locked_tasks = task_persist.read().expect("task rwlock poisoned");
+            assert_eq!(Some(2), locked_tasks.tasks[0].assignee_user_id);
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_eq!(
+                vec![WebhookEvent::TaskAssigned {
+                    task_id: 1,
+                    assignee_user_id: Some(2),
+                    previous_assignee_user_id: None,
+                }],
+                locked_webhooks.events
+            );
+        }
+
+        #[tokio::test]
+        async fn unassigns_task() {
+            let user_persist = two_users();
+            let task_persist = unassigned_task();
+            task_persist.write().expect("task rwlock poisoned").tasks[0].assignee_user_id = Some(2);
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let assign_result = TaskService {}
+                .assign_task(
+                    &Caller::Trusted,
+                    1,
+                    None,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(assign_result).is_ok();
+
+            let locked_tasks = task_persist.read().expect("task rwlock poisoned");
+            assert_eq!(None, locked_tasks.tasks[0].assignee_user_id);
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_eq!(
+                vec![WebhookEvent::TaskAssigned {
+                    task_id: 1,
+                    assignee_user_id: None,
+                    previous_assignee_user_id: Some(2),
+                }],
+                locked_webhooks.events
+            );
+        }
+
+        #[tokio::test]
+        async fn does_not_notify_when_assignee_is_unchanged() {
+            let user_persist = two_users();
+            let task_persist = unassigned_task();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let assign_result = TaskService {}
+                .assign_task(
+                    &Caller::Trusted,
+                    1,
+                    None,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(assign_result).is_ok();
+            assert!(webhooks
+                .read()
+                .expect("webhook rwlock poisoned")
+                .events
+                .is_empty());
+        }
+
+        #[tokio::test]
+        async fn fails_if_assignee_doesnt_exist() {
+            let user_persist = two_users();
+            let task_persist = unassigned_task();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let assign_result = TaskService {}
+                .assign_task(
+                    &Caller::Trusted,
+                    1,
+                    Some(3),
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert!(matches!(
+                assign_result,
+                Err(TaskError::AssigneeDoesNotExist)
+            ));
+            assert_eq!(
+                None,
+                task_persist.read().expect("task rwlock poisoned").tasks[0].assignee_user_id
+            );
+        }
+
+        #[tokio::test]
+        async fn fails_if_task_doesnt_exist() {
+            let user_persist = two_users();
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let assign_result = TaskService {}
+                .assign_task(
+                    &Caller::Trusted,
+                    1,
+                    Some(2),
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert!(matches!(assign_result, Err(TaskError::TaskDoesNotExist)));
+        }
+    }
+
+    mod tasks_assigned_to {
+        use super::*;
+
+        #[tokio::test]
+        async fn only_returns_tasks_assigned_to_user() {
+            let user_persist = RwLock::new(InMemoryUserPersistence::new_with_users(&[
+                domain::user::test_util::user_create_default(),
+                CreateUser {
+                    first_name: "Jane".to_owned(),
+                    last_name: "Doe".to_owned(),
+                },
+            ]));
+            let new_task = |description: &str, assignee_user_id| NewTask {
+                description: description.to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id,
+            };
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: new_task("Assigned to Jane", Some(2)),
+                },
+                NewTaskWithOwner {
+                    owner: 1,
+                    task: new_task("Unassigned", None),
+                },
+                NewTaskWithOwner {
+                    owner: 2,
+                    task: new_task("Jane's own task", None),
+                },
+            ]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let assigned_result = TaskService {}
+                .tasks_assigned_to(
+                    &Caller::Trusted,
+                    2,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(assigned_result.as_deref(), Ok([
+                TodoTask {
+                    id: 1,
+                    owner_user_id: 1,
+                    item_desc,
+                    ..
+                }
+            ]) if item_desc == "Assigned to Jane"));
+        }
+
+        #[tokio::test]
+        async fn fails_if_user_doesnt_exist() {
+            let user_persist = InMemoryUserPersistence::new_locked();
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let assigned_result = TaskService {}
+                .tasks_assigned_to(
+                    &Caller::Trusted,
+                    2,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(assigned_result, Err(TaskError::UserDoesNotExist)));
+        }
+    }
+
+    mod task_dependencies {
+        use super::*;
+
+        fn dependency(task_id: i32, blocked_by_task_id: i32) -> TaskDependency {
+            TaskDependency {
+                task_id,
+                blocked_by_task_id,
+            }
+        }
+
+        /// Sets up two users, the first with three tasks and the second with one
+        fn prepare() -> (
+            RwLock<InMemoryUserPersistence>,
+            RwLock<InMemoryUserTaskPersistence>,
+        ) {
+            let user_persist = domain::user::test_util::two_users();
+            let new_task = |owner: i32, description: &str| NewTaskWithOwner {
+                owner,
+                task: NewTask {
+                    description: description.to_owned(),
+                    due_date: None,
+                    recurrence: None,
+                    assignee_user_id: None,
+                },
+            };
+            let task_persist = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+                new_task(1, "Paint the fence"),
+                new_task(1, "Buy paint"),
+                new_task(1, "Sand the fence"),
+                new_task(2, "Someone else's task"),
+            ]));
+
+            (user_persist, task_persist)
+        }
+
+        #[tokio::test]
+        async fn blocked_task_cant_be_completed_until_blocker_is() {
+            let (user_persist, task_persist) = prepare();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let add_result = TaskService {}
+                .add_dependency(
+                    &Caller::Trusted,
+                    1,
+                    &dependency(1, 2),
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert_that!(add_result).is_ok();
+            assert!(task_persist.read().unwrap().tasks[0].blocked);
+            assert_eq!(vec![1], task_persist.read().unwrap().dependency_locks);
+
+            let blocked_result = TaskService {}
+                .complete_task(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert!(matches!(blocked_result, Err(TaskError::TaskBlocked)));
+            assert_that!(webhooks.read().unwrap().events).is_empty();
+
+            for task_id in [2, 1] {
+                let complete_result = TaskService {}
+                    .complete_task(
+                        &Caller::Trusted,
+                        task_id,
+                        &mut ext_cxn,
+                        &task_persist,
+                        &task_persist,
+                        &webhooks,
+                    )
+                    .await;
+                assert_that!(complete_result).is_ok();
+            }
+            let locked_tasks = task_persist.read().unwrap();
+            assert!(locked_tasks.tasks[0].completed);
+            assert!(!locked_tasks.tasks[0].blocked);
+        }
+
+        #[tokio::test]
+        async fn rejects_cycles() {
+            let (user_persist, task_persist) = prepare();
+            task_persist.write().unwrap().dependencies = vec![dependency(1, 2), dependency(2, 3)];
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            for new_dependency in [dependency(3, 1), dependency(3, 3)] {
+                let add_result = TaskService {}
+                    .add_dependency(
+                        &Caller::Trusted,
+                        1,
+                        &new_dependency,
+                        &mut ext_cxn,
+                        &user_persist,
+                        &task_persist,
+                        &task_persist,
+                    )
+                    .await;
+                assert!(matches!(add_result, Err(TaskError::DependencyCycle)));
+            }
+            assert_eq!(2, task_persist.read().unwrap().dependencies.len());
+        }
+
+        #[tokio::test]
+        async fn rejects_blockers_belonging_to_other_users() {
+            let (user_persist, task_persist) = prepare();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let add_result = TaskService {}
+                .add_dependency(
+                    &Caller::Trusted,
+                    1,
+                    &dependency(1, 4),
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(add_result, Err(TaskError::TaskDoesNotExist)));
+            assert_that!(task_persist.read().unwrap().dependencies).is_empty();
+        }
+
+        #[tokio::test]
+        async fn removing_dependency_unblocks_task() {
+            let (user_persist, task_persist) = prepare();
+            {
+                let mut locked_tasks = task_persist.write().unwrap();
+                locked_tasks.dependencies = vec![dependency(1, 2)];
+                locked_tasks.refresh_blocked();
+            }
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
-            let delete_result = TaskService {}.delete_task(5, &mut ext_cxn, &writer).await;
-            assert_that!(delete_result).is_ok();
+            let remove_result = TaskService {}
+                .remove_dependency(
+                    &Caller::Trusted,
+                    1,
+                    &dependency(1, 2),
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert_that!(remove_result).is_ok();
+            assert!(!task_persist.read().unwrap().tasks[0].blocked);
+
+            let remove_again_result = TaskService {}
+                .remove_dependency(
+                    &Caller::Trusted,
+                    1,
+                    &dependency(1, 2),
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert!(matches!(
+                remove_again_result,
+                Err(TaskError::DependencyDoesNotExist)
+            ));
         }
 
         #[tokio::test]
-        async fn returns_port_err() {
-            let writer = InMemoryUserTaskPersistence::new_locked();
+        async fn orders_tasks_after_their_blockers() {
+            let (user_persist, task_persist) = prepare();
+            task_persist.write().unwrap().dependencies = vec![dependency(1, 3), dependency(3, 2)];
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
-            {
-                let mut locked_writer = writer.write().expect("writer rw lock poisoned");
-                locked_writer.connected = Connectivity::Disconnected;
-            }
 
-            let delete_result = TaskService {}.delete_task(1, &mut ext_cxn, &writer).await;
-            assert_that!(delete_result).is_err();
+            let ordered_result = TaskService {}
+                .tasks_in_dependency_order(
+                    &Caller::Trusted,
+                    1,
+                    &mut ext_cxn,
+                    &user_persist,
+                    &task_persist,
+                    &task_persist,
+                )
+                .await;
+            assert_that!(ordered_result)
+                .is_ok()
+                .matches(|tasks| tasks.iter().map(|task| task.id).eq([2, 3, 1]));
         }
     }
 
-    mod update_task {
+    mod access_policy {
         use super::*;
-        use crate::domain::test_util::Connectivity;
+        use crate::domain::access::AccessDenied;
+        use crate::domain::user::test_util::two_users;
+        use crate::domain::user::Role;
 
-        #[tokio::test]
-        async fn happy_path() {
-            let writer = RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
+        const FIRST_USER: Caller = Caller::User {
+            user_id: 1,
+            role: Role::User,
+        };
+
+        /// Two users who own one task each
+        fn tasks_for_two_users() -> RwLock<InMemoryUserTaskPersistence> {
+            RwLock::new(InMemoryUserTaskPersistence::new_with_tasks(&[
                 NewTaskWithOwner {
                     owner: 1,
                     task: NewTask {
-                        description: "abcde".to_owned(),
+                        description: "Something to do".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
                 NewTaskWithOwner {
-                    owner: 1,
+                    owner: 2,
                     task: NewTask {
-                        description: "fghij".to_owned(),
+                        description: "Someone else's thing to do".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                 },
-            ]));
+            ]))
+        }
+
+        #[tokio::test]
+        async fn users_may_only_read_their_own_tasks() {
+            let user_persist = two_users();
+            let task_persist = tasks_for_two_users();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let own_tasks = TaskService {}
+                .tasks_for_user(&FIRST_USER, 1, &mut ext_cxn, &user_persist, &task_persist)
+                .await;
+            assert_that!(own_tasks).is_ok().has_length(1);
+
+            let other_tasks = TaskService {}
+                .tasks_for_user(&FIRST_USER, 2, &mut ext_cxn, &user_persist, &task_persist)
+                .await;
+            let Err(TaskError::Forbidden(AccessDenied {
+                action: Action::ManageTasks { owner_user_id: 2 },
+            })) = other_tasks
+            else {
+                panic!("Expected access to be denied, got {:#?}", other_tasks);
+            };
+        }
+
+        #[tokio::test]
+        async fn users_may_not_change_the_tasks_of_others() {
+            let task_persist = tasks_for_two_users();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
+            let delete_result = TaskService {}
+                .delete_task(
+                    &FIRST_USER,
+                    2,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert!(matches!(delete_result, Err(TaskError::Forbidden(_))));
+
             let update_result = TaskService {}
                 .update_task(
+                    &FIRST_USER,
                     2,
                     &UpdateTask {
-                        description: "Something to do".to_owned(),
+                        description: "Mine now".to_owned(),
                     },
                     &mut ext_cxn,
-                    &writer,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
                 )
                 .await;
+            assert!(matches!(update_result, Err(TaskError::Forbidden(_))));
 
-            assert_that!(update_result).is_ok();
+            let complete_result = TaskService {}
+                .complete_task(
+                    &FIRST_USER,
+                    2,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert!(matches!(complete_result, Err(TaskError::Forbidden(_))));
 
-            let locked_writer = writer.read().expect("rw lock poisoned");
-            assert_eq!("Something to do", locked_writer.tasks[1].item_desc);
+            let locked_tasks = task_persist.read().expect("task rwlock poisoned");
+            assert_eq!(2, locked_tasks.tasks.len());
+            assert_eq!(
+                "Someone else's thing to do",
+                locked_tasks.tasks[1].item_desc
+            );
+            assert!(!locked_tasks.tasks[1].completed);
+            assert!(webhooks
+                .read()
+                .expect("webhook rwlock poisoned")
+                .events
+                .is_empty());
         }
 
         #[tokio::test]
-        async fn happy_path_task_doesnt_exist() {
-            let writer = InMemoryUserTaskPersistence::new_locked();
+        async fn users_may_not_restore_the_trashed_tasks_of_others() {
+            let task_persist = tasks_for_two_users();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            TaskService {}
+                .delete_task(
+                    &Caller::Trusted,
+                    2,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await
+                .expect("Could not delete a task");
 
-            let update_result = TaskService {}
-                .update_task(
-                    5,
-                    &UpdateTask {
-                        description: "Something to do".to_owned(),
-                    },
+            let restore_result = TaskService {}
+                .restore_task(
+                    &FIRST_USER,
+                    2,
                     &mut ext_cxn,
-                    &writer,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
                 )
                 .await;
-            assert_that!(update_result).is_ok();
+            assert!(matches!(restore_result, Err(TaskError::Forbidden(_))));
+            assert_eq!(
+                1,
+                task_persist
+                    .read()
+                    .expect("task rwlock poisoned")
+                    .trash
+                    .len()
+            );
         }
 
         #[tokio::test]
-        async fn returns_port_err() {
-            let mut raw_writer = InMemoryUserTaskPersistence::new();
-            raw_writer.connected = Connectivity::Disconnected;
-            let writer = RwLock::new(raw_writer);
+        async fn assignees_may_change_tasks_assigned_to_them() {
+            let task_persist = tasks_for_two_users();
+            task_persist.write().expect("task rwlock poisoned").tasks[1].assignee_user_id = Some(1);
+            let webhooks = InMemoryWebhookPersistence::new_locked();
             let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
 
-            let update_result = TaskService {}
-                .update_task(
+            let complete_result = TaskService {}
+                .complete_task(
+                    &FIRST_USER,
+                    2,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(complete_result).is_ok();
+            assert!(task_persist.read().expect("task rwlock poisoned").tasks[1].completed);
+        }
+
+        #[tokio::test]
+        async fn admins_may_change_anyones_tasks() {
+            let task_persist = tasks_for_two_users();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+            let admin = Caller::User {
+                user_id: 1,
+                role: Role::Admin,
+            };
+
+            let delete_result = TaskService {}
+                .delete_task(
+                    &admin,
+                    2,
+                    &mut ext_cxn,
+                    &task_persist,
+                    &task_persist,
+                    &webhooks,
+                )
+                .await;
+            assert_that!(delete_result).is_ok();
+            assert_eq!(
+                1,
+                task_persist
+                    .read()
+                    .expect("task rwlock poisoned")
+                    .trash
+                    .len()
+            );
+        }
+
+        #[tokio::test]
+        async fn anonymous_callers_may_not_create_tasks() {
+            let user_persist = two_users();
+            let task_persist = InMemoryUserTaskPersistence::new_locked();
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let create_result = TaskService {}
+                .create_task_for_user(
+                    &Caller::Anonymous,
                     1,
-                    &UpdateTask {
+                    &NewTask {
                         description: "Something to do".to_owned(),
+                        due_date: None,
+                        recurrence: None,
+                        assignee_user_id: None,
                     },
                     &mut ext_cxn,
-                    &writer,
+                    &user_persist,
+                    &task_persist,
+                    &webhooks,
                 )
                 .await;
-            assert_that!(update_result).is_err();
+            assert!(matches!(create_result, Err(TaskError::Forbidden(_))));
+            assert!(task_persist
+                .read()
+                .expect("task rwlock poisoned")
+                .tasks
+                .is_empty());
         }
     }
 }
@@ -599,6 +3724,7 @@ mod tests {
 #[cfg(test)]
 pub mod test_util {
     use super::*;
+    use crate::domain::share::TaskShare;
     use crate::domain::test_util::{Connectivity, FakeImplementation};
     use crate::domain::user::driven_ports::DetectUser;
     use std::sync::{Mutex, RwLock};
@@ -607,6 +3733,12 @@ pub mod test_util {
     /// the traits for all task driven ports
     pub struct InMemoryUserTaskPersistence {
         pub tasks: Vec<TodoTask>,
+        pub trash: Vec<TrashedTask>,
+        /// Tasks shared with other users, which are managed through the share driven ports
+        pub shares: Vec<TaskShare>,
+        pub dependencies: Vec<TaskDependency>,
+        /// Users whose dependencies were locked, in the order they were locked
+        pub dependency_locks: Vec<i32>,
         pub connected: Connectivity,
         highest_task_id: i32,
     }
@@ -622,6 +3754,10 @@ pub mod test_util {
         pub fn new() -> InMemoryUserTaskPersistence {
             InMemoryUserTaskPersistence {
                 tasks: Vec::new(),
+                trash: Vec::new(),
+                shares: Vec::new(),
+                dependencies: Vec::new(),
+                dependency_locks: Vec::new(),
                 connected: Connectivity::Connected,
                 highest_task_id: 0,
             }
@@ -633,12 +3769,18 @@ pub mod test_util {
                 tasks: tasks
                     .iter()
                     .enumerate()
-                    .map(|(index, task_with_owner)| TodoTask {
-                        id: index as i32 + 1,
-                        owner_user_id: task_with_owner.owner,
-                        item_desc: task_with_owner.task.description.clone(),
+                    .map(|(index, task_with_owner)| {
+                        task_from_create(
+                            task_with_owner.owner,
+                            index as i32 + 1,
+                            &task_with_owner.task,
+                        )
                     })
                     .collect(),
+                trash: Vec::new(),
+                shares: Vec::new(),
+                dependencies: Vec::new(),
+                dependency_locks: Vec::new(),
                 connected: Connectivity::Connected,
                 highest_task_id: tasks.len() as i32,
             }
@@ -649,6 +3791,28 @@ pub mod test_util {
         pub fn new_locked() -> RwLock<InMemoryUserTaskPersistence> {
             RwLock::new(Self::new())
         }
+
+        /// Brings the blocked flag on each task up to date with its dependencies, the same way the database
+        /// works it out whenever tasks are read
+        pub fn refresh_blocked(&mut self) {
+            let open_task_ids: Vec<i32> = self
+                .tasks
+                .iter()
+                .filter(|task| !task.completed)
+                .map(|task| task.id)
+                .collect();
+            let dependencies = &self.dependencies;
+            let all_tasks = self
+                .tasks
+                .iter_mut()
+                .chain(self.trash.iter_mut().map(|trashed| &mut trashed.task));
+            for task in all_tasks {
+                task.blocked = dependencies.iter().any(|dependency| {
+                    dependency.task_id == task.id
+                        && open_task_ids.contains(&dependency.blocked_by_task_id)
+                });
+            }
+        }
     }
 
     impl driven_ports::TaskReader for RwLock<InMemoryUserTaskPersistence> {
@@ -672,25 +3836,276 @@ pub mod test_util {
                 })
                 .collect();
 
-            Ok(matching_tasks)
+            Ok(matching_tasks)
+        }
+
+        async fn task_page_for_user(
+            &self,
+            user_id: i32,
+            after_task_id: Option<i32>,
+            limit: i64,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TodoTask>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let mut page: Vec<TodoTask> = persistence
+                .tasks
+                .iter()
+                .filter(|task| {
+                    task.owner_user_id == user_id
+                        && after_task_id.is_none_or(|after_id| task.id > after_id)
+                })
+                .cloned()
+                .collect();
+            page.sort_by_key(|task| task.id);
+            page.truncate(limit as usize);
+
+            Ok(page)
+        }
+
+        async fn user_task_by_id(
+            &self,
+            user_id: i32,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TodoTask>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let task = persistence
+                .tasks
+                .iter()
+                .find(|task| {
+                    task.id == task_id
+                        && (task.owner_user_id == user_id
+                            || task.assignee_user_id == Some(user_id)
+                            || persistence.shares.iter().any(|share| {
+                                share.shared_with_user_id == user_id && share_covers(share, task)
+                            }))
+                })
+                .cloned();
+
+            Ok(task)
+        }
+
+        async fn task_by_id(
+            &self,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TodoTask>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let task = persistence
+                .tasks
+                .iter()
+                .find(|task| task.id == task_id)
+                .cloned();
+
+            Ok(task)
+        }
+
+        async fn task_owner(
+            &self,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<i32>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let owner = persistence
+                .tasks
+                .iter()
+                .find(|task| task.id == task_id)
+                .map(|task| task.owner_user_id);
+
+            Ok(owner)
+        }
+
+        async fn trashed_task_owner(
+            &self,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<i32>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let owner = persistence
+                .trash
+                .iter()
+                .find(|trashed| trashed.task.id == task_id)
+                .map(|trashed| trashed.task.owner_user_id);
+
+            Ok(owner)
+        }
+
+        async fn task_assignee(
+            &self,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<i32>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let assignee = persistence
+                .tasks
+                .iter()
+                .chain(persistence.trash.iter().map(|trashed| &trashed.task))
+                .find(|task| task.id == task_id)
+                .and_then(|task| task.assignee_user_id);
+
+            Ok(assignee)
+        }
+
+        async fn tasks_assigned_to(
+            &self,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TodoTask>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let mut assigned_tasks: Vec<TodoTask> = persistence
+                .tasks
+                .iter()
+                .filter(|task| task.assignee_user_id == Some(user_id))
+                .cloned()
+                .collect();
+            assigned_tasks.sort_by_key(|task| task.id);
+
+            Ok(assigned_tasks)
+        }
+
+        async fn shared_permission(
+            &self,
+            user_id: i32,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<SharePermission>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let Some(task) = persistence.tasks.iter().find(|task| task.id == task_id) else {
+                return Ok(None);
+            };
+            let permission = persistence
+                .shares
+                .iter()
+                .filter(|share| share.shared_with_user_id == user_id && share_covers(share, task))
+                .map(|share| share.permission)
+                .max();
+
+            Ok(permission)
+        }
+
+        async fn trashed_shared_permission(
+            &self,
+            user_id: i32,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<SharePermission>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let Some(trashed) = persistence
+                .trash
+                .iter()
+                .find(|trashed| trashed.task.id == task_id)
+            else {
+                return Ok(None);
+            };
+            let permission = persistence
+                .shares
+                .iter()
+                .filter(|share| {
+                    share.shared_with_user_id == user_id && share_covers(share, &trashed.task)
+                })
+                .map(|share| share.permission)
+                .max();
+
+            Ok(permission)
+        }
+
+        async fn task_summary_for_user(
+            &self,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<TaskSummary, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let user_tasks = persistence
+                .tasks
+                .iter()
+                .filter(|task| task.owner_user_id == user_id);
+            let summary = user_tasks.fold(TaskSummary::default(), |mut summary, task| {
+                summary.total += 1;
+                if task.completed {
+                    summary.completed += 1;
+                }
+                summary
+            });
+
+            // In-memory tasks have no due dates, so none of them are overdue
+            Ok(summary)
+        }
+
+        async fn search_tasks(
+            &self,
+            user_id: i32,
+            search_text: &str,
+            limit: i64,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskSearchMatch>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            // A plain case-insensitive substring match stands in for full-text search
+            let search_text = search_text.to_lowercase();
+            let matches = persistence
+                .tasks
+                .iter()
+                .filter(|task| task.owner_user_id == user_id)
+                .filter_map(|task| {
+                    let match_start = task.item_desc.to_lowercase().find(&search_text)?;
+                    let match_end = match_start + search_text.len();
+                    let snippet = format!(
+                        "{}{SEARCH_HIGHLIGHT_START}{}{SEARCH_HIGHLIGHT_END}{}",
+                        &task.item_desc[..match_start],
+                        &task.item_desc[match_start..match_end],
+                        &task.item_desc[match_end..],
+                    );
+
+                    Some(TaskSearchMatch {
+                        task: task.clone(),
+                        rank: 1.0,
+                        snippet,
+                    })
+                })
+                .take(limit as usize)
+                .collect();
+
+            Ok(matches)
         }
 
-        async fn user_task_by_id(
+        async fn trashed_tasks_for_user(
             &self,
             user_id: i32,
-            task_id: i32,
             _ext_cxn: &mut impl ExternalConnectivity,
-        ) -> Result<Option<TodoTask>, Error> {
+        ) -> Result<Vec<TrashedTask>, Error> {
             let persistence = self.read().expect("task persist rw lock poisoned");
             persistence.connected.blow_up_if_disconnected()?;
 
-            let task = persistence
-                .tasks
+            let trashed_tasks = persistence
+                .trash
                 .iter()
-                .find(|task| task.owner_user_id == user_id && task.id == task_id)
-                .map(Clone::clone);
+                .rev()
+                .filter(|trashed| trashed.task.owner_user_id == user_id)
+                .cloned()
+                .collect();
 
-            Ok(task)
+            Ok(trashed_tasks)
         }
     }
 
@@ -727,12 +4142,56 @@ pub mod test_util {
                 .find(|(_, task)| task.id == task_id)
                 .map(|(idx, _)| idx);
             if let Some(idx) = item_index {
-                persistence.tasks.remove(idx);
+                let task = persistence.tasks.remove(idx);
+                persistence.trash.push(TrashedTask {
+                    task,
+                    deleted_at: Utc::now(),
+                });
+                persistence.refresh_blocked();
             }
 
             Ok(())
         }
 
+        async fn restore_task(
+            &self,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TodoTask>, Error> {
+            let mut persistence = self.write().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let Some(idx) = persistence
+                .trash
+                .iter()
+                .position(|trashed| trashed.task.id == task_id)
+            else {
+                return Ok(None);
+            };
+            let restored = persistence.trash.remove(idx).task;
+            persistence.tasks.push(restored.clone());
+            persistence.refresh_blocked();
+
+            Ok(Some(restored))
+        }
+
+        async fn purge_trashed_tasks(
+            &self,
+            retention: Duration,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<u64, Error> {
+            let mut persistence = self.write().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let cutoff = Utc::now() - retention;
+            let trash_size = persistence.trash.len();
+            persistence
+                .trash
+                .retain(|trashed| trashed.deleted_at >= cutoff);
+
+            Ok((trash_size - persistence.trash.len()) as u64)
+        }
+
         async fn update_task(
             &self,
             task_id: i32,
@@ -754,6 +4213,118 @@ pub mod test_util {
 
             Ok(())
         }
+
+        async fn complete_task(
+            &self,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), Error> {
+            let mut persistence = self.write().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            if let Some(task) = persistence.tasks.iter_mut().find(|task| task.id == task_id) {
+                task.completed = true;
+            }
+            persistence.refresh_blocked();
+
+            Ok(())
+        }
+
+        async fn assign_task(
+            &self,
+            task_id: i32,
+            assignee_user_id: Option<i32>,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), Error> {
+            let mut persistence = self.write().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            if let Some(task) = persistence.tasks.iter_mut().find(|task| task.id == task_id) {
+                task.assignee_user_id = assignee_user_id;
+            }
+
+            Ok(())
+        }
+    }
+
+    impl driven_ports::DependencyStore for RwLock<InMemoryUserTaskPersistence> {
+        async fn dependencies_for_user(
+            &self,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TaskDependency>, Error> {
+            let persistence = self.read().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let user_task_ids: Vec<i32> = persistence
+                .tasks
+                .iter()
+                .chain(persistence.trash.iter().map(|trashed| &trashed.task))
+                .filter(|task| task.owner_user_id == user_id)
+                .map(|task| task.id)
+                .collect();
+            let dependencies = persistence
+                .dependencies
+                .iter()
+                .filter(|dependency| user_task_ids.contains(&dependency.task_id))
+                .copied()
+                .collect();
+
+            Ok(dependencies)
+        }
+
+        async fn lock_dependencies_for_user(
+            &self,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), Error> {
+            let mut persistence = self.write().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+            persistence.dependency_locks.push(user_id);
+
+            Ok(())
+        }
+
+        async fn add_dependency(
+            &self,
+            dependency: &TaskDependency,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), Error> {
+            let mut persistence = self.write().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            if !persistence.dependencies.contains(dependency) {
+                persistence.dependencies.push(*dependency);
+                persistence.refresh_blocked();
+            }
+
+            Ok(())
+        }
+
+        async fn remove_dependency(
+            &self,
+            dependency: &TaskDependency,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, Error> {
+            let mut persistence = self.write().expect("task persist rw lock poisoned");
+            persistence.connected.blow_up_if_disconnected()?;
+
+            let dependency_count = persistence.dependencies.len();
+            persistence
+                .dependencies
+                .retain(|existing| existing != dependency);
+            persistence.refresh_blocked();
+
+            Ok(persistence.dependencies.len() < dependency_count)
+        }
+    }
+
+    /// Whether a share grants access to a task, either on its own or along with the rest of its owner's tasks
+    pub fn share_covers(share: &TaskShare, task: &TodoTask) -> bool {
+        share.owner_user_id == task.owner_user_id
+            && share
+                .task_id
+                .is_none_or(|shared_task_id| shared_task_id == task.id)
     }
 
     /// Creates a new [TodoTask] from a create payload plus some supplemental information
@@ -762,6 +4333,12 @@ pub mod test_util {
             id: task_id,
             owner_user_id: user_id,
             item_desc: new_task.description.clone(),
+            completed: false,
+            due_date: new_task.due_date,
+            recurrence: new_task.recurrence.clone(),
+            assignee_user_id: new_task.assignee_user_id,
+            comment_count: 0,
+            blocked: false,
         }
     }
 
@@ -770,9 +4347,30 @@ pub mod test_util {
         pub tasks_for_user_result: FakeImplementation<i32, Result<Vec<TodoTask>, TaskError>>,
         pub user_task_by_id_result:
             FakeImplementation<(i32, i32), Result<Option<TodoTask>, TaskError>>,
+        pub search_tasks_result:
+            FakeImplementation<(i32, String), Result<Vec<TaskSearchMatch>, TaskError>>,
+        pub export_page_result:
+            FakeImplementation<(i32, Option<i32>), Result<Vec<TodoTask>, TaskError>>,
+        pub import_tasks_result:
+            FakeImplementation<(i32, Vec<ImportedTask>), Result<Vec<i32>, TaskError>>,
         pub create_task_for_user_result: FakeImplementation<(i32, NewTask), Result<i32, TaskError>>,
-        pub delete_task_result: FakeImplementation<i32, Result<(), anyhow::Error>>,
-        pub update_task_result: FakeImplementation<(i32, UpdateTask), Result<(), anyhow::Error>>,
+        pub delete_task_result: FakeImplementation<i32, Result<(), TaskError>>,
+        pub complete_task_result: FakeImplementation<i32, Result<Option<TodoTask>, TaskError>>,
+        pub upcoming_occurrences_result:
+            FakeImplementation<(i32, i32, usize), Result<Vec<NaiveDate>, TaskError>>,
+        pub trashed_tasks_for_user_result:
+            FakeImplementation<i32, Result<Vec<TrashedTask>, TaskError>>,
+        pub restore_task_result: FakeImplementation<i32, Result<TodoTask, TaskError>>,
+        pub update_task_result: FakeImplementation<(i32, UpdateTask), Result<(), TaskError>>,
+        pub assign_task_result: FakeImplementation<(i32, Option<i32>), Result<TodoTask, TaskError>>,
+        pub tasks_assigned_to_result: FakeImplementation<i32, Result<Vec<TodoTask>, TaskError>>,
+        pub apply_batch_operation_result:
+            FakeImplementation<(i32, BatchOperation), Result<i32, TaskError>>,
+        pub add_dependency_result: FakeImplementation<(i32, TaskDependency), Result<(), TaskError>>,
+        pub remove_dependency_result:
+            FakeImplementation<(i32, TaskDependency), Result<(), TaskError>>,
+        pub tasks_in_dependency_order_result:
+            FakeImplementation<i32, Result<Vec<TodoTask>, TaskError>>,
     }
 
     impl MockTaskService {
@@ -781,9 +4379,22 @@ pub mod test_util {
             MockTaskService {
                 tasks_for_user_result: FakeImplementation::new(),
                 user_task_by_id_result: FakeImplementation::new(),
+                search_tasks_result: FakeImplementation::new(),
+                export_page_result: FakeImplementation::new(),
+                import_tasks_result: FakeImplementation::new(),
                 create_task_for_user_result: FakeImplementation::new(),
                 delete_task_result: FakeImplementation::new(),
+                complete_task_result: FakeImplementation::new(),
+                upcoming_occurrences_result: FakeImplementation::new(),
+                trashed_tasks_for_user_result: FakeImplementation::new(),
+                restore_task_result: FakeImplementation::new(),
                 update_task_result: FakeImplementation::new(),
+                assign_task_result: FakeImplementation::new(),
+                tasks_assigned_to_result: FakeImplementation::new(),
+                apply_batch_operation_result: FakeImplementation::new(),
+                add_dependency_result: FakeImplementation::new(),
+                remove_dependency_result: FakeImplementation::new(),
+                tasks_in_dependency_order_result: FakeImplementation::new(),
             }
         }
 
@@ -805,6 +4416,7 @@ pub mod test_util {
     impl driving_ports::TaskPort for Mutex<MockTaskService> {
         async fn tasks_for_user(
             &self,
+            _caller: &Caller,
             user_id: i32,
             _ext_cxn: &mut impl ExternalConnectivity,
             _u_detect: &impl DetectUser,
@@ -818,6 +4430,7 @@ pub mod test_util {
 
         async fn user_task_by_id(
             &self,
+            _caller: &Caller,
             user_id: i32,
             task_id: i32,
             _ext_cxn: &mut impl ExternalConnectivity,
@@ -832,13 +4445,67 @@ pub mod test_util {
             locked_self.user_task_by_id_result.return_value_result()
         }
 
+        async fn search_tasks(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            search_text: &str,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_read: &impl TaskReader,
+        ) -> Result<Vec<TaskSearchMatch>, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self
+                .search_tasks_result
+                .save_arguments((user_id, search_text.to_owned()));
+
+            locked_self.search_tasks_result.return_value_result()
+        }
+
+        async fn export_page(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            after_task_id: Option<i32>,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_read: &impl TaskReader,
+        ) -> Result<Vec<TodoTask>, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self
+                .export_page_result
+                .save_arguments((user_id, after_task_id));
+
+            locked_self.export_page_result.return_value_result()
+        }
+
+        async fn import_tasks(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            tasks: &[ImportedTask],
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_write: &impl TaskWriter,
+            _event_outbox: &impl EventOutbox,
+        ) -> Result<Vec<i32>, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self
+                .import_tasks_result
+                .save_arguments((user_id, tasks.to_vec()));
+
+            locked_self.import_tasks_result.return_value_result()
+        }
+
         async fn create_task_for_user(
             &self,
+            _caller: &Caller,
             user_id: i32,
             task: &NewTask,
             _ext_cxn: &mut impl ExternalConnectivity,
             _u_detect: &impl DetectUser,
             _task_write: &impl TaskWriter,
+            _event_outbox: &impl EventOutbox,
         ) -> Result<i32, TaskError> {
             let mut locked_self = self.lock().expect("mock task service mutex poisoned");
             locked_self
@@ -852,29 +4519,221 @@ pub mod test_util {
 
         async fn delete_task(
             &self,
+            _caller: &Caller,
             task_id: i32,
             _ext_cxn: &mut impl ExternalConnectivity,
+            _task_read: &impl TaskReader,
             _task_write: &impl TaskWriter,
-        ) -> Result<(), anyhow::Error> {
+            _event_outbox: &impl EventOutbox,
+        ) -> Result<(), TaskError> {
             let mut locked_self = self.lock().expect("mock task service mutex poisoned");
             locked_self.delete_task_result.save_arguments(task_id);
 
-            locked_self.delete_task_result.return_value_anyhow()
+            locked_self.delete_task_result.return_value_result()
+        }
+
+        async fn complete_task(
+            &self,
+            _caller: &Caller,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _task_read: &impl TaskReader,
+            _task_write: &impl TaskWriter,
+            _event_outbox: &impl EventOutbox,
+        ) -> Result<Option<TodoTask>, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self.complete_task_result.save_arguments(task_id);
+
+            locked_self.complete_task_result.return_value_result()
+        }
+
+        async fn upcoming_occurrences(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            task_id: i32,
+            limit: usize,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_read: &impl TaskReader,
+        ) -> Result<Vec<NaiveDate>, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self
+                .upcoming_occurrences_result
+                .save_arguments((user_id, task_id, limit));
+
+            locked_self
+                .upcoming_occurrences_result
+                .return_value_result()
+        }
+
+        async fn trashed_tasks_for_user(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_read: &impl TaskReader,
+        ) -> Result<Vec<TrashedTask>, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self
+                .trashed_tasks_for_user_result
+                .save_arguments(user_id);
+
+            locked_self
+                .trashed_tasks_for_user_result
+                .return_value_result()
+        }
+
+        async fn restore_task(
+            &self,
+            _caller: &Caller,
+            task_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _task_read: &impl TaskReader,
+            _task_write: &impl TaskWriter,
+            _event_outbox: &impl EventOutbox,
+        ) -> Result<TodoTask, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self.restore_task_result.save_arguments(task_id);
+
+            locked_self.restore_task_result.return_value_result()
+        }
+
+        async fn purge_trash(
+            &self,
+            _retention: Duration,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _task_write: &impl TaskWriter,
+        ) -> Result<u64, anyhow::Error> {
+            Ok(0)
         }
 
         async fn update_task(
             &self,
+            _caller: &Caller,
             task_id: i32,
             update: &UpdateTask,
             _ext_cxn: &mut impl ExternalConnectivity,
+            _task_read: &impl TaskReader,
             _task_write: &impl TaskWriter,
-        ) -> Result<(), anyhow::Error> {
+            _event_outbox: &impl EventOutbox,
+        ) -> Result<(), TaskError> {
             let mut locked_self = self.lock().expect("mock task service mutex poisoned");
             locked_self
                 .update_task_result
                 .save_arguments((task_id, update.clone()));
 
-            locked_self.update_task_result.return_value_anyhow()
+            locked_self.update_task_result.return_value_result()
+        }
+
+        async fn assign_task(
+            &self,
+            _caller: &Caller,
+            task_id: i32,
+            assignee_user_id: Option<i32>,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_read: &impl TaskReader,
+            _task_write: &impl TaskWriter,
+            _event_outbox: &impl EventOutbox,
+        ) -> Result<TodoTask, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self
+                .assign_task_result
+                .save_arguments((task_id, assignee_user_id));
+
+            locked_self.assign_task_result.return_value_result()
+        }
+
+        async fn tasks_assigned_to(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_read: &impl TaskReader,
+        ) -> Result<Vec<TodoTask>, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self.tasks_assigned_to_result.save_arguments(user_id);
+
+            locked_self.tasks_assigned_to_result.return_value_result()
+        }
+
+        async fn apply_batch_operation(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            operation: &BatchOperation,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_read: &impl TaskReader,
+            _task_write: &impl TaskWriter,
+            _event_outbox: &impl EventOutbox,
+        ) -> Result<i32, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self
+                .apply_batch_operation_result
+                .save_arguments((user_id, operation.clone()));
+
+            locked_self
+                .apply_batch_operation_result
+                .return_value_result()
+        }
+
+        async fn add_dependency(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            dependency: &TaskDependency,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_read: &impl TaskReader,
+            _dependency_store: &impl DependencyStore,
+        ) -> Result<(), TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self
+                .add_dependency_result
+                .save_arguments((user_id, *dependency));
+
+            locked_self.add_dependency_result.return_value_result()
+        }
+
+        async fn remove_dependency(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            dependency: &TaskDependency,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_read: &impl TaskReader,
+            _dependency_store: &impl DependencyStore,
+        ) -> Result<(), TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self
+                .remove_dependency_result
+                .save_arguments((user_id, *dependency));
+
+            locked_self.remove_dependency_result.return_value_result()
+        }
+
+        async fn tasks_in_dependency_order(
+            &self,
+            _caller: &Caller,
+            user_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _u_detect: &impl DetectUser,
+            _task_read: &impl TaskReader,
+            _dependency_store: &impl DependencyStore,
+        ) -> Result<Vec<TodoTask>, TaskError> {
+            let mut locked_self = self.lock().expect("mock task service mutex poisoned");
+            locked_self
+                .tasks_in_dependency_order_result
+                .save_arguments(user_id);
+
+            locked_self
+                .tasks_in_dependency_order_result
+                .return_value_result()
         }
     }
 }
@@ -0,0 +1,184 @@
+use crate::domain::todo::TodoTask;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Says that a task can't be completed until the task blocking it has been
+pub struct TaskDependency {
+    pub task_id: i32,
+    pub blocked_by_task_id: i32,
+}
+
+/// Determines whether adding [new_dependency] to a set of existing dependencies would leave a task blocking
+/// itself, either directly or through a chain of other tasks
+pub fn creates_cycle(dependencies: &[TaskDependency], new_dependency: TaskDependency) -> bool {
+    let mut blockers: HashMap<i32, Vec<i32>> = HashMap::new();
+    for dependency in dependencies {
+        blockers
+            .entry(dependency.task_id)
+            .or_default()
+            .push(dependency.blocked_by_task_id);
+    }
+
+    // The new dependency closes a cycle if its blocker is already waiting on the task it would block
+    let mut visited = HashSet::new();
+    let mut to_visit = vec![new_dependency.blocked_by_task_id];
+    while let Some(task_id) = to_visit.pop() {
+        if task_id == new_dependency.task_id {
+            return true;
+        }
+        if visited.insert(task_id) {
+            to_visit.extend(blockers.get(&task_id).into_iter().flatten());
+        }
+    }
+
+    false
+}
+
+/// Orders tasks so each one comes after every task blocking it. Tasks which are free to go in either order are
+/// ordered by ID, and dependencies on tasks which aren't in [tasks] are ignored.
+pub fn topological_order(tasks: Vec<TodoTask>, dependencies: &[TaskDependency]) -> Vec<TodoTask> {
+    let mut tasks_by_id: BTreeMap<i32, TodoTask> =
+        tasks.into_iter().map(|task| (task.id, task)).collect();
+    let mut blocker_counts: HashMap<i32, usize> = HashMap::new();
+    let mut blocked_tasks: HashMap<i32, Vec<i32>> = HashMap::new();
+    for dependency in dependencies.iter().filter(|dependency| {
+        tasks_by_id.contains_key(&dependency.task_id)
+            && tasks_by_id.contains_key(&dependency.blocked_by_task_id)
+    }) {
+        *blocker_counts.entry(dependency.task_id).or_default() += 1;
+        blocked_tasks
+            .entry(dependency.blocked_by_task_id)
+            .or_default()
+            .push(dependency.task_id);
+    }
+
+    let mut ready: BTreeSet<i32> = tasks_by_id
+        .keys()
+        .filter(|task_id| !blocker_counts.contains_key(task_id))
+        .copied()
+        .collect();
+    let mut ordered = Vec::with_capacity(tasks_by_id.len());
+    while let Some(task_id) = ready.pop_first() {
+        for blocked_task_id in blocked_tasks.remove(&task_id).unwrap_or_default() {
+            let remaining_blockers = blocker_counts
+                .get_mut(&blocked_task_id)
+                .expect("Blocked tasks should have a blocker count");
+            *remaining_blockers -= 1;
+            if *remaining_blockers == 0 {
+                ready.insert(blocked_task_id);
+            }
+        }
+        ordered.extend(tasks_by_id.remove(&task_id));
+    }
+
+    // Dependencies shouldn't ever form a cycle, but if they do the tasks caught in it still belong in the list
+    ordered.extend(tasks_by_id.into_values());
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depends(task_id: i32, blocked_by_task_id: i32) -> TaskDependency {
+        TaskDependency {
+            task_id,
+            blocked_by_task_id,
+        }
+    }
+
+    fn task(id: i32) -> TodoTask {
+        TodoTask {
+            id,
+            owner_user_id: 1,
+            item_desc: format!("Task {id}"),
+            completed: false,
+            due_date: None,
+            recurrence: None,
+            assignee_user_id: None,
+            comment_count: 0,
+            blocked: false,
+        }
+    }
+
+    fn ordered_ids(tasks: Vec<TodoTask>, dependencies: &[TaskDependency]) -> Vec<i32> {
+        topological_order(tasks, dependencies)
+            .into_iter()
+            .map(|task| task.id)
+            .collect()
+    }
+
+    mod creates_cycle {
+        use super::*;
+
+        #[test]
+        fn detects_a_task_blocking_itself() {
+            assert!(creates_cycle(&[], depends(1, 1)));
+        }
+
+        #[test]
+        fn detects_tasks_blocking_each_other() {
+            assert!(creates_cycle(&[depends(1, 2)], depends(2, 1)));
+        }
+
+        #[test]
+        fn detects_cycles_through_other_tasks() {
+            let dependencies = [depends(1, 2), depends(2, 3), depends(3, 4)];
+
+            assert!(creates_cycle(&dependencies, depends(4, 1)));
+        }
+
+        #[test]
+        fn allows_tasks_sharing_a_blocker() {
+            let dependencies = [depends(1, 3), depends(2, 3), depends(1, 2)];
+
+            assert!(!creates_cycle(&dependencies, depends(4, 3)));
+            assert!(!creates_cycle(&dependencies, depends(4, 1)));
+            assert!(!creates_cycle(&dependencies, depends(3, 4)));
+        }
+    }
+
+    mod topological_order {
+        use super::*;
+
+        #[test]
+        fn puts_blockers_first() {
+            let tasks = vec![task(1), task(2), task(3)];
+            let dependencies = [depends(1, 3), depends(3, 2)];
+
+            assert_eq!(vec![2, 3, 1], ordered_ids(tasks, &dependencies));
+        }
+
+        #[test]
+        fn orders_independent_tasks_by_id() {
+            let tasks = vec![task(4), task(2), task(1), task(3)];
+            let dependencies = [depends(2, 4)];
+
+            assert_eq!(vec![1, 3, 4, 2], ordered_ids(tasks, &dependencies));
+        }
+
+        #[test]
+        fn waits_for_every_blocker() {
+            let tasks = vec![task(1), task(2), task(3), task(4)];
+            let dependencies = [depends(1, 2), depends(1, 4), depends(3, 2)];
+
+            assert_eq!(vec![2, 3, 4, 1], ordered_ids(tasks, &dependencies));
+        }
+
+        #[test]
+        fn ignores_dependencies_on_missing_tasks() {
+            let tasks = vec![task(1), task(2)];
+            let dependencies = [depends(1, 5), depends(5, 2)];
+
+            assert_eq!(vec![1, 2], ordered_ids(tasks, &dependencies));
+        }
+
+        #[test]
+        fn keeps_tasks_caught_in_a_cycle() {
+            let tasks = vec![task(1), task(2), task(3)];
+            let dependencies = [depends(1, 2), depends(2, 1)];
+
+            assert_eq!(vec![3, 1, 2], ordered_ids(tasks, &dependencies));
+        }
+    }
+}
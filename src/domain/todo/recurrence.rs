@@ -0,0 +1,606 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// How many periods in a row may go by without an occurrence before a rule is assumed to never
+/// match again, such as the 31st of every February
+const MAX_EMPTY_PERIODS: u32 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How often a recurring task repeats
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Daily => "DAILY",
+            Self::Weekly => "WEEKLY",
+            Self::Monthly => "MONTHLY",
+            Self::Yearly => "YEARLY",
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+/// Reasons a recurrence rule can't be used
+pub enum RecurrenceError {
+    #[error("The rule must contain a FREQ part.")]
+    MissingFrequency,
+    #[error("\"{0}\" is not of the form NAME=VALUE.")]
+    MalformedPart(String),
+    #[error("{value} is not a valid value for {part}.")]
+    InvalidValue { part: String, value: String },
+    #[error("{0} is not supported in task recurrence rules.")]
+    UnsupportedPart(String),
+    #[error("{0} appears more than once.")]
+    DuplicatePart(String),
+    #[error("COUNT and UNTIL cannot be used together.")]
+    CountWithUntil,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An iCalendar (RFC 5545) recurrence rule describing the due dates of a repeating task. Tasks are due on days
+/// rather than at times, so only the parts of RRULE which deal in whole days are supported: FREQ (DAILY, WEEKLY,
+/// MONTHLY, or YEARLY), INTERVAL, COUNT, UNTIL, BYDAY without ordinals, BYMONTHDAY for monthly rules, and WKST.
+pub struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    by_weekday: Vec<Weekday>,
+    by_month_day: Vec<i32>,
+    count: Option<u32>,
+    until: Option<NaiveDate>,
+    week_start: Weekday,
+}
+
+impl Recurrence {
+    /// Lists the due dates of the series which starts on [start], beginning with [start] itself
+    pub fn occurrences(&self, start: NaiveDate) -> impl Iterator<Item = NaiveDate> + '_ {
+        let mut pending = VecDeque::from([start]);
+        let mut period = 0;
+        let mut empty_periods = 0;
+        let mut emitted = 0;
+
+        std::iter::from_fn(move || loop {
+            if self.count.is_some_and(|count| emitted >= count) {
+                return None;
+            }
+            if let Some(date) = pending.pop_front() {
+                if self.until.is_some_and(|until| date > until) {
+                    return None;
+                }
+                emitted += 1;
+                return Some(date);
+            }
+            if empty_periods >= MAX_EMPTY_PERIODS {
+                return None;
+            }
+
+            // The start is always the first occurrence, even if it doesn't match the rule
+            let dates = self.dates_in_period(start, period)?;
+            pending.extend(dates.into_iter().filter(|date| *date > start));
+            period += 1;
+            empty_periods = if pending.is_empty() {
+                empty_periods + 1
+            } else {
+                0
+            };
+        })
+    }
+
+    /// Finds the occurrence after the one on [due_date], along with the rule the following occurrence carries
+    /// forward. Returns [None] once the series is over.
+    pub fn next_occurrence(&self, due_date: NaiveDate) -> Option<(NaiveDate, Recurrence)> {
+        let next_due_date = self.occurrences(due_date).nth(1)?;
+        let remaining_rule = Recurrence {
+            count: self.count.map(|count| count - 1),
+            ..self.clone()
+        };
+
+        Some((next_due_date, remaining_rule))
+    }
+
+    /// Every date in the [period]th period of the series starting on [start] which matches the rule, in order.
+    /// Returns [None] if the period is past the range of representable dates.
+    fn dates_in_period(&self, start: NaiveDate, period: u32) -> Option<Vec<NaiveDate>> {
+        let step = period.checked_mul(self.interval)?;
+        let dates = match self.frequency {
+            Frequency::Daily => {
+                let day = start.checked_add_days(Days::new(step.into()))?;
+                vec![day]
+                    .into_iter()
+                    .filter(|day| self.matches_weekday(*day))
+                    .collect()
+            }
+            Frequency::Weekly => {
+                let days_into_week = start.weekday().days_since(self.week_start);
+                let week = start
+                    .checked_sub_days(Days::new(days_into_week.into()))?
+                    .checked_add_days(Days::new(u64::from(step) * 7))?;
+                let weekdays = if self.by_weekday.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_weekday.clone()
+                };
+
+                week.iter_days()
+                    .take(7)
+                    .filter(|day| weekdays.contains(&day.weekday()))
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let month = start.with_day(1)?.checked_add_months(Months::new(step))?;
+                let days_in_month = month
+                    .checked_add_months(Months::new(1))?
+                    .signed_duration_since(month)
+                    .num_days() as i32;
+
+                month
+                    .iter_days()
+                    .take(days_in_month as usize)
+                    .filter(|day| {
+                        let day_of_month = day.day() as i32;
+                        let matches_month_day = if self.by_month_day.is_empty() {
+                            !self.by_weekday.is_empty() || day_of_month == start.day() as i32
+                        } else {
+                            self.by_month_day.iter().any(|by_day| {
+                                *by_day == day_of_month
+                                    || *by_day == day_of_month - days_in_month - 1
+                            })
+                        };
+                        matches_month_day && self.matches_weekday(*day)
+                    })
+                    .collect()
+            }
+            Frequency::Yearly => {
+                let year = start.year().checked_add(i32::try_from(step).ok()?)?;
+                NaiveDate::from_ymd_opt(year, start.month(), start.day())
+                    .into_iter()
+                    .collect()
+            }
+        };
+
+        Some(dates)
+    }
+
+    fn matches_weekday(&self, day: NaiveDate) -> bool {
+        self.by_weekday.is_empty() || self.by_weekday.contains(&day.weekday())
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = RecurrenceError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim();
+        let rule = rule
+            .strip_prefix("RRULE:")
+            .or_else(|| rule.strip_prefix("rrule:"))
+            .unwrap_or(rule);
+
+        let mut frequency = None;
+        let mut interval = None;
+        let mut by_weekday = None;
+        let mut by_month_day = None;
+        let mut count = None;
+        let mut until = None;
+        let mut week_start = None;
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| RecurrenceError::MalformedPart(part.to_owned()))?;
+            let name = name.trim().to_ascii_uppercase();
+            let value = value.trim().to_ascii_uppercase();
+            let invalid_value = || RecurrenceError::InvalidValue {
+                part: name.clone(),
+                value: value.clone(),
+            };
+
+            let already_set = match name.as_str() {
+                "FREQ" => frequency
+                    .replace(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        "SECONDLY" | "MINUTELY" | "HOURLY" => {
+                            return Err(RecurrenceError::UnsupportedPart(format!("FREQ={value}")))
+                        }
+                        _ => return Err(invalid_value()),
+                    })
+                    .is_some(),
+                "INTERVAL" => interval
+                    .replace(parse_positive(&value).ok_or_else(invalid_value)?)
+                    .is_some(),
+                "COUNT" => count
+                    .replace(parse_positive(&value).ok_or_else(invalid_value)?)
+                    .is_some(),
+                "UNTIL" => until
+                    .replace(parse_until(&value).ok_or_else(invalid_value)?)
+                    .is_some(),
+                "BYDAY" => {
+                    let weekdays = value
+                        .split(',')
+                        .map(|day| parse_weekday(day).ok_or_else(invalid_value))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    by_weekday.replace(weekdays).is_some()
+                }
+                "BYMONTHDAY" => {
+                    let month_days = value
+                        .split(',')
+                        .map(|day| {
+                            day.parse::<i32>()
+                                .ok()
+                                .filter(|day| (1..=31).contains(&day.abs()))
+                                .ok_or_else(invalid_value)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    by_month_day.replace(month_days).is_some()
+                }
+                "WKST" => week_start
+                    .replace(parse_weekday(&value).ok_or_else(invalid_value)?)
+                    .is_some(),
+                _ => return Err(RecurrenceError::UnsupportedPart(name)),
+            };
+            if already_set {
+                return Err(RecurrenceError::DuplicatePart(name));
+            }
+        }
+
+        let frequency = frequency.ok_or(RecurrenceError::MissingFrequency)?;
+        if count.is_some() && until.is_some() {
+            return Err(RecurrenceError::CountWithUntil);
+        }
+        if by_weekday.is_some() && frequency == Frequency::Yearly {
+            return Err(RecurrenceError::UnsupportedPart(
+                "BYDAY with FREQ=YEARLY".to_owned(),
+            ));
+        }
+        if by_month_day.is_some() && frequency != Frequency::Monthly {
+            return Err(RecurrenceError::UnsupportedPart(format!(
+                "BYMONTHDAY with FREQ={}",
+                frequency.name()
+            )));
+        }
+
+        Ok(Recurrence {
+            frequency,
+            interval: interval.unwrap_or(1),
+            by_weekday: by_weekday.unwrap_or_default(),
+            by_month_day: by_month_day.unwrap_or_default(),
+            count,
+            until,
+            week_start: week_start.unwrap_or(Weekday::Mon),
+        })
+    }
+}
+
+impl fmt::Display for Recurrence {
+    /// Writes the rule in its canonical RRULE form, without the "RRULE:" prefix
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.name())?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_weekday.is_empty() {
+            let weekdays: Vec<&str> = self.by_weekday.iter().map(weekday_name).collect();
+            write!(f, ";BYDAY={}", weekdays.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let month_days: Vec<String> =
+                self.by_month_day.iter().map(ToString::to_string).collect();
+            write!(f, ";BYMONTHDAY={}", month_days.join(","))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        if self.week_start != Weekday::Mon {
+            write!(f, ";WKST={}", weekday_name(&self.week_start))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_positive(value: &str) -> Option<u32> {
+    value.parse().ok().filter(|number| *number > 0)
+}
+
+/// UNTIL may be a date or a date-time. Only the date matters for tasks.
+fn parse_until(value: &str) -> Option<NaiveDate> {
+    let date = value.split_once('T').map_or(value, |(date, _)| date);
+    NaiveDate::parse_from_str(date, "%Y%m%d").ok()
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn weekday_name(weekday: &Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn first_occurrences(rule: &str, start: NaiveDate, how_many: usize) -> Vec<NaiveDate> {
+        let recurrence: Recurrence = rule.parse().expect("rule should parse");
+        recurrence.occurrences(start).take(how_many).collect()
+    }
+
+    mod parsing {
+        use super::*;
+
+        #[test]
+        fn round_trips_to_canonical_form() {
+            let recurrence: Recurrence = "RRULE:freq=weekly;byday=mo,we;interval=2;count=6;wkst=su"
+                .parse()
+                .unwrap();
+            assert_eq!(
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;COUNT=6;WKST=SU",
+                recurrence.to_string()
+            );
+            assert_eq!(Ok(recurrence.clone()), recurrence.to_string().parse());
+        }
+
+        #[test]
+        fn accepts_until_as_a_date_time() {
+            let recurrence: Recurrence = "FREQ=DAILY;UNTIL=20240310T235959Z".parse().unwrap();
+            assert_eq!("FREQ=DAILY;UNTIL=20240310", recurrence.to_string());
+        }
+
+        #[test]
+        fn rejects_bad_rules() {
+            let cases = [
+                ("", RecurrenceError::MissingFrequency),
+                ("INTERVAL=2", RecurrenceError::MissingFrequency),
+                ("FREQ", RecurrenceError::MalformedPart("FREQ".to_owned())),
+                (
+                    "FREQ=FORTNIGHTLY",
+                    RecurrenceError::InvalidValue {
+                        part: "FREQ".to_owned(),
+                        value: "FORTNIGHTLY".to_owned(),
+                    },
+                ),
+                (
+                    "FREQ=HOURLY",
+                    RecurrenceError::UnsupportedPart("FREQ=HOURLY".to_owned()),
+                ),
+                (
+                    "FREQ=DAILY;INTERVAL=0",
+                    RecurrenceError::InvalidValue {
+                        part: "INTERVAL".to_owned(),
+                        value: "0".to_owned(),
+                    },
+                ),
+                (
+                    "FREQ=MONTHLY;BYDAY=1MO",
+                    RecurrenceError::InvalidValue {
+                        part: "BYDAY".to_owned(),
+                        value: "1MO".to_owned(),
+                    },
+                ),
+                (
+                    "FREQ=MONTHLY;BYMONTHDAY=32",
+                    RecurrenceError::InvalidValue {
+                        part: "BYMONTHDAY".to_owned(),
+                        value: "32".to_owned(),
+                    },
+                ),
+                (
+                    "FREQ=DAILY;BYHOUR=9",
+                    RecurrenceError::UnsupportedPart("BYHOUR".to_owned()),
+                ),
+                (
+                    "FREQ=WEEKLY;BYMONTHDAY=1",
+                    RecurrenceError::UnsupportedPart("BYMONTHDAY with FREQ=WEEKLY".to_owned()),
+                ),
+                (
+                    "FREQ=DAILY;FREQ=WEEKLY",
+                    RecurrenceError::DuplicatePart("FREQ".to_owned()),
+                ),
+                (
+                    "FREQ=DAILY;COUNT=3;UNTIL=20240101",
+                    RecurrenceError::CountWithUntil,
+                ),
+            ];
+
+            for (rule, expected_err) in cases {
+                assert_eq!(
+                    Err(expected_err),
+                    rule.parse::<Recurrence>(),
+                    "Unexpected result for rule {rule:?}"
+                );
+            }
+        }
+    }
+
+    mod occurrences {
+        use super::*;
+
+        #[test]
+        fn daily_with_interval() {
+            assert_eq!(
+                vec![date(2024, 2, 27), date(2024, 3, 1), date(2024, 3, 4)],
+                first_occurrences("FREQ=DAILY;INTERVAL=3", date(2024, 2, 27), 3)
+            );
+        }
+
+        #[test]
+        fn daily_on_weekdays() {
+            assert_eq!(
+                vec![date(2024, 3, 8), date(2024, 3, 11), date(2024, 3, 12)],
+                first_occurrences("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR", date(2024, 3, 8), 3)
+            );
+        }
+
+        #[test]
+        fn weekly_defaults_to_the_start_weekday() {
+            assert_eq!(
+                vec![date(2024, 3, 4), date(2024, 3, 11), date(2024, 3, 18)],
+                first_occurrences("FREQ=WEEKLY", date(2024, 3, 4), 3)
+            );
+        }
+
+        #[test]
+        fn every_other_week_on_several_days() {
+            // Starting on a Wednesday includes the rest of that week before skipping a week
+            assert_eq!(
+                vec![
+                    date(2024, 3, 6),
+                    date(2024, 3, 8),
+                    date(2024, 3, 18),
+                    date(2024, 3, 20),
+                    date(2024, 3, 22),
+                ],
+                first_occurrences("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR", date(2024, 3, 6), 5)
+            );
+        }
+
+        #[test]
+        fn week_start_decides_which_days_share_a_week() {
+            assert_eq!(
+                vec![date(2024, 3, 5), date(2024, 3, 10), date(2024, 3, 19)],
+                first_occurrences("FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,SU", date(2024, 3, 5), 3)
+            );
+            assert_eq!(
+                vec![date(2024, 3, 5), date(2024, 3, 17), date(2024, 3, 19)],
+                first_occurrences(
+                    "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,SU;WKST=SU",
+                    date(2024, 3, 5),
+                    3
+                )
+            );
+        }
+
+        #[test]
+        fn monthly_skips_months_without_the_day() {
+            assert_eq!(
+                vec![date(2024, 1, 31), date(2024, 3, 31), date(2024, 5, 31)],
+                first_occurrences("FREQ=MONTHLY", date(2024, 1, 31), 3)
+            );
+        }
+
+        #[test]
+        fn monthly_on_the_last_day() {
+            assert_eq!(
+                vec![date(2024, 1, 31), date(2024, 2, 29), date(2024, 3, 31)],
+                first_occurrences("FREQ=MONTHLY;BYMONTHDAY=-1", date(2024, 1, 31), 3)
+            );
+        }
+
+        #[test]
+        fn monthly_on_weekdays() {
+            assert_eq!(
+                vec![date(2024, 2, 26), date(2024, 3, 4), date(2024, 3, 11)],
+                first_occurrences("FREQ=MONTHLY;BYDAY=MO", date(2024, 2, 26), 3)
+            );
+        }
+
+        #[test]
+        fn yearly_on_leap_day() {
+            assert_eq!(
+                vec![date(2024, 2, 29), date(2028, 2, 29)],
+                first_occurrences("FREQ=YEARLY", date(2024, 2, 29), 2)
+            );
+        }
+
+        #[test]
+        fn start_counts_as_an_occurrence_even_if_it_doesnt_match() {
+            assert_eq!(
+                vec![date(2024, 3, 6), date(2024, 3, 11), date(2024, 3, 18)],
+                first_occurrences("FREQ=WEEKLY;BYDAY=MO", date(2024, 3, 6), 3)
+            );
+        }
+
+        #[test]
+        fn stops_at_count_and_until() {
+            assert_eq!(
+                vec![date(2024, 3, 1), date(2024, 3, 2), date(2024, 3, 3)],
+                first_occurrences("FREQ=DAILY;COUNT=3", date(2024, 3, 1), 10)
+            );
+            assert_eq!(
+                vec![date(2024, 3, 1), date(2024, 3, 8)],
+                first_occurrences("FREQ=WEEKLY;UNTIL=20240314", date(2024, 3, 1), 10)
+            );
+        }
+
+        #[test]
+        fn ends_when_the_rule_can_never_match() {
+            assert_eq!(
+                vec![date(2024, 2, 1)],
+                first_occurrences(
+                    "FREQ=MONTHLY;INTERVAL=12;BYMONTHDAY=30",
+                    date(2024, 2, 1),
+                    10
+                )
+            );
+        }
+    }
+
+    mod next_occurrence {
+        use super::*;
+
+        #[test]
+        fn carries_remaining_count_forward() {
+            let recurrence: Recurrence = "FREQ=DAILY;COUNT=2".parse().unwrap();
+
+            let (next_due_date, next_rule) = recurrence
+                .next_occurrence(date(2024, 3, 1))
+                .expect("series should continue");
+            assert_eq!(date(2024, 3, 2), next_due_date);
+            assert_eq!("FREQ=DAILY;COUNT=1", next_rule.to_string());
+
+            assert_eq!(None, next_rule.next_occurrence(next_due_date));
+        }
+
+        #[test]
+        fn continues_the_same_series() {
+            let recurrence: Recurrence = "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR".parse().unwrap();
+            let start = date(2024, 3, 6);
+
+            let mut due_date = start;
+            let mut rule = recurrence.clone();
+            let mut completed_dates = vec![due_date];
+            for _ in 0..6 {
+                (due_date, rule) = rule.next_occurrence(due_date).unwrap();
+                completed_dates.push(due_date);
+            }
+
+            let expected: Vec<NaiveDate> = recurrence.occurrences(start).take(7).collect();
+            assert_eq!(expected, completed_dates);
+        }
+    }
+}
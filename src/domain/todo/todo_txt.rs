@@ -0,0 +1,411 @@
+use chrono::NaiveDate;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// How dates are written in todo.txt
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Written before a description which would otherwise be read as starting with a completion marker, priority
+/// or date
+const ESCAPE: char = '\\';
+
+#[derive(Debug, Error, PartialEq, Eq)]
+/// Reasons a line can't be read as a todo.txt task
+pub enum TodoTxtError {
+    #[error("The task has no description.")]
+    MissingDescription,
+    #[error("A task must be on a single line.")]
+    MultipleLines,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A task written as a line of a todo.txt file (<https://github.com/todotxt/todo.txt>), such as
+/// `x (A) 2024-03-05 2024-03-01 Call mom +family @phone due:2024-03-06`. Projects (`+family`), contexts
+/// (`@phone`) and `key:value` tags are part of the description, just as they are in the format itself.
+/// The format has no way to tell a description such as `x marks the spot` apart from a completed task, so a
+/// description which starts with something that looks like a marker, priority or date, or with a backslash,
+/// is written with a backslash in front of it, which is dropped when it's read back.
+pub struct TodoTxtTask {
+    pub completed: bool,
+    /// From `A`, the most important, to `Z`
+    pub priority: Option<char>,
+    /// Only completed tasks have a completion date, and a completed task needs one to have a creation date
+    pub completion_date: Option<NaiveDate>,
+    pub creation_date: Option<NaiveDate>,
+    pub description: String,
+}
+
+impl TodoTxtTask {
+    /// The value of the first `key:value` tag in the description with the given key
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.description
+            .split_whitespace()
+            .find_map(|word| tag_value(word, key))
+    }
+
+    /// Removes every `key:value` tag with the given key from the description, returning the value of the
+    /// first one
+    pub fn take_tag(&mut self, key: &str) -> Option<String> {
+        let value = self.tag(key).map(str::to_owned);
+        if value.is_some() {
+            self.description = self
+                .description
+                .split_whitespace()
+                .filter(|word| tag_value(word, key).is_none())
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+
+        value
+    }
+
+    /// Adds a `key:value` tag to the end of the description
+    pub fn add_tag(&mut self, key: &str, value: impl fmt::Display) {
+        if self.description.is_empty() {
+            self.description = format!("{key}:{value}");
+        } else {
+            self.description = format!("{} {key}:{value}", self.description);
+        }
+    }
+}
+
+/// The value of a word if it's a `key:value` tag with the given key
+fn tag_value<'word>(word: &'word str, key: &str) -> Option<&'word str> {
+    word.strip_prefix(key)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .filter(|value| !value.is_empty())
+}
+
+/// Splits the first word off a line, returning it along with the rest of the line
+fn split_word(line: &str) -> (&str, &str) {
+    match line.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim_start()),
+        None => (line, ""),
+    }
+}
+
+/// Parses a priority written as `(A)`
+fn parse_priority(word: &str) -> Option<char> {
+    let mut chars = word.chars();
+    match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some('('), Some(priority), Some(')'), None) if priority.is_ascii_uppercase() => {
+            Some(priority)
+        }
+        _ => None,
+    }
+}
+
+/// Parses a date written as `2024-03-05`
+fn parse_date(word: &str) -> Option<NaiveDate> {
+    if word.len() != 10 {
+        return None;
+    }
+    NaiveDate::parse_from_str(word, DATE_FORMAT).ok()
+}
+
+/// Whether a description has to be escaped so its start isn't read as a completion marker, priority or date
+fn needs_escape(description: &str) -> bool {
+    let (first_word, _) = split_word(description);
+    first_word == "x"
+        || first_word.starts_with(ESCAPE)
+        || parse_priority(first_word).is_some()
+        || parse_date(first_word).is_some()
+}
+
+impl FromStr for TodoTxtTask {
+    type Err = TodoTxtError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        if line.contains(['\n', '\r']) {
+            return Err(TodoTxtError::MultipleLines);
+        }
+
+        let mut rest = line.trim();
+        let completed = match rest.strip_prefix("x ") {
+            Some(after_marker) => {
+                rest = after_marker.trim_start();
+                true
+            }
+            None => false,
+        };
+
+        let (word, after_word) = split_word(rest);
+        let priority = parse_priority(word);
+        if priority.is_some() {
+            rest = after_word;
+        }
+
+        let mut dates = Vec::with_capacity(2);
+        let date_limit = if completed { 2 } else { 1 };
+        while dates.len() < date_limit {
+            let (word, after_word) = split_word(rest);
+            let Some(date) = parse_date(word) else {
+                break;
+            };
+            dates.push(date);
+            rest = after_word;
+        }
+        let (completion_date, creation_date) = match (completed, dates.as_slice()) {
+            (true, [completion_date, creation_date]) => {
+                (Some(*completion_date), Some(*creation_date))
+            }
+            (true, [completion_date]) => (Some(*completion_date), None),
+            (false, [creation_date]) => (None, Some(*creation_date)),
+            _ => (None, None),
+        };
+
+        let description = rest.strip_prefix(ESCAPE).unwrap_or(rest);
+        if description.is_empty() {
+            return Err(TodoTxtError::MissingDescription);
+        }
+
+        Ok(TodoTxtTask {
+            completed,
+            priority,
+            completion_date,
+            creation_date,
+            description: description.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for TodoTxtTask {
+    /// Writes the task as a single line, without a line break at the end
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.completed {
+            write!(f, "x ")?;
+        }
+        if let Some(priority) = self.priority {
+            write!(f, "({priority}) ")?;
+        }
+        if let Some(completion_date) = self.completion_date {
+            write!(f, "{} ", completion_date.format(DATE_FORMAT))?;
+        }
+        if let Some(creation_date) = self.creation_date {
+            write!(f, "{} ", creation_date.format(DATE_FORMAT))?;
+        }
+
+        if needs_escape(&self.description) {
+            write!(f, "{ESCAPE}")?;
+        }
+        write!(f, "{}", self.description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    mod parsing {
+        use super::*;
+
+        #[test]
+        fn reads_every_part() {
+            let task: TodoTxtTask =
+                "x (A) 2024-03-05 2024-03-01 Call mom +family @phone due:2024-03-06"
+                    .parse()
+                    .unwrap();
+            assert_eq!(
+                TodoTxtTask {
+                    completed: true,
+                    priority: Some('A'),
+                    completion_date: Some(date(2024, 3, 5)),
+                    creation_date: Some(date(2024, 3, 1)),
+                    description: "Call mom +family @phone due:2024-03-06".to_owned(),
+                },
+                task
+            );
+            assert_eq!(Some("2024-03-06"), task.tag("due"));
+        }
+
+        #[test]
+        fn single_date_of_open_task_is_creation_date() {
+            let task: TodoTxtTask = "(B) 2024-03-01 Water the plants".parse().unwrap();
+            assert_eq!(Some(date(2024, 3, 1)), task.creation_date);
+            assert_eq!(None, task.completion_date);
+            assert_eq!("Water the plants", task.description);
+        }
+
+        #[test]
+        fn single_date_of_completed_task_is_completion_date() {
+            let task: TodoTxtTask = "x 2024-03-05 Water the plants".parse().unwrap();
+            assert_eq!(Some(date(2024, 3, 5)), task.completion_date);
+            assert_eq!(None, task.creation_date);
+        }
+
+        #[test]
+        fn lowercase_priority_and_x_without_space_are_description() {
+            let task: TodoTxtTask = "(a) xylophone lessons".parse().unwrap();
+            assert!(!task.completed);
+            assert_eq!(None, task.priority);
+            assert_eq!("(a) xylophone lessons", task.description);
+
+            let task: TodoTxtTask = "xylophone lessons".parse().unwrap();
+            assert!(!task.completed);
+        }
+
+        #[test]
+        fn drops_escape_in_front_of_description() {
+            let task: TodoTxtTask = "\\x marks the spot".parse().unwrap();
+            assert!(!task.completed);
+            assert_eq!("x marks the spot", task.description);
+
+            let task: TodoTxtTask = "(B) \\2024-03-01 was a Friday".parse().unwrap();
+            assert_eq!(Some('B'), task.priority);
+            assert_eq!(None, task.creation_date);
+            assert_eq!("2024-03-01 was a Friday", task.description);
+        }
+
+        #[test]
+        fn rejects_lines_without_description() {
+            assert_eq!(
+                Err(TodoTxtError::MissingDescription),
+                "x (A) 2024-03-05".parse::<TodoTxtTask>()
+            );
+            assert_eq!(
+                Err(TodoTxtError::MissingDescription),
+                "   ".parse::<TodoTxtTask>()
+            );
+        }
+
+        #[test]
+        fn rejects_multiple_lines() {
+            assert_eq!(
+                Err(TodoTxtError::MultipleLines),
+                "First\nSecond".parse::<TodoTxtTask>()
+            );
+        }
+    }
+
+    mod writing {
+        use super::*;
+
+        fn open_task(description: &str) -> TodoTxtTask {
+            TodoTxtTask {
+                completed: false,
+                priority: None,
+                completion_date: None,
+                creation_date: None,
+                description: description.to_owned(),
+            }
+        }
+
+        #[test]
+        fn escapes_descriptions_which_look_like_markers() {
+            for (description, line) in [
+                ("x marks the spot", "\\x marks the spot"),
+                ("(A) grade", "\\(A) grade"),
+                ("2024-03-01 was a Friday", "\\2024-03-01 was a Friday"),
+                ("\\o/ celebrate", "\\\\o/ celebrate"),
+            ] {
+                assert_eq!(line, open_task(description).to_string());
+            }
+        }
+
+        #[test]
+        fn leaves_other_descriptions_alone() {
+            assert_eq!(
+                "xylophone lessons",
+                open_task("xylophone lessons").to_string()
+            );
+            assert_eq!("(a) xylophone", open_task("(a) xylophone").to_string());
+        }
+    }
+
+    mod tags {
+        use super::*;
+
+        #[test]
+        fn take_tag_removes_every_match() {
+            let mut task: TodoTxtTask = "Pay rent due:2024-03-01 +home due:2024-04-01 overdue:no"
+                .parse()
+                .unwrap();
+            assert_eq!(Some("2024-03-01".to_owned()), task.take_tag("due"));
+            assert_eq!("Pay rent +home overdue:no", task.description);
+            assert_eq!(None, task.take_tag("due"));
+        }
+
+        #[test]
+        fn add_tag_appends_to_description() {
+            let mut task: TodoTxtTask = "Pay rent".parse().unwrap();
+            task.add_tag("due", date(2024, 3, 1));
+            assert_eq!("Pay rent due:2024-03-01", task.to_string());
+        }
+    }
+
+    /// Any word, including ones which look like the completion marker, a priority, a date or an escape
+    fn any_word() -> impl Strategy<Value = String> {
+        prop_oneof![
+            4 => "\\S{1,10}",
+            1 => Just("x".to_owned()),
+            1 => "\\([A-Z]\\)",
+            1 => any_date().prop_map(|date| date.format(DATE_FORMAT).to_string()),
+            1 => "\\\\\\S{0,5}",
+        ]
+    }
+
+    /// Any description which fits on one line, made of words, projects, contexts and tags
+    fn description() -> impl Strategy<Value = String> {
+        let word = prop_oneof![
+            4 => any_word(),
+            1 => "[+@][a-zA-Z0-9_-]{1,10}",
+            1 => "[a-z]{1,6}:[a-zA-Z0-9=;-]{1,12}",
+        ];
+        prop::collection::vec(word, 1..8).prop_map(|words| words.join(" "))
+    }
+
+    fn any_date() -> impl Strategy<Value = NaiveDate> {
+        (1900..2200i32, 1..=12u32, 1..=28u32).prop_map(|(year, month, day)| date(year, month, day))
+    }
+
+    prop_compose! {
+        fn any_task()(
+            completed in any::<bool>(),
+            priority in prop::option::of(prop::char::range('A', 'Z')),
+            first_date in prop::option::of(any_date()),
+            second_date in prop::option::of(any_date()),
+            description in description(),
+        ) -> TodoTxtTask {
+            let (completion_date, creation_date) = match (completed, first_date) {
+                (true, Some(completion_date)) => (Some(completion_date), second_date),
+                (true, None) => (None, None),
+                (false, creation_date) => (None, creation_date),
+            };
+
+            TodoTxtTask { completed, priority, completion_date, creation_date, description }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn written_tasks_read_back_the_same(task in any_task()) {
+            prop_assert_eq!(Ok(task.clone()), task.to_string().parse());
+        }
+
+        #[test]
+        fn read_tasks_write_back_the_same(line in any_task().prop_map(|task| task.to_string())) {
+            let task: TodoTxtTask = line.parse().unwrap();
+            prop_assert_eq!(line, task.to_string());
+        }
+
+        #[test]
+        fn tags_come_back_out(task in any_task(), key in "[a-z]{1,6}", value in "[a-zA-Z0-9=;-]{1,12}") {
+            let mut task = task;
+            task.take_tag(&key);
+            task.add_tag(&key, &value);
+
+            let mut read_back: TodoTxtTask = task.to_string().parse().unwrap();
+            prop_assert_eq!(Some(value), read_back.take_tag(&key));
+        }
+
+        #[test]
+        fn reading_never_panics(line in ".*") {
+            let _ = line.parse::<TodoTxtTask>();
+        }
+    }
+}
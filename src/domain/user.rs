@@ -1,8 +1,39 @@
-use crate::domain::user::driving_ports::CreateUserError;
+use crate::domain;
+use crate::domain::access::{Action, Caller};
+use crate::domain::user::driving_ports::{AdminBootstrap, CreateUserError, UserError};
 use crate::domain::Error;
 use crate::external_connections::ExternalConnectivity;
 use anyhow::Context;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// What a user is allowed to do, as decided by [domain::access::authorize]
+pub enum Role {
+    /// Can only see themselves and manage their own tasks
+    #[default]
+    User,
+    /// Can see every user and manage anyone's tasks
+    Admin,
+}
+
+impl Role {
+    /// The name the role is stored under, such as "admin"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Looks up a role by its name, returning [None] if no role has that name
+    pub fn from_name(name: &str) -> Option<Role> {
+        match name {
+            "user" => Some(Self::User),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Default)]
 #[cfg_attr(test, derive(Clone))]
 /// A user who can own to-do items
@@ -10,6 +41,15 @@ pub struct TodoUser {
     pub id: i32,
     pub first_name: String,
     pub last_name: String,
+    pub role: Role,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(test, derive(Clone))]
+/// A user along with an overview of the tasks they own
+pub struct UserWithTaskSummary {
+    pub user: TodoUser,
+    pub tasks: domain::todo::TaskSummary,
 }
 
 /// The set of driven ports that can be invoked by the business logic
@@ -30,6 +70,24 @@ pub mod driven_ports {
             id: i32,
             ext_cxn: &mut impl ExternalConnectivity,
         ) -> Result<Option<TodoUser>, anyhow::Error>;
+        /// Retrieve users whose first name, last name, or full name starts with [name_prefix],
+        /// ignoring case and accents
+        async fn search_by_name(
+            &self,
+            name_prefix: &str,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TodoUser>, anyhow::Error>;
+        /// Retrieve the user with exactly the given name
+        async fn by_name<'names>(
+            &self,
+            description: UserDescription<'names>,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TodoUser>, anyhow::Error>;
+        /// Count the users who are admins
+        async fn admin_count(
+            &self,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<i64, anyhow::Error>;
     }
 
     /// An external system which can accept new user data
@@ -40,6 +98,14 @@ pub mod driven_ports {
             user: &CreateUser,
             ext_cxn: &mut impl ExternalConnectivity,
         ) -> Result<i32, anyhow::Error>;
+
+        /// Change the role of a user, returning false if the user doesn't exist
+        async fn set_role(
+            &self,
+            user_id: i32,
+            role: Role,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error>;
     }
 
     /// Contains a description of a user's unique personal information
@@ -87,30 +153,124 @@ pub mod driving_ports {
         PortError(#[from] anyhow::Error),
     }
 
+    #[derive(Debug, Error)]
+    /// Defines the set of reasons why users couldn't be looked up or changed
+    pub enum UserError {
+        #[error(transparent)]
+        Forbidden(#[from] domain::access::AccessDenied),
+        #[error("The specified user did not exist.")]
+        UserDoesNotExist,
+        #[error("The last admin can't stop being an admin.")]
+        LastAdmin,
+        #[error(transparent)]
+        PortError(#[from] anyhow::Error),
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(test, derive(Clone))]
+    /// What happened when making sure the system has an admin
+    pub enum AdminBootstrap {
+        /// There already was an admin, so nothing was changed
+        AdminAlreadyExists,
+        /// The user with the given ID already existed and was made an admin
+        Promoted(i32),
+        /// A new admin was created with the given ID
+        Created(i32),
+    }
+
     /// The driving port which exposes business logic involving users to driving adapters
     pub trait UserPort {
+        /// Work out who is calling the application from the ID of the user they claim to be. Callers who
+        /// don't claim to be anyone, or claim to be a user who doesn't exist, are anonymous.
+        async fn identify_caller(
+            &self,
+            user_id: Option<i32>,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_reader: &impl driven_ports::UserReader,
+        ) -> Result<Caller, anyhow::Error>;
+
         /// Retrieve the set of users in the system
         async fn get_users(
             &self,
+            caller: &Caller,
             ext_cxn: &mut impl ExternalConnectivity,
             u_reader: &impl driven_ports::UserReader,
-        ) -> Result<Vec<TodoUser>, anyhow::Error>;
+        ) -> Result<Vec<TodoUser>, UserError>;
+
+        /// Retrieve a single user along with counts of the tasks they own
+        async fn get_user(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_reader: &impl driven_ports::UserReader,
+            task_read: &impl domain::todo::driven_ports::TaskReader,
+        ) -> Result<Option<UserWithTaskSummary>, UserError>;
 
-        /// Create a new user who can be responsible for to-do items
+        /// Retrieve the set of users whose names start with the given text
+        async fn search_users(
+            &self,
+            caller: &Caller,
+            name: &str,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_reader: &impl driven_ports::UserReader,
+        ) -> Result<Vec<TodoUser>, UserError>;
+
+        /// Change what a user is allowed to do. The last admin can't be made a regular user, so there's always
+        /// someone who can manage roles.
+        async fn set_user_role(
+            &self,
+            caller: &Caller,
+            user_id: i32,
+            role: Role,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_reader: &impl driven_ports::UserReader,
+            u_writer: &impl driven_ports::UserWriter,
+        ) -> Result<(), UserError>;
+
+        /// Make sure the system has an admin by making the described user one if there are no admins yet,
+        /// creating them if they don't exist
+        async fn bootstrap_admin(
+            &self,
+            admin: &CreateUser,
+            ext_cxn: &mut impl ExternalConnectivity,
+            u_reader: &impl driven_ports::UserReader,
+            u_writer: &impl driven_ports::UserWriter,
+            u_detect: &impl driven_ports::DetectUser,
+            event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
+        ) -> Result<AdminBootstrap, anyhow::Error>;
+
+        /// Create a new user who can be responsible for to-do items, recording a
+        /// [WebhookEvent::UserCreated][domain::webhook::WebhookEvent::UserCreated] event
         async fn create_user(
             &self,
             new_user: &CreateUser,
             ext_cxn: &mut impl ExternalConnectivity,
             u_writer: &impl driven_ports::UserWriter,
             u_detect: &impl driven_ports::DetectUser,
+            event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
         ) -> Result<i32, CreateUserError>;
     }
 
     #[cfg(test)]
     mod cue_clone {
-        use crate::domain::user::driving_ports::CreateUserError;
+        use crate::domain::user::driving_ports::{CreateUserError, UserError};
         use anyhow::anyhow;
 
+        // Implements clone for UserError so the error type can be used with mocks
+        impl Clone for UserError {
+            fn clone(&self) -> Self {
+                match self {
+                    UserError::Forbidden(denied) => UserError::Forbidden(denied.clone()),
+                    UserError::UserDoesNotExist => UserError::UserDoesNotExist,
+                    UserError::LastAdmin => UserError::LastAdmin,
+                    UserError::PortError(anyhow_err) => {
+                        UserError::PortError(anyhow!(format!("{}", anyhow_err)))
+                    }
+                }
+            }
+        }
+
         // Implements clone for CreateUserInfo in tests so the error type can be used with mocks
         impl Clone for CreateUserError {
             fn clone(&self) -> Self {
@@ -154,17 +314,177 @@ pub(super) async fn verify_user_exists(
 }
 
 impl driving_ports::UserPort for UserService {
+    async fn identify_caller(
+        &self,
+        user_id: Option<i32>,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_reader: &impl driven_ports::UserReader,
+    ) -> Result<Caller, anyhow::Error> {
+        let Some(user_id) = user_id else {
+            return Ok(Caller::Anonymous);
+        };
+
+        let user = u_reader
+            .by_id(user_id, ext_cxn)
+            .await
+            .context("Looking up the calling user")?;
+        match user {
+            Some(user) => Ok(Caller::User {
+                user_id: user.id,
+                role: user.role,
+            }),
+            None => {
+                log::warn!("Calling user {user_id} does not exist, so they're anonymous");
+                Ok(Caller::Anonymous)
+            }
+        }
+    }
+
     async fn get_users(
         &self,
+        caller: &Caller,
         ext_cxn: &mut impl ExternalConnectivity,
         u_reader: &impl driven_ports::UserReader,
-    ) -> Result<Vec<TodoUser>, anyhow::Error> {
+    ) -> Result<Vec<TodoUser>, UserError> {
+        domain::access::authorize(caller, Action::ListUsers)?;
+
         let all_users_result = u_reader.all(ext_cxn).await;
         if let Err(ref port_err) = all_users_result {
             log::error!("User fetch failure: {port_err}");
         }
 
-        all_users_result.context("Failed fetching users")
+        Ok(all_users_result.context("Failed fetching users")?)
+    }
+
+    async fn get_user(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_reader: &impl driven_ports::UserReader,
+        task_read: &impl domain::todo::driven_ports::TaskReader,
+    ) -> Result<Option<UserWithTaskSummary>, UserError> {
+        domain::access::authorize(caller, Action::ViewUser(user_id))?;
+
+        let Some(user) = u_reader
+            .by_id(user_id, &mut *ext_cxn)
+            .await
+            .context("Fetching user by ID")?
+        else {
+            return Ok(None);
+        };
+
+        let tasks = task_read
+            .task_summary_for_user(user_id, &mut *ext_cxn)
+            .await
+            .context("Summarizing tasks for a user")?;
+
+        Ok(Some(UserWithTaskSummary { user, tasks }))
+    }
+
+    async fn search_users(
+        &self,
+        caller: &Caller,
+        name: &str,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_reader: &impl driven_ports::UserReader,
+    ) -> Result<Vec<TodoUser>, UserError> {
+        // Collapse runs of whitespace so "John   D" matches the same users as "John D"
+        let normalized_name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        if normalized_name.is_empty() {
+            return self.get_users(caller, ext_cxn, u_reader).await;
+        }
+        domain::access::authorize(caller, Action::ListUsers)?;
+
+        let search_result = u_reader.search_by_name(&normalized_name, ext_cxn).await;
+        if let Err(ref port_err) = search_result {
+            log::error!("User search failure: {port_err}");
+        }
+
+        Ok(search_result.context("Failed searching users by name")?)
+    }
+
+    async fn set_user_role(
+        &self,
+        caller: &Caller,
+        user_id: i32,
+        role: Role,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_reader: &impl driven_ports::UserReader,
+        u_writer: &impl driven_ports::UserWriter,
+    ) -> Result<(), UserError> {
+        domain::access::authorize(caller, Action::ChangeRoles)?;
+
+        let user = u_reader
+            .by_id(user_id, &mut *ext_cxn)
+            .await
+            .context("Fetching user whose role is changing")?
+            .ok_or(UserError::UserDoesNotExist)?;
+        if user.role == role {
+            return Ok(());
+        }
+        if user.role == Role::Admin {
+            let admin_count = u_reader
+                .admin_count(&mut *ext_cxn)
+                .await
+                .context("Counting admins before removing one")?;
+            if admin_count <= 1 {
+                return Err(UserError::LastAdmin);
+            }
+        }
+
+        let user_existed = u_writer
+            .set_role(user_id, role, &mut *ext_cxn)
+            .await
+            .context("Changing a user's role")?;
+        if !user_existed {
+            return Err(UserError::UserDoesNotExist);
+        }
+
+        Ok(())
+    }
+
+    async fn bootstrap_admin(
+        &self,
+        admin: &CreateUser,
+        ext_cxn: &mut impl ExternalConnectivity,
+        u_reader: &impl driven_ports::UserReader,
+        u_writer: &impl driven_ports::UserWriter,
+        u_detect: &impl driven_ports::DetectUser,
+        event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
+    ) -> Result<AdminBootstrap, anyhow::Error> {
+        let admin_count = u_reader
+            .admin_count(&mut *ext_cxn)
+            .await
+            .context("Counting admins during bootstrap")?;
+        if admin_count > 0 {
+            return Ok(AdminBootstrap::AdminAlreadyExists);
+        }
+
+        let description = driven_ports::UserDescription {
+            first_name: &admin.first_name,
+            last_name: &admin.last_name,
+        };
+        let existing_user = u_reader
+            .by_name(description, &mut *ext_cxn)
+            .await
+            .context("Looking up the user to make an admin")?;
+        let (admin_id, bootstrap) = match existing_user {
+            Some(user) => (user.id, AdminBootstrap::Promoted(user.id)),
+            None => {
+                let user_id = self
+                    .create_user(admin, &mut *ext_cxn, u_writer, u_detect, event_outbox)
+                    .await
+                    .context("Creating the first admin")?;
+                (user_id, AdminBootstrap::Created(user_id))
+            }
+        };
+        u_writer
+            .set_role(admin_id, Role::Admin, &mut *ext_cxn)
+            .await
+            .context("Making the first admin")?;
+
+        Ok(bootstrap)
     }
 
     async fn create_user(
@@ -173,6 +493,7 @@ impl driving_ports::UserPort for UserService {
         ext_cxn: &mut impl ExternalConnectivity,
         u_writer: &impl driven_ports::UserWriter,
         u_detect: &impl driven_ports::DetectUser,
+        event_outbox: &impl domain::webhook::driven_ports::EventOutbox,
     ) -> Result<i32, CreateUserError> {
         let description = driven_ports::UserDescription {
             first_name: &new_user.first_name,
@@ -187,10 +508,23 @@ impl driving_ports::UserPort for UserService {
             return Err(CreateUserError::UserAlreadyExists);
         }
 
-        Ok(u_writer
-            .create_user(new_user, ext_cxn)
+        let user_id = u_writer
+            .create_user(new_user, &mut *ext_cxn)
+            .await
+            .context("Trying to create user at service level")?;
+        event_outbox
+            .record_event(
+                &domain::webhook::WebhookEvent::UserCreated {
+                    user_id,
+                    first_name: new_user.first_name.clone(),
+                    last_name: new_user.last_name.clone(),
+                },
+                &mut *ext_cxn,
+            )
             .await
-            .context("Trying to create user at service level")?)
+            .context("Recording user creation")?;
+
+        Ok(user_id)
     }
 }
 
@@ -200,6 +534,8 @@ mod tests {
     use crate::domain::test_util::Connectivity;
     use crate::domain::user::driven_ports::UserWriter;
     use crate::domain::user::driving_ports::UserPort;
+    use crate::domain::webhook::test_util::InMemoryWebhookPersistence;
+    use crate::domain::webhook::WebhookEvent;
     use crate::external_connections;
     use speculoos::prelude::*;
     use std::sync::RwLock;
@@ -277,7 +613,9 @@ mod tests {
                 let locked_user_data = RwLock::new(user_data);
                 let user_service = UserService {};
 
-                let users_result = user_service.get_users(&mut db_cxn, &locked_user_data).await;
+                let users_result = user_service
+                    .get_users(&Caller::Trusted, &mut db_cxn, &locked_user_data)
+                    .await;
                 let fetched_users = match users_result {
                     Ok(users) => users,
                     Err(error) => panic!("Should have fetched users but failed: {}", error),
@@ -289,16 +627,19 @@ mod tests {
                             id: 1,
                             first_name: fn1,
                             last_name: ln1,
+                            ..
                         },
                         TodoUser {
                             id: 2,
                             first_name: fn2,
                             last_name: ln2,
+                            ..
                         },
                         TodoUser {
                             id: 3,
                             first_name: fn3,
-                            last_name: ln3
+                            last_name: ln3,
+                            ..
                         }
                     ] if fn1 == "John" &&
                         ln1 == "Doe" &&
@@ -318,11 +659,182 @@ mod tests {
                 let locked_user_data = RwLock::new(user_data);
                 let user_service = UserService {};
 
-                let get_result = user_service.get_users(&mut db_cxn, &locked_user_data).await;
+                let get_result = user_service
+                    .get_users(&Caller::Trusted, &mut db_cxn, &locked_user_data)
+                    .await;
                 assert_that!(get_result).is_err();
             }
         }
 
+        mod get_user {
+            use super::*;
+            use crate::domain::todo::test_util::{InMemoryUserTaskPersistence, NewTaskWithOwner};
+            use crate::domain::todo::{NewTask, TaskSummary};
+
+            #[tokio::test]
+            async fn happy_path() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = RwLock::new(test_util::InMemoryUserPersistence::new_with_users(&[
+                    test_util::user_create_default(),
+                ]));
+                let mut raw_task_data = InMemoryUserTaskPersistence::new_with_tasks(&[
+                    NewTaskWithOwner {
+                        owner: 1,
+                        task: NewTask {
+                            description: "abcde".to_owned(),
+                            due_date: None,
+                            recurrence: None,
+                            assignee_user_id: None,
+                        },
+                    },
+                    NewTaskWithOwner {
+                        owner: 1,
+                        task: NewTask {
+                            description: "fghij".to_owned(),
+                            due_date: None,
+                            recurrence: None,
+                            assignee_user_id: None,
+                        },
+                    },
+                    NewTaskWithOwner {
+                        owner: 2,
+                        task: NewTask {
+                            description: "klmno".to_owned(),
+                            due_date: None,
+                            recurrence: None,
+                            assignee_user_id: None,
+                        },
+                    },
+                ]);
+                raw_task_data.tasks[1].completed = true;
+                let task_data = RwLock::new(raw_task_data);
+
+                let user_result = UserService {}
+                    .get_user(&Caller::Trusted, 1, &mut db_cxn, &user_data, &task_data)
+                    .await;
+                assert_that!(user_result)
+                    .is_ok()
+                    .is_some()
+                    .matches(|found| {
+                        matches!(
+                            found,
+                            UserWithTaskSummary {
+                                user: TodoUser { id: 1, .. },
+                                tasks: TaskSummary {
+                                    total: 2,
+                                    completed: 1,
+                                    overdue: 0,
+                                },
+                            }
+                        )
+                    });
+            }
+
+            #[tokio::test]
+            async fn returns_none_for_missing_user() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = test_util::InMemoryUserPersistence::new_locked();
+                let task_data = InMemoryUserTaskPersistence::new_locked();
+
+                let user_result = UserService {}
+                    .get_user(&Caller::Trusted, 1, &mut db_cxn, &user_data, &task_data)
+                    .await;
+                assert_that!(user_result).is_ok().is_none();
+            }
+
+            #[tokio::test]
+            async fn propagates_error() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = RwLock::new(test_util::InMemoryUserPersistence::new_with_users(&[
+                    test_util::user_create_default(),
+                ]));
+                let mut raw_task_data = InMemoryUserTaskPersistence::new();
+                raw_task_data.connected = Connectivity::Disconnected;
+                let task_data = RwLock::new(raw_task_data);
+
+                let user_result = UserService {}
+                    .get_user(&Caller::Trusted, 1, &mut db_cxn, &user_data, &task_data)
+                    .await;
+                assert_that!(user_result).is_err();
+            }
+        }
+
+        mod search_users {
+            use super::*;
+
+            fn user_data() -> RwLock<test_util::InMemoryUserPersistence> {
+                RwLock::new(test_util::InMemoryUserPersistence::new_with_users(&[
+                    CreateUser {
+                        first_name: "John".to_owned(),
+                        last_name: "Doe".to_owned(),
+                    },
+                    CreateUser {
+                        first_name: "Jane".to_owned(),
+                        last_name: "Johnson".to_owned(),
+                    },
+                    CreateUser {
+                        first_name: "Jeff".to_owned(),
+                        last_name: "Smith".to_owned(),
+                    },
+                ]))
+            }
+
+            #[tokio::test]
+            async fn matches_first_or_last_name_prefix() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = user_data();
+
+                let search_result = UserService {}
+                    .search_users(&Caller::Trusted, "jo", &mut db_cxn, &user_data)
+                    .await;
+                assert_that!(search_result).is_ok().matches(|users| {
+                    matches!(
+                        users.as_slice(),
+                        [TodoUser { id: 1, .. }, TodoUser { id: 2, .. }]
+                    )
+                });
+            }
+
+            #[tokio::test]
+            async fn normalizes_whitespace_in_full_name() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = user_data();
+
+                let search_result = UserService {}
+                    .search_users(&Caller::Trusted, "  john   d ", &mut db_cxn, &user_data)
+                    .await;
+                assert_that!(search_result)
+                    .is_ok()
+                    .matches(|users| matches!(users.as_slice(), [TodoUser { id: 1, .. }]));
+            }
+
+            #[tokio::test]
+            async fn blank_name_returns_everyone() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = user_data();
+
+                let search_result = UserService {}
+                    .search_users(&Caller::Trusted, "  ", &mut db_cxn, &user_data)
+                    .await;
+                assert_that!(search_result)
+                    .is_ok()
+                    .matches(|users| users.len() == 3);
+            }
+
+            #[tokio::test]
+            async fn propagates_error() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let mut user_data = test_util::InMemoryUserPersistence::new();
+                user_data.connectivity = Connectivity::Disconnected;
+                let locked_user_data = RwLock::new(user_data);
+
+                let search_result = UserService {}
+                    .search_users(&Caller::Trusted, "jo", &mut db_cxn, &locked_user_data)
+                    .await;
+                assert_that!(search_result).is_err();
+            }
+        }
+
         mod create_user {
             use super::*;
 
@@ -333,10 +845,22 @@ mod tests {
                 let user_service = UserService {};
                 let new_user = test_util::user_create_default();
 
+                let webhooks = InMemoryWebhookPersistence::new_locked();
+
                 let create_result = user_service
-                    .create_user(&new_user, &mut db_cxn, &user_data, &user_data)
+                    .create_user(&new_user, &mut db_cxn, &user_data, &user_data, &webhooks)
                     .await;
-                assert_that!(create_result).is_ok();
+                assert_that!(create_result).is_ok_containing(1);
+
+                let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+                assert_eq!(
+                    vec![WebhookEvent::UserCreated {
+                        user_id: 1,
+                        first_name: new_user.first_name,
+                        last_name: new_user.last_name,
+                    }],
+                    locked_webhooks.events
+                );
             }
 
             #[tokio::test]
@@ -354,8 +878,16 @@ mod tests {
                     last_name: "Rittenhouse".to_owned(),
                 };
 
+                let webhooks = InMemoryWebhookPersistence::new_locked();
+
                 let create_result = user_service
-                    .create_user(&new_user, &mut db_cxn, &locked_user_data, &locked_user_data)
+                    .create_user(
+                        &new_user,
+                        &mut db_cxn,
+                        &locked_user_data,
+                        &locked_user_data,
+                        &webhooks,
+                    )
                     .await;
                 let returned_error = match create_result {
                     Err(error) => error,
@@ -368,6 +900,9 @@ mod tests {
 
                 assert_that!(returned_error)
                     .matches(|err| matches!(err, CreateUserError::UserAlreadyExists));
+
+                let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+                assert_that!(locked_webhooks.events).is_empty();
             }
 
             #[tokio::test]
@@ -379,14 +914,309 @@ mod tests {
                 let user_service = UserService {};
                 let new_user = test_util::user_create_default();
 
+                let webhooks = InMemoryWebhookPersistence::new_locked();
+
                 let create_result = user_service
-                    .create_user(&new_user, &mut db_cxn, &locked_user_data, &locked_user_data)
+                    .create_user(
+                        &new_user,
+                        &mut db_cxn,
+                        &locked_user_data,
+                        &locked_user_data,
+                        &webhooks,
+                    )
                     .await;
                 assert_that!(create_result)
                     .is_err()
                     .matches(|err| matches!(err, CreateUserError::PortError(_)));
             }
         }
+
+        /// Users named John, Jane and Jeff Doe, where Jane is an admin
+        fn users_with_admin() -> RwLock<test_util::InMemoryUserPersistence> {
+            let mut user_data = test_util::InMemoryUserPersistence::new_with_users(&[
+                CreateUser {
+                    first_name: "John".to_owned(),
+                    last_name: "Doe".to_owned(),
+                },
+                CreateUser {
+                    first_name: "Jane".to_owned(),
+                    last_name: "Doe".to_owned(),
+                },
+                CreateUser {
+                    first_name: "Jeff".to_owned(),
+                    last_name: "Doe".to_owned(),
+                },
+            ]);
+            user_data.created_users[1].role = Role::Admin;
+
+            RwLock::new(user_data)
+        }
+
+        mod identify_caller {
+            use super::*;
+
+            #[tokio::test]
+            async fn uses_role_of_user() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = users_with_admin();
+
+                for (user_id, role) in [(1, Role::User), (2, Role::Admin)] {
+                    let caller_result = UserService
+                        .identify_caller(Some(user_id), &mut db_cxn, &user_data)
+                        .await;
+                    assert_that!(caller_result).is_ok_containing(Caller::User { user_id, role });
+                }
+            }
+
+            #[tokio::test]
+            async fn unknown_users_are_anonymous() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = users_with_admin();
+
+                for user_id in [None, Some(10)] {
+                    let caller_result = UserService
+                        .identify_caller(user_id, &mut db_cxn, &user_data)
+                        .await;
+                    assert_that!(caller_result).is_ok_containing(Caller::Anonymous);
+                }
+            }
+        }
+
+        mod access_policy {
+            use super::*;
+
+            #[tokio::test]
+            async fn only_admins_list_users() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = users_with_admin();
+                let user = Caller::User {
+                    user_id: 1,
+                    role: Role::User,
+                };
+                let admin = Caller::User {
+                    user_id: 2,
+                    role: Role::Admin,
+                };
+
+                let users_result = UserService.get_users(&user, &mut db_cxn, &user_data).await;
+                assert_that!(users_result)
+                    .is_err()
+                    .matches(|err| matches!(err, UserError::Forbidden(_)));
+                let search_result = UserService
+                    .search_users(&user, "j", &mut db_cxn, &user_data)
+                    .await;
+                assert_that!(search_result)
+                    .is_err()
+                    .matches(|err| matches!(err, UserError::Forbidden(_)));
+
+                let users_result = UserService.get_users(&admin, &mut db_cxn, &user_data).await;
+                assert_that!(users_result)
+                    .is_ok()
+                    .matches(|users| users.len() == 3);
+            }
+
+            #[tokio::test]
+            async fn users_only_view_themselves() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = users_with_admin();
+                let task_data =
+                    crate::domain::todo::test_util::InMemoryUserTaskPersistence::new_locked();
+                let user = Caller::User {
+                    user_id: 1,
+                    role: Role::User,
+                };
+
+                let own_result = UserService
+                    .get_user(&user, 1, &mut db_cxn, &user_data, &task_data)
+                    .await;
+                assert_that!(own_result).is_ok().is_some();
+
+                let other_result = UserService
+                    .get_user(&user, 2, &mut db_cxn, &user_data, &task_data)
+                    .await;
+                assert_that!(other_result)
+                    .is_err()
+                    .matches(|err| matches!(err, UserError::Forbidden(_)));
+            }
+        }
+
+        mod set_user_role {
+            use super::*;
+
+            fn admin() -> Caller {
+                Caller::User {
+                    user_id: 2,
+                    role: Role::Admin,
+                }
+            }
+
+            #[tokio::test]
+            async fn happy_path() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = users_with_admin();
+
+                let promote_result = UserService
+                    .set_user_role(
+                        &admin(),
+                        1,
+                        Role::Admin,
+                        &mut db_cxn,
+                        &user_data,
+                        &user_data,
+                    )
+                    .await;
+                assert_that!(promote_result).is_ok();
+
+                // With two admins, one of them can stop being an admin
+                let demote_result = UserService
+                    .set_user_role(&admin(), 2, Role::User, &mut db_cxn, &user_data, &user_data)
+                    .await;
+                assert_that!(demote_result).is_ok();
+
+                let locked_users = user_data.read().expect("user rwlock poisoned");
+                let roles: Vec<Role> = locked_users
+                    .created_users
+                    .iter()
+                    .map(|user| user.role)
+                    .collect();
+                assert_eq!(vec![Role::Admin, Role::User, Role::User], roles);
+            }
+
+            #[tokio::test]
+            async fn keeps_last_admin() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = users_with_admin();
+
+                let demote_result = UserService
+                    .set_user_role(&admin(), 2, Role::User, &mut db_cxn, &user_data, &user_data)
+                    .await;
+                assert_that!(demote_result)
+                    .is_err()
+                    .matches(|err| matches!(err, UserError::LastAdmin));
+            }
+
+            #[tokio::test]
+            async fn fails_if_user_doesnt_exist() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = users_with_admin();
+
+                let promote_result = UserService
+                    .set_user_role(
+                        &admin(),
+                        10,
+                        Role::Admin,
+                        &mut db_cxn,
+                        &user_data,
+                        &user_data,
+                    )
+                    .await;
+                assert_that!(promote_result)
+                    .is_err()
+                    .matches(|err| matches!(err, UserError::UserDoesNotExist));
+            }
+
+            #[tokio::test]
+            async fn only_admins_change_roles() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = users_with_admin();
+                let user = Caller::User {
+                    user_id: 1,
+                    role: Role::User,
+                };
+
+                let promote_result = UserService
+                    .set_user_role(&user, 1, Role::Admin, &mut db_cxn, &user_data, &user_data)
+                    .await;
+                assert_that!(promote_result)
+                    .is_err()
+                    .matches(|err| matches!(err, UserError::Forbidden(_)));
+
+                let locked_users = user_data.read().expect("user rwlock poisoned");
+                assert_eq!(Role::User, locked_users.created_users[0].role);
+            }
+        }
+
+        mod bootstrap_admin {
+            use super::*;
+
+            fn admin_description() -> CreateUser {
+                CreateUser {
+                    first_name: "Jeff".to_owned(),
+                    last_name: "Doe".to_owned(),
+                }
+            }
+
+            #[tokio::test]
+            async fn creates_missing_admin() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = test_util::InMemoryUserPersistence::new_locked();
+                let webhooks = InMemoryWebhookPersistence::new_locked();
+
+                let bootstrap_result = UserService
+                    .bootstrap_admin(
+                        &admin_description(),
+                        &mut db_cxn,
+                        &user_data,
+                        &user_data,
+                        &user_data,
+                        &webhooks,
+                    )
+                    .await;
+                assert_that!(bootstrap_result).is_ok_containing(AdminBootstrap::Created(1));
+
+                let locked_users = user_data.read().expect("user rwlock poisoned");
+                assert_eq!(Role::Admin, locked_users.created_users[0].role);
+                let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+                assert_eq!(1, locked_webhooks.events.len());
+            }
+
+            #[tokio::test]
+            async fn promotes_existing_user() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = RwLock::new(test_util::InMemoryUserPersistence::new_with_users(&[
+                    test_util::user_create_default(),
+                    admin_description(),
+                ]));
+                let webhooks = InMemoryWebhookPersistence::new_locked();
+
+                let bootstrap_result = UserService
+                    .bootstrap_admin(
+                        &admin_description(),
+                        &mut db_cxn,
+                        &user_data,
+                        &user_data,
+                        &user_data,
+                        &webhooks,
+                    )
+                    .await;
+                assert_that!(bootstrap_result).is_ok_containing(AdminBootstrap::Promoted(2));
+
+                let locked_users = user_data.read().expect("user rwlock poisoned");
+                assert_eq!(Role::Admin, locked_users.created_users[1].role);
+            }
+
+            #[tokio::test]
+            async fn does_nothing_once_there_is_an_admin() {
+                let mut db_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+                let user_data = users_with_admin();
+                let webhooks = InMemoryWebhookPersistence::new_locked();
+
+                let bootstrap_result = UserService
+                    .bootstrap_admin(
+                        &admin_description(),
+                        &mut db_cxn,
+                        &user_data,
+                        &user_data,
+                        &user_data,
+                        &webhooks,
+                    )
+                    .await;
+                assert_that!(bootstrap_result).is_ok_containing(AdminBootstrap::AdminAlreadyExists);
+
+                let locked_users = user_data.read().expect("user rwlock poisoned");
+                assert_eq!(Role::User, locked_users.created_users[2].role);
+            }
+        }
     }
 }
 
@@ -429,6 +1259,7 @@ pub mod test_util {
                         id: (index + 1) as i32,
                         first_name: user_info.first_name.clone(),
                         last_name: user_info.last_name.clone(),
+                        role: Role::User,
                     })
                     .collect(),
                 connectivity: Connectivity::Connected,
@@ -457,10 +1288,33 @@ pub mod test_util {
                 id,
                 first_name: user.first_name.clone(),
                 last_name: user.last_name.clone(),
+                role: Role::User,
             });
 
             Ok(persister.highest_user_id)
         }
+
+        async fn set_role(
+            &self,
+            user_id: i32,
+            role: Role,
+            _: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error> {
+            let mut persister = self.write().expect("user write rwlock poisoned");
+            persister.connectivity.blow_up_if_disconnected()?;
+
+            let user = persister
+                .created_users
+                .iter_mut()
+                .find(|user| user.id == user_id);
+            match user {
+                Some(user) => {
+                    user.role = role;
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
     }
 
     impl driven_ports::UserReader for RwLock<InMemoryUserPersistence> {
@@ -478,6 +1332,7 @@ pub mod test_util {
                     id: user.id,
                     first_name: user.first_name.clone(),
                     last_name: user.last_name.clone(),
+                    role: user.role,
                 })
                 .collect())
         }
@@ -496,10 +1351,66 @@ pub mod test_util {
                     id: user.id,
                     first_name: user.first_name.clone(),
                     last_name: user.last_name.clone(),
+                    role: user.role,
                 })),
                 None => Ok(None),
             }
         }
+
+        async fn search_by_name(
+            &self,
+            name_prefix: &str,
+            _: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<TodoUser>, anyhow::Error> {
+            let persister = self.read().expect("user read rwlock poisoned");
+            persister.connectivity.blow_up_if_disconnected()?;
+
+            // Only ignores case, since accent folding is left to the real database
+            let name_prefix = name_prefix.to_lowercase();
+            Ok(persister
+                .created_users
+                .iter()
+                .filter(|user| {
+                    let full_name =
+                        format!("{} {}", user.first_name, user.last_name).to_lowercase();
+                    full_name.starts_with(&name_prefix)
+                        || full_name.contains(&format!(" {name_prefix}"))
+                })
+                .cloned()
+                .collect())
+        }
+
+        async fn by_name<'names>(
+            &self,
+            description: UserDescription<'names>,
+            _: &mut impl ExternalConnectivity,
+        ) -> Result<Option<TodoUser>, anyhow::Error> {
+            let persister = self.read().expect("user read rwlock poisoned");
+            persister.connectivity.blow_up_if_disconnected()?;
+
+            Ok(persister
+                .created_users
+                .iter()
+                .find(|user| {
+                    user.first_name == description.first_name
+                        && user.last_name == description.last_name
+                })
+                .cloned())
+        }
+
+        async fn admin_count(
+            &self,
+            _: &mut impl ExternalConnectivity,
+        ) -> Result<i64, anyhow::Error> {
+            let persister = self.read().expect("user read rwlock poisoned");
+            persister.connectivity.blow_up_if_disconnected()?;
+
+            Ok(persister
+                .created_users
+                .iter()
+                .filter(|user| user.role == Role::Admin)
+                .count() as i64)
+        }
     }
 
     /// Creates a new CreateUser payload
@@ -510,6 +1421,22 @@ pub mod test_util {
         }
     }
 
+    /// Creates a fake holding two users, with the IDs 1 and 2
+    pub fn two_users() -> RwLock<InMemoryUserPersistence> {
+        RwLock::new(InMemoryUserPersistence::new_with_users(&[
+            user_create_default(),
+            user_create_default(),
+        ]))
+    }
+
+    /// Describes a regular user calling on their own behalf
+    pub fn as_user(user_id: i32) -> Caller {
+        Caller::User {
+            user_id,
+            role: Role::User,
+        }
+    }
+
     impl DetectUser for RwLock<InMemoryUserPersistence> {
         async fn user_exists(
             &self,
@@ -538,8 +1465,12 @@ pub mod test_util {
 
     /// A mock of UserService for use in API tests
     pub struct MockUserService {
-        pub get_users_response: FakeImplementation<(), Result<Vec<TodoUser>, Error>>,
+        pub get_users_response: FakeImplementation<(), Result<Vec<TodoUser>, UserError>>,
+        pub get_user_response:
+            FakeImplementation<i32, Result<Option<UserWithTaskSummary>, UserError>>,
+        pub search_users_response: FakeImplementation<String, Result<Vec<TodoUser>, UserError>>,
         pub create_user_response: FakeImplementation<CreateUser, Result<i32, CreateUserError>>,
+        pub set_user_role_response: FakeImplementation<(i32, Role), Result<(), UserError>>,
     }
 
     impl MockUserService {
@@ -547,7 +1478,10 @@ pub mod test_util {
         pub fn new() -> MockUserService {
             MockUserService {
                 get_users_response: FakeImplementation::new(),
+                get_user_response: FakeImplementation::new(),
+                search_users_response: FakeImplementation::new(),
                 create_user_response: FakeImplementation::new(),
+                set_user_role_response: FakeImplementation::new(),
             }
         }
 
@@ -563,13 +1497,80 @@ pub mod test_util {
     }
 
     impl UserPort for Mutex<MockUserService> {
+        async fn identify_caller(
+            &self,
+            _: Option<i32>,
+            _: &mut impl ExternalConnectivity,
+            _: &impl UserReader,
+        ) -> Result<Caller, Error> {
+            // Callers are identified before handlers run, so API tests never need this
+            Ok(Caller::Trusted)
+        }
+
         async fn get_users(
             &self,
+            _: &Caller,
             _: &mut impl ExternalConnectivity,
             _: &impl UserReader,
-        ) -> Result<Vec<TodoUser>, Error> {
+        ) -> Result<Vec<TodoUser>, UserError> {
             let locked_self = self.lock().expect("Lock is poisoned!");
-            locked_self.get_users_response.return_value_anyhow()
+            locked_self.get_users_response.return_value_result()
+        }
+
+        async fn get_user(
+            &self,
+            _: &Caller,
+            user_id: i32,
+            _: &mut impl ExternalConnectivity,
+            _: &impl UserReader,
+            _: &impl domain::todo::driven_ports::TaskReader,
+        ) -> Result<Option<UserWithTaskSummary>, UserError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self.get_user_response.save_arguments(user_id);
+            locked_self.get_user_response.return_value_result()
+        }
+
+        async fn search_users(
+            &self,
+            _: &Caller,
+            name: &str,
+            _: &mut impl ExternalConnectivity,
+            _: &impl UserReader,
+        ) -> Result<Vec<TodoUser>, UserError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .search_users_response
+                .save_arguments(name.to_owned());
+            locked_self.search_users_response.return_value_result()
+        }
+
+        async fn set_user_role(
+            &self,
+            _: &Caller,
+            user_id: i32,
+            role: Role,
+            _: &mut impl ExternalConnectivity,
+            _: &impl UserReader,
+            _: &impl UserWriter,
+        ) -> Result<(), UserError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .set_user_role_response
+                .save_arguments((user_id, role));
+            locked_self.set_user_role_response.return_value_result()
+        }
+
+        async fn bootstrap_admin(
+            &self,
+            _: &CreateUser,
+            _: &mut impl ExternalConnectivity,
+            _: &impl UserReader,
+            _: &impl UserWriter,
+            _: &impl DetectUser,
+            _: &impl domain::webhook::driven_ports::EventOutbox,
+        ) -> Result<AdminBootstrap, Error> {
+            // Admins are bootstrapped when the app starts, so API tests never need this
+            Ok(AdminBootstrap::AdminAlreadyExists)
         }
 
         async fn create_user(
@@ -578,6 +1579,7 @@ pub mod test_util {
             _: &mut impl ExternalConnectivity,
             _: &impl UserWriter,
             _: &impl DetectUser,
+            _: &impl domain::webhook::driven_ports::EventOutbox,
         ) -> Result<i32, CreateUserError> {
             let mut locked_self = self.lock().expect("Lock is poisoned!");
             locked_self
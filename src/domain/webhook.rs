@@ -0,0 +1,1115 @@
+use crate::domain::webhook::driven_ports::{
+    DeliveryQueue, SubscriptionReader, SubscriptionWriter, WebhookSender,
+};
+use crate::domain::webhook::driving_ports::WebhookError;
+use crate::external_connections::ExternalConnectivity;
+use anyhow::Context;
+use hmac::{Hmac, Mac};
+use log::{error, warn};
+use serde_json::json;
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kinds of events which can be delivered to webhook subscribers
+pub enum EventType {
+    TaskCreated,
+    TaskUpdated,
+    TaskDeleted,
+    TaskAssigned,
+    UserCreated,
+}
+
+impl EventType {
+    /// The name of the event type as it's sent to subscribers, such as "task.created"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TaskCreated => "task.created",
+            Self::TaskUpdated => "task.updated",
+            Self::TaskDeleted => "task.deleted",
+            Self::TaskAssigned => "task.assigned",
+            Self::UserCreated => "user.created",
+        }
+    }
+
+    /// Looks up an event type by its name, returning [None] if no event type has that name
+    pub fn from_name(name: &str) -> Option<EventType> {
+        match name {
+            "task.created" => Some(Self::TaskCreated),
+            "task.updated" => Some(Self::TaskUpdated),
+            "task.deleted" => Some(Self::TaskDeleted),
+            "task.assigned" => Some(Self::TaskAssigned),
+            "user.created" => Some(Self::UserCreated),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(Clone, PartialEq))]
+/// Something that happened in the system which webhook subscribers may want to hear about
+pub enum WebhookEvent {
+    TaskCreated {
+        task_id: i32,
+        user_id: i32,
+        description: String,
+    },
+    /// Only the fields of the task which changed are provided
+    TaskUpdated {
+        task_id: i32,
+        description: Option<String>,
+        completed: Option<bool>,
+    },
+    TaskDeleted {
+        task_id: i32,
+    },
+    /// A task was given to a different assignee. Either assignee is [None] when the task is unassigned.
+    TaskAssigned {
+        task_id: i32,
+        assignee_user_id: Option<i32>,
+        previous_assignee_user_id: Option<i32>,
+    },
+    UserCreated {
+        user_id: i32,
+        first_name: String,
+        last_name: String,
+    },
+}
+
+impl WebhookEvent {
+    /// The type subscribers use to choose which events they receive
+    pub fn event_type(&self) -> EventType {
+        match self {
+            Self::TaskCreated { .. } => EventType::TaskCreated,
+            Self::TaskUpdated { .. } => EventType::TaskUpdated,
+            Self::TaskDeleted { .. } => EventType::TaskDeleted,
+            Self::TaskAssigned { .. } => EventType::TaskAssigned,
+            Self::UserCreated { .. } => EventType::UserCreated,
+        }
+    }
+
+    /// The event's data as it's sent to subscribers
+    pub fn payload(&self) -> serde_json::Value {
+        match self {
+            Self::TaskCreated {
+                task_id,
+                user_id,
+                description,
+            } => json!({
+                "task_id": task_id,
+                "user_id": user_id,
+                "description": description,
+            }),
+            Self::TaskUpdated {
+                task_id,
+                description,
+                completed,
+            } => {
+                let mut payload = json!({ "task_id": task_id });
+                if let Some(description) = description {
+                    payload["description"] = json!(description);
+                }
+                if let Some(completed) = completed {
+                    payload["completed"] = json!(completed);
+                }
+
+                payload
+            }
+            Self::TaskDeleted { task_id } => json!({ "task_id": task_id }),
+            Self::TaskAssigned {
+                task_id,
+                assignee_user_id,
+                previous_assignee_user_id,
+            } => json!({
+                "task_id": task_id,
+                "assignee_user_id": assignee_user_id,
+                "previous_assignee_user_id": previous_assignee_user_id,
+            }),
+            Self::UserCreated {
+                user_id,
+                first_name,
+                last_name,
+            } => json!({
+                "user_id": user_id,
+                "first_name": first_name,
+                "last_name": last_name,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(Clone))]
+/// A URL which receives the events it subscribed to. The secret used to sign deliveries is never read back.
+pub struct WebhookSubscription {
+    pub id: i32,
+    pub url: String,
+    pub event_types: Vec<EventType>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(Clone, PartialEq, Eq))]
+/// Contains information necessary to register a new webhook subscription
+pub struct NewWebhookSubscription {
+    pub url: String,
+    pub event_types: Vec<EventType>,
+    /// Shared with the subscriber so they can verify deliveries came from us
+    pub secret: String,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(Clone, PartialEq))]
+/// An event waiting to be delivered to a single subscription
+pub struct PendingDelivery {
+    pub id: i64,
+    pub url: String,
+    pub secret: String,
+    pub event_type: EventType,
+    pub payload: serde_json::Value,
+    /// The number of delivery attempts made before this one
+    pub attempts: i32,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(Clone, PartialEq, Eq))]
+/// A signed HTTP POST request which delivers an event to a subscriber
+pub struct WebhookRequest {
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+/// Describes what happened to the deliveries attempted during a dispatch
+pub struct DispatchSummary {
+    pub delivered: usize,
+    pub retrying: usize,
+    /// Deliveries which failed too many times and won't be attempted again
+    pub failed: usize,
+}
+
+/// Header containing the name of the delivered event's type
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+/// Header containing the ID of the delivery, which stays the same across retries
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+/// Header containing the Unix time, in seconds, the delivery was signed at
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+/// Header containing the signature of the delivery produced by [sign_payload]
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// The number of times a delivery is attempted before giving up on it
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 8;
+/// The most deliveries attempted in a single dispatch
+const DELIVERY_BATCH_SIZE: i64 = 20;
+/// How long a claimed delivery is hidden from other dispatchers. If a dispatcher dies mid-delivery,
+/// the delivery is picked up again once this passes.
+const DELIVERY_LEASE: Duration = Duration::from_secs(5 * 60);
+/// The delay before the first retry of a failed delivery. Each later retry waits twice as long.
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// The longest a failed delivery waits before it's retried
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// Signs a delivery so subscribers can verify it came from us and wasn't tampered with.
+/// The signature is an HMAC-SHA256 of `"{timestamp}.{body}"` keyed with the subscription's secret,
+/// formatted as `sha256=<hex digest>`.
+pub fn sign_payload(secret: &str, timestamp: u64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// How long to wait before retrying a delivery which has failed [attempts_made] times
+pub fn retry_delay(attempts_made: i32) -> Duration {
+    let doublings = attempts_made.saturating_sub(1).clamp(0, 16) as u32;
+
+    BASE_RETRY_DELAY
+        .saturating_mul(2u32.pow(doublings))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Builds the signed request which delivers an event to a subscriber
+fn build_request(delivery: &PendingDelivery, timestamp: u64) -> WebhookRequest {
+    let body = json!({
+        "id": delivery.id,
+        "event": delivery.event_type.name(),
+        "data": delivery.payload,
+    })
+    .to_string();
+    let signature = sign_payload(&delivery.secret, timestamp, &body);
+
+    WebhookRequest {
+        url: delivery.url.clone(),
+        headers: vec![
+            (EVENT_HEADER, delivery.event_type.name().to_owned()),
+            (DELIVERY_HEADER, delivery.id.to_string()),
+            (TIMESTAMP_HEADER, timestamp.to_string()),
+            (SIGNATURE_HEADER, signature),
+        ],
+        body,
+    }
+}
+
+/// The set of driven ports invoked by webhook business logic
+pub mod driven_ports {
+    use super::*;
+
+    /// An external system which records events so they can be delivered to subscribers later
+    pub trait EventOutbox: Sync {
+        /// Record an event for delivery to every subscription interested in its type.
+        /// Callers should use the same connection as the change which produced the event
+        /// so the event is only kept if the change is committed.
+        async fn record_event(
+            &self,
+            event: &WebhookEvent,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+    }
+
+    /// An external system which tracks the deliveries recorded by an [EventOutbox]
+    pub trait DeliveryQueue: Sync {
+        /// Claim up to [limit] deliveries which are due, hiding them from other dispatchers for [lease]
+        async fn claim_pending(
+            &self,
+            limit: i64,
+            lease: Duration,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<PendingDelivery>, anyhow::Error>;
+
+        /// Record that a delivery was received by its subscriber
+        async fn mark_delivered(
+            &self,
+            delivery_id: i64,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Record that a delivery attempt failed and it should be attempted again after [retry_in]
+        async fn schedule_retry(
+            &self,
+            delivery_id: i64,
+            error: &str,
+            retry_in: Duration,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+
+        /// Record that a delivery attempt failed and it should not be attempted again
+        async fn mark_failed(
+            &self,
+            delivery_id: i64,
+            error: &str,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error>;
+    }
+
+    /// An external system which can read webhook subscriptions
+    pub trait SubscriptionReader: Sync {
+        /// Retrieve every webhook subscription
+        async fn all(
+            &self,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<WebhookSubscription>, anyhow::Error>;
+    }
+
+    /// An external system which can create and remove webhook subscriptions
+    pub trait SubscriptionWriter: Sync {
+        /// Create a new subscription, returning its ID
+        async fn create_subscription(
+            &self,
+            subscription: &NewWebhookSubscription,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<i32, anyhow::Error>;
+
+        /// Delete a subscription along with its undelivered events, returning false if it didn't exist
+        async fn delete_subscription(
+            &self,
+            subscription_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error>;
+    }
+
+    /// An external system which sends webhook requests to subscribers
+    pub trait WebhookSender: Sync {
+        /// Send the request, returning an error if it couldn't be sent or the subscriber
+        /// didn't respond with a successful status
+        async fn send(&self, request: &WebhookRequest) -> Result<(), anyhow::Error>;
+    }
+}
+
+/// Contains the driving port for managing and delivering webhooks
+pub mod driving_ports {
+    use super::*;
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    /// The set of things that can go wrong while managing webhook subscriptions
+    pub enum WebhookError {
+        #[error("The specified webhook subscription did not exist.")]
+        SubscriptionDoesNotExist,
+        #[error(transparent)]
+        PortError(#[from] anyhow::Error),
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::items_after_test_module)]
+    mod webhook_error_clone {
+        use super::WebhookError;
+        use anyhow::anyhow;
+
+        // Implements clone for WebhookError so it can be used in mocks during API tests
+        impl Clone for WebhookError {
+            fn clone(&self) -> Self {
+                match self {
+                    Self::SubscriptionDoesNotExist => Self::SubscriptionDoesNotExist,
+                    Self::PortError(err) => Self::PortError(anyhow!(format!("{}", err))),
+                }
+            }
+        }
+    }
+
+    /// The driving port which exposes webhook business logic to driving adapters
+    pub trait WebhookPort {
+        /// Retrieve every webhook subscription
+        async fn list_subscriptions(
+            &self,
+            ext_cxn: &mut impl ExternalConnectivity,
+            sub_read: &impl driven_ports::SubscriptionReader,
+        ) -> Result<Vec<WebhookSubscription>, anyhow::Error>;
+
+        /// Register a URL to receive events of the given types
+        async fn create_subscription(
+            &self,
+            subscription: &NewWebhookSubscription,
+            ext_cxn: &mut impl ExternalConnectivity,
+            sub_write: &impl driven_ports::SubscriptionWriter,
+        ) -> Result<i32, anyhow::Error>;
+
+        /// Stop sending events to a subscription
+        async fn delete_subscription(
+            &self,
+            subscription_id: i32,
+            ext_cxn: &mut impl ExternalConnectivity,
+            sub_write: &impl driven_ports::SubscriptionWriter,
+        ) -> Result<(), WebhookError>;
+
+        /// Attempt to deliver a batch of pending events to their subscribers, scheduling failed
+        /// deliveries to be retried with an increasing delay
+        async fn dispatch_pending(
+            &self,
+            ext_cxn: &mut impl ExternalConnectivity,
+            queue: &impl driven_ports::DeliveryQueue,
+            sender: &impl driven_ports::WebhookSender,
+        ) -> Result<DispatchSummary, anyhow::Error>;
+    }
+}
+
+/// Implementation of the driving port for webhooks
+pub struct WebhookService;
+
+impl driving_ports::WebhookPort for WebhookService {
+    async fn list_subscriptions(
+        &self,
+        ext_cxn: &mut impl ExternalConnectivity,
+        sub_read: &impl SubscriptionReader,
+    ) -> Result<Vec<WebhookSubscription>, anyhow::Error> {
+        sub_read
+            .all(&mut *ext_cxn)
+            .await
+            .context("Listing webhook subscriptions")
+    }
+
+    async fn create_subscription(
+        &self,
+        subscription: &NewWebhookSubscription,
+        ext_cxn: &mut impl ExternalConnectivity,
+        sub_write: &impl SubscriptionWriter,
+    ) -> Result<i32, anyhow::Error> {
+        sub_write
+            .create_subscription(subscription, &mut *ext_cxn)
+            .await
+            .context("Creating webhook subscription")
+    }
+
+    async fn delete_subscription(
+        &self,
+        subscription_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+        sub_write: &impl SubscriptionWriter,
+    ) -> Result<(), WebhookError> {
+        let deleted = sub_write
+            .delete_subscription(subscription_id, &mut *ext_cxn)
+            .await
+            .context("Deleting webhook subscription")?;
+
+        if deleted {
+            Ok(())
+        } else {
+            Err(WebhookError::SubscriptionDoesNotExist)
+        }
+    }
+
+    async fn dispatch_pending(
+        &self,
+        ext_cxn: &mut impl ExternalConnectivity,
+        queue: &impl DeliveryQueue,
+        sender: &impl WebhookSender,
+    ) -> Result<DispatchSummary, anyhow::Error> {
+        let deliveries = queue
+            .claim_pending(DELIVERY_BATCH_SIZE, DELIVERY_LEASE, &mut *ext_cxn)
+            .await
+            .context("Claiming pending webhook deliveries")?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_secs())
+            .unwrap_or_default();
+
+        let mut summary = DispatchSummary::default();
+        for delivery in deliveries {
+            let request = build_request(&delivery, timestamp);
+            let send_error = match sender.send(&request).await {
+                Ok(()) => {
+                    summary.delivered += 1;
+                    if let Err(queue_err) = queue.mark_delivered(delivery.id, &mut *ext_cxn).await {
+                        error!(
+                            "Could not mark webhook delivery {} as delivered: {queue_err}",
+                            delivery.id
+                        );
+                    }
+                    continue;
+                }
+                Err(send_err) => format!("{send_err:#}"),
+            };
+
+            let attempts_made = delivery.attempts + 1;
+            let queue_result = if attempts_made >= MAX_DELIVERY_ATTEMPTS {
+                warn!(
+                    "Giving up on webhook delivery {} after {attempts_made} attempts: {send_error}",
+                    delivery.id
+                );
+                summary.failed += 1;
+                queue
+                    .mark_failed(delivery.id, &send_error, &mut *ext_cxn)
+                    .await
+            } else {
+                summary.retrying += 1;
+                queue
+                    .schedule_retry(
+                        delivery.id,
+                        &send_error,
+                        retry_delay(attempts_made),
+                        &mut *ext_cxn,
+                    )
+                    .await
+            };
+            if let Err(queue_err) = queue_result {
+                error!(
+                    "Could not record failure of webhook delivery {}: {queue_err}",
+                    delivery.id
+                );
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::driving_ports::WebhookPort;
+    use super::test_util::*;
+    use super::*;
+    use crate::domain::test_util::Connectivity;
+    use crate::external_connections;
+    use speculoos::prelude::*;
+    use std::sync::RwLock;
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        let signature = sign_payload("Jefe", 1700000000, r#"{"id":1}"#);
+
+        assert_eq!(
+            "sha256=05b728475104591e6ba9301ccd86c0f18c79ea7330ef9b9869435607a745d2bf",
+            signature
+        );
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_a_limit() {
+        assert_eq!(Duration::from_secs(30), retry_delay(1));
+        assert_eq!(Duration::from_secs(60), retry_delay(2));
+        assert_eq!(Duration::from_secs(240), retry_delay(4));
+        assert_eq!(MAX_RETRY_DELAY, retry_delay(20));
+    }
+
+    #[test]
+    fn event_type_names_round_trip() {
+        for event_type in [
+            EventType::TaskCreated,
+            EventType::TaskUpdated,
+            EventType::TaskDeleted,
+            EventType::TaskAssigned,
+            EventType::UserCreated,
+        ] {
+            assert_eq!(Some(event_type), EventType::from_name(event_type.name()));
+        }
+        assert_eq!(None, EventType::from_name("task.exploded"));
+    }
+
+    #[test]
+    fn task_update_payload_only_contains_changes() {
+        let event = WebhookEvent::TaskUpdated {
+            task_id: 3,
+            description: None,
+            completed: Some(true),
+        };
+
+        assert_eq!(json!({ "task_id": 3, "completed": true }), event.payload());
+    }
+
+    #[test]
+    fn task_assignment_payload_includes_unassigned_tasks() {
+        let event = WebhookEvent::TaskAssigned {
+            task_id: 3,
+            assignee_user_id: None,
+            previous_assignee_user_id: Some(2),
+        };
+
+        assert_eq!(
+            json!({ "task_id": 3, "assignee_user_id": null, "previous_assignee_user_id": 2 }),
+            event.payload()
+        );
+    }
+
+    mod delete_subscription {
+        use super::*;
+
+        #[tokio::test]
+        async fn happy_path() {
+            let webhooks = RwLock::new(InMemoryWebhookPersistence::new_with_subscriptions(&[
+                subscription_to(&[EventType::TaskCreated]),
+            ]));
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let delete_result = WebhookService
+                .delete_subscription(1, &mut ext_cxn, &webhooks)
+                .await;
+            assert_that!(delete_result).is_ok();
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_that!(locked_webhooks.subscriptions).is_empty();
+        }
+
+        #[tokio::test]
+        async fn returns_error_when_subscription_missing() {
+            let webhooks = InMemoryWebhookPersistence::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let delete_result = WebhookService
+                .delete_subscription(1, &mut ext_cxn, &webhooks)
+                .await;
+            assert!(matches!(
+                delete_result,
+                Err(WebhookError::SubscriptionDoesNotExist)
+            ));
+        }
+    }
+
+    mod dispatch_pending {
+        use super::*;
+
+        fn webhooks_with_task_event() -> RwLock<InMemoryWebhookPersistence> {
+            let mut webhooks = InMemoryWebhookPersistence::new_with_subscriptions(&[
+                subscription_to(&[EventType::TaskCreated]),
+                subscription_to(&[EventType::UserCreated]),
+            ]);
+            webhooks.add_event(WebhookEvent::TaskCreated {
+                task_id: 7,
+                user_id: 1,
+                description: "Something to do".to_owned(),
+            });
+
+            RwLock::new(webhooks)
+        }
+
+        #[tokio::test]
+        async fn delivers_signed_events_to_subscribers() {
+            let webhooks = webhooks_with_task_event();
+            let sender = FakeWebhookSender::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let dispatch_result = WebhookService
+                .dispatch_pending(&mut ext_cxn, &webhooks, &sender)
+                .await;
+            assert_that!(dispatch_result)
+                .is_ok()
+                .is_equal_to(DispatchSummary {
+                    delivered: 1,
+                    ..DispatchSummary::default()
+                });
+
+            let locked_sender = sender.read().expect("sender rwlock poisoned");
+            let [request] = locked_sender.sent.as_slice() else {
+                panic!("Expected one request, got {:#?}", locked_sender.sent);
+            };
+            let header = |name: &str| {
+                request
+                    .headers
+                    .iter()
+                    .find(|(header_name, _)| *header_name == name)
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_else(|| panic!("Missing header {name}"))
+            };
+            let timestamp: u64 = header(TIMESTAMP_HEADER).parse().unwrap();
+            assert_eq!("https://example.com/hook", request.url);
+            assert_eq!("task.created", header(EVENT_HEADER));
+            assert_eq!(
+                sign_payload(SECRET, timestamp, &request.body),
+                header(SIGNATURE_HEADER)
+            );
+            assert_eq!(
+                json!({
+                    "id": 1,
+                    "event": "task.created",
+                    "data": { "task_id": 7, "user_id": 1, "description": "Something to do" },
+                }),
+                serde_json::from_str::<serde_json::Value>(&request.body).unwrap()
+            );
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_eq!(
+                DeliveryState::Delivered,
+                locked_webhooks.deliveries[0].state
+            );
+        }
+
+        #[tokio::test]
+        async fn schedules_retry_when_delivery_fails() {
+            let webhooks = webhooks_with_task_event();
+            let mut raw_sender = FakeWebhookSender::new();
+            raw_sender.connectivity = Connectivity::Disconnected;
+            let sender = RwLock::new(raw_sender);
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let dispatch_result = WebhookService
+                .dispatch_pending(&mut ext_cxn, &webhooks, &sender)
+                .await;
+            assert_that!(dispatch_result)
+                .is_ok()
+                .is_equal_to(DispatchSummary {
+                    retrying: 1,
+                    ..DispatchSummary::default()
+                });
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert_eq!(1, locked_webhooks.deliveries[0].delivery.attempts);
+            assert_eq!(
+                DeliveryState::RetryScheduled(retry_delay(1)),
+                locked_webhooks.deliveries[0].state
+            );
+        }
+
+        #[tokio::test]
+        async fn gives_up_after_max_attempts() {
+            let webhooks = webhooks_with_task_event();
+            {
+                let mut locked_webhooks = webhooks.write().expect("webhook rwlock poisoned");
+                locked_webhooks.deliveries[0].delivery.attempts = MAX_DELIVERY_ATTEMPTS - 1;
+            }
+            let mut raw_sender = FakeWebhookSender::new();
+            raw_sender.connectivity = Connectivity::Disconnected;
+            let sender = RwLock::new(raw_sender);
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let dispatch_result = WebhookService
+                .dispatch_pending(&mut ext_cxn, &webhooks, &sender)
+                .await;
+            assert_that!(dispatch_result)
+                .is_ok()
+                .is_equal_to(DispatchSummary {
+                    failed: 1,
+                    ..DispatchSummary::default()
+                });
+
+            let locked_webhooks = webhooks.read().expect("webhook rwlock poisoned");
+            assert!(matches!(
+                locked_webhooks.deliveries[0].state,
+                DeliveryState::Failed(_)
+            ));
+        }
+
+        #[tokio::test]
+        async fn propagates_queue_error() {
+            let mut raw_webhooks = InMemoryWebhookPersistence::new();
+            raw_webhooks.connectivity = Connectivity::Disconnected;
+            let webhooks = RwLock::new(raw_webhooks);
+            let sender = FakeWebhookSender::new_locked();
+            let mut ext_cxn = external_connections::test_util::FakeExternalConnectivity::new();
+
+            let dispatch_result = WebhookService
+                .dispatch_pending(&mut ext_cxn, &webhooks, &sender)
+                .await;
+            assert_that!(dispatch_result).is_err();
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::driven_ports::*;
+    use super::driving_ports::WebhookPort;
+    use super::*;
+    use crate::domain::test_util::{Connectivity, FakeImplementation};
+    use anyhow::anyhow;
+    use std::sync::{Mutex, RwLock};
+
+    /// The secret used by subscriptions created with [subscription_to]
+    pub const SECRET: &str = "a-very-secret-value";
+
+    /// Creates a subscription for https://example.com/hook which receives the given event types
+    pub fn subscription_to(event_types: &[EventType]) -> NewWebhookSubscription {
+        NewWebhookSubscription {
+            url: "https://example.com/hook".to_owned(),
+            event_types: event_types.to_vec(),
+            secret: SECRET.to_owned(),
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    /// Describes where a delivery in [InMemoryWebhookPersistence] is in its lifecycle
+    pub enum DeliveryState {
+        Pending,
+        Delivered,
+        RetryScheduled(Duration),
+        Failed(String),
+    }
+
+    /// A delivery kept by [InMemoryWebhookPersistence]
+    pub struct InMemoryDelivery {
+        pub delivery: PendingDelivery,
+        pub state: DeliveryState,
+    }
+
+    /// A fake of the webhook driven ports which keeps subscriptions, recorded events, and
+    /// deliveries in memory. Only [DeliveryState::Pending] deliveries are claimed, so retries are never due.
+    pub struct InMemoryWebhookPersistence {
+        pub subscriptions: Vec<(WebhookSubscription, String)>,
+        pub events: Vec<WebhookEvent>,
+        pub deliveries: Vec<InMemoryDelivery>,
+        pub connectivity: Connectivity,
+    }
+
+    impl InMemoryWebhookPersistence {
+        /// Constructor for InMemoryWebhookPersistence
+        pub fn new() -> InMemoryWebhookPersistence {
+            InMemoryWebhookPersistence {
+                subscriptions: Vec::new(),
+                events: Vec::new(),
+                deliveries: Vec::new(),
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for InMemoryWebhookPersistence which starts with a set of subscriptions
+        pub fn new_with_subscriptions(
+            subscriptions: &[NewWebhookSubscription],
+        ) -> InMemoryWebhookPersistence {
+            let mut persistence = Self::new();
+            for subscription in subscriptions {
+                persistence.add_subscription(subscription);
+            }
+
+            persistence
+        }
+
+        /// Constructor for InMemoryWebhookPersistence which wraps it in an RwLock so it can be
+        /// immediately used as a driven port
+        pub fn new_locked() -> RwLock<InMemoryWebhookPersistence> {
+            RwLock::new(Self::new())
+        }
+
+        fn add_subscription(&mut self, subscription: &NewWebhookSubscription) -> i32 {
+            let id = self
+                .subscriptions
+                .last()
+                .map(|(existing, _)| existing.id + 1)
+                .unwrap_or(1);
+            self.subscriptions.push((
+                WebhookSubscription {
+                    id,
+                    url: subscription.url.clone(),
+                    event_types: subscription.event_types.clone(),
+                },
+                subscription.secret.clone(),
+            ));
+
+            id
+        }
+
+        /// Records an event and queues a delivery for each subscription interested in it
+        pub fn add_event(&mut self, event: WebhookEvent) {
+            let event_type = event.event_type();
+            let new_deliveries: Vec<InMemoryDelivery> = self
+                .subscriptions
+                .iter()
+                .filter(|(subscription, _)| subscription.event_types.contains(&event_type))
+                .enumerate()
+                .map(|(idx, (subscription, secret))| InMemoryDelivery {
+                    delivery: PendingDelivery {
+                        id: (self.deliveries.len() + idx + 1) as i64,
+                        url: subscription.url.clone(),
+                        secret: secret.clone(),
+                        event_type,
+                        payload: event.payload(),
+                        attempts: 0,
+                    },
+                    state: DeliveryState::Pending,
+                })
+                .collect();
+
+            self.deliveries.extend(new_deliveries);
+            self.events.push(event);
+        }
+
+        fn delivery_mut(
+            &mut self,
+            delivery_id: i64,
+        ) -> Result<&mut InMemoryDelivery, anyhow::Error> {
+            self.deliveries
+                .iter_mut()
+                .find(|stored| stored.delivery.id == delivery_id)
+                .ok_or_else(|| anyhow!("No delivery with ID {delivery_id}"))
+        }
+    }
+
+    impl EventOutbox for RwLock<InMemoryWebhookPersistence> {
+        async fn record_event(
+            &self,
+            event: &WebhookEvent,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut persistence = self.write().expect("webhook rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            persistence.add_event(event.clone());
+            Ok(())
+        }
+    }
+
+    impl DeliveryQueue for RwLock<InMemoryWebhookPersistence> {
+        async fn claim_pending(
+            &self,
+            limit: i64,
+            _lease: Duration,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<PendingDelivery>, anyhow::Error> {
+            let persistence = self.read().expect("webhook rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            Ok(persistence
+                .deliveries
+                .iter()
+                .filter(|stored| stored.state == DeliveryState::Pending)
+                .take(limit as usize)
+                .map(|stored| stored.delivery.clone())
+                .collect())
+        }
+
+        async fn mark_delivered(
+            &self,
+            delivery_id: i64,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut persistence = self.write().expect("webhook rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            let stored = persistence.delivery_mut(delivery_id)?;
+            stored.delivery.attempts += 1;
+            stored.state = DeliveryState::Delivered;
+            Ok(())
+        }
+
+        async fn schedule_retry(
+            &self,
+            delivery_id: i64,
+            _error: &str,
+            retry_in: Duration,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut persistence = self.write().expect("webhook rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            let stored = persistence.delivery_mut(delivery_id)?;
+            stored.delivery.attempts += 1;
+            stored.state = DeliveryState::RetryScheduled(retry_in);
+            Ok(())
+        }
+
+        async fn mark_failed(
+            &self,
+            delivery_id: i64,
+            error: &str,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<(), anyhow::Error> {
+            let mut persistence = self.write().expect("webhook rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            let stored = persistence.delivery_mut(delivery_id)?;
+            stored.delivery.attempts += 1;
+            stored.state = DeliveryState::Failed(error.to_owned());
+            Ok(())
+        }
+    }
+
+    impl SubscriptionReader for RwLock<InMemoryWebhookPersistence> {
+        async fn all(
+            &self,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<Vec<WebhookSubscription>, anyhow::Error> {
+            let persistence = self.read().expect("webhook rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            Ok(persistence
+                .subscriptions
+                .iter()
+                .map(|(subscription, _)| subscription.clone())
+                .collect())
+        }
+    }
+
+    impl SubscriptionWriter for RwLock<InMemoryWebhookPersistence> {
+        async fn create_subscription(
+            &self,
+            subscription: &NewWebhookSubscription,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<i32, anyhow::Error> {
+            let mut persistence = self.write().expect("webhook rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            Ok(persistence.add_subscription(subscription))
+        }
+
+        async fn delete_subscription(
+            &self,
+            subscription_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+        ) -> Result<bool, anyhow::Error> {
+            let mut persistence = self.write().expect("webhook rwlock poisoned");
+            persistence.connectivity.blow_up_if_disconnected()?;
+
+            let subscription_count = persistence.subscriptions.len();
+            persistence
+                .subscriptions
+                .retain(|(subscription, _)| subscription.id != subscription_id);
+            Ok(persistence.subscriptions.len() < subscription_count)
+        }
+    }
+
+    /// A fake [WebhookSender] which records the requests it's asked to send
+    /// instead of sending them. Every request fails while disconnected.
+    pub struct FakeWebhookSender {
+        pub sent: Vec<WebhookRequest>,
+        pub connectivity: Connectivity,
+    }
+
+    impl FakeWebhookSender {
+        /// Constructor for FakeWebhookSender
+        pub fn new() -> FakeWebhookSender {
+            FakeWebhookSender {
+                sent: Vec::new(),
+                connectivity: Connectivity::Connected,
+            }
+        }
+
+        /// Constructor for FakeWebhookSender which wraps it in an RwLock so it can be
+        /// immediately used as a driven port
+        pub fn new_locked() -> RwLock<FakeWebhookSender> {
+            RwLock::new(Self::new())
+        }
+    }
+
+    impl WebhookSender for RwLock<FakeWebhookSender> {
+        async fn send(&self, request: &WebhookRequest) -> Result<(), anyhow::Error> {
+            let mut sender = self.write().expect("sender rwlock poisoned");
+            sender.connectivity.blow_up_if_disconnected()?;
+
+            sender.sent.push(request.clone());
+            Ok(())
+        }
+    }
+
+    /// A mock of WebhookService for use in API tests
+    pub struct MockWebhookService {
+        pub list_subscriptions_result:
+            FakeImplementation<(), Result<Vec<WebhookSubscription>, anyhow::Error>>,
+        pub create_subscription_result:
+            FakeImplementation<NewWebhookSubscription, Result<i32, anyhow::Error>>,
+        pub delete_subscription_result: FakeImplementation<i32, Result<(), WebhookError>>,
+    }
+
+    impl MockWebhookService {
+        /// Constructor for MockWebhookService
+        pub fn new() -> MockWebhookService {
+            MockWebhookService {
+                list_subscriptions_result: FakeImplementation::new(),
+                create_subscription_result: FakeImplementation::new(),
+                delete_subscription_result: FakeImplementation::new(),
+            }
+        }
+
+        /// Constructs a new MockWebhookService, allowing for configuration of mocks
+        /// in the builder function before the mock is wrapped in a Mutex for use in API tests
+        pub fn build_locked(builder: impl FnOnce(&mut Self)) -> Mutex<Self> {
+            let mut new_svc = Self::new();
+            builder(&mut new_svc);
+
+            Mutex::new(new_svc)
+        }
+    }
+
+    impl WebhookPort for Mutex<MockWebhookService> {
+        async fn list_subscriptions(
+            &self,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _sub_read: &impl SubscriptionReader,
+        ) -> Result<Vec<WebhookSubscription>, anyhow::Error> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self.list_subscriptions_result.save_arguments(());
+            locked_self.list_subscriptions_result.return_value_anyhow()
+        }
+
+        async fn create_subscription(
+            &self,
+            subscription: &NewWebhookSubscription,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _sub_write: &impl SubscriptionWriter,
+        ) -> Result<i32, anyhow::Error> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .create_subscription_result
+                .save_arguments(subscription.clone());
+            locked_self.create_subscription_result.return_value_anyhow()
+        }
+
+        async fn delete_subscription(
+            &self,
+            subscription_id: i32,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _sub_write: &impl SubscriptionWriter,
+        ) -> Result<(), WebhookError> {
+            let mut locked_self = self.lock().expect("Lock is poisoned!");
+            locked_self
+                .delete_subscription_result
+                .save_arguments(subscription_id);
+            locked_self.delete_subscription_result.return_value_result()
+        }
+
+        async fn dispatch_pending(
+            &self,
+            _ext_cxn: &mut impl ExternalConnectivity,
+            _queue: &impl DeliveryQueue,
+            _sender: &impl WebhookSender,
+        ) -> Result<DispatchSummary, anyhow::Error> {
+            Ok(DispatchSummary::default())
+        }
+    }
+}
@@ -1,27 +1,72 @@
 use crate::domain;
+use chrono::{DateTime, NaiveDate, Utc};
 use derive_more::Display;
 use serde::{Deserialize, Serialize};
 use utoipa::openapi::{RefOr, Schema};
-use utoipa::{openapi, OpenApi, ToSchema};
-use validator::{Validate, ValidationErrors};
+use utoipa::{openapi, IntoParams, OpenApi, ToSchema};
+use validator::{Validate, ValidationError, ValidationErrors};
 
 #[derive(OpenApi)]
 #[openapi(components(
     schemas(
         TodoUser,
+        UserDetails,
+        Role,
+        UserRole,
+        TaskSummary,
         NewUser,
         InsertedUser,
         NewTask,
         TodoTask,
+        CompletedTask,
+        TrashedTask,
         UpdateTask,
+        TaskAssignment,
+        NewTaskDependency,
         InsertedTask,
+        TaskSearchResult,
+        FeedToken,
+        SharePermission,
+        NewShare,
+        TaskShare,
+        InsertedShare,
+        SharedTask,
+        NewComment,
+        UpdateComment,
+        TaskComment,
+        TaskAttachment,
+        AttachmentUpload,
+        NewReminder,
+        TaskReminder,
+        DigestPreferences,
+        TaskFileFormat,
+        ImportedTask,
+        ImportedTasks,
+        ImportRowIssue,
+        TaskBatch,
+        BatchMode,
+        TaskBatchOperation,
+        TaskBatchUpdate,
+        TaskBatchResult,
+        TaskBatchOperationResult,
+        WebhookEventType,
+        NewWebhookSubscription,
+        WebhookSubscription,
+        InsertedWebhookSubscription,
+        TaskChange,
+        AuditEntityType,
+        AuditAction,
+        AuditEntry,
         BasicError,
         ExtraInfo,
         ValidationErrorSchema,
     ),
     responses(
         err_resps::BasicError400Validation,
+        err_resps::BasicError403,
         err_resps::BasicError404,
+        err_resps::BasicError422IdempotencyKeyReused,
+        err_resps::BasicError429,
         err_resps::BasicError500,
     ),
 ))]
@@ -38,6 +83,7 @@ pub struct TodoUser {
     pub first_name: String,
     #[schema(example = "Doe")]
     pub last_name: String,
+    pub role: Role,
 }
 
 impl From<domain::user::TodoUser> for TodoUser {
@@ -46,10 +92,107 @@ impl From<domain::user::TodoUser> for TodoUser {
             id: value.id,
             first_name: value.first_name,
             last_name: value.last_name,
+            role: Role::from(value.role),
         }
     }
 }
 
+/// What a user is allowed to do. Admins may see every user and manage anyone's tasks, while other users only
+/// see themselves and manage their own tasks.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    User,
+    Admin,
+}
+
+impl From<domain::user::Role> for Role {
+    fn from(value: domain::user::Role) -> Self {
+        match value {
+            domain::user::Role::User => Role::User,
+            domain::user::Role::Admin => Role::Admin,
+        }
+    }
+}
+
+impl From<Role> for domain::user::Role {
+    fn from(value: Role) -> Self {
+        match value {
+            Role::User => domain::user::Role::User,
+            Role::Admin => domain::user::Role::Admin,
+        }
+    }
+}
+
+/// DTO for changing the role of a user
+#[derive(Deserialize, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct UserRole {
+    pub role: Role,
+}
+
+/// DTO for a single user, including an overview of their tasks
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug))]
+pub struct UserDetails {
+    #[schema(example = 4)]
+    pub id: i32,
+    #[schema(example = "John")]
+    pub first_name: String,
+    #[schema(example = "Doe")]
+    pub last_name: String,
+    pub role: Role,
+    pub task_summary: TaskSummary,
+}
+
+impl From<domain::user::UserWithTaskSummary> for UserDetails {
+    fn from(value: domain::user::UserWithTaskSummary) -> Self {
+        UserDetails {
+            id: value.user.id,
+            first_name: value.user.first_name,
+            last_name: value.user.last_name,
+            role: Role::from(value.user.role),
+            task_summary: TaskSummary::from(value.tasks),
+        }
+    }
+}
+
+/// DTO containing counts of a user's tasks
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug))]
+pub struct TaskSummary {
+    #[schema(example = 12)]
+    pub total: i64,
+    #[schema(example = 5)]
+    pub completed: i64,
+    /// Incomplete tasks whose due date has passed
+    #[schema(example = 2)]
+    pub overdue: i64,
+}
+
+impl From<domain::todo::TaskSummary> for TaskSummary {
+    fn from(value: domain::todo::TaskSummary) -> Self {
+        TaskSummary {
+            total: value.total,
+            completed: value.completed,
+            overdue: value.overdue,
+        }
+    }
+}
+
+/// Query parameters for listing users
+#[derive(Deserialize, Validate, IntoParams, Default)]
+#[into_params(parameter_in = Query)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct UserListParams {
+    /// Only list users whose first name, last name, or full name starts with this text.
+    /// Case and accents are ignored.
+    // Long enough to hold a full name made of the longest possible first and last names
+    #[validate(length(max = 257))]
+    pub name: Option<String>,
+}
+
 /// DTO for creating a new user via the API
 #[derive(Deserialize, Display, Validate, ToSchema)]
 #[display(fmt = "{} {}", "first_name", "last_name")]
@@ -71,27 +214,83 @@ pub struct InsertedUser {
 
 /// DTO for creating a new task via the API
 #[derive(Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_new_task"))]
 #[cfg_attr(test, derive(Serialize))]
 pub struct NewTask {
     #[validate(length(min = 1))]
     pub item_desc: String,
+    /// The day the task should be done by
+    #[schema(example = "2024-03-04")]
+    pub due_date: Option<NaiveDate>,
+    /// An iCalendar RRULE describing how the task repeats, such as `FREQ=WEEKLY;BYDAY=MO`. Requires `due_date`,
+    /// which is the first occurrence. Completing the task creates its next occurrence.
+    #[schema(example = "FREQ=WEEKLY;BYDAY=MO")]
+    #[validate(custom = "validate_recurrence_rule")]
+    pub recurrence: Option<String>,
+    /// The user who should do the task, who doesn't have to be the user creating it. Tasks without an assignee
+    /// are unassigned.
+    #[schema(example = 7)]
+    pub assignee_user_id: Option<i32>,
+}
+
+/// A recurring task needs a due date to count its occurrences from
+fn validate_new_task(new_task: &NewTask) -> Result<(), ValidationError> {
+    if new_task.recurrence.is_some() && new_task.due_date.is_none() {
+        Err(ValidationError::new("recurrence_without_due_date"))
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_recurrence_rule(rule: &str) -> Result<(), ValidationError> {
+    match rule.parse::<domain::todo::recurrence::Recurrence>() {
+        Ok(_) => Ok(()),
+        Err(rule_err) => {
+            let mut error = ValidationError::new("recurrence");
+            error.message = Some(rule_err.to_string().into());
+            Err(error)
+        }
+    }
 }
 
 impl From<NewTask> for domain::todo::NewTask {
     fn from(value: NewTask) -> Self {
         domain::todo::NewTask {
             description: value.item_desc,
+            due_date: value.due_date,
+            // Rules which don't parse are rejected when the DTO is validated
+            recurrence: value.recurrence.and_then(|rule| rule.parse().ok()),
+            assignee_user_id: value.assignee_user_id,
         }
     }
 }
 
 /// DTO for a returned task on the API
 #[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
 pub struct TodoTask {
     #[schema(example = 10)]
     pub id: i32,
     #[schema(example = "Something to do")]
     pub description: String,
+    #[schema(example = false)]
+    pub completed: bool,
+    #[schema(example = "2024-03-04")]
+    pub due_date: Option<NaiveDate>,
+    /// The iCalendar RRULE describing how the task repeats
+    #[schema(example = "FREQ=WEEKLY;BYDAY=MO")]
+    pub recurrence: Option<String>,
+    /// The user who should do the task. Not included if the task is unassigned.
+    #[schema(example = 7)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee_user_id: Option<i32>,
+    /// How many comments have been left on the task
+    #[schema(example = 2)]
+    pub comment_count: i64,
+    /// Whether the task is blocked by another task which hasn't been completed yet. Blocked tasks can't be
+    /// completed.
+    #[schema(example = false)]
+    pub blocked: bool,
 }
 
 impl From<domain::todo::TodoTask> for TodoTask {
@@ -99,6 +298,59 @@ impl From<domain::todo::TodoTask> for TodoTask {
         TodoTask {
             id: value.id,
             description: value.item_desc,
+            completed: value.completed,
+            due_date: value.due_date,
+            recurrence: value.recurrence.map(|recurrence| recurrence.to_string()),
+            assignee_user_id: value.assignee_user_id,
+            comment_count: value.comment_count,
+            blocked: value.blocked,
+        }
+    }
+}
+
+/// DTO describing the outcome of completing a task
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+pub struct CompletedTask {
+    /// The task created for the next occurrence of a recurring task. Not included if the task doesn't recur or
+    /// its recurrence has ended.
+    pub next_occurrence: Option<TodoTask>,
+}
+
+/// The most upcoming occurrences which are previewed if the caller doesn't ask for a number
+pub const DEFAULT_PREVIEWED_OCCURRENCES: usize = 5;
+
+/// Query parameters for previewing a task's upcoming occurrences
+#[derive(Deserialize, Validate, IntoParams, Default)]
+#[into_params(parameter_in = Query)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct OccurrencePreviewParams {
+    /// How many occurrences to list, starting with the task's current due date. Defaults to 5.
+    #[validate(range(min = 1, max = 100))]
+    pub count: Option<usize>,
+}
+
+/// DTO for a task which has been moved to the trash
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+pub struct TrashedTask {
+    #[schema(example = 10)]
+    pub id: i32,
+    #[schema(example = "Something to do")]
+    pub description: String,
+    #[schema(example = false)]
+    pub completed: bool,
+    /// When the task was moved to the trash
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl From<domain::todo::TrashedTask> for TrashedTask {
+    fn from(value: domain::todo::TrashedTask) -> Self {
+        TrashedTask {
+            id: value.task.id,
+            description: value.task.item_desc,
+            completed: value.task.completed,
+            deleted_at: value.deleted_at,
         }
     }
 }
@@ -119,13 +371,930 @@ impl From<UpdateTask> for domain::todo::UpdateTask {
     }
 }
 
+/// DTO for giving a task to a different assignee via the API
+#[derive(Deserialize, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct TaskAssignment {
+    /// The user who should do the task, or null to unassign it
+    #[schema(example = 7)]
+    pub assignee_user_id: Option<i32>,
+}
+
+/// DTO for saying that a task is blocked by another task via the API
+#[derive(Deserialize, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct NewTaskDependency {
+    /// The task which has to be completed first. It must belong to the same user.
+    #[schema(example = 4)]
+    pub blocked_by_task_id: i32,
+}
+
 /// DTO for a newly created task
 #[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
 pub struct InsertedTask {
     #[schema(example = 5)]
     pub id: i32,
 }
 
+/// Query parameters for searching a user's tasks
+#[derive(Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct TaskSearchParams {
+    /// Text to look for in task descriptions. Supports quoted phrases, `or`, and `-` to exclude a word.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 200))]
+    pub q: String,
+}
+
+/// DTO containing a newly issued calendar feed token
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+pub struct FeedToken {
+    /// Passed as the `token` query parameter of the user's calendar feed. It can't be retrieved again.
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")]
+    pub token: String,
+}
+
+/// Query parameters for reading a user's calendar feed
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CalendarFeedParams {
+    /// The user's current feed token
+    pub token: Option<String>,
+}
+
+/// How much a user may do with tasks shared with them. `edit` allows changing, completing and trashing the
+/// tasks as well as seeing them.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[serde(rename_all = "snake_case")]
+pub enum SharePermission {
+    Read,
+    Edit,
+}
+
+impl From<SharePermission> for domain::share::SharePermission {
+    fn from(value: SharePermission) -> Self {
+        match value {
+            SharePermission::Read => domain::share::SharePermission::Read,
+            SharePermission::Edit => domain::share::SharePermission::Edit,
+        }
+    }
+}
+
+impl From<domain::share::SharePermission> for SharePermission {
+    fn from(value: domain::share::SharePermission) -> Self {
+        match value {
+            domain::share::SharePermission::Read => SharePermission::Read,
+            domain::share::SharePermission::Edit => SharePermission::Edit,
+        }
+    }
+}
+
+/// DTO for sharing a user's tasks with another user
+#[derive(Deserialize, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct NewShare {
+    #[schema(example = 7)]
+    pub shared_with_user_id: i32,
+    /// The single task to share. Every task the user has, including ones they create later, is shared if
+    /// this is left out.
+    #[schema(example = 10)]
+    pub task_id: Option<i32>,
+    pub permission: SharePermission,
+}
+
+impl From<NewShare> for domain::share::NewShare {
+    fn from(value: NewShare) -> Self {
+        domain::share::NewShare {
+            shared_with_user_id: value.shared_with_user_id,
+            task_id: value.task_id,
+            permission: domain::share::SharePermission::from(value.permission),
+        }
+    }
+}
+
+/// DTO for access to a user's tasks which they've granted to another user
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+pub struct TaskShare {
+    #[schema(example = 3)]
+    pub id: i32,
+    #[schema(example = 7)]
+    pub shared_with_user_id: i32,
+    /// The single task which is shared, or null if every task is shared
+    #[schema(example = 10)]
+    pub task_id: Option<i32>,
+    pub permission: SharePermission,
+}
+
+impl From<domain::share::TaskShare> for TaskShare {
+    fn from(value: domain::share::TaskShare) -> Self {
+        TaskShare {
+            id: value.id,
+            shared_with_user_id: value.shared_with_user_id,
+            task_id: value.task_id,
+            permission: SharePermission::from(value.permission),
+        }
+    }
+}
+
+/// DTO for a newly granted share
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+pub struct InsertedShare {
+    #[schema(example = 3)]
+    pub id: i32,
+}
+
+/// DTO for a task which another user has shared
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+pub struct SharedTask {
+    pub task: TodoTask,
+    /// The user who owns the task and shared it
+    #[schema(example = 4)]
+    pub owner_user_id: i32,
+    /// The strongest permission granted on the task, whether it was shared on its own or with the rest of
+    /// its owner's tasks
+    pub permission: SharePermission,
+}
+
+impl From<domain::share::SharedTask> for SharedTask {
+    fn from(value: domain::share::SharedTask) -> Self {
+        SharedTask {
+            owner_user_id: value.task.owner_user_id,
+            task: TodoTask::from(value.task),
+            permission: SharePermission::from(value.permission),
+        }
+    }
+}
+
+/// DTO for leaving a comment on a task
+#[derive(Deserialize, Validate, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct NewComment {
+    #[validate(length(min = 1, max = 5000))]
+    #[schema(example = "Can we move this to Friday?")]
+    pub body: String,
+    /// The comment being replied to, which must be on the same task. Leave out to start a new thread.
+    #[schema(example = 12)]
+    pub parent_comment_id: Option<i32>,
+}
+
+impl From<NewComment> for domain::comment::NewComment {
+    fn from(value: NewComment) -> Self {
+        domain::comment::NewComment {
+            body: value.body,
+            parent_comment_id: value.parent_comment_id,
+        }
+    }
+}
+
+/// DTO for editing a comment
+#[derive(Deserialize, Validate, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct UpdateComment {
+    #[validate(length(min = 1, max = 5000))]
+    #[schema(example = "Can we move this to Monday?")]
+    pub body: String,
+}
+
+/// DTO for a comment left on a task
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug, PartialEq, Eq))]
+pub struct TaskComment {
+    #[schema(example = 13)]
+    pub id: i32,
+    #[schema(example = 10)]
+    pub task_id: i32,
+    #[schema(example = 4)]
+    pub author_user_id: i32,
+    /// The comment this one replies to, or null if it starts a thread
+    #[schema(example = 12)]
+    pub parent_comment_id: Option<i32>,
+    #[schema(example = "Can we move this to Friday?")]
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    /// When the comment was last edited, or null if it never has been
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<domain::comment::TaskComment> for TaskComment {
+    fn from(value: domain::comment::TaskComment) -> Self {
+        TaskComment {
+            id: value.id,
+            task_id: value.task_id,
+            author_user_id: value.author_user_id,
+            parent_comment_id: value.parent_comment_id,
+            body: value.body,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+/// Query parameters for paging through the comments on a task
+#[derive(Deserialize, Validate, IntoParams, Default)]
+#[into_params(parameter_in = Query)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct CommentPageParams {
+    /// Only include comments newer than the comment with this ID. Pass the ID of the last comment
+    /// in a page to retrieve the next page.
+    pub after_id: Option<i32>,
+    /// The most comments to return. Defaults to 50.
+    #[validate(range(min = 1, max = 200))]
+    pub limit: Option<i64>,
+}
+
+impl From<CommentPageParams> for domain::comment::CommentPage {
+    fn from(value: CommentPageParams) -> Self {
+        domain::comment::CommentPage {
+            after_id: value.after_id,
+            limit: value
+                .limit
+                .unwrap_or(domain::comment::DEFAULT_COMMENT_PAGE_SIZE),
+        }
+    }
+}
+
+/// DTO for a file attached to a task. Its contents are downloaded separately.
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug, PartialEq, Eq))]
+pub struct TaskAttachment {
+    #[schema(example = 7)]
+    pub id: i32,
+    #[schema(example = 10)]
+    pub task_id: i32,
+    #[schema(example = 4)]
+    pub uploader_user_id: i32,
+    #[schema(example = "receipt.png")]
+    pub file_name: String,
+    #[schema(example = "image/png")]
+    pub content_type: String,
+    #[schema(example = 48213)]
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<domain::attachment::Attachment> for TaskAttachment {
+    fn from(value: domain::attachment::Attachment) -> Self {
+        TaskAttachment {
+            id: value.id,
+            task_id: value.task_id,
+            uploader_user_id: value.uploader_user_id,
+            file_name: value.file_name,
+            content_type: value.content_type,
+            size_bytes: value.size_bytes,
+            created_at: value.created_at,
+        }
+    }
+}
+
+/// The multipart form a file is attached to a task with. Only documents the request, since uploads are
+/// streamed rather than deserialized.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct AttachmentUpload {
+    /// The file to attach. Its name and media type are taken from the part's `Content-Disposition` and
+    /// `Content-Type` headers.
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+/// DTO for setting a reminder on a task via the API
+#[derive(Deserialize, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct NewReminder {
+    /// When to be reminded of the task, which must be in the future
+    #[schema(example = "2024-05-01T09:00:00Z")]
+    pub remind_at: DateTime<Utc>,
+}
+
+/// DTO for a reminder set on a task
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug, PartialEq, Eq))]
+pub struct TaskReminder {
+    #[schema(example = 3)]
+    pub id: i32,
+    #[schema(example = 10)]
+    pub task_id: i32,
+    #[schema(example = 4)]
+    pub user_id: i32,
+    pub remind_at: DateTime<Utc>,
+    /// When the reminder was sent, or null if it hasn't been yet
+    pub sent_at: Option<DateTime<Utc>>,
+    /// When sending the reminder was given up on after repeated failures, or null if it hasn't been
+    pub failed_at: Option<DateTime<Utc>>,
+}
+
+impl From<domain::reminder::TaskReminder> for TaskReminder {
+    fn from(value: domain::reminder::TaskReminder) -> Self {
+        TaskReminder {
+            id: value.id,
+            task_id: value.task_id,
+            user_id: value.user_id,
+            remind_at: value.remind_at,
+            sent_at: value.sent_at,
+            failed_at: value.failed_at,
+        }
+    }
+}
+
+/// DTO for how a user wants to hear about their tasks by email
+#[derive(Serialize, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_digest_preferences"))]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct DigestPreferences {
+    /// The address emails about the user's tasks are sent to
+    #[schema(example = "dana@example.com")]
+    #[validate(email, length(max = 254))]
+    pub email: Option<String>,
+    /// Whether the user is emailed a digest of their open and overdue tasks each morning. Requires `email`.
+    pub daily_digest: bool,
+}
+
+/// A daily digest needs an address to be sent to
+fn validate_digest_preferences(preferences: &DigestPreferences) -> Result<(), ValidationError> {
+    if preferences.daily_digest && preferences.email.is_none() {
+        Err(ValidationError::new("daily_digest_without_email"))
+    } else {
+        Ok(())
+    }
+}
+
+impl From<domain::digest::DigestPreferences> for DigestPreferences {
+    fn from(value: domain::digest::DigestPreferences) -> Self {
+        DigestPreferences {
+            email: value.email,
+            daily_digest: value.daily_digest,
+        }
+    }
+}
+
+impl From<DigestPreferences> for domain::digest::DigestPreferences {
+    fn from(value: DigestPreferences) -> Self {
+        domain::digest::DigestPreferences {
+            email: value.email,
+            daily_digest: value.daily_digest,
+        }
+    }
+}
+
+/// The file formats tasks can be exported to and imported from
+#[derive(Deserialize, Default, Clone, Copy, ToSchema)]
+#[cfg_attr(test, derive(Serialize, Debug, PartialEq, Eq))]
+#[serde(rename_all = "lowercase")]
+pub enum TaskFileFormat {
+    /// A CSV file with a header row naming the columns
+    Csv,
+    /// A JSON array of tasks
+    #[default]
+    Json,
+    /// A todo.txt file, with a task on each line
+    TodoTxt,
+}
+
+/// Query parameters choosing the format of a task export or import
+#[derive(Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TaskFileParams {
+    /// The format of the file. Defaults to JSON.
+    pub format: Option<TaskFileFormat>,
+}
+
+/// The most tasks which may be imported in a single request
+pub const MAX_IMPORTED_TASKS: usize = 5000;
+
+/// DTO for a task in an import. Exported tasks can be imported as they are, since columns which aren't
+/// listed here, such as `id`, are ignored.
+#[derive(Deserialize, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct ImportedTask {
+    #[schema(example = "Something to do")]
+    pub description: String,
+    #[serde(default)]
+    #[schema(example = false)]
+    pub completed: bool,
+    #[schema(example = "2024-03-04")]
+    pub due_date: Option<NaiveDate>,
+    /// An iCalendar RRULE describing how the task repeats. Requires `due_date`.
+    #[schema(example = "FREQ=WEEKLY;BYDAY=MO")]
+    pub recurrence: Option<String>,
+}
+
+impl ImportedTask {
+    /// Checks the task against the same rules as a [NewTask], converting it to a domain task if it passes
+    pub fn validate_into_domain(self) -> Result<domain::todo::ImportedTask, ValidationErrors> {
+        let new_task = NewTask {
+            item_desc: self.description,
+            due_date: self.due_date,
+            recurrence: self.recurrence,
+            assignee_user_id: None,
+        };
+        new_task.validate()?;
+
+        Ok(domain::todo::ImportedTask {
+            task: domain::todo::NewTask::from(new_task),
+            completed: self.completed,
+        })
+    }
+}
+
+/// DTO describing the tasks created by an import
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+pub struct ImportedTasks {
+    /// The IDs of the new tasks, in the same order as the import
+    #[schema(example = json!([11, 12]))]
+    pub task_ids: Vec<i32>,
+}
+
+/// A row of an import which couldn't be imported
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ImportRowIssue {
+    /// The line of the file the row starts on, counting from 1
+    #[schema(example = 3)]
+    pub line: u64,
+    /// What was wrong with the row
+    #[schema(example = "The task breaks the rules for new tasks.")]
+    pub error_description: String,
+    /// Which rules for new tasks the row broke, if it could be read at all
+    pub validation_issues: Option<ValidationErrorSchema>,
+}
+
+/// DTO for a task which matched a search
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+pub struct TaskSearchResult {
+    pub task: TodoTask,
+    /// Relevance of the task to the search. Results are sorted with the highest rank first.
+    #[schema(example = 0.06)]
+    pub rank: f32,
+    /// HTML-escaped excerpt of the task description with matching words wrapped in `<mark>` tags
+    #[schema(example = "<mark>Buy</mark> groceries")]
+    pub snippet: String,
+}
+
+impl From<domain::todo::TaskSearchMatch> for TaskSearchResult {
+    fn from(value: domain::todo::TaskSearchMatch) -> Self {
+        TaskSearchResult {
+            task: TodoTask::from(value.task),
+            rank: value.rank,
+            snippet: value.snippet,
+        }
+    }
+}
+
+/// The most operations which may be submitted in a single task batch
+pub const MAX_BATCH_OPERATIONS: u64 = 100;
+
+/// DTO for applying several task operations for a user in one request
+#[derive(Deserialize, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct TaskBatch {
+    /// How failures of individual operations affect the rest of the batch
+    #[serde(default)]
+    pub mode: BatchMode,
+    /// Operations to apply, in order
+    pub operations: Vec<TaskBatchOperation>,
+}
+
+// Implemented by hand because validator's length check needs to serialize the operations
+impl Validate for TaskBatch {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let operation_count = self.operations.len() as u64;
+        if operation_count == 0 || operation_count > MAX_BATCH_OPERATIONS {
+            let mut error = ValidationError::new("length");
+            error.add_param("min".into(), &1);
+            error.add_param("max".into(), &MAX_BATCH_OPERATIONS);
+            error.add_param("actual".into(), &operation_count);
+            errors.add("operations", error);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Controls how a task batch handles failing operations
+#[derive(Deserialize, Default, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// All operations are applied in one transaction. If any operation fails, none of them are saved.
+    #[default]
+    Atomic,
+    /// Each operation is saved on its own, so failing operations do not prevent others from being saved.
+    BestEffort,
+}
+
+/// A single operation inside a [TaskBatch]
+#[derive(Deserialize, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TaskBatchOperation {
+    Create(NewTask),
+    Update(TaskBatchUpdate),
+    Delete { task_id: i32 },
+    Complete { task_id: i32 },
+}
+
+impl TaskBatchOperation {
+    /// Validates the data carried by the operation, if any
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            TaskBatchOperation::Create(new_task) => new_task.validate(),
+            TaskBatchOperation::Update(update) => update.validate(),
+            TaskBatchOperation::Delete { .. } | TaskBatchOperation::Complete { .. } => Ok(()),
+        }
+    }
+}
+
+impl From<TaskBatchOperation> for domain::todo::BatchOperation {
+    fn from(value: TaskBatchOperation) -> Self {
+        match value {
+            TaskBatchOperation::Create(new_task) => {
+                domain::todo::BatchOperation::Create(new_task.into())
+            }
+            TaskBatchOperation::Update(update) => domain::todo::BatchOperation::Update {
+                task_id: update.task_id,
+                update: domain::todo::UpdateTask {
+                    description: update.description,
+                },
+            },
+            TaskBatchOperation::Delete { task_id } => {
+                domain::todo::BatchOperation::Delete { task_id }
+            }
+            TaskBatchOperation::Complete { task_id } => {
+                domain::todo::BatchOperation::Complete { task_id }
+            }
+        }
+    }
+}
+
+/// DTO for updating the content of a task inside a [TaskBatch]
+#[derive(Deserialize, Validate, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct TaskBatchUpdate {
+    #[schema(example = 5)]
+    pub task_id: i32,
+    #[validate(length(min = 1))]
+    pub description: String,
+}
+
+/// DTO describing what happened to each operation in a [TaskBatch]
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug))]
+pub struct TaskBatchResult {
+    /// True if changes made by the batch were saved
+    pub committed: bool,
+    /// The outcome of each operation, in the order the operations were submitted
+    pub results: Vec<TaskBatchOperationResult>,
+}
+
+/// The outcome of a single operation inside a [TaskBatch]
+#[derive(Serialize, Debug, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TaskBatchOperationResult {
+    /// The operation was applied to the task with the given ID
+    Succeeded { task_id: i32 },
+    /// The operation could not be applied
+    Failed { error: BasicError },
+    /// The operation was applied, but undone because another operation in an atomic batch failed
+    RolledBack,
+    /// The operation was not attempted because another operation in an atomic batch failed
+    Skipped,
+}
+
+/// The types of events a webhook subscription can receive
+#[derive(Serialize, Deserialize, Clone, Copy, ToSchema)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub enum WebhookEventType {
+    #[serde(rename = "task.created")]
+    TaskCreated,
+    #[serde(rename = "task.updated")]
+    TaskUpdated,
+    #[serde(rename = "task.deleted")]
+    TaskDeleted,
+    #[serde(rename = "task.assigned")]
+    TaskAssigned,
+    #[serde(rename = "user.created")]
+    UserCreated,
+}
+
+impl From<WebhookEventType> for domain::webhook::EventType {
+    fn from(value: WebhookEventType) -> Self {
+        match value {
+            WebhookEventType::TaskCreated => domain::webhook::EventType::TaskCreated,
+            WebhookEventType::TaskUpdated => domain::webhook::EventType::TaskUpdated,
+            WebhookEventType::TaskDeleted => domain::webhook::EventType::TaskDeleted,
+            WebhookEventType::TaskAssigned => domain::webhook::EventType::TaskAssigned,
+            WebhookEventType::UserCreated => domain::webhook::EventType::UserCreated,
+        }
+    }
+}
+
+impl From<domain::webhook::EventType> for WebhookEventType {
+    fn from(value: domain::webhook::EventType) -> Self {
+        match value {
+            domain::webhook::EventType::TaskCreated => WebhookEventType::TaskCreated,
+            domain::webhook::EventType::TaskUpdated => WebhookEventType::TaskUpdated,
+            domain::webhook::EventType::TaskDeleted => WebhookEventType::TaskDeleted,
+            domain::webhook::EventType::TaskAssigned => WebhookEventType::TaskAssigned,
+            domain::webhook::EventType::UserCreated => WebhookEventType::UserCreated,
+        }
+    }
+}
+
+/// DTO for registering a URL to receive webhook events
+#[derive(Deserialize, Validate, ToSchema)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct NewWebhookSubscription {
+    /// The HTTP(S) URL events are POSTed to
+    #[schema(example = "https://example.com/hooks/todo")]
+    #[validate(custom = "validate_webhook_url")]
+    pub url: String,
+    /// The types of events sent to the URL
+    #[validate(length(min = 1))]
+    pub event_types: Vec<WebhookEventType>,
+    /// Used to sign deliveries. The `X-Webhook-Signature` header of each delivery contains `sha256=` followed by
+    /// the hex encoded HMAC-SHA256 of `{X-Webhook-Timestamp}.{request body}`, keyed with this secret.
+    #[schema(example = "3f1c0a9e8b7d6c5e4f3a2b1c")]
+    #[validate(length(min = 16, max = 256))]
+    pub secret: String,
+}
+
+/// Only absolute HTTP and HTTPS URLs can receive webhooks
+fn validate_webhook_url(url: &str) -> Result<(), ValidationError> {
+    let is_http = url.starts_with("http://") || url.starts_with("https://");
+    if is_http && validator::validate_url(url) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("url"))
+    }
+}
+
+impl From<NewWebhookSubscription> for domain::webhook::NewWebhookSubscription {
+    fn from(value: NewWebhookSubscription) -> Self {
+        domain::webhook::NewWebhookSubscription {
+            url: value.url,
+            event_types: value.event_types.into_iter().map(Into::into).collect(),
+            secret: value.secret,
+        }
+    }
+}
+
+/// DTO for a webhook subscription. Its secret is never returned.
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug))]
+pub struct WebhookSubscription {
+    #[schema(example = 3)]
+    pub id: i32,
+    #[schema(example = "https://example.com/hooks/todo")]
+    pub url: String,
+    pub event_types: Vec<WebhookEventType>,
+}
+
+impl From<domain::webhook::WebhookSubscription> for WebhookSubscription {
+    fn from(value: domain::webhook::WebhookSubscription) -> Self {
+        WebhookSubscription {
+            id: value.id,
+            url: value.url,
+            event_types: value.event_types.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// DTO containing the ID of a newly registered webhook subscription
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug))]
+pub struct InsertedWebhookSubscription {
+    #[schema(example = 3)]
+    pub id: i32,
+}
+
+/// DTO sent as the data of a task event. The event's type names the kind of change
+/// and its ID can be sent back in the `Last-Event-ID` header to resume the stream.
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug, PartialEq, Eq))]
+pub struct TaskChange {
+    #[schema(example = 10)]
+    pub task_id: i32,
+    /// The task's description after the change. Not included when the task was deleted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Something to do")]
+    pub description: Option<String>,
+    /// Whether the task is completed after the change. Not included when the task was deleted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = false)]
+    pub completed: Option<bool>,
+}
+
+impl From<domain::task_event::TaskChange> for TaskChange {
+    fn from(value: domain::task_event::TaskChange) -> Self {
+        TaskChange {
+            task_id: value.task_id,
+            description: value.description,
+            completed: value.completed,
+        }
+    }
+}
+
+/// DTO for a command sent by a client over a task collaboration socket. The command's operation
+/// uses the same format as an operation in a [TaskBatch].
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct TaskSocketCommand {
+    /// Chosen by the client and sent back with the command's result so the two can be matched up
+    pub request_id: String,
+    #[serde(flatten)]
+    pub operation: TaskBatchOperation,
+}
+
+/// DTO for a message sent to a client over a task collaboration socket
+#[derive(Serialize)]
+#[cfg_attr(test, derive(Deserialize, Debug))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskSocketMessage {
+    /// A change made to one of the user's tasks by any client, including the one receiving it
+    Change {
+        id: i64,
+        event: String,
+        change: TaskChange,
+    },
+    /// The outcome of a command sent by the client. `request_id` is null if the command couldn't be read.
+    #[serde(rename = "result")]
+    CommandResult {
+        request_id: Option<String>,
+        result: TaskBatchOperationResult,
+    },
+}
+
+impl From<domain::task_event::TaskChange> for TaskSocketMessage {
+    fn from(value: domain::task_event::TaskChange) -> Self {
+        TaskSocketMessage::Change {
+            id: value.id,
+            event: value.kind.name().to_owned(),
+            change: TaskChange::from(value),
+        }
+    }
+}
+
+/// The kinds of entities whose changes are recorded in the audit log
+#[derive(Serialize, Deserialize, Clone, Copy, ToSchema)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEntityType {
+    Task,
+    User,
+}
+
+impl From<AuditEntityType> for domain::audit::AuditEntityType {
+    fn from(value: AuditEntityType) -> Self {
+        match value {
+            AuditEntityType::Task => domain::audit::AuditEntityType::Task,
+            AuditEntityType::User => domain::audit::AuditEntityType::User,
+        }
+    }
+}
+
+impl From<domain::audit::AuditEntityType> for AuditEntityType {
+    fn from(value: domain::audit::AuditEntityType) -> Self {
+        match value {
+            domain::audit::AuditEntityType::Task => AuditEntityType::Task,
+            domain::audit::AuditEntityType::User => AuditEntityType::User,
+        }
+    }
+}
+
+/// The kinds of changes recorded in the audit log
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug, PartialEq, Eq))]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Complete,
+    Assign,
+    Delete,
+    Restore,
+    Purge,
+}
+
+impl From<domain::audit::AuditAction> for AuditAction {
+    fn from(value: domain::audit::AuditAction) -> Self {
+        match value {
+            domain::audit::AuditAction::Create => AuditAction::Create,
+            domain::audit::AuditAction::Update => AuditAction::Update,
+            domain::audit::AuditAction::Complete => AuditAction::Complete,
+            domain::audit::AuditAction::Assign => AuditAction::Assign,
+            domain::audit::AuditAction::Delete => AuditAction::Delete,
+            domain::audit::AuditAction::Restore => AuditAction::Restore,
+            domain::audit::AuditAction::Purge => AuditAction::Purge,
+        }
+    }
+}
+
+/// Query parameters for searching the audit log
+#[derive(Deserialize, Validate, IntoParams, Default)]
+#[into_params(parameter_in = Query)]
+#[validate(schema(function = "validate_audit_log_params"))]
+#[cfg_attr(test, derive(Serialize))]
+pub struct AuditLogParams {
+    /// Only include changes to this type of entity
+    #[param(inline)]
+    pub entity_type: Option<AuditEntityType>,
+    /// Only include changes to the entity with this ID. Requires `entity_type`.
+    pub entity_id: Option<i32>,
+    /// Only include changes made by the user with this ID
+    pub actor_user_id: Option<i32>,
+    /// Only include changes made at or after this time
+    #[param(example = "2024-03-01T00:00:00Z")]
+    pub since: Option<DateTime<Utc>>,
+    /// Only include changes made before this time
+    #[param(example = "2024-03-02T00:00:00Z")]
+    pub until: Option<DateTime<Utc>>,
+    /// Only include entries older than the entry with this ID. Pass the ID of the last entry
+    /// in a page to retrieve the next page.
+    pub before_id: Option<i64>,
+    /// The most entries to return. Defaults to 50.
+    #[validate(range(min = 1, max = 200))]
+    pub limit: Option<i64>,
+}
+
+/// IDs are only unique within an entity type, and a time range must end after it starts
+fn validate_audit_log_params(params: &AuditLogParams) -> Result<(), ValidationError> {
+    if params.entity_id.is_some() && params.entity_type.is_none() {
+        return Err(ValidationError::new("entity_id_without_entity_type"));
+    }
+
+    match (params.since, params.until) {
+        (Some(since), Some(until)) if since >= until => {
+            Err(ValidationError::new("empty_time_range"))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl From<AuditLogParams> for domain::audit::AuditFilter {
+    fn from(value: AuditLogParams) -> Self {
+        domain::audit::AuditFilter {
+            entity_type: value.entity_type.map(Into::into),
+            entity_id: value.entity_id,
+            actor_user_id: value.actor_user_id,
+            since: value.since,
+            until: value.until,
+            before_id: value.before_id,
+            limit: value.limit.unwrap_or(domain::audit::DEFAULT_AUDIT_ENTRIES),
+        }
+    }
+}
+
+/// DTO for a single change recorded in the audit log
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize, Debug))]
+pub struct AuditEntry {
+    #[schema(example = 31)]
+    pub id: i64,
+    /// The user who made the change. Null if the request didn't identify a user.
+    #[schema(example = 4)]
+    pub actor_user_id: Option<i32>,
+    pub action: AuditAction,
+    pub entity_type: AuditEntityType,
+    #[schema(example = 10)]
+    pub entity_id: i32,
+    /// The entity before the change. Null if the entity was created.
+    #[schema(value_type = Option<Object>, example = json!({"id": 10, "user_id": 4, "item_desc": "Something to do", "completed": false, "due_date": null}))]
+    pub before: Option<serde_json::Value>,
+    /// The entity after the change. Null if the entity was purged.
+    #[schema(value_type = Option<Object>, example = json!({"id": 10, "user_id": 4, "item_desc": "Something else to do", "completed": false, "due_date": null}))]
+    pub after: Option<serde_json::Value>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl From<domain::audit::AuditEntry> for AuditEntry {
+    fn from(value: domain::audit::AuditEntry) -> Self {
+        AuditEntry {
+            id: value.id,
+            actor_user_id: value.actor_user_id,
+            action: AuditAction::from(value.action),
+            entity_type: AuditEntityType::from(value.entity_type),
+            entity_id: value.entity_id,
+            before: value.before,
+            after: value.after,
+            recorded_at: value.recorded_at,
+        }
+    }
+}
+
 /// Contains diagnostic information about an API failure
 #[derive(Serialize, Debug, ToSchema)]
 #[cfg_attr(test, derive(Deserialize))]
@@ -167,8 +1336,21 @@ pub mod err_resps {
             }
         })
     )]
+    #[allow(dead_code)]
     pub struct BasicError400Validation(BasicError);
 
+    #[derive(ToResponse)]
+    #[response(
+        description = "The caller is not allowed to do what they asked",
+        example = json!({
+            "error_code": "forbidden",
+            "error_description": "The caller is not allowed to manage the tasks of user 3.",
+            "extra_info": null
+        })
+    )]
+    #[allow(dead_code)]
+    pub struct BasicError403(BasicError);
+
     #[derive(ToResponse)]
     #[response(
         description = "Entity could not be found",
@@ -178,8 +1360,33 @@ pub mod err_resps {
             "extra_info": null
         })
     )]
+    #[allow(dead_code)]
     pub struct BasicError404(BasicError);
 
+    #[derive(ToResponse)]
+    #[response(
+        description = "The idempotency key was already used for a request with a different body",
+        example = json!({
+            "error_code": "idempotency_key_reused",
+            "error_description": "The idempotency key was already used for a request with a different body.",
+            "extra_info": null
+        })
+    )]
+    #[allow(dead_code)]
+    pub struct BasicError422IdempotencyKeyReused(BasicError);
+
+    #[derive(ToResponse)]
+    #[response(
+        description = "The client made too many requests and should wait for the number of seconds in the Retry-After header",
+        example = json!({
+            "error_code": "rate_limited",
+            "error_description": "Too many requests were made. Try again in 30 seconds.",
+            "extra_info": null
+        })
+    )]
+    #[allow(dead_code)]
+    pub struct BasicError429(BasicError);
+
     #[derive(ToResponse)]
     #[response(
         description = "Something unexpected went wrong inside the server",
@@ -189,6 +1396,7 @@ pub mod err_resps {
             "extra_info": null
         })
     )]
+    #[allow(dead_code)]
     pub struct BasicError500(BasicError);
 }
 
@@ -197,6 +1405,7 @@ pub mod err_resps {
 #[serde(untagged)]
 pub enum ExtraInfo {
     ValidationIssues(ValidationErrorSchema),
+    ImportIssues(Vec<ImportRowIssue>),
     Message(String),
 }
 
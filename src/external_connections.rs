@@ -1,15 +1,10 @@
+use crate::domain::attachment::driven_ports::BlobStore;
+use log::error;
 use sqlx::PgConnection;
 
 use std::fmt::{Debug, Display};
-use std::future::Future;
 use thiserror::Error;
 
-/// TransactableExternalConnectivity represents an [ExternalConnectivity] that can initiate
-/// a database transaction
-pub trait TransactableExternalConnectivity: ExternalConnectivity + Transactable + Sync {}
-
-impl<T: ExternalConnectivity + Transactable + Sync> TransactableExternalConnectivity for T {}
-
 /// ExternalConnectivity owns clients that are able to communicate with the outside world,
 /// such as database clients, HTTP clients, and more.
 pub trait ExternalConnectivity: Sync {
@@ -17,9 +12,13 @@ pub trait ExternalConnectivity: Sync {
     where
         Self: 'handle;
     type Error: Debug + Display;
+    type BlobStore: BlobStore;
 
     /// Acquire a handle which allows borrowing a connection from the database pool
     async fn database_cxn(&mut self) -> Result<Self::Handle<'_>, Self::Error>;
+
+    /// Access the storage which holds the contents of files, such as task attachments
+    fn blob_store(&self) -> &Self::BlobStore;
 }
 
 /// ConnectionHandle is a handle borrowed from [ExternalConnectivity] which can be
@@ -29,6 +28,24 @@ pub trait ConnectionHandle {
     fn borrow_connection(&mut self) -> &mut PgConnection;
 }
 
+/// The isolation level a database transaction runs at. See the
+/// [PostgreSQL documentation](https://www.postgresql.org/docs/current/transaction-iso.html)
+/// for what each level guarantees.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IsolationLevel {
+    #[default]
+    ReadCommitted,
+    RepeatableRead,
+}
+
+/// Settings which control how a database transaction behaves. The default matches the
+/// database's default transaction: READ COMMITTED and writable.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionOptions {
+    pub isolation_level: IsolationLevel,
+    pub read_only: bool,
+}
+
 /// Anything that can initiate a database transaction
 pub trait Transactable: Sync {
     type Handle<'handle>: TransactionHandle + 'handle
@@ -37,7 +54,35 @@ pub trait Transactable: Sync {
     type Error: Debug + Display;
 
     /// Retrieve a handle which contains a database connection in an active transaction
-    async fn start_transaction(&self) -> Result<Self::Handle<'_>, Self::Error>;
+    /// configured with the given options
+    async fn start_transaction_with_options(
+        &self,
+        options: TransactionOptions,
+    ) -> Result<Self::Handle<'_>, Self::Error>;
+
+    /// Retrieve a handle which contains a database connection in an active transaction
+    /// using the default [TransactionOptions]
+    async fn start_transaction(&self) -> Result<Self::Handle<'_>, Self::Error> {
+        self.start_transaction_with_options(TransactionOptions::default())
+            .await
+    }
+}
+
+/// Anything already inside a database transaction which can nest a transaction inside it via a savepoint.
+/// Releasing the savepoint keeps the work done since it was created, while rolling back to it undoes only
+/// that work. Savepoints are taken on the same handle as the outer transaction rather than handing out a
+/// new one, so futures holding them can still be proven Send inside request handlers and spawned jobs.
+pub trait Savepointable: Sync {
+    type Error: Debug + Display;
+
+    /// Create a savepoint inside the current transaction
+    async fn create_savepoint(&mut self) -> Result<(), Self::Error>;
+
+    /// Release the most recently created savepoint, keeping the work done since it was created
+    async fn release_savepoint(&mut self) -> Result<(), Self::Error>;
+
+    /// Undo the work done since the most recently created savepoint, then release it
+    async fn rollback_to_savepoint(&mut self) -> Result<(), Self::Error>;
 }
 
 /// TransactionHandle is a handle borrowed from [Transactable] which represents
@@ -51,7 +96,6 @@ pub trait TransactionHandle: Sync {
     async fn commit(self) -> Result<(), Self::Error>;
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Error)]
 /// This error reports issues that occur during database transactions, allowing the
 /// original result of a [with_transaction]'s lambda to be retrieved even if the transaction
@@ -85,16 +129,14 @@ where
 // ErrBegin = "The error returned if we fail to start a transaction"
 // Handle = "The thing that can give you a database connection"
 // ErrCommit = "The error returned if we fail to commit the transaction"
-// Fn = "The function which contains code executed in a database transaction"
-// Fut = "The future returned from the function passed via transaction_context which may be awaited for the return value"
-// Ret = "The type Fut resolves to if the transaction was a success"
-// ErrSource = "The error Fut resolves to if the user returns an error from Fn"
+// Fn = "The async closure which contains code executed in a database transaction"
+// Ret = "The type Fn resolves to if the transaction was a success"
+// ErrSource = "The error Fn resolves to if the user returns an error"
 /// Accepts [tx_origin] which can start a database transaction. It then starts a transaction and
 /// invokes [transaction_context] with the started transaction. When [transaction_context] completes,
 /// the transaction handle passed to it is committed as long as [transaction_context] does not return
 /// a [Result::Err].
-#[allow(dead_code)]
-pub async fn with_transaction<'tx, TxAble, ErrBegin, Handle, ErrCommit, Fn, Fut, Ret, ErrSource>(
+pub async fn with_transaction<'tx, TxAble, ErrBegin, Handle, ErrCommit, Fn, Ret, ErrSource>(
     tx_origin: &'tx TxAble,
     transaction_context: Fn,
 ) -> Result<Ret, TxOrSourceError<Ret, ErrSource, TxAble::Error, Handle::Error>>
@@ -103,28 +145,111 @@ where
     ErrBegin: Debug + Display,
     Handle: TransactionHandle<Error = ErrCommit>,
     ErrCommit: Debug + Display,
-    Fn: FnOnce(&mut Handle) -> Fut,
-    Fut: Future<Output = Result<Ret, ErrSource>>,
+    Fn: AsyncFnOnce(&mut Handle) -> Result<Ret, ErrSource>,
     ErrSource: Debug + Display,
 {
-    let mut tx_handle = tx_origin
+    let tx_handle = tx_origin
         .start_transaction()
         .await
         .map_err(|err| TxOrSourceError::TxBegin(err))?;
-    let ret_val = transaction_context(&mut tx_handle).await;
-    if ret_val.is_ok() {
-        let commit_result = tx_handle.commit().await;
-        if let Err(commit_err) = commit_result {
-            return Err(TxOrSourceError::TxCommit {
-                successful_result: ret_val.unwrap(),
-                transaction_err: commit_err,
-            });
+
+    run_and_commit(tx_handle, transaction_context).await
+}
+
+/// Works the same as [with_transaction], but starts the transaction with the given [options]
+/// so callers can request a stricter isolation level or a read-only transaction.
+pub async fn with_transaction_options<
+    'tx,
+    TxAble,
+    ErrBegin,
+    Handle,
+    ErrCommit,
+    Fn,
+    Ret,
+    ErrSource,
+>(
+    tx_origin: &'tx TxAble,
+    options: TransactionOptions,
+    transaction_context: Fn,
+) -> Result<Ret, TxOrSourceError<Ret, ErrSource, TxAble::Error, Handle::Error>>
+where
+    TxAble: Transactable<Handle<'tx> = Handle, Error = ErrBegin>,
+    ErrBegin: Debug + Display,
+    Handle: TransactionHandle<Error = ErrCommit>,
+    ErrCommit: Debug + Display,
+    Fn: AsyncFnOnce(&mut Handle) -> Result<Ret, ErrSource>,
+    ErrSource: Debug + Display,
+{
+    let tx_handle = tx_origin
+        .start_transaction_with_options(options)
+        .await
+        .map_err(|err| TxOrSourceError::TxBegin(err))?;
+
+    run_and_commit(tx_handle, transaction_context).await
+}
+
+/// Accepts [tx_cxn] which is already inside a database transaction, creates a savepoint, and invokes
+/// [transaction_context] with the transaction. The savepoint is released if [transaction_context]
+/// succeeds and rolled back if it returns a [Result::Err], leaving the rest of the outer transaction intact.
+/// This lets domain logic running inside an outer transaction undo only its own portion of the work.
+/// If rolling back fails, the outer transaction can't be committed either, so the failure surfaces there.
+pub async fn with_savepoint<TxCxn, Fn, Ret, ErrSource>(
+    tx_cxn: &mut TxCxn,
+    transaction_context: Fn,
+) -> Result<Ret, TxOrSourceError<Ret, ErrSource, TxCxn::Error, TxCxn::Error>>
+where
+    TxCxn: Savepointable,
+    Fn: AsyncFnOnce(&mut TxCxn) -> Result<Ret, ErrSource>,
+    ErrSource: Debug + Display,
+{
+    tx_cxn
+        .create_savepoint()
+        .await
+        .map_err(|err| TxOrSourceError::TxBegin(err))?;
+
+    let ret_val = match transaction_context(&mut *tx_cxn).await {
+        Ok(value) => value,
+        Err(error) => {
+            if let Err(rollback_err) = tx_cxn.rollback_to_savepoint().await {
+                error!("Failed to roll back savepoint, the outer transaction can no longer commit: {rollback_err}");
+            }
+            return Err(TxOrSourceError::Source(error));
         }
+    };
+
+    match tx_cxn.release_savepoint().await {
+        Ok(_) => Ok(ret_val),
+        Err(release_err) => Err(TxOrSourceError::TxCommit {
+            successful_result: ret_val,
+            transaction_err: release_err,
+        }),
     }
+}
 
-    match ret_val {
-        Ok(value) => Ok(value),
-        Err(error) => Err(TxOrSourceError::Source(error)),
+/// Invokes [transaction_context] with the started transaction and commits it if the lambda succeeds.
+/// Dropping the handle on failure rolls back the transaction.
+async fn run_and_commit<Handle, ErrBegin, ErrCommit, Fn, Ret, ErrSource>(
+    mut tx_handle: Handle,
+    transaction_context: Fn,
+) -> Result<Ret, TxOrSourceError<Ret, ErrSource, ErrBegin, ErrCommit>>
+where
+    ErrBegin: Debug + Display,
+    Handle: TransactionHandle<Error = ErrCommit>,
+    ErrCommit: Debug + Display,
+    Fn: AsyncFnOnce(&mut Handle) -> Result<Ret, ErrSource>,
+    ErrSource: Debug + Display,
+{
+    let ret_val = match transaction_context(&mut tx_handle).await {
+        Ok(value) => value,
+        Err(error) => return Err(TxOrSourceError::Source(error)),
+    };
+
+    match tx_handle.commit().await {
+        Ok(_) => Ok(ret_val),
+        Err(commit_err) => Err(TxOrSourceError::TxCommit {
+            successful_result: ret_val,
+            transaction_err: commit_err,
+        }),
     }
 }
 
@@ -142,7 +267,7 @@ mod with_transaction_test {
     #[tokio::test]
     async fn commits_on_success() {
         let ext_cxn = test_util::FakeExternalConnectivity::new();
-        let tx_result = with_transaction(&ext_cxn, |_tx_cxn| async {
+        let tx_result = with_transaction(&ext_cxn, async |_tx_cxn| {
             println!("Woohoo!");
             Ok::<(), SampleErr>(())
         })
@@ -155,7 +280,7 @@ mod with_transaction_test {
     #[tokio::test]
     async fn does_not_commit_on_failure() {
         let ext_cxn = test_util::FakeExternalConnectivity::new();
-        let tx_result = with_transaction(&ext_cxn, |_tx_cxn| async {
+        let tx_result = with_transaction(&ext_cxn, async |_tx_cxn| {
             println!("Whoopsie!");
             Err::<(), SampleErr>(SampleErr)
         })
@@ -166,24 +291,101 @@ mod with_transaction_test {
             .matches(|inner_err| matches!(inner_err, TxOrSourceError::Source(SampleErr)));
         assert_that!(ext_cxn.did_transaction_commit()).is_false();
     }
+
+    #[tokio::test]
+    async fn uses_default_options() {
+        let ext_cxn = test_util::FakeExternalConnectivity::new();
+        let tx_result = with_transaction(&ext_cxn, async |_tx_cxn| Ok::<(), SampleErr>(())).await;
+
+        assert_that!(tx_result).is_ok();
+        assert_that!(ext_cxn.started_transactions())
+            .is_equal_to(vec![TransactionOptions::default()]);
+    }
+
+    #[tokio::test]
+    async fn starts_transaction_with_requested_options() {
+        let ext_cxn = test_util::FakeExternalConnectivity::new();
+        let options = TransactionOptions {
+            isolation_level: IsolationLevel::RepeatableRead,
+            read_only: true,
+        };
+        let tx_result =
+            with_transaction_options(&ext_cxn, options, async |_tx_cxn| Ok::<(), SampleErr>(()))
+                .await;
+
+        assert_that!(tx_result).is_ok();
+        assert_that!(ext_cxn.started_transactions()).is_equal_to(vec![options]);
+        assert_that!(ext_cxn.did_transaction_commit()).is_true();
+    }
+
+    mod with_savepoint {
+        use super::*;
+
+        #[tokio::test]
+        async fn releases_savepoint_on_success() {
+            let ext_cxn = test_util::FakeExternalConnectivity::new();
+            let tx_result = with_transaction(&ext_cxn, async |tx_cxn| {
+                with_savepoint(tx_cxn, async |sp_cxn| {
+                    assert_that!(sp_cxn.is_transacting()).is_true();
+                    Ok::<(), SampleErr>(())
+                })
+                .await
+                .map_err(|_| SampleErr)
+            })
+            .await;
+
+            assert_that!(tx_result).is_ok();
+            assert_that!(ext_cxn.savepoints_started()).is_equal_to(1);
+            assert_that!(ext_cxn.savepoints_released()).is_equal_to(1);
+            assert_that!(ext_cxn.did_transaction_commit()).is_true();
+        }
+
+        #[tokio::test]
+        async fn rolls_back_savepoint_without_failing_outer_transaction() {
+            let ext_cxn = test_util::FakeExternalConnectivity::new();
+            let tx_result = with_transaction(&ext_cxn, async |tx_cxn| {
+                let savepoint_result =
+                    with_savepoint(tx_cxn, async |_sp_cxn| Err::<(), SampleErr>(SampleErr)).await;
+                assert_that!(savepoint_result)
+                    .is_err()
+                    .matches(|inner_err| matches!(inner_err, TxOrSourceError::Source(SampleErr)));
+
+                Ok::<(), SampleErr>(())
+            })
+            .await;
+
+            assert_that!(tx_result).is_ok();
+            assert_that!(ext_cxn.savepoints_started()).is_equal_to(1);
+            assert_that!(ext_cxn.savepoints_released()).is_equal_to(0);
+            assert_that!(ext_cxn.did_transaction_commit()).is_true();
+        }
+    }
 }
 
 #[cfg(test)]
 pub mod test_util {
     use crate::external_connections::{
-        ConnectionHandle, ExternalConnectivity, Transactable, TransactionHandle,
+        ConnectionHandle, ExternalConnectivity, Savepointable, Transactable, TransactionHandle,
+        TransactionOptions,
     };
 
+    use crate::domain::attachment::test_util::InMemoryBlobStore;
     use sqlx::PgConnection;
     use std::convert::Infallible;
-    use std::sync::atomic::{AtomicBool, Ordering};
-    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
 
     /// A fake for ExternalConnectivity so unit tests don't actually have to connect to external systems.
-    /// Also allows inspection in tests to verify a database transaction was committed
+    /// Also allows inspection in tests to verify a database transaction was committed, which options
+    /// transactions were started with, and how savepoints were used. Files are kept in memory.
     pub struct FakeExternalConnectivity {
         is_transacting: bool,
+        blob_store: Arc<InMemoryBlobStore>,
+        savepoint_depth: usize,
         downstream_transaction_committed: Arc<AtomicBool>,
+        started_transactions: Arc<Mutex<Vec<TransactionOptions>>>,
+        savepoints_started: Arc<AtomicUsize>,
+        savepoints_released: Arc<AtomicUsize>,
     }
 
     impl FakeExternalConnectivity {
@@ -191,12 +393,16 @@ pub mod test_util {
         pub fn new() -> Self {
             Self {
                 is_transacting: false,
+                blob_store: Arc::new(InMemoryBlobStore::new()),
+                savepoint_depth: 0,
                 downstream_transaction_committed: Arc::new(AtomicBool::new(false)),
+                started_transactions: Arc::new(Mutex::new(Vec::new())),
+                savepoints_started: Arc::new(AtomicUsize::new(0)),
+                savepoints_released: Arc::new(AtomicUsize::new(0)),
             }
         }
 
         /// Returns true if a database transaction is active
-        #[allow(dead_code)]
         pub fn is_transacting(&self) -> bool {
             self.is_transacting
         }
@@ -205,6 +411,41 @@ pub mod test_util {
         pub fn did_transaction_commit(&self) -> bool {
             self.downstream_transaction_committed.load(Ordering::SeqCst)
         }
+
+        /// Returns the options passed to every database transaction started from this fake
+        pub fn started_transactions(&self) -> Vec<TransactionOptions> {
+            self.started_transactions
+                .lock()
+                .expect("transaction options mutex poisoned")
+                .clone()
+        }
+
+        /// Returns the number of savepoints that were started inside a transaction
+        pub fn savepoints_started(&self) -> usize {
+            self.savepoints_started.load(Ordering::SeqCst)
+        }
+
+        /// Returns the number of savepoints that were released (committed). Savepoints which were started
+        /// but not released were rolled back.
+        pub fn savepoints_released(&self) -> usize {
+            self.savepoints_released.load(Ordering::SeqCst)
+        }
+
+        /// Creates a copy of this fake which shares its recorded state, used to represent
+        /// a transaction or savepoint started from it
+        fn nested(&self) -> FakeExternalConnectivity {
+            FakeExternalConnectivity {
+                is_transacting: true,
+                blob_store: Arc::clone(&self.blob_store),
+                savepoint_depth: 0,
+                downstream_transaction_committed: Arc::clone(
+                    &self.downstream_transaction_committed,
+                ),
+                started_transactions: Arc::clone(&self.started_transactions),
+                savepoints_started: Arc::clone(&self.savepoints_started),
+                savepoints_released: Arc::clone(&self.savepoints_released),
+            }
+        }
     }
 
     /// A fake database connection handle which panics if code tries to acquire
@@ -220,11 +461,16 @@ pub mod test_util {
     impl ExternalConnectivity for FakeExternalConnectivity {
         type Handle<'cxn> = MockHandle;
         type Error = Infallible;
+        type BlobStore = InMemoryBlobStore;
 
         #[allow(clippy::diverging_sub_expression)]
         async fn database_cxn(&mut self) -> Result<Self::Handle<'_>, Self::Error> {
             Ok(MockHandle {})
         }
+
+        fn blob_store(&self) -> &InMemoryBlobStore {
+            &self.blob_store
+        }
     }
 
     impl TransactionHandle for FakeExternalConnectivity {
@@ -235,6 +481,10 @@ pub mod test_util {
                 panic!("Tried to commit when we weren't in a transaction!")
             }
 
+            if self.savepoint_depth > 0 {
+                panic!("Tried to commit with savepoints still open!")
+            }
+
             self.downstream_transaction_committed
                 .store(true, Ordering::SeqCst);
             Ok(())
@@ -245,13 +495,49 @@ pub mod test_util {
         type Handle<'handle> = FakeExternalConnectivity;
         type Error = Infallible;
 
-        async fn start_transaction(&self) -> Result<FakeExternalConnectivity, Self::Error> {
-            Ok(FakeExternalConnectivity {
-                is_transacting: true,
-                downstream_transaction_committed: Arc::clone(
-                    &self.downstream_transaction_committed,
-                ),
-            })
+        async fn start_transaction_with_options(
+            &self,
+            options: TransactionOptions,
+        ) -> Result<FakeExternalConnectivity, Self::Error> {
+            self.started_transactions
+                .lock()
+                .expect("transaction options mutex poisoned")
+                .push(options);
+
+            Ok(self.nested())
+        }
+    }
+
+    impl Savepointable for FakeExternalConnectivity {
+        type Error = Infallible;
+
+        async fn create_savepoint(&mut self) -> Result<(), Self::Error> {
+            if !self.is_transacting {
+                panic!("Tried to start a savepoint when we weren't in a transaction!")
+            }
+
+            self.savepoint_depth += 1;
+            self.savepoints_started.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn release_savepoint(&mut self) -> Result<(), Self::Error> {
+            if self.savepoint_depth == 0 {
+                panic!("Tried to release a savepoint which wasn't started!")
+            }
+
+            self.savepoint_depth -= 1;
+            self.savepoints_released.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn rollback_to_savepoint(&mut self) -> Result<(), Self::Error> {
+            if self.savepoint_depth == 0 {
+                panic!("Tried to roll back a savepoint which wasn't started!")
+            }
+
+            self.savepoint_depth -= 1;
+            Ok(())
         }
     }
 }
@@ -0,0 +1,141 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::routing_utils::CALLING_USER_HEADER;
+use crate::{api, dto};
+
+use super::test_util;
+
+async fn prepare_access_controlled_app() -> (Router, sqlx::PgPool) {
+    test_util::prepare_application_with(
+        Router::new().nest("/users", api::user::user_routes()),
+        |shared_data| {
+            shared_data.access_control = true;
+        },
+    )
+    .await
+}
+
+async fn create_user(app: &mut Router, first_name: &str) -> i32 {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: first_name.to_owned(),
+            last_name: String::from("Access"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    assert_eq!(StatusCode::CREATED, create_user_resp.status());
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    user.id
+}
+
+async fn make_admin(db: &sqlx::PgPool, user_id: i32) {
+    sqlx::query("UPDATE todo_user SET role = 'admin' WHERE id = $1")
+        .bind(user_id)
+        .execute(db)
+        .await
+        .expect("Could not promote the user to admin");
+}
+
+fn get_request(uri: String, calling_user: Option<i32>) -> Request<Body> {
+    let mut builder = Request::builder().method(Method::GET).uri(uri);
+    if let Some(calling_user) = calling_user {
+        builder = builder.header(CALLING_USER_HEADER, calling_user);
+    }
+
+    builder.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn users_only_reach_their_own_tasks() {
+    let (mut app, _) = prepare_access_controlled_app().await;
+    let owner_id = create_user(&mut app, "Olivia").await;
+    let other_id = create_user(&mut app, "Oscar").await;
+
+    let own_tasks_resp = app
+        .call(get_request(
+            format!("/users/{owner_id}/tasks"),
+            Some(owner_id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, own_tasks_resp.status());
+
+    let other_tasks_resp = app
+        .call(get_request(
+            format!("/users/{owner_id}/tasks"),
+            Some(other_id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::FORBIDDEN, other_tasks_resp.status());
+    let error: dto::BasicError = deserialize_body(other_tasks_resp.into_body()).await;
+    assert_eq!("forbidden", error.error_code);
+
+    let anonymous_resp = app
+        .call(get_request(format!("/users/{owner_id}/tasks"), None))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::FORBIDDEN, anonymous_resp.status());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn only_admins_list_users_and_change_roles() {
+    let (mut app, db) = prepare_access_controlled_app().await;
+    let admin_id = create_user(&mut app, "Adele").await;
+    let user_id = create_user(&mut app, "Ulrich").await;
+    make_admin(&db, admin_id).await;
+
+    let user_list_resp = app
+        .call(get_request("/users".to_owned(), Some(user_id)))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::FORBIDDEN, user_list_resp.status());
+
+    let promote_by_user_req = Request::builder()
+        .method(Method::PUT)
+        .uri(format!("/users/{user_id}/role"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(CALLING_USER_HEADER, user_id)
+        .body(dto_to_body(&dto::UserRole {
+            role: dto::Role::Admin,
+        }))
+        .unwrap();
+    let promote_by_user_resp = app.call(promote_by_user_req).await.unwrap();
+    assert_eq!(StatusCode::FORBIDDEN, promote_by_user_resp.status());
+
+    let admin_list_resp = app
+        .call(get_request("/users".to_owned(), Some(admin_id)))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, admin_list_resp.status());
+
+    let promote_req = Request::builder()
+        .method(Method::PUT)
+        .uri(format!("/users/{user_id}/role"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(CALLING_USER_HEADER, admin_id)
+        .body(dto_to_body(&dto::UserRole {
+            role: dto::Role::Admin,
+        }))
+        .unwrap();
+    let promote_resp = app.call(promote_req).await.unwrap();
+    assert_eq!(StatusCode::NO_CONTENT, promote_resp.status());
+
+    let user_detail_resp = app
+        .call(get_request(format!("/users/{user_id}"), Some(user_id)))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, user_detail_resp.status());
+    let user_details: dto::UserDetails = deserialize_body(user_detail_resp.into_body()).await;
+    assert!(matches!(user_details.role, dto::Role::Admin));
+}
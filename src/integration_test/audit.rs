@@ -0,0 +1,233 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use serde_json::json;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::routing_utils::CALLING_USER_HEADER;
+use crate::{api, dto};
+
+use super::test_util;
+
+fn test_router() -> Router<std::sync::Arc<crate::SharedData>> {
+    Router::new()
+        .nest("/users", api::user::user_routes())
+        .nest("/tasks", api::todo::task_routes())
+        .nest("/audit", api::audit::audit_routes())
+}
+
+fn audit_request(query: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::GET)
+        .uri(format!("/audit?{query}"))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn records_changes_with_actor_and_state() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    let create_task_req = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/users/{}/tasks", user.id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(CALLING_USER_HEADER, user.id)
+        .body(dto_to_body(&dto::NewTask {
+            item_desc: "Write the report".to_owned(),
+            due_date: None,
+            recurrence: None,
+            assignee_user_id: None,
+        }))
+        .unwrap();
+    let create_task_resp = app.call(create_task_req).await.unwrap();
+    let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+
+    let update_task_req = Request::builder()
+        .method(Method::PATCH)
+        .uri(format!("/tasks/{}", task.id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(CALLING_USER_HEADER, user.id)
+        .body(dto_to_body(&dto::UpdateTask {
+            description: "Write the final report".to_owned(),
+        }))
+        .unwrap();
+    let update_task_resp = app.call(update_task_req).await.unwrap();
+    assert_eq!(StatusCode::OK, update_task_resp.status());
+
+    let delete_task_req = Request::builder()
+        .method(Method::DELETE)
+        .uri(format!("/tasks/{}", task.id))
+        .header(CALLING_USER_HEADER, user.id)
+        .body(Body::empty())
+        .unwrap();
+    let delete_task_resp = app.call(delete_task_req).await.unwrap();
+    assert_eq!(StatusCode::OK, delete_task_resp.status());
+
+    let task_history_resp = app
+        .call(audit_request(&format!(
+            "entity_type=task&entity_id={}",
+            task.id
+        )))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, task_history_resp.status());
+    let task_history: Vec<dto::AuditEntry> = deserialize_body(task_history_resp.into_body()).await;
+
+    let actions: Vec<&dto::AuditAction> = task_history.iter().map(|entry| &entry.action).collect();
+    assert_eq!(
+        vec![
+            &dto::AuditAction::Delete,
+            &dto::AuditAction::Update,
+            &dto::AuditAction::Create
+        ],
+        actions
+    );
+    assert!(task_history
+        .iter()
+        .all(|entry| entry.actor_user_id == Some(user.id)));
+
+    let update = &task_history[1];
+    assert_eq!(
+        Some(&json!("Write the report")),
+        update.before.as_ref().map(|before| &before["item_desc"])
+    );
+    assert_eq!(
+        Some(&json!("Write the final report")),
+        update.after.as_ref().map(|after| &after["item_desc"])
+    );
+    assert!(task_history[0]
+        .after
+        .as_ref()
+        .is_some_and(|after| !after["deleted_at"].is_null()));
+    assert!(task_history[2].before.is_none());
+
+    let user_history_resp = app
+        .call(audit_request(&format!(
+            "entity_type=user&entity_id={}",
+            user.id
+        )))
+        .await
+        .unwrap();
+    let user_history: Vec<dto::AuditEntry> = deserialize_body(user_history_resp.into_body()).await;
+    assert!(matches!(user_history.as_slice(), [
+        dto::AuditEntry {
+            actor_user_id: None,
+            action: dto::AuditAction::Create,
+            after: Some(after),
+            ..
+        }
+    ] if after["first_name"] == "John"));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn pages_through_entries_by_actor() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+
+    for (actor, first_name) in [(7, "Jane"), (8, "Jim"), (7, "Jill"), (7, "Joe")] {
+        let create_user_req = Request::builder()
+            .method(Method::POST)
+            .uri("/users")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(CALLING_USER_HEADER, actor)
+            .body(dto_to_body(&dto::NewUser {
+                first_name: first_name.to_owned(),
+                last_name: String::from("Doe"),
+            }))
+            .unwrap();
+        let create_user_resp = app.call(create_user_req).await.unwrap();
+        assert_eq!(StatusCode::CREATED, create_user_resp.status());
+    }
+
+    let first_page_resp = app
+        .call(audit_request("actor_user_id=7&limit=2"))
+        .await
+        .unwrap();
+    let first_page: Vec<dto::AuditEntry> = deserialize_body(first_page_resp.into_body()).await;
+    let first_names: Vec<&serde_json::Value> = first_page
+        .iter()
+        .filter_map(|entry| entry.after.as_ref().map(|after| &after["first_name"]))
+        .collect();
+    assert_eq!(vec!["Joe", "Jill"], first_names);
+
+    let second_page_resp = app
+        .call(audit_request(&format!(
+            "actor_user_id=7&limit=2&before_id={}",
+            first_page[1].id
+        )))
+        .await
+        .unwrap();
+    let second_page: Vec<dto::AuditEntry> = deserialize_body(second_page_resp.into_body()).await;
+    let first_names: Vec<&serde_json::Value> = second_page
+        .iter()
+        .filter_map(|entry| entry.after.as_ref().map(|after| &after["first_name"]))
+        .collect();
+    assert_eq!(vec!["Jane"], first_names);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn rejects_invalid_calling_user() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(CALLING_USER_HEADER, "somebody")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let response = app.call(create_user_req).await.unwrap();
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let body: dto::BasicError = deserialize_body(response.into_body()).await;
+    assert_eq!("invalid_calling_user", body.error_code);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn audit_log_cannot_be_changed() {
+    let (mut app, db) = test_util::prepare_application(test_router()).await;
+
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    assert_eq!(StatusCode::CREATED, create_user_resp.status());
+
+    for statement in [
+        "UPDATE audit_log SET actor_user_id = 99",
+        "DELETE FROM audit_log",
+        "TRUNCATE audit_log",
+    ] {
+        let change_result = sqlx::query(statement).execute(&db).await;
+        assert!(
+            change_result.is_err(),
+            "Audit log allowed the statement: {statement}"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use chrono::NaiveDate;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+fn test_router() -> Router<std::sync::Arc<crate::SharedData>> {
+    Router::new()
+        .nest("/users", api::user::user_routes())
+        .nest("/tasks", api::todo::task_routes())
+}
+
+fn request(method: Method, uri: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap()
+}
+
+async fn create_user_with_task(app: &mut Router) -> i32 {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    let create_task_req = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/users/{}/tasks", user.id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewTask {
+            item_desc: "Water the plants, then the lawn".to_owned(),
+            due_date: NaiveDate::from_ymd_opt(2024, 3, 5),
+            recurrence: None,
+            assignee_user_id: None,
+        }))
+        .unwrap();
+    let create_task_resp = app.call(create_task_req).await.unwrap();
+    assert_eq!(StatusCode::CREATED, create_task_resp.status());
+
+    user.id
+}
+
+async fn issue_token(app: &mut Router, user_id: i32) -> String {
+    let issue_resp = app
+        .call(request(
+            Method::POST,
+            &format!("/users/{user_id}/feed-token"),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, issue_resp.status());
+
+    let feed_token: dto::FeedToken = deserialize_body(issue_resp.into_body()).await;
+    feed_token.token
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn serves_tasks_as_calendar() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+    let user_id = create_user_with_task(&mut app).await;
+    let token = issue_token(&mut app, user_id).await;
+
+    let feed_resp = app
+        .call(request(
+            Method::GET,
+            &format!("/users/{user_id}/tasks.ics?token={token}"),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, feed_resp.status());
+    assert_eq!(
+        "text/calendar; charset=utf-8",
+        feed_resp.headers()[header::CONTENT_TYPE]
+    );
+
+    let body = to_bytes(feed_resp.into_body(), usize::MAX).await.unwrap();
+    let calendar = String::from_utf8(body.to_vec()).unwrap();
+    assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    assert!(calendar.contains("\r\nSUMMARY:Water the plants\\, then the lawn\r\n"));
+    assert!(calendar.contains("\r\nSTATUS:NEEDS-ACTION\r\n"));
+    assert!(calendar.contains("\r\nDUE;VALUE=DATE:20240305\r\n"));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn rejects_missing_and_revoked_tokens() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+    let user_id = create_user_with_task(&mut app).await;
+    let replaced_token = issue_token(&mut app, user_id).await;
+    let revoked_token = issue_token(&mut app, user_id).await;
+
+    let revoke_resp = app
+        .call(request(
+            Method::DELETE,
+            &format!("/users/{user_id}/feed-token"),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, revoke_resp.status());
+
+    for uri in [
+        format!("/users/{user_id}/tasks.ics"),
+        format!("/users/{user_id}/tasks.ics?token={replaced_token}"),
+        format!("/users/{user_id}/tasks.ics?token={revoked_token}"),
+    ] {
+        let feed_resp = app.call(request(Method::GET, &uri)).await.unwrap();
+        assert_eq!(StatusCode::UNAUTHORIZED, feed_resp.status());
+
+        let body: dto::BasicError = deserialize_body(feed_resp.into_body()).await;
+        assert_eq!("invalid_feed_token", body.error_code);
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn issuing_token_for_missing_user_returns_404() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+
+    let issue_resp = app
+        .call(request(Method::POST, "/users/1000/feed-token"))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, issue_resp.status());
+
+    let body: dto::BasicError = deserialize_body(issue_resp.into_body()).await;
+    assert_eq!("no_matching_user", body.error_code);
+}
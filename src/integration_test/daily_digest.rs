@@ -0,0 +1,166 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use chrono::{Days, Utc};
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::domain::digest::driving_ports::DigestPort;
+use crate::domain::digest::DigestRun;
+use crate::external_connections::with_transaction;
+use crate::outbound::smtp_client::test_util::MailCatcher;
+use crate::outbound::smtp_client::SmtpClient;
+use crate::outbound::smtp_digest_sender::SmtpDigestSender;
+use crate::persistence::db_digest_driven_ports::DbDigestStore;
+use crate::persistence::db_todo_driven_ports::DbTaskReader;
+use crate::{api, domain, dto, persistence};
+
+use super::test_util;
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn digest_is_emailed_once_a_day() {
+    let routes = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, db) = test_util::prepare_application(routes).await;
+
+    let mut user_ids = Vec::new();
+    for (first_name, last_name) in [("Dana", "Gest"), ("Eli", "Quiet")] {
+        let create_user_resp = app
+            .call(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/users")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(dto_to_body(&dto::NewUser {
+                        first_name: first_name.to_owned(),
+                        last_name: last_name.to_owned(),
+                    }))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+        user_ids.push(user.id);
+    }
+    let [user_id, quiet_user_id] = user_ids[..] else {
+        unreachable!();
+    };
+    let preferences_uri = format!("/users/{user_id}/digest-preferences");
+
+    let no_email_resp = app
+        .call(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&preferences_uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(dto_to_body(&dto::DigestPreferences {
+                    email: None,
+                    daily_digest: true,
+                }))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::BAD_REQUEST, no_email_resp.status());
+
+    let preferences = dto::DigestPreferences {
+        email: Some("dana@example.com".to_owned()),
+        daily_digest: true,
+    };
+    let set_resp = app
+        .call(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&preferences_uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(dto_to_body(&preferences))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, set_resp.status());
+
+    let get_resp = app
+        .call(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&preferences_uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let stored: dto::DigestPreferences = deserialize_body(get_resp.into_body()).await;
+    assert_eq!(preferences, stored);
+
+    // The quiet user has an address but hasn't asked for a digest
+    let quiet_resp = app
+        .call(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(format!("/users/{quiet_user_id}/digest-preferences"))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(dto_to_body(&dto::DigestPreferences {
+                    email: Some("eli@example.com".to_owned()),
+                    daily_digest: false,
+                }))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, quiet_resp.status());
+
+    let today = Utc::now().date_naive();
+    for (item_desc, due_date) in [
+        ("Pay rent", today.checked_sub_days(Days::new(1))),
+        ("Water plants", None),
+    ] {
+        let create_task_resp = app
+            .call(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/users/{user_id}/tasks"))
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(dto_to_body(&dto::NewTask {
+                        item_desc: item_desc.to_owned(),
+                        due_date,
+                        recurrence: None,
+                        assignee_user_id: None,
+                    }))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::CREATED, create_task_resp.status());
+    }
+
+    let catcher = MailCatcher::start().await;
+    let sender = SmtpDigestSender::new(SmtpClient::new(catcher.config()).unwrap());
+    let ext_cxn = persistence::ExternalConnectivity::new(db);
+    for expected_sent in [1, 0] {
+        let run = with_transaction(&ext_cxn, async |tx_cxn| {
+            domain::digest::DigestService
+                .send_due_digests(today, &mut *tx_cxn, &DbDigestStore, &DbTaskReader, &sender)
+                .await
+        })
+        .await
+        .expect("Sending digests failed");
+        assert_eq!(
+            DigestRun {
+                sent: expected_sent,
+                ..DigestRun::default()
+            },
+            run
+        );
+    }
+
+    let messages = catcher.messages();
+    let [message] = messages.as_slice() else {
+        panic!("Expected one email, got {messages:#?}");
+    };
+    assert_eq!(vec!["dana@example.com".to_owned()], message.to);
+    assert!(message.data.contains("2 open tasks, 1 overdue"));
+    let overdue_at = message.data.find("Overdue\r\n- Pay rent").unwrap();
+    let no_due_date_at = message.data.find("No due date\r\n- Water plants").unwrap();
+    assert!(overdue_at < no_due_date_at);
+}
@@ -0,0 +1,62 @@
+use axum::body::Body;
+use axum::http::{header, HeaderValue, Method, Request, StatusCode};
+use axum::Router;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::http_stack::CorsOrigins;
+use crate::api::test_util::deserialize_body;
+use crate::{api, dto};
+
+use super::test_util;
+
+fn test_router() -> Router<std::sync::Arc<crate::SharedData>> {
+    Router::new().nest("/users", api::user::user_routes())
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn rejects_large_bodies_as_basic_errors() {
+    let (mut app, _) = test_util::prepare_application_with(test_router(), |shared_data| {
+        shared_data.http.max_request_bytes = 32;
+    })
+    .await;
+
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            "{\"first_name\": \"Someone with a very long name\", \"last_name\": \"Doe\"}",
+        ))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, create_user_resp.status());
+
+    let body: dto::BasicError = deserialize_body(create_user_resp.into_body()).await;
+    assert_eq!("request_too_large", body.error_code);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn allows_configured_origins() {
+    let (mut app, _) = test_util::prepare_application_with(test_router(), |shared_data| {
+        shared_data.http.cors_origins =
+            CorsOrigins::List(vec![HeaderValue::from_static("https://example.com")]);
+    })
+    .await;
+
+    let get_users_req = Request::builder()
+        .method(Method::GET)
+        .uri("/users")
+        .header(header::ORIGIN, "https://example.com")
+        .body(Body::empty())
+        .unwrap();
+    let get_users_resp = app.call(get_users_req).await.unwrap();
+    assert_eq!(StatusCode::OK, get_users_resp.status());
+    assert_eq!(
+        Some(&HeaderValue::from_static("https://example.com")),
+        get_users_resp
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+    );
+}
@@ -0,0 +1,110 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::idempotency::{IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER};
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+fn create_user_request(key: &str, first_name: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(IDEMPOTENCY_KEY_HEADER, key)
+        .body(dto_to_body(&dto::NewUser {
+            first_name: first_name.to_owned(),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap()
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn retried_request_is_replayed() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+
+    let first_response = app
+        .call(create_user_request("retry-me", "John"))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, first_response.status());
+    let first_user: dto::InsertedUser = deserialize_body(first_response.into_body()).await;
+
+    let retried_response = app
+        .call(create_user_request("retry-me", "John"))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, retried_response.status());
+    assert_eq!(
+        "true",
+        retried_response.headers()[IDEMPOTENT_REPLAYED_HEADER]
+    );
+    let retried_user: dto::InsertedUser = deserialize_body(retried_response.into_body()).await;
+    assert_eq!(first_user.id, retried_user.id);
+
+    let list_users_req = Request::builder()
+        .method(Method::GET)
+        .uri("/users")
+        .body(Body::empty())
+        .unwrap();
+    let list_users_resp = app.call(list_users_req).await.unwrap();
+    let users: Vec<dto::TodoUser> = deserialize_body(list_users_resp.into_body()).await;
+    assert_eq!(1, users.len());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn reused_key_with_different_body_is_rejected() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+
+    let first_response = app
+        .call(create_user_request("reused", "John"))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, first_response.status());
+
+    let reused_response = app
+        .call(create_user_request("reused", "Jane"))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, reused_response.status());
+    let error: dto::BasicError = deserialize_body(reused_response.into_body()).await;
+    assert_eq!("idempotency_key_reused", error.error_code);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn concurrent_requests_with_the_same_key_run_once() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+
+    let mut other_app = app.clone();
+    let (first_response, second_response) = tokio::join!(
+        app.call(create_user_request("racing", "John")),
+        other_app.call(create_user_request("racing", "John")),
+    );
+    // The request which lost the race either saw the other one in progress or had its response replayed
+    let replayed_or_in_progress = [first_response.unwrap(), second_response.unwrap()]
+        .iter()
+        .filter(|response| {
+            response.status() == StatusCode::CONFLICT
+                || response.headers().contains_key(IDEMPOTENT_REPLAYED_HEADER)
+        })
+        .count();
+    assert_eq!(1, replayed_or_in_progress);
+
+    let list_users_req = Request::builder()
+        .method(Method::GET)
+        .uri("/users")
+        .body(Body::empty())
+        .unwrap();
+    let list_users_resp = app.call(list_users_req).await.unwrap();
+    let users: Vec<dto::TodoUser> = deserialize_body(list_users_resp.into_body()).await;
+    assert_eq!(1, users.len());
+}
@@ -1,2 +1,24 @@
+mod access_control;
+mod audit;
+mod calendar_feed;
+mod daily_digest;
+mod http_stack;
+mod idempotency;
+mod rate_limit;
+mod recurring_tasks;
+mod task_assignment;
+mod task_attachments;
+mod task_batch;
+mod task_comments;
+mod task_dependencies;
+mod task_events;
+mod task_reminders;
+mod task_search;
+mod task_sharing;
+mod task_socket;
+mod task_transfer;
+mod task_trash;
 mod test_util;
+mod transaction;
 mod user_api;
+mod webhook;
@@ -0,0 +1,181 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use std::collections::HashMap;
+use std::time::Duration;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::rate_limit::{RATE_LIMIT_LIMIT_HEADER, RATE_LIMIT_REMAINING_HEADER};
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::domain::rate_limit::{RateLimit, RateLimits};
+use crate::routing_utils::CALLING_USER_HEADER;
+use crate::{api, dto};
+
+use super::test_util;
+
+fn test_router() -> Router<std::sync::Arc<crate::SharedData>> {
+    Router::new().nest("/users", api::user::user_routes())
+}
+
+/// Limits task creation to two requests a minute and everything else to five
+fn limits() -> RateLimits {
+    RateLimits {
+        default: Some(RateLimit {
+            capacity: 5,
+            period: Duration::from_secs(60),
+        }),
+        routes: HashMap::from([(
+            "POST /users/:user_id/tasks".to_owned(),
+            RateLimit {
+                capacity: 2,
+                period: Duration::from_secs(60),
+            },
+        )]),
+    }
+}
+
+async fn create_user(app: &mut Router) -> i32 {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    user.id
+}
+
+fn create_task_request(user_id: i32, calling_user: Option<i32>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/users/{user_id}/tasks"))
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(calling_user) = calling_user {
+        builder = builder.header(CALLING_USER_HEADER, calling_user);
+    }
+
+    builder
+        .body(dto_to_body(&dto::NewTask {
+            item_desc: "Something to do".to_owned(),
+            due_date: None,
+            recurrence: None,
+            assignee_user_id: None,
+        }))
+        .unwrap()
+}
+
+async fn limits_task_creation_per_client(share_rate_limits: bool) {
+    let (mut app, _) = test_util::prepare_application_with(test_router(), |shared_data| {
+        shared_data.rate_limits = limits();
+        shared_data.share_rate_limits = share_rate_limits;
+        // Requests are only counted against a user once access control has verified who they're from
+        shared_data.access_control = true;
+    })
+    .await;
+    let user_id = create_user(&mut app).await;
+    let other_user_id = create_user(&mut app).await;
+
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        let create_task_resp = app
+            .call(create_task_request(user_id, Some(user_id)))
+            .await
+            .unwrap();
+        statuses.push(create_task_resp.status());
+        if create_task_resp.status() == StatusCode::TOO_MANY_REQUESTS {
+            assert!(create_task_resp.headers().contains_key(header::RETRY_AFTER));
+            assert_eq!(
+                Some("0"),
+                create_task_resp
+                    .headers()
+                    .get(RATE_LIMIT_REMAINING_HEADER)
+                    .and_then(|value| value.to_str().ok())
+            );
+
+            let body: dto::BasicError = deserialize_body(create_task_resp.into_body()).await;
+            assert_eq!("rate_limited", body.error_code);
+        }
+    }
+    assert_eq!(
+        vec![
+            StatusCode::CREATED,
+            StatusCode::CREATED,
+            StatusCode::TOO_MANY_REQUESTS
+        ],
+        statuses
+    );
+
+    // Other users and other routes have their own buckets
+    let other_client_resp = app
+        .call(create_task_request(other_user_id, Some(other_user_id)))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, other_client_resp.status());
+
+    let get_tasks_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/users/{user_id}/tasks"))
+        .header(CALLING_USER_HEADER, user_id)
+        .body(Body::empty())
+        .unwrap();
+    let get_tasks_resp = app.call(get_tasks_req).await.unwrap();
+    assert_eq!(StatusCode::OK, get_tasks_resp.status());
+    assert_eq!(
+        Some("5"),
+        get_tasks_resp
+            .headers()
+            .get(RATE_LIMIT_LIMIT_HEADER)
+            .and_then(|value| value.to_str().ok())
+    );
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn limits_requests_in_memory() {
+    limits_task_creation_per_client(false).await;
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn limits_requests_in_database() {
+    limits_task_creation_per_client(true).await;
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn database_buckets_are_shared_between_instances() {
+    let (mut first_app, db) = test_util::prepare_application_with(test_router(), |shared_data| {
+        shared_data.rate_limits = limits();
+        shared_data.share_rate_limits = true;
+        shared_data.access_control = true;
+    })
+    .await;
+    let user_id = create_user(&mut first_app).await;
+
+    let shared_data = crate::SharedData {
+        rate_limits: limits(),
+        share_rate_limits: true,
+        access_control: true,
+        ..crate::SharedData::new(crate::persistence::ExternalConnectivity::new(db))
+    };
+    let mut second_app = crate::build_app(test_router(), std::sync::Arc::new(shared_data));
+
+    for app in [&mut first_app, &mut second_app] {
+        let create_task_resp = app
+            .call(create_task_request(user_id, Some(user_id)))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::CREATED, create_task_resp.status());
+    }
+
+    let create_task_resp = first_app
+        .call(create_task_request(user_id, Some(user_id)))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::TOO_MANY_REQUESTS, create_task_resp.status());
+}
@@ -0,0 +1,177 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use chrono::NaiveDate;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+fn test_router() -> Router<std::sync::Arc<crate::SharedData>> {
+    Router::new()
+        .nest("/users", api::user::user_routes())
+        .nest("/tasks", api::todo::task_routes())
+}
+
+fn request(method: Method, uri: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn create_task_request(user_id: i32, new_task: &dto::NewTask) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(format!("/users/{user_id}/tasks"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(new_task))
+        .unwrap()
+}
+
+async fn create_user(app: &mut Router) -> i32 {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+    user.id
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn completing_recurring_task_schedules_next_occurrence() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+    let user_id = create_user(&mut app).await;
+
+    let create_task_resp = app
+        .call(create_task_request(
+            user_id,
+            &dto::NewTask {
+                item_desc: "Take out the trash".to_owned(),
+                due_date: NaiveDate::from_ymd_opt(2024, 3, 4),
+                recurrence: Some("FREQ=WEEKLY;BYDAY=MO,TH".to_owned()),
+                assignee_user_id: None,
+            },
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, create_task_resp.status());
+    let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+
+    let preview_resp = app
+        .call(request(
+            Method::GET,
+            &format!("/users/{user_id}/tasks/{}/occurrences?count=3", task.id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, preview_resp.status());
+    let occurrences: Vec<NaiveDate> = deserialize_body(preview_resp.into_body()).await;
+    assert_eq!(
+        vec![
+            NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 7).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 11).unwrap(),
+        ],
+        occurrences
+    );
+
+    let complete_resp = app
+        .call(request(
+            Method::POST,
+            &format!("/tasks/{}/complete", task.id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, complete_resp.status());
+    let completed: dto::CompletedTask = deserialize_body(complete_resp.into_body()).await;
+    let next_occurrence = completed
+        .next_occurrence
+        .expect("Completing a recurring task didn't schedule the next occurrence");
+    assert_eq!(
+        NaiveDate::from_ymd_opt(2024, 3, 7),
+        next_occurrence.due_date
+    );
+    assert_eq!(
+        Some("FREQ=WEEKLY;BYDAY=MO,TH"),
+        next_occurrence.recurrence.as_deref()
+    );
+
+    let tasks_resp = app
+        .call(request(Method::GET, &format!("/users/{user_id}/tasks")))
+        .await
+        .unwrap();
+    let tasks: Vec<dto::TodoTask> = deserialize_body(tasks_resp.into_body()).await;
+    assert!(matches!(tasks.as_slice(), [
+        dto::TodoTask { id: first_id, completed: true, .. },
+        dto::TodoTask { id: next_id, completed: false, .. },
+    ] if *first_id == task.id && *next_id == next_occurrence.id));
+
+    let complete_again_resp = app
+        .call(request(
+            Method::POST,
+            &format!("/tasks/{}/complete", task.id),
+        ))
+        .await
+        .unwrap();
+    let completed_again: dto::CompletedTask =
+        deserialize_body(complete_again_resp.into_body()).await;
+    assert!(completed_again.next_occurrence.is_none());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn rejects_invalid_recurrence() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+    let user_id = create_user(&mut app).await;
+
+    let invalid_tasks = [
+        dto::NewTask {
+            item_desc: "Take out the trash".to_owned(),
+            due_date: NaiveDate::from_ymd_opt(2024, 3, 4),
+            recurrence: Some("FREQ=FORTNIGHTLY".to_owned()),
+            assignee_user_id: None,
+        },
+        dto::NewTask {
+            item_desc: "Take out the trash".to_owned(),
+            due_date: None,
+            recurrence: Some("FREQ=WEEKLY".to_owned()),
+            assignee_user_id: None,
+        },
+    ];
+    for new_task in &invalid_tasks {
+        let create_task_resp = app
+            .call(create_task_request(user_id, new_task))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::BAD_REQUEST, create_task_resp.status());
+
+        let body: dto::BasicError = deserialize_body(create_task_resp.into_body()).await;
+        assert_eq!("invalid_input", body.error_code);
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn completing_missing_task_returns_404() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+
+    let complete_resp = app
+        .call(request(Method::POST, "/tasks/1000/complete"))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, complete_resp.status());
+
+    let body: dto::BasicError = deserialize_body(complete_resp.into_body()).await;
+    assert_eq!("no_matching_task", body.error_code);
+}
@@ -0,0 +1,181 @@
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+async fn prepare_access_controlled_app() -> Router {
+    let routes = Router::new()
+        .nest("/users", api::user::user_routes())
+        .nest("/tasks", api::todo::task_routes());
+    let (app, _) = test_util::prepare_application_with(routes, |shared_data| {
+        shared_data.access_control = true;
+    })
+    .await;
+
+    app
+}
+
+async fn create_user(app: &mut Router, first_name: &str) -> i32 {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: first_name.to_owned(),
+            last_name: String::from("Assignment"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    user.id
+}
+
+async fn assigned_task_ids(app: &mut Router, user_id: i32) -> Vec<i32> {
+    let assigned_resp = app
+        .call(test_util::request_as(
+            Method::GET,
+            format!("/users/{user_id}/assigned-tasks"),
+            user_id,
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, assigned_resp.status());
+    let assigned_tasks: Vec<dto::TodoTask> = deserialize_body(assigned_resp.into_body()).await;
+
+    assigned_tasks.into_iter().map(|task| task.id).collect()
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn assignees_work_on_tasks_created_for_them() {
+    let mut app = prepare_access_controlled_app().await;
+    let creator_id = create_user(&mut app, "Carla").await;
+    let assignee_id = create_user(&mut app, "Arnold").await;
+
+    let create_task_resp = app
+        .call(test_util::request_as(
+            Method::POST,
+            format!("/users/{creator_id}/tasks"),
+            creator_id,
+            Some(&dto::NewTask {
+                item_desc: "Book the venue".to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: Some(assignee_id),
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, create_task_resp.status());
+    let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+
+    assert_eq!(
+        vec![task.id],
+        assigned_task_ids(&mut app, assignee_id).await
+    );
+    assert!(assigned_task_ids(&mut app, creator_id).await.is_empty());
+
+    let task_resp = app
+        .call(test_util::request_as(
+            Method::GET,
+            format!("/users/{assignee_id}/tasks/{}", task.id),
+            assignee_id,
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, task_resp.status());
+    let assigned_task: dto::TodoTask = deserialize_body(task_resp.into_body()).await;
+    assert_eq!(Some(assignee_id), assigned_task.assignee_user_id);
+
+    let complete_resp = app
+        .call(test_util::request_as(
+            Method::POST,
+            format!("/tasks/{}/complete", task.id),
+            assignee_id,
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, complete_resp.status());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn reassigning_moves_task_between_users() {
+    let mut app = prepare_access_controlled_app().await;
+    let creator_id = create_user(&mut app, "Carmen").await;
+    let assignee_id = create_user(&mut app, "Alfred").await;
+
+    let create_task_resp = app
+        .call(test_util::request_as(
+            Method::POST,
+            format!("/users/{creator_id}/tasks"),
+            creator_id,
+            Some(&dto::NewTask {
+                item_desc: "Order the catering".to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+            }),
+        ))
+        .await
+        .unwrap();
+    let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+
+    for (assignee_user_id, expected_status) in [
+        (Some(-1), StatusCode::NOT_FOUND),
+        (Some(assignee_id), StatusCode::OK),
+    ] {
+        let assign_resp = app
+            .call(test_util::request_as(
+                Method::PUT,
+                format!("/tasks/{}/assignee", task.id),
+                creator_id,
+                Some(&dto::TaskAssignment { assignee_user_id }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(expected_status, assign_resp.status());
+    }
+    assert_eq!(
+        vec![task.id],
+        assigned_task_ids(&mut app, assignee_id).await
+    );
+
+    // The assignee can hand the task back, after which it's no longer theirs to change
+    let unassign_resp = app
+        .call(test_util::request_as(
+            Method::PUT,
+            format!("/tasks/{}/assignee", task.id),
+            assignee_id,
+            Some(&dto::TaskAssignment {
+                assignee_user_id: None,
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, unassign_resp.status());
+    let unassigned_task: dto::TodoTask = deserialize_body(unassign_resp.into_body()).await;
+    assert_eq!(None, unassigned_task.assignee_user_id);
+    assert!(assigned_task_ids(&mut app, assignee_id).await.is_empty());
+
+    let update_resp = app
+        .call(test_util::request_as(
+            Method::PATCH,
+            format!("/tasks/{}", task.id),
+            assignee_id,
+            Some(&dto::UpdateTask {
+                description: "Order pizza instead".to_owned(),
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::FORBIDDEN, update_resp.status());
+}
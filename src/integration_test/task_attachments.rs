@@ -0,0 +1,169 @@
+use axum::body::{self, Body};
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::outbound::blob_storage::BlobStorage;
+use crate::outbound::local_blob_store::LocalBlobStore;
+use crate::{api, domain, dto};
+
+use super::test_util;
+
+const BOUNDARY: &str = "task-attachments-boundary";
+
+/// Prepares an app which keeps attached files in a fresh temporary directory and only accepts small images
+async fn prepare_app() -> Router {
+    let mut dir_name = [0u8; 8];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut dir_name);
+    let blob_dir =
+        std::env::temp_dir().join(format!("task-attachments-test-{}", hex::encode(dir_name)));
+
+    let routes = Router::new().nest("/users", api::user::user_routes());
+    let (app, _) = test_util::prepare_application_with(routes, |shared_data| {
+        shared_data.ext_cxn = shared_data
+            .ext_cxn
+            .clone()
+            .with_blob_storage(BlobStorage::Local(LocalBlobStore::new(blob_dir)));
+        shared_data.attachment_limits = domain::attachment::AttachmentLimits {
+            max_bytes: 32,
+            content_types: vec!["image/*".to_owned()],
+        };
+    })
+    .await;
+
+    app
+}
+
+/// Builds a multipart upload of a single file
+fn upload_request(
+    uri: String,
+    file_name: &str,
+    content_type: &str,
+    content: &str,
+) -> Request<Body> {
+    let body = format!(
+        "--{BOUNDARY}\r\n\
+        Content-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\n\
+        Content-Type: {content_type}\r\n\r\n\
+        {content}\r\n\
+        --{BOUNDARY}--\r\n"
+    );
+
+    Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        )
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn attach_download_and_delete_files() {
+    let mut app = prepare_app().await;
+
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("Ada"),
+            last_name: String::from("Attachments"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    let create_task_req = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/users/{}/tasks", user.id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewTask {
+            item_desc: "File the expense report".to_owned(),
+            due_date: None,
+            recurrence: None,
+            assignee_user_id: None,
+        }))
+        .unwrap();
+    let create_task_resp = app.call(create_task_req).await.unwrap();
+    let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+    let attachments_uri = format!("/users/{}/tasks/{}/attachments", user.id, task.id);
+
+    let upload_resp = app
+        .call(upload_request(
+            attachments_uri.clone(),
+            "receipt.png",
+            "image/png",
+            "not really a png",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, upload_resp.status());
+    let attachment: dto::TaskAttachment = deserialize_body(upload_resp.into_body()).await;
+    assert_eq!("receipt.png", attachment.file_name);
+    assert_eq!(16, attachment.size_bytes);
+
+    // Files which break the limits are turned away
+    let too_large_resp = app
+        .call(upload_request(
+            attachments_uri.clone(),
+            "huge.png",
+            "image/png",
+            &"x".repeat(33),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, too_large_resp.status());
+    let wrong_type_resp = app
+        .call(upload_request(
+            attachments_uri.clone(),
+            "notes.txt",
+            "text/plain",
+            "Just some notes",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::UNSUPPORTED_MEDIA_TYPE, wrong_type_resp.status());
+
+    let list_req = Request::builder()
+        .uri(attachments_uri.clone())
+        .body(Body::empty())
+        .unwrap();
+    let list_resp = app.call(list_req).await.unwrap();
+    let attachments: Vec<dto::TaskAttachment> = deserialize_body(list_resp.into_body()).await;
+    assert_eq!(
+        vec![attachment.id],
+        attachments.iter().map(|a| a.id).collect::<Vec<_>>()
+    );
+
+    let download_req = Request::builder()
+        .uri(format!("{attachments_uri}/{}", attachment.id))
+        .body(Body::empty())
+        .unwrap();
+    let download_resp = app.call(download_req).await.unwrap();
+    assert_eq!(StatusCode::OK, download_resp.status());
+    assert_eq!("image/png", download_resp.headers()[header::CONTENT_TYPE]);
+    let downloaded = body::to_bytes(download_resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(b"not really a png".as_slice(), downloaded.as_ref());
+
+    let delete_req = Request::builder()
+        .method(Method::DELETE)
+        .uri(format!("{attachments_uri}/{}", attachment.id))
+        .body(Body::empty())
+        .unwrap();
+    let delete_resp = app.call(delete_req).await.unwrap();
+    assert_eq!(StatusCode::OK, delete_resp.status());
+
+    let missing_req = Request::builder()
+        .uri(format!("{attachments_uri}/{}", attachment.id))
+        .body(Body::empty())
+        .unwrap();
+    let missing_resp = app.call(missing_req).await.unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, missing_resp.status());
+}
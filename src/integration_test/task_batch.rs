@@ -0,0 +1,178 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+async fn create_user(app: &mut Router) -> i32 {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let response = app.call(create_user_req).await.unwrap();
+    assert_eq!(StatusCode::CREATED, response.status());
+
+    let new_user: dto::InsertedUser = deserialize_body(response.into_body()).await;
+    new_user.id
+}
+
+fn batch_request(user_id: i32, batch: &dto::TaskBatch) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(format!("/users/{user_id}/tasks/batch"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(batch))
+        .unwrap()
+}
+
+async fn tasks_for_user(app: &mut Router, user_id: i32) -> Vec<dto::TodoTask> {
+    let list_tasks_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/users/{user_id}/tasks"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.call(list_tasks_req).await.unwrap();
+    assert_eq!(StatusCode::OK, response.status());
+
+    deserialize_body(response.into_body()).await
+}
+
+fn create_operation(description: &str) -> dto::TaskBatchOperation {
+    dto::TaskBatchOperation::Create(dto::NewTask {
+        item_desc: description.to_owned(),
+        due_date: None,
+        recurrence: None,
+        assignee_user_id: None,
+    })
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn atomic_batch_applies_all_operations() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+    let user_id = create_user(&mut app).await;
+
+    let batch = dto::TaskBatch {
+        mode: dto::BatchMode::Atomic,
+        operations: vec![
+            create_operation("Something to do"),
+            create_operation("Another thing to do"),
+        ],
+    };
+    let response = app.call(batch_request(user_id, &batch)).await.unwrap();
+    assert_eq!(StatusCode::OK, response.status());
+    let batch_result: dto::TaskBatchResult = deserialize_body(response.into_body()).await;
+    assert!(batch_result.committed);
+    let [dto::TaskBatchOperationResult::Succeeded { task_id: first_id }, dto::TaskBatchOperationResult::Succeeded { task_id: second_id }] =
+        batch_result.results.as_slice()
+    else {
+        panic!("Unexpected batch results: {:#?}", batch_result.results);
+    };
+
+    let batch = dto::TaskBatch {
+        mode: dto::BatchMode::Atomic,
+        operations: vec![
+            dto::TaskBatchOperation::Update(dto::TaskBatchUpdate {
+                task_id: *first_id,
+                description: "Something else to do".to_owned(),
+            }),
+            dto::TaskBatchOperation::Complete { task_id: *first_id },
+            dto::TaskBatchOperation::Delete {
+                task_id: *second_id,
+            },
+        ],
+    };
+    let response = app.call(batch_request(user_id, &batch)).await.unwrap();
+    let batch_result: dto::TaskBatchResult = deserialize_body(response.into_body()).await;
+    assert!(batch_result.committed);
+
+    let tasks = tasks_for_user(&mut app, user_id).await;
+    assert!(matches!(tasks.as_slice(), [
+        dto::TodoTask {
+            id,
+            description,
+            completed: true,
+            due_date: None,
+            recurrence: None,
+            assignee_user_id: None,
+            comment_count: 0,
+            blocked: false,
+        }
+    ] if id == first_id && description == "Something else to do"));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn failed_atomic_batch_saves_nothing() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+    let user_id = create_user(&mut app).await;
+
+    let batch = dto::TaskBatch {
+        mode: dto::BatchMode::Atomic,
+        operations: vec![
+            create_operation("Something to do"),
+            dto::TaskBatchOperation::Complete { task_id: 9999 },
+            create_operation("Another thing to do"),
+        ],
+    };
+    let response = app.call(batch_request(user_id, &batch)).await.unwrap();
+    assert_eq!(StatusCode::OK, response.status());
+
+    let batch_result: dto::TaskBatchResult = deserialize_body(response.into_body()).await;
+    assert!(!batch_result.committed);
+    assert!(matches!(
+        batch_result.results.as_slice(),
+        [
+            dto::TaskBatchOperationResult::RolledBack,
+            dto::TaskBatchOperationResult::Failed { error },
+            dto::TaskBatchOperationResult::Skipped,
+        ] if error.error_code == "no_matching_task"
+    ));
+
+    let tasks = tasks_for_user(&mut app, user_id).await;
+    assert!(tasks.is_empty());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn best_effort_batch_keeps_successful_operations() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+    let user_id = create_user(&mut app).await;
+
+    let batch = dto::TaskBatch {
+        mode: dto::BatchMode::BestEffort,
+        operations: vec![
+            create_operation("Something to do"),
+            dto::TaskBatchOperation::Delete { task_id: 9999 },
+            create_operation("Another thing to do"),
+        ],
+    };
+    let response = app.call(batch_request(user_id, &batch)).await.unwrap();
+    assert_eq!(StatusCode::OK, response.status());
+
+    let batch_result: dto::TaskBatchResult = deserialize_body(response.into_body()).await;
+    assert!(batch_result.committed);
+    assert!(matches!(
+        batch_result.results.as_slice(),
+        [
+            dto::TaskBatchOperationResult::Succeeded { .. },
+            dto::TaskBatchOperationResult::Failed { error },
+            dto::TaskBatchOperationResult::Succeeded { .. },
+        ] if error.error_code == "no_matching_task"
+    ));
+
+    let tasks = tasks_for_user(&mut app, user_id).await;
+    assert_eq!(2, tasks.len());
+}
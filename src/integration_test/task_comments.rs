@@ -0,0 +1,224 @@
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+async fn prepare_access_controlled_app() -> Router {
+    let routes = Router::new().nest("/users", api::user::user_routes());
+    let (app, _) = test_util::prepare_application_with(routes, |shared_data| {
+        shared_data.access_control = true;
+    })
+    .await;
+
+    app
+}
+
+async fn create_user(app: &mut Router, first_name: &str) -> i32 {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: first_name.to_owned(),
+            last_name: String::from("Comments"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    user.id
+}
+
+async fn comment(
+    app: &mut Router,
+    user_id: i32,
+    task_id: i32,
+    body: &str,
+    parent_comment_id: Option<i32>,
+) -> dto::TaskComment {
+    let comment_resp = app
+        .call(test_util::request_as(
+            Method::POST,
+            format!("/users/{user_id}/tasks/{task_id}/comments"),
+            user_id,
+            Some(&dto::NewComment {
+                body: body.to_owned(),
+                parent_comment_id,
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, comment_resp.status());
+
+    deserialize_body(comment_resp.into_body()).await
+}
+
+async fn list_comments(
+    app: &mut Router,
+    user_id: i32,
+    task_id: i32,
+    query: &str,
+) -> Vec<dto::TaskComment> {
+    let list_resp = app
+        .call(test_util::request_as(
+            Method::GET,
+            format!("/users/{user_id}/tasks/{task_id}/comments{query}"),
+            user_id,
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, list_resp.status());
+
+    deserialize_body(list_resp.into_body()).await
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn users_discuss_shared_tasks() {
+    let mut app = prepare_access_controlled_app().await;
+    let owner_id = create_user(&mut app, "Olive").await;
+    let teammate_id = create_user(&mut app, "Theo").await;
+
+    let create_task_resp = app
+        .call(test_util::request_as(
+            Method::POST,
+            format!("/users/{owner_id}/tasks"),
+            owner_id,
+            Some(&dto::NewTask {
+                item_desc: "Plan the offsite".to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+            }),
+        ))
+        .await
+        .unwrap();
+    let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+
+    // The teammate can't see the task until it's shared with them
+    let hidden_resp = app
+        .call(test_util::request_as(
+            Method::POST,
+            format!("/users/{teammate_id}/tasks/{}/comments", task.id),
+            teammate_id,
+            Some(&dto::NewComment {
+                body: "Hello?".to_owned(),
+                parent_comment_id: None,
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, hidden_resp.status());
+
+    let share_resp = app
+        .call(test_util::request_as(
+            Method::POST,
+            format!("/users/{owner_id}/shares"),
+            owner_id,
+            Some(&dto::NewShare {
+                shared_with_user_id: teammate_id,
+                task_id: Some(task.id),
+                permission: dto::SharePermission::Read,
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, share_resp.status());
+
+    let question = comment(&mut app, owner_id, task.id, "Any venue ideas?", None).await;
+    let answer = comment(
+        &mut app,
+        teammate_id,
+        task.id,
+        "The lake house",
+        Some(question.id),
+    )
+    .await;
+    assert_eq!(teammate_id, answer.author_user_id);
+    assert_eq!(Some(question.id), answer.parent_comment_id);
+
+    let first_page = list_comments(&mut app, teammate_id, task.id, "?limit=1").await;
+    assert_eq!(
+        vec![question.id],
+        first_page.iter().map(|c| c.id).collect::<Vec<_>>()
+    );
+    let second_page = list_comments(
+        &mut app,
+        teammate_id,
+        task.id,
+        &format!("?after_id={}", question.id),
+    )
+    .await;
+    assert_eq!(
+        vec![answer.id],
+        second_page.iter().map(|c| c.id).collect::<Vec<_>>()
+    );
+
+    let task_resp = app
+        .call(test_util::request_as(
+            Method::GET,
+            format!("/users/{owner_id}/tasks/{}", task.id),
+            owner_id,
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    let fetched_task: dto::TodoTask = deserialize_body(task_resp.into_body()).await;
+    assert_eq!(2, fetched_task.comment_count);
+
+    // Only the author may edit a comment
+    let owner_edit_resp = app
+        .call(test_util::request_as(
+            Method::PATCH,
+            format!("/users/{owner_id}/tasks/{}/comments/{}", task.id, answer.id),
+            owner_id,
+            Some(&dto::UpdateComment {
+                body: "The beach".to_owned(),
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::FORBIDDEN, owner_edit_resp.status());
+
+    let edit_resp = app
+        .call(test_util::request_as(
+            Method::PATCH,
+            format!(
+                "/users/{teammate_id}/tasks/{}/comments/{}",
+                task.id, answer.id
+            ),
+            teammate_id,
+            Some(&dto::UpdateComment {
+                body: "The lake house, or the beach".to_owned(),
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, edit_resp.status());
+    let edited: dto::TaskComment = deserialize_body(edit_resp.into_body()).await;
+    assert_eq!("The lake house, or the beach", edited.body);
+    assert!(edited.updated_at.is_some());
+
+    // Deleting the question takes the reply with it
+    let delete_resp = app
+        .call(test_util::request_as(
+            Method::DELETE,
+            format!(
+                "/users/{owner_id}/tasks/{}/comments/{}",
+                task.id, question.id
+            ),
+            owner_id,
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, delete_resp.status());
+    assert!(list_comments(&mut app, owner_id, task.id, "")
+        .await
+        .is_empty());
+}
@@ -0,0 +1,192 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use serde::Serialize;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+/// Builds a request, with a JSON body if one is given
+fn request(method: Method, uri: String, body: Option<&impl Serialize>) -> Request<Body> {
+    let builder = Request::builder().method(method).uri(uri);
+
+    match body {
+        Some(body) => builder
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(dto_to_body(body))
+            .unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
+
+async fn create_task(app: &mut Router, user_id: i32, description: &str) -> i32 {
+    let create_task_resp = app
+        .call(request(
+            Method::POST,
+            format!("/users/{user_id}/tasks"),
+            Some(&dto::NewTask {
+                item_desc: description.to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+            }),
+        ))
+        .await
+        .unwrap();
+    let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+
+    task.id
+}
+
+async fn add_dependency(
+    app: &mut Router,
+    user_id: i32,
+    task_id: i32,
+    blocked_by_task_id: i32,
+) -> StatusCode {
+    let add_resp = app
+        .call(request(
+            Method::POST,
+            format!("/users/{user_id}/tasks/{task_id}/dependencies"),
+            Some(&dto::NewTaskDependency { blocked_by_task_id }),
+        ))
+        .await
+        .unwrap();
+
+    add_resp.status()
+}
+
+async fn complete_task(app: &mut Router, task_id: i32) -> StatusCode {
+    let complete_resp = app
+        .call(request(
+            Method::POST,
+            format!("/tasks/{task_id}/complete"),
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+
+    complete_resp.status()
+}
+
+async fn ordered_tasks(app: &mut Router, user_id: i32) -> Vec<dto::TodoTask> {
+    let ordered_resp = app
+        .call(request(
+            Method::GET,
+            format!("/users/{user_id}/tasks/ordered"),
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, ordered_resp.status());
+
+    deserialize_body(ordered_resp.into_body()).await
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn blocked_tasks_wait_for_their_blockers() {
+    let routes = Router::new()
+        .nest("/users", api::user::user_routes())
+        .nest("/tasks", api::todo::task_routes());
+    let (mut app, _) = test_util::prepare_application(routes).await;
+
+    let create_user_resp = app
+        .call(request(
+            Method::POST,
+            "/users".to_owned(),
+            Some(&dto::NewUser {
+                first_name: String::from("Dee"),
+                last_name: String::from("Pendency"),
+            }),
+        ))
+        .await
+        .unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    let paint = create_task(&mut app, user.id, "Paint the fence").await;
+    let buy_paint = create_task(&mut app, user.id, "Buy paint").await;
+    let sand = create_task(&mut app, user.id, "Sand the fence").await;
+
+    assert_eq!(
+        StatusCode::NO_CONTENT,
+        add_dependency(&mut app, user.id, paint, sand).await
+    );
+    assert_eq!(
+        StatusCode::NO_CONTENT,
+        add_dependency(&mut app, user.id, sand, buy_paint).await
+    );
+    // Buying paint can't wait on painting, since painting already waits on buying paint
+    assert_eq!(
+        StatusCode::CONFLICT,
+        add_dependency(&mut app, user.id, buy_paint, paint).await
+    );
+
+    let tasks = ordered_tasks(&mut app, user.id).await;
+    assert_eq!(
+        vec![(buy_paint, false), (sand, true), (paint, true)],
+        tasks
+            .iter()
+            .map(|task| (task.id, task.blocked))
+            .collect::<Vec<_>>()
+    );
+
+    assert_eq!(StatusCode::CONFLICT, complete_task(&mut app, paint).await);
+    assert_eq!(StatusCode::OK, complete_task(&mut app, buy_paint).await);
+    assert_eq!(StatusCode::OK, complete_task(&mut app, sand).await);
+    assert_eq!(StatusCode::OK, complete_task(&mut app, paint).await);
+
+    let remove_resp = app
+        .call(request(
+            Method::DELETE,
+            format!("/users/{}/tasks/{paint}/dependencies/{sand}", user.id),
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::NO_CONTENT, remove_resp.status());
+    let remove_again_resp = app
+        .call(request(
+            Method::DELETE,
+            format!("/users/{}/tasks/{paint}/dependencies/{sand}", user.id),
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, remove_again_resp.status());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn concurrent_dependencies_cannot_form_a_cycle() {
+    let routes = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(routes).await;
+
+    let create_user_resp = app
+        .call(request(
+            Method::POST,
+            "/users".to_owned(),
+            Some(&dto::NewUser {
+                first_name: String::from("Con"),
+                last_name: String::from("Current"),
+            }),
+        ))
+        .await
+        .unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+    let paint = create_task(&mut app, user.id, "Paint the fence").await;
+    let sand = create_task(&mut app, user.id, "Sand the fence").await;
+
+    // Each dependency is fine alone, but together they would block both tasks forever
+    let mut other_app = app.clone();
+    let (paint_status, sand_status) = tokio::join!(
+        add_dependency(&mut app, user.id, paint, sand),
+        add_dependency(&mut other_app, user.id, sand, paint),
+    );
+    let mut statuses = vec![paint_status, sand_status];
+    statuses.sort();
+    assert_eq!(vec![StatusCode::NO_CONTENT, StatusCode::CONFLICT], statuses);
+}
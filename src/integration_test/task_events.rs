@@ -0,0 +1,141 @@
+use axum::body::{Body, BodyDataStream};
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::time::Duration;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+/// Reads the next event from an SSE response body as a map of its fields, skipping keep-alive comments
+async fn next_event(body: &mut BodyDataStream, buffer: &mut String) -> HashMap<String, String> {
+    loop {
+        if let Some((event, rest)) = buffer.split_once("\n\n") {
+            let event: HashMap<String, String> = event
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .filter(|(field, _)| !field.is_empty())
+                .map(|(field, value)| (field.to_owned(), value.trim_start().to_owned()))
+                .collect();
+            *buffer = rest.to_owned();
+            if event.is_empty() {
+                continue;
+            }
+
+            return event;
+        }
+
+        let chunk = tokio::time::timeout(Duration::from_secs(5), body.next())
+            .await
+            .expect("Timed out waiting for a task event")
+            .expect("Task event stream ended")
+            .expect("Could not read task event stream");
+        buffer.push_str(std::str::from_utf8(&chunk).expect("Event stream wasn't UTF-8"));
+    }
+}
+
+fn open_stream_request(user_id: i32, last_event_id: Option<&str>) -> Request<Body> {
+    let mut request = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/users/{user_id}/tasks/events"));
+    if let Some(last_event_id) = last_event_id {
+        request = request.header("Last-Event-ID", last_event_id);
+    }
+
+    request.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn streams_task_changes_and_resumes_after_last_event() {
+    let router = Router::new()
+        .nest("/users", api::user::user_routes())
+        .nest("/tasks", api::todo::task_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    let stream_resp = app.call(open_stream_request(user.id, None)).await.unwrap();
+    assert_eq!(StatusCode::OK, stream_resp.status());
+    assert_eq!(
+        "text/event-stream",
+        stream_resp.headers()[header::CONTENT_TYPE]
+    );
+    let mut live_events = stream_resp.into_body().into_data_stream();
+    let mut live_buffer = String::new();
+
+    let create_task_req = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/users/{}/tasks", user.id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewTask {
+            item_desc: "Write the report".to_owned(),
+            due_date: None,
+            recurrence: None,
+            assignee_user_id: None,
+        }))
+        .unwrap();
+    let create_task_resp = app.call(create_task_req).await.unwrap();
+    let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+
+    let created = next_event(&mut live_events, &mut live_buffer).await;
+    assert_eq!("task.created", created["event"]);
+    let created_data: dto::TaskChange = serde_json::from_str(&created["data"]).unwrap();
+    assert_eq!(
+        dto::TaskChange {
+            task_id: task.id,
+            description: Some("Write the report".to_owned()),
+            completed: Some(false),
+        },
+        created_data
+    );
+
+    let update_task_req = Request::builder()
+        .method(Method::PATCH)
+        .uri(format!("/tasks/{}", task.id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::UpdateTask {
+            description: "Write the final report".to_owned(),
+        }))
+        .unwrap();
+    let update_task_resp = app.call(update_task_req).await.unwrap();
+    assert_eq!(StatusCode::OK, update_task_resp.status());
+
+    let updated = next_event(&mut live_events, &mut live_buffer).await;
+    assert_eq!("task.updated", updated["event"]);
+
+    let resumed_resp = app
+        .call(open_stream_request(user.id, Some(&created["id"])))
+        .await
+        .unwrap();
+    let mut resumed_events = resumed_resp.into_body().into_data_stream();
+    let mut resumed_buffer = String::new();
+
+    let missed = next_event(&mut resumed_events, &mut resumed_buffer).await;
+    assert_eq!(updated["id"], missed["id"]);
+    assert_eq!("task.updated", missed["event"]);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn returns_404_for_unknown_user() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+
+    let response = app.call(open_stream_request(9999, None)).await.unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+}
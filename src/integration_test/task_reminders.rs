@@ -0,0 +1,186 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use chrono::{Duration, Utc};
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::domain::reminder::driven_ports::DueReminderQueue;
+use crate::domain::reminder::driving_ports::ReminderPort;
+use crate::domain::reminder::DispatchSummary;
+use crate::external_connections::with_transaction;
+use crate::outbound::smtp_client::test_util::MailCatcher;
+use crate::outbound::smtp_client::SmtpClient;
+use crate::outbound::smtp_notifier::SmtpNotifier;
+use crate::persistence::db_reminder_driven_ports::DbReminderStore;
+use crate::{api, domain, dto, persistence};
+
+use super::test_util;
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn due_reminders_are_sent_once() {
+    let routes = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, db) = test_util::prepare_application(routes).await;
+
+    let create_user_resp = app
+        .call(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/users")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(dto_to_body(&dto::NewUser {
+                    first_name: String::from("Remy"),
+                    last_name: String::from("Nder"),
+                }))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    let set_email_resp = app
+        .call(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(format!("/users/{}/digest-preferences", user.id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(dto_to_body(&dto::DigestPreferences {
+                    email: Some("remy@example.com".to_owned()),
+                    daily_digest: false,
+                }))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, set_email_resp.status());
+
+    let create_task_resp = app
+        .call(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/users/{}/tasks", user.id))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(dto_to_body(&dto::NewTask {
+                    item_desc: "Renew passport".to_owned(),
+                    due_date: None,
+                    recurrence: None,
+                    assignee_user_id: None,
+                }))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+    let reminders_uri = format!("/users/{}/tasks/{}/reminders", user.id, task.id);
+
+    let past_reminder_resp = app
+        .call(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&reminders_uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(dto_to_body(&dto::NewReminder {
+                    remind_at: Utc::now() - Duration::hours(1),
+                }))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::BAD_REQUEST, past_reminder_resp.status());
+
+    let add_reminder_resp = app
+        .call(
+            Request::builder()
+                .method(Method::POST)
+                .uri(&reminders_uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(dto_to_body(&dto::NewReminder {
+                    remind_at: Utc::now() + Duration::hours(1),
+                }))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, add_reminder_resp.status());
+    let reminder: dto::TaskReminder = deserialize_body(add_reminder_resp.into_body()).await;
+
+    // Reminders can only be set in the future, so wind this one back until it's due
+    sqlx::query("UPDATE task_reminder SET remind_at = now() - interval '1 minute' WHERE id = $1")
+        .bind(reminder.id)
+        .execute(&db)
+        .await
+        .expect("Could not make the reminder due");
+
+    let ext_cxn = persistence::ExternalConnectivity::new(db);
+    let claim_result = with_transaction(&ext_cxn, async |first_tx| {
+        let first_claim = DbReminderStore.claim_due(10, &mut *first_tx).await?;
+
+        // While the first transaction holds the reminder, another dispatcher passes over it
+        let second_claim = with_transaction(&ext_cxn, async |second_tx| {
+            DbReminderStore.claim_due(10, &mut *second_tx).await
+        })
+        .await
+        .expect("Second claim failed");
+
+        Ok::<_, anyhow::Error>((first_claim, second_claim))
+    })
+    .await;
+    let (first_claim, second_claim) = claim_result.expect("First claim failed");
+    assert_eq!(
+        vec![reminder.id],
+        first_claim
+            .iter()
+            .map(|reminder| reminder.id)
+            .collect::<Vec<_>>()
+    );
+    assert!(second_claim.is_empty());
+
+    let catcher = MailCatcher::start().await;
+    let notifier = SmtpNotifier::new(SmtpClient::new(catcher.config()).unwrap());
+    for expected_sent in [1, 0] {
+        let summary = with_transaction(&ext_cxn, async |tx_cxn| {
+            domain::reminder::ReminderService
+                .dispatch_due(&mut *tx_cxn, &DbReminderStore, &notifier)
+                .await
+        })
+        .await
+        .expect("Dispatching reminders failed");
+        assert_eq!(
+            DispatchSummary {
+                sent: expected_sent,
+                ..DispatchSummary::default()
+            },
+            summary
+        );
+    }
+
+    let messages = catcher.messages();
+    let [message] = messages.as_slice() else {
+        panic!("Expected one email, got {messages:#?}");
+    };
+    assert_eq!(vec!["remy@example.com".to_owned()], message.to);
+    assert!(message
+        .data
+        .contains("Subject: Reminder: Renew passport\r\n"));
+
+    let list_resp = app
+        .call(
+            Request::builder()
+                .method(Method::GET)
+                .uri(&reminders_uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let reminders: Vec<dto::TaskReminder> = deserialize_body(list_resp.into_body()).await;
+    assert!(matches!(
+        reminders.as_slice(),
+        [dto::TaskReminder {
+            sent_at: Some(_),
+            failed_at: None,
+            ..
+        }]
+    ));
+}
@@ -0,0 +1,127 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+async fn create_user(app: &mut Router, first_name: &str) -> i32 {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: first_name.to_owned(),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let response = app.call(create_user_req).await.unwrap();
+    assert_eq!(StatusCode::CREATED, response.status());
+
+    let new_user: dto::InsertedUser = deserialize_body(response.into_body()).await;
+    new_user.id
+}
+
+async fn create_task(app: &mut Router, user_id: i32, description: &str) -> i32 {
+    let create_task_req = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/users/{user_id}/tasks"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewTask {
+            item_desc: description.to_owned(),
+            due_date: None,
+            recurrence: None,
+            assignee_user_id: None,
+        }))
+        .unwrap();
+    let response = app.call(create_task_req).await.unwrap();
+    assert_eq!(StatusCode::CREATED, response.status());
+
+    let new_task: dto::InsertedTask = deserialize_body(response.into_body()).await;
+    new_task.id
+}
+
+async fn search(app: &mut Router, user_id: i32, search_text: &str) -> Vec<dto::TaskSearchResult> {
+    let search_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/users/{user_id}/tasks/search?q={search_text}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.call(search_req).await.unwrap();
+    assert_eq!(StatusCode::OK, response.status());
+
+    deserialize_body(response.into_body()).await
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn finds_and_ranks_matching_tasks() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+    let user_id = create_user(&mut app, "John").await;
+    let other_user_id = create_user(&mut app, "Jane").await;
+
+    let weak_match_id = create_task(&mut app, user_id, "Buy groceries for the party").await;
+    let strong_match_id = create_task(&mut app, user_id, "Groceries: bought the groceries").await;
+    create_task(&mut app, user_id, "Walk the dog").await;
+    create_task(&mut app, other_user_id, "Pick up groceries").await;
+
+    let matches = search(&mut app, user_id, "grocery").await;
+    let [first_match, second_match] = matches.as_slice() else {
+        panic!("Expected two search results, got {}", matches.len());
+    };
+
+    assert_eq!(strong_match_id, first_match.task.id);
+    assert_eq!(weak_match_id, second_match.task.id);
+    assert!(first_match.rank > second_match.rank);
+    assert_eq!(
+        "Buy <mark>groceries</mark> for the party",
+        second_match.snippet
+    );
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn escapes_markup_in_snippets() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+    let user_id = create_user(&mut app, "John").await;
+    create_task(
+        &mut app,
+        user_id,
+        "Fix <script>alert(1)</script> & <mark>bread</mark> recipe",
+    )
+    .await;
+
+    let matches = search(&mut app, user_id, "recipe").await;
+    let [search_match] = matches.as_slice() else {
+        panic!("Expected one search result, got {}", matches.len());
+    };
+
+    assert_eq!(
+        "Fix &lt;script&gt;alert(1)&lt;/script&gt; &amp; &lt;mark&gt;bread&lt;/mark&gt; <mark>recipe</mark>",
+        search_match.snippet
+    );
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn search_without_query_is_rejected() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+    let user_id = create_user(&mut app, "John").await;
+
+    let search_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/users/{user_id}/tasks/search"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.call(search_req).await.unwrap();
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let error: dto::BasicError = deserialize_body(response.into_body()).await;
+    assert_eq!("invalid_input", error.error_code);
+}
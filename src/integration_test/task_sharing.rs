@@ -0,0 +1,256 @@
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+async fn prepare_access_controlled_app() -> Router {
+    let routes = Router::new()
+        .nest("/users", api::user::user_routes())
+        .nest("/tasks", api::todo::task_routes());
+    let (app, _) = test_util::prepare_application_with(routes, |shared_data| {
+        shared_data.access_control = true;
+    })
+    .await;
+
+    app
+}
+
+async fn create_user(app: &mut Router, first_name: &str) -> i32 {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: first_name.to_owned(),
+            last_name: String::from("Sharing"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    user.id
+}
+
+async fn create_task(app: &mut Router, user_id: i32, description: &str) -> i32 {
+    let create_task_resp = app
+        .call(test_util::request_as(
+            Method::POST,
+            format!("/users/{user_id}/tasks"),
+            user_id,
+            Some(&dto::NewTask {
+                item_desc: description.to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, create_task_resp.status());
+    let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+
+    task.id
+}
+
+async fn share(
+    app: &mut Router,
+    owner_id: i32,
+    new_share: dto::NewShare,
+) -> (StatusCode, Option<i32>) {
+    let share_resp = app
+        .call(test_util::request_as(
+            Method::POST,
+            format!("/users/{owner_id}/shares"),
+            owner_id,
+            Some(&new_share),
+        ))
+        .await
+        .unwrap();
+    let status = share_resp.status();
+    if status != StatusCode::CREATED {
+        return (status, None);
+    }
+    let inserted: dto::InsertedShare = deserialize_body(share_resp.into_body()).await;
+
+    (status, Some(inserted.id))
+}
+
+async fn update_status(app: &mut Router, task_id: i32, calling_user: i32) -> StatusCode {
+    app.call(test_util::request_as(
+        Method::PATCH,
+        format!("/tasks/{task_id}"),
+        calling_user,
+        Some(&dto::UpdateTask {
+            description: "Changed by a teammate".to_owned(),
+        }),
+    ))
+    .await
+    .unwrap()
+    .status()
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn shared_tasks_follow_their_permission() {
+    let mut app = prepare_access_controlled_app().await;
+    let owner_id = create_user(&mut app, "Sharon").await;
+    let teammate_id = create_user(&mut app, "Teddy").await;
+    let shared_task_id = create_task(&mut app, owner_id, "Plan the offsite").await;
+    let private_task_id = create_task(&mut app, owner_id, "Write my review").await;
+
+    let (status, share_id) = share(
+        &mut app,
+        owner_id,
+        dto::NewShare {
+            shared_with_user_id: teammate_id,
+            task_id: Some(shared_task_id),
+            permission: dto::SharePermission::Read,
+        },
+    )
+    .await;
+    assert_eq!(StatusCode::CREATED, status);
+
+    for (task_id, expected_status) in [
+        (shared_task_id, StatusCode::OK),
+        (private_task_id, StatusCode::NOT_FOUND),
+    ] {
+        let task_resp = app
+            .call(test_util::request_as(
+                Method::GET,
+                format!("/users/{teammate_id}/tasks/{task_id}"),
+                teammate_id,
+                None::<&()>,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(expected_status, task_resp.status(), "Task {task_id}");
+    }
+
+    let shared_tasks_resp = app
+        .call(test_util::request_as(
+            Method::GET,
+            format!("/users/{teammate_id}/shared-tasks"),
+            teammate_id,
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    let shared_tasks: Vec<dto::SharedTask> = deserialize_body(shared_tasks_resp.into_body()).await;
+    assert!(matches!(shared_tasks.as_slice(), [
+        dto::SharedTask {
+            task: dto::TodoTask { id, .. },
+            owner_user_id,
+            permission: dto::SharePermission::Read,
+        }
+    ] if *id == shared_task_id && *owner_user_id == owner_id));
+
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        update_status(&mut app, shared_task_id, teammate_id).await
+    );
+
+    // Sharing the task again upgrades the existing share
+    let (_, upgraded_share_id) = share(
+        &mut app,
+        owner_id,
+        dto::NewShare {
+            shared_with_user_id: teammate_id,
+            task_id: Some(shared_task_id),
+            permission: dto::SharePermission::Edit,
+        },
+    )
+    .await;
+    assert_eq!(share_id, upgraded_share_id);
+    assert_eq!(
+        StatusCode::OK,
+        update_status(&mut app, shared_task_id, teammate_id).await
+    );
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        update_status(&mut app, private_task_id, teammate_id).await
+    );
+
+    let revoke_resp = app
+        .call(test_util::request_as(
+            Method::DELETE,
+            format!("/users/{owner_id}/shares/{}", share_id.unwrap()),
+            owner_id,
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, revoke_resp.status());
+
+    let task_resp = app
+        .call(test_util::request_as(
+            Method::GET,
+            format!("/users/{teammate_id}/tasks/{shared_task_id}"),
+            teammate_id,
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, task_resp.status());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn sharing_every_task_includes_later_tasks() {
+    let mut app = prepare_access_controlled_app().await;
+    let owner_id = create_user(&mut app, "Lisa").await;
+    let teammate_id = create_user(&mut app, "Liam").await;
+
+    let (status, _) = share(
+        &mut app,
+        owner_id,
+        dto::NewShare {
+            shared_with_user_id: teammate_id,
+            task_id: None,
+            permission: dto::SharePermission::Edit,
+        },
+    )
+    .await;
+    assert_eq!(StatusCode::CREATED, status);
+    let later_task_id = create_task(&mut app, owner_id, "Created after sharing").await;
+
+    assert_eq!(
+        StatusCode::OK,
+        update_status(&mut app, later_task_id, teammate_id).await
+    );
+
+    let shares_resp = app
+        .call(test_util::request_as(
+            Method::GET,
+            format!("/users/{owner_id}/shares"),
+            owner_id,
+            None::<&()>,
+        ))
+        .await
+        .unwrap();
+    let shares: Vec<dto::TaskShare> = deserialize_body(shares_resp.into_body()).await;
+    assert!(matches!(shares.as_slice(), [
+        dto::TaskShare {
+            shared_with_user_id,
+            task_id: None,
+            permission: dto::SharePermission::Edit,
+            ..
+        }
+    ] if *shared_with_user_id == teammate_id));
+
+    // Teammates can't share tasks which were only shared with them
+    let (status, _) = share(
+        &mut app,
+        teammate_id,
+        dto::NewShare {
+            shared_with_user_id: owner_id,
+            task_id: Some(later_task_id),
+            permission: dto::SharePermission::Read,
+        },
+    )
+    .await;
+    assert_eq!(StatusCode::NOT_FOUND, status);
+}
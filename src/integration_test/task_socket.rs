@@ -0,0 +1,87 @@
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn applies_commands_and_shares_changes() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    assert_eq!(StatusCode::CREATED, create_user_resp.status());
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    // WebSocket upgrades need a real connection, so serve the app instead of calling it directly
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Could not bind the application");
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let (mut socket, _) =
+        tokio_tungstenite::connect_async(format!("ws://{address}/users/{}/tasks/socket", user.id))
+            .await
+            .expect("Could not open the task socket");
+    socket
+        .send(Message::Text(
+            r#"{"request_id":"1","op":"create","item_desc":"Write the report"}"#.to_owned(),
+        ))
+        .await
+        .unwrap();
+
+    let mut created_task_id = None;
+    let mut created_change = None;
+    while created_task_id.is_none() || created_change.is_none() {
+        let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+            .await
+            .expect("Timed out waiting for a socket message")
+            .expect("Task socket closed")
+            .expect("Could not read from the task socket");
+        let Message::Text(text) = message else {
+            continue;
+        };
+
+        match serde_json::from_str(&text).unwrap() {
+            dto::TaskSocketMessage::CommandResult {
+                request_id,
+                result: dto::TaskBatchOperationResult::Succeeded { task_id },
+            } => {
+                assert_eq!(Some("1".to_owned()), request_id);
+                created_task_id = Some(task_id);
+            }
+            dto::TaskSocketMessage::Change { event, change, .. } => {
+                assert_eq!("task.created", event);
+                created_change = Some(change);
+            }
+            other => panic!("Unexpected socket message {:?}", other),
+        }
+    }
+
+    assert_eq!(
+        dto::TaskChange {
+            task_id: created_task_id.unwrap(),
+            description: Some("Write the report".to_owned()),
+            completed: Some(false),
+        },
+        created_change.unwrap()
+    );
+}
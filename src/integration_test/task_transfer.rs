@@ -0,0 +1,195 @@
+use axum::body::{self, Body};
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::{api, dto};
+
+use super::test_util;
+
+fn test_router() -> Router<std::sync::Arc<crate::SharedData>> {
+    Router::new().nest("/users", api::user::user_routes())
+}
+
+async fn create_user(app: &mut Router, first_name: &str) -> i32 {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: first_name.to_owned(),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    user.id
+}
+
+fn import_request(user_id: i32, format: &str, file: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(format!("/users/{user_id}/tasks/import?format={format}"))
+        .body(Body::from(file.to_owned()))
+        .unwrap()
+}
+
+async fn export(app: &mut Router, user_id: i32, format: &str) -> String {
+    let export_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/users/{user_id}/tasks/export?format={format}"))
+        .body(Body::empty())
+        .unwrap();
+    let export_resp = app.call(export_req).await.unwrap();
+    assert_eq!(StatusCode::OK, export_resp.status());
+
+    let export_bytes = body::to_bytes(export_resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(export_bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn csv_export_round_trips_through_import() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+    let user_id = create_user(&mut app, "John").await;
+
+    let file = "description,completed,due_date,recurrence\n\
+        \"Buy eggs, milk\",true,,\n\
+        Stretch,false,2024-03-04,FREQ=DAILY\n";
+    let import_resp = app
+        .call(import_request(user_id, "csv", file))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, import_resp.status());
+    let imported: dto::ImportedTasks = deserialize_body(import_resp.into_body()).await;
+    assert_eq!(2, imported.task_ids.len());
+
+    let exported = export(&mut app, user_id, "csv").await;
+    let other_user_id = create_user(&mut app, "Jane").await;
+    let reimport_resp = app
+        .call(import_request(other_user_id, "csv", &exported))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, reimport_resp.status());
+
+    let tasks_resp = app
+        .call(
+            Request::builder()
+                .method(Method::GET)
+                .uri(format!("/users/{other_user_id}/tasks"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let tasks: Vec<dto::TodoTask> = deserialize_body(tasks_resp.into_body()).await;
+    assert!(matches!(tasks.as_slice(), [
+        dto::TodoTask { description: first, completed: true, due_date: None, recurrence: None, .. },
+        dto::TodoTask { description: second, completed: false, due_date: Some(_), recurrence: Some(recurrence), .. },
+    ] if first == "Buy eggs, milk" && second == "Stretch" && recurrence == "FREQ=DAILY"));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn json_export_lists_all_tasks() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+    let user_id = create_user(&mut app, "John").await;
+
+    let file = "[{\"description\": \"Water the plants\"}, {\"description\": \"Feed the cat\", \"completed\": true}]";
+    let import_resp = app
+        .call(import_request(user_id, "json", file))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, import_resp.status());
+
+    let exported: Vec<dto::TodoTask> =
+        serde_json::from_str(&export(&mut app, user_id, "json").await).unwrap();
+    assert!(matches!(exported.as_slice(), [
+        dto::TodoTask { description: first, completed: false, .. },
+        dto::TodoTask { description: second, completed: true, .. },
+    ] if first == "Water the plants" && second == "Feed the cat"));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn invalid_rows_prevent_the_whole_import() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+    let user_id = create_user(&mut app, "John").await;
+
+    let file = "description,due_date\nFine,\n,\nAlso fine,\nBad date,yesterday\n";
+    let import_resp = app
+        .call(import_request(user_id, "csv", file))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::BAD_REQUEST, import_resp.status());
+
+    let body: serde_json::Value = deserialize_body(import_resp.into_body()).await;
+    assert_eq!("invalid_import", body["error_code"]);
+    let lines: Vec<u64> = body["extra_info"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| issue["line"].as_u64().unwrap())
+        .collect();
+    assert_eq!(vec![3, 5], lines);
+
+    assert_eq!("[]", export(&mut app, user_id, "json").await);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn export_for_missing_user_returns_404() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+
+    let export_req = Request::builder()
+        .method(Method::GET)
+        .uri("/users/1234/tasks/export")
+        .body(Body::empty())
+        .unwrap();
+    let export_resp = app.call(export_req).await.unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, export_resp.status());
+
+    let body: dto::BasicError = deserialize_body(export_resp.into_body()).await;
+    assert_eq!("no_matching_user", body.error_code);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn todo_txt_export_round_trips_through_import() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+    let user_id = create_user(&mut app, "John").await;
+
+    let file = "(A) Call mom +family @phone due:2024-03-06\n\
+        x 2024-03-05 Stretch due:2024-03-04 rrule:FREQ=DAILY\n";
+    let import_resp = app
+        .call(import_request(user_id, "todotxt", file))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::CREATED, import_resp.status());
+
+    let export_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/users/{user_id}/tasks/export?format=todotxt"))
+        .body(Body::empty())
+        .unwrap();
+    let export_resp = app.call(export_req).await.unwrap();
+    assert_eq!(
+        Some("text/plain; charset=utf-8"),
+        export_resp
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+    );
+    let export_bytes = body::to_bytes(export_resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(
+        "(A) Call mom +family @phone due:2024-03-06\n\
+        x Stretch due:2024-03-04 rrule:FREQ=DAILY\n",
+        String::from_utf8(export_bytes.to_vec()).unwrap()
+    );
+}
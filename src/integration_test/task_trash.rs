@@ -0,0 +1,207 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use std::time::Duration;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::domain::todo::driven_ports::TaskWriter;
+use crate::persistence::db_todo_driven_ports::DbTaskWriter;
+use crate::persistence::ExternalConnectivity;
+use crate::{api, dto};
+
+use super::test_util;
+
+fn test_router() -> Router<std::sync::Arc<crate::SharedData>> {
+    Router::new()
+        .nest("/users", api::user::user_routes())
+        .nest("/tasks", api::todo::task_routes())
+}
+
+fn request(method: Method, uri: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap()
+}
+
+async fn create_user_with_tasks(app: &mut Router, descriptions: &[&str]) -> (i32, Vec<i32>) {
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let user: dto::InsertedUser = deserialize_body(create_user_resp.into_body()).await;
+
+    let mut task_ids = Vec::new();
+    for description in descriptions {
+        let create_task_req = Request::builder()
+            .method(Method::POST)
+            .uri(format!("/users/{}/tasks", user.id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(dto_to_body(&dto::NewTask {
+                item_desc: (*description).to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+            }))
+            .unwrap();
+        let create_task_resp = app.call(create_task_req).await.unwrap();
+        let task: dto::InsertedTask = deserialize_body(create_task_resp.into_body()).await;
+        task_ids.push(task.id);
+    }
+
+    (user.id, task_ids)
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn deleted_tasks_move_to_trash_until_restored() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+    let (user_id, task_ids) =
+        create_user_with_tasks(&mut app, &["Water the plants", "Feed the cat"]).await;
+
+    let delete_resp = app
+        .call(request(Method::DELETE, &format!("/tasks/{}", task_ids[0])))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, delete_resp.status());
+
+    let tasks_resp = app
+        .call(request(Method::GET, &format!("/users/{user_id}/tasks")))
+        .await
+        .unwrap();
+    let tasks: Vec<dto::TodoTask> = deserialize_body(tasks_resp.into_body()).await;
+    assert!(matches!(tasks.as_slice(), [dto::TodoTask { id, .. }] if *id == task_ids[1]));
+
+    let trashed_task_resp = app
+        .call(request(
+            Method::GET,
+            &format!("/users/{user_id}/tasks/{}", task_ids[0]),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, trashed_task_resp.status());
+
+    let search_resp = app
+        .call(request(
+            Method::GET,
+            &format!("/users/{user_id}/tasks/search?q=plants"),
+        ))
+        .await
+        .unwrap();
+    let search_results: Vec<dto::TaskSearchResult> =
+        deserialize_body(search_resp.into_body()).await;
+    assert!(search_results.is_empty());
+
+    let trash_resp = app
+        .call(request(
+            Method::GET,
+            &format!("/users/{user_id}/tasks/trash"),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, trash_resp.status());
+    let trash: Vec<dto::TrashedTask> = deserialize_body(trash_resp.into_body()).await;
+    assert!(matches!(trash.as_slice(), [
+        dto::TrashedTask { id, description, .. }
+    ] if *id == task_ids[0] && description == "Water the plants"));
+
+    let restore_resp = app
+        .call(request(
+            Method::POST,
+            &format!("/tasks/{}/restore", task_ids[0]),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(StatusCode::OK, restore_resp.status());
+    let restored: dto::TodoTask = deserialize_body(restore_resp.into_body()).await;
+    assert_eq!(task_ids[0], restored.id);
+
+    let tasks_resp = app
+        .call(request(Method::GET, &format!("/users/{user_id}/tasks")))
+        .await
+        .unwrap();
+    let tasks: Vec<dto::TodoTask> = deserialize_body(tasks_resp.into_body()).await;
+    assert_eq!(2, tasks.len());
+
+    let trash_resp = app
+        .call(request(
+            Method::GET,
+            &format!("/users/{user_id}/tasks/trash"),
+        ))
+        .await
+        .unwrap();
+    let trash: Vec<dto::TrashedTask> = deserialize_body(trash_resp.into_body()).await;
+    assert!(trash.is_empty());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn restoring_a_task_outside_the_trash_returns_404() {
+    let (mut app, _) = test_util::prepare_application(test_router()).await;
+    let (_, task_ids) = create_user_with_tasks(&mut app, &["Water the plants"]).await;
+
+    for task_id in [task_ids[0], task_ids[0] + 1000] {
+        let restore_resp = app
+            .call(request(Method::POST, &format!("/tasks/{task_id}/restore")))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::NOT_FOUND, restore_resp.status());
+
+        let body: dto::BasicError = deserialize_body(restore_resp.into_body()).await;
+        assert_eq!("no_matching_task", body.error_code);
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn purge_only_removes_tasks_past_retention() {
+    let (mut app, db) = test_util::prepare_application(test_router()).await;
+    let (user_id, task_ids) =
+        create_user_with_tasks(&mut app, &["Old news", "Recently trashed", "Still wanted"]).await;
+    for task_id in &task_ids[..2] {
+        let delete_resp = app
+            .call(request(Method::DELETE, &format!("/tasks/{task_id}")))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK, delete_resp.status());
+    }
+    sqlx::query("UPDATE todo_item SET deleted_at = now() - interval '2 days' WHERE id = $1")
+        .bind(task_ids[0])
+        .execute(&db)
+        .await
+        .unwrap();
+
+    let mut ext_cxn = ExternalConnectivity::new(db.clone());
+    let purged = DbTaskWriter::new(None)
+        .purge_trashed_tasks(Duration::from_secs(24 * 60 * 60), &mut ext_cxn)
+        .await
+        .unwrap();
+    assert_eq!(1, purged);
+
+    let trash_resp = app
+        .call(request(
+            Method::GET,
+            &format!("/users/{user_id}/tasks/trash"),
+        ))
+        .await
+        .unwrap();
+    let trash: Vec<dto::TrashedTask> = deserialize_body(trash_resp.into_body()).await;
+    assert!(matches!(trash.as_slice(), [dto::TrashedTask { id, .. }] if *id == task_ids[1]));
+
+    let purge_audits: i64 = sqlx::query_scalar(
+        "SELECT count(*) FROM audit_log WHERE action = 'purge' AND entity_id = $1",
+    )
+    .bind(task_ids[0])
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    assert_eq!(1, purge_audits);
+}
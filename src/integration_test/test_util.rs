@@ -1,9 +1,14 @@
-use crate::persistence::ExternalConnectivity;
-use crate::{app_env, configure_logger, db, SharedData};
+use crate::api::test_util::dto_to_body;
+use crate::persistence::{db_task_event_driven_ports, ExternalConnectivity};
+use crate::routing_utils::CALLING_USER_HEADER;
+use crate::{app_env, build_app, configure_logger, db, SharedData};
+use axum::body::Body;
+use axum::http::{header, Method, Request};
 use axum::Router;
 use dotenv::dotenv;
 use lazy_static::lazy_static;
 use rand::{thread_rng, Rng};
+use serde::Serialize;
 use sqlx::{Connection, PgConnection, Row};
 use std::env;
 use std::sync::Arc;
@@ -103,9 +108,18 @@ async fn prepare_db(pg_connection_base_url: &str) -> sqlx::PgPool {
 /// Prepares a database-connected application for integration tests, attaching routes via the provided
 /// Axum router. This function returns both the database pool and a prepared application instance
 /// which can handle requests based on the registered routes passed to the function.
+/// Like the running application, it forwards task change notifications from the database to task event streams.
 ///
 /// Expects that the [TEST_DB_URL](app_env::test::TEST_DB_URL) environment variable is populated.
 pub async fn prepare_application(routes: Router<Arc<SharedData>>) -> (Router, sqlx::PgPool) {
+    prepare_application_with(routes, |_| {}).await
+}
+
+/// Like [prepare_application], but lets the shared app data be configured before the application is built
+pub async fn prepare_application_with(
+    routes: Router<Arc<SharedData>>,
+    configure: impl FnOnce(&mut SharedData),
+) -> (Router, sqlx::PgPool) {
     // As soon as we're done configuring the logger we can release the mutex
     {
         let mut mutex_handle = LOGGER_INITIALIZED.lock().await;
@@ -127,9 +141,35 @@ pub async fn prepare_application(routes: Router<Arc<SharedData>>) -> (Router, sq
     });
 
     let db = prepare_db(pg_connection_base_url.as_str()).await;
-    let app = routes.with_state(Arc::new(SharedData {
-        ext_cxn: ExternalConnectivity::new(db.clone()),
-    }));
+    let mut shared_data = SharedData::new(ExternalConnectivity::new(db.clone()));
+    configure(&mut shared_data);
+    let shared_data = Arc::new(shared_data);
+    tokio::spawn(db_task_event_driven_ports::listen_for_task_changes(
+        db.clone(),
+        shared_data.task_changes.clone(),
+    ));
+    let app = build_app(routes, shared_data);
 
     (app, db)
 }
+
+/// Builds a request made by the given user, with a JSON body if one is given
+pub fn request_as(
+    method: Method,
+    uri: String,
+    calling_user: i32,
+    body: Option<&impl Serialize>,
+) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(CALLING_USER_HEADER, calling_user);
+
+    match body {
+        Some(body) => builder
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(dto_to_body(body))
+            .unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    }
+}
@@ -0,0 +1,78 @@
+use axum::Router;
+use speculoos::prelude::*;
+
+use crate::domain::user::driven_ports::{UserReader, UserWriter};
+use crate::domain::user::CreateUser;
+use crate::external_connections::{
+    with_savepoint, with_transaction, with_transaction_options, TransactionOptions, TxOrSourceError,
+};
+use crate::persistence::db_user_driven_ports::{DbReadUsers, DbWriteUsers};
+use crate::persistence::ExternalConnectivity;
+
+use super::test_util;
+
+fn user(first_name: &str) -> CreateUser {
+    CreateUser {
+        first_name: first_name.to_owned(),
+        last_name: "Doe".to_owned(),
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn rolled_back_savepoint_keeps_outer_transaction_work() {
+    let (_, db) = test_util::prepare_application(Router::new()).await;
+    let mut ext_cxn = ExternalConnectivity::new(db);
+
+    let tx_result = with_transaction(&ext_cxn, async |tx_cxn| {
+        DbWriteUsers::new(None)
+            .create_user(&user("John"), &mut *tx_cxn)
+            .await?;
+
+        let savepoint_result = with_savepoint(tx_cxn, async |sp_cxn| {
+            DbWriteUsers::new(None)
+                .create_user(&user("Jane"), &mut *sp_cxn)
+                .await?;
+            Err::<(), _>(anyhow::anyhow!("Rolling back Jane"))
+        })
+        .await;
+        assert_that!(savepoint_result)
+            .is_err()
+            .matches(|err| matches!(err, TxOrSourceError::Source(_)));
+
+        Ok::<(), anyhow::Error>(())
+    })
+    .await;
+    assert_that!(tx_result).is_ok();
+
+    let users = DbReadUsers
+        .all(&mut ext_cxn)
+        .await
+        .expect("Could not read users");
+    assert_that!(users
+        .iter()
+        .map(|user| user.first_name.as_str())
+        .collect::<Vec<_>>())
+    .is_equal_to(vec!["John"]);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn read_only_transaction_rejects_writes() {
+    let (_, db) = test_util::prepare_application(Router::new()).await;
+    let ext_cxn = ExternalConnectivity::new(db);
+    let options = TransactionOptions {
+        read_only: true,
+        ..TransactionOptions::default()
+    };
+
+    let tx_result = with_transaction_options(&ext_cxn, options, async |tx_cxn| {
+        DbWriteUsers::new(None)
+            .create_user(&user("John"), &mut *tx_cxn)
+            .await
+    })
+    .await;
+    assert_that!(tx_result)
+        .is_err()
+        .matches(|err| matches!(err, TxOrSourceError::Source(_)));
+}
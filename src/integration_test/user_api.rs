@@ -67,7 +67,97 @@ async fn can_retrieve_user() {
         id: user_id.id,
         first_name: String::from("John"),
         last_name: String::from("Doe"),
+        role: dto::Role::User,
     };
 
     assert_eq!(expected_user, received_user[0]);
 }
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn can_search_users_by_name() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+
+    for (first_name, last_name) in [("José", "Núñez"), ("John", "Doe"), ("Jane", "Smith")] {
+        let create_req = Request::builder()
+            .method(Method::POST)
+            .uri("/users")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(dto_to_body(&dto::NewUser {
+                first_name: first_name.to_owned(),
+                last_name: last_name.to_owned(),
+            }))
+            .unwrap();
+        let create_resp = app.call(create_req).await.unwrap();
+        assert_eq!(StatusCode::CREATED, create_resp.status());
+    }
+
+    let mut search = async |name: &str| -> Vec<String> {
+        let search_req = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/users?name={name}"))
+            .body(Body::empty())
+            .unwrap();
+        let search_resp = app.call(search_req).await.unwrap();
+        assert_eq!(StatusCode::OK, search_resp.status());
+
+        let users: Vec<dto::TodoUser> = deserialize_body(search_resp.into_body()).await;
+        users.into_iter().map(|user| user.first_name).collect()
+    };
+
+    assert_eq!(vec!["José"], search("NUNE").await);
+    assert_eq!(vec!["John", "José"], search("jo").await);
+    assert_eq!(vec!["John"], search("john%20d").await);
+    assert!(search("j_").await.is_empty());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn can_retrieve_single_user_with_task_summary() {
+    let router = Router::new().nest("/users", api::user::user_routes());
+    let (mut app, _) = test_util::prepare_application(router).await;
+
+    let create_response = app.call(create_user_request()).await.unwrap();
+    assert_eq!(StatusCode::CREATED, create_response.status());
+    let user_id: dto::InsertedUser = deserialize_body(create_response.into_body()).await;
+
+    for item_desc in ["Wash the car", "Walk the dog"] {
+        let create_task_req = Request::builder()
+            .method(Method::POST)
+            .uri(format!("/users/{}/tasks", user_id.id))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(dto_to_body(&dto::NewTask {
+                item_desc: item_desc.to_owned(),
+                due_date: None,
+                recurrence: None,
+                assignee_user_id: None,
+            }))
+            .unwrap();
+        let create_task_resp = app.call(create_task_req).await.unwrap();
+        assert_eq!(StatusCode::CREATED, create_task_resp.status());
+    }
+
+    let get_user_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/users/{}", user_id.id))
+        .body(Body::empty())
+        .unwrap();
+    let get_user_resp = app.call(get_user_req).await.unwrap();
+    assert_eq!(StatusCode::OK, get_user_resp.status());
+
+    let user: dto::UserDetails = deserialize_body(get_user_resp.into_body()).await;
+    assert_eq!(user_id.id, user.id);
+    assert_eq!("John", user.first_name);
+    assert_eq!(2, user.task_summary.total);
+    assert_eq!(0, user.task_summary.completed);
+    assert_eq!(0, user.task_summary.overdue);
+
+    let missing_user_req = Request::builder()
+        .method(Method::GET)
+        .uri(format!("/users/{}", user_id.id + 1))
+        .body(Body::empty())
+        .unwrap();
+    let missing_user_resp = app.call(missing_user_req).await.unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, missing_user_resp.status());
+}
@@ -0,0 +1,116 @@
+use axum::extract::State;
+use axum::http::{header, HeaderMap, Method, Request, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tower::Service; // THIS IS REQUIRED FOR Router.call()
+
+use crate::api::test_util::{deserialize_body, dto_to_body};
+use crate::domain::webhook::driving_ports::WebhookPort;
+use crate::domain::webhook::{
+    sign_payload, DispatchSummary, EVENT_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use crate::outbound::http_webhook_sender::HttpWebhookSender;
+use crate::{api, domain, dto, persistence};
+
+use super::test_util;
+
+const SECRET: &str = "integration-test-secret";
+
+type ReceivedRequests = Arc<Mutex<Vec<(HeaderMap, String)>>>;
+
+/// Starts a server on a random local port which records every request it receives, returning its URL
+async fn start_receiver() -> (String, ReceivedRequests) {
+    let received: ReceivedRequests = Arc::default();
+    let receiver = Router::new()
+        .route(
+            "/hook",
+            post(
+                |State(received): State<ReceivedRequests>, headers: HeaderMap, body: String| async move {
+                    received.lock().unwrap().push((headers, body));
+                    StatusCode::NO_CONTENT
+                },
+            ),
+        )
+        .with_state(Arc::clone(&received));
+
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Could not bind webhook receiver");
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, receiver).await });
+
+    (format!("http://{address}/hook"), received)
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration_test"), ignore)]
+async fn delivers_signed_events_to_subscribers() {
+    let (receiver_url, received) = start_receiver().await;
+    let router = Router::new()
+        .nest("/users", api::user::user_routes())
+        .nest("/webhooks", api::webhook::webhook_routes());
+    let (mut app, db) = test_util::prepare_application(router).await;
+
+    let subscribe_req = Request::builder()
+        .method(Method::POST)
+        .uri("/webhooks")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewWebhookSubscription {
+            url: receiver_url,
+            event_types: vec![dto::WebhookEventType::UserCreated],
+            secret: SECRET.to_owned(),
+        }))
+        .unwrap();
+    let subscribe_resp = app.call(subscribe_req).await.unwrap();
+    assert_eq!(StatusCode::CREATED, subscribe_resp.status());
+
+    let create_user_req = Request::builder()
+        .method(Method::POST)
+        .uri("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(dto_to_body(&dto::NewUser {
+            first_name: String::from("Jane"),
+            last_name: String::from("Doe"),
+        }))
+        .unwrap();
+    let create_user_resp = app.call(create_user_req).await.unwrap();
+    let (create_parts, create_body) = create_user_resp.into_parts();
+    assert_eq!(StatusCode::CREATED, create_parts.status);
+    let new_user: dto::InsertedUser = deserialize_body(create_body).await;
+
+    let mut ext_cxn = persistence::ExternalConnectivity::new(db);
+    let summary = domain::webhook::WebhookService
+        .dispatch_pending(
+            &mut ext_cxn,
+            &persistence::db_webhook_driven_ports::DbEventOutbox,
+            &HttpWebhookSender::new().unwrap(),
+        )
+        .await
+        .expect("Dispatching webhooks failed");
+    assert_eq!(
+        DispatchSummary {
+            delivered: 1,
+            ..DispatchSummary::default()
+        },
+        summary
+    );
+
+    let received = received.lock().unwrap();
+    let [(headers, body)] = received.as_slice() else {
+        panic!("Expected exactly one webhook request, got {:#?}", received);
+    };
+    let header = |name: &str| headers.get(name).unwrap().to_str().unwrap().to_owned();
+    assert_eq!("user.created", header(EVENT_HEADER));
+
+    let timestamp: u64 = header(TIMESTAMP_HEADER).parse().unwrap();
+    assert_eq!(
+        sign_payload(SECRET, timestamp, body),
+        header(SIGNATURE_HEADER)
+    );
+
+    let body: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!("user.created", body["event"]);
+    assert_eq!(new_user.id, body["data"]["user_id"]);
+}
@@ -1,18 +1,22 @@
 use std::env;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::extract::State;
 
-use axum::Router;
+use axum::{middleware, Router};
 use dotenv::dotenv;
 use log::*;
 use tokio::net::TcpListener;
+use tokio::sync::broadcast;
 
 mod api;
 mod app_env;
 mod db;
 mod domain;
 mod dto;
+mod outbound;
 // mod entity;
 mod persistence;
 // mod routes;
@@ -35,11 +39,96 @@ pub fn configure_logger() {
 /// Global data store which is shared among HTTP routes
 pub struct SharedData {
     pub ext_cxn: persistence::ExternalConnectivity,
+    /// How long stored responses for idempotent requests are kept
+    pub idempotency_key_ttl: Duration,
+    /// Notifies open task event streams that tasks have changed
+    pub task_changes: broadcast::Sender<persistence::db_task_event_driven_ports::TaskChangeNotice>,
+    /// How often clients may make requests
+    pub rate_limits: domain::rate_limit::RateLimits,
+    /// Whether rate limits are kept in the database so every instance of the app shares them
+    pub share_rate_limits: bool,
+    /// The rate limit buckets of this instance, used when rate limits aren't shared
+    pub rate_limit_buckets: persistence::memory_rate_limit_driven_ports::MemoryBucketStore,
+    /// CORS, compression, body size and timeout settings applied to every route
+    pub http: api::http_stack::HttpSettings,
+    /// Whether callers are held to the access policy. When it's off every caller may do anything.
+    pub access_control: bool,
+    /// How large files attached to tasks may be and which types are allowed
+    pub attachment_limits: domain::attachment::AttachmentLimits,
+}
+
+impl SharedData {
+    /// Constructs the shared app data, using default settings for anything not provided
+    pub fn new(ext_cxn: persistence::ExternalConnectivity) -> Self {
+        let (task_changes, _) = broadcast::channel(TASK_CHANGE_NOTICE_CAPACITY);
+
+        SharedData {
+            ext_cxn,
+            idempotency_key_ttl: DEFAULT_IDEMPOTENCY_KEY_TTL,
+            task_changes,
+            rate_limits: domain::rate_limit::RateLimits {
+                default: Some(DEFAULT_RATE_LIMIT),
+                ..Default::default()
+            },
+            share_rate_limits: false,
+            rate_limit_buckets: Default::default(),
+            http: api::http_stack::HttpSettings {
+                cors_origins: api::http_stack::CorsOrigins::Disabled,
+                compress_responses: true,
+                max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+                request_timeout: Some(DEFAULT_REQUEST_TIMEOUT),
+            },
+            access_control: false,
+            attachment_limits: Default::default(),
+        }
+    }
 }
 
 /// Type alias for the extractor used to get access to the global app state
 type AppState = State<Arc<SharedData>>;
 
+/// How long stored responses for idempotent requests are kept if not configured
+const DEFAULT_IDEMPOTENCY_KEY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// How many task change notices can be waiting for a slow task event stream before it has to catch up
+const TASK_CHANGE_NOTICE_CAPACITY: usize = 256;
+/// How long task changes are kept so streams can resume from them
+const TASK_CHANGE_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+/// How long deleted tasks stay in the trash if not configured
+const DEFAULT_TASK_TRASH_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// How often each client may make requests to routes without a limit of their own if not configured
+const DEFAULT_RATE_LIMIT: domain::rate_limit::RateLimit = domain::rate_limit::RateLimit {
+    capacity: 600,
+    period: Duration::from_secs(60),
+};
+/// The largest request body which will be accepted if not configured
+const DEFAULT_MAX_REQUEST_BYTES: usize = 2 * 1024 * 1024;
+/// How long a request may take before it's abandoned if not configured
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// The mail server used if not configured
+const DEFAULT_SMTP_HOST: &str = "localhost";
+/// The address mail is sent from if not configured
+const DEFAULT_SMTP_FROM: &str = "noreply@localhost";
+/// The hour of the day, in UTC, digests start going out if not configured
+const DEFAULT_DIGEST_SEND_HOUR: u32 = 7;
+
+/// Attaches the middleware shared by every route to the given routes and provides them the app state,
+/// producing a router which is ready to serve requests
+pub fn build_app(routes: Router<Arc<SharedData>>, shared_data: Arc<SharedData>) -> Router {
+    let http_settings = shared_data.http.clone();
+    let app = routes
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&shared_data),
+            api::idempotency::idempotency_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&shared_data),
+            api::rate_limit::rate_limit_middleware,
+        ))
+        .with_state(shared_data);
+
+    api::http_stack::apply_http_stack(app, &http_settings)
+}
+
 #[tokio::main]
 async fn main() {
     if dotenv().is_err() {
@@ -48,21 +137,252 @@ async fn main() {
     configure_logger();
     let db_url = env::var(app_env::DB_URL).expect("Could not get database URL from environment");
 
+    let idempotency_key_ttl = match env::var(app_env::IDEMPOTENCY_KEY_TTL_SECONDS) {
+        Ok(ttl) => Duration::from_secs(
+            ttl.parse()
+                .expect("Idempotency key TTL must be a number of seconds"),
+        ),
+        Err(_) => DEFAULT_IDEMPOTENCY_KEY_TTL,
+    };
+    let task_trash_retention = match env::var(app_env::TASK_TRASH_RETENTION_SECONDS) {
+        Ok(retention) => Duration::from_secs(
+            retention
+                .parse()
+                .expect("Task trash retention must be a number of seconds"),
+        ),
+        Err(_) => DEFAULT_TASK_TRASH_RETENTION,
+    };
+    let default_rate_limit = match env::var(app_env::RATE_LIMIT) {
+        Ok(limit) if limit.trim().eq_ignore_ascii_case("off") => None,
+        Ok(limit) => Some(
+            limit
+                .parse()
+                .unwrap_or_else(|err| panic!("Invalid default rate limit: {err}")),
+        ),
+        Err(_) => Some(DEFAULT_RATE_LIMIT),
+    };
+    let route_rate_limits = match env::var(app_env::RATE_LIMIT_ROUTES) {
+        Ok(routes) => domain::rate_limit::RateLimits::parse_routes(&routes)
+            .unwrap_or_else(|err| panic!("Invalid route rate limit: {err}")),
+        Err(_) => Default::default(),
+    };
+    let share_rate_limits = env::var(app_env::RATE_LIMIT_SHARED)
+        .is_ok_and(|shared| shared.trim().eq_ignore_ascii_case("true"));
+    let cors_origins = match env::var(app_env::CORS_ALLOWED_ORIGINS) {
+        Ok(origins) => origins
+            .parse()
+            .unwrap_or_else(|err| panic!("Invalid CORS origins: {err}")),
+        Err(_) => api::http_stack::CorsOrigins::Disabled,
+    };
+    let compress_responses = !env::var(app_env::RESPONSE_COMPRESSION)
+        .is_ok_and(|compression| compression.trim().eq_ignore_ascii_case("off"));
+    let max_request_bytes = match env::var(app_env::MAX_REQUEST_BODY_BYTES) {
+        Ok(max_bytes) => max_bytes
+            .parse()
+            .expect("Max request body size must be a number of bytes"),
+        Err(_) => DEFAULT_MAX_REQUEST_BYTES,
+    };
+    let request_timeout = match env::var(app_env::REQUEST_TIMEOUT_SECONDS) {
+        Ok(timeout) if timeout.trim().eq_ignore_ascii_case("off") => None,
+        Ok(timeout) => Some(Duration::from_secs(
+            timeout
+                .parse()
+                .expect("Request timeout must be a number of seconds"),
+        )),
+        Err(_) => Some(DEFAULT_REQUEST_TIMEOUT),
+    };
+    let access_control = env::var(app_env::ACCESS_CONTROL)
+        .is_ok_and(|access_control| access_control.trim().eq_ignore_ascii_case("on"));
+    let bootstrap_admin = match (
+        env::var(app_env::BOOTSTRAP_ADMIN_FIRST_NAME),
+        env::var(app_env::BOOTSTRAP_ADMIN_LAST_NAME),
+    ) {
+        (Ok(first_name), Ok(last_name)) => Some(domain::user::CreateUser {
+            first_name,
+            last_name,
+        }),
+        (Err(_), Err(_)) => None,
+        _ => panic!("Both the first and last name of the bootstrap admin must be given"),
+    };
+
+    let attachment_limits = domain::attachment::AttachmentLimits {
+        max_bytes: match env::var(app_env::ATTACHMENT_MAX_BYTES) {
+            Ok(max_bytes) => max_bytes
+                .parse()
+                .expect("Max attachment size must be a number of bytes"),
+            Err(_) => domain::attachment::DEFAULT_MAX_ATTACHMENT_BYTES,
+        },
+        content_types: match env::var(app_env::ATTACHMENT_CONTENT_TYPES) {
+            Ok(content_types) => {
+                domain::attachment::AttachmentLimits::parse_content_types(&content_types)
+                    .unwrap_or_else(|err| panic!("Invalid attachment content types: {err}"))
+            }
+            Err(_) => domain::attachment::AttachmentLimits::default().content_types,
+        },
+    };
+    let blob_storage_kind = env::var(app_env::BLOB_STORAGE).unwrap_or_else(|_| "local".to_owned());
+    let blob_storage = match blob_storage_kind.trim().to_ascii_lowercase().as_str() {
+        "local" => outbound::blob_storage::BlobStorage::Local(
+            outbound::local_blob_store::LocalBlobStore::new(
+                env::var(app_env::BLOB_STORAGE_DIR).unwrap_or_else(|_| {
+                    outbound::blob_storage::DEFAULT_BLOB_STORAGE_DIR.to_owned()
+                }),
+            ),
+        ),
+        "s3" => {
+            let s3_setting = |name: &str| {
+                env::var(name).unwrap_or_else(|_| panic!("{name} must be set to store files in S3"))
+            };
+            let s3_store =
+                outbound::s3_blob_store::S3BlobStore::new(outbound::s3_blob_store::S3Config {
+                    endpoint: s3_setting(app_env::S3_ENDPOINT),
+                    bucket: s3_setting(app_env::S3_BUCKET),
+                    region: env::var(app_env::S3_REGION)
+                        .unwrap_or_else(|_| outbound::s3_blob_store::DEFAULT_S3_REGION.to_owned()),
+                    access_key_id: s3_setting(app_env::S3_ACCESS_KEY_ID),
+                    secret_access_key: s3_setting(app_env::S3_SECRET_ACCESS_KEY),
+                })
+                .unwrap_or_else(|err| panic!("Could not configure S3 blob storage: {err}"));
+            outbound::blob_storage::BlobStorage::S3(s3_store)
+        }
+        _ => panic!("Unknown blob storage {blob_storage_kind:?}, expected \"local\" or \"s3\""),
+    };
+
+    let smtp_config = outbound::smtp_client::SmtpConfig {
+        host: env::var(app_env::SMTP_HOST).unwrap_or_else(|_| DEFAULT_SMTP_HOST.to_owned()),
+        port: match env::var(app_env::SMTP_PORT) {
+            Ok(port) => port.parse().expect("SMTP port must be a port number"),
+            Err(_) => outbound::smtp_client::DEFAULT_SMTP_PORT,
+        },
+        from: env::var(app_env::SMTP_FROM).unwrap_or_else(|_| DEFAULT_SMTP_FROM.to_owned()),
+    };
+    let digest_send_hour = match env::var(app_env::DIGEST_SEND_HOUR) {
+        Ok(hour) => hour
+            .parse::<u32>()
+            .ok()
+            .filter(|hour| *hour < 24)
+            .expect("Digest send hour must be an hour of the day from 0 to 23"),
+        Err(_) => DEFAULT_DIGEST_SEND_HOUR,
+    };
+    let digest_sender = outbound::smtp_digest_sender::SmtpDigestSender::new(
+        outbound::smtp_client::SmtpClient::new(smtp_config.clone())
+            .unwrap_or_else(|err| panic!("Could not configure SMTP: {err}")),
+    );
+    let notifier_kind = env::var(app_env::NOTIFIER).unwrap_or_else(|_| "log".to_owned());
+    let reminder_notifier = match notifier_kind.trim().to_ascii_lowercase().as_str() {
+        "log" => {
+            outbound::reminder_notifier::ReminderNotifier::Log(outbound::log_notifier::LogNotifier)
+        }
+        "smtp" => {
+            let smtp_client = outbound::smtp_client::SmtpClient::new(smtp_config.clone())
+                .unwrap_or_else(|err| panic!("Could not configure SMTP: {err}"));
+            outbound::reminder_notifier::ReminderNotifier::Smtp(
+                outbound::smtp_notifier::SmtpNotifier::new(smtp_client),
+            )
+        }
+        "webhook" => {
+            let url = env::var(app_env::REMINDER_WEBHOOK_URL).unwrap_or_else(|_| {
+                panic!(
+                    "{} must be set to post reminders",
+                    app_env::REMINDER_WEBHOOK_URL
+                )
+            });
+            outbound::reminder_notifier::ReminderNotifier::Webhook(
+                outbound::webhook_notifier::WebhookNotifier::new(url)
+                    .expect("Could not construct the reminder webhook HTTP client"),
+            )
+        }
+        _ => {
+            panic!("Unknown notifier {notifier_kind:?}, expected \"log\", \"smtp\" or \"webhook\"")
+        }
+    };
+
     let sqlx_db_connection = db::connect_sqlx(&db_url).await;
-    let ext_cxn = persistence::ExternalConnectivity::new(sqlx_db_connection);
+    let ext_cxn = persistence::ExternalConnectivity::new(sqlx_db_connection.clone())
+        .with_blob_storage(blob_storage);
+    let shared_data = Arc::new(SharedData {
+        idempotency_key_ttl,
+        rate_limits: domain::rate_limit::RateLimits {
+            default: default_rate_limit,
+            routes: route_rate_limits,
+        },
+        share_rate_limits,
+        http: api::http_stack::HttpSettings {
+            cors_origins,
+            compress_responses,
+            max_request_bytes,
+            request_timeout,
+        },
+        access_control,
+        attachment_limits,
+        ..SharedData::new(ext_cxn.clone())
+    });
+
+    if let Some(admin) = bootstrap_admin {
+        api::access::bootstrap_admin(admin, &ext_cxn, &domain::user::UserService)
+            .await
+            .unwrap_or_else(|err| panic!("Could not bootstrap the first admin: {err}"));
+    }
+
+    tokio::spawn(api::idempotency::purge_expired_keys(
+        ext_cxn.clone(),
+        Duration::from_secs(60 * 60),
+    ));
+    tokio::spawn(api::webhook::dispatch_webhooks(
+        ext_cxn.clone(),
+        outbound::http_webhook_sender::HttpWebhookSender::new()
+            .expect("Could not construct the webhook HTTP client"),
+        Duration::from_secs(5),
+    ));
+    tokio::spawn(api::reminder::dispatch_reminders(
+        ext_cxn.clone(),
+        reminder_notifier,
+        Duration::from_secs(10),
+    ));
+    tokio::spawn(api::digest::send_digests(
+        ext_cxn.clone(),
+        digest_sender,
+        digest_send_hour,
+        Duration::from_secs(5 * 60),
+    ));
+    tokio::spawn(
+        persistence::db_task_event_driven_ports::listen_for_task_changes(
+            sqlx_db_connection,
+            shared_data.task_changes.clone(),
+        ),
+    );
+    tokio::spawn(api::user::purge_task_changes(
+        ext_cxn.clone(),
+        TASK_CHANGE_RETENTION,
+        Duration::from_secs(60 * 60),
+    ));
+    tokio::spawn(api::rate_limit::purge_idle_buckets(
+        Arc::clone(&shared_data),
+        Duration::from_secs(10 * 60),
+    ));
+    tokio::spawn(api::todo::purge_trash(
+        ext_cxn,
+        task_trash_retention,
+        Duration::from_secs(60 * 60),
+    ));
 
-    let router = Router::new()
+    let routes = Router::new()
         .nest("/users", api::user::user_routes())
         .nest("/tasks", api::todo::task_routes())
-        .merge(api::swagger_main::build_documentation())
-        .with_state(Arc::new(SharedData { ext_cxn }));
+        .nest("/webhooks", api::webhook::webhook_routes())
+        .nest("/audit", api::audit::audit_routes())
+        .merge(api::swagger_main::build_documentation());
+    let router = build_app(routes, shared_data);
 
     info!("Starting server.");
     let network_listener = match TcpListener::bind(&"0.0.0.0:8080").await {
         Ok(listener) => listener,
         Err(bind_err) => panic!("Could not listen on requested port! {}", bind_err),
     };
-    axum::serve(network_listener, router.into_make_service())
-        .await
-        .unwrap();
+    axum::serve(
+        network_listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
@@ -0,0 +1,43 @@
+use crate::domain::attachment::driven_ports::BlobStore;
+use crate::domain::attachment::ByteStream;
+use crate::outbound::local_blob_store::LocalBlobStore;
+use crate::outbound::s3_blob_store::S3BlobStore;
+
+/// The directory files are stored in when no other storage is configured
+pub const DEFAULT_BLOB_STORAGE_DIR: &str = "attachments";
+
+/// The storage [crate::persistence::ExternalConnectivity] keeps the contents of files in, chosen when the app
+/// starts
+pub enum BlobStorage {
+    Local(LocalBlobStore),
+    S3(S3BlobStore),
+}
+
+impl Default for BlobStorage {
+    fn default() -> Self {
+        BlobStorage::Local(LocalBlobStore::new(DEFAULT_BLOB_STORAGE_DIR))
+    }
+}
+
+impl BlobStore for BlobStorage {
+    async fn put_blob(&self, key: &str, content: ByteStream) -> Result<u64, anyhow::Error> {
+        match self {
+            BlobStorage::Local(store) => store.put_blob(key, content).await,
+            BlobStorage::S3(store) => store.put_blob(key, content).await,
+        }
+    }
+
+    async fn get_blob(&self, key: &str) -> Result<Option<ByteStream>, anyhow::Error> {
+        match self {
+            BlobStorage::Local(store) => store.get_blob(key).await,
+            BlobStorage::S3(store) => store.get_blob(key).await,
+        }
+    }
+
+    async fn delete_blob(&self, key: &str) -> Result<(), anyhow::Error> {
+        match self {
+            BlobStorage::Local(store) => store.delete_blob(key).await,
+            BlobStorage::S3(store) => store.delete_blob(key).await,
+        }
+    }
+}
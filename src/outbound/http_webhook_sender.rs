@@ -0,0 +1,51 @@
+use crate::domain;
+use crate::domain::webhook::WebhookRequest;
+use anyhow::{bail, Context};
+use reqwest::header::CONTENT_TYPE;
+use std::time::Duration;
+
+/// How long a subscriber has to respond to a webhook delivery before it's considered failed
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A driven adapter which delivers webhooks to subscribers over HTTP
+pub struct HttpWebhookSender {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookSender {
+    /// Constructs a sender with a timeout suitable for webhook deliveries. Redirects are not
+    /// followed, so subscribers must register the URL which actually accepts deliveries.
+    pub fn new() -> Result<Self, anyhow::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .context("Building webhook HTTP client")?;
+
+        Ok(HttpWebhookSender { client })
+    }
+}
+
+impl domain::webhook::driven_ports::WebhookSender for HttpWebhookSender {
+    async fn send(&self, request: &WebhookRequest) -> Result<(), anyhow::Error> {
+        let mut http_request = self
+            .client
+            .post(&request.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(request.body.clone());
+        for (name, value) in &request.headers {
+            http_request = http_request.header(*name, value);
+        }
+
+        let response = http_request
+            .send()
+            .await
+            .with_context(|| format!("Sending webhook to {}", request.url))?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("Webhook subscriber responded with status {status}");
+        }
+
+        Ok(())
+    }
+}
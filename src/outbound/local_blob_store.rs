@@ -0,0 +1,187 @@
+use crate::domain;
+use crate::domain::attachment::ByteStream;
+use anyhow::{bail, Context};
+use futures::{StreamExt, TryStreamExt};
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+
+/// A driven adapter which stores files in a directory on the local filesystem. Keys are treated as paths
+/// relative to that directory.
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    /// Constructs a store which keeps files under the given directory, creating it when the first file is
+    /// stored
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalBlobStore { root: root.into() }
+    }
+
+    /// Determines where the file with the given key lives, refusing keys which would escape the store's
+    /// directory
+    fn path_for(&self, key: &str) -> Result<PathBuf, anyhow::Error> {
+        let relative = Path::new(key);
+        let is_contained = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        if key.is_empty() || !is_contained {
+            bail!("{key:?} is not a valid blob key");
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+impl domain::attachment::driven_ports::BlobStore for LocalBlobStore {
+    async fn put_blob(&self, key: &str, mut content: ByteStream) -> Result<u64, anyhow::Error> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Creating directory for blob {key}"))?;
+        }
+
+        let mut file = fs::File::create(&path)
+            .await
+            .with_context(|| format!("Creating file for blob {key}"))?;
+        let mut written = 0u64;
+        let write_result: Result<(), anyhow::Error> = async {
+            while let Some(chunk) = content.next().await {
+                let chunk = chunk?;
+                file.write_all(&chunk)
+                    .await
+                    .with_context(|| format!("Writing blob {key}"))?;
+                written += chunk.len() as u64;
+            }
+            file.flush()
+                .await
+                .with_context(|| format!("Flushing blob {key}"))
+        }
+        .await;
+
+        if let Err(err) = write_result {
+            drop(file);
+            // Don't leave a partial file behind for something to find later
+            let _ = fs::remove_file(&path).await;
+            return Err(err);
+        }
+        Ok(written)
+    }
+
+    async fn get_blob(&self, key: &str) -> Result<Option<ByteStream>, anyhow::Error> {
+        let path = self.path_for(key)?;
+        let file = match fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(anyhow::Error::new(err).context(format!("Opening blob {key}")));
+            }
+        };
+
+        Ok(Some(Box::pin(
+            ReaderStream::new(file).map_err(anyhow::Error::from),
+        )))
+    }
+
+    async fn delete_blob(&self, key: &str) -> Result<(), anyhow::Error> {
+        let path = self.path_for(key)?;
+        match fs::remove_file(&path).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                Err(anyhow::Error::new(err).context(format!("Removing blob {key}")))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::attachment::driven_ports::BlobStore;
+    use anyhow::anyhow;
+    use bytes::Bytes;
+    use futures::stream;
+    use speculoos::prelude::*;
+
+    /// Creates a store in a fresh directory under the system's temporary directory
+    fn temp_store() -> LocalBlobStore {
+        let mut dir_name = [0u8; 8];
+        rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut dir_name);
+
+        LocalBlobStore::new(
+            std::env::temp_dir().join(format!("local-blob-store-test-{}", hex::encode(dir_name))),
+        )
+    }
+
+    fn content(chunks: Vec<Result<&'static [u8], anyhow::Error>>) -> ByteStream {
+        Box::pin(stream::iter(
+            chunks
+                .into_iter()
+                .map(|chunk| chunk.map(Bytes::from_static)),
+        ))
+    }
+
+    #[tokio::test]
+    async fn stores_and_reads_back_files() {
+        let store = temp_store();
+
+        let written = store
+            .put_blob("tasks/1/abc", content(vec![Ok(b"hello "), Ok(b"world")]))
+            .await
+            .expect("Could not store blob");
+        let read_back: Vec<Bytes> = store
+            .get_blob("tasks/1/abc")
+            .await
+            .expect("Could not read blob")
+            .expect("Blob was missing")
+            .try_collect()
+            .await
+            .expect("Could not read blob contents");
+
+        assert_eq!(11, written);
+        assert_eq!(b"hello world".to_vec(), read_back.concat());
+
+        store
+            .delete_blob("tasks/1/abc")
+            .await
+            .expect("Could not delete blob");
+        assert_that!(store.get_blob("tasks/1/abc").await.unwrap().is_none()).is_true();
+        let _ = fs::remove_dir_all(&store.root).await;
+    }
+
+    #[tokio::test]
+    async fn removes_partial_file_when_content_fails() {
+        let store = temp_store();
+
+        let put_result = store
+            .put_blob(
+                "tasks/1/abc",
+                content(vec![Ok(b"hello "), Err(anyhow!("Connection reset"))]),
+            )
+            .await;
+
+        assert_that!(put_result).is_err();
+        assert_that!(store.get_blob("tasks/1/abc").await.unwrap().is_none()).is_true();
+        let _ = fs::remove_dir_all(&store.root).await;
+    }
+
+    #[tokio::test]
+    async fn refuses_keys_outside_directory() {
+        let store = temp_store();
+
+        assert_that!(store.delete_blob("../escape").await).is_err();
+        assert_that!(store.delete_blob("/etc/passwd").await).is_err();
+        assert_that!(store.delete_blob("").await).is_err();
+    }
+
+    #[tokio::test]
+    async fn deleting_missing_file_succeeds() {
+        let store = temp_store();
+
+        assert_that!(store.delete_blob("tasks/1/missing").await).is_ok();
+    }
+}
@@ -0,0 +1,16 @@
+use crate::domain;
+use crate::domain::reminder::DueReminder;
+use log::info;
+
+/// A driven adapter which writes reminders to the application log instead of sending them anywhere
+pub struct LogNotifier;
+
+impl domain::reminder::driven_ports::Notifier for LogNotifier {
+    async fn notify(&self, reminder: &DueReminder) -> Result<(), anyhow::Error> {
+        info!(
+            "Reminder {} for user {}: task {} \"{}\" at {}",
+            reminder.id, reminder.user_id, reminder.task_id, reminder.item_desc, reminder.remind_at
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+pub mod blob_storage;
+pub mod http_webhook_sender;
+pub mod local_blob_store;
+pub mod log_notifier;
+pub mod reminder_notifier;
+pub mod s3_blob_store;
+pub mod smtp_client;
+pub mod smtp_digest_sender;
+pub mod smtp_notifier;
+pub mod webhook_notifier;
@@ -0,0 +1,22 @@
+use crate::domain::reminder::driven_ports::Notifier;
+use crate::domain::reminder::DueReminder;
+use crate::outbound::log_notifier::LogNotifier;
+use crate::outbound::smtp_notifier::SmtpNotifier;
+use crate::outbound::webhook_notifier::WebhookNotifier;
+
+/// The way users are told about due reminders, chosen when the app starts
+pub enum ReminderNotifier {
+    Log(LogNotifier),
+    Smtp(SmtpNotifier),
+    Webhook(WebhookNotifier),
+}
+
+impl Notifier for ReminderNotifier {
+    async fn notify(&self, reminder: &DueReminder) -> Result<(), anyhow::Error> {
+        match self {
+            ReminderNotifier::Log(notifier) => notifier.notify(reminder).await,
+            ReminderNotifier::Smtp(notifier) => notifier.notify(reminder).await,
+            ReminderNotifier::Webhook(notifier) => notifier.notify(reminder).await,
+        }
+    }
+}
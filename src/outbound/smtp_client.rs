@@ -0,0 +1,441 @@
+use anyhow::{anyhow, bail, Context};
+use chrono::Utc;
+use rand::RngCore;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// The port a local mail catcher such as Mailpit accepts mail on
+pub const DEFAULT_SMTP_PORT: u16 = 1025;
+/// How long a mail server has to accept a message before sending it is considered failed
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+/// The name this client introduces itself with
+const CLIENT_NAME: &str = "sample-rest";
+
+/// Settings for reaching a mail server
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    /// The address mail is sent from
+    pub from: String,
+}
+
+/// A message to send to a single recipient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub text_body: String,
+    /// An HTML version of the message, sent alongside the text version for clients which can show it
+    pub html_body: Option<String>,
+}
+
+/// Sends mail over plain SMTP without authentication, which suits a local mail catcher or a relay on a
+/// trusted network
+pub struct SmtpClient {
+    config: SmtpConfig,
+}
+
+impl SmtpClient {
+    /// Constructs a client for the mail server described by [config]
+    pub fn new(config: SmtpConfig) -> Result<Self, anyhow::Error> {
+        check_header_value("sender address", &config.from)?;
+
+        Ok(SmtpClient { config })
+    }
+
+    /// Sends a message, returning an error if the mail server didn't accept it
+    pub async fn send(&self, email: &Email) -> Result<(), anyhow::Error> {
+        check_header_value("recipient address", &email.to)?;
+        check_header_value("subject", &email.subject)?;
+        let message = build_message(&self.config.from, email);
+
+        tokio::time::timeout(SEND_TIMEOUT, self.deliver(&email.to, &message))
+            .await
+            .map_err(|_| anyhow!("Timed out sending mail to {}", email.to))?
+    }
+
+    async fn deliver(&self, to: &str, message: &str) -> Result<(), anyhow::Error> {
+        let stream = TcpStream::connect((self.config.host.as_str(), self.config.port))
+            .await
+            .with_context(|| {
+                format!(
+                    "Connecting to mail server {}:{}",
+                    self.config.host, self.config.port
+                )
+            })?;
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+
+        expect_reply(&mut reader, 220).await?;
+        let commands = [
+            (format!("EHLO {CLIENT_NAME}\r\n"), 250),
+            (format!("MAIL FROM:<{}>\r\n", self.config.from), 250),
+            (format!("RCPT TO:<{to}>\r\n"), 250),
+            ("DATA\r\n".to_owned(), 354),
+        ];
+        for (command, expected_code) in commands {
+            writer.write_all(command.as_bytes()).await?;
+            expect_reply(&mut reader, expected_code)
+                .await
+                .with_context(|| format!("Sending {}", command.trim_end()))?;
+        }
+
+        writer.write_all(dot_stuff(message).as_bytes()).await?;
+        writer.write_all(b".\r\n").await?;
+        expect_reply(&mut reader, 250)
+            .await
+            .context("Sending message contents")?;
+
+        // The message has been accepted, so a server which hangs up early doesn't matter
+        let _ = writer.write_all(b"QUIT\r\n").await;
+        Ok(())
+    }
+}
+
+/// Rejects values which would let a header be split into several
+fn check_header_value(name: &str, value: &str) -> Result<(), anyhow::Error> {
+    if value.contains(['\r', '\n']) {
+        bail!("The {name} may not contain line breaks");
+    }
+    Ok(())
+}
+
+/// Reads a possibly multi-line reply from the server, failing if its code isn't the one expected
+async fn expect_reply(
+    reader: &mut (impl AsyncBufReadExt + Unpin),
+    expected_code: u16,
+) -> Result<(), anyhow::Error> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            bail!("Mail server closed the connection");
+        }
+        // Every line but the last has a hyphen after the code
+        if line.as_bytes().get(3) != Some(&b'-') {
+            break;
+        }
+    }
+
+    let code: u16 = line
+        .get(..3)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| anyhow!("Mail server sent a malformed reply: {}", line.trim_end()))?;
+    if code != expected_code {
+        bail!("Mail server replied {}", line.trim_end());
+    }
+    Ok(())
+}
+
+/// Builds the message sent after `DATA`, with headers and a body made of CRLF-terminated lines
+fn build_message(from: &str, email: &Email) -> String {
+    let mut message = format!(
+        "From: <{from}>\r\n\
+        To: <{}>\r\n\
+        Subject: {}\r\n\
+        Date: {}\r\n\
+        MIME-Version: 1.0\r\n",
+        email.to,
+        encode_header(&email.subject),
+        Utc::now().to_rfc2822(),
+    );
+
+    match &email.html_body {
+        None => {
+            message.push_str(&text_part("text/plain", &email.text_body));
+        }
+        Some(html_body) => {
+            let mut boundary_bytes = [0u8; 12];
+            rand::thread_rng().fill_bytes(&mut boundary_bytes);
+            let boundary = hex::encode(boundary_bytes);
+            message.push_str(&format!(
+                "Content-Type: multipart/alternative; boundary=\"{boundary}\"\r\n\r\n"
+            ));
+            // Clients show the last alternative they understand, so HTML goes after text
+            for (content_type, body) in [("text/plain", &email.text_body), ("text/html", html_body)]
+            {
+                message.push_str(&format!("--{boundary}\r\n"));
+                message.push_str(&text_part(content_type, body));
+            }
+            message.push_str(&format!("--{boundary}--\r\n"));
+        }
+    }
+
+    message
+}
+
+/// Formats the headers and body of a single UTF-8 text part
+fn text_part(content_type: &str, body: &str) -> String {
+    let mut part = format!(
+        "Content-Type: {content_type}; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n"
+    );
+    for line in body.lines() {
+        part.push_str(line);
+        part.push_str("\r\n");
+    }
+
+    part
+}
+
+/// Encodes a header value as an RFC 2047 encoded word if it contains anything other than printable ASCII
+fn encode_header(value: &str) -> String {
+    if value.bytes().all(|byte| (b' '..=b'~').contains(&byte)) {
+        return value.to_owned();
+    }
+
+    let encoded: String = value
+        .bytes()
+        .map(|byte| match byte {
+            b' ' => "_".to_owned(),
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' => (byte as char).to_string(),
+            _ => format!("={byte:02X}"),
+        })
+        .collect();
+    format!("=?UTF-8?Q?{encoded}?=")
+}
+
+/// Doubles the dot at the start of any line, so no line of the message can end it early
+fn dot_stuff(message: &str) -> String {
+    message
+        .split_inclusive("\r\n")
+        .map(|line| {
+            if line.starts_with('.') {
+                format!(".{line}")
+            } else {
+                line.to_owned()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_util::MailCatcher;
+    use super::*;
+
+    fn email(html_body: Option<&str>) -> Email {
+        Email {
+            to: "sam@example.com".to_owned(),
+            subject: "Reminder: Renew passport".to_owned(),
+            text_body: "Renew passport\n.\nSoon".to_owned(),
+            html_body: html_body.map(str::to_owned),
+        }
+    }
+
+    #[tokio::test]
+    async fn delivers_mail_to_the_server() {
+        let catcher = MailCatcher::start().await;
+        let client = SmtpClient::new(catcher.config()).unwrap();
+
+        client
+            .send(&email(None))
+            .await
+            .expect("Could not send mail");
+
+        let messages = catcher.messages();
+        let [message] = messages.as_slice() else {
+            panic!("Expected one message, got {messages:#?}");
+        };
+        assert_eq!("noreply@example.com", message.from);
+        assert_eq!(vec!["sam@example.com".to_owned()], message.to);
+        assert!(message
+            .data
+            .contains("Subject: Reminder: Renew passport\r\n"));
+        assert!(message
+            .data
+            .contains("Content-Type: text/plain; charset=utf-8\r\n"));
+        // The catcher undoes dot stuffing, so the lone dot arrives intact
+        assert!(message
+            .data
+            .ends_with("\r\n\r\nRenew passport\r\n.\r\nSoon\r\n"));
+    }
+
+    #[tokio::test]
+    async fn sends_html_as_an_alternative() {
+        let catcher = MailCatcher::start().await;
+        let client = SmtpClient::new(catcher.config()).unwrap();
+
+        client
+            .send(&email(Some("<p>Renew passport</p>")))
+            .await
+            .expect("Could not send mail");
+
+        let messages = catcher.messages();
+        let data = &messages[0].data;
+        assert!(data.contains("Content-Type: multipart/alternative; boundary="));
+        let text_at = data.find("Content-Type: text/plain").unwrap();
+        let html_at = data.find("Content-Type: text/html").unwrap();
+        assert!(text_at < html_at);
+        assert!(data.contains("<p>Renew passport</p>\r\n"));
+    }
+
+    #[tokio::test]
+    async fn fails_when_server_rejects_recipient() {
+        let catcher = MailCatcher::start_rejecting_recipients().await;
+        let client = SmtpClient::new(catcher.config()).unwrap();
+
+        let send_result = client.send(&email(None)).await;
+        assert!(send_result.is_err());
+        assert!(catcher.messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_header_injection() {
+        let catcher = MailCatcher::start().await;
+        let client = SmtpClient::new(catcher.config()).unwrap();
+
+        let send_result = client
+            .send(&Email {
+                subject: "Hi\r\nBcc: everyone@example.com".to_owned(),
+                ..email(None)
+            })
+            .await;
+        assert!(send_result.is_err());
+    }
+
+    #[test]
+    fn encodes_non_ascii_subjects() {
+        assert_eq!("Plain subject", encode_header("Plain subject"));
+        assert_eq!("=?UTF-8?Q?Caf=C3=A9_list?=", encode_header("Café list"));
+    }
+}
+
+#[cfg(test)]
+pub mod test_util {
+    use super::SmtpConfig;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    /// A message received by a [MailCatcher]
+    #[derive(Debug, Clone)]
+    pub struct CaughtMessage {
+        pub from: String,
+        pub to: Vec<String>,
+        /// Everything sent after `DATA`, with dot stuffing undone
+        pub data: String,
+    }
+
+    /// A minimal SMTP server on a random local port which keeps the messages it receives in memory
+    pub struct MailCatcher {
+        port: u16,
+        messages: Arc<Mutex<Vec<CaughtMessage>>>,
+        server: JoinHandle<()>,
+    }
+
+    impl MailCatcher {
+        /// Starts a catcher which accepts every message
+        pub async fn start() -> MailCatcher {
+            Self::start_with(true).await
+        }
+
+        /// Starts a catcher which refuses every recipient, so no message is accepted
+        pub async fn start_rejecting_recipients() -> MailCatcher {
+            Self::start_with(false).await
+        }
+
+        async fn start_with(accept_recipients: bool) -> MailCatcher {
+            let listener = TcpListener::bind("127.0.0.1:0")
+                .await
+                .expect("Could not bind mail catcher");
+            let port = listener.local_addr().unwrap().port();
+            let messages = Arc::new(Mutex::new(Vec::new()));
+
+            let caught = Arc::clone(&messages);
+            let server = tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let caught = Arc::clone(&caught);
+                    tokio::spawn(async move {
+                        let (reader, mut writer) = stream.into_split();
+                        let mut lines = BufReader::new(reader).lines();
+                        let mut reply = async |line: &str| {
+                            let _ = writer.write_all(format!("{line}\r\n").as_bytes()).await;
+                        };
+
+                        reply("220 mail-catcher ready").await;
+                        let mut from = String::new();
+                        let mut to = Vec::new();
+                        while let Ok(Some(line)) = lines.next_line().await {
+                            let upper = line.to_ascii_uppercase();
+                            if upper.starts_with("EHLO") {
+                                reply("250-mail-catcher").await;
+                                reply("250 8BITMIME").await;
+                            } else if upper.starts_with("MAIL FROM:") {
+                                from = address(&line);
+                                reply("250 OK").await;
+                            } else if upper.starts_with("RCPT TO:") && accept_recipients {
+                                to.push(address(&line));
+                                reply("250 OK").await;
+                            } else if upper.starts_with("RCPT TO:") {
+                                reply("550 No such mailbox").await;
+                            } else if upper == "DATA" && !to.is_empty() {
+                                reply("354 End data with <CR><LF>.<CR><LF>").await;
+                                let mut data = String::new();
+                                while let Ok(Some(data_line)) = lines.next_line().await {
+                                    if data_line == "." {
+                                        break;
+                                    }
+                                    data.push_str(
+                                        data_line.strip_prefix('.').unwrap_or(&data_line),
+                                    );
+                                    data.push_str("\r\n");
+                                }
+                                caught.lock().unwrap().push(CaughtMessage {
+                                    from: from.clone(),
+                                    to: to.clone(),
+                                    data,
+                                });
+                                reply("250 Queued").await;
+                            } else if upper == "DATA" {
+                                reply("503 No valid recipients").await;
+                            } else if upper == "QUIT" {
+                                reply("221 Bye").await;
+                                break;
+                            } else {
+                                reply("500 Unrecognized command").await;
+                            }
+                        }
+                    });
+                }
+            });
+
+            MailCatcher {
+                port,
+                messages,
+                server,
+            }
+        }
+
+        /// Settings for sending mail to this catcher from noreply@example.com
+        pub fn config(&self) -> SmtpConfig {
+            SmtpConfig {
+                host: "127.0.0.1".to_owned(),
+                port: self.port,
+                from: "noreply@example.com".to_owned(),
+            }
+        }
+
+        /// The messages received so far, oldest first
+        pub fn messages(&self) -> Vec<CaughtMessage> {
+            self.messages.lock().unwrap().clone()
+        }
+    }
+
+    impl Drop for MailCatcher {
+        fn drop(&mut self) {
+            self.server.abort();
+        }
+    }
+
+    /// Pulls the address out of a `MAIL FROM:<...>` or `RCPT TO:<...>` command
+    fn address(command: &str) -> String {
+        command
+            .split_once('<')
+            .and_then(|(_, rest)| rest.split_once('>'))
+            .map(|(address, _)| address.to_owned())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,233 @@
+use crate::domain;
+use crate::domain::digest::{DigestGroup, DigestSection, TaskDigest};
+use crate::domain::todo::TodoTask;
+use crate::outbound::smtp_client::{Email, SmtpClient};
+use std::fmt::Write;
+
+/// How days are written in digests, such as "Wed, 1 May 2024"
+const DAY_FORMAT: &str = "%a, %-d %b %Y";
+
+/// A driven adapter which emails digests to the users they're for over SMTP
+pub struct SmtpDigestSender {
+    client: SmtpClient,
+}
+
+impl SmtpDigestSender {
+    /// Constructs a sender which emails digests through [client]
+    pub fn new(client: SmtpClient) -> Self {
+        SmtpDigestSender { client }
+    }
+}
+
+/// Describes how many open and overdue tasks a digest has, such as "3 open tasks, 1 overdue"
+fn task_counts(digest: &TaskDigest) -> String {
+    let open = digest.task_count();
+    let mut counts = format!("{open} open {}", if open == 1 { "task" } else { "tasks" });
+    let overdue = digest.overdue_count();
+    if overdue > 0 {
+        let _ = write!(counts, ", {overdue} overdue");
+    }
+
+    counts
+}
+
+/// Describes when a task in a group is due, if the group's heading doesn't already say
+fn due_note(group: &DigestGroup, task: &TodoTask) -> Option<String> {
+    match (group.section, task.due_date) {
+        (DigestSection::Overdue | DigestSection::Upcoming, Some(due_date)) => {
+            Some(format!("due {}", due_date.format(DAY_FORMAT)))
+        }
+        _ => None,
+    }
+}
+
+/// Fills in the plain text template of a digest
+fn render_text(digest: &TaskDigest) -> String {
+    let mut text = format!(
+        "Good morning {},\n\nHere's where your tasks stand on {}: {}.\n",
+        digest.recipient.first_name,
+        digest.date.format(DAY_FORMAT),
+        task_counts(digest),
+    );
+    for group in &digest.groups {
+        let _ = write!(text, "\n{}\n", group.section.heading());
+        for task in &group.tasks {
+            let _ = write!(text, "- {}", task.item_desc.replace(['\r', '\n'], " "));
+            if let Some(note) = due_note(group, task) {
+                let _ = write!(text, " ({note})");
+            }
+            if task.blocked {
+                text.push_str(" [blocked]");
+            }
+            text.push('\n');
+        }
+    }
+
+    text
+}
+
+/// Escapes text so it can be placed in HTML element content or a quoted attribute
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
+
+/// Fills in the HTML template of a digest
+fn render_html(digest: &TaskDigest) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<body>\n<p>Good morning {},</p>\n\
+        <p>Here's where your tasks stand on {}: {}.</p>\n",
+        escape_html(&digest.recipient.first_name),
+        digest.date.format(DAY_FORMAT),
+        task_counts(digest),
+    );
+    for group in &digest.groups {
+        let _ = write!(
+            html,
+            "<h2>{}</h2>\n<ul>\n",
+            escape_html(group.section.heading())
+        );
+        for task in &group.tasks {
+            let _ = write!(html, "<li>{}", escape_html(&task.item_desc));
+            if let Some(note) = due_note(group, task) {
+                let _ = write!(html, " <small>({note})</small>");
+            }
+            if task.blocked {
+                html.push_str(" <em>blocked</em>");
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+
+    html
+}
+
+/// Writes the email carrying a digest
+fn digest_email(digest: &TaskDigest) -> Email {
+    Email {
+        to: digest.recipient.email.clone(),
+        subject: format!(
+            "Your tasks for {}: {}",
+            digest.date.format(DAY_FORMAT),
+            task_counts(digest)
+        ),
+        text_body: render_text(digest),
+        html_body: Some(render_html(digest)),
+    }
+}
+
+impl domain::digest::driven_ports::DigestSender for SmtpDigestSender {
+    async fn send_digest(&self, digest: &TaskDigest) -> Result<(), anyhow::Error> {
+        self.client.send(&digest_email(digest)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::digest::driven_ports::DigestSender;
+    use crate::domain::digest::DigestRecipient;
+    use crate::outbound::smtp_client::test_util::MailCatcher;
+    use chrono::NaiveDate;
+
+    fn task(id: i32, item_desc: &str, due_date: Option<NaiveDate>) -> TodoTask {
+        TodoTask {
+            id,
+            owner_user_id: 1,
+            item_desc: item_desc.to_owned(),
+            completed: false,
+            due_date,
+            recurrence: None,
+            assignee_user_id: None,
+            comment_count: 0,
+            blocked: false,
+        }
+    }
+
+    fn digest() -> TaskDigest {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let mut blocked_task = task(3, "Buy <paint> & brushes", None);
+        blocked_task.blocked = true;
+
+        TaskDigest {
+            recipient: DigestRecipient {
+                user_id: 1,
+                first_name: "Dana".to_owned(),
+                email: "dana@example.com".to_owned(),
+            },
+            date: today,
+            groups: vec![
+                DigestGroup {
+                    section: DigestSection::Overdue,
+                    tasks: vec![task(1, "Pay rent", today.pred_opt())],
+                },
+                DigestGroup {
+                    section: DigestSection::NoDueDate,
+                    tasks: vec![blocked_task],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn renders_text() {
+        assert_eq!(
+            "Good morning Dana,\n\n\
+            Here's where your tasks stand on Wed, 1 May 2024: 2 open tasks, 1 overdue.\n\
+            \n\
+            Overdue\n\
+            - Pay rent (due Tue, 30 Apr 2024)\n\
+            \n\
+            No due date\n\
+            - Buy <paint> & brushes [blocked]\n",
+            render_text(&digest())
+        );
+    }
+
+    #[test]
+    fn escapes_html() {
+        let html = render_html(&digest());
+
+        assert!(html.contains(
+            "<h2>Overdue</h2>\n<ul>\n<li>Pay rent <small>(due Tue, 30 Apr 2024)</small></li>\n"
+        ));
+        assert!(html.contains("<li>Buy &lt;paint&gt; &amp; brushes <em>blocked</em></li>\n"));
+    }
+
+    #[tokio::test]
+    async fn emails_the_digest() {
+        let catcher = MailCatcher::start().await;
+        let sender = SmtpDigestSender::new(SmtpClient::new(catcher.config()).unwrap());
+
+        sender
+            .send_digest(&digest())
+            .await
+            .expect("Could not send digest");
+
+        let messages = catcher.messages();
+        let [message] = messages.as_slice() else {
+            panic!("Expected one message, got {messages:#?}");
+        };
+        assert_eq!(vec!["dana@example.com".to_owned()], message.to);
+        assert!(message
+            .data
+            .contains("Subject: Your tasks for Wed, 1 May 2024: 2 open tasks, 1 overdue\r\n"));
+        assert!(message
+            .data
+            .contains("- Pay rent (due Tue, 30 Apr 2024)\r\n"));
+        assert!(message.data.contains("Content-Type: text/html"));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::domain;
+use crate::domain::reminder::DueReminder;
+use crate::outbound::smtp_client::{Email, SmtpClient};
+use anyhow::anyhow;
+
+/// A driven adapter which emails reminders to the users they're for over SMTP
+pub struct SmtpNotifier {
+    client: SmtpClient,
+}
+
+impl SmtpNotifier {
+    /// Constructs a notifier which emails reminders through [client]
+    pub fn new(client: SmtpClient) -> Self {
+        SmtpNotifier { client }
+    }
+}
+
+/// Writes the email telling a user about a reminder. Line breaks in the description are replaced with spaces,
+/// since the subject has to fit on one line.
+fn reminder_email(reminder: &DueReminder, to: &str) -> Email {
+    let subject_desc = reminder
+        .item_desc
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Email {
+        to: to.to_owned(),
+        subject: format!("Reminder: {subject_desc}"),
+        text_body: format!(
+            "You asked to be reminded of this task at {}:\n\n{}\n\nTask {} for user {}",
+            reminder.remind_at.to_rfc2822(),
+            reminder.item_desc,
+            reminder.task_id,
+            reminder.user_id,
+        ),
+        html_body: None,
+    }
+}
+
+impl domain::reminder::driven_ports::Notifier for SmtpNotifier {
+    async fn notify(&self, reminder: &DueReminder) -> Result<(), anyhow::Error> {
+        let Some(to) = &reminder.email else {
+            return Err(anyhow!(
+                "User {} has no email address to send the reminder to",
+                reminder.user_id
+            ));
+        };
+
+        self.client.send(&reminder_email(reminder, to)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::reminder::driven_ports::Notifier;
+    use crate::outbound::smtp_client::test_util::MailCatcher;
+    use chrono::{TimeZone, Utc};
+
+    fn reminder(item_desc: &str, email: Option<&str>) -> DueReminder {
+        DueReminder {
+            id: 3,
+            task_id: 8,
+            user_id: 2,
+            item_desc: item_desc.to_owned(),
+            remind_at: Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap(),
+            attempts: 0,
+            email: email.map(str::to_owned),
+        }
+    }
+
+    #[tokio::test]
+    async fn emails_the_reminder() {
+        let catcher = MailCatcher::start().await;
+        let notifier = SmtpNotifier::new(SmtpClient::new(catcher.config()).unwrap());
+
+        notifier
+            .notify(&reminder("Renew passport", Some("remy@example.com")))
+            .await
+            .expect("Could not send reminder");
+
+        let messages = catcher.messages();
+        let [message] = messages.as_slice() else {
+            panic!("Expected one message, got {messages:#?}");
+        };
+        assert_eq!(vec!["remy@example.com".to_owned()], message.to);
+        assert!(message
+            .data
+            .contains("Subject: Reminder: Renew passport\r\n"));
+        assert!(message.data.contains("Wed, 1 May 2024 09:00:00 +0000"));
+    }
+
+    #[tokio::test]
+    async fn puts_multiline_descriptions_on_one_subject_line() {
+        let catcher = MailCatcher::start().await;
+        let notifier = SmtpNotifier::new(SmtpClient::new(catcher.config()).unwrap());
+
+        notifier
+            .notify(&reminder(
+                "Renew passport\r\nand book flights",
+                Some("remy@example.com"),
+            ))
+            .await
+            .expect("Could not send reminder");
+
+        let messages = catcher.messages();
+        let [message] = messages.as_slice() else {
+            panic!("Expected one message, got {messages:#?}");
+        };
+        assert!(message
+            .data
+            .contains("Subject: Reminder: Renew passport and book flights\r\n"));
+    }
+
+    #[tokio::test]
+    async fn fails_for_users_without_an_email_address() {
+        let catcher = MailCatcher::start().await;
+        let notifier = SmtpNotifier::new(SmtpClient::new(catcher.config()).unwrap());
+
+        let notify_result = notifier.notify(&reminder("Renew passport", None)).await;
+
+        assert!(notify_result.is_err());
+        assert!(catcher.messages().is_empty());
+    }
+}
@@ -0,0 +1,57 @@
+use crate::domain;
+use crate::domain::reminder::DueReminder;
+use anyhow::{bail, Context};
+use serde_json::json;
+use std::time::Duration;
+
+/// How long the receiving endpoint has to respond before the reminder is considered unsent
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A driven adapter which posts reminders as JSON to a single URL
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    /// Constructs a notifier which posts every reminder to [url]. Redirects are not followed.
+    pub fn new(url: String) -> Result<Self, anyhow::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(NOTIFY_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .context("Building reminder webhook HTTP client")?;
+
+        Ok(WebhookNotifier { client, url })
+    }
+}
+
+/// Builds the JSON body describing a reminder
+fn reminder_body(reminder: &DueReminder) -> serde_json::Value {
+    json!({
+        "reminder_id": reminder.id,
+        "task_id": reminder.task_id,
+        "user_id": reminder.user_id,
+        "description": reminder.item_desc,
+        "remind_at": reminder.remind_at,
+    })
+}
+
+impl domain::reminder::driven_ports::Notifier for WebhookNotifier {
+    async fn notify(&self, reminder: &DueReminder) -> Result<(), anyhow::Error> {
+        let response = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(reminder_body(reminder).to_string())
+            .send()
+            .await
+            .with_context(|| format!("Sending reminder to {}", self.url))?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("Reminder webhook responded with status {status}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::domain;
+use crate::domain::attachment::{Attachment, NewAttachment};
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as};
+
+/// A database-based driven adapter for storing the details of files attached to tasks
+pub struct DbAttachmentStore;
+
+/// DTO containing the details of a task attachment from the database
+struct TaskAttachmentRow {
+    id: i32,
+    task_id: i32,
+    uploader_user_id: i32,
+    file_name: String,
+    content_type: String,
+    size_bytes: i64,
+    storage_key: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<TaskAttachmentRow> for Attachment {
+    fn from(value: TaskAttachmentRow) -> Self {
+        Attachment {
+            id: value.id,
+            task_id: value.task_id,
+            uploader_user_id: value.uploader_user_id,
+            file_name: value.file_name,
+            content_type: value.content_type,
+            size_bytes: value.size_bytes,
+            storage_key: value.storage_key,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl domain::attachment::driven_ports::AttachmentStore for DbAttachmentStore {
+    async fn add_attachment(
+        &self,
+        task_id: i32,
+        uploader_user_id: i32,
+        new_attachment: &NewAttachment,
+        storage_key: &str,
+        size_bytes: i64,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Attachment, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let attachment = query_as!(
+            TaskAttachmentRow,
+            "INSERT INTO task_attachment(task_id, uploader_user_id, file_name, content_type, size_bytes, storage_key) \
+            VALUES ($1, $2, $3, $4, $5, $6) \
+            RETURNING id, task_id, uploader_user_id, file_name, content_type, size_bytes, storage_key, created_at",
+            task_id,
+            uploader_user_id,
+            new_attachment.file_name,
+            new_attachment.content_type,
+            size_bytes,
+            storage_key,
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to add a task attachment")?;
+
+        Ok(attachment.into())
+    }
+
+    async fn attachment_by_id(
+        &self,
+        attachment_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<Attachment>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let attachment = query_as!(
+            TaskAttachmentRow,
+            "SELECT ta.id, ta.task_id, ta.uploader_user_id, ta.file_name, ta.content_type, ta.size_bytes, \
+            ta.storage_key, ta.created_at FROM task_attachment ta WHERE ta.id = $1",
+            attachment_id,
+        )
+        .fetch_optional(cxn.borrow_connection())
+        .await
+        .context("trying to fetch a task attachment")?;
+
+        Ok(attachment.map(Attachment::from))
+    }
+
+    async fn attachments_for_task(
+        &self,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<Attachment>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let attachments = query_as!(
+            TaskAttachmentRow,
+            "SELECT ta.id, ta.task_id, ta.uploader_user_id, ta.file_name, ta.content_type, ta.size_bytes, \
+            ta.storage_key, ta.created_at FROM task_attachment ta WHERE ta.task_id = $1 ORDER BY ta.id",
+            task_id,
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch the attachments on a task")?;
+
+        Ok(attachments.into_iter().map(Attachment::from).collect())
+    }
+
+    async fn delete_attachment(
+        &self,
+        attachment_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<bool, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let delete_result = query!("DELETE FROM task_attachment WHERE id = $1", attachment_id)
+            .execute(cxn.borrow_connection())
+            .await
+            .context("trying to delete a task attachment")?;
+
+        Ok(delete_result.rows_affected() > 0)
+    }
+}
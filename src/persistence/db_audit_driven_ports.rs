@@ -0,0 +1,90 @@
+use crate::domain;
+use crate::domain::audit::{AuditAction, AuditEntityType, AuditEntry, AuditFilter};
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use sqlx::query_as;
+
+/// A database-based driven adapter for reading the audit log
+pub struct DbAuditLog;
+
+/// DTO containing an audit log entry as it's stored in the database
+struct AuditLogRow {
+    id: i64,
+    actor_user_id: Option<i32>,
+    action: String,
+    entity_type: String,
+    entity_id: i32,
+    before_state: Option<serde_json::Value>,
+    after_state: Option<serde_json::Value>,
+    recorded_at: DateTime<Utc>,
+}
+
+impl TryFrom<AuditLogRow> for AuditEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(value: AuditLogRow) -> Result<Self, Self::Error> {
+        let action = AuditAction::from_name(&value.action).ok_or_else(|| {
+            anyhow!(
+                "Audit entry {} has unknown action {}",
+                value.id,
+                value.action
+            )
+        })?;
+        let entity_type = AuditEntityType::from_name(&value.entity_type).ok_or_else(|| {
+            anyhow!(
+                "Audit entry {} has unknown entity type {}",
+                value.id,
+                value.entity_type
+            )
+        })?;
+
+        Ok(AuditEntry {
+            id: value.id,
+            actor_user_id: value.actor_user_id,
+            action,
+            entity_type,
+            entity_id: value.entity_id,
+            before: value.before_state,
+            after: value.after_state,
+            recorded_at: value.recorded_at,
+        })
+    }
+}
+
+impl domain::audit::driven_ports::AuditLogReader for DbAuditLog {
+    async fn entries(
+        &self,
+        filter: &AuditFilter,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<AuditEntry>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let rows = query_as!(
+            AuditLogRow,
+            r#"SELECT al.id, al.actor_user_id, al.action, al.entity_type, al.entity_id,
+                al.before_state, al.after_state, al.recorded_at
+            FROM audit_log al
+            WHERE ($1::text IS NULL OR al.entity_type = $1)
+                AND ($2::integer IS NULL OR al.entity_id = $2)
+                AND ($3::integer IS NULL OR al.actor_user_id = $3)
+                AND ($4::timestamptz IS NULL OR al.recorded_at >= $4)
+                AND ($5::timestamptz IS NULL OR al.recorded_at < $5)
+                AND ($6::bigint IS NULL OR al.id < $6)
+            ORDER BY al.id DESC
+            LIMIT $7"#,
+            filter.entity_type.map(|entity_type| entity_type.name()),
+            filter.entity_id,
+            filter.actor_user_id,
+            filter.since,
+            filter.until,
+            filter.before_id,
+            filter.limit,
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to read the audit log")?;
+
+        rows.into_iter().map(AuditEntry::try_from).collect()
+    }
+}
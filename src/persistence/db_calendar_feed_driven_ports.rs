@@ -0,0 +1,68 @@
+use crate::domain;
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::Context;
+use sqlx::query;
+
+/// A database-based driven adapter for storing calendar feed tokens
+pub struct DbFeedTokenStore;
+
+impl domain::calendar_feed::driven_ports::FeedTokenStore for DbFeedTokenStore {
+    async fn replace_token(
+        &self,
+        user_id: i32,
+        token_hash: &str,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "INSERT INTO calendar_feed_token(user_id, token_hash) VALUES ($1, $2) \
+            ON CONFLICT (user_id) DO UPDATE SET token_hash = EXCLUDED.token_hash, created_at = now()",
+            user_id,
+            token_hash,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to replace a calendar feed token")?;
+
+        Ok(())
+    }
+
+    async fn revoke_token(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "DELETE FROM calendar_feed_token WHERE user_id = $1",
+            user_id
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to revoke a calendar feed token")?;
+
+        Ok(())
+    }
+
+    async fn token_matches(
+        &self,
+        user_id: i32,
+        token_hash: &str,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<bool, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let matching_token = query!(
+            "SELECT cft.user_id FROM calendar_feed_token cft WHERE cft.user_id = $1 AND cft.token_hash = $2",
+            user_id,
+            token_hash,
+        )
+        .fetch_optional(cxn.borrow_connection())
+        .await
+        .context("trying to check a calendar feed token")?;
+
+        Ok(matching_token.is_some())
+    }
+}
@@ -0,0 +1,144 @@
+use crate::domain;
+use crate::domain::comment::{CommentPage, NewComment, TaskComment};
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as};
+
+/// A database-based driven adapter for storing the comments left on tasks
+pub struct DbCommentStore;
+
+/// DTO containing a task comment from the database
+struct TaskCommentRow {
+    id: i32,
+    task_id: i32,
+    author_user_id: i32,
+    parent_comment_id: Option<i32>,
+    body: String,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<TaskCommentRow> for TaskComment {
+    fn from(value: TaskCommentRow) -> Self {
+        TaskComment {
+            id: value.id,
+            task_id: value.task_id,
+            author_user_id: value.author_user_id,
+            parent_comment_id: value.parent_comment_id,
+            body: value.body,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+impl domain::comment::driven_ports::CommentStore for DbCommentStore {
+    async fn add_comment(
+        &self,
+        task_id: i32,
+        author_user_id: i32,
+        new_comment: &NewComment,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<TaskComment, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let comment = query_as!(
+            TaskCommentRow,
+            "INSERT INTO task_comment(task_id, author_user_id, parent_comment_id, body) \
+            VALUES ($1, $2, $3, $4) \
+            RETURNING id, task_id, author_user_id, parent_comment_id, body, created_at, updated_at",
+            task_id,
+            author_user_id,
+            new_comment.parent_comment_id,
+            new_comment.body,
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to add a task comment")?;
+
+        Ok(comment.into())
+    }
+
+    async fn comment_by_id(
+        &self,
+        comment_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<TaskComment>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let comment = query_as!(
+            TaskCommentRow,
+            "SELECT tc.id, tc.task_id, tc.author_user_id, tc.parent_comment_id, tc.body, tc.created_at, \
+            tc.updated_at FROM task_comment tc WHERE tc.id = $1",
+            comment_id,
+        )
+        .fetch_optional(cxn.borrow_connection())
+        .await
+        .context("trying to fetch a task comment")?;
+
+        Ok(comment.map(TaskComment::from))
+    }
+
+    async fn edit_comment(
+        &self,
+        comment_id: i32,
+        body: &str,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<TaskComment>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let comment = query_as!(
+            TaskCommentRow,
+            "UPDATE task_comment SET body = $2, updated_at = now() WHERE id = $1 \
+            RETURNING id, task_id, author_user_id, parent_comment_id, body, created_at, updated_at",
+            comment_id,
+            body,
+        )
+        .fetch_optional(cxn.borrow_connection())
+        .await
+        .context("trying to edit a task comment")?;
+
+        Ok(comment.map(TaskComment::from))
+    }
+
+    async fn delete_comment(
+        &self,
+        comment_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<bool, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // Replies are removed by the cascading foreign key on parent_comment_id
+        let delete_result = query!("DELETE FROM task_comment WHERE id = $1", comment_id)
+            .execute(cxn.borrow_connection())
+            .await
+            .context("trying to delete a task comment")?;
+
+        Ok(delete_result.rows_affected() > 0)
+    }
+
+    async fn comments_for_task(
+        &self,
+        task_id: i32,
+        page: &CommentPage,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<TaskComment>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let comments = query_as!(
+            TaskCommentRow,
+            "SELECT tc.id, tc.task_id, tc.author_user_id, tc.parent_comment_id, tc.body, tc.created_at, \
+            tc.updated_at FROM task_comment tc \
+            WHERE tc.task_id = $1 AND ($2::integer IS NULL OR tc.id > $2) ORDER BY tc.id LIMIT $3",
+            task_id,
+            page.after_id,
+            page.limit,
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch the comments on a task")?;
+
+        Ok(comments.into_iter().map(TaskComment::from).collect())
+    }
+}
@@ -0,0 +1,134 @@
+use crate::domain;
+use crate::domain::digest::{DigestPreferences, DigestRecipient};
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::Context;
+use chrono::NaiveDate;
+use sqlx::{query, query_as};
+
+/// A database-based driven adapter which stores users' digest preferences and hands out the users whose
+/// digests are due
+pub struct DbDigestStore;
+
+/// DTO containing a user's digest preferences from the database
+struct DigestPreferencesRow {
+    email: Option<String>,
+    daily_digest: bool,
+}
+
+impl From<DigestPreferencesRow> for DigestPreferences {
+    fn from(value: DigestPreferencesRow) -> Self {
+        DigestPreferences {
+            email: value.email,
+            daily_digest: value.daily_digest,
+        }
+    }
+}
+
+/// DTO containing a user whose digest is due
+struct DigestRecipientRow {
+    id: i32,
+    first_name: String,
+    email: String,
+}
+
+impl From<DigestRecipientRow> for DigestRecipient {
+    fn from(value: DigestRecipientRow) -> Self {
+        DigestRecipient {
+            user_id: value.id,
+            first_name: value.first_name,
+            email: value.email,
+        }
+    }
+}
+
+impl domain::digest::driven_ports::DigestPreferenceStore for DbDigestStore {
+    async fn digest_preferences(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<DigestPreferences>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let preferences = query_as!(
+            DigestPreferencesRow,
+            "SELECT tu.email, tu.daily_digest FROM todo_user tu WHERE tu.id = $1",
+            user_id,
+        )
+        .fetch_optional(cxn.borrow_connection())
+        .await
+        .context("trying to fetch a user's digest preferences")?;
+
+        Ok(preferences.map(DigestPreferences::from))
+    }
+
+    async fn set_digest_preferences(
+        &self,
+        user_id: i32,
+        preferences: &DigestPreferences,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<bool, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let update_result = query!(
+            "UPDATE todo_user SET email = $2, daily_digest = $3 WHERE id = $1",
+            user_id,
+            preferences.email,
+            preferences.daily_digest,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to update a user's digest preferences")?;
+
+        Ok(update_result.rows_affected() > 0)
+    }
+}
+
+impl domain::digest::driven_ports::DigestQueue for DbDigestStore {
+    async fn claim_due(
+        &self,
+        day: NaiveDate,
+        limit: i64,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<DigestRecipient>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // Like reminders, claimed users stay locked until the caller's transaction ends and other runs skip them.
+        // NO KEY UPDATE is enough for that and still lets tasks be created for the user in the meantime.
+        let recipients = query_as!(
+            DigestRecipientRow,
+            "SELECT tu.id, tu.first_name, tu.email AS \"email!\" FROM todo_user tu \
+            WHERE tu.daily_digest AND tu.email IS NOT NULL \
+            AND (tu.last_digest_on IS NULL OR tu.last_digest_on < $1) \
+            ORDER BY tu.id \
+            LIMIT $2 \
+            FOR NO KEY UPDATE SKIP LOCKED",
+            day,
+            limit,
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to claim due digests")?;
+
+        Ok(recipients.into_iter().map(DigestRecipient::from).collect())
+    }
+
+    async fn mark_done(
+        &self,
+        user_id: i32,
+        day: NaiveDate,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "UPDATE todo_user SET last_digest_on = $2 WHERE id = $1",
+            user_id,
+            day,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to record a user's digest as done")?;
+
+        Ok(())
+    }
+}
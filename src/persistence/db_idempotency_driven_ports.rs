@@ -0,0 +1,137 @@
+use crate::domain;
+use crate::domain::idempotency::driven_ports::ClaimResult;
+use crate::domain::idempotency::{IdempotencyRecord, IdempotentRequest, StoredResponse};
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::Context;
+use sqlx::{query, query_as};
+use std::time::Duration;
+
+/// A database-based driven adapter for storing idempotency keys
+pub struct DbIdempotencyStore;
+
+/// DTO containing an idempotency key after a request tried to claim it
+struct IdempotencyKeyRow {
+    request_fingerprint: String,
+    response_status: Option<i16>,
+    response_content_type: Option<String>,
+    response_body: Option<Vec<u8>>,
+    /// Whether the request which last tried to claim the key got it
+    last_claimed: bool,
+}
+
+impl From<IdempotencyKeyRow> for IdempotencyRecord {
+    fn from(value: IdempotencyKeyRow) -> Self {
+        let response = match (value.response_status, value.response_body) {
+            (Some(status), Some(body)) => Some(StoredResponse {
+                status: status as u16,
+                content_type: value.response_content_type,
+                body,
+            }),
+            _ => None,
+        };
+
+        IdempotencyRecord {
+            fingerprint: value.request_fingerprint,
+            response,
+        }
+    }
+}
+
+impl domain::idempotency::driven_ports::IdempotencyStore for DbIdempotencyStore {
+    async fn claim(
+        &self,
+        request: &IdempotentRequest,
+        ttl: Duration,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<ClaimResult, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // Expired keys are taken over by the new request as if they never existed. Live keys are left as they
+        // are, but still go through the update so the row is locked and returned in the same statement, which
+        // means concurrent requests with the same key can't both believe they claimed it.
+        let key = query_as!(
+            IdempotencyKeyRow,
+            "INSERT INTO idempotency_key AS ik(request_scope, idempotency_key, request_fingerprint, last_claimed, expires_at) \
+            VALUES ($1, $2, $3, true, now() + make_interval(secs => $4)) \
+            ON CONFLICT (request_scope, idempotency_key) DO UPDATE SET \
+                request_fingerprint = CASE WHEN ik.expires_at < now() THEN EXCLUDED.request_fingerprint ELSE ik.request_fingerprint END, \
+                response_status = CASE WHEN ik.expires_at < now() THEN NULL ELSE ik.response_status END, \
+                response_content_type = CASE WHEN ik.expires_at < now() THEN NULL ELSE ik.response_content_type END, \
+                response_body = CASE WHEN ik.expires_at < now() THEN NULL ELSE ik.response_body END, \
+                last_claimed = ik.expires_at < now(), \
+                expires_at = CASE WHEN ik.expires_at < now() THEN EXCLUDED.expires_at ELSE ik.expires_at END \
+            RETURNING ik.request_fingerprint, ik.response_status, ik.response_content_type, ik.response_body, ik.last_claimed",
+            request.scope,
+            request.key,
+            request.fingerprint,
+            ttl.as_secs_f64(),
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to claim an idempotency key")?;
+
+        if key.last_claimed {
+            Ok(ClaimResult::Claimed)
+        } else {
+            Ok(ClaimResult::AlreadyClaimed(IdempotencyRecord::from(key)))
+        }
+    }
+
+    async fn save_response(
+        &self,
+        request: &IdempotentRequest,
+        response: &StoredResponse,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "UPDATE idempotency_key SET response_status = $1, response_content_type = $2, response_body = $3 \
+            WHERE request_scope = $4 AND idempotency_key = $5",
+            response.status as i16,
+            response.content_type,
+            response.body,
+            request.scope,
+            request.key,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to save the response for an idempotency key")?;
+
+        Ok(())
+    }
+
+    async fn release(
+        &self,
+        request: &IdempotentRequest,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "DELETE FROM idempotency_key \
+            WHERE request_scope = $1 AND idempotency_key = $2 AND response_status IS NULL",
+            request.scope,
+            request.key,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to release an idempotency key")?;
+
+        Ok(())
+    }
+
+    async fn purge_expired(
+        &self,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<u64, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let purge_result = query!("DELETE FROM idempotency_key WHERE expires_at < now()")
+            .execute(cxn.borrow_connection())
+            .await
+            .context("trying to purge expired idempotency keys")?;
+
+        Ok(purge_result.rows_affected())
+    }
+}
@@ -0,0 +1,65 @@
+use crate::domain;
+use crate::domain::rate_limit::{RateLimit, TokenTake};
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::Context;
+use sqlx::query;
+use std::time::Duration;
+
+/// A database-based driven adapter for rate limit buckets, which lets every instance of the app share them
+pub struct DbBucketStore;
+
+impl domain::rate_limit::driven_ports::BucketStore for DbBucketStore {
+    async fn take_token(
+        &self,
+        bucket_key: &str,
+        limit: &RateLimit,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<TokenTake, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // Every expression in the update sees the bucket as it was, so the refill is worked out the same
+        // way for the token count and for whether a token was taken
+        let bucket = query!(
+            "INSERT INTO rate_limit_bucket AS bucket(bucket_key, tokens, last_allowed, updated_at) \
+            VALUES ($1, $2::float8 - 1, true, now()) \
+            ON CONFLICT (bucket_key) DO UPDATE SET \
+                tokens = CASE \
+                    WHEN least($2::float8, bucket.tokens + extract(epoch FROM now() - bucket.updated_at)::float8 * $3::float8) >= 1 \
+                    THEN least($2::float8, bucket.tokens + extract(epoch FROM now() - bucket.updated_at)::float8 * $3::float8) - 1 \
+                    ELSE least($2::float8, bucket.tokens + extract(epoch FROM now() - bucket.updated_at)::float8 * $3::float8) \
+                END, \
+                last_allowed = least($2::float8, bucket.tokens + extract(epoch FROM now() - bucket.updated_at)::float8 * $3::float8) >= 1, \
+                updated_at = now() \
+            RETURNING bucket.tokens, bucket.last_allowed",
+            bucket_key,
+            f64::from(limit.capacity),
+            limit.refill_rate(),
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to take a token from a rate limit bucket")?;
+
+        Ok(TokenTake {
+            allowed: bucket.last_allowed,
+            tokens_left: bucket.tokens,
+        })
+    }
+
+    async fn purge_idle(
+        &self,
+        idle_for: Duration,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<u64, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let purge_result = query!(
+            "DELETE FROM rate_limit_bucket WHERE updated_at < now() - make_interval(secs => $1)",
+            idle_for.as_secs_f64(),
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to purge idle rate limit buckets")?;
+
+        Ok(purge_result.rows_affected())
+    }
+}
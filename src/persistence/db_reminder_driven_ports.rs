@@ -0,0 +1,205 @@
+use crate::domain;
+use crate::domain::reminder::{DueReminder, TaskReminder};
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as};
+use std::time::Duration;
+
+/// A database-based driven adapter which stores the reminders set on tasks and hands them out once they're due
+pub struct DbReminderStore;
+
+/// DTO containing a task reminder from the database
+struct TaskReminderRow {
+    id: i32,
+    task_id: i32,
+    user_id: i32,
+    remind_at: DateTime<Utc>,
+    sent_at: Option<DateTime<Utc>>,
+    failed_at: Option<DateTime<Utc>>,
+}
+
+impl From<TaskReminderRow> for TaskReminder {
+    fn from(value: TaskReminderRow) -> Self {
+        TaskReminder {
+            id: value.id,
+            task_id: value.task_id,
+            user_id: value.user_id,
+            remind_at: value.remind_at,
+            sent_at: value.sent_at,
+            failed_at: value.failed_at,
+        }
+    }
+}
+
+/// DTO containing a due reminder claimed from the database, joined with its task
+struct DueReminderRow {
+    id: i32,
+    task_id: i32,
+    user_id: i32,
+    item_desc: String,
+    remind_at: DateTime<Utc>,
+    attempts: i32,
+    email: Option<String>,
+}
+
+impl From<DueReminderRow> for DueReminder {
+    fn from(value: DueReminderRow) -> Self {
+        DueReminder {
+            id: value.id,
+            task_id: value.task_id,
+            user_id: value.user_id,
+            item_desc: value.item_desc,
+            remind_at: value.remind_at,
+            attempts: value.attempts,
+            email: value.email,
+        }
+    }
+}
+
+impl domain::reminder::driven_ports::ReminderStore for DbReminderStore {
+    async fn add_reminder(
+        &self,
+        task_id: i32,
+        user_id: i32,
+        remind_at: DateTime<Utc>,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<TaskReminder, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let reminder = query_as!(
+            TaskReminderRow,
+            "INSERT INTO task_reminder(task_id, user_id, remind_at) VALUES ($1, $2, $3) \
+            RETURNING id, task_id, user_id, remind_at, sent_at, failed_at",
+            task_id,
+            user_id,
+            remind_at,
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to add a task reminder")?;
+
+        Ok(reminder.into())
+    }
+
+    async fn reminders_for_task(
+        &self,
+        task_id: i32,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<TaskReminder>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let reminders = query_as!(
+            TaskReminderRow,
+            "SELECT id, task_id, user_id, remind_at, sent_at, failed_at FROM task_reminder \
+            WHERE task_id = $1 AND user_id = $2 ORDER BY remind_at, id",
+            task_id,
+            user_id,
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch the reminders on a task")?;
+
+        Ok(reminders.into_iter().map(TaskReminder::from).collect())
+    }
+
+    async fn delete_reminder(
+        &self,
+        reminder_id: i32,
+        task_id: i32,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<bool, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let delete_result = query!(
+            "DELETE FROM task_reminder WHERE id = $1 AND task_id = $2 AND user_id = $3",
+            reminder_id,
+            task_id,
+            user_id,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to delete a task reminder")?;
+
+        Ok(delete_result.rows_affected() > 0)
+    }
+}
+
+impl domain::reminder::driven_ports::DueReminderQueue for DbReminderStore {
+    async fn claim_due(
+        &self,
+        limit: i64,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<DueReminder>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // The row locks are held until the caller's transaction ends, and SKIP LOCKED makes other dispatchers
+        // pass over them instead of waiting, so each reminder is only sent by one instance
+        let reminders = query_as!(
+            DueReminderRow,
+            "SELECT tr.id, tr.task_id, tr.user_id, ti.item_desc, tr.remind_at, tr.attempts, tu.email \
+            FROM task_reminder tr \
+            JOIN todo_item ti ON ti.id = tr.task_id \
+            JOIN todo_user tu ON tu.id = tr.user_id \
+            WHERE tr.sent_at IS NULL AND tr.failed_at IS NULL AND tr.remind_at <= now() \
+            AND (tr.next_attempt_at IS NULL OR tr.next_attempt_at <= now()) \
+            AND NOT ti.completed AND ti.deleted_at IS NULL \
+            ORDER BY tr.remind_at, tr.id \
+            LIMIT $1 \
+            FOR UPDATE OF tr SKIP LOCKED",
+            limit,
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to claim due reminders")?;
+
+        Ok(reminders.into_iter().map(DueReminder::from).collect())
+    }
+
+    async fn mark_sent(
+        &self,
+        reminder_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "UPDATE task_reminder SET attempts = attempts + 1, sent_at = now(), last_error = NULL \
+            WHERE id = $1",
+            reminder_id,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to mark a reminder as sent")?;
+
+        Ok(())
+    }
+
+    async fn record_failure(
+        &self,
+        reminder_id: i32,
+        error: &str,
+        retry_after: Option<Duration>,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // Without a time to retry at, the reminder is given up on
+        query!(
+            "UPDATE task_reminder SET attempts = attempts + 1, last_error = $2, \
+            next_attempt_at = now() + make_interval(secs => $3), \
+            failed_at = CASE WHEN $3 IS NULL THEN now() END \
+            WHERE id = $1",
+            reminder_id,
+            error,
+            retry_after.map(|retry_after| retry_after.as_secs_f64()),
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to record a failed reminder")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use crate::domain;
+use crate::domain::share::{NewShare, SharePermission, SharedTask, TaskShare};
+use crate::domain::todo::TodoTask;
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::{anyhow, Context};
+use chrono::NaiveDate;
+use sqlx::{query, query_as};
+
+/// A database-based driven adapter for storing the shares users grant on their tasks
+pub struct DbShareStore;
+
+/// DTO containing a share from the database
+struct TaskShareRow {
+    id: i32,
+    owner_user_id: i32,
+    shared_with_user_id: i32,
+    task_id: Option<i32>,
+    permission: String,
+}
+
+impl TryFrom<TaskShareRow> for TaskShare {
+    type Error = anyhow::Error;
+
+    fn try_from(value: TaskShareRow) -> Result<Self, Self::Error> {
+        let permission = SharePermission::from_name(&value.permission).ok_or_else(|| {
+            anyhow!(
+                "Share {} has an unknown permission {}",
+                value.id,
+                value.permission
+            )
+        })?;
+
+        Ok(TaskShare {
+            id: value.id,
+            owner_user_id: value.owner_user_id,
+            shared_with_user_id: value.shared_with_user_id,
+            task_id: value.task_id,
+            permission,
+        })
+    }
+}
+
+/// DTO containing a shared to-do item along with whether any of its shares allow editing it
+struct SharedItemRow {
+    id: i32,
+    user_id: i32,
+    item_desc: String,
+    completed: bool,
+    due_date: Option<NaiveDate>,
+    recurrence_rule: Option<String>,
+    assignee_user_id: Option<i32>,
+    comment_count: i64,
+    blocked: bool,
+    can_edit: bool,
+}
+
+impl TryFrom<SharedItemRow> for SharedTask {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SharedItemRow) -> Result<Self, Self::Error> {
+        let recurrence = value
+            .recurrence_rule
+            .map(|rule| {
+                rule.parse().with_context(|| {
+                    format!("Task {} has an invalid recurrence rule {rule}", value.id)
+                })
+            })
+            .transpose()?;
+        let permission = if value.can_edit {
+            SharePermission::Edit
+        } else {
+            SharePermission::Read
+        };
+
+        Ok(SharedTask {
+            task: TodoTask {
+                id: value.id,
+                owner_user_id: value.user_id,
+                item_desc: value.item_desc,
+                completed: value.completed,
+                due_date: value.due_date,
+                recurrence,
+                assignee_user_id: value.assignee_user_id,
+                comment_count: value.comment_count,
+                blocked: value.blocked,
+            },
+            permission,
+        })
+    }
+}
+
+impl domain::share::driven_ports::ShareStore for DbShareStore {
+    async fn grant_share(
+        &self,
+        owner_user_id: i32,
+        new_share: &NewShare,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<i32, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let share_id = query_as!(
+            super::NewId,
+            "INSERT INTO task_share(owner_user_id, shared_with_user_id, task_id, permission) \
+            VALUES ($1, $2, $3, $4) \
+            ON CONFLICT (owner_user_id, shared_with_user_id, coalesce(task_id, 0)) \
+            DO UPDATE SET permission = EXCLUDED.permission \
+            RETURNING id",
+            owner_user_id,
+            new_share.shared_with_user_id,
+            new_share.task_id,
+            new_share.permission.name(),
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to grant a task share")?;
+
+        Ok(share_id.id)
+    }
+
+    async fn revoke_share(
+        &self,
+        owner_user_id: i32,
+        share_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<bool, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let revoke_result = query!(
+            "DELETE FROM task_share WHERE id = $1 AND owner_user_id = $2",
+            share_id,
+            owner_user_id,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to revoke a task share")?;
+
+        Ok(revoke_result.rows_affected() > 0)
+    }
+
+    async fn shares_granted_by(
+        &self,
+        owner_user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<TaskShare>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let shares = query_as!(
+            TaskShareRow,
+            "SELECT ts.id, ts.owner_user_id, ts.shared_with_user_id, ts.task_id, ts.permission \
+            FROM task_share ts WHERE ts.owner_user_id = $1 ORDER BY ts.id",
+            owner_user_id,
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch the shares granted by a user")?
+        .into_iter()
+        .map(TaskShare::try_from)
+        .collect::<Result<_, _>>()?;
+
+        Ok(shares)
+    }
+
+    async fn tasks_shared_with(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<SharedTask>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let shared_tasks = query_as!(
+            SharedItemRow,
+            r#"SELECT ti.id, ti.user_id, ti.item_desc, ti.completed, ti.due_date, ti.recurrence_rule,
+                ti.assignee_user_id,
+                (SELECT count(*) FROM task_comment tc WHERE tc.task_id = ti.id) AS "comment_count!",
+                EXISTS (SELECT 1 FROM task_dependency td JOIN todo_item blocker ON blocker.id = td.blocked_by_task_id
+                    WHERE td.task_id = ti.id AND NOT blocker.completed AND blocker.deleted_at IS NULL) AS "blocked!",
+                bool_or(ts.permission = 'edit') AS "can_edit!"
+            FROM todo_item ti
+            JOIN task_share ts ON ts.owner_user_id = ti.user_id AND (ts.task_id IS NULL OR ts.task_id = ti.id)
+            WHERE ts.shared_with_user_id = $1 AND ti.deleted_at IS NULL
+            GROUP BY ti.id ORDER BY ti.id"#,
+            user_id,
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch the tasks shared with a user")?
+        .into_iter()
+        .map(SharedTask::try_from)
+        .collect::<Result<_, _>>()?;
+
+        Ok(shared_tasks)
+    }
+}
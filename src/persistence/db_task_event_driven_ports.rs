@@ -0,0 +1,190 @@
+use crate::domain;
+use crate::domain::task_event::{TaskChange, TaskChangeKind};
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::{anyhow, bail, Context};
+use log::{error, warn};
+use sqlx::postgres::PgListener;
+use sqlx::{query, query_as, PgPool};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// The channel the database sends task change notifications on
+const TASK_CHANGE_CHANNEL: &str = "task_changes";
+/// How long to wait before reconnecting after listening for task changes fails
+const LISTEN_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// A database-based driven adapter for reading the log of task changes
+pub struct DbTaskChangeLog;
+
+/// DTO containing a task change as it's stored in the database
+struct TaskChangeRow {
+    id: i64,
+    task_id: i32,
+    event_type: String,
+    item_desc: Option<String>,
+    completed: Option<bool>,
+}
+
+impl TryFrom<TaskChangeRow> for TaskChange {
+    type Error = anyhow::Error;
+
+    fn try_from(value: TaskChangeRow) -> Result<Self, Self::Error> {
+        let kind = TaskChangeKind::from_name(&value.event_type).ok_or_else(|| {
+            anyhow!(
+                "Task change {} has unknown type {}",
+                value.id,
+                value.event_type
+            )
+        })?;
+
+        Ok(TaskChange {
+            id: value.id,
+            task_id: value.task_id,
+            kind,
+            description: value.item_desc,
+            completed: value.completed,
+        })
+    }
+}
+
+impl domain::task_event::driven_ports::TaskChangeLog for DbTaskChangeLog {
+    async fn changes_since(
+        &self,
+        user_id: i32,
+        after_id: i64,
+        limit: i64,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<TaskChange>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let rows = query_as!(
+            TaskChangeRow,
+            "SELECT te.id, te.task_id, te.event_type, te.item_desc, te.completed FROM task_event te \
+            WHERE te.user_id = $1 AND te.id > $2 ORDER BY te.id LIMIT $3",
+            user_id,
+            after_id,
+            limit,
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch task changes")?;
+
+        rows.into_iter().map(TaskChange::try_from).collect()
+    }
+
+    async fn latest_change_id(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<i64, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let latest = query!(
+            "SELECT coalesce(max(te.id), 0) AS \"latest_id!\" FROM task_event te WHERE te.user_id = $1",
+            user_id,
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to look up the latest task change")?;
+
+        Ok(latest.latest_id)
+    }
+
+    async fn purge_changes_older_than(
+        &self,
+        age: Duration,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<u64, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let purge_result = query!(
+            "DELETE FROM task_event WHERE created_at < now() - make_interval(secs => $1)",
+            age.as_secs_f64(),
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to purge old task changes")?;
+
+        Ok(purge_result.rows_affected())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A notification that tasks have changed, shared with every open task event stream
+pub enum TaskChangeNotice {
+    /// The tasks of the user with this ID changed
+    User(i32),
+    /// Notifications may have been missed, so any user's tasks could have changed
+    Resync,
+}
+
+/// Receives task change notices on behalf of a single task event stream
+pub struct TaskChangeSubscription(broadcast::Receiver<TaskChangeNotice>);
+
+impl TaskChangeSubscription {
+    /// Starts receiving the notices sent through `sender`
+    pub fn new(sender: &broadcast::Sender<TaskChangeNotice>) -> Self {
+        TaskChangeSubscription(sender.subscribe())
+    }
+}
+
+impl domain::task_event::driven_ports::TaskChangeNotices for TaskChangeSubscription {
+    async fn wait_for_user(&mut self, user_id: i32) -> Result<(), anyhow::Error> {
+        loop {
+            match self.0.recv().await {
+                Ok(TaskChangeNotice::User(changed_user)) if changed_user != user_id => continue,
+                Ok(_) | Err(RecvError::Lagged(_)) => return Ok(()),
+                Err(RecvError::Closed) => bail!("Task change notifications have stopped"),
+            }
+        }
+    }
+}
+
+/// Listens for task change notifications from the database and forwards them to `notices`.
+/// A single listener serves every stream in the application so each stream doesn't hold its own connection.
+pub async fn listen_for_task_changes(db: PgPool, notices: broadcast::Sender<TaskChangeNotice>) {
+    loop {
+        let listen_result = async {
+            let mut listener = PgListener::connect_with(&db).await?;
+            listener.listen(TASK_CHANGE_CHANNEL).await?;
+            Ok::<_, sqlx::Error>(listener)
+        }
+        .await;
+        let mut listener = match listen_result {
+            Ok(listener) => listener,
+            Err(listen_err) => {
+                error!("Could not listen for task changes: {listen_err}");
+                tokio::time::sleep(LISTEN_RETRY_DELAY).await;
+                continue;
+            }
+        };
+        // Sending fails when nobody is streaming task events, which is fine to ignore
+        let _ = notices.send(TaskChangeNotice::Resync);
+
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(notification)) => match notification.payload().parse() {
+                    Ok(user_id) => {
+                        let _ = notices.send(TaskChangeNotice::User(user_id));
+                    }
+                    Err(_) => warn!(
+                        "Ignoring task change notification with unexpected payload {}",
+                        notification.payload()
+                    ),
+                },
+                // The listener reconnects on the next call, but anything sent in the meantime is lost
+                Ok(None) => {
+                    warn!("Lost connection while listening for task changes");
+                    let _ = notices.send(TaskChangeNotice::Resync);
+                }
+                Err(recv_err) => {
+                    error!("Stopped receiving task changes: {recv_err}");
+                    break;
+                }
+            }
+        }
+
+        tokio::time::sleep(LISTEN_RETRY_DELAY).await;
+    }
+}
@@ -1,8 +1,17 @@
 use crate::domain;
-use crate::domain::todo::{NewTask, TodoTask, UpdateTask};
+use crate::domain::audit::{AuditAction, AuditEntityType};
+use crate::domain::share::SharePermission;
+use crate::domain::todo::dependency::TaskDependency;
+use crate::domain::todo::recurrence::Recurrence;
+use crate::domain::todo::{
+    NewTask, TaskSearchMatch, TaskSummary, TodoTask, TrashedTask, UpdateTask, SEARCH_HIGHLIGHT_END,
+    SEARCH_HIGHLIGHT_START,
+};
 use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
 use anyhow::{Context, Error};
+use chrono::{DateTime, NaiveDate, Utc};
 use sqlx::{query, query_as};
+use std::time::Duration;
 
 /// A database-based driven adapter for reading tasks
 pub struct DbTaskReader;
@@ -12,15 +21,114 @@ struct TodoItemRow {
     id: i32,
     user_id: i32,
     item_desc: String,
+    completed: bool,
+    due_date: Option<NaiveDate>,
+    recurrence_rule: Option<String>,
+    assignee_user_id: Option<i32>,
+    comment_count: i64,
+    blocked: bool,
 }
 
-impl From<TodoItemRow> for domain::todo::TodoTask {
-    fn from(value: TodoItemRow) -> Self {
-        TodoTask {
+impl TryFrom<TodoItemRow> for TodoTask {
+    type Error = Error;
+
+    fn try_from(value: TodoItemRow) -> Result<Self, Self::Error> {
+        let recurrence = value
+            .recurrence_rule
+            .map(|rule| {
+                rule.parse::<Recurrence>().with_context(|| {
+                    format!("Task {} has an invalid recurrence rule {rule}", value.id)
+                })
+            })
+            .transpose()?;
+
+        Ok(TodoTask {
             id: value.id,
             owner_user_id: value.user_id,
             item_desc: value.item_desc,
-        }
+            completed: value.completed,
+            due_date: value.due_date,
+            recurrence,
+            assignee_user_id: value.assignee_user_id,
+            comment_count: value.comment_count,
+            blocked: value.blocked,
+        })
+    }
+}
+
+/// DTO containing a to-do item which was moved to the trash
+struct TrashedItemRow {
+    id: i32,
+    user_id: i32,
+    item_desc: String,
+    completed: bool,
+    due_date: Option<NaiveDate>,
+    recurrence_rule: Option<String>,
+    assignee_user_id: Option<i32>,
+    comment_count: i64,
+    blocked: bool,
+    deleted_at: DateTime<Utc>,
+}
+
+impl TryFrom<TrashedItemRow> for TrashedTask {
+    type Error = Error;
+
+    fn try_from(value: TrashedItemRow) -> Result<Self, Self::Error> {
+        let task = TodoTask::try_from(TodoItemRow {
+            id: value.id,
+            user_id: value.user_id,
+            item_desc: value.item_desc,
+            completed: value.completed,
+            due_date: value.due_date,
+            recurrence_rule: value.recurrence_rule,
+            assignee_user_id: value.assignee_user_id,
+            comment_count: value.comment_count,
+            blocked: value.blocked,
+        })?;
+
+        Ok(TrashedTask {
+            task,
+            deleted_at: value.deleted_at,
+        })
+    }
+}
+
+/// DTO containing a to-do item which matched a full-text search
+struct TaskSearchRow {
+    id: i32,
+    user_id: i32,
+    item_desc: String,
+    completed: bool,
+    due_date: Option<NaiveDate>,
+    recurrence_rule: Option<String>,
+    assignee_user_id: Option<i32>,
+    comment_count: i64,
+    blocked: bool,
+    rank: f32,
+    snippet: String,
+}
+
+impl TryFrom<TaskSearchRow> for TaskSearchMatch {
+    type Error = Error;
+
+    fn try_from(value: TaskSearchRow) -> Result<Self, Self::Error> {
+        let task = TodoTask::try_from(TodoItemRow {
+            id: value.id,
+            user_id: value.user_id,
+            item_desc: value.item_desc,
+            completed: value.completed,
+            due_date: value.due_date,
+            recurrence_rule: value.recurrence_rule,
+            assignee_user_id: value.assignee_user_id,
+            comment_count: value.comment_count,
+            blocked: value.blocked,
+        })?;
+
+        Ok(TaskSearchMatch {
+            task,
+            rank: value.rank,
+            snippet: value.snippet,
+        })
     }
 }
 
@@ -34,15 +142,53 @@ impl domain::todo::driven_ports::TaskReader for DbTaskReader {
 
         let todo_items: Vec<TodoTask> = query_as!(
             TodoItemRow,
-            "SELECT ti.* FROM todo_item ti WHERE ti.user_id = $1",
+            "SELECT ti.id, ti.user_id, ti.item_desc, ti.completed, ti.due_date, ti.recurrence_rule, \
+            ti.assignee_user_id, \
+            (SELECT count(*) FROM task_comment tc WHERE tc.task_id = ti.id) AS \"comment_count!\", \
+            EXISTS (SELECT 1 FROM task_dependency td JOIN todo_item blocker ON blocker.id = td.blocked_by_task_id \
+            WHERE td.task_id = ti.id AND NOT blocker.completed AND blocker.deleted_at IS NULL) AS \"blocked!\" \
+            FROM todo_item ti WHERE ti.user_id = $1 AND ti.deleted_at IS NULL",
             user_id
         )
         .fetch_all(cxn.borrow_connection())
         .await
         .context("trying to fetch todo items for a user")?
         .into_iter()
-        .map(domain::todo::TodoTask::from)
-        .collect();
+        .map(TodoTask::try_from)
+        .collect::<Result<_, _>>()?;
+
+        Ok(todo_items)
+    }
+
+    async fn task_page_for_user(
+        &self,
+        user_id: i32,
+        after_task_id: Option<i32>,
+        limit: i64,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<TodoTask>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let todo_items: Vec<TodoTask> = query_as!(
+            TodoItemRow,
+            "SELECT ti.id, ti.user_id, ti.item_desc, ti.completed, ti.due_date, ti.recurrence_rule, \
+            ti.assignee_user_id, \
+            (SELECT count(*) FROM task_comment tc WHERE tc.task_id = ti.id) AS \"comment_count!\", \
+            EXISTS (SELECT 1 FROM task_dependency td JOIN todo_item blocker ON blocker.id = td.blocked_by_task_id \
+            WHERE td.task_id = ti.id AND NOT blocker.completed AND blocker.deleted_at IS NULL) AS \"blocked!\" \
+            FROM todo_item ti \
+            WHERE ti.user_id = $1 AND ti.deleted_at IS NULL AND ($2::integer IS NULL OR ti.id > $2) \
+            ORDER BY ti.id LIMIT $3",
+            user_id,
+            after_task_id,
+            limit,
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch a page of todo items for a user")?
+        .into_iter()
+        .map(TodoTask::try_from)
+        .collect::<Result<_, _>>()?;
 
         Ok(todo_items)
     }
@@ -57,21 +203,304 @@ impl domain::todo::driven_ports::TaskReader for DbTaskReader {
 
         let todo_item: Option<TodoTask> = query_as!(
             TodoItemRow,
-            "SELECT ti.* FROM todo_item ti WHERE ti.user_id = $1 AND ti.id = $2",
+            "SELECT ti.id, ti.user_id, ti.item_desc, ti.completed, ti.due_date, ti.recurrence_rule, \
+            ti.assignee_user_id, \
+            (SELECT count(*) FROM task_comment tc WHERE tc.task_id = ti.id) AS \"comment_count!\", \
+            EXISTS (SELECT 1 FROM task_dependency td JOIN todo_item blocker ON blocker.id = td.blocked_by_task_id \
+            WHERE td.task_id = ti.id AND NOT blocker.completed AND blocker.deleted_at IS NULL) AS \"blocked!\" \
+            FROM todo_item ti WHERE ti.id = $2 AND ti.deleted_at IS NULL \
+            AND (ti.user_id = $1 OR ti.assignee_user_id = $1 OR EXISTS ( \
+                SELECT 1 FROM task_share ts WHERE ts.shared_with_user_id = $1 \
+                AND ts.owner_user_id = ti.user_id AND (ts.task_id IS NULL OR ts.task_id = ti.id)))",
             user_id,
             task_id
         )
         .fetch_optional(cxn.borrow_connection())
         .await
         .context("trying to fetch a todo item by ID")?
-        .map(domain::todo::TodoTask::from);
+        .map(TodoTask::try_from)
+        .transpose()?;
+
+        Ok(todo_item)
+    }
+
+    async fn task_by_id(
+        &self,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<TodoTask>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let todo_item: Option<TodoTask> = query_as!(
+            TodoItemRow,
+            "SELECT ti.id, ti.user_id, ti.item_desc, ti.completed, ti.due_date, ti.recurrence_rule, \
+            ti.assignee_user_id, \
+            (SELECT count(*) FROM task_comment tc WHERE tc.task_id = ti.id) AS \"comment_count!\", \
+            EXISTS (SELECT 1 FROM task_dependency td JOIN todo_item blocker ON blocker.id = td.blocked_by_task_id \
+            WHERE td.task_id = ti.id AND NOT blocker.completed AND blocker.deleted_at IS NULL) AS \"blocked!\" \
+            FROM todo_item ti WHERE ti.id = $1 AND ti.deleted_at IS NULL",
+            task_id
+        )
+        .fetch_optional(cxn.borrow_connection())
+        .await
+        .context("trying to fetch any user's todo item by ID")?
+        .map(TodoTask::try_from)
+        .transpose()?;
 
         Ok(todo_item)
     }
+
+    async fn task_owner(
+        &self,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<i32>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let owner = query!(
+            "SELECT ti.user_id FROM todo_item ti WHERE ti.id = $1 AND ti.deleted_at IS NULL",
+            task_id
+        )
+        .fetch_optional(cxn.borrow_connection())
+        .await
+        .context("trying to look up the owner of a todo item")?
+        .map(|row| row.user_id);
+
+        Ok(owner)
+    }
+
+    async fn trashed_task_owner(
+        &self,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<i32>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let owner = query!(
+            "SELECT ti.user_id FROM todo_item ti WHERE ti.id = $1 AND ti.deleted_at IS NOT NULL",
+            task_id
+        )
+        .fetch_optional(cxn.borrow_connection())
+        .await
+        .context("trying to look up the owner of a trashed todo item")?
+        .map(|row| row.user_id);
+
+        Ok(owner)
+    }
+
+    async fn task_assignee(
+        &self,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<i32>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let assignee = query!(
+            "SELECT ti.assignee_user_id FROM todo_item ti WHERE ti.id = $1",
+            task_id
+        )
+        .fetch_optional(cxn.borrow_connection())
+        .await
+        .context("trying to look up the assignee of a todo item")?
+        .and_then(|row| row.assignee_user_id);
+
+        Ok(assignee)
+    }
+
+    async fn tasks_assigned_to(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<TodoTask>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let todo_items: Vec<TodoTask> = query_as!(
+            TodoItemRow,
+            "SELECT ti.id, ti.user_id, ti.item_desc, ti.completed, ti.due_date, ti.recurrence_rule, \
+            ti.assignee_user_id, \
+            (SELECT count(*) FROM task_comment tc WHERE tc.task_id = ti.id) AS \"comment_count!\", \
+            EXISTS (SELECT 1 FROM task_dependency td JOIN todo_item blocker ON blocker.id = td.blocked_by_task_id \
+            WHERE td.task_id = ti.id AND NOT blocker.completed AND blocker.deleted_at IS NULL) AS \"blocked!\" \
+            FROM todo_item ti WHERE ti.assignee_user_id = $1 AND ti.deleted_at IS NULL ORDER BY ti.id",
+            user_id
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch the todo items assigned to a user")?
+        .into_iter()
+        .map(TodoTask::try_from)
+        .collect::<Result<_, _>>()?;
+
+        Ok(todo_items)
+    }
+
+    async fn shared_permission(
+        &self,
+        user_id: i32,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<SharePermission>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // With no matching shares there's nothing for bool_or() to aggregate, so it's null
+        let can_edit = query!(
+            "SELECT bool_or(ts.permission = 'edit') AS can_edit FROM task_share ts \
+            JOIN todo_item ti ON ts.owner_user_id = ti.user_id AND (ts.task_id IS NULL OR ts.task_id = ti.id) \
+            WHERE ti.id = $2 AND ts.shared_with_user_id = $1 AND ti.deleted_at IS NULL",
+            user_id,
+            task_id
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to look up the permission shared on a todo item")?
+        .can_edit;
+
+        Ok(can_edit.map(|can_edit| {
+            if can_edit {
+                SharePermission::Edit
+            } else {
+                SharePermission::Read
+            }
+        }))
+    }
+
+    async fn trashed_shared_permission(
+        &self,
+        user_id: i32,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<SharePermission>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // With no matching shares there's nothing for bool_or() to aggregate, so it's null
+        let can_edit = query!(
+            "SELECT bool_or(ts.permission = 'edit') AS can_edit FROM task_share ts \
+            JOIN todo_item ti ON ts.owner_user_id = ti.user_id AND (ts.task_id IS NULL OR ts.task_id = ti.id) \
+            WHERE ti.id = $2 AND ts.shared_with_user_id = $1 AND ti.deleted_at IS NOT NULL",
+            user_id,
+            task_id
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to look up the permission shared on a trashed todo item")?
+        .can_edit;
+
+        Ok(can_edit.map(|can_edit| {
+            if can_edit {
+                SharePermission::Edit
+            } else {
+                SharePermission::Read
+            }
+        }))
+    }
+
+    async fn task_summary_for_user(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<TaskSummary, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let summary = query_as!(
+            TaskSummary,
+            r#"SELECT count(*) AS "total!",
+                count(*) FILTER (WHERE ti.completed) AS "completed!",
+                count(*) FILTER (WHERE NOT ti.completed AND ti.due_date < current_date) AS "overdue!"
+            FROM todo_item ti WHERE ti.user_id = $1 AND ti.deleted_at IS NULL"#,
+            user_id
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to count todo items for a user")?;
+
+        Ok(summary)
+    }
+
+    async fn search_tasks(
+        &self,
+        user_id: i32,
+        search_text: &str,
+        limit: i64,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<TaskSearchMatch>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+        let headline_options =
+            format!("StartSel={SEARCH_HIGHLIGHT_START}, StopSel={SEARCH_HIGHLIGHT_END}");
+
+        // The description is HTML-escaped before it's highlighted, so the only markup in a snippet is the
+        // highlighting itself
+        let matches: Vec<TaskSearchMatch> = query_as!(
+            TaskSearchRow,
+            r#"SELECT ti.id, ti.user_id, ti.item_desc, ti.completed, ti.due_date, ti.recurrence_rule,
+                ti.assignee_user_id,
+                (SELECT count(*) FROM task_comment tc WHERE tc.task_id = ti.id) AS "comment_count!",
+                EXISTS (SELECT 1 FROM task_dependency td JOIN todo_item blocker ON blocker.id = td.blocked_by_task_id
+                    WHERE td.task_id = ti.id AND NOT blocker.completed AND blocker.deleted_at IS NULL) AS "blocked!",
+                ts_rank(ti.item_desc_search, search_query) AS "rank!",
+                ts_headline('english', replace(replace(replace(replace(replace(ti.item_desc,
+                    '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;'),
+                    search_query, $3) AS "snippet!"
+            FROM todo_item ti, websearch_to_tsquery('english', $2) search_query
+            WHERE ti.user_id = $1 AND ti.deleted_at IS NULL AND ti.item_desc_search @@ search_query
+            ORDER BY 10 DESC, ti.id
+            LIMIT $4"#,
+            user_id,
+            search_text,
+            headline_options,
+            limit
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to search todo items for a user")?
+        .into_iter()
+        .map(TaskSearchMatch::try_from)
+        .collect::<Result<_, _>>()?;
+
+        Ok(matches)
+    }
+
+    async fn trashed_tasks_for_user(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<TrashedTask>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let trashed_items: Vec<TrashedTask> = query_as!(
+            TrashedItemRow,
+            r#"SELECT ti.id, ti.user_id, ti.item_desc, ti.completed, ti.due_date, ti.recurrence_rule,
+                ti.assignee_user_id,
+                (SELECT count(*) FROM task_comment tc WHERE tc.task_id = ti.id) AS "comment_count!",
+                EXISTS (SELECT 1 FROM task_dependency td JOIN todo_item blocker ON blocker.id = td.blocked_by_task_id
+                    WHERE td.task_id = ti.id AND NOT blocker.completed AND blocker.deleted_at IS NULL) AS "blocked!",
+                ti.deleted_at AS "deleted_at!"
+            FROM todo_item ti
+            WHERE ti.user_id = $1 AND ti.deleted_at IS NOT NULL
+            ORDER BY ti.deleted_at DESC, ti.id"#,
+            user_id
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch trashed todo items for a user")?
+        .into_iter()
+        .map(TrashedTask::try_from)
+        .collect::<Result<_, _>>()?;
+
+        Ok(trashed_items)
+    }
 }
 
-/// A database-based driven adapter for writing new tasks
-pub struct DbTaskWriter;
+/// A database-based driven adapter for writing new tasks. Every change it makes is recorded in the audit log.
+pub struct DbTaskWriter {
+    actor_user_id: Option<i32>,
+}
+
+impl DbTaskWriter {
+    /// Creates a task writer whose changes are recorded in the audit log as made by the given user
+    pub fn new(actor_user_id: Option<i32>) -> Self {
+        DbTaskWriter { actor_user_id }
+    }
+}
 
 impl domain::todo::driven_ports::TaskWriter for DbTaskWriter {
     async fn create_task_for_user(
@@ -84,9 +513,22 @@ impl domain::todo::driven_ports::TaskWriter for DbTaskWriter {
 
         let new_id = query_as!(
             super::NewId,
-            "INSERT INTO todo_item(user_id, item_desc) VALUES ($1, $2) RETURNING todo_item.id",
+            r#"WITH inserted AS (
+                INSERT INTO todo_item(user_id, item_desc, due_date, recurrence_rule, assignee_user_id)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING todo_item.id, to_jsonb(todo_item) - 'item_desc_search' AS state
+            )
+            INSERT INTO audit_log(actor_user_id, action, entity_type, entity_id, after_state)
+            SELECT $6, $7, $8, inserted.id, inserted.state FROM inserted
+            RETURNING audit_log.entity_id AS id"#,
             user_id,
-            new_task.description
+            new_task.description,
+            new_task.due_date,
+            new_task.recurrence.as_ref().map(ToString::to_string),
+            new_task.assignee_user_id,
+            self.actor_user_id,
+            AuditAction::Create.name(),
+            AuditEntityType::Task.name(),
         )
         .fetch_one(cxn.borrow_connection())
         .await
@@ -102,14 +544,93 @@ impl domain::todo::driven_ports::TaskWriter for DbTaskWriter {
     ) -> Result<(), Error> {
         let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
 
-        query!("DELETE FROM todo_item WHERE id = $1", task_id)
-            .execute(cxn.borrow_connection())
-            .await
-            .context("trying to remove a task from the database")?;
+        query!(
+            r#"WITH deleted AS (
+                UPDATE todo_item SET deleted_at = now() FROM todo_item previous
+                WHERE todo_item.id = $1 AND previous.id = todo_item.id AND todo_item.deleted_at IS NULL
+                RETURNING todo_item.id,
+                    to_jsonb(previous) - 'item_desc_search' AS before_state,
+                    to_jsonb(todo_item) - 'item_desc_search' AS after_state
+            )
+            INSERT INTO audit_log(actor_user_id, action, entity_type, entity_id, before_state, after_state)
+            SELECT $2, $3, $4, deleted.id, deleted.before_state, deleted.after_state FROM deleted"#,
+            task_id,
+            self.actor_user_id,
+            AuditAction::Delete.name(),
+            AuditEntityType::Task.name(),
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to move a task to the trash")?;
 
         Ok(())
     }
 
+    async fn restore_task(
+        &self,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<TodoTask>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let restored_item = query_as!(
+            TodoItemRow,
+            r#"WITH restored AS (
+                UPDATE todo_item SET deleted_at = NULL FROM todo_item previous
+                WHERE todo_item.id = $1 AND previous.id = todo_item.id AND todo_item.deleted_at IS NOT NULL
+                RETURNING todo_item.id, todo_item.user_id, todo_item.item_desc, todo_item.completed,
+                    todo_item.due_date, todo_item.recurrence_rule, todo_item.assignee_user_id,
+                    to_jsonb(previous) - 'item_desc_search' AS before_state,
+                    to_jsonb(todo_item) - 'item_desc_search' AS after_state
+            ), audited AS (
+                INSERT INTO audit_log(actor_user_id, action, entity_type, entity_id, before_state, after_state)
+                SELECT $2, $3, $4, restored.id, restored.before_state, restored.after_state FROM restored
+            )
+            SELECT restored.id AS "id!", restored.user_id AS "user_id!",
+                restored.item_desc AS "item_desc!", restored.completed AS "completed!",
+                restored.due_date, restored.recurrence_rule, restored.assignee_user_id,
+                (SELECT count(*) FROM task_comment tc WHERE tc.task_id = restored.id) AS "comment_count!",
+                EXISTS (SELECT 1 FROM task_dependency td JOIN todo_item blocker ON blocker.id = td.blocked_by_task_id
+                    WHERE td.task_id = restored.id AND NOT blocker.completed AND blocker.deleted_at IS NULL) AS "blocked!"
+            FROM restored"#,
+            task_id,
+            self.actor_user_id,
+            AuditAction::Restore.name(),
+            AuditEntityType::Task.name(),
+        )
+        .fetch_optional(cxn.borrow_connection())
+        .await
+        .context("trying to restore a task from the trash")?;
+
+        restored_item.map(TodoTask::try_from).transpose()
+    }
+
+    async fn purge_trashed_tasks(
+        &self,
+        retention: Duration,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<u64, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let purge_result = query!(
+            r#"WITH purged AS (
+                DELETE FROM todo_item WHERE deleted_at < now() - make_interval(secs => $1)
+                RETURNING todo_item.id, to_jsonb(todo_item) - 'item_desc_search' AS state
+            )
+            INSERT INTO audit_log(actor_user_id, action, entity_type, entity_id, before_state)
+            SELECT $2, $3, $4, purged.id, purged.state FROM purged"#,
+            retention.as_secs_f64(),
+            self.actor_user_id,
+            AuditAction::Purge.name(),
+            AuditEntityType::Task.name(),
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to purge trashed tasks")?;
+
+        Ok(purge_result.rows_affected())
+    }
+
     async fn update_task(
         &self,
         task_id: i32,
@@ -118,10 +639,22 @@ impl domain::todo::driven_ports::TaskWriter for DbTaskWriter {
     ) -> Result<(), Error> {
         let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
 
+        // Joining the task to itself gives access to its state from before the update
         query!(
-            "UPDATE todo_item SET item_desc = $1 WHERE id = $2",
+            r#"WITH updated AS (
+                UPDATE todo_item SET item_desc = $1 FROM todo_item previous
+                WHERE todo_item.id = $2 AND previous.id = todo_item.id AND todo_item.deleted_at IS NULL
+                RETURNING todo_item.id,
+                    to_jsonb(previous) - 'item_desc_search' AS before_state,
+                    to_jsonb(todo_item) - 'item_desc_search' AS after_state
+            )
+            INSERT INTO audit_log(actor_user_id, action, entity_type, entity_id, before_state, after_state)
+            SELECT $3, $4, $5, updated.id, updated.before_state, updated.after_state FROM updated"#,
             update.description,
-            task_id
+            task_id,
+            self.actor_user_id,
+            AuditAction::Update.name(),
+            AuditEntityType::Task.name(),
         )
         .execute(cxn.borrow_connection())
         .await
@@ -129,4 +662,147 @@ impl domain::todo::driven_ports::TaskWriter for DbTaskWriter {
 
         Ok(())
     }
+
+    async fn complete_task(
+        &self,
+        task_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            r#"WITH updated AS (
+                UPDATE todo_item SET completed = true FROM todo_item previous
+                WHERE todo_item.id = $1 AND previous.id = todo_item.id AND todo_item.deleted_at IS NULL
+                RETURNING todo_item.id,
+                    to_jsonb(previous) - 'item_desc_search' AS before_state,
+                    to_jsonb(todo_item) - 'item_desc_search' AS after_state
+            )
+            INSERT INTO audit_log(actor_user_id, action, entity_type, entity_id, before_state, after_state)
+            SELECT $2, $3, $4, updated.id, updated.before_state, updated.after_state FROM updated"#,
+            task_id,
+            self.actor_user_id,
+            AuditAction::Complete.name(),
+            AuditEntityType::Task.name(),
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to mark a task as completed in the database")?;
+
+        Ok(())
+    }
+
+    async fn assign_task(
+        &self,
+        task_id: i32,
+        assignee_user_id: Option<i32>,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            r#"WITH updated AS (
+                UPDATE todo_item SET assignee_user_id = $1 FROM todo_item previous
+                WHERE todo_item.id = $2 AND previous.id = todo_item.id AND todo_item.deleted_at IS NULL
+                RETURNING todo_item.id,
+                    to_jsonb(previous) - 'item_desc_search' AS before_state,
+                    to_jsonb(todo_item) - 'item_desc_search' AS after_state
+            )
+            INSERT INTO audit_log(actor_user_id, action, entity_type, entity_id, before_state, after_state)
+            SELECT $3, $4, $5, updated.id, updated.before_state, updated.after_state FROM updated"#,
+            assignee_user_id,
+            task_id,
+            self.actor_user_id,
+            AuditAction::Assign.name(),
+            AuditEntityType::Task.name(),
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to assign a task in the database")?;
+
+        Ok(())
+    }
+}
+
+/// A database-based driven adapter for tracking which tasks are blocked by which other tasks
+pub struct DbDependencyStore;
+
+impl domain::todo::driven_ports::DependencyStore for DbDependencyStore {
+    async fn dependencies_for_user(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<TaskDependency>, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let dependencies = query_as!(
+            TaskDependency,
+            "SELECT td.task_id, td.blocked_by_task_id FROM task_dependency td \
+            JOIN todo_item ti ON ti.id = td.task_id \
+            WHERE ti.user_id = $1 ORDER BY td.task_id, td.blocked_by_task_id",
+            user_id
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch the dependencies between a user's tasks")?;
+
+        Ok(dependencies)
+    }
+
+    async fn lock_dependencies_for_user(
+        &self,
+        user_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // Advisory locks share one key space, so the table's name keeps these apart from other kinds of locks
+        query!(
+            "SELECT pg_advisory_xact_lock(hashtext('task_dependency'), $1)",
+            user_id
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to lock a user's task dependencies")?;
+
+        Ok(())
+    }
+
+    async fn add_dependency(
+        &self,
+        dependency: &TaskDependency,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "INSERT INTO task_dependency(task_id, blocked_by_task_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            dependency.task_id,
+            dependency.blocked_by_task_id,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to add a task dependency")?;
+
+        Ok(())
+    }
+
+    async fn remove_dependency(
+        &self,
+        dependency: &TaskDependency,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<bool, Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let delete_result = query!(
+            "DELETE FROM task_dependency WHERE task_id = $1 AND blocked_by_task_id = $2",
+            dependency.task_id,
+            dependency.blocked_by_task_id,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to remove a task dependency")?;
+
+        Ok(delete_result.rows_affected() > 0)
+    }
 }
@@ -1,9 +1,10 @@
 use super::Count;
 use crate::domain;
+use crate::domain::audit::{AuditAction, AuditEntityType};
 use crate::domain::user::driven_ports::UserDescription;
-use crate::domain::user::{CreateUser, TodoUser};
+use crate::domain::user::{CreateUser, Role, TodoUser};
 use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use sqlx::query_as;
 
 /// A database-based driven adapter for detecting the presence of existing users
@@ -58,15 +59,22 @@ struct TodoUserRow {
     id: i32,
     first_name: String,
     last_name: String,
+    role: String,
 }
 
-impl From<TodoUserRow> for TodoUser {
-    fn from(value: TodoUserRow) -> Self {
-        TodoUser {
+impl TryFrom<TodoUserRow> for TodoUser {
+    type Error = anyhow::Error;
+
+    fn try_from(value: TodoUserRow) -> Result<Self, Self::Error> {
+        let role = Role::from_name(&value.role)
+            .ok_or_else(|| anyhow!("User {} has unknown role {}", value.id, value.role))?;
+
+        Ok(TodoUser {
             id: value.id,
             first_name: value.first_name,
             last_name: value.last_name,
-        }
+            role,
+        })
     }
 }
 
@@ -77,13 +85,16 @@ impl domain::user::driven_ports::UserReader for DbReadUsers {
     ) -> Result<Vec<TodoUser>, anyhow::Error> {
         let mut connection = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
 
-        let users: Vec<TodoUser> = query_as!(TodoUserRow, "SELECT * FROM todo_user")
-            .fetch_all(connection.borrow_connection())
-            .await
-            .context("Fetching all users")?
-            .into_iter()
-            .map(domain::user::TodoUser::from)
-            .collect();
+        let users = query_as!(
+            TodoUserRow,
+            "SELECT tu.id, tu.first_name, tu.last_name, tu.role FROM todo_user tu"
+        )
+        .fetch_all(connection.borrow_connection())
+        .await
+        .context("Fetching all users")?
+        .into_iter()
+        .map(TodoUser::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
 
         Ok(users)
     }
@@ -97,19 +108,104 @@ impl domain::user::driven_ports::UserReader for DbReadUsers {
 
         let user = query_as!(
             TodoUserRow,
-            "SELECT * FROM todo_user tu WHERE tu.id = $1",
+            "SELECT tu.id, tu.first_name, tu.last_name, tu.role FROM todo_user tu WHERE tu.id = $1",
             id
         )
         .fetch_optional(cxn_handle.borrow_connection())
         .await
         .context("Fetching a user by id")?;
 
-        Ok(user.map(TodoUser::from))
+        user.map(TodoUser::try_from).transpose()
+    }
+
+    async fn search_by_name(
+        &self,
+        name_prefix: &str,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<TodoUser>, anyhow::Error> {
+        let mut cxn_handle = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // The name is matched against the start of the full name or the start of any later word in it
+        let users = query_as!(
+            TodoUserRow,
+            r#"SELECT tu.id, tu.first_name, tu.last_name, tu.role FROM todo_user tu
+            WHERE todo_user_search_name(tu.first_name, tu.last_name)
+                    LIKE lower(public.unaccent('public.unaccent'::regdictionary, $1)) || '%'
+                OR todo_user_search_name(tu.first_name, tu.last_name)
+                    LIKE '% ' || lower(public.unaccent('public.unaccent'::regdictionary, $1)) || '%'
+            ORDER BY tu.last_name, tu.first_name, tu.id"#,
+            escape_like_pattern(name_prefix)
+        )
+        .fetch_all(cxn_handle.borrow_connection())
+        .await
+        .context("Searching users by name")?
+        .into_iter()
+        .map(TodoUser::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(users)
+    }
+
+    async fn by_name<'names>(
+        &self,
+        description: UserDescription<'names>,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Option<TodoUser>, anyhow::Error> {
+        let mut cxn_handle = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let user = query_as!(
+            TodoUserRow,
+            r#"SELECT tu.id, tu.first_name, tu.last_name, tu.role FROM todo_user tu
+            WHERE tu.first_name = $1 AND tu.last_name = $2
+            ORDER BY tu.id LIMIT 1"#,
+            description.first_name,
+            description.last_name
+        )
+        .fetch_optional(cxn_handle.borrow_connection())
+        .await
+        .context("Fetching a user by name")?;
+
+        user.map(TodoUser::try_from).transpose()
+    }
+
+    async fn admin_count(
+        &self,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<i64, anyhow::Error> {
+        let mut cxn_handle = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let admin_count = query_as!(
+            Count,
+            "SELECT count(*) FROM todo_user tu WHERE tu.role = $1",
+            Role::Admin.name()
+        )
+        .fetch_one(cxn_handle.borrow_connection())
+        .await
+        .context("Counting admins")?;
+
+        Ok(admin_count.count())
     }
 }
 
-/// A database-based driven adapter for writing new users into the database
-pub struct DbWriteUsers;
+/// Escapes characters with special meaning in SQL LIKE patterns so user input is matched literally
+fn escape_like_pattern(raw: &str) -> String {
+    raw.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// A database-based driven adapter for writing new users into the database. Every change it makes
+/// is recorded in the audit log.
+pub struct DbWriteUsers {
+    actor_user_id: Option<i32>,
+}
+
+impl DbWriteUsers {
+    /// Creates a user writer whose changes are recorded in the audit log as made by the given user
+    pub fn new(actor_user_id: Option<i32>) -> Self {
+        DbWriteUsers { actor_user_id }
+    }
+}
 
 impl domain::user::driven_ports::UserWriter for DbWriteUsers {
     async fn create_user(
@@ -121,9 +217,18 @@ impl domain::user::driven_ports::UserWriter for DbWriteUsers {
 
         let user = query_as!(
             super::NewId,
-            "INSERT INTO todo_user(first_name, last_name) VALUES ($1, $2) RETURNING todo_user.id",
+            r#"WITH inserted AS (
+                INSERT INTO todo_user(first_name, last_name) VALUES ($1, $2)
+                RETURNING todo_user.id, to_jsonb(todo_user) AS state
+            )
+            INSERT INTO audit_log(actor_user_id, action, entity_type, entity_id, after_state)
+            SELECT $3, $4, $5, inserted.id, inserted.state FROM inserted
+            RETURNING audit_log.entity_id AS id"#,
             user.first_name,
             user.last_name,
+            self.actor_user_id,
+            AuditAction::Create.name(),
+            AuditEntityType::User.name(),
         )
         .fetch_one(cxn_handle.borrow_connection())
         .await
@@ -131,4 +236,36 @@ impl domain::user::driven_ports::UserWriter for DbWriteUsers {
 
         Ok(user.id)
     }
+
+    async fn set_role(
+        &self,
+        user_id: i32,
+        role: Role,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<bool, anyhow::Error> {
+        let mut cxn_handle = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // Joining the user to itself gives access to their state from before the update
+        let updated = query_as!(
+            super::NewId,
+            r#"WITH updated AS (
+                UPDATE todo_user SET role = $1 FROM todo_user previous
+                WHERE todo_user.id = $2 AND previous.id = todo_user.id
+                RETURNING todo_user.id, to_jsonb(previous) AS before_state, to_jsonb(todo_user) AS after_state
+            )
+            INSERT INTO audit_log(actor_user_id, action, entity_type, entity_id, before_state, after_state)
+            SELECT $3, $4, $5, updated.id, updated.before_state, updated.after_state FROM updated
+            RETURNING audit_log.entity_id AS id"#,
+            role.name(),
+            user_id,
+            self.actor_user_id,
+            AuditAction::Update.name(),
+            AuditEntityType::User.name(),
+        )
+        .fetch_optional(cxn_handle.borrow_connection())
+        .await
+        .context("Changing a user's role")?;
+
+        Ok(updated.is_some())
+    }
 }
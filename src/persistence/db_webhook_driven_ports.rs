@@ -0,0 +1,264 @@
+use crate::domain;
+use crate::domain::webhook::{
+    EventType, NewWebhookSubscription, PendingDelivery, WebhookEvent, WebhookSubscription,
+};
+use crate::external_connections::{ConnectionHandle, ExternalConnectivity};
+use anyhow::{anyhow, Context};
+use sqlx::{query, query_as};
+use std::time::Duration;
+
+/// A database-based driven adapter which records webhook events in an outbox table
+/// and tracks their delivery
+pub struct DbEventOutbox;
+
+/// DTO containing a delivery claimed from the outbox, joined with its subscription
+struct PendingDeliveryRow {
+    id: i64,
+    url: String,
+    secret: String,
+    event_type: String,
+    payload: serde_json::Value,
+    attempts: i32,
+}
+
+impl TryFrom<PendingDeliveryRow> for PendingDelivery {
+    type Error = anyhow::Error;
+
+    fn try_from(value: PendingDeliveryRow) -> Result<Self, Self::Error> {
+        let event_type = EventType::from_name(&value.event_type).ok_or_else(|| {
+            anyhow!(
+                "Webhook delivery {} has unknown event type {}",
+                value.id,
+                value.event_type
+            )
+        })?;
+
+        Ok(PendingDelivery {
+            id: value.id,
+            url: value.url,
+            secret: value.secret,
+            event_type,
+            payload: value.payload,
+            attempts: value.attempts,
+        })
+    }
+}
+
+impl domain::webhook::driven_ports::EventOutbox for DbEventOutbox {
+    async fn record_event(
+        &self,
+        event: &WebhookEvent,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "INSERT INTO webhook_outbox(subscription_id, event_type, payload) \
+            SELECT ws.id, $1, $2 FROM webhook_subscription ws WHERE $1 = ANY(ws.event_types)",
+            event.event_type().name(),
+            event.payload(),
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to record a webhook event")?;
+
+        Ok(())
+    }
+}
+
+impl domain::webhook::driven_ports::DeliveryQueue for DbEventOutbox {
+    async fn claim_pending(
+        &self,
+        limit: i64,
+        lease: Duration,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<PendingDelivery>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        // Pushing next_attempt_at past the lease hides claimed rows from other dispatchers without holding
+        // a transaction open while requests are sent. SKIP LOCKED stops concurrent claims from blocking each other.
+        let rows = query_as!(
+            PendingDeliveryRow,
+            "UPDATE webhook_outbox wo SET next_attempt_at = now() + make_interval(secs => $2) \
+            FROM webhook_subscription ws \
+            WHERE ws.id = wo.subscription_id AND wo.id IN ( \
+                SELECT pending.id FROM webhook_outbox pending \
+                WHERE pending.delivered_at IS NULL AND pending.failed_at IS NULL AND pending.next_attempt_at <= now() \
+                ORDER BY pending.id \
+                LIMIT $1 \
+                FOR UPDATE SKIP LOCKED \
+            ) \
+            RETURNING wo.id, ws.url, ws.secret, wo.event_type, wo.payload, wo.attempts",
+            limit,
+            lease.as_secs_f64(),
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to claim pending webhook deliveries")?;
+
+        let mut deliveries = rows
+            .into_iter()
+            .map(PendingDelivery::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        deliveries.sort_by_key(|delivery| delivery.id);
+
+        Ok(deliveries)
+    }
+
+    async fn mark_delivered(
+        &self,
+        delivery_id: i64,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "UPDATE webhook_outbox SET attempts = attempts + 1, delivered_at = now(), last_error = NULL \
+            WHERE id = $1",
+            delivery_id,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to mark a webhook delivery as delivered")?;
+
+        Ok(())
+    }
+
+    async fn schedule_retry(
+        &self,
+        delivery_id: i64,
+        error: &str,
+        retry_in: Duration,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "UPDATE webhook_outbox SET attempts = attempts + 1, last_error = $2, \
+                next_attempt_at = now() + make_interval(secs => $3) \
+            WHERE id = $1",
+            delivery_id,
+            error,
+            retry_in.as_secs_f64(),
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to schedule a webhook delivery retry")?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        delivery_id: i64,
+        error: &str,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        query!(
+            "UPDATE webhook_outbox SET attempts = attempts + 1, last_error = $2, failed_at = now() \
+            WHERE id = $1",
+            delivery_id,
+            error,
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to mark a webhook delivery as failed")?;
+
+        Ok(())
+    }
+}
+
+/// A database-based driven adapter for managing webhook subscriptions
+pub struct DbWebhookSubscriptions;
+
+/// DTO containing a webhook subscription without its secret
+struct WebhookSubscriptionRow {
+    id: i32,
+    url: String,
+    event_types: Vec<String>,
+}
+
+impl From<WebhookSubscriptionRow> for WebhookSubscription {
+    fn from(value: WebhookSubscriptionRow) -> Self {
+        WebhookSubscription {
+            id: value.id,
+            url: value.url,
+            event_types: value
+                .event_types
+                .iter()
+                .filter_map(|name| EventType::from_name(name))
+                .collect(),
+        }
+    }
+}
+
+impl domain::webhook::driven_ports::SubscriptionReader for DbWebhookSubscriptions {
+    async fn all(
+        &self,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<Vec<WebhookSubscription>, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let subscriptions = query_as!(
+            WebhookSubscriptionRow,
+            "SELECT ws.id, ws.url, ws.event_types FROM webhook_subscription ws ORDER BY ws.id"
+        )
+        .fetch_all(cxn.borrow_connection())
+        .await
+        .context("trying to fetch webhook subscriptions")?
+        .into_iter()
+        .map(WebhookSubscription::from)
+        .collect();
+
+        Ok(subscriptions)
+    }
+}
+
+impl domain::webhook::driven_ports::SubscriptionWriter for DbWebhookSubscriptions {
+    async fn create_subscription(
+        &self,
+        subscription: &NewWebhookSubscription,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<i32, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+        let event_types: Vec<String> = subscription
+            .event_types
+            .iter()
+            .map(|event_type| event_type.name().to_owned())
+            .collect();
+
+        let new_subscription = query_as!(
+            super::NewId,
+            "INSERT INTO webhook_subscription(url, event_types, secret) VALUES ($1, $2, $3) \
+            RETURNING webhook_subscription.id",
+            subscription.url,
+            &event_types,
+            subscription.secret,
+        )
+        .fetch_one(cxn.borrow_connection())
+        .await
+        .context("trying to create a webhook subscription")?;
+
+        Ok(new_subscription.id)
+    }
+
+    async fn delete_subscription(
+        &self,
+        subscription_id: i32,
+        ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<bool, anyhow::Error> {
+        let mut cxn = ext_cxn.database_cxn().await.map_err(super::anyhowify)?;
+
+        let delete_result = query!(
+            "DELETE FROM webhook_subscription WHERE id = $1",
+            subscription_id
+        )
+        .execute(cxn.borrow_connection())
+        .await
+        .context("trying to delete a webhook subscription")?;
+
+        Ok(delete_result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::domain;
+use crate::domain::rate_limit::{RateLimit, TokenTake};
+use crate::external_connections::ExternalConnectivity;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A driven adapter which keeps rate limit buckets in the memory of this instance of the app. Limits aren't
+/// shared with other instances, so use [DbBucketStore](super::db_rate_limit_driven_ports::DbBucketStore) when
+/// running more than one.
+#[derive(Default)]
+pub struct MemoryBucketStore {
+    /// The tokens in each bucket, along with when they were last counted
+    buckets: Mutex<HashMap<String, (f64, Instant)>>,
+}
+
+impl domain::rate_limit::driven_ports::BucketStore for MemoryBucketStore {
+    async fn take_token(
+        &self,
+        bucket_key: &str,
+        limit: &RateLimit,
+        _ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<TokenTake, anyhow::Error> {
+        let now = Instant::now();
+        let mut buckets = self
+            .buckets
+            .lock()
+            .expect("rate limit buckets mutex poisoned");
+        let (tokens, counted_at) = buckets
+            .entry(bucket_key.to_owned())
+            .or_insert_with(|| (f64::from(limit.capacity), now));
+
+        *tokens = limit.refill(*tokens, now.duration_since(*counted_at));
+        *counted_at = now;
+        let allowed = *tokens >= 1.0;
+        if allowed {
+            *tokens -= 1.0;
+        }
+
+        Ok(TokenTake {
+            allowed,
+            tokens_left: *tokens,
+        })
+    }
+
+    async fn purge_idle(
+        &self,
+        idle_for: Duration,
+        _ext_cxn: &mut impl ExternalConnectivity,
+    ) -> Result<u64, anyhow::Error> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .expect("rate limit buckets mutex poisoned");
+        let bucket_count = buckets.len();
+        buckets.retain(|_, (_, counted_at)| counted_at.elapsed() < idle_for);
+
+        Ok((bucket_count - buckets.len()) as u64)
+    }
+}
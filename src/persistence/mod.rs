@@ -1,10 +1,24 @@
+pub mod db_attachment_driven_ports;
+pub mod db_audit_driven_ports;
+pub mod db_calendar_feed_driven_ports;
+pub mod db_comment_driven_ports;
+pub mod db_digest_driven_ports;
+pub mod db_idempotency_driven_ports;
+pub mod db_rate_limit_driven_ports;
+pub mod db_reminder_driven_ports;
+pub mod db_share_driven_ports;
+pub mod db_task_event_driven_ports;
 pub mod db_todo_driven_ports;
 pub mod db_user_driven_ports;
+pub mod db_webhook_driven_ports;
+pub mod memory_rate_limit_driven_ports;
 
 use crate::external_connections;
-use crate::external_connections::ConnectionHandle;
+use crate::external_connections::{ConnectionHandle, IsolationLevel, TransactionOptions};
+use crate::outbound::blob_storage::BlobStorage;
 use anyhow::{anyhow, Context};
 use std::fmt::{Debug, Display};
+use std::sync::Arc;
 
 use sqlx::pool::PoolConnection;
 use sqlx::{Acquire, PgConnection, PgPool, Postgres, Transaction};
@@ -15,13 +29,26 @@ use sqlx::{Acquire, PgConnection, PgPool, Postgres, Transaction};
 #[derive(Clone)]
 pub struct ExternalConnectivity {
     db: PgPool,
+    blobs: Arc<BlobStorage>,
 }
 
 impl ExternalConnectivity {
     /// Accepts the set of clients used to connect to external systems and constructs
-    /// an instance of ExternalConnectivity owning those clients
+    /// an instance of ExternalConnectivity owning those clients. Files are stored on the local
+    /// filesystem until [ExternalConnectivity::with_blob_storage] says otherwise.
     pub fn new(db: PgPool) -> Self {
-        ExternalConnectivity { db }
+        ExternalConnectivity {
+            db,
+            blobs: Arc::new(BlobStorage::default()),
+        }
+    }
+
+    /// Replaces where the contents of files are stored
+    pub fn with_blob_storage(self, blobs: BlobStorage) -> Self {
+        ExternalConnectivity {
+            blobs: Arc::new(blobs),
+            ..self
+        }
     }
 }
 
@@ -39,6 +66,7 @@ impl ConnectionHandle for PoolConnectionHandle {
 impl external_connections::ExternalConnectivity for ExternalConnectivity {
     type Handle<'cxn_borrow> = PoolConnectionHandle;
     type Error = anyhow::Error;
+    type BlobStore = BlobStorage;
 
     async fn database_cxn(&mut self) -> Result<Self::Handle<'_>, Self::Error> {
         let handle = PoolConnectionHandle {
@@ -47,27 +75,66 @@ impl external_connections::ExternalConnectivity for ExternalConnectivity {
 
         Ok(handle)
     }
+
+    fn blob_store(&self) -> &BlobStorage {
+        &self.blobs
+    }
 }
 
 impl external_connections::Transactable for ExternalConnectivity {
-    type Handle<'handle> = ExternalConnectionsInTransaction<'handle>;
+    // Transactions started from the pool own their connection, so the handle doesn't borrow from self.
+    // Keeping the lifetime out of the handle lets futures holding it be proven Send inside request handlers.
+    type Handle<'handle> = ExternalConnectionsInTransaction<'static>;
     type Error = anyhow::Error;
 
-    async fn start_transaction(&self) -> Result<Self::Handle<'_>, Self::Error> {
-        let transaction = self
+    async fn start_transaction_with_options(
+        &self,
+        options: TransactionOptions,
+    ) -> Result<Self::Handle<'_>, Self::Error> {
+        let mut transaction = self
             .db
             .begin()
             .await
             .context("Starting transaction from db pool")?;
 
-        Ok(ExternalConnectionsInTransaction { txn: transaction })
+        if options != TransactionOptions::default() {
+            sqlx::query(&set_transaction_statement(&options))
+                .execute(&mut *transaction)
+                .await
+                .context("Configuring database transaction")?;
+        }
+
+        Ok(ExternalConnectionsInTransaction {
+            txn: transaction,
+            blobs: Arc::clone(&self.blobs),
+            savepoints: 0,
+        })
     }
 }
 
+/// Builds the `SET TRANSACTION` statement which applies the given options to a freshly started transaction
+fn set_transaction_statement(options: &TransactionOptions) -> String {
+    let isolation_level = match options.isolation_level {
+        IsolationLevel::ReadCommitted => "READ COMMITTED",
+        IsolationLevel::RepeatableRead => "REPEATABLE READ",
+    };
+    let access_mode = if options.read_only {
+        "READ ONLY"
+    } else {
+        "READ WRITE"
+    };
+
+    format!("SET TRANSACTION ISOLATION LEVEL {isolation_level}, {access_mode}")
+}
+
 /// A variant of ExternalConnectivity where the database client has an active database transaction
-/// which can later be committed
+/// which can later be committed. Blob storage isn't transactional, so it's shared with the connectivity the
+/// transaction started from.
 pub struct ExternalConnectionsInTransaction<'tx> {
     txn: Transaction<'tx, Postgres>,
+    blobs: Arc<BlobStorage>,
+    /// How many savepoints are open inside the transaction
+    savepoints: usize,
 }
 
 /// A handle from ExternalConnectionsInTransaction which can connect to a database
@@ -76,8 +143,12 @@ pub struct TransactionHandle<'tx> {
 }
 
 impl<'tx> external_connections::ExternalConnectivity for ExternalConnectionsInTransaction<'tx> {
-    type Handle<'tx_borrow> = TransactionHandle<'tx_borrow> where Self: 'tx_borrow;
+    type Handle<'tx_borrow>
+        = TransactionHandle<'tx_borrow>
+    where
+        Self: 'tx_borrow;
     type Error = anyhow::Error;
+    type BlobStore = BlobStorage;
 
     async fn database_cxn(&mut self) -> Result<TransactionHandle<'_>, Self::Error> {
         let handle = self
@@ -90,6 +161,10 @@ impl<'tx> external_connections::ExternalConnectivity for ExternalConnectionsInTr
             active_transaction: handle,
         })
     }
+
+    fn blob_store(&self) -> &BlobStorage {
+        &self.blobs
+    }
 }
 
 impl<'tx> ConnectionHandle for TransactionHandle<'tx> {
@@ -111,6 +186,55 @@ impl<'tx> external_connections::TransactionHandle for ExternalConnectionsInTrans
     }
 }
 
+impl<'tx> external_connections::Savepointable for ExternalConnectionsInTransaction<'tx> {
+    type Error = anyhow::Error;
+
+    async fn create_savepoint(&mut self) -> Result<(), Self::Error> {
+        sqlx::query(&format!(
+            "SAVEPOINT {}",
+            savepoint_name(self.savepoints + 1)
+        ))
+        .execute(&mut *self.txn)
+        .await
+        .context("Starting savepoint inside database transaction")?;
+        self.savepoints += 1;
+
+        Ok(())
+    }
+
+    async fn release_savepoint(&mut self) -> Result<(), Self::Error> {
+        let name = savepoint_name(self.savepoints);
+        self.savepoints -= 1;
+        sqlx::query(&format!("RELEASE SAVEPOINT {name}"))
+            .execute(&mut *self.txn)
+            .await
+            .context("Releasing savepoint inside database transaction")?;
+
+        Ok(())
+    }
+
+    async fn rollback_to_savepoint(&mut self) -> Result<(), Self::Error> {
+        let name = savepoint_name(self.savepoints);
+        self.savepoints -= 1;
+        sqlx::query(&format!("ROLLBACK TO SAVEPOINT {name}"))
+            .execute(&mut *self.txn)
+            .await
+            .context("Rolling back savepoint inside database transaction")?;
+        // Rolling back to a savepoint keeps it around, so it's released afterwards just like a successful one
+        sqlx::query(&format!("RELEASE SAVEPOINT {name}"))
+            .execute(&mut *self.txn)
+            .await
+            .context("Releasing rolled back savepoint inside database transaction")?;
+
+        Ok(())
+    }
+}
+
+/// Names the savepoint at the given depth. The names differ from the ones sqlx uses for nested transactions.
+fn savepoint_name(depth: usize) -> String {
+    format!("nested_savepoint_{depth}")
+}
+
 /// Utility DTO for consuming the output of the PostgreSQL `count()` function
 struct Count {
     count: Option<i64>,
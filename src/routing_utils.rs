@@ -1,4 +1,7 @@
+use axum::async_trait;
 use axum::extract::rejection::JsonRejection;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum_macros::FromRequest;
@@ -11,15 +14,21 @@ use validator::ValidationErrors;
 /// Represents a generic 500 internal server error which turns into a [BasicError]
 pub struct GenericErrorResponse(pub anyhow::Error);
 
+impl From<GenericErrorResponse> for BasicError {
+    fn from(value: GenericErrorResponse) -> Self {
+        BasicError {
+            error_code: "internal_error".to_owned(),
+            error_description: format!("An unexpected error occurred: {}", value.0),
+            extra_info: None,
+        }
+    }
+}
+
 impl IntoResponse for GenericErrorResponse {
     fn into_response(self) -> Response {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(BasicError {
-                error_code: "internal_error".to_owned(),
-                error_description: format!("An unexpected error occurred: {}", self.0),
-                extra_info: None,
-            }),
+            Json(BasicError::from(self)),
         )
             .into_response()
     }
@@ -28,17 +37,19 @@ impl IntoResponse for GenericErrorResponse {
 /// Response type that wraps validation errors and turns them into [BasicError]s
 pub struct ValidationErrorResponse(ValidationErrors);
 
+impl From<ValidationErrorResponse> for BasicError {
+    fn from(value: ValidationErrorResponse) -> Self {
+        BasicError {
+            error_code: "invalid_input".into(),
+            error_description: "Submitted data was invalid.".to_owned(),
+            extra_info: Some(ExtraInfo::ValidationIssues(ValidationErrorSchema(value.0))),
+        }
+    }
+}
+
 impl IntoResponse for ValidationErrorResponse {
     fn into_response(self) -> Response {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(BasicError {
-                error_code: "invalid_input".into(),
-                error_description: "Submitted data was invalid.".to_owned(),
-                extra_info: Some(ExtraInfo::ValidationIssues(ValidationErrorSchema(self.0))),
-            }),
-        )
-            .into_response()
+        (StatusCode::BAD_REQUEST, Json(BasicError::from(self))).into_response()
     }
 }
 
@@ -64,18 +75,33 @@ impl<T: Serialize> IntoResponse for Json<T> {
 /// Response type representing JSON parse errors
 pub struct JsonErrorResponse {
     parse_problem: String,
+    /// Whether the body couldn't be read because it was too large, rather than because it wasn't valid JSON
+    too_large: bool,
 }
 
 impl From<JsonRejection> for JsonErrorResponse {
     fn from(value: JsonRejection) -> Self {
         JsonErrorResponse {
             parse_problem: value.body_text(),
+            too_large: value.status() == StatusCode::PAYLOAD_TOO_LARGE,
         }
     }
 }
 
 impl IntoResponse for JsonErrorResponse {
     fn into_response(self) -> Response {
+        if self.too_large {
+            return (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                axum::Json(BasicError {
+                    error_code: "request_too_large".into(),
+                    error_description: "The passed request body was too large.".into(),
+                    extra_info: Some(ExtraInfo::Message(self.parse_problem)),
+                }),
+            )
+                .into_response();
+        }
+
         (
             StatusCode::BAD_REQUEST,
             axum::Json(BasicError {
@@ -88,3 +114,48 @@ impl IntoResponse for JsonErrorResponse {
             .into_response()
     }
 }
+
+/// The header identifying the user making a request. It's trusted as-is, so it should be set by
+/// something in front of the API which has already authenticated the caller.
+pub const CALLING_USER_HEADER: &str = "X-User-Id";
+
+/// Extractor for the ID of the user making a request, taken from the [CALLING_USER_HEADER].
+/// Contains [None] if the request didn't identify a user.
+pub struct CallingUser(pub Option<i32>);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CallingUser {
+    type Rejection = InvalidCallingUserResponse;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(header_value) = parts.headers.get(CALLING_USER_HEADER) else {
+            return Ok(CallingUser(None));
+        };
+
+        header_value
+            .to_str()
+            .ok()
+            .and_then(|user_id| user_id.trim().parse().ok())
+            .map(|user_id| CallingUser(Some(user_id)))
+            .ok_or(InvalidCallingUserResponse)
+    }
+}
+
+/// Response type representing a [CALLING_USER_HEADER] which isn't a user ID
+pub struct InvalidCallingUserResponse;
+
+impl IntoResponse for InvalidCallingUserResponse {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::BAD_REQUEST,
+            axum::Json(BasicError {
+                error_code: "invalid_calling_user".into(),
+                error_description: format!(
+                    "The {CALLING_USER_HEADER} header must contain the ID of a user."
+                ),
+                extra_info: None,
+            }),
+        )
+            .into_response()
+    }
+}